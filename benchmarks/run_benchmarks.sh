@@ -112,7 +112,8 @@ run_cpp_benchmarks() {
 
         if [ -f "./gafro_modern_benchmarks" ]; then
             echo "--- C++ Benchmark Results ---"
-            ./gafro_modern_benchmarks --benchmark_format=console
+            ./gafro_modern_benchmarks --benchmark_format=console \
+                --benchmark_out=benchmark_results.json --benchmark_out_format=json
             print_success "C++ benchmarks completed"
         else
             print_warning "C++ benchmark executable not found, skipping..."
@@ -132,6 +133,10 @@ run_rust_benchmarks() {
         cd benchmarks/rust
 
         echo "--- Rust Benchmark Results ---"
+        # The runner also normalizes the C++ results into target/criterion/perf_report_cpp.json
+        if [ -f ../cpp/build/benchmark_results.json ]; then
+            export GAFRO_CPP_BENCHMARK_JSON="$(pwd)/../cpp/build/benchmark_results.json"
+        fi
         cargo bench
         print_success "Rust benchmarks completed"
 
@@ -141,6 +146,18 @@ run_rust_benchmarks() {
     fi
 }
 
+# Run the representation comparison suite, appending to a CSV
+run_representation_suite() {
+    print_status "Running representation comparison suite..."
+
+    cd benchmarks/rust
+
+    cargo run --release --bin representation_suite -- "${1:-representations.csv}"
+    print_success "Representation results appended to benchmarks/rust/${1:-representations.csv}"
+
+    cd ../..
+}
+
 # Generate comparison report
 generate_report() {
     print_status "Generating benchmark report..."
@@ -267,12 +284,17 @@ elif [ "$1" = "--rust-only" ]; then
     check_dependencies
     build_rust_benchmarks
     run_rust_benchmarks
+elif [ "$1" = "--representations" ]; then
+    check_dependencies
+    run_representation_suite "$2"
 elif [ "$1" = "--help" ]; then
-    echo "Usage: $0 [--cpp-only|--rust-only|--help]"
+    echo "Usage: $0 [--cpp-only|--rust-only|--representations [file.csv]|--help]"
     echo ""
     echo "Options:"
     echo "  --cpp-only    Run only C++ benchmarks"
     echo "  --rust-only   Run only Rust benchmarks"
+    echo "  --representations [file.csv]"
+    echo "                Compare multivector layouts and append rows to a CSV"
     echo "  --help        Show this help message"
     echo ""
     echo "Default: Run both C++ and Rust benchmarks"
@@ -2,12 +2,14 @@
 //
 // SPDX-License-Identifier: MPL-2.0
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
+use criterion::{black_box, criterion_group, Criterion, BenchmarkId};
 use gafro_modern::prelude::*;
 use gafro_modern::si_units::{self, UnitExt, TAU, PI};
 use rand::{thread_rng, Rng};
 use std::time::Duration;
 
+mod perf_report;
+
 /// Generate test data for benchmarks
 fn generate_scalars(count: usize) -> Vec<f64> {
     let mut rng = thread_rng();
@@ -32,7 +34,7 @@ fn generate_ga_terms(count: usize) -> Vec<GATerm<f64>> {
             if i % 2 == 0 {
                 GATerm::scalar(rng.gen_range(-10.0..10.0))
             } else {
-                let components: Vec<(i32, f64)> = (0..3)
+                let components: Vec<(i32, f64)> = (1..=3)
                     .map(|j| (j, rng.gen_range(-10.0..10.0)))
                     .collect();
                 GATerm::vector(components)
@@ -77,7 +79,7 @@ fn bench_ga_term_operations(c: &mut Criterion) {
             |b, &size| {
                 let vectors1: Vec<GATerm<f64>> = (0..size)
                     .map(|_| {
-                        let components: Vec<(i32, f64)> = (0..3)
+                        let components: Vec<(i32, f64)> = (1..=3)
                             .map(|j| (j, thread_rng().gen_range(-10.0..10.0)))
                             .collect();
                         GATerm::vector(components)
@@ -85,7 +87,7 @@ fn bench_ga_term_operations(c: &mut Criterion) {
                     .collect();
                 let vectors2: Vec<GATerm<f64>> = (0..size)
                     .map(|_| {
-                        let components: Vec<(i32, f64)> = (0..3)
+                        let components: Vec<(i32, f64)> = (1..=3)
                             .map(|j| (j, thread_rng().gen_range(-10.0..10.0)))
                             .collect();
                         GATerm::vector(components)
@@ -104,6 +106,30 @@ fn bench_ga_term_operations(c: &mut Criterion) {
             },
         );
 
+        // In-place accumulation into a single vector
+        group.bench_with_input(
+            BenchmarkId::new("vector_add_assign", size),
+            size,
+            |b, &size| {
+                let vectors: Vec<GATerm<f64>> = (0..size)
+                    .map(|_| {
+                        let components: Vec<(i32, f64)> = (1..=3)
+                            .map(|j| (j, thread_rng().gen_range(-10.0..10.0)))
+                            .collect();
+                        GATerm::vector(components)
+                    })
+                    .collect();
+
+                b.iter(|| {
+                    let mut accumulator = GATerm::vector(vec![(1, 0.0), (2, 0.0), (3, 0.0)]);
+                    for vector in &vectors {
+                        pattern_matching::operations::add_assign(&mut accumulator, black_box(vector));
+                    }
+                    black_box(accumulator);
+                });
+            },
+        );
+
         // Pattern matching
         group.bench_with_input(
             BenchmarkId::new("pattern_matching", size),
@@ -359,8 +385,8 @@ fn bench_cross_language_consistency(c: &mut Criterion) {
         let test_vectors: Vec<(Vec<(i32, f64)>, Vec<(i32, f64)>)> = (0..100)
             .map(|_| {
                 let mut rng = thread_rng();
-                let v1 = (0..3).map(|i| (i, rng.gen_range(-10.0..10.0))).collect();
-                let v2 = (0..3).map(|i| (i, rng.gen_range(-10.0..10.0))).collect();
+                let v1 = (1..=3).map(|i| (i, rng.gen_range(-10.0..10.0))).collect();
+                let v2 = (1..=3).map(|i| (i, rng.gen_range(-10.0..10.0))).collect();
                 (v1, v2)
             })
             .collect();
@@ -393,7 +419,7 @@ fn bench_memory_allocation(c: &mut Criterion) {
                             let term = GATerm::scalar(black_box(3.14));
                             black_box(term);
                         } else {
-                            let components = vec![(0, 1.0), (1, 2.0), (2, 3.0)];
+                            let components = vec![(1, 1.0), (2, 2.0), (3, 3.0)];
                             let term = GATerm::vector(black_box(components));
                             black_box(term);
                         }
@@ -434,6 +460,273 @@ fn bench_memory_allocation(c: &mut Criterion) {
     group.finish();
 }
 
+/// Sparse vs dense vs hybrid multivector representations
+fn bench_multivector_representations(c: &mut Criterion) {
+    use gafro_modern::ga_term::BladeTerm;
+    use gafro_modern::multivector::{mask_indices, DensityPolicy, Multivector};
+
+    let mut group = c.benchmark_group("multivector_representations");
+    let mut rng = thread_rng();
+
+    // Number of non-zero blades out of the 32 in Cl(5,0); Multivector products are Euclidean
+    for blades in [2, 4, 8, 16, 32].iter() {
+        let terms: Vec<BladeTerm<f64>> = (0..*blades as u32)
+            .map(|i| BladeTerm::new(mask_indices(i * 31 % 32), rng.gen_range(-10.0..10.0)))
+            .collect();
+        let base = Multivector::from_terms(5, &terms).unwrap();
+
+        for (name, policy) in [
+            ("sparse", DensityPolicy::always_sparse()),
+            ("dense", DensityPolicy::always_dense()),
+            ("hybrid", DensityPolicy::default()),
+        ] {
+            let lhs = base.clone().with_policy(policy);
+            let rhs = base.clone().with_policy(policy);
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_geometric_product", name), blades),
+                blades,
+                |b, _| b.iter(|| black_box(black_box(&lhs) * black_box(&rhs))),
+            );
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_add_assign", name), blades),
+                blades,
+                |b, _| {
+                    b.iter(|| {
+                        let mut sum = lhs.clone();
+                        sum.add_assign(black_box(&rhs));
+                        black_box(sum)
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Nearest-obstacle queries for a robot's links, BVH vs brute force
+fn bench_obstacle_queries(c: &mut Criterion) {
+    use gafro_modern::collision::{Bvh, Capsule, Shape};
+    use gafro_modern::primitives::{Aabb, Sphere};
+    use gafro_modern::sample::Sampler;
+
+    let mut group = c.benchmark_group("obstacle_queries");
+    let mut sampler = Sampler::new(1);
+    let world = Aabb::new([-50.0; 3], [50.0; 3]);
+
+    // A seven-link arm folded around the origin
+    let links: Vec<Capsule> = (0..7)
+        .map(|i| {
+            let start = [0.3 * i as f64, 0.0, 0.5 * i as f64];
+            Capsule::new(start, [start[0] + 0.3, 0.0, start[2] + 0.5], 0.05)
+        })
+        .collect();
+
+    for count in [1000, 5000, 10000].iter() {
+        let shapes: Vec<Shape> = (0..*count)
+            .map(|i| {
+                let center = sampler.point_in_box(&world);
+                if i % 2 == 0 {
+                    Shape::Sphere(Sphere::new(center, sampler.range(0.1, 1.0)))
+                } else {
+                    Shape::Box(Aabb::new(center, [center[0] + 1.0, center[1] + 0.5, center[2] + 0.2]))
+                }
+            })
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("bvh_build", count), count, |b, _| {
+            b.iter(|| black_box(Bvh::build(black_box(shapes.clone()))))
+        });
+
+        let bvh = Bvh::build(shapes.clone());
+        group.bench_with_input(BenchmarkId::new("bvh_nearest_batch", count), count, |b, _| {
+            b.iter(|| black_box(bvh.nearest_batch(black_box(&links))))
+        });
+
+        group.bench_with_input(BenchmarkId::new("brute_force_nearest_batch", count), count, |b, _| {
+            b.iter(|| {
+                let nearest: Vec<f64> = links
+                    .iter()
+                    .map(|link| shapes.iter().map(|s| *link.distance_to(s).value()).fold(f64::INFINITY, f64::min))
+                    .collect();
+                black_box(nearest)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// Compact kernels against the f64 motor types; build with `--features fast-math`
+/// to measure the f32 embedded profile
+fn bench_embedded_kernels(c: &mut Criterion) {
+    use gafro_modern::fast_math::{forward_kinematics, CompactJoint, CompactMotor, CompactRotor, Real};
+    use gafro_modern::motor::{Motor, Rotor};
+    use gafro_modern::sample::Sampler;
+
+    let mut sampler = Sampler::new(2);
+    let rotor = sampler.rotor();
+    let compact = CompactRotor::from(&rotor);
+    let points: Vec<[f64; 3]> = (0..1000).map(|_| sampler.unit_vector()).collect();
+    let compact_points: Vec<[Real; 3]> = points.iter().map(|p| p.map(|c| c as Real)).collect();
+
+    let mut group = c.benchmark_group("rotor_application");
+    group.bench_function("f64_rotor", |b| {
+        b.iter(|| points.iter().map(|&p| rotor.apply(black_box(p))).fold(0.0, |sum, q| sum + q[0]))
+    });
+    group.bench_function("compact_rotor", |b| {
+        b.iter(|| compact_points.iter().map(|&p| compact.apply(black_box(p))).fold(0.0, |sum, q| sum + q[0]))
+    });
+    group.finish();
+
+    // Seven revolute joints alternating about z and y, 0.3 m apart
+    let axes: Vec<[f64; 3]> = (0..7).map(|i| if i % 2 == 0 { [0.0, 0.0, 1.0] } else { [0.0, 1.0, 0.0] }).collect();
+    let link = Motor::from_translation([0.3, 0.0, 0.0]);
+    let joints: Vec<CompactJoint> = axes
+        .iter()
+        .map(|axis| CompactJoint { placement: CompactMotor::from(&link), axis: axis.map(|c| c as Real) })
+        .collect();
+    let angles = [0.3, -0.5, 1.1, 0.7, -1.4, 0.2, 2.0];
+    let compact_angles = angles.map(|a| a as Real);
+
+    let mut group = c.benchmark_group("forward_kinematics");
+    group.bench_function("f64_motor", |b| {
+        b.iter(|| {
+            axes.iter().zip(black_box(angles)).fold(Motor::identity(), |pose, (axis, angle)| {
+                pose * link * Motor::from_rotor(Rotor::from_axis_angle(*axis, angle))
+            })
+        })
+    });
+    group.bench_function("compact_motor", |b| b.iter(|| forward_kinematics(&joints, black_box(&compact_angles))));
+    group.finish();
+}
+
+fn bench_lazy_expressions(c: &mut Criterion) {
+    use gafro_modern::clifford::Algebra;
+    use gafro_modern::expression::{Dense, Expr};
+    use gafro_modern::ga_term::BladeTerm;
+    use gafro_modern::multivector::{mask_indices, Multivector};
+    use gafro_modern::sample::Sampler;
+
+    // Euclidean 5D so the heap-backed Multivector computes the same products
+    type Euclidean5 = Algebra<5, 0, 0, 32>;
+    let mut sampler = Sampler::new(3);
+    let arrays: Vec<[f64; 32]> = (0..4).map(|_| std::array::from_fn(|_| sampler.range(-1.0, 1.0))).collect();
+    let multivectors: Vec<Multivector<f64>> = arrays
+        .iter()
+        .map(|c| {
+            let terms: Vec<BladeTerm<f64>> = (0..32).map(|mask| BladeTerm::new(mask_indices(mask as u32), c[mask])).collect();
+            Multivector::from_terms(5, &terms).unwrap()
+        })
+        .collect();
+    let dense: Vec<Dense<Euclidean5, f64, 32>> = arrays.iter().map(|&c| Dense::new(c)).collect();
+
+    // a*b + c*d: two heap intermediates, two stack intermediates, or one fused pass
+    let mut group = c.benchmark_group("lazy_expressions");
+    group.bench_function("eager_multivector", |b| {
+        let [p, q, r, s] = [0, 1, 2, 3].map(|i| &multivectors[i]);
+        b.iter(|| black_box(p).geometric_product(q) + black_box(r).geometric_product(s))
+    });
+    group.bench_function("eager_dense", |b| {
+        let [p, q, r, s] = [0, 1, 2, 3].map(|i| &arrays[i]);
+        b.iter(|| {
+            let pq = Euclidean5::geometric_product(black_box(p), q);
+            let rs = Euclidean5::geometric_product(black_box(r), s);
+            std::array::from_fn::<f64, 32, _>(|k| pq[k] + rs[k])
+        })
+    });
+    group.bench_function("lazy_dense", |b| {
+        let [p, q, r, s] = [0, 1, 2, 3].map(|i| &dense[i]);
+        b.iter(|| (black_box(p) * q + black_box(r) * s).eval())
+    });
+    group.finish();
+}
+
+fn bench_batch_motor_application(c: &mut Criterion) {
+    use gafro_modern::batch;
+    use gafro_modern::primitives::Aabb;
+    use gafro_modern::sample::{MotorBounds, Sampler};
+
+    let mut sampler = Sampler::new(4);
+    let motor = sampler.motor(&MotorBounds::new(Aabb::new([-1.0; 3], [1.0; 3])));
+    #[cfg(feature = "gpu")]
+    let gpu = gafro_modern::gpu::GpuBatch::new().ok();
+
+    // Per-call upload and readback make the GPU lose on small clouds; the sizes bracket the crossover
+    let mut group = c.benchmark_group("batch_motor_application");
+    for size in [100, 1_000, 10_000, 100_000, 1_000_000] {
+        let points: Vec<[f64; 3]> = (0..size).map(|_| sampler.point_in_box(&Aabb::new([-10.0; 3], [10.0; 3]))).collect();
+        group.bench_with_input(BenchmarkId::new("cpu", size), &points, |b, points| {
+            b.iter(|| batch::transform_points(&motor, black_box(points)))
+        });
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = &gpu {
+            group.bench_with_input(BenchmarkId::new("gpu", size), &points, |b, points| {
+                b.iter(|| gpu.transform_points(&motor, black_box(points)).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+/// GNAT nearest-neighbor queries in a wrapped 7-dof joint space and in pose space
+/// against a linear scan, at planner roadmap sizes
+fn bench_configuration_space_neighbors(c: &mut Criterion) {
+    use gafro_modern::cspace::{Gnat, JointMetric, Metric, MotorMetric};
+    use gafro_modern::primitives::Aabb;
+    use gafro_modern::sample::{MotorBounds, Sampler};
+    use gafro_modern::si_units::Length;
+
+    let mut group = c.benchmark_group("configuration_space_neighbors");
+    group.sample_size(20);
+    let mut sampler = Sampler::new(5);
+    let metric = JointMetric { weights: vec![1.0, 1.0, 1.0, 0.8, 0.6, 0.4, 0.2], wrapping: vec![true, false, false, false, false, false, true] };
+    let configuration = |sampler: &mut Sampler| (0..7).map(|_| sampler.range(-TAU / 2.0, TAU / 2.0)).collect::<Vec<f64>>();
+    let queries: Vec<Vec<f64>> = (0..100).map(|_| configuration(&mut sampler)).collect();
+
+    for count in [10_000, 100_000, 200_000] {
+        let points: Vec<Vec<f64>> = (0..count).map(|_| configuration(&mut sampler)).collect();
+        if count == 100_000 {
+            group.bench_with_input(BenchmarkId::new("joint_gnat_build", count), &points, |b, points| {
+                b.iter(|| black_box(Gnat::build(metric.clone(), points.clone())))
+            });
+        }
+        let tree = Gnat::build(metric.clone(), points.clone());
+        group.bench_with_input(BenchmarkId::new("joint_gnat_nearest_100", count), &queries, |b, queries| {
+            b.iter(|| queries.iter().map(|q| tree.nearest(black_box(q))).collect::<Vec<_>>())
+        });
+        group.bench_with_input(BenchmarkId::new("joint_gnat_10_nearest_100", count), &queries, |b, queries| {
+            b.iter(|| queries.iter().map(|q| tree.k_nearest(black_box(q), 10)).collect::<Vec<_>>())
+        });
+        group.bench_with_input(BenchmarkId::new("joint_linear_scan_100", count), &queries, |b, queries| {
+            b.iter(|| {
+                let nearest: Vec<f64> =
+                    queries.iter().map(|q| points.iter().map(|p| metric.distance(q, p)).fold(f64::INFINITY, f64::min)).collect();
+                black_box(nearest)
+            })
+        });
+    }
+
+    let bounds = MotorBounds::new(Aabb::new([-20.0; 3], [20.0; 3]));
+    let metric = MotorMetric::new(Length::new(1.0));
+    let poses: Vec<_> = (0..100_000).map(|_| sampler.motor(&bounds)).collect();
+    let pose_queries: Vec<_> = (0..100).map(|_| sampler.motor(&bounds)).collect();
+    let tree = Gnat::build(metric, poses.clone());
+    group.bench_with_input(BenchmarkId::new("motor_gnat_nearest_100", poses.len()), &pose_queries, |b, queries| {
+        b.iter(|| queries.iter().map(|q| tree.nearest(black_box(q))).collect::<Vec<_>>())
+    });
+    group.bench_with_input(BenchmarkId::new("motor_linear_scan_100", poses.len()), &pose_queries, |b, queries| {
+        b.iter(|| {
+            let nearest: Vec<f64> =
+                queries.iter().map(|q| poses.iter().map(|p| metric.distance(q, p)).fold(f64::INFINITY, f64::min)).collect();
+            black_box(nearest)
+        })
+    });
+    group.finish();
+}
+
 /// Configuration
 criterion_group!(
     name = benches;
@@ -446,7 +739,20 @@ criterion_group!(
         bench_grade_indexed_operations,
         bench_si_units_operations,
         bench_cross_language_consistency,
-        bench_memory_allocation
+        bench_memory_allocation,
+        bench_multivector_representations,
+        bench_obstacle_queries,
+        bench_embedded_kernels,
+        bench_lazy_expressions,
+        bench_batch_motor_application,
+        bench_configuration_space_neighbors
 );
 
-criterion_main!(benches);
\ No newline at end of file
+/// Run the benchmarks as `criterion_main!` would, then export the normalized report
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+    if let Err(e) = perf_report::export() {
+        eprintln!("Perf report export failed: {}", e);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,104 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Normalized JSON perf reports for Rust-vs-C++ comparison
+//!
+//! After the benchmarks run, [`export`] walks Criterion's output directory and
+//! writes `perf_report.json` in the shape of `benchmarks/perf_report.schema.json`:
+//! one result per benchmark with its group, operation, size parameter, mean
+//! nanoseconds per iteration and throughput when one was configured. When
+//! `GAFRO_CPP_BENCHMARK_JSON` names a Google Benchmark JSON file
+//! (`--benchmark_out_format=json`), it is converted to `perf_report_cpp.json`
+//! in the same shape, so a dashboard reads both languages alike. The report
+//! types are [`gafro_modern::bench`]'s, which downstream benchmarks share.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use gafro_modern::bench::{Measurement, Report, Throughput};
+use serde_json::Value;
+
+/// Criterion's output directory for this build
+pub fn criterion_dir() -> PathBuf {
+    let target = std::env::var_os("CARGO_TARGET_DIR").map_or_else(|| PathBuf::from("target"), PathBuf::from);
+    target.join("criterion")
+}
+
+/// Results of every benchmark Criterion has measured under `dir`
+pub fn read_criterion(dir: &Path) -> io::Result<Vec<Measurement>> {
+    let mut results = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.file_name().is_some_and(|n| n == "benchmark.json") && dir.ends_with("new") {
+                results.extend(criterion_result(&path, &dir.join("estimates.json"))?);
+            }
+        }
+    }
+    Ok(results)
+}
+
+fn criterion_result(benchmark: &Path, estimates: &Path) -> io::Result<Option<Measurement>> {
+    let benchmark: Value = serde_json::from_str(&fs::read_to_string(benchmark)?)?;
+    let estimates: Value = serde_json::from_str(&fs::read_to_string(estimates)?)?;
+    let Some(ns_per_iter) = estimates["mean"]["point_estimate"].as_f64() else { return Ok(None) };
+    let group = benchmark["group_id"].as_str().unwrap_or_default().to_string();
+    let op = benchmark["function_id"].as_str().map_or_else(|| group.clone(), str::to_string);
+    let size = benchmark["value_str"].as_str().and_then(|v| v.parse().ok());
+    let throughput = [("Elements", "elements"), ("Bytes", "bytes")].into_iter().find_map(|(key, unit)| {
+        let count = benchmark["throughput"][key].as_f64()?;
+        Some(Throughput { unit: unit.to_string(), per_second: count / (ns_per_iter * 1e-9) })
+    });
+    Ok(Some(Measurement { group, op, size, ns_per_iter, throughput }))
+}
+
+/// Results of a Google Benchmark JSON report; names are `op/size[/...]`
+pub fn read_google_benchmark(json: &str) -> serde_json::Result<Vec<Measurement>> {
+    let report: Value = serde_json::from_str(json)?;
+    let benchmarks = report["benchmarks"].as_array().cloned().unwrap_or_default();
+    Ok(benchmarks
+        .iter()
+        .filter(|b| b["run_type"].as_str() != Some("aggregate"))
+        .filter_map(|b| {
+            let name = b["name"].as_str()?;
+            let scale = match b["time_unit"].as_str().unwrap_or("ns") {
+                "us" => 1e3,
+                "ms" => 1e6,
+                "s" => 1e9,
+                _ => 1.0,
+            };
+            let mut parts = name.split('/');
+            let op = parts.next()?.to_string();
+            let size = parts.next().and_then(|s| s.parse().ok());
+            let throughput = [("items_per_second", "elements"), ("bytes_per_second", "bytes")]
+                .into_iter()
+                .find_map(|(key, unit)| Some(Throughput { unit: unit.to_string(), per_second: b[key].as_f64()? }));
+            Some(Measurement { group: op.clone(), op, size, ns_per_iter: b["real_time"].as_f64()? * scale, throughput })
+        })
+        .collect())
+}
+
+/// Write the normalized reports next to Criterion's output
+pub fn export() -> io::Result<()> {
+    let dir = criterion_dir();
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let write = |name: &str, report: &Report| -> io::Result<()> {
+        let path = dir.join(name);
+        fs::write(&path, serde_json::to_string_pretty(report)?)?;
+        println!("Perf report ({} results) written to {}", report.results.len(), path.display());
+        Ok(())
+    };
+    write("perf_report.json", &Report::new("rust", read_criterion(&dir)?))?;
+    if let Some(cpp) = std::env::var_os("GAFRO_CPP_BENCHMARK_JSON") {
+        let results = read_google_benchmark(&fs::read_to_string(cpp)?)?;
+        write("perf_report_cpp.json", &Report::new("cpp", results))?;
+    }
+    Ok(())
+}
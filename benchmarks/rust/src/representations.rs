@@ -0,0 +1,276 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Representation comparison suite
+//!
+//! Times rotor addition, rotor product and the rotor sandwich `R v R̃` in the
+//! Euclidean 3D algebra for four layouts of a batch of multivectors:
+//!
+//! - `sparse_vec`: [`Multivector`] forced sparse, a heap `Vec` of blades each
+//! - `smallvec`: blades in an inline `SmallVec`, no heap for up to 8 blades
+//! - `dense_array`: `[f64; 8]` per element, products from the compile-time tables
+//! - `soa_batch`: one column per blade for the whole batch
+//!
+//! Batch sizes run from 1 to 10⁶ elements, so the working set moves from L1
+//! through the last-level cache to DRAM (a dense element is 64 bytes). Each
+//! row of the CSV is one (representation, operation, size) with the
+//! nanoseconds per element of the best of several timed repetitions. Rows are
+//! appended with a `machine` column, so runs on several machines can share a
+//! file:
+//!
+//! ```text
+//! cargo run --release --bin representation_suite -- results.csv
+//! ```
+//!
+//! The machine name is `GAFRO_BENCH_MACHINE` when set, else the host name.
+
+use std::fs::OpenOptions;
+use std::hint::black_box;
+use std::io::Write;
+use std::time::Instant;
+
+use gafro_modern::clifford::Vga3;
+use gafro_modern::ga_term::BladeTerm;
+use gafro_modern::multivector::{mask_indices, DensityPolicy, Multivector};
+use gafro_modern::sample::Sampler;
+use smallvec::SmallVec;
+
+const SIZES: [usize; 7] = [1, 10, 100, 1_000, 10_000, 100_000, 1_000_000];
+const OPERATIONS: [&str; 3] = ["add", "product", "sandwich"];
+/// Elements processed per timed repetition, at least, so small batches are not all overhead
+const MIN_ELEMENTS: usize = 1_000_000;
+const REPETITIONS: usize = 5;
+
+/// Rotor blades (scalar, e12, e13, e23) and vector blades (e1, e2, e3)
+const ROTOR_MASKS: [usize; 4] = [0b000, 0b011, 0b101, 0b110];
+const VECTOR_MASKS: [usize; 3] = [0b001, 0b010, 0b100];
+
+type Dense = [f64; 8];
+type Blades = SmallVec<[(usize, f64); 8]>;
+
+/// Operands of one batch in the dense layout, converted into the others
+struct Batch {
+    rotors: Vec<Dense>,
+    others: Vec<Dense>,
+    vectors: Vec<Dense>,
+}
+
+impl Batch {
+    fn new(size: usize, sampler: &mut Sampler) -> Self {
+        let mut rotor = || {
+            let r = sampler.rotor();
+            let mut c = [0.0; 8];
+            (c[0b000], c[0b011], c[0b101], c[0b110]) = (r.scalar, r.e12, r.e13, r.e23);
+            c
+        };
+        let rotors = (0..size).map(|_| rotor()).collect();
+        let others = (0..size).map(|_| rotor()).collect();
+        let vectors = (0..size)
+            .map(|_| {
+                let v = sampler.unit_vector();
+                let mut c = [0.0; 8];
+                (c[0b001], c[0b010], c[0b100]) = (v[0], v[1], v[2]);
+                c
+            })
+            .collect();
+        Self { rotors, others, vectors }
+    }
+}
+
+fn sparse(c: &Dense, masks: &[usize]) -> Multivector<f64> {
+    let terms: Vec<BladeTerm<f64>> = masks.iter().map(|&m| BladeTerm::new(mask_indices(m as u32), c[m])).collect();
+    Multivector::from_terms(3, &terms).unwrap().with_policy(DensityPolicy::always_sparse())
+}
+
+fn blades(c: &Dense, masks: &[usize]) -> Blades {
+    masks.iter().map(|&m| (m, c[m])).collect()
+}
+
+/// Geometric product of blade lists through the Vga3 table
+fn blades_product(a: &Blades, b: &Blades) -> Blades {
+    let mut result: Blades = SmallVec::new();
+    for &(i, x) in a {
+        for &(j, y) in b {
+            let entry = Vga3::GEOMETRIC[i][j];
+            let value = f64::from(entry.sign) * x * y;
+            match result.iter_mut().find(|(m, _)| *m == entry.blade) {
+                Some((_, c)) => *c += value,
+                None => result.push((entry.blade, value)),
+            }
+        }
+    }
+    result
+}
+
+fn blades_sum(a: &Blades, b: &Blades) -> Blades {
+    let mut result = a.clone();
+    for &(m, y) in b {
+        match result.iter_mut().find(|(n, _)| *n == m) {
+            Some((_, c)) => *c += y,
+            None => result.push((m, y)),
+        }
+    }
+    result
+}
+
+fn blades_reverse(a: &Blades) -> Blades {
+    a.iter().map(|&(m, c)| (m, f64::from(Vga3::REVERSE[m]) * c)).collect()
+}
+
+/// Columns of a batch, one per blade
+struct Columns([Vec<f64>; 8]);
+
+impl Columns {
+    fn new(elements: &[Dense]) -> Self {
+        Self(std::array::from_fn(|blade| elements.iter().map(|e| e[blade]).collect()))
+    }
+
+    fn len(&self) -> usize {
+        self.0[0].len()
+    }
+
+    fn zero(len: usize) -> Self {
+        Self(std::array::from_fn(|_| vec![0.0; len]))
+    }
+
+    fn sum(&self, other: &Columns) -> Columns {
+        Columns(std::array::from_fn(|blade| self.0[blade].iter().zip(&other.0[blade]).map(|(a, b)| a + b).collect()))
+    }
+
+    /// Product over the listed blades of each side, one pass over the batch per blade pair
+    fn product(&self, lhs_masks: &[usize], other: &Columns, rhs_masks: &[usize]) -> Columns {
+        let mut result = Columns::zero(self.len());
+        for &i in lhs_masks {
+            for &j in rhs_masks {
+                let entry = Vga3::GEOMETRIC[i][j];
+                let sign = f64::from(entry.sign);
+                let (a, b) = (&self.0[i], &other.0[j]);
+                for (k, out) in result.0[entry.blade].iter_mut().enumerate() {
+                    *out += sign * a[k] * b[k];
+                }
+            }
+        }
+        result
+    }
+
+    fn reverse(&self) -> Columns {
+        Columns(std::array::from_fn(|blade| self.0[blade].iter().map(|c| f64::from(Vga3::REVERSE[blade]) * c).collect()))
+    }
+}
+
+/// Best time per element over the repetitions, in nanoseconds
+fn time(size: usize, mut run: impl FnMut()) -> (usize, f64) {
+    let inner = MIN_ELEMENTS.div_ceil(size);
+    let best = (0..REPETITIONS)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..inner {
+                run();
+            }
+            start.elapsed().as_nanos() as f64
+        })
+        .fold(f64::INFINITY, f64::min);
+    (inner, best / (inner * size) as f64)
+}
+
+fn measure(representation: &str, operation: &str, batch: &Batch) -> (usize, f64) {
+    let size = batch.rotors.len();
+    let (r, o, v) = (&batch.rotors, &batch.others, &batch.vectors);
+    match representation {
+        "sparse_vec" => {
+            let (r, o): (Vec<_>, Vec<_>) = (r.iter().map(|c| sparse(c, &ROTOR_MASKS)).collect(), o.iter().map(|c| sparse(c, &ROTOR_MASKS)).collect());
+            let v: Vec<_> = v.iter().map(|c| sparse(c, &VECTOR_MASKS)).collect();
+            match operation {
+                "add" => time(size, || {
+                    black_box(r.iter().zip(&o).map(|(a, b)| a.clone() + b.clone()).collect::<Vec<_>>());
+                }),
+                "product" => time(size, || {
+                    black_box(r.iter().zip(&o).map(|(a, b)| a * b).collect::<Vec<_>>());
+                }),
+                _ => time(size, || {
+                    black_box(r.iter().zip(&v).map(|(a, x)| &(a * x) * &a.reverse()).collect::<Vec<_>>());
+                }),
+            }
+        }
+        "smallvec" => {
+            let (r, o): (Vec<_>, Vec<_>) = (r.iter().map(|c| blades(c, &ROTOR_MASKS)).collect(), o.iter().map(|c| blades(c, &ROTOR_MASKS)).collect());
+            let v: Vec<_> = v.iter().map(|c| blades(c, &VECTOR_MASKS)).collect();
+            match operation {
+                "add" => time(size, || {
+                    black_box(r.iter().zip(&o).map(|(a, b)| blades_sum(a, b)).collect::<Vec<_>>());
+                }),
+                "product" => time(size, || {
+                    black_box(r.iter().zip(&o).map(|(a, b)| blades_product(a, b)).collect::<Vec<_>>());
+                }),
+                _ => time(size, || {
+                    black_box(r.iter().zip(&v).map(|(a, x)| blades_product(&blades_product(a, x), &blades_reverse(a))).collect::<Vec<_>>());
+                }),
+            }
+        }
+        "dense_array" => match operation {
+            "add" => time(size, || {
+                black_box(r.iter().zip(o).map(|(a, b)| std::array::from_fn::<f64, 8, _>(|k| a[k] + b[k])).collect::<Vec<_>>());
+            }),
+            "product" => time(size, || {
+                black_box(r.iter().zip(o).map(|(a, b)| Vga3::geometric_product(a, b)).collect::<Vec<_>>());
+            }),
+            _ => time(size, || {
+                black_box(
+                    r.iter()
+                        .zip(v)
+                        .map(|(a, x)| Vga3::geometric_product(&Vga3::geometric_product(a, x), &Vga3::reverse(a)))
+                        .collect::<Vec<_>>(),
+                );
+            }),
+        },
+        _ => {
+            let (r, o, v) = (Columns::new(r), Columns::new(o), Columns::new(v));
+            match operation {
+                "add" => time(size, || {
+                    black_box(r.sum(&o));
+                }),
+                "product" => time(size, || {
+                    black_box(r.product(&ROTOR_MASKS, &o, &ROTOR_MASKS));
+                }),
+                _ => time(size, || {
+                    // R v has odd grades only: the vector and trivector blades
+                    let rv = r.product(&ROTOR_MASKS, &v, &VECTOR_MASKS);
+                    black_box(rv.product(&[0b001, 0b010, 0b100, 0b111], &r.reverse(), &ROTOR_MASKS));
+                }),
+            }
+        }
+    }
+}
+
+fn machine() -> String {
+    std::env::var("GAFRO_BENCH_MACHINE")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok().map(|h| h.trim().to_string()))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn main() -> std::io::Result<()> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "representations.csv".to_string());
+    let new_file = std::fs::metadata(&path).map(|m| m.len() == 0).unwrap_or(true);
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    if new_file {
+        writeln!(file, "machine,arch,representation,operation,size,iterations,ns_per_element")?;
+    }
+
+    let (machine, arch) = (machine(), std::env::consts::ARCH);
+    let mut sampler = Sampler::new(1);
+    for size in SIZES {
+        let batch = Batch::new(size, &mut sampler);
+        for representation in ["sparse_vec", "smallvec", "dense_array", "soa_batch"] {
+            for operation in OPERATIONS {
+                let (iterations, ns) = measure(representation, operation, &batch);
+                writeln!(file, "{},{},{},{},{},{},{:.3}", machine, arch, representation, operation, size, iterations, ns)?;
+                println!("{:>12} {:>9} {:>8} {:>8.2} ns/element", representation, operation, size, ns);
+            }
+        }
+    }
+    println!("appended to {}", path);
+    Ok(())
+}
@@ -19,6 +19,10 @@
 #include <cmath>
 #include <variant>
 #include <chrono>
+#include <optional>
+
+// Shared with the JSON test runner so both derive tolerances the same way
+#include "../../shared_tests/cpp/tolerance.hpp"
 
 // Simplified JSON parsing for demonstration
 namespace json_simple {
@@ -101,20 +105,29 @@ Mass kilograms(double v) { return Mass(v); }
 
 // === Validation Test Functions ===
 
+using gafro_test::Tolerance;
+
+struct TestRecord {
+    std::string name;
+    bool passed;
+    double error;
+    std::optional<Tolerance> tolerance;
+};
+
 class Phase2Validator {
 private:
-    int tests_run_ = 0;
-    int tests_passed_ = 0;
-    double total_error_ = 0.0;
-
-    void record_test(bool passed, double error = 0.0) {
-        tests_run_++;
-        if (passed) tests_passed_++;
-        total_error_ += error;
+    std::vector<TestRecord> records_;
+
+    /// Record a check without a numerical comparison
+    void record_test(const std::string& name, bool passed) {
+        records_.push_back({name, passed, 0.0, std::nullopt});
     }
 
-    bool within_tolerance(double actual, double expected, double tolerance) {
-        return std::abs(actual - expected) <= tolerance;
+    /// Compare against the expected value and record the result
+    bool check(const std::string& name, double actual, double expected, const Tolerance& tolerance) {
+        bool passed = tolerance.check(actual, expected);
+        records_.push_back({name, passed, std::abs(actual - expected), tolerance});
+        return passed;
     }
 
 public:
@@ -129,10 +142,8 @@ public:
             auto sum = s1 + s2;
 
             double expected = 5.85987;
-            double tolerance = 1e-5;
-
-            bool passed = within_tolerance(sum.value, expected, tolerance);
-            record_test(passed, std::abs(sum.value - expected));
+            // Three decimal literals and one addition
+            bool passed = check("scalar addition", sum.value, expected, Tolerance::budget(4, expected));
 
             std::cout << "✓ Scalar addition: " << sum.value
                       << " (expected: " << expected << ") "
@@ -147,7 +158,7 @@ public:
             static_assert(Bivector::grade == 2, "Bivector grade must be 2");
 
             std::cout << "✓ Compile-time grade checking: PASS\n";
-            record_test(true);
+            record_test("compile-time grades", true);
         }
     }
 
@@ -162,10 +173,7 @@ public:
             auto velocity = distance / time;
 
             double expected = 5.0;
-            double tolerance = 1e-10;
-
-            bool passed = within_tolerance(velocity.value, expected, tolerance);
-            record_test(passed, std::abs(velocity.value - expected));
+            bool passed = check("velocity", velocity.value, expected, Tolerance::budget(1, expected));
 
             std::cout << "✓ Velocity calculation: " << velocity.value << " m/s "
                       << " (expected: " << expected << ") "
@@ -185,10 +193,8 @@ public:
             auto force = mass * acceleration;
 
             double expected = 49.05;
-            double tolerance = 1e-10;
-
-            bool passed = within_tolerance(force.value, expected, tolerance);
-            record_test(passed, std::abs(force.value - expected));
+            // Two decimal literals and one multiplication
+            bool passed = check("force", force.value, expected, Tolerance::budget(3, expected));
 
             std::cout << "✓ Force calculation: " << force.value << " N "
                       << " (expected: " << expected << ") "
@@ -216,13 +222,11 @@ public:
 
             double expected_sin = 1.0;
             double expected_cos = 0.0; // approximately
-            double tolerance = 1e-10;
+            // Rounding of τ, then the trigonometric function
+            auto tolerance = Tolerance::budget(2, TAU);
 
-            bool sin_passed = within_tolerance(sin_val, expected_sin, tolerance);
-            bool cos_passed = std::abs(cos_val) < tolerance; // cos(π/2) ≈ 0
-
-            record_test(sin_passed, std::abs(sin_val - expected_sin));
-            record_test(cos_passed, std::abs(cos_val));
+            bool sin_passed = check("sin(τ/4)", sin_val, expected_sin, tolerance);
+            bool cos_passed = check("cos(τ/4)", cos_val, expected_cos, tolerance);
 
             std::cout << "✓ Quarter turn (τ/4): sin=" << sin_val << ", cos=" << cos_val << "\n";
             std::cout << "  τ/4 = " << quarter_turn << " radians (more intuitive than π/2)\n";
@@ -237,13 +241,10 @@ public:
 
             double expected_sin = 0.0;
             double expected_cos = 1.0;
-            double tolerance = 1e-10;
-
-            bool sin_passed = std::abs(sin_val) < tolerance;
-            bool cos_passed = within_tolerance(cos_val, expected_cos, tolerance);
+            auto tolerance = Tolerance::budget(2, TAU);
 
-            record_test(sin_passed, std::abs(sin_val));
-            record_test(cos_passed, std::abs(cos_val - expected_cos));
+            bool sin_passed = check("sin(τ)", sin_val, expected_sin, tolerance);
+            bool cos_passed = check("cos(τ)", cos_val, expected_cos, tolerance);
 
             std::cout << "✓ Full turn (1τ): sin=" << sin_val << ", cos=" << cos_val << "\n";
             std::cout << "  1τ = " << full_turn << " radians (more intuitive than 2π)\n";
@@ -260,7 +261,7 @@ public:
             std::cout << "  Modern: " << robot_angle_tau << " radians (τ-based)\n";
             std::cout << "  Fraction: " << robot_angle_degrees / 360.0 << "τ (more intuitive!)\n";
 
-            record_test(true); // Always passes - just demonstrating clarity
+            record_test("joint angle in τ", true); // Always passes - just demonstrating clarity
         }
     }
 
@@ -279,13 +280,11 @@ public:
 
             double expected_x = 0.35355339059327373;
             double expected_y = 0.35355339059327373;
-            double tolerance = 1e-10;
+            // τ, the degree conversion (two ops), cos/sin, the link length and the expected literal
+            auto tolerance = Tolerance::budget(6, joint_angle_deg * TAU);
 
-            bool x_passed = within_tolerance(end_x, expected_x, tolerance);
-            bool y_passed = within_tolerance(end_y, expected_y, tolerance);
-
-            record_test(x_passed, std::abs(end_x - expected_x));
-            record_test(y_passed, std::abs(end_y - expected_y));
+            bool x_passed = check("forward kinematics x", end_x, expected_x, tolerance);
+            bool y_passed = check("forward kinematics y", end_y, expected_y, tolerance);
 
             std::cout << "✓ Forward kinematics (45° joint):\n";
             std::cout << "  End effector position: (" << end_x << ", " << end_y << ")\n";
@@ -304,13 +303,12 @@ public:
 
             double expected_error = 0.8;
             double expected_velocity = 1.6;
-            double tolerance = 1e-10;
-
-            bool error_passed = within_tolerance(position_error.value, expected_error, tolerance);
-            bool velocity_passed = within_tolerance(control_velocity.value, expected_velocity, tolerance);
 
-            record_test(error_passed, std::abs(position_error.value - expected_error));
-            record_test(velocity_passed, std::abs(control_velocity.value - expected_velocity));
+            // Two decimal literals and a subtraction, then the gain multiplication
+            bool error_passed =
+                check("position error", position_error.value, expected_error, Tolerance::budget(3, 1.0));
+            bool velocity_passed =
+                check("control velocity", control_velocity.value, expected_velocity, Tolerance::budget(4, 1.6));
 
             std::cout << "✓ Velocity control:\n";
             std::cout << "  Position error: " << position_error.value << " m\n";
@@ -348,20 +346,33 @@ public:
         std::cout << "  Velocity (10m/2s): " << velocity_test << " m/s\n";
         std::cout << "  Kinetic Energy (5kg, 5m/s): " << kinetic_energy << " J\n";
 
-        record_test(true); // These are reference values for Rust comparison
+        record_test("cross-language reference values", true); // These are reference values for Rust comparison
     }
 
     void print_summary() {
         std::cout << "\n📊 VALIDATION SUMMARY\n";
         std::cout << "=====================\n";
-        std::cout << "Tests run: " << tests_run_ << "\n";
-        std::cout << "Tests passed: " << tests_passed_ << "\n";
+
+        int tests_run = static_cast<int>(records_.size());
+        int tests_passed = 0;
+        double total_error = 0.0;
+        for (const auto& record : records_) {
+            std::cout << "  [" << (record.passed ? "PASS" : "FAIL") << "] " << std::left << std::setw(32)
+                      << record.name << std::right << " error " << std::setprecision(2) << std::scientific
+                      << record.error << std::defaultfloat << "  tolerance "
+                      << (record.tolerance ? record.tolerance->describe() : "n/a") << "\n";
+            if (record.passed) tests_passed++;
+            total_error += record.error;
+        }
+
+        std::cout << "\nTests run: " << tests_run << "\n";
+        std::cout << "Tests passed: " << tests_passed << "\n";
         std::cout << "Success rate: " << std::setprecision(1) << std::fixed
-                  << (100.0 * tests_passed_ / tests_run_) << "%\n";
+                  << (100.0 * tests_passed / tests_run) << "%\n";
         std::cout << "Average error: " << std::setprecision(2) << std::scientific
-                  << (total_error_ / tests_run_) << "\n";
+                  << (total_error / tests_run) << "\n";
 
-        if (tests_passed_ == tests_run_) {
+        if (tests_passed == tests_run) {
             std::cout << "\n🎉 ALL TESTS PASSED! Phase 2 implementation is validated.\n";
             std::cout << "✅ Ready for cross-language comparison with Rust.\n";
         } else {
@@ -138,6 +138,31 @@ struct Angle {
     }
 };
 
+/// Weighted intrinsic mean of headings, normalized to [0, τ)
+///
+/// Planar case of average_rotors in rust_modern: each step moves the estimate by
+/// the weighted mean of the wrapped differences, so headings either side of 0°
+/// average correctly.
+Angle average_headings(const std::vector<Angle>& headings, const std::vector<double>& weights) {
+    double total = 0.0;
+    for (double weight : weights) {
+        total += weight;
+    }
+    double mean = headings.front().radians;
+    for (int iteration = 0; iteration < 64; ++iteration) {
+        double step = 0.0;
+        for (size_t i = 0; i < headings.size(); ++i) {
+            double difference = std::remainder(headings[i].radians - mean, TAU);
+            step += weights[i] / total * difference;
+        }
+        mean += step;
+        if (std::abs(step) < 1e-12) {
+            break;
+        }
+    }
+    return Angle(mean).normalized();
+}
+
 // === Autonomous Navigation Controller ===
 class AutonomousNavigationDemo {
 private:
@@ -315,7 +340,7 @@ public:
 
         // Odometry reading (distance traveled)
         auto odometry_distance = meters(3.2);
-        auto odometry_heading = Angle::from_degrees(85.0);
+        auto odometry_heading = Angle::from_degrees(-255.0); // unwrapped, same direction as 105°
 
         // IMU reading (angular velocity)
         auto imu_angular_vel = radians_per_second(0.1);
@@ -331,10 +356,18 @@ public:
 
         // Fuse sensor data with type safety
         auto estimated_angular_change = imu_angular_vel * measurement_time;
-        auto fused_heading = current_heading_ + Angle(estimated_angular_change.value);
+        auto gyro_heading = (current_heading_ + Angle(estimated_angular_change.value)).normalized();
+
+        // Averaging the raw angles lands on the opposite side of the circle
+        auto naive_heading = Angle(0.5 * (gyro_heading.radians + odometry_heading.radians));
+
+        // Average the headings on the circle instead, trusting the gyro twice as much
+        auto fused_heading = average_headings({gyro_heading, odometry_heading}, {2.0, 1.0});
 
         std::cout << "\nFusion Results:\n";
         std::cout << "  Angular change: " << estimated_angular_change.value << " rad\n";
+        std::cout << "  Gyro heading: " << gyro_heading.to_degrees() << "°\n";
+        std::cout << "  Naive angle average: " << naive_heading.to_degrees() << "° (wrong: ignores the wrap)\n";
         std::cout << "  Fused heading: " << fused_heading.to_degrees() << "°\n";
 
         // Type system ensures dimensional correctness
@@ -347,7 +380,7 @@ public:
             current_position_.y + odometry_distance.value * std::sin(fused_heading.radians),
             current_position_.z
         );
-        current_heading_ = fused_heading.normalized();
+        current_heading_ = fused_heading;
 
         std::cout << "✓ Updated position: (" << current_position_.x << ", "
                   << current_position_.y << ", " << current_position_.z << ")\n";
@@ -20,6 +20,7 @@ const TAU: f64 = 2.0 * PI; // τ = 2π
 
 // Import canonical output for consistent formatting
 use gafro_test_runner::canonical_output::{CanonicalOutput, PositionLike};
+use gafro_modern::motor::{average_rotors, Rotor};
 
 // === Type-Safe Coordinate Systems ===
 trait Frame {
@@ -425,7 +426,7 @@ impl AutonomousNavigationDemo {
 
         // Odometry reading (distance traveled)
         let odometry_distance = meters(3.2);
-        let odometry_heading = Angle::from_degrees(85.0);
+        let odometry_heading = Angle::from_degrees(-255.0); // unwrapped, same direction as 105°
 
         // IMU reading (angular velocity)
         let imu_angular_vel = radians_per_second(0.1);
@@ -441,10 +442,24 @@ impl AutonomousNavigationDemo {
 
         // Fuse sensor data with type safety
         let estimated_angular_change = imu_angular_vel * measurement_time;
-        let fused_heading = self.current_heading + estimated_angular_change;
+        let gyro_heading = (self.current_heading + estimated_angular_change).normalized();
+
+        // Averaging the raw angles lands on the opposite side of the circle
+        let naive_heading = Angle::new(0.5 * (gyro_heading.radians + odometry_heading.radians));
+
+        // Average the headings as rotors instead, trusting the gyro twice as much
+        let up = [0.0, 0.0, 1.0];
+        let headings = [
+            Rotor::from_axis_angle(up, gyro_heading.radians),
+            Rotor::from_axis_angle(up, odometry_heading.radians),
+        ];
+        let fused_rotor = average_rotors(&headings, Some(&[2.0, 1.0])).expect("two positively weighted headings");
+        let fused_heading = Angle::new(fused_rotor.rotation_vector()[2]).normalized();
 
         println!("\nFusion Results:");
         println!("  Angular change: {} rad", estimated_angular_change.radians);
+        println!("  Gyro heading: {:.1}°", gyro_heading.to_degrees());
+        println!("  Naive angle average: {:.1}° (wrong: ignores the wrap)", naive_heading.to_degrees());
         println!("  Fused heading: {:.1}°", fused_heading.to_degrees());
 
         // Type system ensures dimensional correctness (compile-time verification)
@@ -456,7 +471,7 @@ impl AutonomousNavigationDemo {
             self.current_position.y + odometry_distance.value * fused_heading.radians.sin(),
             self.current_position.z,
         );
-        self.current_heading = fused_heading.normalized();
+        self.current_heading = fused_heading;
 
         println!("✓ Updated position: ({}, {}, {})",
                 self.current_position.x, self.current_position.y, self.current_position.z);
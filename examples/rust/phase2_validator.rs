@@ -13,6 +13,12 @@
 use std::f64::consts::PI;
 use std::time::Instant;
 
+// Shared with the JSON test runner so both derive tolerances the same way
+#[path = "../../shared_tests/rust/src/tolerance.rs"]
+mod tolerance;
+
+use tolerance::Tolerance;
+
 // === Mathematical Constants ===
 const TAU: f64 = 2.0 * PI; // τ = 2π
 
@@ -123,31 +129,32 @@ fn kilograms(v: f64) -> Mass {
 
 // === Validation Test Functions ===
 
+struct TestRecord {
+    name: &'static str,
+    passed: bool,
+    error: f64,
+    tolerance: Option<Tolerance>,
+}
+
 struct Phase2Validator {
-    tests_run: u32,
-    tests_passed: u32,
-    total_error: f64,
+    records: Vec<TestRecord>,
 }
 
 impl Phase2Validator {
     fn new() -> Self {
-        Self {
-            tests_run: 0,
-            tests_passed: 0,
-            total_error: 0.0,
-        }
+        Self { records: Vec::new() }
     }
 
-    fn record_test(&mut self, passed: bool, error: f64) {
-        self.tests_run += 1;
-        if passed {
-            self.tests_passed += 1;
-        }
-        self.total_error += error;
+    /// Record a check without a numerical comparison
+    fn record_test(&mut self, name: &'static str, passed: bool) {
+        self.records.push(TestRecord { name, passed, error: 0.0, tolerance: None });
     }
 
-    fn within_tolerance(&self, actual: f64, expected: f64, tolerance: f64) -> bool {
-        (actual - expected).abs() <= tolerance
+    /// Compare against the expected value and record the result
+    fn check(&mut self, name: &'static str, actual: f64, expected: f64, tolerance: Tolerance) -> bool {
+        let passed = tolerance.check(actual, expected);
+        self.records.push(TestRecord { name, passed, error: (actual - expected).abs(), tolerance: Some(tolerance) });
+        passed
     }
 
     fn run_type_safety_tests(&mut self) {
@@ -161,10 +168,8 @@ impl Phase2Validator {
             let sum = s1 + s2;
 
             let expected = 5.85987;
-            let tolerance = 1e-5;
-
-            let passed = self.within_tolerance(sum.value, expected, tolerance);
-            self.record_test(passed, (sum.value - expected).abs());
+            // Three decimal literals and one addition
+            let passed = self.check("scalar addition", sum.value, expected, Tolerance::budget(4, expected));
 
             println!("✓ Scalar addition: {} (expected: {}) {}",
                     sum.value, expected, if passed { "PASS" } else { "FAIL" });
@@ -179,7 +184,7 @@ impl Phase2Validator {
             assert_eq!(Bivector::grade(), 2);
 
             println!("✓ Compile-time grade checking: PASS");
-            self.record_test(true, 0.0);
+            self.record_test("compile-time grades", true);
         }
     }
 
@@ -194,10 +199,7 @@ impl Phase2Validator {
             let velocity = distance / time;
 
             let expected = 5.0;
-            let tolerance = 1e-10;
-
-            let passed = self.within_tolerance(velocity.value, expected, tolerance);
-            self.record_test(passed, (velocity.value - expected).abs());
+            let passed = self.check("velocity", velocity.value, expected, Tolerance::budget(1, expected));
 
             println!("✓ Velocity calculation: {} m/s (expected: {}) {}",
                     velocity.value, expected, if passed { "PASS" } else { "FAIL" });
@@ -214,10 +216,8 @@ impl Phase2Validator {
             let force = mass * acceleration;
 
             let expected = 49.05;
-            let tolerance = 1e-10;
-
-            let passed = self.within_tolerance(force.value, expected, tolerance);
-            self.record_test(passed, (force.value - expected).abs());
+            // Two decimal literals and one multiplication
+            let passed = self.check("force", force.value, expected, Tolerance::budget(3, expected));
 
             println!("✓ Force calculation: {} N (expected: {}) {}",
                     force.value, expected, if passed { "PASS" } else { "FAIL" });
@@ -240,13 +240,11 @@ impl Phase2Validator {
 
             let expected_sin = 1.0;
             let expected_cos = 0.0; // approximately
-            let tolerance = 1e-10;
-
-            let sin_passed = self.within_tolerance(sin_val, expected_sin, tolerance);
-            let cos_passed = cos_val.abs() < tolerance; // cos(π/2) ≈ 0
+            // Rounding of τ, then the trigonometric function
+            let tolerance = Tolerance::budget(2, TAU);
 
-            self.record_test(sin_passed, (sin_val - expected_sin).abs());
-            self.record_test(cos_passed, cos_val.abs());
+            let sin_passed = self.check("sin(τ/4)", sin_val, expected_sin, tolerance);
+            let cos_passed = self.check("cos(τ/4)", cos_val, expected_cos, tolerance);
 
             println!("✓ Quarter turn (τ/4): sin={}, cos={}", sin_val, cos_val);
             println!("  τ/4 = {} radians (more intuitive than π/2)", quarter_turn);
@@ -261,13 +259,10 @@ impl Phase2Validator {
 
             let expected_sin = 0.0;
             let expected_cos = 1.0;
-            let tolerance = 1e-10;
+            let tolerance = Tolerance::budget(2, TAU);
 
-            let sin_passed = sin_val.abs() < tolerance;
-            let cos_passed = self.within_tolerance(cos_val, expected_cos, tolerance);
-
-            self.record_test(sin_passed, sin_val.abs());
-            self.record_test(cos_passed, (cos_val - expected_cos).abs());
+            let sin_passed = self.check("sin(τ)", sin_val, expected_sin, tolerance);
+            let cos_passed = self.check("cos(τ)", cos_val, expected_cos, tolerance);
 
             println!("✓ Full turn (1τ): sin={}, cos={}", sin_val, cos_val);
             println!("  1τ = {} radians (more intuitive than 2π)", full_turn);
@@ -284,7 +279,7 @@ impl Phase2Validator {
             println!("  Modern: {} radians (τ-based)", robot_angle_tau);
             println!("  Fraction: {}τ (more intuitive!)", robot_angle_degrees / 360.0);
 
-            self.record_test(true, 0.0); // Always passes - just demonstrating clarity
+            self.record_test("joint angle in τ", true); // Always passes - just demonstrating clarity
         }
     }
 
@@ -303,13 +298,11 @@ impl Phase2Validator {
 
             let expected_x = 0.35355339059327373;
             let expected_y = 0.35355339059327373;
-            let tolerance = 1e-10;
-
-            let x_passed = self.within_tolerance(end_x, expected_x, tolerance);
-            let y_passed = self.within_tolerance(end_y, expected_y, tolerance);
+            // τ, the degree conversion (two ops), cos/sin, the link length and the expected literal
+            let tolerance = Tolerance::budget(6, joint_angle_deg * TAU);
 
-            self.record_test(x_passed, (end_x - expected_x).abs());
-            self.record_test(y_passed, (end_y - expected_y).abs());
+            let x_passed = self.check("forward kinematics x", end_x, expected_x, tolerance);
+            let y_passed = self.check("forward kinematics y", end_y, expected_y, tolerance);
 
             println!("✓ Forward kinematics (45° joint):");
             println!("  End effector position: ({}, {})", end_x, end_y);
@@ -328,13 +321,12 @@ impl Phase2Validator {
 
             let expected_error = 0.8;
             let expected_velocity = 1.6;
-            let tolerance = 1e-10;
 
-            let error_passed = self.within_tolerance(position_error.value, expected_error, tolerance);
-            let velocity_passed = self.within_tolerance(control_velocity.value, expected_velocity, tolerance);
-
-            self.record_test(error_passed, (position_error.value - expected_error).abs());
-            self.record_test(velocity_passed, (control_velocity.value - expected_velocity).abs());
+            // Two decimal literals and a subtraction, then the gain multiplication
+            let error_passed =
+                self.check("position error", position_error.value, expected_error, Tolerance::budget(3, 1.0));
+            let velocity_passed =
+                self.check("control velocity", control_velocity.value, expected_velocity, Tolerance::budget(4, 1.6));
 
             println!("✓ Velocity control:");
             println!("  Position error: {} m", position_error.value);
@@ -372,18 +364,32 @@ impl Phase2Validator {
         println!("  Velocity (10m/2s): {} m/s", velocity_test);
         println!("  Kinetic Energy (5kg, 5m/s): {} J", kinetic_energy);
 
-        self.record_test(true, 0.0); // These are reference values for C++ comparison
+        self.record_test("cross-language reference values", true); // These are reference values for C++ comparison
     }
 
     fn print_summary(&self) {
         println!("\n📊 VALIDATION SUMMARY");
         println!("=====================");
-        println!("Tests run: {}", self.tests_run);
-        println!("Tests passed: {}", self.tests_passed);
-        println!("Success rate: {:.1}%", 100.0 * self.tests_passed as f64 / self.tests_run as f64);
-        println!("Average error: {:.2e}", self.total_error / self.tests_run as f64);
+        for record in &self.records {
+            let tolerance = record.tolerance.map_or("n/a".to_string(), |t| t.to_string());
+            println!(
+                "  [{}] {:<32} error {:.2e}  tolerance {}",
+                if record.passed { "PASS" } else { "FAIL" },
+                record.name,
+                record.error,
+                tolerance
+            );
+        }
+
+        let tests_run = self.records.len();
+        let tests_passed = self.records.iter().filter(|record| record.passed).count();
+        let total_error: f64 = self.records.iter().map(|record| record.error).sum();
+        println!("\nTests run: {}", tests_run);
+        println!("Tests passed: {}", tests_passed);
+        println!("Success rate: {:.1}%", 100.0 * tests_passed as f64 / tests_run as f64);
+        println!("Average error: {:.2e}", total_error / tests_run as f64);
 
-        if self.tests_passed == self.tests_run {
+        if tests_passed == tests_run {
             println!("\n🎉 ALL TESTS PASSED! Phase 2 implementation is validated.");
             println!("✅ Ready for cross-language comparison with C++.");
         } else {
@@ -0,0 +1,24 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Records the compiler and target for `gafro_modern::build_info()`
+
+use std::process::Command;
+
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(&rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|v| v.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=GAFRO_RUSTC_VERSION={}", version);
+    println!("cargo:rustc-env=GAFRO_TARGET={}", std::env::var("TARGET").unwrap_or_default());
+    println!("cargo:rustc-env=GAFRO_PROFILE={}", std::env::var("PROFILE").unwrap_or_default());
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
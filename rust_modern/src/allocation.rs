@@ -0,0 +1,262 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Thrust allocation
+//!
+//! A [`ThrustAllocator`] splits a body-frame [`Wrench`] among fixed thrusters.
+//! Each [`ThrusterPlacement`] pushes along a unit direction from a point of the
+//! vehicle, contributing the column `(p × d, d)` of the configuration matrix
+//! `B`, and the unconstrained split is the minimum-norm least-squares solution
+//! `Bᵀ (B Bᵀ)⁻¹ τ`. Wrench components no thruster can produce are dropped.
+//!
+//! Thrust limits are handled by redistribution: thrusters whose share falls
+//! outside their limits are clamped and fixed, and the rest of the wrench is
+//! solved again over the remaining thrusters. [`Allocation::achieved`] is the
+//! wrench the clamped thrusts actually produce.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::grasp::Wrench;
+use crate::linalg::{self, dense, Vector3};
+use crate::si_units::Force;
+
+/// Regularization of `B Bᵀ`, so wrench components no thruster reaches are dropped
+const ALLOCATION_DAMPING: f64 = 1e-9;
+
+/// Thruster layouts that cannot be allocated
+#[derive(Debug, Clone, PartialEq)]
+pub enum AllocationError {
+    NoThrusters,
+    /// The thruster's direction is the zero vector
+    ZeroDirection(String),
+    /// The thruster's reverse limit is above its forward limit
+    InvertedLimits(String),
+}
+
+impl fmt::Display for AllocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocationError::NoThrusters => write!(f, "no thrusters to allocate to"),
+            AllocationError::ZeroDirection(name) => write!(f, "thruster '{}' has no direction", name),
+            AllocationError::InvertedLimits(name) => write!(f, "thruster '{}' has its reverse limit above its forward limit", name),
+        }
+    }
+}
+
+impl std::error::Error for AllocationError {}
+
+/// Where a thruster sits on the vehicle and how hard it can push
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThrusterPlacement {
+    pub name: String,
+    /// Point of action in the body frame
+    pub position: Vector3,
+    /// Direction of positive thrust in the body frame
+    pub direction: Vector3,
+    /// Most negative (reverse) thrust
+    pub min: Force,
+    pub max: Force,
+}
+
+impl ThrusterPlacement {
+    /// Thruster pushing up to `max` either way
+    pub fn new(name: &str, position: Vector3, direction: Vector3, max: Force) -> Self {
+        let max = Force::new(max.value().abs());
+        Self { name: name.to_string(), position, direction, min: Force::new(-max.value()), max }
+    }
+
+    /// Reverse thrust limit, for thrusters weaker backwards
+    pub fn with_reverse(mut self, min: Force) -> Self {
+        self.min = min;
+        self
+    }
+}
+
+/// Thrusts for a demanded wrench and what they achieve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Allocation {
+    /// One per thruster, in placement order
+    pub thrusts: Vec<Force>,
+    /// Wrench of the thrusts about the body origin
+    pub achieved: Wrench,
+    /// Whether any thruster was clamped to a limit
+    pub saturated: bool,
+}
+
+/// Splits body-frame wrenches among a fixed set of thrusters
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrustAllocator {
+    placements: Vec<ThrusterPlacement>,
+    /// Column `(p × d, d)` of each thruster
+    columns: Vec<[f64; 6]>,
+}
+
+impl ThrustAllocator {
+    /// Allocator for `placements`, whose directions are normalized
+    pub fn new(mut placements: Vec<ThrusterPlacement>) -> Result<Self, AllocationError> {
+        if placements.is_empty() {
+            return Err(AllocationError::NoThrusters);
+        }
+        let mut columns = Vec::with_capacity(placements.len());
+        for placement in &mut placements {
+            let length = linalg::norm(placement.direction);
+            if length == 0.0 {
+                return Err(AllocationError::ZeroDirection(placement.name.clone()));
+            }
+            if placement.min > placement.max {
+                return Err(AllocationError::InvertedLimits(placement.name.clone()));
+            }
+            placement.direction = linalg::scale(placement.direction, 1.0 / length);
+            let d = placement.direction;
+            let moment = linalg::cross(placement.position, d);
+            columns.push([moment[0], moment[1], moment[2], d[0], d[1], d[2]]);
+        }
+        Ok(Self { placements, columns })
+    }
+
+    pub fn placements(&self) -> &[ThrusterPlacement] {
+        &self.placements
+    }
+
+    /// Wrench about the body origin of one thrust per thruster, in newtons
+    pub fn wrench(&self, thrusts: &[f64]) -> Wrench {
+        let mut total = [0.0; 6];
+        for (column, thrust) in self.columns.iter().zip(thrusts) {
+            for (total, c) in total.iter_mut().zip(column) {
+                *total += c * thrust;
+            }
+        }
+        Wrench::new([total[3], total[4], total[5]], [total[0], total[1], total[2]])
+    }
+
+    /// Thrusts within the limits producing `demand`, or as much of it as they can
+    pub fn allocate(&self, demand: &Wrench) -> Allocation {
+        let target = [demand.torque[0], demand.torque[1], demand.torque[2], demand.force[0], demand.force[1], demand.force[2]];
+        let mut thrusts = vec![0.0; self.columns.len()];
+        let mut fixed = vec![false; self.columns.len()];
+        let mut saturated = false;
+
+        while fixed.iter().any(|fixed| !fixed) {
+            let mut residual = target;
+            let mut gram = [0.0; 36];
+            for (k, column) in self.columns.iter().enumerate() {
+                if fixed[k] {
+                    for (r, c) in residual.iter_mut().zip(column) {
+                        *r -= c * thrusts[k];
+                    }
+                } else {
+                    for i in 0..6 {
+                        for j in 0..6 {
+                            gram[i * 6 + j] += column[i] * column[j];
+                        }
+                    }
+                }
+            }
+            for i in 0..6 {
+                gram[i * 6 + i] += ALLOCATION_DAMPING;
+            }
+            let Some(y) = dense::cholesky_solve(&gram, 6, &residual) else {
+                break;
+            };
+
+            let mut clamped = false;
+            for (k, column) in self.columns.iter().enumerate() {
+                if fixed[k] {
+                    continue;
+                }
+                let thrust: f64 = column.iter().zip(&y).map(|(c, y)| c * y).sum();
+                let (min, max) = (*self.placements[k].min.value(), *self.placements[k].max.value());
+                thrusts[k] = thrust.clamp(min, max);
+                if thrusts[k] != thrust {
+                    fixed[k] = true;
+                    clamped = true;
+                }
+            }
+            if !clamped {
+                break;
+            }
+            saturated = true;
+        }
+
+        Allocation { achieved: self.wrench(&thrusts), thrusts: thrusts.into_iter().map(Force::new).collect(), saturated }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Four horizontal thrusters at 45° in the corners and two vertical ones
+    fn vectored() -> ThrustAllocator {
+        let h = std::f64::consts::FRAC_1_SQRT_2;
+        let max = Force::new(50.0);
+        ThrustAllocator::new(vec![
+            ThrusterPlacement::new("front_left", [0.4, 0.3, 0.0], [h, -h, 0.0], max),
+            ThrusterPlacement::new("front_right", [0.4, -0.3, 0.0], [h, h, 0.0], max),
+            ThrusterPlacement::new("rear_left", [-0.4, 0.3, 0.0], [h, h, 0.0], max),
+            ThrusterPlacement::new("rear_right", [-0.4, -0.3, 0.0], [h, -h, 0.0], max),
+            ThrusterPlacement::new("vertical_left", [0.0, 0.3, 0.0], [0.0, 0.0, 1.0], max),
+            ThrusterPlacement::new("vertical_right", [0.0, -0.3, 0.0], [0.0, 0.0, 1.0], max),
+        ])
+        .unwrap()
+    }
+
+    fn close(a: &Wrench, b: &Wrench, tolerance: f64) -> bool {
+        a.to_array().iter().zip(b.to_array()).all(|(a, b)| (a - b).abs() < tolerance)
+    }
+
+    #[test]
+    fn test_unconstrained_allocation_reproduces_the_wrench() {
+        let allocator = vectored();
+        let demand = Wrench::new([20.0, -10.0, 15.0], [0.0, 0.0, 4.0]);
+        let allocation = allocator.allocate(&demand);
+        assert!(!allocation.saturated);
+        assert!(close(&allocation.achieved, &demand, 1e-6), "{:?}", allocation.achieved);
+
+        // Pure surge is shared evenly by the horizontal thrusters
+        let surge = allocator.allocate(&Wrench::new([20.0, 0.0, 0.0], [0.0; 3]));
+        let horizontal: Vec<f64> = surge.thrusts[..4].iter().map(|t| *t.value()).collect();
+        assert!(horizontal.iter().all(|t| (t - 20.0 / (4.0 * std::f64::consts::FRAC_1_SQRT_2)).abs() < 1e-6));
+
+        // No thruster can roll the vehicle without heaving, so roll is traded off
+        let roll = allocator.allocate(&Wrench::new([0.0; 3], [3.0, 0.0, 0.0]));
+        assert!((roll.achieved.torque[0] - 3.0).abs() < 1e-6 && roll.achieved.force[2].abs() < 1e-6);
+        let pitch = allocator.allocate(&Wrench::new([0.0; 3], [0.0, 3.0, 0.0]));
+        assert!(pitch.thrusts.iter().all(|t| t.value().abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_saturated_thrusters_are_redistributed() {
+        let h = std::f64::consts::FRAC_1_SQRT_2;
+        let weak = ThrusterPlacement::new("weak", [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], Force::new(5.0));
+        let strong = ThrusterPlacement::new("strong", [0.0, 0.0, 0.0], [h, h, 0.0], Force::new(100.0));
+        let sway = ThrusterPlacement::new("sway", [0.0, 0.0, 0.0], [0.0, 1.0, 0.0], Force::new(100.0));
+        let allocator = ThrustAllocator::new(vec![weak, strong, sway]).unwrap();
+
+        // The weak thruster clamps and the others still deliver the full demand
+        let demand = Wrench::new([30.0, 0.0, 0.0], [0.0; 3]);
+        let allocation = allocator.allocate(&demand);
+        assert!(allocation.saturated);
+        assert_eq!(allocation.thrusts[0], Force::new(5.0));
+        assert!(close(&allocation.achieved, &demand, 1e-6), "{:?}", allocation);
+
+        // Beyond what the thrusters can give, every one ends at a limit
+        let allocation = vectored().allocate(&Wrench::new([500.0, 0.0, 0.0], [0.0; 3]));
+        assert!(allocation.saturated);
+        assert!(allocation.thrusts[..4].iter().all(|t| *t.value() == 50.0));
+        assert!((allocation.achieved.force[0] - 200.0 * h).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_layouts() {
+        assert_eq!(ThrustAllocator::new(vec![]), Err(AllocationError::NoThrusters));
+        let still = ThrusterPlacement::new("still", [0.0; 3], [0.0; 3], Force::new(10.0));
+        assert_eq!(ThrustAllocator::new(vec![still]), Err(AllocationError::ZeroDirection("still".to_string())));
+        let inverted = ThrusterPlacement::new("inverted", [0.0; 3], [1.0, 0.0, 0.0], Force::new(10.0)).with_reverse(Force::new(20.0));
+        assert_eq!(ThrustAllocator::new(vec![inverted]), Err(AllocationError::InvertedLimits("inverted".to_string())));
+    }
+}
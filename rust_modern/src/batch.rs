@@ -0,0 +1,58 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Batch motor application on the CPU
+//!
+//! Point-cloud scale loops over [`Motor::apply_point`] and motor products.
+//! Pairwise functions combine the `i`-th elements of both slices and stop at
+//! the shorter one. With the `gpu` feature, [`crate::gpu::GpuBatch`] offers
+//! the same functions as methods running on a compute device.
+
+use crate::linalg::Vector3;
+use crate::motor::Motor;
+
+/// Every point moved by one motor
+pub fn transform_points(motor: &Motor, points: &[Vector3]) -> Vec<Vector3> {
+    points.iter().map(|&p| motor.apply_point(p)).collect()
+}
+
+/// Point `i` moved by motor `i`
+pub fn transform_points_pairwise(motors: &[Motor], points: &[Vector3]) -> Vec<Vector3> {
+    motors.iter().zip(points).map(|(motor, &p)| motor.apply_point(p)).collect()
+}
+
+/// Products `lhs[i] * rhs[i]`
+pub fn compose_motors(lhs: &[Motor], rhs: &[Motor]) -> Vec<Motor> {
+    lhs.iter().zip(rhs).map(|(&a, &b)| a * b).collect()
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg;
+    use crate::primitives::Aabb;
+    use crate::sample::{MotorBounds, Sampler};
+
+    #[test]
+    fn test_batches_match_single_calls() {
+        let mut sampler = Sampler::new(14);
+        let bounds = MotorBounds::new(Aabb::new([-1.0; 3], [1.0; 3]));
+        let motors: Vec<Motor> = (0..50).map(|_| sampler.motor(&bounds)).collect();
+        let points: Vec<Vector3> = (0..60).map(|_| sampler.point_in_box(&Aabb::new([-2.0; 3], [2.0; 3]))).collect();
+
+        let moved = transform_points(&motors[0], &points);
+        assert_eq!(moved.len(), 60);
+        assert!(moved.iter().zip(&points).all(|(m, &p)| *m == motors[0].apply_point(p)));
+
+        let pairwise = transform_points_pairwise(&motors, &points);
+        assert_eq!(pairwise.len(), 50);
+        assert_eq!(pairwise[7], motors[7].apply_point(points[7]));
+
+        let composed = compose_motors(&motors, &motors[1..]);
+        assert_eq!(composed.len(), 49);
+        let twice = motors[3].apply_point(motors[4].apply_point(points[0]));
+        assert!(linalg::norm(linalg::sub(composed[3].apply_point(points[0]), twice)) < 1e-12);
+    }
+}
@@ -0,0 +1,326 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Microbenchmarks of user GA expressions
+//!
+//! A small wall-clock harness for downstream code, without a dependency on
+//! Criterion or a copy of the benchmarks crate. Each operation gets a
+//! [`Sampler`] seeded from [`BenchOptions::seed`] to build its inputs, so runs
+//! are reproducible, and returns the closure to time. Results use the
+//! normalized report of `benchmarks/perf_report.schema.json`, the one the
+//! benchmark runner exports, so they land on the same dashboards:
+//!
+//! ```
+//! use gafro_modern::gafro_bench;
+//! use gafro_modern::bench::BenchOptions;
+//!
+//! let report = gafro_bench!("my_expressions", BenchOptions::quick();
+//!     "rotor_apply" => |sampler| {
+//!         let (rotor, v) = (sampler.rotor(), sampler.unit_vector());
+//!         move || rotor.apply(v)
+//!     },
+//!     "compose" in [10, 100] => |sampler, size| {
+//!         let rotors: Vec<_> = (0..size).map(|_| sampler.rotor()).collect();
+//!         move || rotors.iter().fold(rotors[0], |acc, r| acc * *r)
+//!     },
+//! );
+//! assert_eq!(report.results.len(), 3);
+//! println!("{}", report);
+//! ```
+//!
+//! Each sample runs enough iterations to fill its share of the measurement
+//! time, and `ns_per_iter` is the mean over samples. Sized operations report
+//! throughput in elements per second.
+
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::environment::{build_info, BuildInfo};
+use crate::sample::Sampler;
+
+/// Version of the normalized report layout
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Timing budget of each operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchOptions {
+    pub warm_up: Duration,
+    pub measurement: Duration,
+    pub samples: usize,
+    /// Seed of the sampler handed to every setup closure
+    pub seed: u64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self { warm_up: Duration::from_millis(200), measurement: Duration::from_secs(1), samples: 20, seed: 0 }
+    }
+}
+
+impl BenchOptions {
+    /// Short budget for smoke runs and doctests
+    pub fn quick() -> Self {
+        Self { warm_up: Duration::from_millis(5), measurement: Duration::from_millis(20), samples: 5, seed: 0 }
+    }
+
+    pub fn with_measurement(mut self, measurement: Duration) -> Self {
+        self.measurement = measurement;
+        self
+    }
+
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Machine a report was measured on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Machine {
+    pub os: String,
+    pub arch: String,
+}
+
+impl Machine {
+    pub fn current() -> Self {
+        Self { os: std::env::consts::OS.to_string(), arch: std::env::consts::ARCH.to_string() }
+    }
+}
+
+/// Items processed per second
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Throughput {
+    /// `elements` or `bytes`
+    pub unit: String,
+    pub per_second: f64,
+}
+
+/// One timed operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub group: String,
+    pub op: String,
+    pub size: Option<u64>,
+    pub ns_per_iter: f64,
+    pub throughput: Option<Throughput>,
+}
+
+/// Normalized perf report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub schema_version: u32,
+    /// `rust` or `cpp`
+    pub language: String,
+    /// Unix time in seconds
+    pub generated_at: u64,
+    pub machine: Machine,
+    /// Build and floating-point environment of Rust reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<BuildInfo>,
+    pub results: Vec<Measurement>,
+}
+
+impl Report {
+    /// Report measured now on this machine, results sorted by group, op and size
+    ///
+    /// Rust reports carry this build's [`BuildInfo`].
+    pub fn new(language: &str, mut results: Vec<Measurement>) -> Self {
+        results.sort_by(|a, b| (&a.group, &a.op, a.size).cmp(&(&b.group, &b.op, b.size)));
+        let generated_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let environment = (language == "rust").then(build_info);
+        Self {
+            schema_version: SCHEMA_VERSION,
+            language: language.to_string(),
+            generated_at,
+            machine: Machine::current(),
+            environment,
+            results,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reports serialize")
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in &self.results {
+            let size = m.size.map_or_else(String::new, |s| s.to_string());
+            write!(f, "{:<24} {:<24} {:>8} {:>14.2} ns/iter", m.group, m.op, size, m.ns_per_iter)?;
+            if let Some(t) = &m.throughput {
+                write!(f, " {:>12.3e} {}/s", t.per_second, t.unit)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runner collecting the measurements of one group
+#[derive(Debug, Clone)]
+pub struct Bench {
+    group: String,
+    options: BenchOptions,
+    results: Vec<Measurement>,
+}
+
+impl Bench {
+    pub fn new(group: &str) -> Self {
+        Self { group: group.to_string(), options: BenchOptions::default(), results: Vec::new() }
+    }
+
+    pub fn with_options(mut self, options: BenchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Time the closure built by `setup`
+    pub fn run<F: FnMut() -> R, R>(&mut self, op: &str, setup: impl FnOnce(&mut Sampler) -> F) -> &Measurement {
+        let mut routine = setup(&mut Sampler::new(self.options.seed));
+        let ns_per_iter = self.time(&mut routine);
+        self.push(op, None, ns_per_iter)
+    }
+
+    /// Time the closure built by `setup` for each input size
+    pub fn run_sized<F: FnMut() -> R, R>(
+        &mut self,
+        op: &str,
+        sizes: impl IntoIterator<Item = usize>,
+        mut setup: impl FnMut(&mut Sampler, usize) -> F,
+    ) -> &[Measurement] {
+        let start = self.results.len();
+        for size in sizes {
+            let mut routine = setup(&mut Sampler::new(self.options.seed), size);
+            let ns_per_iter = self.time(&mut routine);
+            self.push(op, Some(size as u64), ns_per_iter);
+        }
+        &self.results[start..]
+    }
+
+    pub fn results(&self) -> &[Measurement] {
+        &self.results
+    }
+
+    pub fn finish(self) -> Report {
+        Report::new("rust", self.results)
+    }
+
+    fn push(&mut self, op: &str, size: Option<u64>, ns_per_iter: f64) -> &Measurement {
+        let throughput = size.map(|s| Throughput { unit: "elements".to_string(), per_second: s as f64 / (ns_per_iter * 1e-9) });
+        self.results.push(Measurement { group: self.group.clone(), op: op.to_string(), size, ns_per_iter, throughput });
+        self.results.last().expect("just pushed")
+    }
+
+    /// Mean nanoseconds per iteration over the samples
+    fn time<F: FnMut() -> R, R>(&self, routine: &mut F) -> f64 {
+        let options = &self.options;
+        // Warm up, doubling the batch, to estimate the cost of one iteration
+        let (mut iterations, mut elapsed, mut batch) = (0u64, Duration::ZERO, 1u64);
+        loop {
+            let start = Instant::now();
+            for _ in 0..batch {
+                black_box(routine());
+            }
+            elapsed += start.elapsed();
+            iterations += batch;
+            batch *= 2;
+            if elapsed >= options.warm_up {
+                break;
+            }
+        }
+        let per_iter = elapsed.as_secs_f64() / iterations.max(1) as f64;
+        let per_sample = options.measurement.as_secs_f64() / options.samples.max(1) as f64;
+        let batch = ((per_sample / per_iter.max(1e-12)) as u64).max(1);
+
+        let total: f64 = (0..options.samples.max(1))
+            .map(|_| {
+                let start = Instant::now();
+                for _ in 0..batch {
+                    black_box(routine());
+                }
+                start.elapsed().as_nanos() as f64 / batch as f64
+            })
+            .sum();
+        total / options.samples.max(1) as f64
+    }
+}
+
+/// Benchmark a group of operations and return its [`Report`]
+///
+/// Entries are `"op" => |sampler| closure` or, for sized inputs,
+/// `"op" in sizes => |sampler, size| closure`; see [`crate::bench`].
+#[macro_export]
+macro_rules! gafro_bench {
+    (@entries $bench:ident;) => {};
+    (@entries $bench:ident; $op:literal in $sizes:expr => $setup:expr $(, $($rest:tt)*)?) => {
+        $bench.run_sized($op, $sizes, $setup);
+        $crate::gafro_bench!(@entries $bench; $($($rest)*)?);
+    };
+    (@entries $bench:ident; $op:literal => $setup:expr $(, $($rest:tt)*)?) => {
+        $bench.run($op, $setup);
+        $crate::gafro_bench!(@entries $bench; $($($rest)*)?);
+    };
+    ($group:expr, $options:expr; $($entries:tt)+) => {{
+        let mut bench = $crate::bench::Bench::new($group).with_options($options);
+        $crate::gafro_bench!(@entries bench; $($entries)+);
+        bench.finish()
+    }};
+    ($group:expr; $($entries:tt)+) => {
+        $crate::gafro_bench!($group, $crate::bench::BenchOptions::default(); $($entries)+)
+    };
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slower_operations_measure_slower() {
+        let report = gafro_bench!("checks", BenchOptions::quick();
+            "short" => |sampler| {
+                let values: Vec<f64> = (0..10).map(|_| sampler.uniform()).collect();
+                move || values.iter().sum::<f64>()
+            },
+            "long" in [1_000, 10_000] => |sampler, size| {
+                let values: Vec<f64> = (0..size).map(|_| sampler.uniform()).collect();
+                move || values.iter().sum::<f64>()
+            },
+        );
+        let ns = |op: &str, size: Option<u64>| report.results.iter().find(|m| m.op == op && m.size == size).unwrap().ns_per_iter;
+        assert!(ns("short", None) < ns("long", Some(1_000)));
+        assert!(ns("long", Some(1_000)) < ns("long", Some(10_000)));
+        assert!(report.results.iter().all(|m| m.group == "checks" && m.ns_per_iter > 0.0));
+        assert_eq!(report.results[0].throughput.as_ref().map(|t| t.unit.as_str()), Some("elements"));
+    }
+
+    #[test]
+    fn test_report_round_trips_the_shared_schema() {
+        let mut bench = Bench::new("group").with_options(BenchOptions::quick().with_seed(3));
+        let first = bench.run("draw", |sampler| {
+            let x = sampler.uniform();
+            move || x * 2.0
+        });
+        assert!(first.size.is_none() && first.throughput.is_none());
+        let report = bench.finish();
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["language"], "rust");
+        assert_eq!(json["environment"]["crate_version"], crate::VERSION);
+        assert_eq!(json["results"][0]["op"], "draw");
+        assert!(json["results"][0]["size"].is_null());
+        let parsed: Report = serde_json::from_value(json).unwrap();
+        assert_eq!((parsed.machine, parsed.generated_at), (report.machine, report.generated_at));
+        assert!((parsed.results[0].ns_per_iter - report.results[0].ns_per_iter).abs() < 1e-9);
+    }
+}
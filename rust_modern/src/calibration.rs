@@ -0,0 +1,304 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Sensor calibration with motors
+//!
+//! Hand-eye calibration solves `A X = X B` for the unknown extrinsic motor `X`,
+//! given paired robot motions `A` and sensor motions `B`. The rotation is the
+//! null vector of the stacked rotor constraints `(L(a) - R(b)) x = 0`; the
+//! translation follows from the linear system `(R_a - I) t_x = R_x t_b - t_a`.
+//!
+//! Both systems are weighted per pair, which [`solve_hand_eye_irls`] uses to
+//! downweight outlier pairs with a [`RobustLoss`].
+
+use std::fmt;
+
+use crate::linalg::{self, square, Matrix3, Vector3};
+use crate::motor::{Motor, Rotor};
+use crate::robust::{irls, IrlsOptions, RobustLoss};
+use crate::si_units::{Angle, Length};
+
+/// Paired relative motions of the robot flange (`A`) and the sensor (`B`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotionPair {
+    pub robot: Motor,
+    pub sensor: Motor,
+}
+
+impl MotionPair {
+    pub fn new(robot: Motor, sensor: Motor) -> Self {
+        Self { robot, sensor }
+    }
+}
+
+/// Disagreement between `A X` and `X B` for one motion pair
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairResidual {
+    /// Rotation angle of the discrepancy in radians
+    pub rotation: f64,
+    /// Translation discrepancy in meters
+    pub translation: f64,
+}
+
+/// Calibrated extrinsic motor with residual statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandEyeSolution {
+    pub extrinsic: Motor,
+    pub residuals: Vec<PairResidual>,
+    /// Weight each pair had in the final solve; all ones unless solved robustly
+    pub weights: Vec<f64>,
+    pub rms_rotation: f64,
+    pub rms_translation: f64,
+}
+
+/// Errors raised by calibration solvers
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationError {
+    /// At least two motion pairs are required
+    InsufficientData { pairs: usize },
+    /// Rotation axes are (nearly) parallel, leaving the solution underdetermined
+    Degenerate,
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::InsufficientData { pairs } => {
+                write!(f, "hand-eye calibration needs at least 2 motion pairs, got {}", pairs)
+            }
+            CalibrationError::Degenerate => write!(f, "motion pairs do not excite two independent rotation axes"),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+/// Residuals at which a motion pair stops counting as an inlier
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandEyeThresholds {
+    pub rotation: Angle,
+    pub translation: Length,
+}
+
+impl HandEyeThresholds {
+    pub fn new(rotation: Angle, translation: Length) -> Self {
+        Self { rotation, translation }
+    }
+
+    /// Larger of the two residuals relative to its threshold; inliers are at most 1
+    pub fn normalize(&self, residual: &PairResidual) -> f64 {
+        (residual.rotation / self.rotation.value()).max(residual.translation / self.translation.value())
+    }
+}
+
+/// Left multiplication matrix: `L(p) q = p q`
+fn left_matrix(p: [f64; 4]) -> [[f64; 4]; 4] {
+    let [w, x, y, z] = p;
+    [[w, -x, -y, -z], [x, w, -z, y], [y, z, w, -x], [z, -y, x, w]]
+}
+
+/// Right multiplication matrix: `R(q) p = p q`
+fn right_matrix(q: [f64; 4]) -> [[f64; 4]; 4] {
+    let [w, x, y, z] = q;
+    [[w, -x, -y, -z], [x, w, z, -y], [y, -z, w, x], [z, y, -x, w]]
+}
+
+/// Quaternion with non-negative scalar part, picking one of the two covers
+fn canonical_quaternion(rotor: &Rotor) -> [f64; 4] {
+    let q = rotor.to_quaternion();
+    if q[0] < 0.0 {
+        q.map(|value| -value)
+    } else {
+        q
+    }
+}
+
+fn solve_weighted(pairs: &[MotionPair], weights: &[f64]) -> Result<Motor, CalibrationError> {
+    let mut normal = [[0.0; 4]; 4];
+    for (pair, weight) in pairs.iter().zip(weights) {
+        let l = left_matrix(canonical_quaternion(&pair.robot.rotor));
+        let r = right_matrix(canonical_quaternion(&pair.sensor.rotor));
+        let mut constraint = [[0.0; 4]; 4];
+        for (i, row) in constraint.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = l[i][j] - r[i][j];
+            }
+        }
+        let contribution = square::mul(&square::transpose(&constraint), &constraint);
+        for (row, other) in normal.iter_mut().zip(contribution) {
+            for (value, c) in row.iter_mut().zip(other) {
+                *value += weight * c;
+            }
+        }
+    }
+
+    let (values, vectors) = square::symmetric_eigen(&normal);
+    // A unique rotation needs a one-dimensional null space
+    if values[1] < 1e-9 * values[3].max(1e-300) {
+        return Err(CalibrationError::Degenerate);
+    }
+    let rotor = Rotor::from_quaternion([vectors[0][0], vectors[1][0], vectors[2][0], vectors[3][0]]).normalized();
+
+    // Least squares for the translation: Σ Cᵀ C t = Σ Cᵀ d with C = R_a - I
+    let mut lhs: Matrix3 = [[0.0; 3]; 3];
+    let mut rhs: Vector3 = [0.0; 3];
+    for (pair, weight) in pairs.iter().zip(weights) {
+        let mut c = pair.robot.rotor.to_rotation_matrix();
+        for (i, row) in c.iter_mut().enumerate() {
+            row[i] -= 1.0;
+        }
+        let d = linalg::sub(rotor.apply(pair.sensor.translation), pair.robot.translation);
+        let ct = linalg::mat3_transpose(&c);
+        lhs = square::add(&lhs, &linalg::mat3_mul(&ct, &c).map(|row| row.map(|value| weight * value)));
+        rhs = linalg::add(rhs, linalg::scale(linalg::mat3_vec(&ct, d), *weight));
+    }
+    let translation = square::mul_vec(&square::inverse(&lhs).ok_or(CalibrationError::Degenerate)?, &rhs);
+
+    Ok(Motor::new(translation, rotor))
+}
+
+/// Residual of `A X` against `X B`
+pub fn pair_residual(pair: &MotionPair, extrinsic: &Motor) -> PairResidual {
+    let ax = pair.robot * *extrinsic;
+    let xb = *extrinsic * pair.sensor;
+    PairResidual {
+        rotation: (ax.rotor.reverse() * xb.rotor).angle(),
+        translation: linalg::norm(linalg::sub(ax.translation, xb.translation)),
+    }
+}
+
+fn summarize(pairs: &[MotionPair], extrinsic: Motor, weights: Vec<f64>) -> HandEyeSolution {
+    let residuals: Vec<PairResidual> = pairs.iter().map(|pair| pair_residual(pair, &extrinsic)).collect();
+    let n = residuals.len() as f64;
+    let rms = |f: fn(&PairResidual) -> f64| (residuals.iter().map(|r| f(r) * f(r)).sum::<f64>() / n).sqrt();
+    HandEyeSolution {
+        extrinsic,
+        rms_rotation: rms(|r| r.rotation),
+        rms_translation: rms(|r| r.translation),
+        residuals,
+        weights,
+    }
+}
+
+/// Batch least-squares hand-eye calibration
+pub fn solve_hand_eye(pairs: &[MotionPair]) -> Result<HandEyeSolution, CalibrationError> {
+    if pairs.len() < 2 {
+        return Err(CalibrationError::InsufficientData { pairs: pairs.len() });
+    }
+    let weights = vec![1.0; pairs.len()];
+    let extrinsic = solve_weighted(pairs, &weights)?;
+    Ok(summarize(pairs, extrinsic, weights))
+}
+
+/// Hand-eye calibration minimizing a robust loss of the pair residuals by IRLS
+pub fn solve_hand_eye_irls(
+    pairs: &[MotionPair],
+    loss: RobustLoss,
+    thresholds: HandEyeThresholds,
+    options: &IrlsOptions,
+) -> Result<HandEyeSolution, CalibrationError> {
+    if pairs.len() < 2 {
+        return Err(CalibrationError::InsufficientData { pairs: pairs.len() });
+    }
+    let fit = irls(
+        pairs.len(),
+        loss,
+        options,
+        |weights| solve_weighted(pairs, weights),
+        |extrinsic| pairs.iter().map(|pair| thresholds.normalize(&pair_residual(pair, extrinsic))).collect(),
+    )?;
+    Ok(summarize(pairs, fit.model, fit.weights))
+}
+
+/// Hand-eye calibration with Huber reweighting to suppress outlier pairs
+///
+/// `rotation_scale` (rad) and `translation_scale` (m) set where residuals stop
+/// counting quadratically.
+pub fn solve_hand_eye_robust(
+    pairs: &[MotionPair],
+    rotation_scale: f64,
+    translation_scale: f64,
+    iterations: usize,
+) -> Result<HandEyeSolution, CalibrationError> {
+    let thresholds = HandEyeThresholds::new(Angle::new(rotation_scale), Length::new(translation_scale));
+    let options = IrlsOptions { max_iterations: iterations, weight_tolerance: 0.0 };
+    solve_hand_eye_irls(pairs, RobustLoss::Huber, thresholds, &options)
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extrinsic() -> Motor {
+        Motor::new([0.05, -0.02, 0.1], Rotor::from_axis_angle([0.3, 1.0, -0.2], 0.8))
+    }
+
+    fn pairs_for(x: &Motor) -> Vec<MotionPair> {
+        let robot_motions = [
+            Motor::new([0.2, 0.0, 0.1], Rotor::from_axis_angle([1.0, 0.0, 0.0], 0.5)),
+            Motor::new([0.0, 0.3, -0.1], Rotor::from_axis_angle([0.0, 1.0, 0.2], 0.7)),
+            Motor::new([-0.1, 0.1, 0.2], Rotor::from_axis_angle([0.1, 0.2, 1.0], -0.6)),
+        ];
+        robot_motions
+            .iter()
+            .map(|a| MotionPair::new(*a, x.inverse() * *a * *x))
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_hand_eye() {
+        let x = extrinsic();
+        let solution = solve_hand_eye(&pairs_for(&x)).unwrap();
+
+        let error = (x.inverse() * solution.extrinsic).log();
+        assert!(error.to_array().iter().all(|value| value.abs() < 1e-9), "{:?}", error);
+        assert!(solution.rms_rotation < 1e-9 && solution.rms_translation < 1e-9);
+    }
+
+    #[test]
+    fn test_robust_hand_eye_rejects_outlier() {
+        let x = extrinsic();
+        let mut pairs = pairs_for(&x);
+        pairs.extend(pairs_for(&x).iter().map(|pair| MotionPair::new(pair.robot * pair.robot, pair.sensor * pair.sensor)));
+        pairs[1].sensor.translation = linalg::add(pairs[1].sensor.translation, [0.3, 0.0, 0.0]);
+
+        let plain = solve_hand_eye(&pairs).unwrap();
+        let robust = solve_hand_eye_robust(&pairs, 1e-3, 1e-3, 20).unwrap();
+
+        let plain_error = linalg::norm(linalg::sub(plain.extrinsic.translation, x.translation));
+        let robust_error = linalg::norm(linalg::sub(robust.extrinsic.translation, x.translation));
+        assert!(robust_error < plain_error, "{} vs {}", robust_error, plain_error);
+        assert!(robust.residuals[1].translation > 0.1);
+    }
+
+    #[test]
+    fn test_tukey_hand_eye_zeroes_outlier_weight() {
+        let x = extrinsic();
+        let mut pairs = pairs_for(&x);
+        pairs.extend(pairs_for(&x).iter().map(|pair| MotionPair::new(pair.robot * pair.robot, pair.sensor * pair.sensor)));
+        pairs[4].sensor.translation = linalg::add(pairs[4].sensor.translation, [0.0, 0.05, 0.0]);
+
+        let thresholds = HandEyeThresholds::new(Angle::new(0.01), Length::new(0.01));
+        let huber = solve_hand_eye_irls(&pairs, RobustLoss::Huber, thresholds, &IrlsOptions::default()).unwrap();
+        let options = IrlsOptions { max_iterations: 50, ..IrlsOptions::default() };
+        let tukey = solve_hand_eye_irls(&pairs, RobustLoss::Tukey, thresholds, &options).unwrap();
+
+        assert_eq!(tukey.weights[4], 0.0);
+        assert!(tukey.weights.iter().enumerate().all(|(i, w)| i == 4 || *w > 0.99));
+        let error = linalg::norm(linalg::sub(tukey.extrinsic.translation, x.translation));
+        assert!(error < 1e-9, "{}", error);
+        assert!(huber.weights[4] < 1.0);
+    }
+
+    #[test]
+    fn test_degenerate_motions() {
+        let x = extrinsic();
+        let pairs = pairs_for(&x);
+
+        assert_eq!(solve_hand_eye(&pairs[..1]), Err(CalibrationError::InsufficientData { pairs: 1 }));
+        let parallel = [pairs[0], MotionPair::new(pairs[0].robot * pairs[0].robot, pairs[0].sensor * pairs[0].sensor)];
+        assert_eq!(solve_hand_eye(&parallel), Err(CalibrationError::Degenerate));
+    }
+}
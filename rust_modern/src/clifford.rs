@@ -0,0 +1,267 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Clifford algebras with multiplication tables built at compile time
+//!
+//! [`Algebra<P, Q, R, N>`] is the algebra of `P` basis vectors squaring to
+//! `+1`, then `Q` squaring to `-1`, then `R` squaring to `0`, with `N = 2^(P+Q+R)`
+//! blades. Blades are indexed by the same bitmask as
+//! [`crate::multivector::BladeMask`]: bit `i - 1` stands for `e_i`, so blade 0
+//! is the scalar and blade `N - 1` the pseudoscalar. Stable Rust cannot size
+//! an array by `2^(P+Q+R)`, so the blade count is a parameter checked against
+//! the signature when the tables are evaluated; use the aliases where they fit.
+//!
+//! The geometric and outer product tables are associated consts computed by
+//! `const fn`, so nothing is built at runtime and a product of dense arrays
+//! ([`Algebra::geometric_product`]) is a loop over constants that the compiler
+//! unrolls for small algebras.
+//!
+//! The conformal algebra [`Cga3`] uses the diagonal basis `e4² = 1, e5² = -1`;
+//! GAFRO's null vectors are `e0 = ½(e5 - e4)` and `e∞ = e4 + e5`.
+
+use std::marker::PhantomData;
+
+use crate::parity::Coefficient;
+
+/// Entry of a multiplication table: `e_a e_b = sign · e_blade`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Product {
+    pub blade: usize,
+    /// `1`, `-1`, or `0` when the product vanishes
+    pub sign: i8,
+}
+
+impl Product {
+    const ZERO: Self = Self { blade: 0, sign: 0 };
+}
+
+/// Algebra `Cl(P, Q, R)` with `N = 2^(P+Q+R)` blades
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Algebra<const P: usize, const Q: usize, const R: usize, const N: usize>(PhantomData<[(); N]>);
+
+/// Euclidean 3D algebra
+pub type Vga3 = Algebra<3, 0, 0, 8>;
+/// Projective (plane-based) 3D algebra, `e4² = 0`
+pub type Pga3 = Algebra<3, 0, 1, 16>;
+/// Conformal 3D algebra
+pub type Cga3 = Algebra<4, 1, 0, 32>;
+
+/// Square of basis vector `i` (from 0)
+const fn metric(i: usize, p: usize, q: usize) -> i8 {
+    if i < p {
+        1
+    } else if i < p + q {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Sign from reordering `e_a e_b` into canonical order, before squaring
+const fn reorder_sign(a: usize, b: usize) -> i8 {
+    let mut shifted = a >> 1;
+    let mut swaps = 0;
+    while shifted != 0 {
+        swaps += (shifted & b).count_ones();
+        shifted >>= 1;
+    }
+    if swaps % 2 == 0 {
+        1
+    } else {
+        -1
+    }
+}
+
+const fn geometric_table<const N: usize>(p: usize, q: usize) -> [[Product; N]; N] {
+    let mut table = [[Product::ZERO; N]; N];
+    let mut a = 0;
+    while a < N {
+        let mut b = 0;
+        while b < N {
+            let mut sign = reorder_sign(a, b);
+            let common = a & b;
+            let mut i = 0;
+            while (common >> i) != 0 {
+                if common & (1 << i) != 0 {
+                    sign *= metric(i, p, q);
+                }
+                i += 1;
+            }
+            table[a][b] = Product { blade: a ^ b, sign };
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+}
+
+const fn outer_table<const N: usize>() -> [[Product; N]; N] {
+    let mut table = [[Product::ZERO; N]; N];
+    let mut a = 0;
+    while a < N {
+        let mut b = 0;
+        while b < N {
+            if a & b == 0 {
+                table[a][b] = Product { blade: a | b, sign: reorder_sign(a, b) };
+            }
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+}
+
+impl<const P: usize, const Q: usize, const R: usize, const N: usize> Algebra<P, Q, R, N> {
+    pub const DIMENSION: usize = {
+        assert!(P + Q + R <= 10, "tables grow as 4^n; use Multivector for larger algebras");
+        assert!(N == 1 << (P + Q + R), "the blade count must be 2^(P+Q+R)");
+        P + Q + R
+    };
+    pub const BLADES: usize = {
+        let _ = Self::DIMENSION;
+        N
+    };
+
+    /// `GEOMETRIC[a][b]` is the product of blades `a` and `b`
+    pub const GEOMETRIC: [[Product; N]; N] = {
+        let _ = Self::DIMENSION;
+        geometric_table::<N>(P, Q)
+    };
+
+    /// `OUTER[a][b]` is the wedge of blades `a` and `b`
+    pub const OUTER: [[Product; N]; N] = {
+        let _ = Self::DIMENSION;
+        outer_table::<N>()
+    };
+
+    /// `(-1)^(k(k-1)/2)` for each blade of grade `k`
+    pub const REVERSE: [i8; N] = {
+        let _ = Self::DIMENSION;
+        let mut signs = [1; N];
+        let mut blade = 0;
+        while blade < N {
+            let k = blade.count_ones();
+            if (k * k.saturating_sub(1) / 2) % 2 == 1 {
+                signs[blade] = -1;
+            }
+            blade += 1;
+        }
+        signs
+    };
+
+    /// Square of basis vector `e_i`, `i` from 1
+    pub const fn metric(i: usize) -> i8 {
+        metric(i - 1, P, Q)
+    }
+
+    pub const fn grade(blade: usize) -> u32 {
+        blade.count_ones()
+    }
+
+    fn product<T: Coefficient>(table: &[[Product; N]; N], a: &[T; N], b: &[T; N]) -> [T; N] {
+        let mut result = [T::default(); N];
+        for (i, row) in table.iter().enumerate() {
+            for (j, entry) in row.iter().enumerate() {
+                let value = a[i] * b[j];
+                match entry.sign {
+                    1 => result[entry.blade] = result[entry.blade] + value,
+                    -1 => result[entry.blade] = result[entry.blade] + -value,
+                    _ => {}
+                }
+            }
+        }
+        result
+    }
+
+    /// Geometric product of dense coefficient arrays
+    pub fn geometric_product<T: Coefficient>(a: &[T; N], b: &[T; N]) -> [T; N] {
+        Self::product(&Self::GEOMETRIC, a, b)
+    }
+
+    pub fn outer_product<T: Coefficient>(a: &[T; N], b: &[T; N]) -> [T; N] {
+        Self::product(&Self::OUTER, a, b)
+    }
+
+    pub fn reverse<T: Coefficient>(a: &[T; N]) -> [T; N] {
+        std::array::from_fn(|blade| if Self::REVERSE[blade] < 0 { -a[blade] } else { a[blade] })
+    }
+}
+
+/// Tables of an algebra with `N` blades, for code generic over the algebra
+/// such as [`crate::expression`]
+pub trait Tables<const N: usize> {
+    const GEOMETRIC: [[Product; N]; N];
+    const OUTER: [[Product; N]; N];
+    const REVERSE: [i8; N];
+}
+
+impl<const P: usize, const Q: usize, const R: usize, const N: usize> Tables<N> for Algebra<P, Q, R, N> {
+    const GEOMETRIC: [[Product; N]; N] = Algebra::<P, Q, R, N>::GEOMETRIC;
+    const OUTER: [[Product; N]; N] = Algebra::<P, Q, R, N>::OUTER;
+    const REVERSE: [i8; N] = Algebra::<P, Q, R, N>::REVERSE;
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ga_term::BladeTerm;
+    use crate::motor::Rotor;
+    use crate::multivector::{mask_indices, Multivector};
+    use crate::sample::Sampler;
+
+    // Evaluated by the compiler: e1 e2 = e12 and e12² = -1
+    const _: () = assert!(Vga3::GEOMETRIC[0b001][0b010].blade == 0b011);
+    const _: () = assert!(Vga3::GEOMETRIC[0b011][0b011].sign == -1);
+
+    #[test]
+    fn test_tables_match_runtime_products() {
+        let mut sampler = Sampler::new(6);
+        let a: [f64; 32] = std::array::from_fn(|_| sampler.range(-1.0, 1.0));
+        let b: [f64; 32] = std::array::from_fn(|_| sampler.range(-1.0, 1.0));
+        let terms = |c: &[f64; 32]| -> Vec<BladeTerm<f64>> {
+            (0..32).map(|mask| BladeTerm::new(mask_indices(mask as u32), c[mask])).collect()
+        };
+        // Algebra<5, 0, 0, 32> is Euclidean, as is Multivector
+        let (lhs, rhs) = (Multivector::from_terms(5, &terms(&a)).unwrap(), Multivector::from_terms(5, &terms(&b)).unwrap());
+        let expected = lhs.geometric_product(&rhs);
+        let product = Algebra::<5, 0, 0, 32>::geometric_product(&a, &b);
+        for (mask, value) in product.iter().enumerate() {
+            assert!((value - expected.get_mask(mask as u32)).abs() < 1e-12);
+        }
+
+        assert_eq!(Cga3::metric(4), 1);
+        assert_eq!(Cga3::metric(5), -1);
+        assert_eq!(Pga3::metric(4), 0);
+        assert_eq!(Pga3::GEOMETRIC[0b1000][0b1000].sign, 0);
+        assert_eq!(Cga3::GEOMETRIC[0b10000][0b10000], Product { blade: 0, sign: -1 });
+        assert_eq!((Cga3::DIMENSION, Cga3::BLADES), (5, 32));
+        assert_eq!(Vga3::OUTER[0b001][0b001].sign, 0);
+        assert_eq!(Vga3::OUTER[0b010][0b001], Product { blade: 0b011, sign: -1 });
+    }
+
+    #[test]
+    fn test_rotor_sandwich_in_vga() {
+        // Rotor (scalar, e23, e13, e12) as a dense Vga3 element
+        let dense = |r: &Rotor| {
+            let mut c = [0.0; 8];
+            (c[0], c[0b110], c[0b101], c[0b011]) = (r.scalar, r.e23, r.e13, r.e12);
+            c
+        };
+        let mut sampler = Sampler::new(8);
+        for _ in 0..50 {
+            let (r1, r2) = (sampler.rotor(), sampler.rotor());
+            let product = Vga3::geometric_product(&dense(&r1), &dense(&r2));
+            assert!(product.iter().zip(dense(&(r1 * r2))).all(|(a, b)| (a - b).abs() < 1e-12));
+
+            let v = sampler.unit_vector();
+            let mut x = [0.0; 8];
+            (x[0b001], x[0b010], x[0b100]) = (v[0], v[1], v[2]);
+            let r = dense(&r1);
+            let rotated = Vga3::geometric_product(&Vga3::geometric_product(&r, &x), &Vga3::reverse(&r));
+            let expected = r1.apply(v);
+            assert!([0b001, 0b010, 0b100].iter().zip(expected).all(|(&blade, e)| (rotated[blade] - e).abs() < 1e-12));
+        }
+    }
+}
@@ -0,0 +1,449 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Point cloud and voxel export for inspection in CloudCompare or Open3D
+//!
+//! A [`PointCloud`] is a list of points with any number of named per-point
+//! [`ScalarField`]s, which viewers color by. Clouds come from:
+//!
+//! - [`sample_workspace`]: end-effector positions at random configurations of
+//!   a chain, with their manipulability;
+//! - [`VoxelGrid`]: points binned into cubes, exported as occupied voxel
+//!   centers with their point counts;
+//! - [`SignedDistanceField::slice`] and [`SignedDistanceField::to_cloud`]:
+//!   distance samples on a plane or the whole grid;
+//! - [`OccupancyGrid::to_cloud`]: cell centers with their occupancy probability.
+//!
+//! Clouds encode to PCD (v0.7) and PLY, ASCII or binary little-endian, with
+//! every value a double. With the `las-export` feature they also encode to
+//! LAS 1.2 (point format 0, millimeter scale), with the first scalar field
+//! stretched over the intensity range. [`PointCloud::save`] picks the format
+//! from the file extension.
+
+use std::fmt;
+
+use crate::frames::Frame;
+use crate::joints::Joint;
+use crate::kinematics::KinematicChain;
+use crate::linalg::{self, Vector3};
+use crate::occupancy::OccupancyGrid;
+use crate::primitives::Aabb;
+use crate::sample::Sampler;
+use crate::sdf::SignedDistanceField;
+use crate::si_units::Length;
+
+/// Errors raised while assembling or writing a cloud
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportError {
+    /// A scalar field does not have one value per point
+    FieldLength { name: String, expected: usize, actual: usize },
+    /// A field name that is empty or contains whitespace
+    InvalidFieldName(String),
+    Io(String),
+    UnknownFormat(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::FieldLength { name, expected, actual } => {
+                write!(f, "field '{}' has {} values for {} points", name, actual, expected)
+            }
+            ExportError::InvalidFieldName(name) => write!(f, "invalid field name '{}'", name),
+            ExportError::Io(message) => write!(f, "could not write cloud: {}", message),
+            ExportError::UnknownFormat(name) => write!(f, "unknown cloud format for '{}' (expected .pcd, .ply or .las)", name),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// Text or binary little-endian body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Ascii,
+    Binary,
+}
+
+/// Named value per point
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarField {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+/// Points with named scalar fields
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PointCloud {
+    pub points: Vec<Vector3>,
+    pub fields: Vec<ScalarField>,
+}
+
+impl PointCloud {
+    pub fn new(points: Vec<Vector3>) -> Self {
+        Self { points, fields: Vec::new() }
+    }
+
+    /// Add a field with one value per point
+    pub fn with_field(mut self, name: &str, values: Vec<f64>) -> Result<Self, ExportError> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(ExportError::InvalidFieldName(name.to_string()));
+        }
+        if values.len() != self.points.len() {
+            return Err(ExportError::FieldLength { name: name.to_string(), expected: self.points.len(), actual: values.len() });
+        }
+        self.fields.push(ScalarField { name: name.to_string(), values });
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn field(&self, name: &str) -> Option<&[f64]> {
+        self.fields.iter().find(|f| f.name == name).map(|f| f.values.as_slice())
+    }
+
+    /// The values of point `i`: x, y, z, then each field
+    fn record(&self, i: usize) -> impl Iterator<Item = f64> + '_ {
+        self.points[i].into_iter().chain(self.fields.iter().map(move |f| f.values[i]))
+    }
+
+    fn body(&self, encoding: Encoding) -> Vec<u8> {
+        let mut body = Vec::new();
+        for i in 0..self.len() {
+            match encoding {
+                Encoding::Ascii => {
+                    let values: Vec<String> = self.record(i).map(|v| v.to_string()).collect();
+                    body.extend_from_slice(values.join(" ").as_bytes());
+                    body.push(b'\n');
+                }
+                Encoding::Binary => self.record(i).for_each(|v| body.extend_from_slice(&v.to_le_bytes())),
+            }
+        }
+        body
+    }
+
+    /// PCD v0.7, an unorganized cloud with the viewpoint at the origin
+    pub fn to_pcd(&self, encoding: Encoding) -> Vec<u8> {
+        let names: Vec<&str> = ["x", "y", "z"].into_iter().chain(self.fields.iter().map(|f| f.name.as_str())).collect();
+        let repeat = |value: &str| vec![value; names.len()].join(" ");
+        let mut pcd = format!(
+            "# .PCD v0.7 - Point Cloud Data file format\nVERSION 0.7\nFIELDS {}\nSIZE {}\nTYPE {}\nCOUNT {}\nWIDTH {}\nHEIGHT 1\nVIEWPOINT 0 0 0 1 0 0 0\nPOINTS {}\nDATA {}\n",
+            names.join(" "),
+            repeat("8"),
+            repeat("F"),
+            repeat("1"),
+            self.len(),
+            self.len(),
+            if encoding == Encoding::Ascii { "ascii" } else { "binary" }
+        )
+        .into_bytes();
+        pcd.extend(self.body(encoding));
+        pcd
+    }
+
+    /// PLY 1.0 with one `vertex` element
+    pub fn to_ply(&self, encoding: Encoding) -> Vec<u8> {
+        let format = if encoding == Encoding::Ascii { "ascii" } else { "binary_little_endian" };
+        let mut ply = format!("ply\nformat {} 1.0\ncomment gafro_modern point cloud\nelement vertex {}\n", format, self.len());
+        for name in ["x", "y", "z"].into_iter().chain(self.fields.iter().map(|f| f.name.as_str())) {
+            ply.push_str(&format!("property double {}\n", name));
+        }
+        ply.push_str("end_header\n");
+        let mut ply = ply.into_bytes();
+        ply.extend(self.body(encoding));
+        ply
+    }
+
+    /// LAS 1.2, point format 0, coordinates in millimeters from the cloud's
+    /// minimum corner; the first field, if any, is stretched over the intensity range
+    #[cfg(feature = "las-export")]
+    pub fn to_las(&self) -> Vec<u8> {
+        const HEADER_SIZE: u16 = 227;
+        const RECORD_LENGTH: u16 = 20;
+        const SCALE: f64 = 1e-3;
+        let bounds = Aabb::from_points(&self.points).unwrap_or(Aabb::new([0.0; 3], [0.0; 3]));
+        let count = self.len() as u32;
+
+        let mut las = Vec::with_capacity(HEADER_SIZE as usize + self.len() * RECORD_LENGTH as usize);
+        las.extend_from_slice(b"LASF");
+        las.extend_from_slice(&[0; 4]); // file source ID, global encoding
+        las.extend_from_slice(&[0; 16]); // project GUID
+        las.extend_from_slice(&[1, 2]);
+        let text = |text: &str| {
+            let mut field = [0u8; 32];
+            field[..text.len()].copy_from_slice(text.as_bytes());
+            field
+        };
+        let (system, software) = (text("OTHER"), text("gafro_modern"));
+        las.extend_from_slice(&system);
+        las.extend_from_slice(&software);
+        las.extend_from_slice(&[0; 4]); // creation day and year unknown
+        las.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+        las.extend_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        las.extend_from_slice(&0u32.to_le_bytes()); // variable length records
+        las.push(0);
+        las.extend_from_slice(&RECORD_LENGTH.to_le_bytes());
+        las.extend_from_slice(&count.to_le_bytes());
+        las.extend_from_slice(&count.to_le_bytes());
+        las.extend_from_slice(&[0; 16]); // points of returns 2 to 5
+        for value in [SCALE; 3].into_iter().chain(bounds.min) {
+            las.extend_from_slice(&value.to_le_bytes());
+        }
+        for axis in 0..3 {
+            las.extend_from_slice(&bounds.max[axis].to_le_bytes());
+            las.extend_from_slice(&bounds.min[axis].to_le_bytes());
+        }
+
+        let intensity = self.fields.first().map(|field| {
+            let (low, high) = field.values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(l, h), &v| (l.min(v), h.max(v)));
+            let span = if high > low { high - low } else { 1.0 };
+            field.values.iter().map(move |v| ((v - low) / span * f64::from(u16::MAX)).round() as u16).collect::<Vec<u16>>()
+        });
+        for (i, point) in self.points.iter().enumerate() {
+            for (coordinate, min) in point.iter().zip(bounds.min) {
+                las.extend_from_slice(&(((coordinate - min) / SCALE).round() as i32).to_le_bytes());
+            }
+            las.extend_from_slice(&intensity.as_ref().map_or(0, |values| values[i]).to_le_bytes());
+            // First of one return, unclassified, nadir scan, no user data or source
+            las.extend_from_slice(&[0b0000_1001, 0, 0, 0, 0, 0]);
+        }
+        las
+    }
+
+    /// Write the cloud, choosing the format by extension: binary `.pcd` and
+    /// `.ply`, and `.las` with the `las-export` feature
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), ExportError> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+        let bytes = match extension.as_deref() {
+            Some("pcd") => self.to_pcd(Encoding::Binary),
+            Some("ply") => self.to_ply(Encoding::Binary),
+            #[cfg(feature = "las-export")]
+            Some("las") => self.to_las(),
+            _ => return Err(ExportError::UnknownFormat(path.display().to_string())),
+        };
+        std::fs::write(path, bytes).map_err(|e| ExportError::Io(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// End-effector positions at `samples` configurations drawn within the joint
+/// limits, with their manipulability as the field `manipulability`
+pub fn sample_workspace(chain: &KinematicChain, samples: usize, seed: u64) -> PointCloud {
+    let joints: Vec<Joint> = chain.joints().copied().collect();
+    let mut sampler = Sampler::new(seed);
+    let (points, manipulability): (Vec<Vector3>, Vec<f64>) = (0..samples)
+        .map(|_| {
+            let q = sampler.joint_coordinates(&joints);
+            let position = chain.forward(&q).expect("coordinates match the chain").translation;
+            (position, chain.jacobian(&q).expect("coordinates match the chain").manipulability())
+        })
+        .unzip();
+    PointCloud::new(points).with_field("manipulability", manipulability).expect("one value per point")
+}
+
+/// Counts of points binned into cubes
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoxelGrid {
+    origin: Vector3,
+    resolution: f64,
+    /// Number of voxels along x, y and z
+    dims: [usize; 3],
+    /// Counts with x varying fastest
+    counts: Vec<u32>,
+}
+
+impl VoxelGrid {
+    /// Grid of cubes of side `resolution` covering `points`
+    pub fn from_points(points: &[Vector3], resolution: Length) -> Self {
+        let resolution = *resolution.value();
+        assert!(resolution > 0.0, "voxel resolution must be positive");
+        let bounds = Aabb::from_points(points).unwrap_or(Aabb::new([0.0; 3], [0.0; 3]));
+        let dims = bounds.extents().map(|e| (e / resolution).floor() as usize + 1);
+        let mut grid = Self { origin: bounds.min, resolution, dims, counts: vec![0; dims.iter().product()] };
+        for &point in points {
+            let index = grid.index(point);
+            grid.counts[index] += 1;
+        }
+        grid
+    }
+
+    fn index(&self, point: Vector3) -> usize {
+        let cell: [usize; 3] =
+            std::array::from_fn(|i| (((point[i] - self.origin[i]) / self.resolution).floor().max(0.0) as usize).min(self.dims[i] - 1));
+        cell[0] + self.dims[0] * (cell[1] + self.dims[1] * cell[2])
+    }
+
+    pub fn resolution(&self) -> Length {
+        Length::new(self.resolution)
+    }
+
+    pub fn dims(&self) -> [usize; 3] {
+        self.dims
+    }
+
+    /// Number of points in the voxel containing `point`
+    pub fn count(&self, point: Vector3) -> u32 {
+        self.counts[self.index(point)]
+    }
+
+    pub fn occupied(&self) -> usize {
+        self.counts.iter().filter(|&&c| c > 0).count()
+    }
+
+    /// Centers of the occupied voxels with their point counts as the field `count`
+    pub fn to_cloud(&self) -> PointCloud {
+        let (points, counts): (Vec<Vector3>, Vec<f64>) = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c > 0)
+            .map(|(i, &c)| {
+                let cell = [i % self.dims[0], (i / self.dims[0]) % self.dims[1], i / (self.dims[0] * self.dims[1])];
+                let center = linalg::add(self.origin, cell.map(|c| (c as f64 + 0.5) * self.resolution));
+                (center, f64::from(c))
+            })
+            .unzip();
+        PointCloud::new(points).with_field("count", counts).expect("one value per point")
+    }
+}
+
+impl SignedDistanceField {
+    /// Samples on the plane `z = height` with their distance as the field
+    /// `distance`; empty when the plane misses the grid
+    pub fn slice(&self, height: Length) -> PointCloud {
+        let (bounds, resolution) = (self.bounds(), *self.resolution().value());
+        let [nx, ny, _] = self.dims();
+        let (points, distances): (Vec<Vector3>, Vec<f64>) = (0..ny)
+            .flat_map(|j| (0..nx).map(move |i| [bounds.min[0] + i as f64 * resolution, bounds.min[1] + j as f64 * resolution, *height.value()]))
+            .filter_map(|p| self.distance(p).map(|d| (p, *d.value())))
+            .unzip();
+        PointCloud::new(points).with_field("distance", distances).expect("one value per point")
+    }
+
+    /// Every sample with its distance as the field `distance`
+    pub fn to_cloud(&self) -> PointCloud {
+        let (bounds, resolution) = (self.bounds(), *self.resolution().value());
+        let mut cloud = PointCloud::default();
+        for k in 0..self.dims()[2] {
+            let slice = self.slice(Length::new(bounds.min[2] + k as f64 * resolution));
+            cloud.points.extend(slice.points);
+            cloud.fields.resize_with(1, || ScalarField { name: "distance".to_string(), values: Vec::new() });
+            cloud.fields[0].values.extend(&slice.fields[0].values);
+        }
+        cloud
+    }
+}
+
+impl<F: Frame> OccupancyGrid<F> {
+    /// Cell centers at `z = 0` with their occupancy probability as the field `probability`
+    pub fn to_cloud(&self) -> PointCloud {
+        let (columns, rows) = self.dimensions();
+        let (points, probabilities): (Vec<Vector3>, Vec<f64>) = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|cell| {
+                let [x, y] = self.cell_center(cell);
+                ([x, y, 0.0], self.probability([x, y]).expect("cell center lies in the grid"))
+            })
+            .unzip();
+        PointCloud::new(points).with_field("probability", probabilities).expect("one value per point")
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::Shape;
+    use crate::geodesy::Enu;
+    use crate::joints::JointLimits;
+    use crate::motor::Motor;
+    use crate::primitives::Sphere;
+    use crate::si_units::{Angle, AngularVelocity, TAU};
+
+    fn cloud() -> PointCloud {
+        PointCloud::new(vec![[0.0, 0.0, 0.0], [1.5, -2.0, 0.25]]).with_field("distance", vec![0.5, -1.0]).unwrap()
+    }
+
+    #[test]
+    fn test_pcd_and_ply_encodings() {
+        let pcd = String::from_utf8(cloud().to_pcd(Encoding::Ascii)).unwrap();
+        assert!(pcd.contains("FIELDS x y z distance\nSIZE 8 8 8 8\nTYPE F F F F\nCOUNT 1 1 1 1\nWIDTH 2\n"));
+        assert!(pcd.ends_with("POINTS 2\nDATA ascii\n0 0 0 0.5\n1.5 -2 0.25 -1\n"));
+
+        let binary = cloud().to_ply(Encoding::Binary);
+        let header = b"ply\nformat binary_little_endian 1.0\ncomment gafro_modern point cloud\nelement vertex 2\n\
+                       property double x\nproperty double y\nproperty double z\nproperty double distance\nend_header\n";
+        assert_eq!(&binary[..header.len()], header);
+        assert_eq!(binary.len(), header.len() + 2 * 4 * 8);
+        let value = |i: usize| f64::from_le_bytes(binary[header.len() + 8 * i..header.len() + 8 * (i + 1)].try_into().unwrap());
+        assert_eq!((value(4), value(5), value(7)), (1.5, -2.0, -1.0));
+
+        assert!(matches!(PointCloud::new(vec![[0.0; 3]]).with_field("d", vec![]), Err(ExportError::FieldLength { .. })));
+        assert!(matches!(PointCloud::new(vec![]).with_field("signed distance", vec![]), Err(ExportError::InvalidFieldName(_))));
+        assert!(matches!(cloud().save("cloud.xyz"), Err(ExportError::UnknownFormat(_))));
+    }
+
+    #[test]
+    fn test_workspace_voxels_and_grids() {
+        let limits = JointLimits::new(Angle::new(-TAU / 2.0), Angle::new(TAU / 2.0), AngularVelocity::new(1.0));
+        let chain = KinematicChain::new()
+            .with_joint(Motor::identity(), Joint::revolute([0.0, 0.0, 1.0], limits))
+            .with_joint(Motor::from_translation([1.0, 0.0, 0.0]), Joint::revolute([0.0, 0.0, 1.0], limits))
+            .with_joint(Motor::from_translation([1.0, 0.0, 0.0]), Joint::Fixed);
+        let workspace = sample_workspace(&chain, 500, 3);
+        assert_eq!(workspace.len(), 500);
+        assert!(workspace.points.iter().all(|p| linalg::norm(*p) <= 2.0 + 1e-9 && p[2].abs() < 1e-12));
+        assert!(workspace.field("manipulability").unwrap().iter().all(|m| *m >= 0.0));
+
+        let voxels = VoxelGrid::from_points(&workspace.points, Length::new(0.5));
+        let cloud = voxels.to_cloud();
+        assert_eq!(cloud.len(), voxels.occupied());
+        assert_eq!(cloud.field("count").unwrap().iter().sum::<f64>(), 500.0);
+        assert_eq!(voxels.count(workspace.points[0]) as f64, {
+            let first = voxels.index(workspace.points[0]);
+            f64::from(voxels.counts[first])
+        });
+
+        let sdf = SignedDistanceField::from_shapes(
+            &[Shape::Sphere(Sphere::new([0.0; 3], 1.0))],
+            &Aabb::new([-2.0; 3], [2.0; 3]),
+            Length::new(0.5),
+        )
+        .unwrap();
+        let slice = sdf.slice(Length::new(0.0));
+        assert_eq!(slice.len(), 81);
+        let center = slice.points.iter().position(|p| linalg::norm(*p) < 1e-12).unwrap();
+        assert!((slice.field("distance").unwrap()[center] + 1.0).abs() < 1e-9);
+        assert!(sdf.slice(Length::new(5.0)).is_empty());
+        assert_eq!(sdf.to_cloud().len(), 9 * 81);
+
+        let grid = OccupancyGrid::<Enu>::new([Length::new(0.0); 2], Length::new(1.0), 3, 2);
+        let cells = grid.to_cloud();
+        assert_eq!((cells.len(), cells.points[4]), (6, [1.5, 1.5, 0.0]));
+        assert!(cells.field("probability").unwrap().iter().all(|p| *p == 0.5));
+    }
+
+    #[cfg(feature = "las-export")]
+    #[test]
+    fn test_las_layout() {
+        let las = cloud().to_las();
+        assert_eq!(&las[..4], b"LASF");
+        assert_eq!(las.len(), 227 + 2 * 20);
+        assert_eq!(u32::from_le_bytes(las[107..111].try_into().unwrap()), 2);
+        let offset_y = f64::from_le_bytes(las[163..171].try_into().unwrap());
+        assert_eq!(offset_y, -2.0);
+        let second = &las[247..267];
+        let x = i32::from_le_bytes(second[0..4].try_into().unwrap());
+        assert_eq!(x, 1500);
+        assert_eq!(u16::from_le_bytes(second[12..14].try_into().unwrap()), 0);
+        assert_eq!(u16::from_le_bytes(las[227 + 12..227 + 14].try_into().unwrap()), u16::MAX);
+    }
+}
@@ -0,0 +1,461 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Obstacle distances and a bounding volume hierarchy
+//!
+//! Obstacles are spheres, boxes and triangles (meshes enter as their triangles).
+//! Robot links are swept spheres ([`Capsule`]s). A [`Bvh`] stores the obstacles'
+//! axis-aligned bounds in a binary tree split at the median of the longest axis,
+//! so nearest-obstacle and clearance queries skip whole subtrees whose bounds are
+//! already farther than the best candidate or the requested margin.
+//!
+//! Distances are signed: negative values are penetration depths for spheres and
+//! boxes. Triangles are thin and report zero when touched.
+
+use serde::{Deserialize, Serialize};
+use crate::linalg::{self, Vector3};
+use crate::motor::Motor;
+use crate::primitives::{Aabb, Sphere};
+use crate::si_units::Length;
+
+/// Largest number of obstacles stored in one leaf
+pub const BVH_LEAF_SIZE: usize = 4;
+
+/// Parameter tolerance of the segment search for boxes and triangles
+const SEGMENT_TOLERANCE: f64 = 1e-9;
+
+/// Obstacle geometry
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    Sphere(Sphere),
+    Box(Aabb),
+    Triangle([Vector3; 3]),
+}
+
+impl Shape {
+    pub fn bounds(&self) -> Aabb {
+        match self {
+            Shape::Sphere(sphere) => Aabb::new(sphere.center, sphere.center).expanded(sphere.radius),
+            Shape::Box(bounds) => *bounds,
+            Shape::Triangle(vertices) => Aabb::from_points(vertices).expect("three vertices"),
+        }
+    }
+
+    pub fn distance_to_point(&self, point: Vector3) -> f64 {
+        match self {
+            Shape::Sphere(sphere) => linalg::norm(linalg::sub(point, sphere.center)) - sphere.radius,
+            Shape::Box(bounds) => bounds.signed_distance(point),
+            Shape::Triangle(vertices) => linalg::norm(linalg::sub(point, closest_point_on_triangle(point, vertices))),
+        }
+    }
+
+    /// Distance from the segment `start`–`end`
+    pub fn distance_to_segment(&self, start: Vector3, end: Vector3) -> f64 {
+        match self {
+            Shape::Sphere(sphere) => {
+                let closest = closest_point_on_segment(sphere.center, start, end);
+                linalg::norm(linalg::sub(closest, sphere.center)) - sphere.radius
+            }
+            // The distance to a convex set is convex along the segment, so a
+            // golden-section search finds its minimum
+            _ => {
+                let at = |t: f64| self.distance_to_point(linalg::add(start, linalg::scale(linalg::sub(end, start), t)));
+                let ratio = (5f64.sqrt() - 1.0) / 2.0;
+                let (mut low, mut high) = (0.0, 1.0);
+                while high - low > SEGMENT_TOLERANCE {
+                    let (a, b) = (high - ratio * (high - low), low + ratio * (high - low));
+                    if at(a) <= at(b) {
+                        high = b;
+                    } else {
+                        low = a;
+                    }
+                }
+                at(0.5 * (low + high)).min(at(0.0)).min(at(1.0))
+            }
+        }
+    }
+}
+
+/// Segment swept by a sphere, e.g. a robot link
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Capsule {
+    pub start: Vector3,
+    pub end: Vector3,
+    pub radius: f64,
+}
+
+impl Capsule {
+    pub fn new(start: Vector3, end: Vector3, radius: f64) -> Self {
+        Self { start, end, radius: radius.abs() }
+    }
+
+    /// A single point, for point queries
+    pub fn point(point: Vector3) -> Self {
+        Self::new(point, point, 0.0)
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        Aabb::new(self.start, self.end).expanded(self.radius)
+    }
+
+    /// The same capsule moved by `motor`
+    pub fn transformed(&self, motor: &Motor) -> Self {
+        Self { start: motor.apply_point(self.start), end: motor.apply_point(self.end), radius: self.radius }
+    }
+
+    /// Signed distance between the capsule surface and `shape`
+    pub fn distance_to(&self, shape: &Shape) -> Length {
+        Length::new(shape.distance_to_segment(self.start, self.end) - self.radius)
+    }
+
+    /// Signed distance between the surfaces of two capsules, negative when they overlap
+    pub fn distance_to_capsule(&self, other: &Capsule) -> Length {
+        let (a, b) = closest_points_between_segments(self.start, self.end, other.start, other.end);
+        Length::new(linalg::norm(linalg::sub(a, b)) - self.radius - other.radius)
+    }
+}
+
+/// Nearest obstacle found by a query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nearest {
+    /// Index of the obstacle in the slice the hierarchy was built from
+    pub index: usize,
+    pub distance: Length,
+}
+
+/// Clearance between a robot link and the obstacles, however they are stored
+pub trait ClearanceQuery {
+    /// Signed distance from the link surface to the nearest obstacle; `None`
+    /// when there are no obstacles or the link lies outside the stored region
+    fn clearance(&self, link: &Capsule) -> Option<Length>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf { bounds: Aabb, start: usize, count: usize },
+    Inner { bounds: Aabb, left: usize, right: usize },
+}
+
+impl Node {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Inner { bounds, .. } => bounds,
+        }
+    }
+}
+
+/// Bounding volume hierarchy over a fixed set of obstacles
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bvh {
+    shapes: Vec<Shape>,
+    /// Obstacle indices, grouped so each leaf owns a contiguous range
+    order: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+impl Bvh {
+    pub fn build(shapes: Vec<Shape>) -> Self {
+        let bounds: Vec<Aabb> = shapes.iter().map(Shape::bounds).collect();
+        let mut order: Vec<usize> = (0..shapes.len()).collect();
+        let mut nodes = Vec::new();
+        if !shapes.is_empty() {
+            build_node(&bounds, &mut order, 0, &mut nodes);
+        }
+        Self { shapes, order, nodes }
+    }
+
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    /// Bounds of all obstacles; `None` when empty
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.nodes.first().map(|node| *node.bounds())
+    }
+
+    /// Obstacles whose bounds overlap `region`
+    pub fn overlapping(&self, region: &Aabb) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.visit(
+            |bounds| bounds.intersects(region),
+            |index| {
+                if self.shapes[index].bounds().intersects(region) {
+                    found.push(index);
+                }
+            },
+        );
+        found.sort_unstable();
+        found
+    }
+
+    /// Obstacles closer to the capsule than `margin`, with their distances
+    pub fn within(&self, capsule: &Capsule, margin: Length) -> Vec<Nearest> {
+        let segment = Aabb::new(capsule.start, capsule.end);
+        let margin = *margin.value();
+        let mut found = Vec::new();
+        self.visit(
+            |bounds| bounds.distance_to_box(&segment) - capsule.radius <= margin,
+            |index| {
+                let distance = capsule.distance_to(&self.shapes[index]);
+                if *distance.value() <= margin {
+                    found.push(Nearest { index, distance });
+                }
+            },
+        );
+        found.sort_by_key(|nearest| nearest.index);
+        found
+    }
+
+    /// The closest obstacle to the capsule; `None` when empty
+    pub fn nearest(&self, capsule: &Capsule) -> Option<Nearest> {
+        self.nearest_within(capsule, Length::new(f64::INFINITY))
+    }
+
+    /// The closest obstacle no farther than `max_distance`
+    pub fn nearest_within(&self, capsule: &Capsule, max_distance: Length) -> Option<Nearest> {
+        // The distance between a node's bounds and the segment's bounds can only
+        // underestimate the distance between their contents
+        let segment = Aabb::new(capsule.start, capsule.end);
+        let lower_bound = |node: usize| self.nodes[node].bounds().distance_to_box(&segment) - capsule.radius;
+        let mut best: Option<Nearest> = None;
+        let mut limit = *max_distance.value();
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            if self.nodes.is_empty() || lower_bound(node) > limit {
+                continue;
+            }
+            match &self.nodes[node] {
+                Node::Leaf { start, count, .. } => {
+                    for &index in &self.order[*start..start + count] {
+                        let distance = capsule.distance_to(&self.shapes[index]);
+                        if *distance.value() <= limit {
+                            limit = *distance.value();
+                            best = Some(Nearest { index, distance });
+                        }
+                    }
+                }
+                Node::Inner { left, right, .. } => {
+                    // Push the farther child first so the nearer one is explored first
+                    if lower_bound(*left) <= lower_bound(*right) {
+                        stack.extend([*right, *left]);
+                    } else {
+                        stack.extend([*left, *right]);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Nearest obstacle for every link of a robot
+    pub fn nearest_batch(&self, links: &[Capsule]) -> Vec<Option<Nearest>> {
+        links.iter().map(|link| self.nearest(link)).collect()
+    }
+
+    fn visit(&self, mut descend: impl FnMut(&Aabb) -> bool, mut leaf: impl FnMut(usize)) {
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(node) = stack.pop() {
+            if !descend(self.nodes[node].bounds()) {
+                continue;
+            }
+            match &self.nodes[node] {
+                Node::Leaf { start, count, .. } => self.order[*start..start + count].iter().for_each(|&i| leaf(i)),
+                Node::Inner { left, right, .. } => stack.extend([*left, *right]),
+            }
+        }
+    }
+}
+
+impl ClearanceQuery for Bvh {
+    fn clearance(&self, link: &Capsule) -> Option<Length> {
+        self.nearest(link).map(|nearest| nearest.distance)
+    }
+}
+
+/// Build the subtree over `order` (whose first element sits at `offset` in the
+/// full order) and return its node index
+fn build_node(bounds: &[Aabb], order: &mut [usize], offset: usize, nodes: &mut Vec<Node>) -> usize {
+    let node_bounds = order.iter().skip(1).fold(bounds[order[0]], |b, &i| b.union(&bounds[i]));
+    let index = nodes.len();
+    if order.len() <= BVH_LEAF_SIZE {
+        nodes.push(Node::Leaf { bounds: node_bounds, start: offset, count: order.len() });
+        return index;
+    }
+
+    let centers: Vec<Vector3> = order.iter().map(|&i| bounds[i].center()).collect();
+    let extents = Aabb::from_points(&centers).expect("non-empty node").extents();
+    let axis = (0..3).max_by(|&a, &b| extents[a].total_cmp(&extents[b])).unwrap_or(0);
+    let middle = order.len() / 2;
+    order.select_nth_unstable_by(middle, |&a, &b| bounds[a].center()[axis].total_cmp(&bounds[b].center()[axis]));
+
+    nodes.push(Node::Leaf { bounds: node_bounds, start: offset, count: 0 });
+    let (lower, upper) = order.split_at_mut(middle);
+    let left = build_node(bounds, lower, offset, nodes);
+    let right = build_node(bounds, upper, offset + middle, nodes);
+    nodes[index] = Node::Inner { bounds: node_bounds, left, right };
+    index
+}
+
+fn closest_point_on_segment(point: Vector3, start: Vector3, end: Vector3) -> Vector3 {
+    let direction = linalg::sub(end, start);
+    let length2 = linalg::dot(direction, direction);
+    if length2 == 0.0 {
+        return start;
+    }
+    let t = (linalg::dot(linalg::sub(point, start), direction) / length2).clamp(0.0, 1.0);
+    linalg::add(start, linalg::scale(direction, t))
+}
+
+/// Closest points of the segments `p1`–`q1` and `p2`–`q2` (Ericson, Real-Time Collision Detection §5.1.9)
+fn closest_points_between_segments(p1: Vector3, q1: Vector3, p2: Vector3, q2: Vector3) -> (Vector3, Vector3) {
+    let (d1, d2, r) = (linalg::sub(q1, p1), linalg::sub(q2, p2), linalg::sub(p1, p2));
+    let (a, e, f) = (linalg::dot(d1, d1), linalg::dot(d2, d2), linalg::dot(d2, r));
+    let (s, t) = if a == 0.0 && e == 0.0 {
+        (0.0, 0.0)
+    } else if a == 0.0 {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = linalg::dot(d1, r);
+        if e == 0.0 {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = linalg::dot(d1, d2);
+            let denominator = a * e - b * b;
+            // Parallel segments have no unique closest pair; any s works, so start from p1
+            let s = if denominator > 0.0 { ((b * f - c * e) / denominator).clamp(0.0, 1.0) } else { 0.0 };
+            let t = (b * s + f) / e;
+            if t < 0.0 {
+                ((-c / a).clamp(0.0, 1.0), 0.0)
+            } else if t > 1.0 {
+                (((b - c) / a).clamp(0.0, 1.0), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+    (linalg::add(p1, linalg::scale(d1, s)), linalg::add(p2, linalg::scale(d2, t)))
+}
+
+/// Closest point of a triangle by Voronoi region (Ericson, Real-Time Collision Detection §5.1.5)
+fn closest_point_on_triangle(p: Vector3, [a, b, c]: &[Vector3; 3]) -> Vector3 {
+    let (ab, ac, ap) = (linalg::sub(*b, *a), linalg::sub(*c, *a), linalg::sub(p, *a));
+    let (d1, d2) = (linalg::dot(ab, ap), linalg::dot(ac, ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return *a;
+    }
+    let bp = linalg::sub(p, *b);
+    let (d3, d4) = (linalg::dot(ab, bp), linalg::dot(ac, bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return *b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return linalg::add(*a, linalg::scale(ab, d1 / (d1 - d3)));
+    }
+    let cp = linalg::sub(p, *c);
+    let (d5, d6) = (linalg::dot(ab, cp), linalg::dot(ac, cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return *c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return linalg::add(*a, linalg::scale(ac, d2 / (d2 - d6)));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return linalg::add(*b, linalg::scale(linalg::sub(*c, *b), w));
+    }
+    let denominator = 1.0 / (va + vb + vc);
+    linalg::add(*a, linalg::add(linalg::scale(ab, vb * denominator), linalg::scale(ac, vc * denominator)))
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::Sampler;
+
+    fn random_obstacles(sampler: &mut Sampler, count: usize) -> Vec<Shape> {
+        let world = Aabb::new([-20.0; 3], [20.0; 3]);
+        (0..count)
+            .map(|i| {
+                let center = sampler.point_in_box(&world);
+                let size = sampler.range(0.1, 1.0);
+                match i % 3 {
+                    0 => Shape::Sphere(Sphere::new(center, size)),
+                    1 => Shape::Box(Aabb::new(center, linalg::add(center, [size, 0.5 * size, 2.0 * size]))),
+                    _ => Shape::Triangle([center, linalg::add(center, [size, 0.0, 0.0]), linalg::add(center, [0.0, size, size])]),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_shape_distances() {
+        let unit_box = Shape::Box(Aabb::new([0.0; 3], [1.0; 3]));
+        assert!((unit_box.distance_to_point([2.0, 0.5, 0.5]) - 1.0).abs() < 1e-12);
+        assert!((unit_box.distance_to_point([0.5, 0.5, 0.75]) + 0.25).abs() < 1e-12);
+        assert!((unit_box.distance_to_segment([-1.0, 2.5, 0.5], [2.5, -1.0, 0.5]) + 0.25).abs() < 1e-6);
+
+        let triangle = Shape::Triangle([[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+        assert!((triangle.distance_to_point([0.25, 0.25, 2.0]) - 2.0).abs() < 1e-12);
+        assert!((triangle.distance_to_point([2.0, 0.0, 0.0]) - 1.0).abs() < 1e-12);
+        assert!((triangle.distance_to_point([1.0, 1.0, 0.0]) - 0.5f64.sqrt()).abs() < 1e-12);
+
+        let link = Capsule::new([-1.0, 0.0, 3.0], [1.0, 0.0, 3.0], 0.5);
+        let ball = Shape::Sphere(Sphere::new([0.0, 0.0, 0.0], 1.0));
+        assert!((link.distance_to(&ball).value() - 1.5).abs() < 1e-12);
+        assert!((link.distance_to(&triangle).value() - 2.5).abs() < 1e-6);
+
+        let crossing = Capsule::new([0.0, -1.0, 1.0], [0.0, 1.0, 1.0], 0.25);
+        assert!((link.distance_to_capsule(&crossing).value() - 1.25).abs() < 1e-12);
+        let parallel = Capsule::new([0.5, 0.0, 3.5], [3.0, 0.0, 3.5], 0.25);
+        assert!((link.distance_to_capsule(&parallel).value() + 0.25).abs() < 1e-12);
+        let beyond = Capsule::point([3.0, 0.0, 3.0]);
+        assert!((link.distance_to_capsule(&beyond).value() - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bvh_matches_brute_force() {
+        let mut sampler = Sampler::new(11);
+        let shapes = random_obstacles(&mut sampler, 500);
+        let bvh = Bvh::build(shapes.clone());
+        assert_eq!(bvh.len(), 500);
+
+        let world = Aabb::new([-25.0; 3], [25.0; 3]);
+        let links: Vec<Capsule> = (0..20)
+            .map(|_| {
+                let start = sampler.point_in_box(&world);
+                Capsule::new(start, linalg::add(start, linalg::scale(sampler.unit_vector(), 2.0)), 0.2)
+            })
+            .collect();
+
+        for (link, nearest) in links.iter().zip(bvh.nearest_batch(&links)) {
+            let nearest = nearest.unwrap();
+            let brute = shapes.iter().map(|s| *link.distance_to(s).value()).fold(f64::INFINITY, f64::min);
+            assert!((nearest.distance.value() - brute).abs() < 1e-9);
+
+            let margin = Length::new(3.0);
+            let expected: Vec<usize> = (0..shapes.len()).filter(|&i| link.distance_to(&shapes[i]) <= margin).collect();
+            let found: Vec<usize> = bvh.within(link, margin).iter().map(|n| n.index).collect();
+            assert_eq!(found, expected);
+
+            let region = link.bounds().expanded(1.0);
+            let expected: Vec<usize> = (0..shapes.len()).filter(|&i| shapes[i].bounds().intersects(&region)).collect();
+            assert_eq!(bvh.overlapping(&region), expected);
+        }
+
+        assert!(bvh.nearest_within(&Capsule::point([100.0; 3]), Length::new(1.0)).is_none());
+        assert!(Bvh::build(vec![]).nearest(&Capsule::point([0.0; 3])).is_none());
+    }
+}
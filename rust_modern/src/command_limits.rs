@@ -0,0 +1,493 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Layered limits on joint velocity commands
+//!
+//! [`JointLimits`](crate::joints::JointLimits) only bounds a joint's position
+//! and speed. A [`LimitFilter`] sits between any controller and the joints and
+//! passes each velocity command through five layers, from the most to the
+//! least binding:
+//!
+//! 1. Hard position: the next step stays inside the range, and the joint can
+//!    still brake to a stop at the acceleration limit before its end.
+//!    Outside the range only motion back toward it passes.
+//! 2. Velocity.
+//! 3. Acceleration, from the previous filtered command.
+//! 4. Jerk, from the previous filtered acceleration.
+//! 5. Soft position: inside the soft zone at either end of the range, the
+//!    speed toward the end falls linearly to zero at the hard limit.
+//!
+//! Each layer narrows the interval of allowed velocities left by the layers
+//! above it; a layer that cannot be met together with them is overridden.
+//! [`Enforcement::Clamp`] clamps every coordinate on its own, while
+//! [`Enforcement::Scale`] shrinks the whole command by one factor to keep its
+//! direction, as Cartesian motion needs. The [`FilteredCommand`] names the
+//! layer that limited each coordinate.
+//!
+//! Commands, positions and limits are plain SI numbers (rad or m and their
+//! rates), like the coordinates of a [`KinematicChain`]; [`LayerSettings`]
+//! gives the typed accelerations and jerks from which
+//! [`LimitFilter::for_chain`] completes the joints' own limits.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::joints::Joint;
+use crate::kinematics::KinematicChain;
+use crate::si_units::{Acceleration, Measure, Time};
+use crate::signal::{AngularAcceleration, AngularJerk, Jerk};
+
+/// Why a filter could not be built or applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum LimitError {
+    /// A slice does not have one value per coordinate
+    CoordinateCount { expected: usize, actual: usize },
+    /// The limits of this coordinate are unordered, not positive or NaN
+    InvalidLimit(usize),
+    /// The time step is not positive
+    InvalidStep,
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::CoordinateCount { expected, actual } => {
+                write!(f, "filter has {} coordinates but {} values were given", expected, actual)
+            }
+            LimitError::InvalidLimit(index) => write!(f, "limits of coordinate {} are invalid", index),
+            LimitError::InvalidStep => write!(f, "time step must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for LimitError {}
+
+/// A layer of limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitLayer {
+    HardPosition,
+    Velocity,
+    Acceleration,
+    Jerk,
+    SoftPosition,
+}
+
+/// Limits of one coordinate, in the SI units of the coordinate and its rates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AxisLimits {
+    pub lower: f64,
+    pub upper: f64,
+    /// Inner range, outside of which the speed toward the nearer end is reduced
+    pub soft_lower: f64,
+    pub soft_upper: f64,
+    pub velocity: f64,
+    /// Infinite when unlimited
+    pub acceleration: f64,
+    /// Infinite when unlimited
+    pub jerk: f64,
+}
+
+impl AxisLimits {
+    /// Position range and speed limit, without a soft zone or acceleration and jerk limits
+    pub fn new(lower: f64, upper: f64, velocity: f64) -> Self {
+        Self { lower, upper, soft_lower: lower, soft_upper: upper, velocity, acceleration: f64::INFINITY, jerk: f64::INFINITY }
+    }
+
+    /// Soft zones covering `fraction` of the range at each end
+    pub fn with_soft_fraction(mut self, fraction: f64) -> Self {
+        let span = self.upper - self.lower;
+        if span.is_finite() {
+            self.soft_lower = self.lower + fraction * span;
+            self.soft_upper = self.upper - fraction * span;
+        }
+        self
+    }
+
+    pub fn with_acceleration(mut self, acceleration: f64) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    pub fn with_jerk(mut self, jerk: f64) -> Self {
+        self.jerk = jerk;
+        self
+    }
+
+    fn is_valid(&self) -> bool {
+        self.lower <= self.soft_lower
+            && self.soft_lower <= self.soft_upper
+            && self.soft_upper <= self.upper
+            && self.velocity > 0.0
+            && self.acceleration > 0.0
+            && self.jerk > 0.0
+    }
+
+    /// Velocities allowed at position `q` after the previous command
+    /// `velocity` and acceleration `acceleration`
+    fn allowed(&self, q: f64, velocity: f64, acceleration: f64, dt: f64) -> Allowed {
+        let braking = |distance: f64| (2.0 * self.acceleration * distance.max(0.0)).sqrt();
+        let hard_upper = if q >= self.upper { 0.0 } else { ((self.upper - q) / dt).min(braking(self.upper - q)) };
+        let hard_lower = if q <= self.lower { 0.0 } else { ((self.lower - q) / dt).max(-braking(q - self.lower)) };
+        let mut allowed = Allowed::new(hard_lower, hard_upper, LimitLayer::HardPosition);
+        allowed.narrow(-self.velocity, self.velocity, LimitLayer::Velocity);
+        allowed.narrow(velocity - self.acceleration * dt, velocity + self.acceleration * dt, LimitLayer::Acceleration);
+        let jerk = self.jerk * dt;
+        allowed.narrow(velocity + (acceleration - jerk) * dt, velocity + (acceleration + jerk) * dt, LimitLayer::Jerk);
+
+        let soft_upper = if q > self.soft_upper { self.velocity * (self.upper - q) / (self.upper - self.soft_upper) } else { f64::INFINITY };
+        let soft_lower = if q < self.soft_lower { -self.velocity * (q - self.lower) / (self.soft_lower - self.lower) } else { f64::NEG_INFINITY };
+        allowed.narrow(soft_lower.min(0.0), soft_upper.max(0.0), LimitLayer::SoftPosition);
+        allowed
+    }
+}
+
+/// Interval of allowed velocities and the layers that set its ends
+#[derive(Debug, Clone, Copy)]
+struct Allowed {
+    lower: f64,
+    upper: f64,
+    lower_layer: LimitLayer,
+    upper_layer: LimitLayer,
+}
+
+impl Allowed {
+    fn new(lower: f64, upper: f64, layer: LimitLayer) -> Self {
+        Self { lower, upper, lower_layer: layer, upper_layer: layer }
+    }
+
+    /// Intersect with `[lower, upper]`, or keep the nearest end when they are disjoint
+    fn narrow(&mut self, lower: f64, upper: f64, layer: LimitLayer) {
+        if lower > self.upper {
+            self.lower = self.upper;
+            self.lower_layer = self.upper_layer;
+        } else if upper < self.lower {
+            self.upper = self.lower;
+            self.upper_layer = self.lower_layer;
+        } else {
+            if lower > self.lower {
+                self.lower = lower;
+                self.lower_layer = layer;
+            }
+            if upper < self.upper {
+                self.upper = upper;
+                self.upper_layer = layer;
+            }
+        }
+    }
+
+    /// `value` clamped into the interval and the layer it hit, if any
+    fn clamp(&self, value: f64) -> (f64, Option<LimitLayer>) {
+        if value > self.upper {
+            (self.upper, Some(self.upper_layer))
+        } else if value < self.lower {
+            (self.lower, Some(self.lower_layer))
+        } else {
+            (value, None)
+        }
+    }
+
+    /// Largest factor in `[0, 1]` keeping `value` scaled inside the
+    /// interval, unless scaling cannot reach it because it excludes zero
+    fn scale(&self, value: f64) -> Option<(f64, LimitLayer)> {
+        if value > self.upper && self.upper >= 0.0 {
+            Some((self.upper / value, self.upper_layer))
+        } else if value < self.lower && self.lower <= 0.0 {
+            Some((self.lower / value, self.lower_layer))
+        } else {
+            None
+        }
+    }
+}
+
+/// How a command outside the limits is brought inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Enforcement {
+    /// Clamp each coordinate independently
+    #[default]
+    Clamp,
+    /// Scale the whole command by one factor, clamping only coordinates that
+    /// scaling cannot bring inside
+    Scale,
+}
+
+/// A limit that changed a coordinate's command
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActiveLimit {
+    pub coordinate: usize,
+    pub layer: LimitLayer,
+    pub requested: f64,
+    pub applied: f64,
+}
+
+/// Output of [`LimitFilter::filter`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilteredCommand {
+    pub velocity: Vec<f64>,
+    /// Factor applied to the whole command under [`Enforcement::Scale`]; 1 otherwise
+    pub scale: f64,
+    /// Coordinates whose command a limit changed, in coordinate order
+    pub active: Vec<ActiveLimit>,
+}
+
+impl FilteredCommand {
+    pub fn is_limited(&self) -> bool {
+        !self.active.is_empty()
+    }
+}
+
+/// Typed accelerations, jerks and soft zones for [`LimitFilter::for_chain`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerSettings {
+    pub angular_acceleration: AngularAcceleration,
+    pub linear_acceleration: Acceleration,
+    pub angular_jerk: Option<AngularJerk>,
+    pub linear_jerk: Option<Jerk>,
+    /// Fraction of each position range taken by the soft zone at either end
+    pub soft_fraction: f64,
+}
+
+impl LayerSettings {
+    pub fn new(angular_acceleration: AngularAcceleration, linear_acceleration: Acceleration) -> Self {
+        Self { angular_acceleration, linear_acceleration, angular_jerk: None, linear_jerk: None, soft_fraction: 0.0 }
+    }
+
+    pub fn with_jerk(mut self, angular: AngularJerk, linear: Jerk) -> Self {
+        self.angular_jerk = Some(angular);
+        self.linear_jerk = Some(linear);
+        self
+    }
+
+    pub fn with_soft_fraction(mut self, fraction: f64) -> Self {
+        self.soft_fraction = fraction;
+        self
+    }
+}
+
+/// Stateful filter of velocity commands through the limit layers
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitFilter {
+    axes: Vec<AxisLimits>,
+    enforcement: Enforcement,
+    /// Previous filtered command
+    velocity: Vec<f64>,
+    /// Rate of change of the previous filtered command
+    acceleration: Vec<f64>,
+}
+
+impl LimitFilter {
+    /// Filter starting at rest
+    pub fn new(axes: Vec<AxisLimits>) -> Result<Self, LimitError> {
+        if let Some(index) = axes.iter().position(|axis| !axis.is_valid()) {
+            return Err(LimitError::InvalidLimit(index));
+        }
+        let dof = axes.len();
+        Ok(Self { axes, enforcement: Enforcement::default(), velocity: vec![0.0; dof], acceleration: vec![0.0; dof] })
+    }
+
+    /// Limits of every coordinate of `chain`: the joints' ranges and speed
+    /// limits with the accelerations, jerks and soft zones of `settings`
+    ///
+    /// A spherical joint limits the speed of each of its three coordinates but
+    /// not their positions, since its range bounds the rotation angle.
+    pub fn for_chain(chain: &KinematicChain, settings: &LayerSettings) -> Result<Self, LimitError> {
+        let angular = |axis: AxisLimits| {
+            axis.with_acceleration(settings.angular_acceleration.raw())
+                .with_jerk(settings.angular_jerk.map_or(f64::INFINITY, |jerk| jerk.raw()))
+                .with_soft_fraction(settings.soft_fraction)
+        };
+        let linear = |axis: AxisLimits| {
+            axis.with_acceleration(settings.linear_acceleration.raw())
+                .with_jerk(settings.linear_jerk.map_or(f64::INFINITY, |jerk| jerk.raw()))
+                .with_soft_fraction(settings.soft_fraction)
+        };
+        let axes = chain
+            .joints()
+            .flat_map(|joint| match joint {
+                Joint::Revolute { limits, .. } => {
+                    vec![angular(AxisLimits::new(*limits.lower.value(), *limits.upper.value(), *limits.max_velocity.value()))]
+                }
+                Joint::Prismatic { limits, .. } => {
+                    vec![linear(AxisLimits::new(*limits.lower.value(), *limits.upper.value(), *limits.max_velocity.value()))]
+                }
+                Joint::Spherical { limits } => {
+                    vec![angular(AxisLimits::new(f64::NEG_INFINITY, f64::INFINITY, *limits.max_velocity.value())); 3]
+                }
+                Joint::Fixed => Vec::new(),
+            })
+            .collect();
+        Self::new(axes)
+    }
+
+    pub fn with_enforcement(mut self, enforcement: Enforcement) -> Self {
+        self.enforcement = enforcement;
+        self
+    }
+
+    pub fn axes(&self) -> &[AxisLimits] {
+        &self.axes
+    }
+
+    /// Previous filtered command
+    pub fn velocity(&self) -> &[f64] {
+        &self.velocity
+    }
+
+    /// Restart from a measured velocity with zero acceleration
+    pub fn reset(&mut self, velocity: &[f64]) -> Result<(), LimitError> {
+        self.check(velocity)?;
+        self.velocity.copy_from_slice(velocity);
+        self.acceleration.iter_mut().for_each(|a| *a = 0.0);
+        Ok(())
+    }
+
+    fn check(&self, values: &[f64]) -> Result<(), LimitError> {
+        if values.len() == self.axes.len() {
+            Ok(())
+        } else {
+            Err(LimitError::CoordinateCount { expected: self.axes.len(), actual: values.len() })
+        }
+    }
+
+    /// Bring the velocity `command` at positions `q` within every layer for a step of `dt`
+    pub fn filter(&mut self, q: &[f64], command: &[f64], dt: Time) -> Result<FilteredCommand, LimitError> {
+        self.check(q)?;
+        self.check(command)?;
+        let dt = *dt.value();
+        if dt.is_nan() || dt <= 0.0 {
+            return Err(LimitError::InvalidStep);
+        }
+        let allowed: Vec<Allowed> = self
+            .axes
+            .iter()
+            .enumerate()
+            .map(|(i, axis)| axis.allowed(q[i], self.velocity[i], self.acceleration[i], dt))
+            .collect();
+
+        let mut scale = 1.0;
+        let mut scaled_by = None;
+        if self.enforcement == Enforcement::Scale {
+            for (i, (allowed, &value)) in allowed.iter().zip(command).enumerate() {
+                if let Some((factor, layer)) = allowed.scale(value) {
+                    if factor < scale {
+                        scale = factor;
+                        scaled_by = Some((i, layer));
+                    }
+                }
+            }
+        }
+
+        let mut velocity = Vec::with_capacity(command.len());
+        let mut active = Vec::new();
+        for (i, (allowed, &requested)) in allowed.iter().zip(command).enumerate() {
+            let (applied, layer) = allowed.clamp(scale * requested);
+            let layer = layer.or_else(|| scaled_by.filter(|(j, _)| *j == i).map(|(_, layer)| layer));
+            if let Some(layer) = layer {
+                active.push(ActiveLimit { coordinate: i, layer, requested, applied });
+            }
+            velocity.push(applied);
+        }
+
+        for ((previous, acceleration), &v) in self.velocity.iter_mut().zip(&mut self.acceleration).zip(&velocity) {
+            *acceleration = (v - *previous) / dt;
+            *previous = v;
+        }
+        Ok(FilteredCommand { velocity, scale, active })
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::joints::{JointLimits, PrismaticLimits};
+    use crate::motor::Motor;
+    use crate::si_units::{Angle, AngularVelocity, Length, Velocity};
+
+    const DT: f64 = 0.01;
+
+    #[test]
+    fn test_velocity_ramps_at_acceleration_and_jerk_limits() {
+        let mut filter = LimitFilter::new(vec![AxisLimits::new(-10.0, 10.0, 1.0).with_acceleration(2.0)]).unwrap();
+        let first = filter.filter(&[0.0], &[5.0], Time::new(DT)).unwrap();
+        assert!((first.velocity[0] - 2.0 * DT).abs() < 1e-12);
+        assert_eq!(first.active[0].layer, LimitLayer::Acceleration);
+        for _ in 0..100 {
+            filter.filter(&[0.0], &[5.0], Time::new(DT)).unwrap();
+        }
+        let cruising = filter.filter(&[0.0], &[5.0], Time::new(DT)).unwrap();
+        assert_eq!((cruising.velocity[0], cruising.active[0].layer), (1.0, LimitLayer::Velocity));
+        assert!(!filter.filter(&[0.0], &[0.995], Time::new(DT)).unwrap().is_limited());
+
+        let mut smooth = LimitFilter::new(vec![AxisLimits::new(-10.0, 10.0, 1.0).with_acceleration(2.0).with_jerk(20.0)]).unwrap();
+        let mut previous = (0.0, 0.0);
+        for step in 0..50 {
+            let command = smooth.filter(&[0.0], &[1.0], Time::new(DT)).unwrap();
+            let acceleration = (command.velocity[0] - previous.0) / DT;
+            assert!(acceleration <= 2.0 + 1e-9 && (acceleration - previous.1).abs() <= 20.0 * DT + 1e-9, "step {}", step);
+            if step == 0 {
+                assert_eq!(command.active[0].layer, LimitLayer::Jerk);
+            }
+            previous = (command.velocity[0], acceleration);
+        }
+        assert!(smooth.reset(&[0.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_position_layers_stop_before_hard_limit() {
+        let axis = AxisLimits::new(-1.0, 1.0, 1.0).with_acceleration(4.0).with_soft_fraction(0.1);
+        let mut filter = LimitFilter::new(vec![axis]).unwrap();
+        let mut q = 0.0;
+        let mut layers = Vec::new();
+        for _ in 0..400 {
+            let command = filter.filter(&[q], &[1.0], Time::new(DT)).unwrap();
+            layers.extend(command.active.iter().map(|a| a.layer));
+            q += command.velocity[0] * DT;
+            assert!(q <= 1.0, "{}", q);
+        }
+        assert!(q > 0.99);
+        assert!(layers.contains(&LimitLayer::SoftPosition));
+
+        // Past the hard limit only commands back inside pass
+        let mut filter = LimitFilter::new(vec![AxisLimits::new(-1.0, 1.0, 1.0)]).unwrap();
+        let outside = filter.filter(&[1.2], &[0.5], Time::new(DT)).unwrap();
+        assert_eq!((outside.velocity[0], outside.active[0].layer), (0.0, LimitLayer::HardPosition));
+        assert_eq!(filter.filter(&[1.2], &[-0.5], Time::new(DT)).unwrap().velocity, vec![-0.5]);
+        assert_eq!(filter.filter(&[0.0], &[0.5], Time::new(0.0)), Err(LimitError::InvalidStep));
+    }
+
+    #[test]
+    fn test_scaling_keeps_direction() {
+        let axes = vec![AxisLimits::new(-10.0, 10.0, 1.0), AxisLimits::new(-10.0, 10.0, 2.0)];
+        let mut filter = LimitFilter::new(axes.clone()).unwrap().with_enforcement(Enforcement::Scale);
+        let command = filter.filter(&[0.0, 0.0], &[2.0, -1.0], Time::new(DT)).unwrap();
+        assert_eq!((command.velocity.clone(), command.scale), (vec![1.0, -0.5], 0.5));
+        assert_eq!(command.active, vec![ActiveLimit { coordinate: 0, layer: LimitLayer::Velocity, requested: 2.0, applied: 1.0 }]);
+
+        let mut clamping = LimitFilter::new(axes).unwrap();
+        assert_eq!(clamping.filter(&[0.0, 0.0], &[2.0, -1.0], Time::new(DT)).unwrap().velocity, vec![1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_filter_for_chain() {
+        let chain = KinematicChain::new()
+            .with_joint(
+                Motor::identity(),
+                Joint::revolute([0.0, 0.0, 1.0], JointLimits::new(Angle::new(-1.0), Angle::new(1.0), AngularVelocity::new(2.0))),
+            )
+            .with_joint(Motor::identity(), Joint::Fixed)
+            .with_joint(
+                Motor::identity(),
+                Joint::prismatic([1.0, 0.0, 0.0], PrismaticLimits::new(Length::new(0.0), Length::new(0.5), Velocity::new(0.1))),
+            );
+        let settings = LayerSettings::new(AngularAcceleration::new(5.0), Acceleration::new(1.0))
+            .with_jerk(AngularJerk::new(50.0), Jerk::new(10.0))
+            .with_soft_fraction(0.1);
+        let filter = LimitFilter::for_chain(&chain, &settings).unwrap();
+        assert_eq!(filter.axes()[0], AxisLimits::new(-1.0, 1.0, 2.0).with_acceleration(5.0).with_jerk(50.0).with_soft_fraction(0.1));
+        assert_eq!((filter.axes()[1].soft_upper, filter.axes()[1].jerk), (0.45, 10.0));
+        assert_eq!(LimitFilter::new(vec![AxisLimits::new(1.0, -1.0, 1.0)]), Err(LimitError::InvalidLimit(0)));
+    }
+}
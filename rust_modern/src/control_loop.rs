@@ -0,0 +1,336 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Fixed-rate control loops
+//!
+//! [`ControlLoop`] calls a controller at a fixed rate, sleeping until each
+//! deadline and handing the controller the measured time step as a [`Time`].
+//! How far the loop started late (its jitter) and how long the controller took
+//! are recorded in [`TimingStats`].
+//!
+//! When the controller runs past the next deadline the [`OverrunPolicy`]
+//! decides what happens: drop the missed ticks and stay on the original grid,
+//! run them back to back until caught up, or restart the grid from now.
+//!
+//! Time comes from a [`Clock`]; [`SystemClock`] sleeps on the monotonic system
+//! clock and [`SimulatedClock`] jumps instantly, for simulation and tests.
+
+use std::cell::Cell;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use crate::si_units::{Frequency, Time};
+
+/// Source of monotonic time
+pub trait Clock {
+    /// Seconds since an arbitrary fixed origin
+    fn now(&self) -> Time;
+    fn sleep_until(&mut self, deadline: Time);
+}
+
+/// Monotonic wall clock
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self { origin: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Time {
+        Time::new(self.origin.elapsed().as_secs_f64())
+    }
+
+    fn sleep_until(&mut self, deadline: Time) {
+        let remaining = deadline.value() - self.now().value();
+        if remaining > 0.0 {
+            std::thread::sleep(Duration::from_secs_f64(remaining));
+        }
+    }
+}
+
+/// Clock that only moves when told to; clones share the same time
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedClock {
+    seconds: Rc<Cell<f64>>,
+}
+
+impl SimulatedClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Let `duration` pass, e.g. to model the controller's computation time
+    pub fn advance(&self, duration: Time) {
+        self.seconds.set(self.seconds.get() + duration.value().max(0.0));
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> Time {
+        Time::new(self.seconds.get())
+    }
+
+    fn sleep_until(&mut self, deadline: Time) {
+        self.seconds.set(self.seconds.get().max(*deadline.value()));
+    }
+}
+
+/// What to do when the controller runs past the next deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverrunPolicy {
+    /// Drop the ticks whose deadlines have passed and keep the original grid
+    Skip,
+    /// Run late ticks immediately, one after another, until back on the grid
+    CatchUp,
+    /// Start a new grid at the moment the overrunning tick finished
+    Reset,
+}
+
+/// What the controller sees each tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tick {
+    /// Ticks run before this one
+    pub iteration: u64,
+    /// Measured time since the previous tick started; the nominal period on the first
+    pub dt: Time,
+    /// Time since the first tick started
+    pub elapsed: Time,
+    /// How late this tick started relative to its deadline
+    pub jitter: Time,
+}
+
+/// Timing record of a loop
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimingStats {
+    pub iterations: u64,
+    /// Ticks in which the controller ran past the next deadline
+    pub overruns: u64,
+    /// Ticks dropped under [`OverrunPolicy::Skip`]
+    pub skipped: u64,
+    jitter_sum: f64,
+    jitter_squares: f64,
+    jitter_max: f64,
+    work_sum: f64,
+    work_max: f64,
+}
+
+impl TimingStats {
+    fn record(&mut self, jitter: f64, work: f64) {
+        self.iterations += 1;
+        self.jitter_sum += jitter;
+        self.jitter_squares += jitter * jitter;
+        self.jitter_max = self.jitter_max.max(jitter);
+        self.work_sum += work;
+        self.work_max = self.work_max.max(work);
+    }
+
+    fn mean(&self, sum: f64) -> Time {
+        Time::new(if self.iterations == 0 { 0.0 } else { sum / self.iterations as f64 })
+    }
+
+    pub fn mean_jitter(&self) -> Time {
+        self.mean(self.jitter_sum)
+    }
+
+    pub fn rms_jitter(&self) -> Time {
+        Time::new(self.mean(self.jitter_squares).value().sqrt())
+    }
+
+    pub fn max_jitter(&self) -> Time {
+        Time::new(self.jitter_max)
+    }
+
+    /// Mean time spent inside the controller
+    pub fn mean_work(&self) -> Time {
+        self.mean(self.work_sum)
+    }
+
+    pub fn max_work(&self) -> Time {
+        Time::new(self.work_max)
+    }
+}
+
+/// Calls a controller at a fixed rate
+#[derive(Debug, Clone)]
+pub struct ControlLoop<C: Clock = SystemClock> {
+    clock: C,
+    period: f64,
+    policy: OverrunPolicy,
+    /// Deadline of the next tick and start of the first, once running
+    schedule: Option<(f64, f64)>,
+    last_start: Option<f64>,
+    stats: TimingStats,
+}
+
+impl ControlLoop {
+    /// Loop on the system clock at `rate`, skipping ticks on overrun
+    pub fn new(rate: Frequency) -> Self {
+        Self::with_clock(rate, SystemClock::new())
+    }
+}
+
+impl<C: Clock> ControlLoop<C> {
+    pub fn with_clock(rate: Frequency, clock: C) -> Self {
+        assert!(*rate.value() > 0.0 && rate.value().is_finite(), "loop rate must be positive and finite");
+        Self {
+            clock,
+            period: 1.0 / rate.value(),
+            policy: OverrunPolicy::Skip,
+            schedule: None,
+            last_start: None,
+            stats: TimingStats::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: OverrunPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn period(&self) -> Time {
+        Time::new(self.period)
+    }
+
+    pub fn stats(&self) -> &TimingStats {
+        &self.stats
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Wait for the next deadline and run `controller` once
+    pub fn step<R>(&mut self, controller: impl FnOnce(&Tick) -> R) -> R {
+        let (deadline, first) = *self.schedule.get_or_insert_with(|| {
+            let now = *self.clock.now().value();
+            (now, now)
+        });
+        self.clock.sleep_until(Time::new(deadline));
+        let start = *self.clock.now().value();
+        let jitter = (start - deadline).max(0.0);
+        let tick = Tick {
+            iteration: self.stats.iterations,
+            dt: Time::new(self.last_start.map_or(self.period, |last| start - last)),
+            elapsed: Time::new(start - first),
+            jitter: Time::new(jitter),
+        };
+
+        let result = controller(&tick);
+
+        let end = *self.clock.now().value();
+        self.stats.record(jitter, end - start);
+        self.last_start = Some(start);
+
+        let mut next = deadline + self.period;
+        if end > next {
+            self.stats.overruns += 1;
+            match self.policy {
+                OverrunPolicy::Skip => {
+                    let missed = ((end - next) / self.period).ceil();
+                    self.stats.skipped += missed as u64;
+                    next += missed * self.period;
+                }
+                OverrunPolicy::CatchUp => {}
+                OverrunPolicy::Reset => next = end,
+            }
+        }
+        self.schedule = Some((next, first));
+        result
+    }
+
+    /// Run until the controller breaks
+    pub fn run<B>(&mut self, mut controller: impl FnMut(&Tick) -> ControlFlow<B>) -> B {
+        loop {
+            if let ControlFlow::Break(value) = self.step(&mut controller) {
+                return value;
+            }
+        }
+    }
+
+    /// Run `iterations` ticks
+    pub fn run_for(&mut self, iterations: u64, mut controller: impl FnMut(&Tick)) {
+        for _ in 0..iterations {
+            self.step(&mut controller);
+        }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::UnitExt;
+
+    #[test]
+    fn test_fixed_rate_on_simulated_clock() {
+        let clock = SimulatedClock::new();
+        let mut control = ControlLoop::with_clock(50.0.hertz(), clock.clone());
+        let mut ticks = Vec::new();
+        control.run_for(5, |tick| {
+            clock.advance(Time::new(0.005));
+            ticks.push(*tick);
+        });
+        assert_eq!(*control.period().value(), 0.02);
+        assert!(ticks.iter().all(|t| (t.dt.value() - 0.02).abs() < 1e-12 && t.jitter.value().abs() < 1e-12));
+        assert!((ticks[4].elapsed.value() - 0.08).abs() < 1e-12);
+        assert_eq!((control.stats().iterations, control.stats().overruns), (5, 0));
+        assert!((control.stats().mean_work().value() - 0.005).abs() < 1e-12);
+
+        // The loop keeps running until the controller breaks
+        let stopped = control.run(|tick| if tick.iteration == 7 { ControlFlow::Break(tick.iteration) } else { ControlFlow::Continue(()) });
+        assert_eq!(stopped, 7);
+    }
+
+    #[test]
+    fn test_overrun_policies() {
+        let starts = |policy: OverrunPolicy| {
+            let clock = SimulatedClock::new();
+            let mut control = ControlLoop::with_clock(10.0.hertz(), clock.clone()).with_policy(policy);
+            let mut starts = Vec::new();
+            control.run_for(4, |tick| {
+                starts.push(*tick.elapsed.value());
+                // The first tick takes 0.25 s against a 0.1 s period
+                clock.advance(Time::new(if tick.iteration == 0 { 0.25 } else { 0.01 }));
+            });
+            (starts, *control.stats())
+        };
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-12);
+
+        let (skip, stats) = starts(OverrunPolicy::Skip);
+        assert!(close(&skip, &[0.0, 0.3, 0.4, 0.5]));
+        assert_eq!((stats.overruns, stats.skipped), (1, 2));
+
+        let (catch_up, stats) = starts(OverrunPolicy::CatchUp);
+        assert!(close(&catch_up, &[0.0, 0.25, 0.26, 0.3]));
+        assert!((stats.max_jitter().value() - 0.15).abs() < 1e-12);
+
+        let (reset, _) = starts(OverrunPolicy::Reset);
+        assert!(close(&reset, &[0.0, 0.25, 0.35, 0.45]));
+    }
+
+    #[test]
+    fn test_system_clock_waits() {
+        let mut control = ControlLoop::new(200.0.hertz());
+        let mut ticks = Vec::new();
+        control.run_for(3, |tick| ticks.push((*tick.elapsed.value(), *tick.jitter.value())));
+        // Sleeping never wakes early; a late wake may shorten the next dt, but
+        // ticks stay on the 5 ms grid
+        let (last, _) = ticks[2];
+        assert!(last >= 2.0 * 0.005 - 1e-4, "{}", last);
+        assert!(ticks.iter().all(|&(_, jitter)| jitter >= 0.0));
+    }
+}
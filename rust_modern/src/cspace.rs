@@ -0,0 +1,433 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Configuration-space metrics and nearest-neighbor search for planners
+//!
+//! A sampling planner spends most of its time asking which stored
+//! configuration is nearest to a new sample, so the distance has to mean what
+//! the robot means by "near":
+//!
+//! - [`JointMetric`] is a weighted Euclidean distance on joint coordinates in
+//!   which continuous revolute joints wrap, so −179° and 179° are 2° apart.
+//! - [`MotorMetric`] combines the translation distance with the rotation
+//!   angle between two poses, scaled by a characteristic length so both are
+//!   in meters.
+//!
+//! Both satisfy the triangle inequality, which is all a [`Gnat`]
+//! (Geometric Near-neighbor Access Tree) needs: each node splits its points
+//! among a few pivots and keeps the range of distances from every pivot to
+//! every other pivot's points, so a query skips whole subtrees without
+//! looking at their coordinates. That is what lets the same tree serve
+//! wrapped joint spaces and poses, where the axis-aligned splits of a
+//! KD-tree do not apply.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::euler::wrap;
+use crate::joints::Joint;
+use crate::kinematics::KinematicChain;
+use crate::linalg;
+use crate::motor::Motor;
+use crate::si_units::{Length, TAU};
+
+/// Distance satisfying the triangle inequality
+pub trait Metric<P> {
+    fn distance(&self, a: &P, b: &P) -> f64;
+}
+
+/// Weighted joint-space distance, wrapping continuous joints
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointMetric {
+    /// Weight of each coordinate's difference
+    pub weights: Vec<f64>,
+    /// Whether each coordinate is an angle on a full circle
+    pub wrapping: Vec<bool>,
+}
+
+impl JointMetric {
+    /// Unit weights and no wrapping over `dof` coordinates
+    pub fn euclidean(dof: usize) -> Self {
+        Self { weights: vec![1.0; dof], wrapping: vec![false; dof] }
+    }
+
+    /// Unit weights over the coordinates of `chain`, wrapping revolute joints
+    /// whose range spans a full turn
+    pub fn for_chain(chain: &KinematicChain) -> Self {
+        let wrapping = chain
+            .joints()
+            .flat_map(|joint| match joint {
+                Joint::Revolute { limits, .. } => vec![*limits.span().value() >= TAU],
+                Joint::Prismatic { .. } => vec![false],
+                Joint::Spherical { .. } => vec![false; 3],
+                Joint::Fixed => Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        Self { weights: vec![1.0; wrapping.len()], wrapping }
+    }
+
+    pub fn with_weights(mut self, weights: Vec<f64>) -> Self {
+        assert_eq!(weights.len(), self.wrapping.len(), "one weight per coordinate");
+        self.weights = weights;
+        self
+    }
+
+    pub fn dof(&self) -> usize {
+        self.wrapping.len()
+    }
+
+    /// Shortest signed difference `b - a` of each coordinate
+    pub fn difference(&self, a: &[f64], b: &[f64]) -> Vec<f64> {
+        a.iter().zip(b).zip(&self.wrapping).map(|((a, b), &wraps)| if wraps { wrap(b - a) } else { b - a }).collect()
+    }
+
+    /// Point a fraction `t` of the way from `a` to `b` along the shortest difference
+    pub fn interpolate(&self, a: &[f64], b: &[f64], t: f64) -> Vec<f64> {
+        let difference = self.difference(a, b);
+        a.iter()
+            .zip(difference)
+            .zip(&self.wrapping)
+            .map(|((a, d), &wraps)| if wraps { wrap(a + t * d) } else { a + t * d })
+            .collect()
+    }
+}
+
+impl Metric<Vec<f64>> for JointMetric {
+    fn distance(&self, a: &Vec<f64>, b: &Vec<f64>) -> f64 {
+        self.difference(a, b).iter().zip(&self.weights).map(|(d, w)| (w * d) * (w * d)).sum::<f64>().sqrt()
+    }
+}
+
+/// Pose distance `√(|Δt|² + (L θ)²)` for translation offset `Δt` and relative
+/// rotation angle `θ`, with `L` the characteristic length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotorMetric {
+    /// Length whose arc at one radian counts as far as that translation
+    pub characteristic_length: Length,
+}
+
+impl MotorMetric {
+    pub fn new(characteristic_length: Length) -> Self {
+        Self { characteristic_length }
+    }
+
+    /// Translation interpolated linearly and rotation along the shortest arc,
+    /// the geodesic of this metric
+    pub fn interpolate(&self, a: &Motor, b: &Motor, t: f64) -> Motor {
+        let translation = linalg::add(a.translation, linalg::scale(linalg::sub(b.translation, a.translation), t));
+        Motor::new(translation, a.rotor.slerp(&b.rotor, t))
+    }
+}
+
+impl Metric<Motor> for MotorMetric {
+    fn distance(&self, a: &Motor, b: &Motor) -> f64 {
+        let translation = linalg::norm(linalg::sub(b.translation, a.translation));
+        let angle = (a.rotor.reverse() * b.rotor).angle();
+        translation.hypot(self.characteristic_length.value() * angle)
+    }
+}
+
+/// Neighbor found by a query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbor {
+    /// Index of the point in insertion order
+    pub index: usize,
+    pub distance: f64,
+}
+
+impl Eq for Neighbor {}
+
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// By distance, so a max-heap keeps the farthest of the current best on top
+impl Ord for Neighbor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance.total_cmp(&other.distance).then(self.index.cmp(&other.index))
+    }
+}
+
+/// Node of a [`Gnat`]: a bucket of points, or pivots each owning a subtree
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(Vec<usize>),
+    Split {
+        pivots: Vec<usize>,
+        /// `ranges[i][j]`: smallest and largest distance from pivot `i` to
+        /// pivot `j` and the points below it
+        ranges: Vec<Vec<(f64, f64)>>,
+        children: Vec<Node>,
+    },
+}
+
+/// Geometric Near-neighbor Access Tree over any [`Metric`]
+#[derive(Debug, Clone)]
+pub struct Gnat<P, M> {
+    metric: M,
+    points: Vec<P>,
+    root: Node,
+    /// Pivots per split node
+    degree: usize,
+    /// Largest bucket before it is split
+    leaf_size: usize,
+}
+
+impl<P, M: Metric<P>> Gnat<P, M> {
+    /// Empty tree with eight pivots per node and buckets of up to 32 points
+    pub fn new(metric: M) -> Self {
+        Self { metric, points: Vec::new(), root: Node::Leaf(Vec::new()), degree: 8, leaf_size: 32 }
+    }
+
+    /// Tree over `points`, built top down
+    pub fn build(metric: M, points: Vec<P>) -> Self {
+        let mut tree = Self::new(metric);
+        tree.points = points;
+        tree.root = tree.split((0..tree.points.len()).collect());
+        tree
+    }
+
+    /// The same points with `degree` pivots per node and buckets of `leaf_size`
+    pub fn with_shape(mut self, degree: usize, leaf_size: usize) -> Self {
+        self.degree = degree.max(2);
+        self.leaf_size = leaf_size.max(1);
+        self.root = self.split((0..self.points.len()).collect());
+        self
+    }
+
+    pub fn metric(&self) -> &M {
+        &self.metric
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn points(&self) -> &[P] {
+        &self.points
+    }
+
+    /// Add a point; returns its index
+    pub fn insert(&mut self, point: P) -> usize {
+        let index = self.points.len();
+        self.points.push(point);
+        let mut root = std::mem::replace(&mut self.root, Node::Leaf(Vec::new()));
+        self.insert_below(&mut root, index);
+        self.root = root;
+        index
+    }
+
+    fn insert_below(&self, node: &mut Node, index: usize) {
+        match node {
+            Node::Leaf(bucket) => {
+                bucket.push(index);
+                if bucket.len() > self.leaf_size {
+                    *node = self.split(std::mem::take(bucket));
+                }
+            }
+            Node::Split { pivots, ranges, children } => {
+                let distances: Vec<f64> = pivots.iter().map(|&p| self.metric.distance(&self.points[p], &self.points[index])).collect();
+                let nearest = argmin(&distances);
+                for (row, &d) in ranges.iter_mut().zip(&distances) {
+                    let range = &mut row[nearest];
+                    *range = (range.0.min(d), range.1.max(d));
+                }
+                self.insert_below(&mut children[nearest], index);
+            }
+        }
+    }
+
+    /// Subtree over `indices`: a bucket when small enough, else pivots chosen
+    /// farthest first with every other point under its nearest pivot
+    fn split(&self, mut indices: Vec<usize>) -> Node {
+        if indices.len() <= self.leaf_size {
+            return Node::Leaf(indices);
+        }
+        let distance = |a: usize, b: usize| self.metric.distance(&self.points[a], &self.points[b]);
+
+        // Farthest-first pivots; `nearest[k]` tracks the distance of point k to the chosen pivots
+        let mut pivots = vec![indices.swap_remove(0)];
+        let mut nearest: Vec<f64> = indices.iter().map(|&i| distance(pivots[0], i)).collect();
+        while pivots.len() < self.degree && !indices.is_empty() {
+            let far = (0..indices.len()).max_by(|&a, &b| nearest[a].total_cmp(&nearest[b])).expect("points remain");
+            let pivot = indices.swap_remove(far);
+            nearest.swap_remove(far);
+            for (k, &i) in indices.iter().enumerate() {
+                nearest[k] = nearest[k].min(distance(pivot, i));
+            }
+            pivots.push(pivot);
+        }
+
+        let m = pivots.len();
+        let mut ranges = vec![vec![(f64::INFINITY, f64::NEG_INFINITY); m]; m];
+        let widen = |range: &mut (f64, f64), d: f64| *range = (range.0.min(d), range.1.max(d));
+        for i in 0..m {
+            for j in 0..m {
+                widen(&mut ranges[i][j], distance(pivots[i], pivots[j]));
+            }
+        }
+        let mut groups = vec![Vec::new(); m];
+        for &point in &indices {
+            let distances: Vec<f64> = pivots.iter().map(|&p| distance(p, point)).collect();
+            let owner = argmin(&distances);
+            for (row, &d) in ranges.iter_mut().zip(&distances) {
+                widen(&mut row[owner], d);
+            }
+            groups[owner].push(point);
+        }
+        let children = groups.into_iter().map(|group| self.split(group)).collect();
+        Node::Split { pivots, ranges, children }
+    }
+
+    /// Nearest stored point to `query`
+    pub fn nearest(&self, query: &P) -> Option<Neighbor> {
+        self.k_nearest(query, 1).into_iter().next()
+    }
+
+    /// Up to `k` nearest stored points, nearest first
+    pub fn k_nearest(&self, query: &P, k: usize) -> Vec<Neighbor> {
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(&self.root, query, f64::INFINITY, &mut |neighbor: Neighbor| {
+                best.push(neighbor);
+                if best.len() > k {
+                    best.pop();
+                }
+                if best.len() < k { f64::INFINITY } else { best.peek().map_or(f64::INFINITY, |n: &Neighbor| n.distance) }
+            });
+        }
+        best.into_sorted_vec()
+    }
+
+    /// Every stored point within `radius` of `query`, nearest first
+    pub fn within(&self, query: &P, radius: f64) -> Vec<Neighbor> {
+        let mut found = Vec::new();
+        self.search(&self.root, query, radius, &mut |neighbor: Neighbor| {
+            if neighbor.distance <= radius {
+                found.push(neighbor);
+            }
+            radius
+        });
+        found.sort();
+        found
+    }
+
+    /// Offer every point of `node` that may lie within `radius` of `query` to
+    /// `visit`, which returns the radius still of interest, pruning subtrees
+    /// by the triangle inequality; returns the final radius
+    fn search(&self, node: &Node, query: &P, mut radius: f64, visit: &mut impl FnMut(Neighbor) -> f64) -> f64 {
+        match node {
+            Node::Leaf(bucket) => {
+                for &index in bucket {
+                    radius = visit(Neighbor { index, distance: self.metric.distance(query, &self.points[index]) });
+                }
+            }
+            Node::Split { pivots, ranges, children } => {
+                let distances: Vec<f64> = pivots.iter().map(|&p| self.metric.distance(query, &self.points[p])).collect();
+                for (&index, &distance) in pivots.iter().zip(&distances) {
+                    radius = visit(Neighbor { index, distance });
+                }
+                let mut order: Vec<usize> = (0..pivots.len()).collect();
+                order.sort_by(|&a, &b| distances[a].total_cmp(&distances[b]));
+                for j in order {
+                    let reachable = distances.iter().zip(ranges).all(|(&d, row)| d - radius <= row[j].1 && d + radius >= row[j].0);
+                    if reachable {
+                        radius = self.search(&children[j], query, radius, visit);
+                    }
+                }
+            }
+        }
+        radius
+    }
+}
+
+fn argmin(values: &[f64]) -> usize {
+    (0..values.len()).min_by(|&a, &b| values[a].total_cmp(&values[b])).expect("at least one value")
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::joints::{JointLimits, PrismaticLimits};
+    use crate::motor::Rotor;
+    use crate::primitives::Aabb;
+    use crate::sample::{MotorBounds, Sampler};
+    use crate::si_units::{Angle, AngularVelocity, Velocity};
+
+    fn brute_force<P>(metric: &impl Metric<P>, points: &[P], query: &P, k: usize) -> Vec<usize> {
+        let mut all: Vec<Neighbor> =
+            points.iter().enumerate().map(|(index, p)| Neighbor { index, distance: metric.distance(query, p) }).collect();
+        all.sort();
+        all.into_iter().take(k).map(|n| n.index).collect()
+    }
+
+    #[test]
+    fn test_joint_metric_wraps_continuous_joints() {
+        let continuous = JointLimits::new(Angle::new(-TAU / 2.0), Angle::new(TAU / 2.0), AngularVelocity::new(1.0));
+        let bounded = JointLimits::new(Angle::new(-3.0), Angle::new(3.0), AngularVelocity::new(1.0));
+        let chain = KinematicChain::new()
+            .with_joint(Motor::identity(), Joint::revolute([0.0, 0.0, 1.0], continuous))
+            .with_joint(Motor::identity(), Joint::revolute([0.0, 1.0, 0.0], bounded))
+            .with_joint(
+                Motor::identity(),
+                Joint::prismatic([1.0, 0.0, 0.0], PrismaticLimits::new(Length::new(0.0), Length::new(1.0), Velocity::new(1.0))),
+            );
+        let metric = JointMetric::for_chain(&chain).with_weights(vec![1.0, 1.0, 2.0]);
+        assert_eq!(metric.wrapping, vec![true, false, false]);
+
+        let (a, b) = (vec![3.1, 3.1, 0.0], vec![-3.1, -3.1, 0.5]);
+        let wrapped = TAU - 6.2;
+        assert!((metric.distance(&a, &b) - (wrapped * wrapped + 6.2 * 6.2 + 1.0).sqrt()).abs() < 1e-12);
+        let middle = metric.interpolate(&a, &b, 0.5);
+        assert!((middle[0].abs() - TAU / 2.0).abs() < 1e-12 && middle[1].abs() < 1e-12 && (middle[2] - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_motor_metric_counts_rotation_as_arc_length() {
+        let metric = MotorMetric::new(Length::new(0.5));
+        let a = Motor::from_translation([1.0, 0.0, 0.0]);
+        let b = Motor::new([1.0, 3.0, 0.0], Rotor::from_axis_angle([0.0, 0.0, 1.0], 8.0));
+        assert!((metric.distance(&a, &b) - 3.0f64.hypot(0.5 * (TAU - 8.0))).abs() < 1e-12);
+        assert!((metric.distance(&a, &metric.interpolate(&a, &b, 0.25)) - 0.25 * metric.distance(&a, &b)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gnat_matches_brute_force() {
+        let mut sampler = Sampler::new(7);
+        let metric = JointMetric { weights: vec![1.0, 0.5, 2.0, 1.0], wrapping: vec![true, true, false, false] };
+        let mut coordinates = || (0..4).map(|_| sampler.range(-TAU / 2.0, TAU / 2.0)).collect::<Vec<f64>>();
+        let points: Vec<Vec<f64>> = (0..5000).map(|_| coordinates()).collect();
+        let queries: Vec<Vec<f64>> = (0..50).map(|_| coordinates()).collect();
+
+        let mut tree = Gnat::build(metric.clone(), points[..4000].to_vec());
+        for point in &points[4000..] {
+            tree.insert(point.clone());
+        }
+        assert_eq!(tree.len(), 5000);
+        for query in &queries {
+            let found: Vec<usize> = tree.k_nearest(query, 5).iter().map(|n| n.index).collect();
+            assert_eq!(found, brute_force(&metric, &points, query, 5));
+            let within = tree.within(query, 1.5);
+            assert_eq!(within.len(), points.iter().filter(|p| metric.distance(query, p) <= 1.5).count());
+        }
+
+        let mut sampler = Sampler::new(8);
+        let bounds = MotorBounds::new(Aabb::new([-5.0; 3], [5.0; 3]));
+        let poses: Vec<Motor> = (0..3000).map(|_| sampler.motor(&bounds)).collect();
+        let metric = MotorMetric::new(Length::new(1.0));
+        let tree = Gnat::build(metric, poses.clone()).with_shape(4, 8);
+        for _ in 0..20 {
+            let query = sampler.motor(&bounds);
+            assert_eq!(tree.nearest(&query).map(|n| n.index), brute_force(&metric, &poses, &query, 1).first().copied());
+        }
+        assert!(Gnat::new(metric).nearest(&Motor::identity()).is_none());
+    }
+}
@@ -0,0 +1,227 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Rounding utilities for cross-language determinism
+//!
+//! Transcendental functions (`sin`, `atan2`, `sqrt` of sums) are not required to
+//! be correctly rounded, so the C++ and Rust implementations can disagree in the
+//! last bits. Quantizing results onto a coarser grid removes that noise before
+//! values are serialized and compared against golden files.
+//!
+//! Both grids are simple to reproduce in other languages:
+//! - [`Quantize::quantize`] rounds to `d` decimal places, half away from zero
+//!   (`std::round(x * 10^d) / 10^d` in C++);
+//! - [`Quantize::round_to_ulp`] rounds away the lowest `n` mantissa bits, half
+//!   away from zero in magnitude.
+//!
+//! Both map `-0.0` to `0.0` so that signs of zero cannot leak into the output.
+//!
+//! The determinism mode is per thread: once set, nonlinear operations (rotor and
+//! motor exponentials and logarithms, normalization, unit-aware math functions)
+//! quantize their results through [`settle`].
+
+use std::cell::Cell;
+
+use crate::ga_term::GATerm;
+use crate::motor::{Bivector, Motor, MotorGenerator, Rotor};
+use crate::pattern_matching::combinators;
+use crate::si_units::Quantity;
+
+/// Grid that results are rounded onto in determinism mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    DecimalPlaces(u32),
+    MantissaBits(u32),
+}
+
+thread_local! {
+    static MODE: Cell<Option<Rounding>> = const { Cell::new(None) };
+}
+
+/// Enable (`Some`) or disable (`None`) determinism mode on this thread
+pub fn set_determinism_mode(mode: Option<Rounding>) {
+    MODE.with(|cell| cell.set(mode));
+}
+
+pub fn determinism_mode() -> Option<Rounding> {
+    MODE.with(Cell::get)
+}
+
+/// Run `f` with the given mode, restoring the previous mode afterwards
+pub fn with_determinism_mode<R>(mode: Option<Rounding>, f: impl FnOnce() -> R) -> R {
+    let previous = determinism_mode();
+    set_determinism_mode(mode);
+    let result = f();
+    set_determinism_mode(previous);
+    result
+}
+
+/// Apply the current determinism mode to a value; identity when disabled
+pub fn settle<Q: Quantize>(value: Q) -> Q {
+    match determinism_mode() {
+        None => value,
+        Some(Rounding::DecimalPlaces(places)) => value.quantize(places),
+        Some(Rounding::MantissaBits(bits)) => value.round_to_ulp(bits),
+    }
+}
+
+/// Values that can be rounded onto a reproducible grid
+pub trait Quantize: Sized {
+    /// Round every coefficient to `decimal_places` decimal places
+    fn quantize(&self, decimal_places: u32) -> Self;
+
+    /// Round away the lowest `bits` mantissa bits of every coefficient
+    fn round_to_ulp(&self, bits: u32) -> Self;
+}
+
+macro_rules! impl_quantize_float {
+    ($float:ty, $bits:ty, $mantissa:expr) => {
+        impl Quantize for $float {
+            fn quantize(&self, decimal_places: u32) -> Self {
+                let scale = (10.0 as $float).powi(decimal_places as i32);
+                let scaled = *self * scale;
+                // Beyond 2^mantissa every representable value is already an integer
+                if !scaled.is_finite() || scaled.abs() >= (2.0 as $float).powi($mantissa) {
+                    return *self + 0.0;
+                }
+                scaled.round() / scale + 0.0
+            }
+
+            fn round_to_ulp(&self, bits: u32) -> Self {
+                if bits == 0 || !self.is_finite() {
+                    return *self + 0.0;
+                }
+                let bits = bits.min($mantissa);
+                let half: $bits = 1 << (bits - 1);
+                let mask: $bits = !((1 << bits) - 1);
+                <$float>::from_bits((self.to_bits() + half) & mask) + 0.0
+            }
+        }
+    };
+}
+
+impl_quantize_float!(f64, u64, 52);
+impl_quantize_float!(f32, u32, 23);
+
+impl<T: Quantize, const N: usize> Quantize for [T; N] {
+    fn quantize(&self, decimal_places: u32) -> Self {
+        std::array::from_fn(|i| self[i].quantize(decimal_places))
+    }
+
+    fn round_to_ulp(&self, bits: u32) -> Self {
+        std::array::from_fn(|i| self[i].round_to_ulp(bits))
+    }
+}
+
+impl<T: Quantize + Clone> Quantize for GATerm<T> {
+    fn quantize(&self, decimal_places: u32) -> Self {
+        combinators::map(self, |c: &T| c.quantize(decimal_places))
+    }
+
+    fn round_to_ulp(&self, bits: u32) -> Self {
+        combinators::map(self, |c: &T| c.round_to_ulp(bits))
+    }
+}
+
+// Dimension exponents named by their SI symbols: M L T I Θ N J
+impl<T: Quantize, const M: i8, const L: i8, const TM: i8, const I: i8, const TH: i8, const N: i8, const J: i8> Quantize
+    for Quantity<T, M, L, TM, I, TH, N, J>
+{
+    fn quantize(&self, decimal_places: u32) -> Self {
+        Quantity::new(self.value().quantize(decimal_places))
+    }
+
+    fn round_to_ulp(&self, bits: u32) -> Self {
+        Quantity::new(self.value().round_to_ulp(bits))
+    }
+}
+
+impl Quantize for Rotor {
+    fn quantize(&self, decimal_places: u32) -> Self {
+        let [scalar, e23, e13, e12] = [self.scalar, self.e23, self.e13, self.e12].quantize(decimal_places);
+        Rotor::new(scalar, e23, e13, e12)
+    }
+
+    fn round_to_ulp(&self, bits: u32) -> Self {
+        let [scalar, e23, e13, e12] = [self.scalar, self.e23, self.e13, self.e12].round_to_ulp(bits);
+        Rotor::new(scalar, e23, e13, e12)
+    }
+}
+
+impl Quantize for Bivector {
+    fn quantize(&self, decimal_places: u32) -> Self {
+        let [e23, e13, e12] = [self.e23, self.e13, self.e12].quantize(decimal_places);
+        Bivector::new(e23, e13, e12)
+    }
+
+    fn round_to_ulp(&self, bits: u32) -> Self {
+        let [e23, e13, e12] = [self.e23, self.e13, self.e12].round_to_ulp(bits);
+        Bivector::new(e23, e13, e12)
+    }
+}
+
+impl Quantize for Motor {
+    fn quantize(&self, decimal_places: u32) -> Self {
+        Motor::new(self.translation.quantize(decimal_places), self.rotor.quantize(decimal_places))
+    }
+
+    fn round_to_ulp(&self, bits: u32) -> Self {
+        Motor::new(self.translation.round_to_ulp(bits), self.rotor.round_to_ulp(bits))
+    }
+}
+
+impl Quantize for MotorGenerator {
+    fn quantize(&self, decimal_places: u32) -> Self {
+        MotorGenerator::new(self.rotation.quantize(decimal_places), self.translation.quantize(decimal_places))
+    }
+
+    fn round_to_ulp(&self, bits: u32) -> Self {
+        MotorGenerator::new(self.rotation.round_to_ulp(bits), self.translation.round_to_ulp(bits))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::units;
+
+    #[test]
+    fn test_float_rounding() {
+        assert_eq!(1.23456.quantize(3), 1.235);
+        assert_eq!((-2.5f64).quantize(0), -3.0);
+        assert_eq!((-1e-9f64).quantize(3).to_bits(), 0.0f64.to_bits());
+        assert_eq!(1e300.quantize(6), 1e300);
+
+        let x = 0.1 + 0.2;
+        assert_ne!(x, 0.3);
+        assert_eq!(x.round_to_ulp(4), 0.3f64.round_to_ulp(4));
+        assert_eq!(1.5f32.round_to_ulp(30), 2.0);
+    }
+
+    #[test]
+    fn test_composite_rounding() {
+        let term = GATerm::bivector(vec![(1, 2, 0.12345), (2, 3, -0.98765)]);
+        assert_eq!(term.quantize(2), GATerm::bivector(vec![(1, 2, 0.12), (2, 3, -0.99)]));
+
+        let length = units::meters(1.23456);
+        assert_eq!(*length.quantize(1).value(), 1.2);
+
+        let motor = Motor::new([0.123456, 0.0, -1.0], Rotor::from_axis_angle([0.0, 0.0, 1.0], 1.0));
+        let quantized = motor.quantize(4);
+        assert_eq!(quantized.translation, [0.1235, 0.0, -1.0]);
+        assert_eq!(quantized.rotor.scalar, 0.8776);
+    }
+
+    #[test]
+    fn test_determinism_mode() {
+        let generator = MotorGenerator::new([0.1, 0.2, 0.3], [1.0, 2.0, 3.0]);
+        let exact = Motor::exp(&generator);
+
+        let settled = with_determinism_mode(Some(Rounding::DecimalPlaces(6)), || Motor::exp(&generator));
+        assert_eq!(settled, exact.quantize(6));
+        assert_eq!(determinism_mode(), None);
+        assert_eq!(Motor::exp(&generator), exact);
+    }
+}
@@ -0,0 +1,438 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Wave and current disturbances for simulation
+//!
+//! A sea state is described by its [`Jonswap`] spectrum and realized as a sum
+//! of regular wave components with random phases drawn from a seeded
+//! [`Sampler`], so a stress test replays exactly from its seed. Component
+//! frequencies are jittered within equal bins, which keeps the realization
+//! from repeating with the bin spacing.
+//!
+//! [`WaveDisturbance`] turns a realization into loads on a submerged vehicle:
+//! - first order: every component pushes with a fixed force per meter of
+//!   amplitude in each degree of freedom, attenuated by `e^(-k d)` with the
+//!   deep-water wave number `k = ω² / g` at depth `d`;
+//! - second order: the slowly varying drift of Newman's approximation,
+//!   `D |Σ aᵢ e^(iθᵢ)|²`, whose mean is `D Σ aᵢ²`.
+//!
+//! [`CurrentGust`] is a mean current plus a first-order Gauss-Markov gust,
+//! applied through quadratic drag. Every model implements [`Disturbance`] and
+//! returns a typed [`Load`]; [`Disturbances`] adds several together.
+//!
+//! Degrees of freedom follow the twist order used by
+//! [`crate::uvms::HullHydrodynamics`]: roll, pitch, yaw, then surge, sway, heave.
+
+use serde::{Deserialize, Serialize};
+
+use crate::grasp::Wrench;
+use crate::sample::Sampler;
+use crate::si_units::{marine, Angle, AngularVelocity, Force, Length, Time, Torque, Velocity, TAU};
+
+/// Lowest and highest realized frequency, as multiples of the peak frequency
+const FREQUENCY_RANGE: (f64, f64) = (0.4, 4.0);
+
+/// Force and torque of a disturbance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Load {
+    pub force: [Force; 3],
+    pub torque: [Torque; 3],
+}
+
+impl Load {
+    /// Load from six components in twist order (N·m, then N)
+    pub fn from_array(a: [f64; 6]) -> Self {
+        Self {
+            force: [Force::new(a[3]), Force::new(a[4]), Force::new(a[5])],
+            torque: [Torque::new(a[0]), Torque::new(a[1]), Torque::new(a[2])],
+        }
+    }
+
+    pub fn to_array(&self) -> [f64; 6] {
+        let (f, t) = (self.force.map(|f| *f.value()), self.torque.map(|t| *t.value()));
+        [t[0], t[1], t[2], f[0], f[1], f[2]]
+    }
+
+    pub fn to_wrench(&self) -> Wrench {
+        let a = self.to_array();
+        Wrench::new([a[3], a[4], a[5]], [a[0], a[1], a[2]])
+    }
+}
+
+impl Default for Load {
+    fn default() -> Self {
+        Load::from_array([0.0; 6])
+    }
+}
+
+impl std::ops::Add for Load {
+    type Output = Load;
+
+    fn add(self, other: Load) -> Load {
+        let (a, b) = (self.to_array(), other.to_array());
+        Load::from_array(std::array::from_fn(|i| a[i] + b[i]))
+    }
+}
+
+/// Source of a time-varying load
+pub trait Disturbance {
+    /// Load at `time`; stateful models expect times that do not decrease
+    fn load(&mut self, time: Time) -> Load;
+}
+
+/// A constant load
+impl Disturbance for Load {
+    fn load(&mut self, _time: Time) -> Load {
+        *self
+    }
+}
+
+/// JONSWAP wave spectrum
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Jonswap {
+    pub significant_height: Length,
+    pub peak_period: Time,
+    /// Peak enhancement factor γ; 1 gives the Pierson-Moskowitz spectrum
+    pub gamma: f64,
+}
+
+impl Jonswap {
+    /// North Sea spectrum with γ = 3.3
+    pub fn new(significant_height: Length, peak_period: Time) -> Self {
+        Self { significant_height, peak_period, gamma: 3.3 }
+    }
+
+    /// Fully developed sea, γ = 1
+    pub fn pierson_moskowitz(significant_height: Length, peak_period: Time) -> Self {
+        Self { gamma: 1.0, ..Self::new(significant_height, peak_period) }
+    }
+
+    pub fn with_peak_enhancement(mut self, gamma: f64) -> Self {
+        self.gamma = gamma.max(1.0);
+        self
+    }
+
+    pub fn peak_frequency(&self) -> AngularVelocity {
+        AngularVelocity::new(TAU / self.peak_period.value())
+    }
+
+    /// Spectral density in m²·s at angular frequency `omega`
+    pub fn density(&self, omega: AngularVelocity) -> f64 {
+        let omega = *omega.value();
+        if omega <= 0.0 {
+            return 0.0;
+        }
+        let peak = *self.peak_frequency().value();
+        let hs = *self.significant_height.value();
+        let pierson_moskowitz = 5.0 / 16.0 * hs * hs * peak.powi(4) / omega.powi(5) * (-1.25 * (peak / omega).powi(4)).exp();
+        let sigma = if omega <= peak { 0.07 } else { 0.09 };
+        let r = (-(omega - peak).powi(2) / (2.0 * sigma * sigma * peak * peak)).exp();
+        // Normalization keeping the significant height close to `hs` (Goda)
+        let normalization = 1.0 - 0.287 * self.gamma.ln();
+        normalization * pierson_moskowitz * self.gamma.powf(r)
+    }
+
+    /// Random realization with `components` regular waves
+    pub fn realize(&self, components: usize, sampler: &mut Sampler) -> SeaState {
+        let peak = *self.peak_frequency().value();
+        let (low, high) = (FREQUENCY_RANGE.0 * peak, FREQUENCY_RANGE.1 * peak);
+        let bin = (high - low) / components.max(1) as f64;
+        let components = (0..components)
+            .map(|i| {
+                let omega = low + (i as f64 + sampler.uniform()) * bin;
+                let amplitude = (2.0 * self.density(AngularVelocity::new(omega)) * bin).sqrt();
+                WaveComponent {
+                    frequency: AngularVelocity::new(omega),
+                    amplitude: Length::new(amplitude),
+                    phase: Angle::new(sampler.range(0.0, TAU)),
+                }
+            })
+            .collect();
+        SeaState { components }
+    }
+}
+
+/// One regular wave of a realization
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WaveComponent {
+    pub frequency: AngularVelocity,
+    pub amplitude: Length,
+    pub phase: Angle,
+}
+
+impl WaveComponent {
+    /// Deep-water wave number `ω² / g`, in rad/m
+    pub fn wave_number(&self) -> f64 {
+        self.frequency.value().powi(2) / marine::gravity::<f64>().value()
+    }
+}
+
+/// A realized sea: the sum of its components
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeaState {
+    components: Vec<WaveComponent>,
+}
+
+impl SeaState {
+    pub fn new(components: Vec<WaveComponent>) -> Self {
+        Self { components }
+    }
+
+    pub fn components(&self) -> &[WaveComponent] {
+        &self.components
+    }
+
+    /// Surface elevation at `time`
+    pub fn elevation(&self, time: Time) -> Length {
+        Length::new(self.envelope(time, Length::new(0.0)).0)
+    }
+
+    /// `4 √m₀` from the component amplitudes
+    pub fn significant_height(&self) -> Length {
+        let m0: f64 = self.components.iter().map(|c| 0.5 * c.amplitude.value().powi(2)).sum();
+        Length::new(4.0 * m0.sqrt())
+    }
+
+    /// In-phase and quadrature sums of the components attenuated to `depth`
+    fn envelope(&self, time: Time, depth: Length) -> (f64, f64) {
+        let (t, d) = (*time.value(), depth.value().max(0.0));
+        self.components.iter().fold((0.0, 0.0), |(cos, sin), c| {
+            let amplitude = c.amplitude.value() * (-c.wave_number() * d).exp();
+            let theta = c.frequency.value() * t + c.phase.value();
+            (cos + amplitude * theta.cos(), sin + amplitude * theta.sin())
+        })
+    }
+}
+
+/// First-order and drift loads of a sea state on a vehicle at depth
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaveDisturbance {
+    sea: SeaState,
+    depth: Length,
+    /// Load per meter of wave amplitude (N·m/m, then N/m)
+    first_order: [f64; 6],
+    /// Drift load per squared meter of amplitude (N·m/m², then N/m²)
+    drift: [f64; 6],
+}
+
+impl WaveDisturbance {
+    /// A vehicle at `depth` that the sea does not load until gains are set
+    pub fn new(sea: SeaState, depth: Length) -> Self {
+        Self { sea, depth, first_order: [0.0; 6], drift: [0.0; 6] }
+    }
+
+    pub fn with_first_order(mut self, gains: [f64; 6]) -> Self {
+        self.first_order = gains;
+        self
+    }
+
+    pub fn with_drift(mut self, coefficients: [f64; 6]) -> Self {
+        self.drift = coefficients;
+        self
+    }
+
+    pub fn sea(&self) -> &SeaState {
+        &self.sea
+    }
+
+    pub fn depth(&self) -> Length {
+        self.depth
+    }
+
+    /// Follow the vehicle as it changes depth
+    pub fn set_depth(&mut self, depth: Length) {
+        self.depth = depth;
+    }
+
+    /// Oscillating load, with zero mean
+    pub fn first_order(&self, time: Time) -> Load {
+        let (elevation, _) = self.sea.envelope(time, self.depth);
+        Load::from_array(self.first_order.map(|gain| gain * elevation))
+    }
+
+    /// Slowly varying drift load
+    pub fn drift(&self, time: Time) -> Load {
+        let (cos, sin) = self.sea.envelope(time, self.depth);
+        Load::from_array(self.drift.map(|coefficient| coefficient * (cos * cos + sin * sin)))
+    }
+
+    /// Mean of the drift load
+    pub fn mean_drift(&self) -> Load {
+        let d = self.depth.value().max(0.0);
+        let energy: f64 =
+            self.sea.components.iter().map(|c| (c.amplitude.value() * (-c.wave_number() * d).exp()).powi(2)).sum();
+        Load::from_array(self.drift.map(|coefficient| coefficient * energy))
+    }
+}
+
+impl Disturbance for WaveDisturbance {
+    fn load(&mut self, time: Time) -> Load {
+        self.first_order(time) + self.drift(time)
+    }
+}
+
+/// Mean current with a first-order Gauss-Markov gust, loading through quadratic drag
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentGust {
+    mean: [f64; 3],
+    /// Stationary standard deviation of the gust on each axis
+    deviation: f64,
+    time_constant: f64,
+    /// Force per squared speed on each axis (N·s²/m²)
+    drag: [f64; 3],
+    gust: [f64; 3],
+    last: Option<f64>,
+    sampler: Sampler,
+}
+
+impl CurrentGust {
+    /// Current around `mean`; the gust starts at zero and is drawn from `seed`
+    pub fn new(mean: [Velocity; 3], deviation: Velocity, time_constant: Time, seed: u64) -> Self {
+        Self {
+            mean: mean.map(|v| *v.value()),
+            deviation: deviation.value().abs(),
+            time_constant: *time_constant.value(),
+            drag: [0.0; 3],
+            gust: [0.0; 3],
+            last: None,
+            sampler: Sampler::new(seed),
+        }
+    }
+
+    pub fn with_drag(mut self, coefficients: [f64; 3]) -> Self {
+        self.drag = coefficients;
+        self
+    }
+
+    /// Current velocity at the most recent sample
+    pub fn velocity(&self) -> [Velocity; 3] {
+        std::array::from_fn(|i| Velocity::new(self.mean[i] + self.gust[i]))
+    }
+
+    /// Advance the gust to `time` by the exact discretization of the process
+    pub fn advance(&mut self, time: Time) -> [Velocity; 3] {
+        let time = *time.value();
+        let dt = self.last.map_or(0.0, |last| (time - last).max(0.0));
+        self.last = Some(self.last.map_or(time, |last| last.max(time)));
+        if dt > 0.0 {
+            let decay = if self.time_constant > 0.0 { (-dt / self.time_constant).exp() } else { 0.0 };
+            let spread = self.deviation * (1.0 - decay * decay).sqrt();
+            for gust in &mut self.gust {
+                *gust = decay * *gust + spread * self.sampler.normal();
+            }
+        }
+        self.velocity()
+    }
+}
+
+impl Disturbance for CurrentGust {
+    fn load(&mut self, time: Time) -> Load {
+        let velocity = self.advance(time).map(|v| *v.value());
+        let force: [f64; 3] = std::array::from_fn(|i| self.drag[i] * velocity[i] * velocity[i].abs());
+        Load::from_array([0.0, 0.0, 0.0, force[0], force[1], force[2]])
+    }
+}
+
+/// Several disturbances acting together
+#[derive(Default)]
+pub struct Disturbances {
+    members: Vec<Box<dyn Disturbance>>,
+}
+
+impl Disturbances {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, disturbance: impl Disturbance + 'static) -> Self {
+        self.members.push(Box::new(disturbance));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+impl Disturbance for Disturbances {
+    fn load(&mut self, time: Time) -> Load {
+        self.members.iter_mut().fold(Load::default(), |total, member| total + member.load(time))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sea(seed: u64) -> SeaState {
+        Jonswap::new(Length::new(2.0), Time::new(8.0)).realize(200, &mut Sampler::new(seed))
+    }
+
+    #[test]
+    fn test_jonswap_peak_and_significant_height() {
+        let spectrum = Jonswap::new(Length::new(2.0), Time::new(8.0));
+        let peak = *spectrum.peak_frequency().value();
+        let density = |omega: f64| spectrum.density(AngularVelocity::new(omega));
+        assert!(density(peak) > density(0.9 * peak) && density(peak) > density(1.1 * peak));
+        assert!(density(peak) > Jonswap::pierson_moskowitz(Length::new(2.0), Time::new(8.0)).density(AngularVelocity::new(peak)));
+        assert_eq!(density(0.0), 0.0);
+
+        let realized = sea(7);
+        assert!((realized.significant_height().value() - 2.0).abs() < 0.15, "{:?}", realized.significant_height());
+        assert_eq!(realized, sea(7));
+        assert_ne!(realized, sea(8));
+
+        // The elevation record has the variance of the spectrum
+        let samples: Vec<f64> = (0..20000).map(|i| *realized.elevation(Time::new(0.25 * i as f64)).value()).collect();
+        let variance = samples.iter().map(|e| e * e).sum::<f64>() / samples.len() as f64;
+        assert!((4.0 * variance.sqrt() - realized.significant_height().value()).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_wave_loads_fade_with_depth() {
+        let gains = [0.0, 0.0, 0.0, 800.0, 0.0, 1200.0];
+        let shallow = WaveDisturbance::new(sea(3), Length::new(2.0)).with_first_order(gains).with_drift([0.0, 0.0, 0.0, 50.0, 0.0, 0.0]);
+        let mut deep = shallow.clone();
+        deep.set_depth(Length::new(60.0));
+
+        let rms = |waves: &WaveDisturbance| {
+            let sum: f64 = (0..4000).map(|i| waves.first_order(Time::new(0.25 * i as f64)).force[2].value().powi(2)).sum();
+            (sum / 4000.0).sqrt()
+        };
+        assert!(rms(&shallow) > 10.0 * rms(&deep), "{} {}", rms(&shallow), rms(&deep));
+
+        // Drift pushes one way, averaging to its mean
+        let mean = *shallow.mean_drift().force[0].value();
+        let average: f64 = (0..20000).map(|i| *shallow.drift(Time::new(0.25 * i as f64)).force[0].value()).sum::<f64>() / 20000.0;
+        assert!(mean > 0.0 && (average - mean).abs() < 0.15 * mean, "{} {}", average, mean);
+        assert!(*deep.mean_drift().force[0].value() < 0.1 * mean);
+        assert_eq!(shallow.first_order(Time::new(1.0)).torque, [Torque::new(0.0); 3]);
+    }
+
+    #[test]
+    fn test_current_gust_statistics_and_combination() {
+        let mean = [Velocity::new(0.5), Velocity::new(0.0), Velocity::new(0.0)];
+        let mut gust = CurrentGust::new(mean, Velocity::new(0.2), Time::new(5.0), 11).with_drag([100.0, 100.0, 0.0]);
+        let speeds: Vec<[f64; 3]> = (0..50000).map(|i| gust.advance(Time::new(0.5 * i as f64)).map(|v| *v.value())).collect();
+        let average = speeds.iter().map(|v| v[0]).sum::<f64>() / speeds.len() as f64;
+        let deviation = (speeds.iter().map(|v| (v[1]).powi(2)).sum::<f64>() / speeds.len() as f64).sqrt();
+        assert!((average - 0.5).abs() < 0.02 && (deviation - 0.2).abs() < 0.02, "{} {}", average, deviation);
+
+        // Repeating a time leaves the gust unchanged
+        let velocity = gust.velocity();
+        assert_eq!(gust.advance(Time::new(0.0)), velocity);
+
+        let steady = Load::from_array([0.0, 0.0, 1.0, 0.0, 0.0, -20.0]);
+        let mut total = Disturbances::new().with(steady).with(CurrentGust::new(mean, Velocity::new(0.0), Time::new(5.0), 1).with_drag([100.0, 0.0, 0.0]));
+        assert_eq!(total.len(), 2);
+        let load = total.load(Time::new(0.0));
+        assert_eq!(load.to_array(), [0.0, 0.0, 1.0, 25.0, 0.0, -20.0]);
+        assert_eq!(load.to_wrench(), Wrench::new([25.0, 0.0, -20.0], [0.0, 0.0, 1.0]));
+    }
+}
@@ -0,0 +1,442 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Rigid-body mass properties and rotational dynamics
+//!
+//! [`Inertia`] is the symmetric map taking an angular-velocity bivector to the
+//! angular-momentum bivector, with entries in kg·m². It is stored about the
+//! body's center of mass together with the mass and the center's position, so
+//! moving it to another frame is a rotation by a [`Motor`] and moving it to
+//! another point is the parallel-axis theorem. Bodies in a common frame add
+//! into a rigid union, and a [`CompositeBody`] keeps named parts so a payload
+//! can be picked up or dropped without recomputing the rest.
+//!
+//! Bivectors use the GAFRO layout of [`Bivector`]; the matrices below act on
+//! the dual rotation vectors in the same frame.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::linalg::{self, square, Matrix3, Matrix6, Vector3};
+use crate::motor::{Bivector, Motor, MotorGenerator, Rotor};
+use crate::si_units::{Energy, Length, Mass, MomentOfInertia};
+
+/// Mass properties that cannot belong to a physical body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InertiaError {
+    NonPositiveMass(f64),
+    NotSymmetric,
+    /// A principal moment is negative or exceeds the sum of the other two
+    NotPhysical([f64; 3]),
+}
+
+impl fmt::Display for InertiaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InertiaError::NonPositiveMass(mass) => write!(f, "mass {} kg is not positive", mass),
+            InertiaError::NotSymmetric => write!(f, "inertia tensor is not symmetric"),
+            InertiaError::NotPhysical(moments) => {
+                write!(f, "principal moments {:?} kg·m² violate the triangle inequality", moments)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InertiaError {}
+
+/// Principal moments in ascending order and the frame of the principal axes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrincipalAxes {
+    pub moments: [MomentOfInertia; 3],
+    /// Maps the principal frame (axes along the moments, origin at the center of
+    /// mass) into the frame the inertia is expressed in
+    pub frame: Motor,
+}
+
+/// Mass, center of mass and rotational inertia of a rigid body
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Inertia {
+    mass: Mass,
+    center: Vector3,
+    /// Tensor about the center of mass, in kg·m²
+    central: Matrix3,
+}
+
+impl Inertia {
+    /// Body with the given tensor about its center of mass
+    pub fn new(mass: Mass, center: Vector3, central: Matrix3) -> Result<Self, InertiaError> {
+        if mass.value().is_nan() || *mass.value() <= 0.0 {
+            return Err(InertiaError::NonPositiveMass(*mass.value()));
+        }
+        let scale = central.iter().flatten().fold(0.0f64, |m, x| m.max(x.abs())).max(f64::MIN_POSITIVE);
+        let tolerance = 1e-9 * scale;
+        if (0..3).any(|i| (0..i).any(|j| (central[i][j] - central[j][i]).abs() > tolerance)) {
+            return Err(InertiaError::NotSymmetric);
+        }
+        let (moments, _) = square::symmetric_eigen(&central);
+        if moments[0] < -tolerance || moments[2] > moments[0] + moments[1] + tolerance {
+            return Err(InertiaError::NotPhysical(moments));
+        }
+        Ok(Self { mass, center, central })
+    }
+
+    /// Principal moments `(ixx, iyy, izz)` along the frame axes at `center`
+    pub fn from_principal(mass: Mass, center: Vector3, moments: [MomentOfInertia; 3]) -> Result<Self, InertiaError> {
+        let mut central = [[0.0; 3]; 3];
+        for (i, moment) in moments.iter().enumerate() {
+            central[i][i] = *moment.value();
+        }
+        Self::new(mass, center, central)
+    }
+
+    /// Point mass; its tensor about the center vanishes
+    pub fn point_mass(mass: Mass, position: Vector3) -> Self {
+        assert!(*mass.value() > 0.0, "mass must be positive");
+        Self { mass, center: position, central: [[0.0; 3]; 3] }
+    }
+
+    /// Uniform box centered at the origin with edge lengths along x, y and z
+    pub fn solid_box(mass: Mass, size: [Length; 3]) -> Self {
+        let [x, y, z] = size.map(|edge| edge.value().powi(2));
+        let k = mass.value() / 12.0;
+        Self::diagonal(mass, [k * (y + z), k * (x + z), k * (x + y)])
+    }
+
+    /// Uniform cylinder centered at the origin with its axis along z
+    pub fn solid_cylinder(mass: Mass, radius: Length, length: Length) -> Self {
+        let m = *mass.value();
+        let r2 = radius.value().powi(2);
+        let across = m * (3.0 * r2 + length.value().powi(2)) / 12.0;
+        Self::diagonal(mass, [across, across, 0.5 * m * r2])
+    }
+
+    pub fn solid_sphere(mass: Mass, radius: Length) -> Self {
+        let moment = 0.4 * mass.value() * radius.value().powi(2);
+        Self::diagonal(mass, [moment; 3])
+    }
+
+    fn diagonal(mass: Mass, moments: [f64; 3]) -> Self {
+        assert!(*mass.value() > 0.0, "mass must be positive");
+        let mut central = [[0.0; 3]; 3];
+        for i in 0..3 {
+            central[i][i] = moments[i];
+        }
+        Self { mass, center: [0.0; 3], central }
+    }
+
+    pub fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    pub fn center_of_mass(&self) -> Vector3 {
+        self.center
+    }
+
+    /// Tensor about the center of mass
+    pub fn central_tensor(&self) -> Matrix3 {
+        self.central
+    }
+
+    /// Tensor about `point` by the parallel-axis theorem
+    pub fn tensor_about(&self, point: Vector3) -> Matrix3 {
+        let d = linalg::sub(self.center, point);
+        let m = *self.mass.value();
+        let d2 = linalg::dot(d, d);
+        std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.central[i][j] + m * (if i == j { d2 } else { 0.0 } - d[i] * d[j]))
+        })
+    }
+
+    /// 6×6 spatial inertia about `point`, in the (angular, linear) order of
+    /// [`MotorGenerator`]: `½ ξᵀ M ξ` is the kinetic energy of a twist `ξ` of `point`
+    pub fn spatial_about(&self, point: Vector3) -> Matrix6 {
+        let m = *self.mass.value();
+        let tensor = self.tensor_about(point);
+        let coupling = linalg::skew(linalg::scale(linalg::sub(self.center, point), m));
+        std::array::from_fn(|i| {
+            std::array::from_fn(|j| match (i < 3, j < 3) {
+                (true, true) => tensor[i][j],
+                (true, false) => coupling[i][j - 3],
+                (false, true) => -coupling[i - 3][j],
+                (false, false) => if i == j { m } else { 0.0 },
+            })
+        })
+    }
+
+    /// Moment about the axis through `point` along the unit `direction`
+    pub fn moment_about_axis(&self, point: Vector3, direction: Vector3) -> MomentOfInertia {
+        MomentOfInertia::new(square::quadratic_form(&self.tensor_about(point), &direction))
+    }
+
+    /// The same body described in the frame `motor` maps into
+    pub fn transform(&self, motor: &Motor) -> Self {
+        let r = motor.rotor.to_rotation_matrix();
+        Self {
+            mass: self.mass,
+            center: motor.apply_point(self.center),
+            central: linalg::mat3_mul(&linalg::mat3_mul(&r, &self.central), &linalg::mat3_transpose(&r)),
+        }
+    }
+
+    /// Principal moments and axes about the center of mass
+    pub fn principal_axes(&self) -> PrincipalAxes {
+        let (moments, mut axes) = square::symmetric_eigen(&self.central);
+        let column = |axes: &Matrix3, j: usize| [axes[0][j], axes[1][j], axes[2][j]];
+        // Keep the axes right-handed so they form a rotation
+        if linalg::dot(column(&axes, 0), linalg::cross(column(&axes, 1), column(&axes, 2))) < 0.0 {
+            for row in &mut axes {
+                row[2] = -row[2];
+            }
+        }
+        PrincipalAxes {
+            moments: moments.map(MomentOfInertia::new),
+            frame: Motor::new(self.center, Rotor::from_rotation_matrix(&axes)),
+        }
+    }
+
+    /// Angular momentum about the center of mass for the angular velocity `omega`
+    pub fn apply(&self, omega: Bivector) -> Bivector {
+        Bivector::from_rotation_vector(linalg::mat3_vec(&self.central, omega.rotation_vector()))
+    }
+
+    /// Angular acceleration under `torque` about the center of mass, from
+    /// Euler's equations `I α = τ - ω × I ω` in the frame of the inertia
+    pub fn angular_acceleration(&self, omega: Bivector, torque: Bivector) -> Bivector {
+        let w = omega.rotation_vector();
+        let gyroscopic = linalg::cross(w, linalg::mat3_vec(&self.central, w));
+        let net = linalg::sub(torque.rotation_vector(), gyroscopic);
+        let alpha = match square::inverse(&self.central) {
+            Some(inverse) => linalg::mat3_vec(&inverse, net),
+            None => [0.0; 3],
+        };
+        Bivector::from_rotation_vector(alpha)
+    }
+
+    /// Kinetic energy for a twist of the frame origin (angular velocity, then
+    /// the velocity of the origin)
+    pub fn kinetic_energy(&self, twist: &MotorGenerator) -> Energy {
+        let w = twist.rotation;
+        let velocity = linalg::add(twist.translation, linalg::cross(w, self.center));
+        let rotational = square::quadratic_form(&self.central, &w);
+        Energy::new(0.5 * (self.mass.value() * linalg::dot(velocity, velocity) + rotational))
+    }
+}
+
+impl std::ops::Add for Inertia {
+    type Output = Inertia;
+
+    /// Rigid union of two bodies expressed in the same frame
+    fn add(self, rhs: Inertia) -> Inertia {
+        let mut sum = MassMoments::of(&self);
+        sum.accumulate(&rhs, 1.0);
+        sum.inertia().expect("sum of positive masses")
+    }
+}
+
+/// Mass, first moment and tensor about the origin, which add across bodies
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct MassMoments {
+    mass: f64,
+    first: Vector3,
+    second: Matrix3,
+}
+
+impl MassMoments {
+    fn of(body: &Inertia) -> Self {
+        let mut moments = Self::default();
+        moments.accumulate(body, 1.0);
+        moments
+    }
+
+    /// Add (`sign = 1`) or remove (`sign = -1`) a body
+    fn accumulate(&mut self, body: &Inertia, sign: f64) {
+        let m = sign * body.mass.value();
+        self.mass += m;
+        self.first = linalg::add(self.first, linalg::scale(body.center, m));
+        self.second = square::add(&self.second, &body.tensor_about([0.0; 3]).map(|row| row.map(|x| sign * x)));
+    }
+
+    fn inertia(&self) -> Option<Inertia> {
+        if self.mass <= 0.0 {
+            return None;
+        }
+        let center = linalg::scale(self.first, 1.0 / self.mass);
+        let shifted = Inertia { mass: Mass::new(self.mass), center, central: [[0.0; 3]; 3] }.tensor_about([0.0; 3]);
+        let central = std::array::from_fn(|i| std::array::from_fn(|j| self.second[i][j] - shifted[i][j]));
+        Some(Inertia { mass: Mass::new(self.mass), center, central })
+    }
+}
+
+/// Named parts combined into one rigid body
+///
+/// Parts are expressed in the composite's frame; a payload held by a gripper is
+/// transformed by the gripper pose before it is inserted. Adding or removing a
+/// part updates the totals without revisiting the other parts.
+#[derive(Debug, Clone, Default)]
+pub struct CompositeBody {
+    parts: Vec<(String, Inertia)>,
+    moments: MassMoments,
+}
+
+impl CompositeBody {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a part, returning the part it replaces under the same name
+    pub fn insert(&mut self, name: &str, part: Inertia) -> Option<Inertia> {
+        let replaced = self.remove(name);
+        self.moments.accumulate(&part, 1.0);
+        self.parts.push((name.into(), part));
+        replaced
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Inertia> {
+        let index = self.parts.iter().position(|(part, _)| part == name)?;
+        let (_, part) = self.parts.remove(index);
+        if self.parts.is_empty() {
+            // Start over exactly rather than keep round-off
+            self.moments = MassMoments::default();
+        } else {
+            self.moments.accumulate(&part, -1.0);
+        }
+        Some(part)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Inertia> {
+        self.parts.iter().find(|(part, _)| part == name).map(|(_, inertia)| inertia)
+    }
+
+    pub fn parts(&self) -> impl Iterator<Item = (&str, &Inertia)> + '_ {
+        self.parts.iter().map(|(name, inertia)| (name.as_str(), inertia))
+    }
+
+    pub fn mass(&self) -> Mass {
+        Mass::new(self.moments.mass)
+    }
+
+    /// Center of mass; `None` while the body is empty
+    pub fn center_of_mass(&self) -> Option<[Length; 3]> {
+        self.inertia().map(|inertia| inertia.center.map(Length::new))
+    }
+
+    /// Combined mass properties; `None` while the body is empty
+    pub fn inertia(&self) -> Option<Inertia> {
+        self.moments.inertia()
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::TAU;
+
+    fn assert_matrix_eq(a: &Matrix3, b: &Matrix3) {
+        for (ra, rb) in a.iter().zip(b) {
+            for (x, y) in ra.iter().zip(rb) {
+                assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_axis_and_transform() {
+        // Thin rod along x: mL²/12 about its center, mL²/3 about an end
+        let rod = Inertia::solid_cylinder(Mass::new(3.0), Length::new(0.0), Length::new(2.0))
+            .transform(&Motor::from_rotor(Rotor::from_axis_angle([0.0, 1.0, 0.0], TAU / 4.0)));
+        assert!((rod.moment_about_axis([0.0; 3], [0.0, 0.0, 1.0]).value() - 1.0).abs() < 1e-12);
+        assert!((rod.moment_about_axis([1.0, 0.0, 0.0], [0.0, 0.0, 1.0]).value() - 4.0).abs() < 1e-12);
+        assert!(rod.moment_about_axis([0.0; 3], [1.0, 0.0, 0.0]).value().abs() < 1e-12);
+
+        let motor = Motor::new([0.5, -1.0, 2.0], Rotor::from_axis_angle([1.0, 2.0, 3.0], 0.7));
+        let body = Inertia::solid_box(Mass::new(2.0), [Length::new(0.3), Length::new(0.5), Length::new(1.0)]);
+        let back = body.transform(&motor).transform(&motor.inverse());
+        assert_matrix_eq(&back.central_tensor(), &body.central_tensor());
+        assert!(linalg::norm(back.center_of_mass()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_spatial_inertia_gives_kinetic_energy() {
+        let motor = Motor::new([0.5, -1.0, 2.0], Rotor::from_axis_angle([1.0, 2.0, 3.0], 0.7));
+        let body = Inertia::solid_box(Mass::new(2.0), [Length::new(0.3), Length::new(0.5), Length::new(1.0)]).transform(&motor);
+        let spatial = body.spatial_about([0.0; 3]);
+        assert_eq!(spatial, square::transpose(&spatial));
+        let twist = MotorGenerator::new([0.3, -1.2, 0.8], [1.5, 0.2, -0.7]);
+        let energy = 0.5 * square::quadratic_form(&spatial, &twist.to_array());
+        assert!((energy - body.kinetic_energy(&twist).value()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_principal_axes() {
+        let moments = [1.0, 2.0, 2.5].map(MomentOfInertia::new);
+        let body = Inertia::from_principal(Mass::new(4.0), [0.0; 3], moments).unwrap();
+        let motor = Motor::new([1.0, 0.0, 0.0], Rotor::from_axis_angle([0.0, 0.0, 1.0], 0.4));
+        let principal = body.transform(&motor).principal_axes();
+        for (found, expected) in principal.moments.iter().zip(moments) {
+            assert!((found.value() - expected.value()).abs() < 1e-9);
+        }
+        // The principal frame diagonalizes the tensor again
+        let recovered = body.transform(&motor).transform(&principal.frame.inverse());
+        let diagonal = [[1.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.5]];
+        assert_matrix_eq(&recovered.central_tensor(), &diagonal);
+
+        assert_eq!(
+            Inertia::from_principal(Mass::new(1.0), [0.0; 3], [1.0, 1.0, 3.0].map(MomentOfInertia::new)),
+            Err(InertiaError::NotPhysical([1.0, 1.0, 3.0]))
+        );
+        assert_eq!(Inertia::new(Mass::new(0.0), [0.0; 3], [[0.0; 3]; 3]), Err(InertiaError::NonPositiveMass(0.0)));
+    }
+
+    #[test]
+    fn test_torque_free_rotation_conserves_energy() {
+        let body = Inertia::solid_box(Mass::new(10.0), [Length::new(0.2), Length::new(0.6), Length::new(1.0)]);
+        let mut omega = Bivector::from_rotation_vector([0.1, 2.0, 0.1]);
+        let energy = |omega: Bivector| *body.kinetic_energy(&MotorGenerator::new(omega.rotation_vector(), [0.0; 3])).value();
+        let momentum = |omega: Bivector| body.apply(omega).norm();
+        let (e0, l0) = (energy(omega), momentum(omega));
+        // Midpoint steps over 2 s
+        let dt = 1e-4;
+        for _ in 0..20000 {
+            let k1 = body.angular_acceleration(omega, Bivector::default()).rotation_vector();
+            let half = Bivector::from_rotation_vector(linalg::add(omega.rotation_vector(), linalg::scale(k1, 0.5 * dt)));
+            let k2 = body.angular_acceleration(half, Bivector::default()).rotation_vector();
+            omega = Bivector::from_rotation_vector(linalg::add(omega.rotation_vector(), linalg::scale(k2, dt)));
+        }
+        assert!((energy(omega) - e0).abs() < 1e-6 * e0);
+        assert!((momentum(omega) - l0).abs() < 1e-6 * l0);
+    }
+
+    #[test]
+    fn test_composite_body() {
+        // Two spheres on the x axis combine into a dumbbell
+        let sphere = |x: f64| Inertia::solid_sphere(Mass::new(1.0), Length::new(0.1)).transform(&Motor::from_translation([x, 0.0, 0.0]));
+        let dumbbell = sphere(-0.5) + sphere(0.5);
+        assert!(linalg::norm(dumbbell.center_of_mass()) < 1e-12);
+        let expected = 2.0 * (0.004 + 0.25);
+        assert!((dumbbell.moment_about_axis([0.0; 3], [0.0, 0.0, 1.0]).value() - expected).abs() < 1e-12);
+
+        // A vehicle picks up and releases a payload in its gripper
+        let mut vehicle = CompositeBody::new();
+        assert_eq!(vehicle.center_of_mass(), None);
+        let hull = Inertia::solid_box(Mass::new(30.0), [Length::new(1.0), Length::new(0.6), Length::new(0.4)]);
+        vehicle.insert("hull", hull);
+        let gripper = Motor::new([0.6, 0.0, -0.3], Rotor::from_axis_angle([0.0, 1.0, 0.0], 0.3));
+        let payload = Inertia::solid_cylinder(Mass::new(2.0), Length::new(0.05), Length::new(0.3)).transform(&gripper);
+        assert_eq!(vehicle.insert("payload", payload), None);
+
+        assert_eq!(*vehicle.mass().value(), 32.0);
+        let [x, _, z] = vehicle.center_of_mass().unwrap();
+        assert!((x.value() - 1.2 / 32.0).abs() < 1e-12 && (z.value() + 0.6 / 32.0).abs() < 1e-12);
+        let combined = vehicle.inertia().unwrap();
+        assert_matrix_eq(&combined.central_tensor(), &(hull + payload).central_tensor());
+
+        assert_eq!(vehicle.remove("payload"), Some(payload));
+        assert_matrix_eq(&vehicle.inertia().unwrap().central_tensor(), &hull.central_tensor());
+        assert_eq!(vehicle.parts().map(|(name, _)| name).collect::<Vec<_>>(), ["hull"]);
+        vehicle.remove("hull");
+        assert_eq!((*vehicle.mass().value(), vehicle.inertia()), (0.0, None));
+    }
+}
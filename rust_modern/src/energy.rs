@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Battery state of charge and power budgets
+//!
+//! Fractions here (state of charge, conversion efficiency, duty cycle) are
+//! [`Ratio`]s, so a percentage cannot be passed where a fraction is meant. A
+//! [`Battery`] tracks the energy it holds; loads are drawn through a discharge
+//! efficiency and charging is stored through a charge efficiency. Thruster
+//! loads come from [`crate::propulsion`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::si_units::{Energy, Length, Power, Ratio, Time, Velocity};
+
+/// Mean power of a load drawing `peak` for the `duty` fraction of the time
+pub fn average_power(peak: Power, duty: Ratio) -> Power {
+    duty.of(peak)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Battery {
+    pub capacity: Energy,
+    state_of_charge: Ratio,
+    /// Delivered over drawn energy while discharging
+    pub discharge_efficiency: Ratio,
+    /// Stored over supplied energy while charging
+    pub charge_efficiency: Ratio,
+}
+
+impl Battery {
+    /// Lossless battery at the given state of charge
+    pub fn new(capacity: Energy, state_of_charge: Ratio) -> Self {
+        assert!(*capacity.value() > 0.0, "battery capacity must be positive");
+        Self { capacity, state_of_charge, discharge_efficiency: Ratio::ONE, charge_efficiency: Ratio::ONE }
+    }
+
+    pub fn with_efficiencies(mut self, discharge: Ratio, charge: Ratio) -> Self {
+        self.discharge_efficiency = discharge;
+        self.charge_efficiency = charge;
+        self
+    }
+
+    pub fn state_of_charge(&self) -> Ratio {
+        self.state_of_charge
+    }
+
+    pub fn stored(&self) -> Energy {
+        self.state_of_charge.of(self.capacity)
+    }
+
+    pub fn is_depleted(&self) -> bool {
+        self.state_of_charge == Ratio::ZERO
+    }
+
+    /// Supply `load` for `dt`; returns the energy actually delivered, which
+    /// falls short once the battery runs empty
+    pub fn discharge(&mut self, load: Power, dt: Time) -> Energy {
+        let demanded = load.value().max(0.0) * dt.value().max(0.0);
+        let efficiency = self.discharge_efficiency.value();
+        if efficiency == 0.0 {
+            return Energy::new(0.0);
+        }
+        let stored = *self.stored().value();
+        let drawn = (demanded / efficiency).min(stored);
+        self.state_of_charge = Ratio::saturating((stored - drawn) / self.capacity.value());
+        Energy::new(drawn * efficiency)
+    }
+
+    /// Charge from `supply` for `dt`; returns the energy stored
+    pub fn charge(&mut self, supply: Power, dt: Time) -> Energy {
+        let stored = *self.stored().value();
+        let added = self.charge_efficiency.value() * supply.value().max(0.0) * dt.value().max(0.0);
+        let added = added.min(self.capacity.value() - stored);
+        self.state_of_charge = Ratio::saturating((stored + added) / self.capacity.value());
+        Energy::new(added)
+    }
+
+    /// Time until empty at a constant `load`; `None` for a non-positive load
+    pub fn endurance(&self, load: Power) -> Option<Time> {
+        (*load.value() > 0.0)
+            .then(|| Time::new(self.stored().value() * self.discharge_efficiency.value() / load.value()))
+    }
+
+    /// Distance covered at `speed` before running empty under `load`
+    pub fn range(&self, load: Power, speed: Velocity) -> Option<Length> {
+        self.endurance(load).map(|time| Length::new(time.value() * speed.value().abs()))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discharge_through_efficiency() {
+        // 1 kWh pack at 80 %, 90 % efficient
+        let efficiency = Ratio::new(0.9).unwrap();
+        let mut battery = Battery::new(Energy::new(3.6e6), Ratio::from_percent(80.0).unwrap())
+            .with_efficiencies(efficiency, efficiency);
+        let endurance = battery.endurance(Power::new(360.0)).unwrap();
+        assert!((endurance.value() - 7200.0).abs() < 1e-9);
+
+        // A thruster at 400 W peak running a quarter of the time
+        let load = average_power(Power::new(400.0), Ratio::new(0.25).unwrap());
+        let delivered = battery.discharge(load, Time::new(3600.0));
+        assert!((delivered.value() - 3.6e5).abs() < 1e-6);
+        assert!((battery.state_of_charge().value() - (0.8 - 0.1 / 0.9)).abs() < 1e-12);
+        assert_eq!(battery.endurance(Power::new(0.0)), None);
+    }
+
+    #[test]
+    fn test_runs_empty_and_recharges() {
+        let mut battery = Battery::new(Energy::new(1000.0), Ratio::new(0.5).unwrap());
+        let delivered = battery.discharge(Power::new(100.0), Time::new(10.0));
+        assert_eq!(*delivered.value(), 500.0);
+        assert!(battery.is_depleted());
+
+        let stored = battery.charge(Power::new(100.0), Time::new(20.0));
+        assert_eq!(*stored.value(), 1000.0);
+        assert_eq!(battery.state_of_charge(), Ratio::ONE);
+    }
+}
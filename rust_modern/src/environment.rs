@@ -0,0 +1,265 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Build and floating-point environment of a result
+//!
+//! When C++ and Rust, or two machines, disagree in the last bits, the first
+//! questions are which compiler, target and features produced each number and
+//! whether the floating-point environment was the same. [`build_info`] answers
+//! them, and every [`crate::bench::Report`] embeds it.
+//!
+//! The floating-point flags are probed at run time rather than assumed: fused
+//! multiply-add is a compile-time target feature, but flush-to-zero and the
+//! rounding mode are state a host program or a library can change under us.
+//!
+//! [`capabilities`] lists the optional subsystems compiled in, so callers can
+//! check for one before relying on it.
+
+use std::fmt;
+use std::hint::black_box;
+
+use serde::{Deserialize, Serialize};
+
+use crate::determinism::{determinism_mode, Rounding};
+
+/// Cargo features this build was compiled with
+const FEATURES: &[(&str, bool)] = &[
+    ("compensated-summation", cfg!(feature = "compensated-summation")),
+    ("versor-drift-check", cfg!(feature = "versor-drift-check")),
+    ("mesh-import", cfg!(feature = "mesh-import")),
+    ("nmea", cfg!(feature = "nmea")),
+    ("mavlink", cfg!(feature = "mavlink")),
+    ("las-export", cfg!(feature = "las-export")),
+    ("fast-math", cfg!(feature = "fast-math")),
+    ("private-grade-indexed", cfg!(feature = "private-grade-indexed")),
+    ("gpu", cfg!(feature = "gpu")),
+    ("ndarray", cfg!(feature = "ndarray")),
+];
+
+/// Floating-point behavior that can change results between machines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FloatEnvironment {
+    /// The target allows fused multiply-add, so `mul_add` and auto-fusion round once
+    pub fma: bool,
+    /// Subnormal results are flushed to zero (FTZ/DAZ set by the host)
+    pub flush_denormals: bool,
+    /// `nearest`, `toward_zero`, `upward` or `downward`
+    pub rounding: String,
+    /// Quantization of nonlinear results on this thread, see [`crate::determinism`]
+    pub determinism: Option<String>,
+}
+
+impl FloatEnvironment {
+    /// Probe the environment of the calling thread
+    pub fn current() -> Self {
+        let tiny = black_box(f64::MIN_POSITIVE);
+        let flush_denormals = black_box(tiny * black_box(0.5)) == 0.0;
+
+        // 1 + 0.75 ulp and -(1 + 0.75 ulp) tell the four IEEE rounding modes apart
+        let nudge = black_box(0.75 * f64::EPSILON);
+        let up = black_box(1.0) + nudge;
+        let down = black_box(-1.0) - nudge;
+        let rounding = match (up > 1.0 + 0.5 * f64::EPSILON, down < -1.0 - 0.5 * f64::EPSILON) {
+            (true, true) => "nearest",
+            (false, false) => "toward_zero",
+            (true, false) => "upward",
+            (false, true) => "downward",
+        };
+
+        let determinism = determinism_mode().map(|mode| match mode {
+            Rounding::DecimalPlaces(d) => format!("{} decimal places", d),
+            Rounding::MantissaBits(n) => format!("{} mantissa bits", n),
+        });
+
+        Self { fma: cfg!(target_feature = "fma"), flush_denormals, rounding: rounding.to_string(), determinism }
+    }
+}
+
+/// Versions, features and floating-point environment of this build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub crate_version: String,
+    pub features: Vec<String>,
+    pub target: String,
+    pub rustc: String,
+    /// `debug` or `release`
+    pub profile: String,
+    pub float: FloatEnvironment,
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "gafro_modern {} ({}, {})", self.crate_version, self.target, self.profile)?;
+        writeln!(f, "{}", self.rustc)?;
+        let features = if self.features.is_empty() { "none".to_string() } else { self.features.join(", ") };
+        writeln!(f, "features: {}", features)?;
+        write!(
+            f,
+            "float: fma {}, flush denormals {}, rounding {}, determinism {}",
+            self.float.fma,
+            self.float.flush_denormals,
+            self.float.rounding,
+            self.float.determinism.as_deref().unwrap_or("off")
+        )
+    }
+}
+
+/// Optional subsystem and whether this build provides it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capability {
+    pub name: String,
+    pub available: bool,
+    pub detail: String,
+}
+
+/// Optional subsystems of this build, queried at run time
+///
+/// Downstream code and the test runner branch on these instead of failing to
+/// link or calling into a stub. Subsystems this version does not provide at all
+/// (`nalgebra`, `ros2`) are listed as unavailable, so asking for them is not an
+/// error either.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub entries: Vec<Capability>,
+}
+
+impl Capabilities {
+    pub fn get(&self, name: &str) -> Option<&Capability> {
+        self.entries.iter().find(|c| c.name == name)
+    }
+
+    /// Whether `name` is known and available
+    pub fn has(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|c| c.available)
+    }
+
+    /// Names of the available subsystems
+    pub fn available(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter(|c| c.available).map(|c| c.name.as_str())
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in &self.entries {
+            writeln!(f, "{:<24} {:<3} {}", c.name, if c.available { "yes" } else { "no" }, c.detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// SIMD extensions compiled in, and on x86 those the CPU offers beyond them
+fn simd_capability() -> Capability {
+    let compiled: Vec<&str> = [
+        ("sse2", cfg!(target_feature = "sse2")),
+        ("avx", cfg!(target_feature = "avx")),
+        ("avx2", cfg!(target_feature = "avx2")),
+        ("fma", cfg!(target_feature = "fma")),
+        ("neon", cfg!(target_feature = "neon")),
+        ("simd128", cfg!(target_feature = "simd128")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect();
+    let mut detail = if compiled.is_empty() { "no SIMD target features".to_string() } else { compiled.join(", ") };
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        let unused: Vec<&str> = [
+            ("avx", std::arch::is_x86_feature_detected!("avx")),
+            ("avx2", std::arch::is_x86_feature_detected!("avx2")),
+            ("fma", std::arch::is_x86_feature_detected!("fma")),
+        ]
+        .into_iter()
+        .filter_map(|(name, detected)| (detected && !compiled.contains(&name)).then_some(name))
+        .collect();
+        if !unused.is_empty() {
+            detail.push_str(&format!("; CPU also has {} (build with -C target-cpu=native)", unused.join(", ")));
+        }
+    }
+
+    Capability { name: "simd".to_string(), available: !compiled.is_empty(), detail }
+}
+
+/// Optional subsystems compiled into this build
+pub fn capabilities() -> Capabilities {
+    let capability = |name: &str, available: bool, detail: &str| Capability {
+        name: name.to_string(),
+        available,
+        detail: detail.to_string(),
+    };
+    let feature = |name: &str, detail: &str| {
+        let enabled = FEATURES.iter().any(|(feature, enabled)| *feature == name && *enabled);
+        let detail = if enabled { detail.to_string() } else { format!("enable the `{}` cargo feature", name) };
+        Capability { name: name.to_string(), available: enabled, detail }
+    };
+
+    Capabilities {
+        entries: vec![
+            simd_capability(),
+            capability("wasm", cfg!(target_arch = "wasm32"), "built for wasm32"),
+            feature("fast-math", "f32 profile: kernels with approximate sin/cos and rsqrt"),
+            feature("gpu", "wgpu compute backend; GpuBatch::new() still needs an adapter"),
+            feature("compensated-summation", "compensated summation in norms and batch sums"),
+            feature("versor-drift-check", "debug warnings on rotor drift"),
+            feature("mesh-import", "STL and OBJ parsers"),
+            feature("nmea", "NMEA 0183 parsing"),
+            feature("mavlink", "MAVLink 2 encoding and decoding"),
+            feature("las-export", "LAS 1.2 point cloud export"),
+            feature("ndarray", "ndarray views of coefficients, Jacobians and inertia"),
+            capability("nalgebra", false, "no nalgebra conversions in this version"),
+            capability("ros2", false, "no ROS 2 interfaces in this version"),
+        ],
+    }
+}
+
+/// Build and floating-point environment of the calling thread
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        crate_version: crate::VERSION.to_string(),
+        features: FEATURES.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| name.to_string()).collect(),
+        target: env!("GAFRO_TARGET").to_string(),
+        rustc: env!("GAFRO_RUSTC_VERSION").to_string(),
+        profile: env!("GAFRO_PROFILE").to_string(),
+        float: FloatEnvironment::current(),
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::determinism::with_determinism_mode;
+
+    #[test]
+    fn test_build_info_describes_this_build() {
+        let info = build_info();
+        assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+        assert!(info.target.contains(std::env::consts::ARCH));
+        assert!(info.rustc.starts_with("rustc "));
+        assert_eq!(info.features.contains(&"gpu".to_string()), cfg!(feature = "gpu"));
+        assert_eq!(info.float.rounding, "nearest");
+        assert!(!info.float.flush_denormals);
+        let json: BuildInfo = serde_json::from_str(&serde_json::to_string(&info).unwrap()).unwrap();
+        assert_eq!(json, info);
+    }
+
+    #[test]
+    fn test_determinism_mode_is_reported() {
+        let float = with_determinism_mode(Some(Rounding::DecimalPlaces(9)), FloatEnvironment::current);
+        assert_eq!(float.determinism.as_deref(), Some("9 decimal places"));
+        assert!(build_info().to_string().contains("determinism off"));
+    }
+
+    #[test]
+    fn test_capabilities_follow_features() {
+        let caps = capabilities();
+        assert_eq!(caps.has("fast-math"), cfg!(feature = "fast-math"));
+        assert_eq!(caps.has("gpu"), cfg!(feature = "gpu"));
+        assert_eq!(caps.has("simd"), cfg!(target_feature = "sse2") || cfg!(target_feature = "neon"));
+        assert!(!caps.has("ros2") && caps.get("ros2").is_some());
+        assert!(!caps.has("no-such-subsystem"));
+        assert!(caps.available().all(|name| caps.has(name)));
+    }
+}
@@ -0,0 +1,328 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Euler and Tait-Bryan angles
+//!
+//! Three angles only describe a rotation together with the axes they turn about
+//! ([`AxisSequence`]) and whether those axes move with the body
+//! ([`Convention::Intrinsic`]) or stay fixed in the world
+//! ([`Convention::Extrinsic`]), so [`EulerAngles`] always carries both. Intrinsic
+//! `Z-Y-X` (yaw, pitch, roll) equals extrinsic `X-Y-Z` (roll, pitch, yaw).
+//!
+//! Decomposition uses the quaternion method of Bernardes and Viollet (2022),
+//! which treats all twelve sequences alike. When the middle angle reaches its
+//! singularity the first and last axes coincide and only their sum or
+//! difference is defined; the last angle is then set to zero and a
+//! [`GimbalLock`] warning is returned alongside the angles.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use crate::motor::Rotor;
+use crate::si_units::{Angle, TAU};
+
+/// Middle-angle distance (rad) from the singularity at which gimbal lock is reported
+pub const GIMBAL_LOCK_TOLERANCE: f64 = 1e-6;
+
+/// Order of the rotation axes, first to last
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AxisSequence {
+    // Tait-Bryan: three distinct axes
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+    // Proper Euler: first and last axis repeat
+    XYX,
+    XZX,
+    YXY,
+    YZY,
+    ZXZ,
+    ZYZ,
+}
+
+impl AxisSequence {
+    pub const ALL: [AxisSequence; 12] = [
+        AxisSequence::XYZ,
+        AxisSequence::XZY,
+        AxisSequence::YXZ,
+        AxisSequence::YZX,
+        AxisSequence::ZXY,
+        AxisSequence::ZYX,
+        AxisSequence::XYX,
+        AxisSequence::XZX,
+        AxisSequence::YXY,
+        AxisSequence::YZY,
+        AxisSequence::ZXZ,
+        AxisSequence::ZYZ,
+    ];
+
+    /// Axis indices (0 = x, 1 = y, 2 = z) in application order
+    pub fn axes(&self) -> [usize; 3] {
+        match self {
+            AxisSequence::XYZ => [0, 1, 2],
+            AxisSequence::XZY => [0, 2, 1],
+            AxisSequence::YXZ => [1, 0, 2],
+            AxisSequence::YZX => [1, 2, 0],
+            AxisSequence::ZXY => [2, 0, 1],
+            AxisSequence::ZYX => [2, 1, 0],
+            AxisSequence::XYX => [0, 1, 0],
+            AxisSequence::XZX => [0, 2, 0],
+            AxisSequence::YXY => [1, 0, 1],
+            AxisSequence::YZY => [1, 2, 1],
+            AxisSequence::ZXZ => [2, 0, 2],
+            AxisSequence::ZYZ => [2, 1, 2],
+        }
+    }
+
+    /// Whether the first and last axis are the same (proper Euler angles)
+    pub fn is_proper_euler(&self) -> bool {
+        let [first, _, last] = self.axes();
+        first == last
+    }
+
+    /// The same axes in the opposite order
+    pub fn reversed(&self) -> Self {
+        let [first, middle, last] = self.axes();
+        Self::from_axes([last, middle, first]).expect("reversing a valid sequence")
+    }
+
+    fn from_axes(axes: [usize; 3]) -> Option<Self> {
+        Self::ALL.into_iter().find(|sequence| sequence.axes() == axes)
+    }
+}
+
+impl fmt::Display for AxisSequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self.axes().map(|axis| ["X", "Y", "Z"][axis]);
+        write!(f, "{}-{}-{}", names[0], names[1], names[2])
+    }
+}
+
+/// Whether the axes of later rotations move with the body or stay fixed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Convention {
+    /// Each rotation turns about an axis of the already rotated body frame
+    Intrinsic,
+    /// Each rotation turns about an axis of the fixed world frame
+    Extrinsic,
+}
+
+/// The middle angle sits at a singularity, so the first and last axes coincide
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GimbalLock {
+    /// Distance of the middle angle from the singularity
+    pub margin: Angle,
+}
+
+impl fmt::Display for GimbalLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "gimbal lock: middle angle is {:e} rad from the singularity, last angle fixed to zero",
+            self.margin.value()
+        )
+    }
+}
+
+/// Three rotation angles with the sequence and convention they are applied in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EulerAngles {
+    /// Angles in the order of `sequence`
+    pub angles: [Angle; 3],
+    pub sequence: AxisSequence,
+    pub convention: Convention,
+}
+
+impl EulerAngles {
+    pub fn new(angles: [Angle; 3], sequence: AxisSequence, convention: Convention) -> Self {
+        Self { angles, sequence, convention }
+    }
+
+    /// Roll about x, then pitch about y, then yaw about z, all about world axes
+    pub fn roll_pitch_yaw(roll: Angle, pitch: Angle, yaw: Angle) -> Self {
+        Self::new([roll, pitch, yaw], AxisSequence::XYZ, Convention::Extrinsic)
+    }
+
+    /// Roll, pitch and yaw of a rotor (extrinsic `X-Y-Z`)
+    pub fn rpy_of(rotor: &Rotor) -> ([Angle; 3], Option<GimbalLock>) {
+        let (angles, lock) = Self::from_rotor(rotor, AxisSequence::XYZ, Convention::Extrinsic);
+        (angles.angles, lock)
+    }
+
+    pub fn to_rotor(&self) -> Rotor {
+        let axes = self.sequence.axes();
+        let rotors: [Rotor; 3] = std::array::from_fn(|i| {
+            let mut axis = [0.0; 3];
+            axis[axes[i]] = 1.0;
+            Rotor::from_axis_angle(axis, *self.angles[i].value())
+        });
+        // Body-frame rotations compose left to right, world-frame ones right to left
+        match self.convention {
+            Convention::Intrinsic => rotors[0] * rotors[1] * rotors[2],
+            Convention::Extrinsic => rotors[2] * rotors[1] * rotors[0],
+        }
+    }
+
+    /// Decompose a rotor; the middle angle lies in `[0, τ/2]` for proper Euler
+    /// sequences and `[-τ/4, τ/4]` for Tait-Bryan ones, the others in `(-τ/2, τ/2]`
+    pub fn from_rotor(rotor: &Rotor, sequence: AxisSequence, convention: Convention) -> (Self, Option<GimbalLock>) {
+        // Intrinsic i-j-k with angles (a, b, c) is extrinsic k-j-i with (c, b, a)
+        let extrinsic = match convention {
+            Convention::Extrinsic => sequence,
+            Convention::Intrinsic => sequence.reversed(),
+        };
+        let (mut angles, margin) = extrinsic_angles(rotor, extrinsic);
+        if convention == Convention::Intrinsic {
+            angles.swap(0, 2);
+        }
+        if margin >= GIMBAL_LOCK_TOLERANCE {
+            return (Self::new(angles.map(Angle::new), sequence, convention), None);
+        }
+
+        // Fix the last angle to zero and give the first the remaining rotation
+        let mut locked = Self::new([0.0, angles[1], 0.0].map(Angle::new), sequence, convention);
+        let residual = match convention {
+            Convention::Intrinsic => *rotor * locked.to_rotor().reverse(),
+            Convention::Extrinsic => locked.to_rotor().reverse() * *rotor,
+        };
+        let [w, x, y, z] = residual.normalized().to_quaternion();
+        locked.angles[0] = Angle::new(wrap(2.0 * [x, y, z][sequence.axes()[0]].atan2(w)));
+        (locked, Some(GimbalLock { margin: Angle::new(margin) }))
+    }
+
+    /// The same rotation expressed in another sequence and convention
+    pub fn convert(&self, sequence: AxisSequence, convention: Convention) -> (Self, Option<GimbalLock>) {
+        Self::from_rotor(&self.to_rotor(), sequence, convention)
+    }
+}
+
+impl From<EulerAngles> for Rotor {
+    fn from(angles: EulerAngles) -> Self {
+        angles.to_rotor()
+    }
+}
+
+/// Angles `(a, b, c)` with `R = R_k(c) R_j(b) R_i(a)` for the sequence `i-j-k`,
+/// and the distance of `b` from its singularity
+fn extrinsic_angles(rotor: &Rotor, sequence: AxisSequence) -> ([f64; 3], f64) {
+    let [i, j, k] = sequence.axes();
+    let proper = i == k;
+    // The third axis of the frame, whether or not the sequence uses it
+    let k = if proper { 3 - i - j } else { k };
+    // +1 when (i, j, k) is a cyclic permutation of (x, y, z)
+    let sign = if (i + 1) % 3 == j { 1.0 } else { -1.0 };
+
+    let [w, x, y, z] = rotor.normalized().to_quaternion();
+    let q = [x, y, z];
+    let (a, b, c, d) = if proper {
+        (w, q[i], q[j], q[k] * sign)
+    } else {
+        (w - q[j], q[i] + q[k] * sign, q[j] + w, q[k] * sign - q[i])
+    };
+
+    let mut middle = 2.0 * c.hypot(d).atan2(a.hypot(b));
+    let margin = middle.min(TAU / 2.0 - middle);
+    let half_sum = b.atan2(a);
+    let half_difference = d.atan2(c);
+    let first = half_sum - half_difference;
+    let mut last = half_sum + half_difference;
+    if !proper {
+        last *= sign;
+        middle -= TAU / 4.0;
+    }
+    ([wrap(first), middle, wrap(last)], margin)
+}
+
+/// Wrap an angle into `(-τ/2, τ/2]`
+pub(crate) fn wrap(angle: f64) -> f64 {
+    let wrapped = angle.rem_euclid(TAU);
+    if wrapped > TAU / 2.0 {
+        wrapped - TAU
+    } else {
+        wrapped
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::{self, Vector3};
+
+    fn assert_same_rotation(a: &Rotor, b: &Rotor) {
+        for v in [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]] {
+            let (pa, pb): (Vector3, Vector3) = (a.apply(v), b.apply(v));
+            assert!(linalg::norm(linalg::sub(pa, pb)) < 1e-9, "{:?} != {:?}", pa, pb);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_all_sequences() {
+        let angle_sets: [[f64; 3]; 3] = [[0.3, -0.7, 1.9], [-2.8, 0.2, -0.4], [1.2, 1.4, 3.0]];
+        for sequence in AxisSequence::ALL {
+            for convention in [Convention::Intrinsic, Convention::Extrinsic] {
+                for values in angle_sets {
+                    let mut values = values;
+                    if sequence.is_proper_euler() {
+                        // Proper Euler sequences put the middle angle in [0, τ/2]
+                        values[1] = values[1].abs();
+                    }
+                    let euler = EulerAngles::new(values.map(Angle::new), sequence, convention);
+                    let (back, lock) = EulerAngles::from_rotor(&euler.to_rotor(), sequence, convention);
+
+                    assert!(lock.is_none());
+                    for (a, b) in back.angles.iter().zip(values) {
+                        assert!((a.value() - b).abs() < 1e-9, "{} {:?}: {:?} != {:?}", sequence, convention, back.angles, values);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_conventions() {
+        let (roll, pitch, yaw) = (0.1, 0.2, 0.3);
+        let rpy = EulerAngles::roll_pitch_yaw(Angle::new(roll), Angle::new(pitch), Angle::new(yaw));
+        let ypr = EulerAngles::new([yaw, pitch, roll].map(Angle::new), AxisSequence::ZYX, Convention::Intrinsic);
+        assert_same_rotation(&rpy.to_rotor(), &ypr.to_rotor());
+
+        // Yawing a quarter turn points the body x axis along world y
+        let heading = EulerAngles::roll_pitch_yaw(Angle::new(0.0), Angle::new(0.0), Angle::new(TAU / 4.0));
+        let nose = heading.to_rotor().apply([1.0, 0.0, 0.0]);
+        assert!(linalg::norm(linalg::sub(nose, [0.0, 1.0, 0.0])) < 1e-12);
+
+        let (converted, _) = rpy.convert(AxisSequence::ZXZ, Convention::Intrinsic);
+        assert_same_rotation(&converted.to_rotor(), &rpy.to_rotor());
+        let (angles, _) = EulerAngles::rpy_of(&rpy.to_rotor());
+        assert!((angles[2].value() - yaw).abs() < 1e-12);
+        assert_eq!(AxisSequence::ZYX.to_string(), "Z-Y-X");
+    }
+
+    #[test]
+    fn test_gimbal_lock() {
+        // Pitching straight up merges roll and yaw
+        let locked = EulerAngles::roll_pitch_yaw(Angle::new(0.4), Angle::new(TAU / 4.0), Angle::new(0.5));
+        let (back, lock) = EulerAngles::from_rotor(&locked.to_rotor(), AxisSequence::XYZ, Convention::Extrinsic);
+        assert!(lock.is_some());
+        assert_eq!(*back.angles[2].value(), 0.0);
+        assert_same_rotation(&back.to_rotor(), &locked.to_rotor());
+
+        // Both singularities of every sequence keep the rotation with the last angle at zero
+        for sequence in AxisSequence::ALL {
+            let singular = if sequence.is_proper_euler() { [0.0, TAU / 2.0] } else { [-TAU / 4.0, TAU / 4.0] };
+            for convention in [Convention::Intrinsic, Convention::Extrinsic] {
+                for middle in singular {
+                    let euler = EulerAngles::new([0.7, middle, 0.2].map(Angle::new), sequence, convention);
+                    let (back, lock) = EulerAngles::from_rotor(&euler.to_rotor(), sequence, convention);
+                    assert!(lock.is_some(), "{} {:?}", sequence, convention);
+                    assert_eq!(*back.angles[2].value(), 0.0);
+                    assert_same_rotation(&back.to_rotor(), &euler.to_rotor());
+                }
+            }
+        }
+    }
+}
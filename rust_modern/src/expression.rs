@@ -0,0 +1,339 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Lazy expressions over dense multivectors
+//!
+//! Operators on [`Dense`] references build a typed expression tree instead of
+//! computing a result: `&a * &b + &c * &d` is a
+//! `Sum<Multiply, Multiply>` that borrows its four operands. Evaluating the
+//! tree with [`Expr::eval`] computes each output blade in one pass, summing
+//! the product terms that land on it, so no intermediate multivector is
+//! materialized and the chain runs as one fused loop on the stack.
+//!
+//! `*` is the geometric product and `^` the outer product, with tables from
+//! [`crate::clifford`]. A product needs every blade of its operands once per
+//! output blade, so an operand that is itself a product or sum is evaluated
+//! into a stack array when the product is built; plain references are read in
+//! place. Scalars multiply through [`Expr::scale`].
+
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+use std::ops::{Add, BitXor, Mul, Neg, Sub};
+
+use crate::clifford::Tables;
+use crate::parity::Coefficient;
+
+/// Dense coefficients of a multivector of algebra `A`, indexed by blade bitmask
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dense<A, T, const N: usize> {
+    coefficients: [T; N],
+    algebra: PhantomData<A>,
+}
+
+impl<A: Tables<N>, T: Coefficient, const N: usize> Dense<A, T, N> {
+    pub fn new(coefficients: [T; N]) -> Self {
+        Self { coefficients, algebra: PhantomData }
+    }
+
+    pub fn zero() -> Self {
+        Self::new([T::default(); N])
+    }
+
+    /// `value` on a single blade
+    pub fn blade(blade: usize, value: T) -> Self {
+        let mut coefficients = [T::default(); N];
+        coefficients[blade] = value;
+        Self::new(coefficients)
+    }
+
+    pub fn get(&self, blade: usize) -> T {
+        self.coefficients[blade]
+    }
+
+    pub fn coefficients(&self) -> &[T; N] {
+        &self.coefficients
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::new(std::array::from_fn(|blade| {
+            if A::REVERSE[blade] < 0 {
+                -self.coefficients[blade]
+            } else {
+                self.coefficients[blade]
+            }
+        }))
+    }
+}
+
+impl<A: Tables<N>, T: Coefficient, const N: usize> From<[T; N]> for Dense<A, T, N> {
+    fn from(coefficients: [T; N]) -> Self {
+        Self::new(coefficients)
+    }
+}
+
+/// Node of a lazy expression whose value is a multivector of algebra `A`
+pub trait Expr<A: Tables<N>, T: Coefficient, const N: usize>: Sized {
+    /// Coefficient of one blade of the value
+    fn coefficient(&self, blade: usize) -> T;
+
+    /// Evaluate every blade in one pass
+    fn eval(&self) -> Dense<A, T, N> {
+        Dense::new(std::array::from_fn(|blade| self.coefficient(blade)))
+    }
+
+    fn scale(self, factor: T) -> Scaled<A, T, N, Self> {
+        Scaled { expr: self, factor, algebra: PhantomData }
+    }
+
+    /// How a product holds this node: a plain multivector by reference,
+    /// anything else evaluated
+    type Operand: Borrow<Dense<A, T, N>>;
+
+    fn into_operand(self) -> Self::Operand;
+}
+
+impl<A: Tables<N>, T: Coefficient, const N: usize> Expr<A, T, N> for &Dense<A, T, N> {
+    fn coefficient(&self, blade: usize) -> T {
+        self.coefficients[blade]
+    }
+
+    type Operand = Self;
+
+    fn into_operand(self) -> Self {
+        self
+    }
+}
+
+/// `lhs + rhs`
+#[derive(Debug, Clone, Copy)]
+pub struct Sum<A, T, const N: usize, L, R> {
+    lhs: L,
+    rhs: R,
+    algebra: PhantomData<(A, T)>,
+}
+
+impl<A: Tables<N>, T: Coefficient, const N: usize, L: Expr<A, T, N>, R: Expr<A, T, N>> Expr<A, T, N> for Sum<A, T, N, L, R> {
+    fn coefficient(&self, blade: usize) -> T {
+        self.lhs.coefficient(blade) + self.rhs.coefficient(blade)
+    }
+
+    type Operand = Dense<A, T, N>;
+
+    fn into_operand(self) -> Dense<A, T, N> {
+        self.eval()
+    }
+}
+
+/// `lhs - rhs`
+#[derive(Debug, Clone, Copy)]
+pub struct Difference<A, T, const N: usize, L, R> {
+    lhs: L,
+    rhs: R,
+    algebra: PhantomData<(A, T)>,
+}
+
+impl<A: Tables<N>, T: Coefficient, const N: usize, L: Expr<A, T, N>, R: Expr<A, T, N>> Expr<A, T, N>
+    for Difference<A, T, N, L, R>
+{
+    fn coefficient(&self, blade: usize) -> T {
+        self.lhs.coefficient(blade) + -self.rhs.coefficient(blade)
+    }
+
+    type Operand = Dense<A, T, N>;
+
+    fn into_operand(self) -> Dense<A, T, N> {
+        self.eval()
+    }
+}
+
+/// `-expr`
+#[derive(Debug, Clone, Copy)]
+pub struct Negated<A, T, const N: usize, E> {
+    expr: E,
+    algebra: PhantomData<(A, T)>,
+}
+
+impl<A: Tables<N>, T: Coefficient, const N: usize, E: Expr<A, T, N>> Expr<A, T, N> for Negated<A, T, N, E> {
+    fn coefficient(&self, blade: usize) -> T {
+        -self.expr.coefficient(blade)
+    }
+
+    type Operand = Dense<A, T, N>;
+
+    fn into_operand(self) -> Dense<A, T, N> {
+        self.eval()
+    }
+}
+
+/// `factor · expr`
+#[derive(Debug, Clone, Copy)]
+pub struct Scaled<A, T, const N: usize, E> {
+    expr: E,
+    factor: T,
+    algebra: PhantomData<A>,
+}
+
+impl<A: Tables<N>, T: Coefficient, const N: usize, E: Expr<A, T, N>> Expr<A, T, N> for Scaled<A, T, N, E> {
+    fn coefficient(&self, blade: usize) -> T {
+        self.factor * self.expr.coefficient(blade)
+    }
+
+    type Operand = Dense<A, T, N>;
+
+    fn into_operand(self) -> Dense<A, T, N> {
+        self.eval()
+    }
+}
+
+/// Which table a [`Multiply`] node reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProductKind {
+    Geometric,
+    Outer,
+}
+
+/// Geometric or outer product of two operands
+#[derive(Debug, Clone, Copy)]
+pub struct Multiply<A, T, const N: usize, L, R> {
+    kind: ProductKind,
+    lhs: L,
+    rhs: R,
+    algebra: PhantomData<(A, T)>,
+}
+
+impl<A: Tables<N>, T: Coefficient, const N: usize, L: Borrow<Dense<A, T, N>>, R: Borrow<Dense<A, T, N>>> Expr<A, T, N>
+    for Multiply<A, T, N, L, R>
+{
+    /// Sum over the blade pairs `(i, i ^ blade)`, the only ones landing on `blade`
+    fn coefficient(&self, blade: usize) -> T {
+        let (lhs, rhs) = (self.lhs.borrow(), self.rhs.borrow());
+        let mut sum = T::default();
+        for i in 0..N {
+            let j = i ^ blade;
+            let entry = match self.kind {
+                ProductKind::Geometric => A::GEOMETRIC[i][j],
+                ProductKind::Outer => A::OUTER[i][j],
+            };
+            let term = lhs.coefficients[i] * rhs.coefficients[j];
+            match entry.sign {
+                1 => sum = sum + term,
+                -1 => sum = sum + -term,
+                _ => {}
+            }
+        }
+        sum
+    }
+
+    type Operand = Dense<A, T, N>;
+
+    fn into_operand(self) -> Dense<A, T, N> {
+        self.eval()
+    }
+}
+
+/// Operators building nodes from any expression
+macro_rules! impl_operators {
+    ($([$($generics:tt)*] $node:ty;)*) => {$(
+        impl<$($generics)*, Rhs: Expr<A, T, N>> Add<Rhs> for $node {
+            type Output = Sum<A, T, N, Self, Rhs>;
+
+            fn add(self, rhs: Rhs) -> Self::Output {
+                Sum { lhs: self, rhs, algebra: PhantomData }
+            }
+        }
+
+        impl<$($generics)*, Rhs: Expr<A, T, N>> Sub<Rhs> for $node {
+            type Output = Difference<A, T, N, Self, Rhs>;
+
+            fn sub(self, rhs: Rhs) -> Self::Output {
+                Difference { lhs: self, rhs, algebra: PhantomData }
+            }
+        }
+
+        impl<$($generics)*> Neg for $node {
+            type Output = Negated<A, T, N, Self>;
+
+            fn neg(self) -> Self::Output {
+                Negated { expr: self, algebra: PhantomData }
+            }
+        }
+
+        impl<$($generics)*, Rhs: Expr<A, T, N>> Mul<Rhs> for $node {
+            type Output = Multiply<A, T, N, <Self as Expr<A, T, N>>::Operand, Rhs::Operand>;
+
+            fn mul(self, rhs: Rhs) -> Self::Output {
+                Multiply { kind: ProductKind::Geometric, lhs: self.into_operand(), rhs: rhs.into_operand(), algebra: PhantomData }
+            }
+        }
+
+        impl<$($generics)*, Rhs: Expr<A, T, N>> BitXor<Rhs> for $node {
+            type Output = Multiply<A, T, N, <Self as Expr<A, T, N>>::Operand, Rhs::Operand>;
+
+            fn bitxor(self, rhs: Rhs) -> Self::Output {
+                Multiply { kind: ProductKind::Outer, lhs: self.into_operand(), rhs: rhs.into_operand(), algebra: PhantomData }
+            }
+        }
+    )*};
+}
+
+impl_operators! {
+    ['r, A: Tables<N>, T: Coefficient, const N: usize] &'r Dense<A, T, N>;
+    [A: Tables<N>, T: Coefficient, const N: usize, L: Expr<A, T, N>, R: Expr<A, T, N>] Sum<A, T, N, L, R>;
+    [A: Tables<N>, T: Coefficient, const N: usize, L: Expr<A, T, N>, R: Expr<A, T, N>] Difference<A, T, N, L, R>;
+    [A: Tables<N>, T: Coefficient, const N: usize, E: Expr<A, T, N>] Negated<A, T, N, E>;
+    [A: Tables<N>, T: Coefficient, const N: usize, E: Expr<A, T, N>] Scaled<A, T, N, E>;
+    [A: Tables<N>, T: Coefficient, const N: usize, L: Borrow<Dense<A, T, N>>, R: Borrow<Dense<A, T, N>>] Multiply<A, T, N, L, R>;
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clifford::{Algebra, Cga3, Vga3};
+    use crate::sample::Sampler;
+
+    fn random<const N: usize>(sampler: &mut Sampler) -> [f64; N] {
+        std::array::from_fn(|_| sampler.range(-1.0, 1.0))
+    }
+
+    fn close<const N: usize>(a: &[f64; N], b: &[f64; N]) -> bool {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-12)
+    }
+
+    #[test]
+    fn test_fused_chain_matches_eager_products() {
+        let mut sampler = Sampler::new(12);
+        let [a, b, c, d] = [(); 4].map(|_| Dense::<Cga3, f64, 32>::new(random(&mut sampler)));
+
+        let lazy = (&a * &b + &c * &d).eval();
+        let ab = Cga3::geometric_product(a.coefficients(), b.coefficients());
+        let cd = Cga3::geometric_product(c.coefficients(), d.coefficients());
+        let eager: [f64; 32] = std::array::from_fn(|k| ab[k] + cd[k]);
+        assert!(close(lazy.coefficients(), &eager));
+
+        // Nested products evaluate their inner product once
+        let nested = ((&a * &b) * &c - (&d ^ &a).scale(2.0)).eval();
+        let abc = Cga3::geometric_product(&ab, c.coefficients());
+        let da = Cga3::outer_product(d.coefficients(), a.coefficients());
+        let expected: [f64; 32] = std::array::from_fn(|k| abc[k] - 2.0 * da[k]);
+        assert!(close(nested.coefficients(), &expected));
+        assert!(close((-&a).eval().coefficients(), &a.coefficients().map(|x| -x)));
+    }
+
+    #[test]
+    fn test_rotor_sandwich_expression() {
+        // e12 rotor by a quarter turn carries e1 to e2
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        let mut r = [0.0; 8];
+        (r[0], r[0b011]) = (half, -half);
+        let rotor = Dense::<Vga3, f64, 8>::new(r);
+        let e1 = Dense::<Vga3, f64, 8>::blade(0b001, 1.0);
+        let rotated = (&rotor * &e1 * &rotor.reverse()).eval();
+        assert!(close(rotated.coefficients(), Dense::<Vga3, f64, 8>::blade(0b010, 1.0).coefficients()));
+
+        // Works over any algebra and coefficient type
+        let pga = Dense::<Algebra<3, 0, 1, 16>, i64, 16>::blade(0b1000, 3);
+        assert_eq!((&pga * &pga).eval(), Dense::zero());
+    }
+}
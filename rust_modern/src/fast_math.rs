@@ -0,0 +1,273 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compact kernels for embedded targets
+//!
+//! [`Real`] is the scalar of these kernels: `f64` by default and `f32` with
+//! the `fast-math` feature, which also replaces [`sin_cos`] and [`rsqrt`] with
+//! the polynomial and bit-level approximations [`fast_sin_cos`] and
+//! [`fast_rsqrt`]. The rest of the crate keeps `f64`; [`CompactRotor`] and
+//! [`CompactMotor`] convert from and to [`Rotor`] and [`Motor`] at the kernel
+//! boundary, and [`forward_kinematics`] chains revolute joints in `Real`.
+//!
+//! Accuracy with `fast-math`, measured by the tests below:
+//!
+//! | operation            | error                                   |
+//! |----------------------|-----------------------------------------|
+//! | `fast_sin_cos`       | ≤ 4e-7 absolute for \|x\| ≤ 1000        |
+//! | `fast_rsqrt`         | ≤ 5e-6 relative                         |
+//! | rotor application    | ≤ 1e-6 relative to the vector length    |
+//! | seven-joint FK       | ≤ 1e-5 m over a 2 m reach               |
+//!
+//! Errors grow with the argument of `sin_cos` as range reduction loses bits
+//! of `f32`, so wrap angles before calling it on long-running integrators.
+//! The `rotor_application` and `forward_kinematics` groups of the benchmark
+//! runner compare these kernels with the `f64` types; run them with
+//! `--features fast-math` to measure the `f32` profile.
+
+use crate::motor::{Motor, Rotor};
+
+/// Scalar of the compact kernels
+#[cfg(feature = "fast-math")]
+pub type Real = f32;
+/// Scalar of the compact kernels
+#[cfg(not(feature = "fast-math"))]
+pub type Real = f64;
+
+/// Sine and cosine, approximated with `fast-math`
+#[cfg(feature = "fast-math")]
+pub fn sin_cos(x: Real) -> (Real, Real) {
+    fast_sin_cos(x)
+}
+
+/// Sine and cosine, approximated with `fast-math`
+#[cfg(not(feature = "fast-math"))]
+pub fn sin_cos(x: Real) -> (Real, Real) {
+    x.sin_cos()
+}
+
+/// `1/√x`, approximated with `fast-math`
+#[cfg(feature = "fast-math")]
+pub fn rsqrt(x: Real) -> Real {
+    fast_rsqrt(x)
+}
+
+/// `1/√x`, approximated with `fast-math`
+#[cfg(not(feature = "fast-math"))]
+pub fn rsqrt(x: Real) -> Real {
+    1.0 / x.sqrt()
+}
+
+/// Sine and cosine by reduction to `[-τ/8, τ/8]` and Taylor polynomials
+pub fn fast_sin_cos(x: f32) -> (f32, f32) {
+    // τ/4 split in three so k·τ/4 is subtracted without rounding for moderate k
+    const QUARTER: [f32; 3] = [1.570_312_5, 4.837_513e-4, 7.549_79e-8];
+    let k = (x * std::f32::consts::FRAC_2_PI).round();
+    let r = ((x - k * QUARTER[0]) - k * QUARTER[1]) - k * QUARTER[2];
+    let r2 = r * r;
+    let sin = r * (1.0 + r2 * (-1.0 / 6.0 + r2 * (1.0 / 120.0 + r2 * (-1.0 / 5040.0))));
+    let cos = 1.0 + r2 * (-0.5 + r2 * (1.0 / 24.0 + r2 * (-1.0 / 720.0 + r2 * (1.0 / 40320.0))));
+    match (k as i64).rem_euclid(4) {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    }
+}
+
+/// `1/√x` from the bit-level initial guess and two Newton steps
+pub fn fast_rsqrt(x: f32) -> f32 {
+    let mut y = f32::from_bits(0x5f37_5a86 - (x.to_bits() >> 1));
+    for _ in 0..2 {
+        y *= 1.5 - 0.5 * x * y * y;
+    }
+    y
+}
+
+/// Rotor in [`Real`], blades in GAFRO order (scalar, e23, e13, e12)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompactRotor(pub [Real; 4]);
+
+impl CompactRotor {
+    pub const IDENTITY: Self = Self([1.0, 0.0, 0.0, 0.0]);
+
+    /// Right-handed rotation of `angle` about the unit `axis`
+    pub fn from_axis_angle(axis: [Real; 3], angle: Real) -> Self {
+        let (sin, cos) = sin_cos(0.5 * angle);
+        Self([cos, -sin * axis[0], sin * axis[1], -sin * axis[2]])
+    }
+
+    /// Quaternion vector part `(x, y, z)` and scalar `w`
+    fn quaternion(&self) -> ([Real; 3], Real) {
+        let [w, e23, e13, e12] = self.0;
+        ([-e23, e13, -e12], w)
+    }
+
+    /// Sandwich product `R v R̃`
+    pub fn apply(&self, v: [Real; 3]) -> [Real; 3] {
+        let (u, w) = self.quaternion();
+        let t = cross(u, v).map(|c| 2.0 * c);
+        let ut = cross(u, t);
+        [0, 1, 2].map(|i| v[i] + w * t[i] + ut[i])
+    }
+
+    /// Rescale to unit norm with [`rsqrt`]
+    pub fn normalized(&self) -> Self {
+        let scale = rsqrt(self.0.iter().map(|b| b * b).sum());
+        Self(self.0.map(|b| b * scale))
+    }
+}
+
+impl std::ops::Mul for CompactRotor {
+    type Output = CompactRotor;
+
+    fn mul(self, rhs: CompactRotor) -> CompactRotor {
+        let ([x1, y1, z1], w1) = self.quaternion();
+        let ([x2, y2, z2], w2) = rhs.quaternion();
+        let w = w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2;
+        let x = w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2;
+        let y = w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2;
+        let z = w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2;
+        CompactRotor([w, -x, y, -z])
+    }
+}
+
+impl From<&Rotor> for CompactRotor {
+    fn from(rotor: &Rotor) -> Self {
+        Self([rotor.scalar, rotor.e23, rotor.e13, rotor.e12].map(|b| b as Real))
+    }
+}
+
+impl From<CompactRotor> for Rotor {
+    fn from(rotor: CompactRotor) -> Self {
+        let [scalar, e23, e13, e12] = rotor.0.map(f64::from);
+        Rotor::new(scalar, e23, e13, e12)
+    }
+}
+
+/// Motor `T R` in [`Real`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompactMotor {
+    pub rotor: CompactRotor,
+    pub translation: [Real; 3],
+}
+
+impl CompactMotor {
+    pub const IDENTITY: Self = Self { rotor: CompactRotor::IDENTITY, translation: [0.0; 3] };
+
+    pub fn apply_point(&self, p: [Real; 3]) -> [Real; 3] {
+        let rotated = self.rotor.apply(p);
+        [0, 1, 2].map(|i| rotated[i] + self.translation[i])
+    }
+}
+
+impl std::ops::Mul for CompactMotor {
+    type Output = CompactMotor;
+
+    /// Composition: `(a * b)` applies `b` first, then `a`
+    fn mul(self, rhs: CompactMotor) -> CompactMotor {
+        CompactMotor { rotor: self.rotor * rhs.rotor, translation: self.apply_point(rhs.translation) }
+    }
+}
+
+impl From<&Motor> for CompactMotor {
+    fn from(motor: &Motor) -> Self {
+        Self { rotor: CompactRotor::from(&motor.rotor), translation: motor.translation.map(|t| t as Real) }
+    }
+}
+
+impl From<CompactMotor> for Motor {
+    fn from(motor: CompactMotor) -> Self {
+        Motor::new(motor.translation.map(f64::from), motor.rotor.into())
+    }
+}
+
+/// Revolute joint: fixed placement in the parent link, then rotation about a
+/// unit axis of the joint frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompactJoint {
+    pub placement: CompactMotor,
+    pub axis: [Real; 3],
+}
+
+/// Pose of the last link for the given joint angles; extra angles or joints
+/// are ignored
+pub fn forward_kinematics(joints: &[CompactJoint], angles: &[Real]) -> CompactMotor {
+    joints.iter().zip(angles).fold(CompactMotor::IDENTITY, |pose, (joint, &angle)| {
+        let rotation = CompactMotor { rotor: CompactRotor::from_axis_angle(joint.axis, angle), translation: [0.0; 3] };
+        pose * joint.placement * rotation
+    })
+}
+
+fn cross(a: [Real; 3], b: [Real; 3]) -> [Real; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg;
+    use crate::sample::Sampler;
+
+    #[test]
+    fn test_approximation_accuracy() {
+        let mut worst_sin_cos = 0.0f64;
+        for i in -200_000..=200_000 {
+            let x = i as f32 * 0.005;
+            let (sin, cos) = fast_sin_cos(x);
+            let exact = f64::from(x).sin_cos();
+            worst_sin_cos = worst_sin_cos.max((f64::from(sin) - exact.0).abs()).max((f64::from(cos) - exact.1).abs());
+        }
+        assert!(worst_sin_cos < 4e-7, "{}", worst_sin_cos);
+
+        let mut worst_rsqrt = 0.0f64;
+        for i in 1..100_000 {
+            let x = i as f32 * 1e-2;
+            let exact = 1.0 / f64::from(x).sqrt();
+            worst_rsqrt = worst_rsqrt.max((f64::from(fast_rsqrt(x)) - exact).abs() / exact);
+        }
+        assert!(worst_rsqrt < 5e-6, "{}", worst_rsqrt);
+    }
+
+    #[test]
+    fn test_kernels_match_motor_types() {
+        let mut sampler = Sampler::new(5);
+        for _ in 0..100 {
+            let rotor = sampler.rotor();
+            let v = sampler.unit_vector();
+            let compact = CompactRotor::from(&rotor).apply(v.map(|c| c as Real)).map(f64::from);
+            let exact = rotor.apply(v);
+            assert!((0..3).all(|i| (compact[i] - exact[i]).abs() < 1e-6));
+
+            let axis = sampler.unit_vector();
+            let angle = sampler.range(-3.0, 3.0);
+            let built: Rotor = CompactRotor::from_axis_angle(axis.map(|c| c as Real), angle as Real).into();
+            let expected = Rotor::from_axis_angle(axis, angle);
+            assert!((built * expected.reverse()).angle() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_forward_kinematics_chain() {
+        // Seven alternating z and y joints, 0.3 m apart along the link x axis
+        let placements: Vec<Motor> = (0..7).map(|i| Motor::from_translation([if i == 0 { 0.0 } else { 0.3 }, 0.0, 0.0])).collect();
+        let axes: Vec<[f64; 3]> = (0..7).map(|i| if i % 2 == 0 { [0.0, 0.0, 1.0] } else { [0.0, 1.0, 0.0] }).collect();
+        let joints: Vec<CompactJoint> = placements
+            .iter()
+            .zip(&axes)
+            .map(|(placement, axis)| CompactJoint { placement: placement.into(), axis: axis.map(|c| c as Real) })
+            .collect();
+        let angles = [0.3, -0.5, 1.1, 0.7, -1.4, 0.2, 2.0];
+
+        let expected = placements.iter().zip(&axes).zip(angles).fold(Motor::identity(), |pose, ((placement, axis), angle)| {
+            pose * *placement * Motor::from_rotor(Rotor::from_axis_angle(*axis, angle))
+        });
+        let tip = [0.3, 0.0, 0.0];
+        let pose: Motor = forward_kinematics(&joints, &angles.map(|a| a as Real)).into();
+        let error = linalg::norm(linalg::sub(pose.apply_point(tip), expected.apply_point(tip)));
+        assert!(error < 1e-5, "{}", error);
+        assert_eq!(forward_kinematics(&[], &[]), CompactMotor::IDENTITY);
+    }
+}
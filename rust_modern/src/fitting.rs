@@ -0,0 +1,349 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Sphere and quadric fitting to point clouds
+//!
+//! Both fits are algebraic. A sphere is the zero set of `a‖p‖² + b·p + c` and
+//! a quadric of `[p; 1]ᵀ Q [p; 1]` for a symmetric 4×4 `Q`; the coefficients
+//! minimizing the summed squared values over the points, at unit norm, are the
+//! eigenvector of the smallest eigenvalue of the points' scatter matrix. The
+//! points are centered and scaled to unit mean distance first, which keeps the
+//! scatter matrix well conditioned, and the coefficients are mapped back.
+//!
+//! A second near-zero eigenvalue means a whole family of surfaces passes through
+//! the points, as for points on a circle, which lie on a pencil of spheres, or
+//! coplanar points, which lie on many quadrics; such fits are rejected. Sphere
+//! fits report the RMS of the exact distances, quadric fits that of the
+//! first-order (Sampson) distance `|f(p)| / ‖∇f(p)‖`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::linalg::{self, dense, square, Matrix3, Vector3};
+use crate::primitives::Sphere;
+use crate::si_units::Length;
+
+/// Second-smallest scatter eigenvalue, relative to the largest, below which the
+/// points do not determine a unique surface
+const DEGENERACY_RATIO: f64 = 1e-10;
+
+/// Largest `|a|` of a unit-norm normalized sphere fit treated as a plane; the
+/// radius would exceed about a million times the extent of the points
+const FLAT_TOLERANCE: f64 = 1e-6;
+
+/// Point sets no surface can be fitted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitError {
+    TooFewPoints { needed: usize, given: usize },
+    /// More than one surface fits the points equally well
+    Degenerate,
+    /// The best sphere through the points is a plane
+    Planar,
+    /// The points are not all finite
+    NonFinite,
+}
+
+impl fmt::Display for FitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitError::TooFewPoints { needed, given } => write!(f, "fit needs {} points, got {}", needed, given),
+            FitError::Degenerate => write!(f, "points do not determine a unique surface"),
+            FitError::Planar => write!(f, "points lie on a plane, not a sphere"),
+            FitError::NonFinite => write!(f, "points contain NaN or infinite coordinates"),
+        }
+    }
+}
+
+impl std::error::Error for FitError {}
+
+/// Quadric surface `[p; 1]ᵀ Q [p; 1] = 0`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quadric {
+    /// Symmetric; defined up to a non-zero scale
+    matrix: [[f64; 4]; 4],
+}
+
+impl Quadric {
+    /// Quadric of the symmetric part of `matrix`
+    pub fn new(matrix: [[f64; 4]; 4]) -> Self {
+        Self { matrix: std::array::from_fn(|i| std::array::from_fn(|j| 0.5 * (matrix[i][j] + matrix[j][i]))) }
+    }
+
+    pub fn from_sphere(sphere: &Sphere) -> Self {
+        let c = sphere.center;
+        Self::new([
+            [1.0, 0.0, 0.0, -c[0]],
+            [0.0, 1.0, 0.0, -c[1]],
+            [0.0, 0.0, 1.0, -c[2]],
+            [-c[0], -c[1], -c[2], linalg::dot(c, c) - sphere.radius * sphere.radius],
+        ])
+    }
+
+    pub fn matrix(&self) -> [[f64; 4]; 4] {
+        self.matrix
+    }
+
+    /// The quadratic part `A` of `pᵀ A p + 2 bᵀ p + c`
+    pub fn quadratic(&self) -> Matrix3 {
+        std::array::from_fn(|i| std::array::from_fn(|j| self.matrix[i][j]))
+    }
+
+    /// Algebraic value at `point`: zero on the surface, with a sign telling the sides apart
+    pub fn value(&self, point: Vector3) -> f64 {
+        square::quadratic_form(&self.matrix, &[point[0], point[1], point[2], 1.0])
+    }
+
+    pub fn gradient(&self, point: Vector3) -> Vector3 {
+        let h = [point[0], point[1], point[2], 1.0];
+        std::array::from_fn(|i| 2.0 * (0..4).map(|j| self.matrix[i][j] * h[j]).sum::<f64>())
+    }
+
+    /// First-order distance `|f(p)| / ‖∇f(p)‖` from `point` to the surface
+    pub fn distance_estimate(&self, point: Vector3) -> Length {
+        let slope = linalg::norm(self.gradient(point));
+        Length::new(if slope > 0.0 { self.value(point).abs() / slope } else { f64::INFINITY })
+    }
+
+    /// Center of symmetry, solving `A p = -b`; `None` for paraboloids and cylinders
+    pub fn center(&self) -> Option<Vector3> {
+        let inverse = square::inverse(&self.quadratic())?;
+        let b = [self.matrix[0][3], self.matrix[1][3], self.matrix[2][3]];
+        Some(linalg::scale(linalg::mat3_vec(&inverse, b), -1.0))
+    }
+
+    /// Eigenvalues of the quadratic part in ascending order, with the axes as columns
+    pub fn principal_axes(&self) -> ([f64; 3], Matrix3) {
+        square::symmetric_eigen(&self.quadratic())
+    }
+
+    /// Semi-axis lengths in the order of [`Quadric::principal_axes`], for an ellipsoid
+    pub fn semi_axes(&self) -> Option<[Length; 3]> {
+        let center = self.center()?;
+        let (values, _) = self.principal_axes();
+        let level = -self.value(center);
+        values
+            .iter()
+            .all(|value| value * level > 0.0)
+            .then(|| values.map(|value| Length::new((level / value).sqrt())))
+    }
+}
+
+/// Best-fitting sphere and how far the points lie from it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SphereFit {
+    pub sphere: Sphere,
+    /// RMS distance of the points from the sphere
+    pub rms: Length,
+}
+
+/// Best-fitting quadric and how far the points lie from it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadricFit {
+    pub quadric: Quadric,
+    /// RMS first-order distance of the points from the quadric
+    pub rms: Length,
+}
+
+/// Centroid and spread about it, mapping `p` to `(p - centroid) / scale`
+pub(crate) struct Normalization {
+    pub(crate) centroid: Vector3,
+    pub(crate) scale: f64,
+}
+
+impl Normalization {
+    /// Scale by the mean distance from the centroid
+    fn new(points: &[Vector3]) -> Result<Self, FitError> {
+        if points.iter().flatten().any(|x| !x.is_finite()) {
+            return Err(FitError::NonFinite);
+        }
+        let n = points.len() as f64;
+        let centroid = linalg::scale(points.iter().fold([0.0; 3], |sum, p| linalg::add(sum, *p)), 1.0 / n);
+        let scale = points.iter().map(|p| linalg::norm(linalg::sub(*p, centroid))).sum::<f64>() / n;
+        if scale == 0.0 {
+            return Err(FitError::Degenerate);
+        }
+        Ok(Self { centroid, scale })
+    }
+
+    fn apply(&self, point: Vector3) -> Vector3 {
+        linalg::scale(linalg::sub(point, self.centroid), 1.0 / self.scale)
+    }
+}
+
+/// Row-major scatter matrix `Σ row rowᵀ`
+fn scatter(rows: &[Vec<f64>]) -> Vec<f64> {
+    let n = rows[0].len();
+    let mut scatter = vec![0.0; n * n];
+    for row in rows {
+        for i in 0..n {
+            for j in 0..n {
+                scatter[i * n + j] += row[i] * row[j];
+            }
+        }
+    }
+    scatter
+}
+
+/// Unit coefficient vector minimizing `kᵀ S k = Σ (rowᵀ k)²`, the smallest
+/// eigenvector of the n×n scatter matrix `S`
+pub(crate) fn smallest_eigenvector(scatter: &[f64], n: usize) -> Result<Vec<f64>, FitError> {
+    let (values, vectors) = dense::symmetric_eigen(scatter, n);
+    if values[1] <= DEGENERACY_RATIO * values[n - 1] {
+        return Err(FitError::Degenerate);
+    }
+    Ok((0..n).map(|i| vectors[i * n]).collect())
+}
+
+fn check_count(points: &[Vector3], needed: usize) -> Result<(), FitError> {
+    if points.len() < needed {
+        return Err(FitError::TooFewPoints { needed, given: points.len() });
+    }
+    Ok(())
+}
+
+/// Sphere through at least four points, in the least-squares sense
+pub fn fit_sphere(points: &[Vector3]) -> Result<SphereFit, FitError> {
+    check_count(points, 4)?;
+    let normalization = Normalization::new(points)?;
+    let rows: Vec<Vec<f64>> = points
+        .iter()
+        .map(|p| {
+            let p = normalization.apply(*p);
+            vec![linalg::dot(p, p), p[0], p[1], p[2], 1.0]
+        })
+        .collect();
+    let sphere = sphere_from_coefficients(&smallest_eigenvector(&scatter(&rows), 5)?, &normalization)?;
+    let squared: f64 = points.iter().map(|p| (linalg::norm(linalg::sub(*p, sphere.center)) - sphere.radius).powi(2)).sum();
+    Ok(SphereFit { sphere, rms: Length::new((squared / points.len() as f64).sqrt()) })
+}
+
+/// General quadric through at least nine points, in the least-squares sense
+pub fn fit_quadric(points: &[Vector3]) -> Result<QuadricFit, FitError> {
+    check_count(points, 9)?;
+    let normalization = Normalization::new(points)?;
+    let rows: Vec<Vec<f64>> = points
+        .iter()
+        .map(|p| {
+            let [x, y, z] = normalization.apply(*p);
+            vec![x * x, y * y, z * z, x * y, x * z, y * z, x, y, z, 1.0]
+        })
+        .collect();
+    let quadric = quadric_from_coefficients(&smallest_eigenvector(&scatter(&rows), 10)?, &normalization);
+    let squared: f64 = points.iter().map(|p| quadric.distance_estimate(*p).value().powi(2)).sum();
+    Ok(QuadricFit { quadric, rms: Length::new((squared / points.len() as f64).sqrt()) })
+}
+
+/// Sphere `a‖p‖² + b·p + c = 0` of the coefficients `k = [a, b, c]` of normalized points
+pub(crate) fn sphere_from_coefficients(k: &[f64], normalization: &Normalization) -> Result<Sphere, FitError> {
+    if k[0].abs() < FLAT_TOLERANCE {
+        return Err(FitError::Planar);
+    }
+    let center = [-k[1] / (2.0 * k[0]), -k[2] / (2.0 * k[0]), -k[3] / (2.0 * k[0])];
+    let radius_squared = linalg::dot(center, center) - k[4] / k[0];
+    if radius_squared <= 0.0 {
+        return Err(FitError::Degenerate);
+    }
+    Ok(Sphere::new(
+        linalg::add(normalization.centroid, linalg::scale(center, normalization.scale)),
+        radius_squared.sqrt() * normalization.scale,
+    ))
+}
+
+/// Quadric of the coefficients of `x², y², z², xy, xz, yz, x, y, z, 1` of normalized points
+pub(crate) fn quadric_from_coefficients(k: &[f64], normalization: &Normalization) -> Quadric {
+    let normalized = [
+        [k[0], 0.5 * k[3], 0.5 * k[4], 0.5 * k[6]],
+        [0.5 * k[3], k[1], 0.5 * k[5], 0.5 * k[7]],
+        [0.5 * k[4], 0.5 * k[5], k[2], 0.5 * k[8]],
+        [0.5 * k[6], 0.5 * k[7], 0.5 * k[8], k[9]],
+    ];
+
+    // Q = Tᵀ Q' T for the homogeneous normalization T = [I/s, -m/s; 0, 1]
+    let (m, s) = (normalization.centroid, normalization.scale);
+    let mut t = [[0.0; 4]; 4];
+    for i in 0..3 {
+        t[i][i] = 1.0 / s;
+        t[i][3] = -m[i] / s;
+    }
+    t[3][3] = 1.0;
+    let mut matrix = square::mul(&square::transpose(&t), &square::mul(&normalized, &t));
+    // Fix the arbitrary sign so the quadratic part has a non-negative trace
+    if matrix[0][0] + matrix[1][1] + matrix[2][2] < 0.0 {
+        matrix = matrix.map(|row| row.map(|x| -x));
+    }
+    Quadric::new(matrix)
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motor::{Motor, Rotor};
+    use crate::sample::Sampler;
+
+    #[test]
+    fn test_sphere_fit() {
+        let truth = Sphere::new([10.0, -4.0, 2.5], 0.8);
+        let mut sampler = Sampler::new(7);
+        let exact: Vec<Vector3> = (0..20).map(|_| linalg::add(truth.center, linalg::scale(sampler.unit_vector(), truth.radius))).collect();
+        let fit = fit_sphere(&exact).unwrap();
+        assert!(linalg::norm(linalg::sub(fit.sphere.center, truth.center)) < 1e-9);
+        assert!((fit.sphere.radius - truth.radius).abs() < 1e-9 && *fit.rms.value() < 1e-9);
+
+        // Millimetre noise moves the estimate by about as much
+        let noisy: Vec<Vector3> = exact.iter().map(|p| linalg::add(*p, std::array::from_fn(|_| 1e-3 * sampler.normal()))).collect();
+        let fit = fit_sphere(&noisy).unwrap();
+        assert!(linalg::norm(linalg::sub(fit.sphere.center, truth.center)) < 3e-3);
+        assert!((fit.sphere.radius - truth.radius).abs() < 3e-3);
+        assert!(*fit.rms.value() > 1e-4 && *fit.rms.value() < 3e-3);
+    }
+
+    #[test]
+    fn test_quadric_fit_recovers_an_ellipsoid() {
+        let motor = Motor::new([1.0, 2.0, -3.0], Rotor::from_axis_angle([1.0, 1.0, 0.0], 0.6));
+        let axes = [0.5, 1.0, 2.0];
+        let mut sampler = Sampler::new(3);
+        let points: Vec<Vector3> = (0..40)
+            .map(|_| {
+                let u = sampler.unit_vector();
+                motor.apply_point(std::array::from_fn(|i| axes[i] * u[i]))
+            })
+            .collect();
+        let fit = fit_quadric(&points).unwrap();
+        assert!(*fit.rms.value() < 1e-9);
+        let center = fit.quadric.center().unwrap();
+        assert!(linalg::norm(linalg::sub(center, motor.translation)) < 1e-9);
+        let semi_axes = fit.quadric.semi_axes().unwrap();
+        for (found, expected) in semi_axes.iter().zip([2.0, 1.0, 0.5]) {
+            assert!((found.value() - expected).abs() < 1e-9, "{:?}", semi_axes);
+        }
+        // The smallest eigenvalue belongs to the longest axis, body z
+        let (_, frame) = fit.quadric.principal_axes();
+        let longest = motor.apply_direction([0.0, 0.0, 1.0]);
+        assert!((linalg::dot([frame[0][0], frame[1][0], frame[2][0]], longest).abs() - 1.0).abs() < 1e-9);
+
+        let sphere = Sphere::new([1.0, 0.0, 0.0], 2.0);
+        let quadric = Quadric::from_sphere(&sphere);
+        assert!((quadric.distance_estimate([3.001, 0.0, 0.0]).value() - 1e-3).abs() < 1e-6);
+        assert!(quadric.value([1.0, 0.0, 0.0]) < 0.0);
+    }
+
+    #[test]
+    fn test_degenerate_point_sets() {
+        assert_eq!(fit_sphere(&[[0.0; 3]; 3]), Err(FitError::TooFewPoints { needed: 4, given: 3 }));
+        assert_eq!(fit_sphere(&[[0.0; 3]; 5]), Err(FitError::Degenerate));
+        assert_eq!(fit_sphere(&[[f64::NAN, 0.0, 0.0]; 5]), Err(FitError::NonFinite));
+
+        // Points on a circle lie on infinitely many spheres
+        let circle: Vec<Vector3> = (0..12).map(|k| [(k as f64 * 0.5).cos(), (k as f64 * 0.5).sin(), 1.0]).collect();
+        assert_eq!(fit_sphere(&circle), Err(FitError::Degenerate));
+
+        // Points scattered on a plane are best fitted by the plane
+        let mut sampler = Sampler::new(5);
+        let flat: Vec<Vector3> = (0..12).map(|_| [sampler.range(-1.0, 1.0), sampler.range(-1.0, 1.0), 0.5]).collect();
+        assert_eq!(fit_sphere(&flat), Err(FitError::Planar));
+        assert_eq!(fit_quadric(&flat), Err(FitError::Degenerate));
+    }
+}
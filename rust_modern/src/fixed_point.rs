@@ -0,0 +1,283 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Q-format fixed-point scalars for targets without an FPU
+//!
+//! [`Fixed<FRAC>`] stores a value as an `i32` scaled by `2^FRAC`, so [`Q16_16`]
+//! covers ±32768 in steps of 1.5e-5 and [`Q2_29`] covers ±4 in steps of
+//! 1.9e-9, enough for unit rotors and direction vectors. Integer arithmetic
+//! gives the same bits on every target. Every operation saturates at
+//! [`Fixed::MIN`] and [`Fixed::MAX`] instead of wrapping, and division by zero
+//! saturates toward the sign of the dividend.
+//!
+//! `Fixed` implements the crate's coefficient traits, [`Real`] and (through
+//! its blanket impl) [`crate::parity::Coefficient`], so GA terms, parity-typed
+//! rotor products, norms and [`crate::summation`] accumulators run on it
+//! unchanged. [`FixedPid`] is a PID step for control loops on such targets.
+//!
+//! Multiplication rounds to nearest and division truncates toward zero; a
+//! single operation is off by at most one unit in the last place.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ga_term::Real;
+
+/// Signed fixed-point number with `FRAC` fractional bits, `1 ≤ FRAC ≤ 30`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Fixed<const FRAC: u32>(i32);
+
+/// 16 integer and 16 fractional bits
+pub type Q16_16 = Fixed<16>;
+/// 2 integer and 29 fractional bits, for unit-scale values
+pub type Q2_29 = Fixed<29>;
+
+impl<const FRAC: u32> Fixed<FRAC> {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << FRAC);
+    pub const MIN: Self = Self(i32::MIN);
+    pub const MAX: Self = Self(i32::MAX);
+    /// Smallest positive value
+    pub const EPSILON: Self = Self(1);
+
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    /// Nearest representable value, saturating; NaN maps to zero
+    pub fn from_f64(value: f64) -> Self {
+        // Float-to-int `as` saturates and maps NaN to zero
+        Self((value * (1u64 << FRAC) as f64).round() as i32)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / (1u64 << FRAC) as f64
+    }
+
+    pub fn from_int(value: i32) -> Self {
+        Self::saturate((value as i64) << FRAC)
+    }
+
+    fn saturate(wide: i64) -> Self {
+        Self(wide.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+
+    pub fn is_saturated(self) -> bool {
+        self == Self::MIN || self == Self::MAX
+    }
+
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        Ord::clamp(self, min, max)
+    }
+}
+
+impl<const FRAC: u32> Add for Fixed<FRAC> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl<const FRAC: u32> Sub for Fixed<FRAC> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl<const FRAC: u32> Neg for Fixed<FRAC> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.saturating_neg())
+    }
+}
+
+impl<const FRAC: u32> Mul for Fixed<FRAC> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let product = self.0 as i64 * rhs.0 as i64;
+        Self::saturate((product + (1 << (FRAC - 1))) >> FRAC)
+    }
+}
+
+impl<const FRAC: u32> Div for Fixed<FRAC> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if rhs.0 == 0 {
+            return match self.0.signum() {
+                0 => Self::ZERO,
+                1 => Self::MAX,
+                _ => Self::MIN,
+            };
+        }
+        Self::saturate(((self.0 as i64) << FRAC) / rhs.0 as i64)
+    }
+}
+
+impl<const FRAC: u32> Real for Fixed<FRAC> {
+    /// Integer square root of the scaled value, rounded down; zero for negatives
+    fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        Self::saturate((((self.0 as u64) << FRAC).isqrt()) as i64)
+    }
+
+    fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+}
+
+impl<const FRAC: u32> fmt::Display for Fixed<FRAC> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f64(), f)
+    }
+}
+
+/// PID step in fixed point: integral clamped to the output limits, derivative
+/// on the error
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FixedPid<const FRAC: u32> {
+    pub kp: Fixed<FRAC>,
+    pub ki: Fixed<FRAC>,
+    pub kd: Fixed<FRAC>,
+    pub min: Fixed<FRAC>,
+    pub max: Fixed<FRAC>,
+    integral: Fixed<FRAC>,
+    previous: Option<Fixed<FRAC>>,
+}
+
+impl<const FRAC: u32> FixedPid<FRAC> {
+    pub fn new(kp: Fixed<FRAC>, ki: Fixed<FRAC>, kd: Fixed<FRAC>) -> Self {
+        Self { kp, ki, kd, min: Fixed::MIN, max: Fixed::MAX, integral: Fixed::ZERO, previous: None }
+    }
+
+    pub fn with_limits(mut self, min: Fixed<FRAC>, max: Fixed<FRAC>) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    pub fn reset(&mut self) {
+        self.integral = Fixed::ZERO;
+        self.previous = None;
+    }
+
+    /// Output for the current `error` after a step of `dt`
+    pub fn update(&mut self, error: Fixed<FRAC>, dt: Fixed<FRAC>) -> Fixed<FRAC> {
+        self.integral = (self.integral + self.ki * error * dt).clamp(self.min, self.max);
+        let derivative = match self.previous {
+            Some(previous) if dt > Fixed::ZERO => self.kd * (error - previous) / dt,
+            _ => Fixed::ZERO,
+        };
+        self.previous = Some(error);
+        (self.kp * error + self.integral + derivative).clamp(self.min, self.max)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ga_term::{BladeTerm, GATerm};
+    use crate::parity::{Even, Odd};
+    use crate::pattern_matching::operations;
+    use crate::sample::Sampler;
+
+    #[test]
+    fn test_arithmetic_against_f64() {
+        let ulp = Q16_16::EPSILON.to_f64();
+        let mut sampler = Sampler::new(9);
+        for _ in 0..10_000 {
+            let (a, b) = (sampler.range(-150.0, 150.0), sampler.range(-150.0, 150.0));
+            let (fa, fb) = (Q16_16::from_f64(a), Q16_16::from_f64(b));
+            let (a, b) = (fa.to_f64(), fb.to_f64());
+            assert_eq!((fa + fb).to_f64(), a + b);
+            assert_eq!((fa - fb).to_f64(), a - b);
+            assert!(((fa * fb).to_f64() - a * b).abs() <= 0.5 * ulp);
+            if b.abs() > 1e-3 {
+                assert!(((fa / fb).to_f64() - a / b).abs() < ulp);
+            }
+            assert!((fa.abs().sqrt().to_f64() - a.abs().sqrt()).abs() < ulp);
+        }
+
+        // Saturation instead of wrap-around
+        let big = Q16_16::from_int(30_000);
+        assert_eq!(big + big, Q16_16::MAX);
+        assert_eq!(-big * big, Q16_16::MIN);
+        assert!((big * big).is_saturated());
+        assert_eq!(Q16_16::ONE / Q16_16::ZERO, Q16_16::MAX);
+        assert_eq!(-Q16_16::MIN, Q16_16::MAX);
+        assert_eq!(Q16_16::from_f64(1e12), Q16_16::MAX);
+        assert_eq!(Q16_16::from_f64(f64::NAN), Q16_16::ZERO);
+        assert_eq!(serde_json::to_string(&Q16_16::ONE).unwrap(), "65536");
+    }
+
+    #[test]
+    fn test_rotor_products_against_f64() {
+        let fixed = |terms: &[BladeTerm<f64>]| -> Vec<BladeTerm<Q2_29>> {
+            terms.iter().map(|t| BladeTerm::from_basis(t.indices.clone(), Q2_29::from_f64(t.coefficient))).collect()
+        };
+        let mut sampler = Sampler::new(4);
+        for _ in 0..200 {
+            let rotor = sampler.rotor();
+            let v = sampler.unit_vector();
+            let reference = rotor.apply(v);
+
+            let r = Even::from_terms(fixed(Even::from(rotor).terms())).unwrap();
+            let vector: Vec<BladeTerm<f64>> = (0..3).map(|i| BladeTerm::new(vec![i as i32 + 1], v[i])).collect();
+            let x = Odd::from_terms(fixed(&vector)).unwrap();
+            let rotated = &(&r * &x) * &r.reverse();
+            for (i, &expected) in reference.iter().enumerate() {
+                let component: f64 = rotated
+                    .vector_part()
+                    .iter()
+                    .filter(|t| t.indices == [i as i32 + 1])
+                    .map(|t| t.coefficient.to_f64())
+                    .sum();
+                assert!((component - expected).abs() < 1e-7, "{} vs {}", component, expected);
+            }
+            // Unit norm survives in fixed point
+            let norm = operations::norm(&GATerm::Multivector(rotated.into_terms()));
+            assert!((norm.to_f64() - 1.0).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_pid_matches_f64_reference() {
+        let q = Q16_16::from_f64;
+        let mut pid = FixedPid::new(q(2.0), q(0.5), q(0.1)).with_limits(q(-10.0), q(10.0));
+        let (mut position, mut integral, mut previous) = (0.0f64, 0.0f64, None::<f64>);
+        let dt = 0.01;
+        for _ in 0..5000 {
+            let error = 1.0 - position;
+            let command = pid.update(q(error), q(dt)).to_f64();
+
+            // The same law in f64, fed the same error
+            integral = (integral + 0.5 * error * dt).clamp(-10.0, 10.0);
+            let derivative = previous.map_or(0.0, |p| 0.1 * (error - p) / dt);
+            previous = Some(error);
+            let reference = (2.0 * error + integral + derivative).clamp(-10.0, 10.0);
+
+            assert!((command - reference).abs() < 0.02, "{} vs {}", command, reference);
+            position += command * dt;
+        }
+        // Integral increments below one unit in the last place are lost, leaving a small offset
+        assert!((position - 1.0).abs() < 2e-3, "{}", position);
+        pid.reset();
+        assert_eq!(pid.update(q(1.0), q(dt)), q(2.0) + q(0.5) * q(1.0) * q(dt));
+    }
+}
@@ -0,0 +1,398 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Coordinate frames and the runtime frame graph
+//!
+//! Compile-time frames are marker types implementing [`Frame`]; transforms between
+//! them are typed as [`FrameTransform<To, From>`]. Frames discovered at runtime
+//! (per-marker, per-sensor, ...) live in a [`FrameGraph`], a tree of named frames
+//! whose edges hold timestamped motors. Lookups walk the tree through the common
+//! ancestor and interpolate each edge at the requested time.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::linalg::Vector3;
+use crate::motor::Motor;
+use crate::si_units::Time;
+
+/// Compile-time coordinate frame
+pub trait Frame {
+    const NAME: &'static str;
+}
+
+/// Rigid transform mapping coordinates in `From` to coordinates in `To`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTransform<To: Frame, From: Frame> {
+    motor: Motor,
+    _phantom: PhantomData<(To, From)>,
+}
+
+impl<To: Frame, From: Frame> FrameTransform<To, From> {
+    pub fn new(motor: Motor) -> Self {
+        Self {
+            motor,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(Motor::identity())
+    }
+
+    pub fn motor(&self) -> &Motor {
+        &self.motor
+    }
+
+    pub fn into_motor(self) -> Motor {
+        self.motor
+    }
+
+    pub fn inverse(&self) -> FrameTransform<From, To> {
+        FrameTransform::new(self.motor.inverse())
+    }
+
+    /// Map a point expressed in `From` into `To`
+    pub fn apply_point(&self, p: Vector3) -> Vector3 {
+        self.motor.apply_point(p)
+    }
+
+    /// Compose with a transform into `From`, yielding one from `Via` to `To`
+    pub fn then<Via: Frame>(&self, inner: &FrameTransform<From, Via>) -> FrameTransform<To, Via> {
+        FrameTransform::new(self.motor * inner.motor)
+    }
+}
+
+impl<A: Frame, B: Frame, C: Frame> std::ops::Mul<FrameTransform<B, C>> for FrameTransform<A, B> {
+    type Output = FrameTransform<A, C>;
+
+    fn mul(self, rhs: FrameTransform<B, C>) -> FrameTransform<A, C> {
+        self.then(&rhs)
+    }
+}
+
+/// Errors raised by frame graph queries and updates
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameError {
+    UnknownFrame(String),
+    NotConnected { target: String, source: String },
+    ExtrapolationRequired { frame: String, time: f64, earliest: f64, latest: f64 },
+    ParentConflict { frame: String, existing: String, requested: String },
+    CycleDetected { frame: String, parent: String },
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::UnknownFrame(name) => write!(f, "unknown frame '{}'", name),
+            FrameError::NotConnected { target, source } => {
+                write!(f, "frames '{}' and '{}' are not connected", target, source)
+            }
+            FrameError::ExtrapolationRequired { frame, time, earliest, latest } => write!(
+                f,
+                "lookup of '{}' at t={} s requires extrapolation (data covers {} s to {} s)",
+                frame, time, earliest, latest
+            ),
+            FrameError::ParentConflict { frame, existing, requested } => write!(
+                f,
+                "frame '{}' already has parent '{}', cannot attach to '{}'",
+                frame, existing, requested
+            ),
+            FrameError::CycleDetected { frame, parent } => {
+                write!(f, "attaching '{}' to '{}' would create a cycle", frame, parent)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// Edge from a child frame to its parent
+#[derive(Debug, Clone)]
+struct FrameLink {
+    parent: String,
+    is_static: bool,
+    /// `parent_T_child` samples sorted by time in seconds
+    history: Vec<(f64, Motor)>,
+}
+
+impl FrameLink {
+    fn motor_at(&self, child: &str, time: Option<f64>) -> Result<Motor, FrameError> {
+        let (first, last) = match (self.history.first(), self.history.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Err(FrameError::UnknownFrame(child.to_string())),
+        };
+        let time = match time {
+            Some(time) if !self.is_static => time,
+            _ => return Ok(last.1),
+        };
+        if time < first.0 || time > last.0 {
+            return Err(FrameError::ExtrapolationRequired {
+                frame: child.to_string(),
+                time,
+                earliest: first.0,
+                latest: last.0,
+            });
+        }
+
+        let upper = self.history.partition_point(|(t, _)| *t < time);
+        let (t1, m1) = self.history[upper];
+        if t1 == time || upper == 0 {
+            return Ok(m1);
+        }
+        let (t0, m0) = self.history[upper - 1];
+        Ok(m0.interpolate(&m1, (time - t0) / (t1 - t0)))
+    }
+}
+
+/// Runtime tree of named frames connected by timestamped motors
+#[derive(Debug, Clone, Default)]
+pub struct FrameGraph {
+    links: HashMap<String, FrameLink>,
+    roots: Vec<String>,
+    cache_duration: Option<f64>,
+}
+
+impl FrameGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only samples newer than `duration` behind the latest sample of each edge
+    pub fn with_cache_duration(mut self, duration: Time<f64>) -> Self {
+        self.cache_duration = Some(*duration.value());
+        self
+    }
+
+    /// Register a frame without a parent
+    pub fn add_frame(&mut self, name: &str) {
+        if !self.has_frame(name) {
+            self.roots.push(name.to_string());
+        }
+    }
+
+    pub fn has_frame(&self, name: &str) -> bool {
+        self.links.contains_key(name) || self.roots.iter().any(|root| root == name)
+    }
+
+    pub fn parent_of(&self, name: &str) -> Option<&str> {
+        self.links.get(name).map(|link| link.parent.as_str())
+    }
+
+    /// All known frame names, sorted
+    pub fn frames(&self) -> Vec<&str> {
+        let mut frames: Vec<&str> = self
+            .roots
+            .iter()
+            .map(String::as_str)
+            .chain(self.links.keys().map(String::as_str))
+            .collect();
+        frames.sort_unstable();
+        frames
+    }
+
+    /// Record `parent_T_child` at `stamp`
+    pub fn set_transform(&mut self, parent: &str, child: &str, motor: Motor, stamp: Time<f64>) -> Result<(), FrameError> {
+        self.insert(parent, child, motor, *stamp.value(), false)
+    }
+
+    /// Record a time-invariant `parent_T_child`
+    pub fn set_static_transform(&mut self, parent: &str, child: &str, motor: Motor) -> Result<(), FrameError> {
+        self.insert(parent, child, motor, 0.0, true)
+    }
+
+    fn insert(&mut self, parent: &str, child: &str, motor: Motor, time: f64, is_static: bool) -> Result<(), FrameError> {
+        if let Some(existing) = self.parent_of(child) {
+            if existing != parent {
+                return Err(FrameError::ParentConflict {
+                    frame: child.to_string(),
+                    existing: existing.to_string(),
+                    requested: parent.to_string(),
+                });
+            }
+        }
+        if self.ancestors(parent).iter().any(|frame| frame == child) {
+            return Err(FrameError::CycleDetected {
+                frame: child.to_string(),
+                parent: parent.to_string(),
+            });
+        }
+
+        self.add_frame(parent);
+        self.roots.retain(|root| root != child);
+
+        let link = self.links.entry(child.to_string()).or_insert_with(|| FrameLink {
+            parent: parent.to_string(),
+            is_static,
+            history: Vec::new(),
+        });
+        link.is_static = is_static;
+        if is_static {
+            link.history = vec![(time, motor)];
+            return Ok(());
+        }
+
+        let index = link.history.partition_point(|(t, _)| *t < time);
+        match link.history.get(index) {
+            Some((t, _)) if *t == time => link.history[index].1 = motor,
+            _ => link.history.insert(index, (time, motor)),
+        }
+        if let (Some(duration), Some(&(latest, _))) = (self.cache_duration, link.history.last()) {
+            link.history.retain(|(t, _)| latest - *t <= duration);
+        }
+        Ok(())
+    }
+
+    /// Chain of frames from `name` up to its root, starting with `name`
+    fn ancestors(&self, name: &str) -> Vec<String> {
+        let mut chain = vec![name.to_string()];
+        let mut current = name;
+        while let Some(parent) = self.parent_of(current) {
+            chain.push(parent.to_string());
+            current = parent;
+        }
+        chain
+    }
+
+    /// `stop_T_frame`, composed along the path from `frame` up to its ancestor `stop`
+    fn chain_to(&self, frame: &str, stop: &str, time: Option<f64>) -> Result<Motor, FrameError> {
+        let mut motor = Motor::identity();
+        let mut current = frame;
+        while current != stop {
+            let link = &self.links[current];
+            motor = link.motor_at(current, time)? * motor;
+            current = &link.parent;
+        }
+        Ok(motor)
+    }
+
+    fn resolve(&self, target: &str, source: &str, time: Option<f64>) -> Result<Motor, FrameError> {
+        for name in [target, source] {
+            if !self.has_frame(name) {
+                return Err(FrameError::UnknownFrame(name.to_string()));
+            }
+        }
+
+        let target_chain = self.ancestors(target);
+        let common = self
+            .ancestors(source)
+            .into_iter()
+            .find(|frame| target_chain.contains(frame))
+            .ok_or_else(|| FrameError::NotConnected {
+                target: target.to_string(),
+                source: source.to_string(),
+            })?;
+
+        let common_t_source = self.chain_to(source, &common, time)?;
+        let common_t_target = self.chain_to(target, &common, time)?;
+        Ok(common_t_target.inverse() * common_t_source)
+    }
+
+    /// `target_T_source` at `time`, interpolating between recorded samples
+    pub fn lookup(&self, target: &str, source: &str, time: Time<f64>) -> Result<Motor, FrameError> {
+        self.resolve(target, source, Some(*time.value()))
+    }
+
+    /// `target_T_source` using the most recent sample of every edge
+    pub fn lookup_latest(&self, target: &str, source: &str) -> Result<Motor, FrameError> {
+        self.resolve(target, source, None)
+    }
+
+    /// Typed lookup between compile-time frames
+    pub fn lookup_typed<To: Frame, From: Frame>(&self, time: Time<f64>) -> Result<FrameTransform<To, From>, FrameError> {
+        self.lookup(To::NAME, From::NAME, time).map(FrameTransform::new)
+    }
+
+    /// Record a typed `Parent_T_Child` at `stamp`
+    pub fn set_typed_transform<Parent: Frame, Child: Frame>(
+        &mut self,
+        transform: &FrameTransform<Parent, Child>,
+        stamp: Time<f64>,
+    ) -> Result<(), FrameError> {
+        self.set_transform(Parent::NAME, Child::NAME, *transform.motor(), stamp)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motor::Rotor;
+    use crate::si_units::{units::seconds, TAU};
+
+    struct World;
+    impl Frame for World {
+        const NAME: &'static str = "world";
+    }
+
+    struct Base;
+    impl Frame for Base {
+        const NAME: &'static str = "base";
+    }
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_chain_lookup() {
+        let mut graph = FrameGraph::new();
+        graph.set_static_transform("world", "base", Motor::from_translation([1.0, 0.0, 0.0])).unwrap();
+        let rotation = Rotor::from_axis_angle([0.0, 0.0, 1.0], TAU / 4.0);
+        graph.set_static_transform("base", "camera", Motor::new([0.0, 0.0, 1.0], rotation)).unwrap();
+        graph.set_static_transform("world", "marker_7", Motor::from_translation([0.0, 2.0, 0.0])).unwrap();
+
+        let world_t_camera = graph.lookup_latest("world", "camera").unwrap();
+        assert_close(world_t_camera.apply_point([1.0, 0.0, 0.0]), [1.0, 1.0, 1.0]);
+
+        let camera_t_world = graph.lookup_latest("camera", "world").unwrap();
+        assert_close(camera_t_world.apply_point([1.0, 1.0, 1.0]), [1.0, 0.0, 0.0]);
+
+        let marker_t_camera = graph.lookup_latest("marker_7", "camera").unwrap();
+        assert_close(marker_t_camera.apply_point([0.0, 0.0, 0.0]), [1.0, -2.0, 1.0]);
+
+        assert_eq!(graph.frames(), vec!["base", "camera", "marker_7", "world"]);
+        assert_eq!(graph.lookup_latest("world", "nowhere"), Err(FrameError::UnknownFrame("nowhere".to_string())));
+    }
+
+    #[test]
+    fn test_interpolation_and_extrapolation() {
+        let mut graph = FrameGraph::new();
+        graph.set_transform("odom", "base", Motor::from_translation([0.0, 0.0, 0.0]), seconds(0.0)).unwrap();
+        graph.set_transform("odom", "base", Motor::from_translation([2.0, 0.0, 0.0]), seconds(2.0)).unwrap();
+
+        let halfway = graph.lookup("odom", "base", seconds(0.5)).unwrap();
+        assert_close(halfway.translation, [0.5, 0.0, 0.0]);
+
+        let error = graph.lookup("odom", "base", seconds(3.0)).unwrap_err();
+        assert!(matches!(error, FrameError::ExtrapolationRequired { .. }));
+
+        graph.add_frame("map");
+        assert!(matches!(graph.lookup_latest("map", "base"), Err(FrameError::NotConnected { .. })));
+        assert!(matches!(
+            graph.set_static_transform("map", "base", Motor::identity()),
+            Err(FrameError::ParentConflict { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cache_duration_and_typed_bridge() {
+        let mut graph = FrameGraph::new().with_cache_duration(seconds(1.0));
+        for step in 0..5 {
+            let t = step as f64;
+            let transform = FrameTransform::<World, Base>::new(Motor::from_translation([t, 0.0, 0.0]));
+            graph.set_typed_transform(&transform, seconds(t)).unwrap();
+        }
+
+        assert!(graph.lookup("world", "base", seconds(1.0)).is_err());
+
+        let world_t_base = graph.lookup_typed::<World, Base>(seconds(3.5)).unwrap();
+        let base_t_world: FrameTransform<Base, World> = graph.lookup_typed(seconds(3.5)).unwrap();
+        assert_close(world_t_base.apply_point([0.0; 3]), [3.5, 0.0, 0.0]);
+        assert_close((world_t_base * base_t_world).apply_point([1.0, 2.0, 3.0]), [1.0, 2.0, 3.0]);
+    }
+}
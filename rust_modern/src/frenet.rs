@@ -0,0 +1,439 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Frenet frames along parametric paths
+//!
+//! A [`SpaceCurve`] gives positions and the first three derivatives along a
+//! parameter, which may be time (a [`BSpline`] of lengths) or arc length (a
+//! [`CurvedPath`]). With `r'`, `r''` and `r'''` those derivatives,
+//!
+//! - the tangent is `T = r' / |r'|`, the binormal `B = r' × r'' / |r' × r''|`
+//!   and the principal normal `N = B × T`, pointing to the center of curvature;
+//! - the curvature is `κ = |r' × r''| / |r'|³` and the torsion
+//!   `τ = (r' × r'') · r''' / |r' × r''|²`, both in 1/m whatever the parameter.
+//!
+//! A [`FrenetFrame`] holds the frame as the rotor taking `e1, e2, e3` to
+//! `T, N, B`, so it composes with motors like any other frame. `N` and `B` are
+//! undefined on straight stretches and flip at inflections; [`frames_along`]
+//! carries the last frame across both by the smallest rotation that follows
+//! the tangent, so the frames stay continuous.
+//!
+//! A [`FrenetProjection`] expresses points, such as obstacles, in Frenet
+//! coordinates: arc length along the path and offsets along `N` and `B`, as
+//! lane- and corridor-following controllers use them.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::linalg::{self, Vector3};
+use crate::motor::{Motor, Rotor};
+use crate::path_primitives::{CurvedPath, Gear};
+use crate::si_units::{Curvature, Length, Measure, Time, TAU};
+use crate::spline::BSpline;
+
+/// Cross products below this fraction of `|r'|³` count as a straight stretch
+const STRAIGHT_TOLERANCE: f64 = 1e-9;
+
+/// Points where the Frenet frame is undefined
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrenetError {
+    /// The curve does not move at this parameter
+    Stationary(f64),
+    /// The curve is locally straight, so it has no normal
+    Straight(f64),
+}
+
+impl fmt::Display for FrenetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrenetError::Stationary(t) => write!(f, "curve is stationary at parameter {}", t),
+            FrenetError::Straight(t) => write!(f, "curve is straight at parameter {}, so it has no normal", t),
+        }
+    }
+}
+
+impl std::error::Error for FrenetError {}
+
+/// Curve in space with derivatives along its parameter
+pub trait SpaceCurve {
+    /// First and last parameter
+    fn domain(&self) -> (f64, f64);
+    fn position(&self, t: f64) -> Vector3;
+    /// `r'`, `r''` and `r'''`
+    fn derivatives(&self, t: f64) -> [Vector3; 3];
+}
+
+/// A spline of positions, parametrized by time
+impl SpaceCurve for BSpline<Length, 3> {
+    fn domain(&self) -> (f64, f64) {
+        let (start, end) = BSpline::domain(self);
+        (*start.value(), *end.value())
+    }
+
+    fn position(&self, t: f64) -> Vector3 {
+        self.value(Time::new(t)).map(Measure::raw)
+    }
+
+    fn derivatives(&self, t: f64) -> [Vector3; 3] {
+        let t = Time::new(t);
+        let velocity = self.derivative();
+        let acceleration = velocity.derivative();
+        [self.rate(t).map(Measure::raw), velocity.rate(t).map(Measure::raw), acceleration.rate(t).map(Measure::raw)]
+    }
+}
+
+/// A Dubins or Reeds-Shepp path in the horizontal plane, parametrized by arc
+/// length; the tangent follows the direction of travel, so it turns around in
+/// reverse
+impl SpaceCurve for CurvedPath {
+    fn domain(&self) -> (f64, f64) {
+        (0.0, *self.length().value())
+    }
+
+    fn position(&self, s: f64) -> Vector3 {
+        let pose = self.pose_at(Length::new(s));
+        [*pose.east.value(), *pose.north.value(), 0.0]
+    }
+
+    fn derivatives(&self, s: f64) -> [Vector3; 3] {
+        let s_typed = Length::new(s);
+        let (sin, cos) = self.pose_at(s_typed).heading.value().sin_cos();
+        let gear = if self.gear_at(s_typed) == Gear::Reverse { -1.0 } else { 1.0 };
+        let k = *self.curvature_at(s_typed).value();
+        let tangent = [gear * cos, gear * sin, 0.0];
+        [tangent, [-k * sin, k * cos, 0.0], linalg::scale(tangent, -k * k)]
+    }
+}
+
+/// Tangent, normal and binormal at a point of a curve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FrenetFrame {
+    /// Curve parameter
+    pub parameter: f64,
+    pub origin: Vector3,
+    /// Takes `e1, e2, e3` to the tangent, normal and binormal
+    pub rotor: Rotor,
+    /// `|dr/dt|`, in meters per unit of parameter
+    pub speed: f64,
+    pub curvature: Curvature,
+    pub torsion: Curvature,
+}
+
+impl FrenetFrame {
+    /// Frame at parameter `t`
+    pub fn at(curve: &impl SpaceCurve, t: f64) -> Result<Self, FrenetError> {
+        let [first, second, third] = curve.derivatives(t);
+        let speed = linalg::norm(first);
+        if speed < f64::EPSILON {
+            return Err(FrenetError::Stationary(t));
+        }
+        let binormal = linalg::cross(first, second);
+        let area = linalg::norm(binormal);
+        if area < STRAIGHT_TOLERANCE * speed.powi(3) {
+            return Err(FrenetError::Straight(t));
+        }
+        let tangent = linalg::scale(first, 1.0 / speed);
+        let binormal = linalg::scale(binormal, 1.0 / area);
+        Ok(Self {
+            parameter: t,
+            origin: curve.position(t),
+            rotor: frame_rotor(tangent, linalg::cross(binormal, tangent), binormal),
+            speed,
+            curvature: Curvature::new(area / speed.powi(3)),
+            torsion: Curvature::new(linalg::dot(linalg::cross(first, second), third) / (area * area)),
+        })
+    }
+
+    pub fn tangent(&self) -> Vector3 {
+        self.rotor.apply([1.0, 0.0, 0.0])
+    }
+
+    pub fn normal(&self) -> Vector3 {
+        self.rotor.apply([0.0, 1.0, 0.0])
+    }
+
+    pub fn binormal(&self) -> Vector3 {
+        self.rotor.apply([0.0, 0.0, 1.0])
+    }
+
+    /// Motor from the frame's coordinates to the curve's
+    pub fn motor(&self) -> Motor {
+        Motor::new(self.origin, self.rotor)
+    }
+}
+
+/// Rotor whose rotation matrix has columns `x`, `y` and `z`
+fn frame_rotor(x: Vector3, y: Vector3, z: Vector3) -> Rotor {
+    Rotor::from_rotation_matrix(&[[x[0], y[0], z[0]], [x[1], y[1], z[1]], [x[2], y[2], z[2]]])
+}
+
+/// Smallest rotation taking `previous`'s tangent to the unit vector
+/// `tangent`, composed onto its frame; a reversed tangent, at a cusp, turns
+/// about the binormal
+fn carry(previous: &FrenetFrame, tangent: Vector3) -> Rotor {
+    let from = previous.tangent();
+    let axis = linalg::cross(from, tangent);
+    let turn = if linalg::norm(axis) > f64::EPSILON {
+        Rotor::from_axis_angle(axis, linalg::norm(axis).atan2(linalg::dot(from, tangent)))
+    } else if linalg::dot(from, tangent) < 0.0 {
+        Rotor::from_axis_angle(previous.binormal(), TAU / 2.0)
+    } else {
+        Rotor::identity()
+    };
+    turn * previous.rotor
+}
+
+/// Frames at `count` evenly spaced parameters, ends included. Where the
+/// curve is straight, or the Frenet normal would flip at an inflection, the
+/// previous frame is turned onto the new tangent instead, and the curvature is
+/// negative while the normal points away from the center of curvature. A curve
+/// straight from its start begins with the horizontal normal left of its
+/// tangent.
+pub fn frames_along(curve: &impl SpaceCurve, count: usize) -> Result<Vec<FrenetFrame>, FrenetError> {
+    let (start, end) = curve.domain();
+    let count = count.max(2);
+    let mut frames: Vec<FrenetFrame> = Vec::with_capacity(count);
+    for k in 0..count {
+        let t = start + (end - start) * k as f64 / (count - 1) as f64;
+        let frame = match (FrenetFrame::at(curve, t), frames.last()) {
+            (Ok(frame), None) => frame,
+            (Ok(frame), Some(previous)) if linalg::dot(frame.normal(), previous.normal()) >= 0.0 => frame,
+            (Ok(frame), Some(previous)) => FrenetFrame {
+                rotor: carry(previous, frame.tangent()),
+                curvature: Curvature::new(-frame.curvature.value()),
+                ..frame
+            },
+            (Err(FrenetError::Straight(_)), previous) => {
+                let first = curve.derivatives(t)[0];
+                let speed = linalg::norm(first);
+                let tangent = linalg::scale(first, 1.0 / speed);
+                let rotor = match previous {
+                    Some(previous) => carry(previous, tangent),
+                    None => initial_rotor(tangent),
+                };
+                FrenetFrame {
+                    parameter: t,
+                    origin: curve.position(t),
+                    rotor,
+                    speed,
+                    curvature: Curvature::new(0.0),
+                    torsion: Curvature::new(0.0),
+                }
+            }
+            (Err(error), _) => return Err(error),
+        };
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+/// Frame on a straight start: the normal is the horizontal left of the
+/// tangent, or perpendicular to `e2` when the tangent is vertical
+fn initial_rotor(tangent: Vector3) -> Rotor {
+    let mut normal = linalg::cross([0.0, 0.0, 1.0], tangent);
+    if linalg::norm(normal) < 1e-6 {
+        normal = linalg::cross(tangent, [0.0, 1.0, 0.0]);
+    }
+    let normal = linalg::scale(normal, 1.0 / linalg::norm(normal));
+    frame_rotor(tangent, normal, linalg::cross(tangent, normal))
+}
+
+/// A point in Frenet coordinates of a curve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FrenetPoint {
+    /// Parameter of the nearest point on the curve
+    pub parameter: f64,
+    /// Arc length from the start of the curve to the nearest point
+    pub arc_length: Length,
+    /// Offset along the normal
+    pub lateral: Length,
+    /// Offset along the binormal
+    pub vertical: Length,
+}
+
+/// Frames sampled along a curve, with their arc lengths, for projecting points
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrenetProjection<C> {
+    curve: C,
+    frames: Vec<FrenetFrame>,
+    arc_lengths: Vec<f64>,
+}
+
+impl<C: SpaceCurve> FrenetProjection<C> {
+    /// Sample `count` frames along `curve`, see [`frames_along`]
+    pub fn new(curve: C, count: usize) -> Result<Self, FrenetError> {
+        let frames = frames_along(&curve, count)?;
+        let mut arc_lengths = vec![0.0];
+        for pair in frames.windows(2) {
+            let last = arc_lengths[arc_lengths.len() - 1];
+            arc_lengths.push(last + arc_length(&curve, pair[0].parameter, pair[1].parameter));
+        }
+        Ok(Self { curve, frames, arc_lengths })
+    }
+
+    pub fn curve(&self) -> &C {
+        &self.curve
+    }
+
+    pub fn frames(&self) -> &[FrenetFrame] {
+        &self.frames
+    }
+
+    pub fn length(&self) -> Length {
+        Length::new(self.arc_lengths[self.arc_lengths.len() - 1])
+    }
+
+    /// Frame at parameter `t`, interpolated between the sampled frames
+    pub fn frame(&self, t: f64) -> FrenetFrame {
+        let k = self.segment(t);
+        let (here, next) = (&self.frames[k], &self.frames[k + 1]);
+        let w = ((t - here.parameter) / (next.parameter - here.parameter)).clamp(0.0, 1.0);
+        let derivatives = self.curve.derivatives(t);
+        FrenetFrame {
+            parameter: t,
+            origin: self.curve.position(t),
+            rotor: here.rotor.slerp(&next.rotor, w),
+            speed: linalg::norm(derivatives[0]),
+            curvature: Curvature::new(here.curvature.value() + w * (next.curvature.value() - here.curvature.value())),
+            torsion: Curvature::new(here.torsion.value() + w * (next.torsion.value() - here.torsion.value())),
+        }
+    }
+
+    /// Frenet coordinates of `point` relative to the nearest point of the curve
+    pub fn project(&self, point: Vector3) -> FrenetPoint {
+        // Nearest sampled chord, then Newton's method on (r(t) - p) · r'(t) = 0
+        let mut best = (f64::INFINITY, self.frames[0].parameter);
+        for pair in self.frames.windows(2) {
+            let chord = linalg::sub(pair[1].origin, pair[0].origin);
+            let offset = linalg::sub(point, pair[0].origin);
+            let squared = linalg::dot(chord, chord);
+            let w = if squared > 0.0 { (linalg::dot(offset, chord) / squared).clamp(0.0, 1.0) } else { 0.0 };
+            let distance = linalg::norm(linalg::sub(offset, linalg::scale(chord, w)));
+            if distance < best.0 {
+                best = (distance, pair[0].parameter + w * (pair[1].parameter - pair[0].parameter));
+            }
+        }
+        let (start, end) = self.curve.domain();
+        let mut t = best.1;
+        for _ in 0..10 {
+            let [first, second, _] = self.curve.derivatives(t);
+            let offset = linalg::sub(self.curve.position(t), point);
+            let slope = linalg::dot(first, first) + linalg::dot(offset, second);
+            if slope <= 0.0 {
+                break;
+            }
+            let next = (t - linalg::dot(offset, first) / slope).clamp(start, end);
+            let converged = (next - t).abs() < 1e-12 * (end - start).max(1.0);
+            t = next;
+            if converged {
+                break;
+            }
+        }
+
+        let frame = self.frame(t);
+        let k = self.segment(t);
+        let offset = linalg::sub(point, frame.origin);
+        FrenetPoint {
+            parameter: t,
+            arc_length: Length::new(self.arc_lengths[k] + arc_length(&self.curve, self.frames[k].parameter, t)),
+            lateral: Length::new(linalg::dot(offset, frame.normal())),
+            vertical: Length::new(linalg::dot(offset, frame.binormal())),
+        }
+    }
+
+    /// Index of the sampled interval holding `t`
+    fn segment(&self, t: f64) -> usize {
+        self.frames[1..self.frames.len() - 1].partition_point(|frame| frame.parameter <= t)
+    }
+}
+
+/// Length of the curve between two parameters, by Simpson's rule
+fn arc_length(curve: &impl SpaceCurve, from: f64, to: f64) -> f64 {
+    let speed = |t: f64| linalg::norm(curve.derivatives(t)[0]);
+    (to - from) / 6.0 * (speed(from) + 4.0 * speed(0.5 * (from + to)) + speed(to))
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_primitives::{dubins, PlanarPose, TurningRadius};
+    use crate::si_units::Angle;
+
+    /// Helix of radius `a` and pitch `2π b`, parametrized by angle
+    struct Helix {
+        a: f64,
+        b: f64,
+    }
+
+    impl SpaceCurve for Helix {
+        fn domain(&self) -> (f64, f64) {
+            (0.0, TAU)
+        }
+
+        fn position(&self, t: f64) -> Vector3 {
+            [self.a * t.cos(), self.a * t.sin(), self.b * t]
+        }
+
+        fn derivatives(&self, t: f64) -> [Vector3; 3] {
+            let (sin, cos) = t.sin_cos();
+            let a = self.a;
+            [[-a * sin, a * cos, self.b], [-a * cos, -a * sin, 0.0], [a * sin, -a * cos, 0.0]]
+        }
+    }
+
+    #[test]
+    fn test_helix_curvature_and_torsion() {
+        let helix = Helix { a: 2.0, b: 0.5 };
+        let frame = FrenetFrame::at(&helix, 1.0).unwrap();
+        let denominator = 2.0 * 2.0 + 0.5 * 0.5;
+        assert!((frame.curvature.value() - 2.0 / denominator).abs() < 1e-12);
+        assert!((frame.torsion.value() - 0.5 / denominator).abs() < 1e-12);
+        // The normal points at the axis
+        let inward = [-(1.0f64).cos(), -(1.0f64).sin(), 0.0];
+        assert!(linalg::norm(linalg::sub(frame.normal(), inward)) < 1e-9);
+        assert!(linalg::norm(linalg::sub(frame.binormal(), linalg::cross(frame.tangent(), frame.normal()))) < 1e-9);
+        // The motor maps the frame's origin onto the curve
+        assert!(linalg::norm(linalg::sub(frame.motor().apply_point([0.0; 3]), helix.position(1.0))) < 1e-12);
+    }
+
+    #[test]
+    fn test_spline_frames_are_continuous() {
+        let points = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 1.0, 0.5], [3.0, 1.0, 0.0], [4.0, 0.0, 0.0]];
+        let spline = BSpline::clamped(3, points.iter().map(|p| p.map(Length::new)).collect(), Time::new(4.0)).unwrap();
+        let frames = frames_along(&spline, 100).unwrap();
+        for pair in frames.windows(2) {
+            let step = (pair[0].rotor.reverse() * pair[1].rotor).angle();
+            assert!(step < 0.3, "frames jump by {} rad at {}", step, pair[1].parameter);
+            assert!(linalg::dot(pair[1].tangent(), pair[1].normal()).abs() < 1e-9);
+        }
+        assert!(matches!(FrenetFrame::at(&Helix { a: 0.0, b: 0.0 }, 0.0), Err(FrenetError::Stationary(_))));
+    }
+
+    #[test]
+    fn test_obstacles_in_frenet_coordinates() {
+        let pose = |east: f64, north: f64, heading: f64| PlanarPose::new(Length::new(east), Length::new(north), Angle::new(heading));
+        let radius = TurningRadius::new(Length::new(5.0)).unwrap();
+        // Straight 10 m east, then a quarter turn left onto north
+        let path = dubins(pose(0.0, 0.0, 0.0), pose(15.0, 5.0, TAU / 4.0), radius).unwrap();
+        assert!((path.length().value() - (10.0 + TAU / 4.0 * 5.0)).abs() < 1e-9, "{:?}", path);
+        let projection = FrenetProjection::new(path, 200).unwrap();
+        assert!((projection.length().value() - projection.curve().length().value()).abs() < 1e-6);
+
+        // On the straight the carried-over normal points left (north)
+        let obstacle = projection.project([4.0, -1.5, 0.0]);
+        assert!((obstacle.arc_length.value() - 4.0).abs() < 1e-6);
+        assert!((obstacle.lateral.value() + 1.5).abs() < 1e-6);
+        assert!(obstacle.vertical.value().abs() < 1e-12);
+
+        // On the arc, a point 1 m inside the turn at its middle
+        let (sin, cos) = (TAU / 8.0).sin_cos();
+        let inside = projection.project([10.0 + 4.0 * sin, 5.0 - 4.0 * cos, 0.0]);
+        assert!((inside.arc_length.value() - (10.0 + TAU / 8.0 * 5.0)).abs() < 1e-6, "{:?}", inside);
+        assert!((inside.lateral.value() - 1.0).abs() < 1e-6);
+        let frame = projection.frame(inside.parameter);
+        assert!((frame.curvature.value() - 0.2).abs() < 1e-12);
+    }
+}
@@ -2,12 +2,128 @@
 //
 // SPDX-License-Identifier: MPL-2.0
 
+use std::fmt;
 use std::marker::PhantomData;
 use serde::{Deserialize, Serialize};
+use crate::multivector::MAX_DIMENSION;
 
-/// Type alias for blade indices
+/// Raw integer blade index, as written in literals and read from files
 pub type Index = i32;
 
+/// Dimension of the default algebra, the conformal algebra Cl(4,1)
+pub const ALGEBRA_DIMENSION: u8 = 5;
+
+/// Integers that name no basis vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexError {
+    /// Basis vectors are numbered from `e1`
+    NotPositive(Index),
+    OutOfRange { index: Index, dimension: u8 },
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexError::NotPositive(index) => write!(f, "basis index {} is not positive; basis vectors are numbered from e1", index),
+            IndexError::OutOfRange { index, dimension } => {
+                write!(f, "basis index e{} outside a {}-dimensional algebra", index, dimension)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IndexError {}
+
+/// Basis vector `e_i` of an algebra, with `i` checked to lie in `1..=dimension`
+///
+/// Integers convert with [`TryFrom`], which checks them against the default
+/// algebra's [`ALGEBRA_DIMENSION`]; [`BasisIndex::new`] checks against any
+/// dimension up to [`MAX_DIMENSION`]. Serialized as the plain integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Index", into = "Index")]
+pub struct BasisIndex(u8);
+
+impl BasisIndex {
+    /// Check `index` against a `dimension`-dimensional algebra; dimensions past
+    /// [`MAX_DIMENSION`] are treated, and reported, as [`MAX_DIMENSION`]
+    pub const fn new(index: Index, dimension: u8) -> Result<Self, IndexError> {
+        let dimension = if dimension < MAX_DIMENSION { dimension } else { MAX_DIMENSION };
+        if index < 1 {
+            return Err(IndexError::NotPositive(index));
+        }
+        if index > dimension as Index {
+            return Err(IndexError::OutOfRange { index, dimension });
+        }
+        Ok(Self(index as u8))
+    }
+
+    /// `e1..=e_dimension` in order
+    pub fn basis(dimension: u8) -> impl Iterator<Item = BasisIndex> {
+        (1..=dimension.min(MAX_DIMENSION)).map(BasisIndex)
+    }
+
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    pub const fn index(self) -> Index {
+        self.0 as Index
+    }
+
+    /// Check `indices` against the default algebra
+    pub fn from_indices(indices: &[Index]) -> Result<Vec<BasisIndex>, IndexError> {
+        indices.iter().map(|&index| BasisIndex::try_from(index)).collect()
+    }
+}
+
+impl TryFrom<Index> for BasisIndex {
+    type Error = IndexError;
+
+    fn try_from(index: Index) -> Result<Self, IndexError> {
+        BasisIndex::new(index, ALGEBRA_DIMENSION)
+    }
+}
+
+impl From<BasisIndex> for Index {
+    fn from(index: BasisIndex) -> Self {
+        index.index()
+    }
+}
+
+impl PartialEq<Index> for BasisIndex {
+    fn eq(&self, other: &Index) -> bool {
+        self.index() == *other
+    }
+}
+
+impl fmt::Display for BasisIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "e{}", self.0)
+    }
+}
+
+/// Unwrap a checked index in the panicking constructors
+pub(crate) fn checked<T>(result: Result<T, IndexError>) -> T {
+    result.unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Check vector components against the default algebra
+pub(crate) fn check_vector<T>(components: Vec<(Index, T)>) -> Result<Vec<(BasisIndex, T)>, IndexError> {
+    components.into_iter().map(|(i, c)| Ok((i.try_into()?, c))).collect()
+}
+
+/// Check bivector components against the default algebra
+pub(crate) fn check_bivector<T>(components: Vec<(Index, Index, T)>) -> Result<Vec<(BasisIndex, BasisIndex, T)>, IndexError> {
+    components.into_iter().map(|(i, j, c)| Ok((i.try_into()?, j.try_into()?, c))).collect()
+}
+
+/// Check trivector components against the default algebra
+pub(crate) fn check_trivector<T>(
+    components: Vec<(Index, Index, Index, T)>,
+) -> Result<Vec<(BasisIndex, BasisIndex, BasisIndex, T)>, IndexError> {
+    components.into_iter().map(|(i, j, k, c)| Ok((i.try_into()?, j.try_into()?, k.try_into()?, c))).collect()
+}
+
 /// Grade enumeration for compile-time grade tracking
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Grade {
@@ -15,9 +131,47 @@ pub enum Grade {
     Vector = 1,
     Bivector = 2,
     Trivector = 3,
+    Quadvector = 4,
+    Pseudoscalar = 5, // Highest grade; its index is the algebra dimension
     Multivector = -1, // General case
 }
 
+impl Grade {
+    /// Grade of a `grade`-blade in an algebra of the given dimension
+    ///
+    /// The top grade is always `Pseudoscalar`; grades above the dimension, and
+    /// unnamed grades of higher-dimensional algebras, map to `Multivector`.
+    pub const fn from_index(grade: u8, dimension: u8) -> Grade {
+        if grade > dimension {
+            return Grade::Multivector;
+        }
+        if grade == dimension {
+            return Grade::Pseudoscalar;
+        }
+        match grade {
+            0 => Grade::Scalar,
+            1 => Grade::Vector,
+            2 => Grade::Bivector,
+            3 => Grade::Trivector,
+            4 => Grade::Quadvector,
+            _ => Grade::Multivector,
+        }
+    }
+
+    /// Numeric grade in an algebra of the given dimension, `None` for mixed grades
+    pub const fn to_index(self, dimension: u8) -> Option<u8> {
+        match self {
+            Grade::Scalar => Some(0),
+            Grade::Vector => Some(1),
+            Grade::Bivector => Some(2),
+            Grade::Trivector => Some(3),
+            Grade::Quadvector => Some(4),
+            Grade::Pseudoscalar => Some(dimension),
+            Grade::Multivector => None,
+        }
+    }
+}
+
 /// Scalar wrapper for type safety
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Scalar<T> {
@@ -59,22 +213,30 @@ impl<T: std::ops::Mul<Output = T>> std::ops::Mul for Scalar<T> {
 /// Blade term representation for general multivectors
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BladeTerm<T> {
-    pub indices: Vec<Index>,
+    pub indices: Vec<BasisIndex>,
     pub coefficient: T,
 }
 
 impl<T> BladeTerm<T> {
+    /// Term of the blade with the given integer indices
+    ///
+    /// Panics if an index lies outside the default algebra; see [`BladeTerm::try_new`].
     pub fn new(indices: Vec<Index>, coefficient: T) -> Self {
+        checked(Self::try_new(&indices, coefficient))
+    }
+
+    pub fn try_new(indices: &[Index], coefficient: T) -> Result<Self, IndexError> {
+        Ok(Self::from_basis(BasisIndex::from_indices(indices)?, coefficient))
+    }
+
+    pub fn from_basis(indices: Vec<BasisIndex>, coefficient: T) -> Self {
         Self { indices, coefficient }
     }
 
     pub fn grade(&self) -> Grade {
-        match self.indices.len() {
-            0 => Grade::Scalar,
-            1 => Grade::Vector,
-            2 => Grade::Bivector,
-            3 => Grade::Trivector,
-            _ => Grade::Multivector,
+        match u8::try_from(self.indices.len()) {
+            Ok(grade) => Grade::from_index(grade, ALGEBRA_DIMENSION),
+            Err(_) => Grade::Multivector,
         }
     }
 }
@@ -86,9 +248,9 @@ impl<T> BladeTerm<T> {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GATerm<T> {
     Scalar(Scalar<T>),                                        // 0-vector (scalar)
-    Vector(Vec<(Index, T)>),                                  // 1-vector
-    Bivector(Vec<(Index, Index, T)>),                         // 2-vector (bivector)
-    Trivector(Vec<(Index, Index, Index, T)>),                 // 3-vector (trivector)
+    Vector(Vec<(BasisIndex, T)>),                             // 1-vector
+    Bivector(Vec<(BasisIndex, BasisIndex, T)>),               // 2-vector (bivector)
+    Trivector(Vec<(BasisIndex, BasisIndex, BasisIndex, T)>),  // 3-vector (trivector)
     Multivector(Vec<BladeTerm<T>>),                          // General multivector
 }
 
@@ -111,21 +273,36 @@ impl<T> GATerm<T> {
 }
 
 /// Factory functions for creating GA terms
+///
+/// The integer forms panic on an index outside the default algebra; the
+/// `try_` forms report it instead.
 impl<T> GATerm<T> {
     pub fn scalar(value: T) -> Self {
         GATerm::Scalar(Scalar::new(value))
     }
 
     pub fn vector(components: Vec<(Index, T)>) -> Self {
-        GATerm::Vector(components)
+        checked(Self::try_vector(components))
     }
 
     pub fn bivector(components: Vec<(Index, Index, T)>) -> Self {
-        GATerm::Bivector(components)
+        checked(Self::try_bivector(components))
     }
 
     pub fn trivector(components: Vec<(Index, Index, Index, T)>) -> Self {
-        GATerm::Trivector(components)
+        checked(Self::try_trivector(components))
+    }
+
+    pub fn try_vector(components: Vec<(Index, T)>) -> Result<Self, IndexError> {
+        check_vector(components).map(GATerm::Vector)
+    }
+
+    pub fn try_bivector(components: Vec<(Index, Index, T)>) -> Result<Self, IndexError> {
+        check_bivector(components).map(GATerm::Bivector)
+    }
+
+    pub fn try_trivector(components: Vec<(Index, Index, Index, T)>) -> Result<Self, IndexError> {
+        check_trivector(components).map(GATerm::Trivector)
     }
 
     pub fn multivector(terms: Vec<BladeTerm<T>>) -> Self {
@@ -145,6 +322,227 @@ impl<T> HasGrade for Scalar<T> {
     }
 }
 
+/// Basis blade of a component, identified by its indices as stored in the term
+#[derive(Debug, Clone, Copy)]
+pub enum Blade<'a> {
+    Inline([BasisIndex; 3], usize),
+    Borrowed(&'a [BasisIndex]),
+}
+
+impl<'a> Blade<'a> {
+    pub fn indices(&self) -> &[BasisIndex] {
+        match self {
+            Blade::Inline(indices, len) => &indices[..*len],
+            Blade::Borrowed(indices) => indices,
+        }
+    }
+
+    pub fn grade(&self) -> usize {
+        self.indices().len()
+    }
+}
+
+impl PartialEq for Blade<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.indices() == other.indices()
+    }
+}
+
+impl Eq for Blade<'_> {}
+
+impl AsRef<[BasisIndex]> for Blade<'_> {
+    fn as_ref(&self) -> &[BasisIndex] {
+        self.indices()
+    }
+}
+
+impl PartialEq<[Index]> for Blade<'_> {
+    fn eq(&self, other: &[Index]) -> bool {
+        self.indices() == other
+    }
+}
+
+/// Iterator over the `(blade, coefficient)` pairs of a [`GATerm`]
+pub struct Components<'a, T> {
+    inner: ComponentsInner<'a, T>,
+}
+
+enum ComponentsInner<'a, T> {
+    Scalar(Option<&'a T>),
+    Vector(std::slice::Iter<'a, (BasisIndex, T)>),
+    Bivector(std::slice::Iter<'a, (BasisIndex, BasisIndex, T)>),
+    Trivector(std::slice::Iter<'a, (BasisIndex, BasisIndex, BasisIndex, T)>),
+    Multivector(std::slice::Iter<'a, BladeTerm<T>>),
+}
+
+impl<'a, T> Iterator for Components<'a, T> {
+    type Item = (Blade<'a>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            ComponentsInner::Scalar(value) => value.take().map(|v| (Blade::Inline([BasisIndex(1); 3], 0), v)),
+            ComponentsInner::Vector(it) => it.next().map(|(i, c)| (Blade::Inline([*i; 3], 1), c)),
+            ComponentsInner::Bivector(it) => it.next().map(|(i, j, c)| (Blade::Inline([*i, *j, *j], 2), c)),
+            ComponentsInner::Trivector(it) => it.next().map(|(i, j, k, c)| (Blade::Inline([*i, *j, *k], 3), c)),
+            ComponentsInner::Multivector(it) => it.next().map(|term| (Blade::Borrowed(&term.indices), &term.coefficient)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match &self.inner {
+            ComponentsInner::Scalar(value) => usize::from(value.is_some()),
+            ComponentsInner::Vector(it) => it.len(),
+            ComponentsInner::Bivector(it) => it.len(),
+            ComponentsInner::Trivector(it) => it.len(),
+            ComponentsInner::Multivector(it) => it.len(),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Components<'_, T> {}
+
+/// Grade-independent component access
+///
+/// Blades are matched on their indices exactly as stored, without reordering.
+impl<T> GATerm<T> {
+    /// Iterate over `(blade, coefficient)` pairs regardless of the grade variant
+    pub fn components(&self) -> Components<'_, T> {
+        let inner = match self {
+            GATerm::Scalar(s) => ComponentsInner::Scalar(Some(&s.value)),
+            GATerm::Vector(v) => ComponentsInner::Vector(v.iter()),
+            GATerm::Bivector(b) => ComponentsInner::Bivector(b.iter()),
+            GATerm::Trivector(t) => ComponentsInner::Trivector(t.iter()),
+            GATerm::Multivector(m) => ComponentsInner::Multivector(m.iter()),
+        };
+        Components { inner }
+    }
+
+    /// Coefficient of the given blade, `None` if the term has no such component
+    pub fn coefficient(&self, blade: &[Index]) -> Option<&T> {
+        self.components().find(|(b, _)| b == blade).map(|(_, c)| c)
+    }
+
+    /// Mutable coefficient of the given blade
+    pub fn coefficient_mut(&mut self, blade: &[Index]) -> Option<&mut T> {
+        match (self, blade) {
+            (GATerm::Scalar(s), []) => Some(&mut s.value),
+            (GATerm::Vector(v), &[i]) => v.iter_mut().find(|c| c.0 == i).map(|c| &mut c.1),
+            (GATerm::Bivector(b), &[i, j]) => b.iter_mut().find(|c| c.0 == i && c.1 == j).map(|c| &mut c.2),
+            (GATerm::Trivector(t), &[i, j, k]) => t.iter_mut().find(|c| c.0 == i && c.1 == j && c.2 == k).map(|c| &mut c.3),
+            (GATerm::Multivector(m), _) => m.iter_mut().find(|term| term.indices == blade).map(|term| &mut term.coefficient),
+            _ => None,
+        }
+    }
+
+    /// Set the coefficient of a blade, adding the component if absent
+    ///
+    /// A blade of a different grade than the term promotes it to `Multivector`.
+    /// Fails, leaving the term unchanged, if an index lies outside the default algebra.
+    pub fn set_coefficient(&mut self, blade: &[Index], value: T) -> Result<(), IndexError> {
+        if let Some(coefficient) = self.coefficient_mut(blade) {
+            *coefficient = value;
+            return Ok(());
+        }
+        let indices = BasisIndex::from_indices(blade)?;
+        match (&mut *self, indices.as_slice()) {
+            (GATerm::Vector(v), &[i]) => v.push((i, value)),
+            (GATerm::Bivector(b), &[i, j]) => b.push((i, j, value)),
+            (GATerm::Trivector(t), &[i, j, k]) => t.push((i, j, k, value)),
+            (GATerm::Multivector(m), _) => m.push(BladeTerm::from_basis(indices, value)),
+            _ => {
+                let placeholder = GATerm::Multivector(Vec::new());
+                let mut terms = std::mem::replace(self, placeholder).into_blade_terms();
+                terms.push(BladeTerm::from_basis(indices, value));
+                *self = GATerm::Multivector(terms);
+            }
+        }
+        Ok(())
+    }
+
+    /// Flatten into general blade terms
+    pub fn into_blade_terms(self) -> Vec<BladeTerm<T>> {
+        match self {
+            GATerm::Scalar(s) => vec![BladeTerm::from_basis(vec![], s.value)],
+            GATerm::Vector(v) => v.into_iter().map(|(i, c)| BladeTerm::from_basis(vec![i], c)).collect(),
+            GATerm::Bivector(b) => b.into_iter().map(|(i, j, c)| BladeTerm::from_basis(vec![i, j], c)).collect(),
+            GATerm::Trivector(t) => t.into_iter().map(|(i, j, k, c)| BladeTerm::from_basis(vec![i, j, k], c)).collect(),
+            GATerm::Multivector(m) => m,
+        }
+    }
+}
+
+/// Consumes the term, yielding its components as blade terms
+impl<T> IntoIterator for GATerm<T> {
+    type Item = BladeTerm<T>;
+    type IntoIter = std::vec::IntoIter<BladeTerm<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_blade_terms().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a GATerm<T> {
+    type Item = (Blade<'a>, &'a T);
+    type IntoIter = Components<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.components()
+    }
+}
+
+impl<T> FromIterator<(BasisIndex, T)> for GATerm<T> {
+    fn from_iter<I: IntoIterator<Item = (BasisIndex, T)>>(iter: I) -> Self {
+        GATerm::Vector(iter.into_iter().collect())
+    }
+}
+
+impl<T> FromIterator<(BasisIndex, BasisIndex, T)> for GATerm<T> {
+    fn from_iter<I: IntoIterator<Item = (BasisIndex, BasisIndex, T)>>(iter: I) -> Self {
+        GATerm::Bivector(iter.into_iter().collect())
+    }
+}
+
+impl<T> FromIterator<(BasisIndex, BasisIndex, BasisIndex, T)> for GATerm<T> {
+    fn from_iter<I: IntoIterator<Item = (BasisIndex, BasisIndex, BasisIndex, T)>>(iter: I) -> Self {
+        GATerm::Trivector(iter.into_iter().collect())
+    }
+}
+
+impl<T> FromIterator<BladeTerm<T>> for GATerm<T> {
+    fn from_iter<I: IntoIterator<Item = BladeTerm<T>>>(iter: I) -> Self {
+        GATerm::Multivector(iter.into_iter().collect())
+    }
+}
+
+/// Real coefficient types: the operations norms need beyond ring arithmetic
+pub trait Real:
+    Copy + Default + PartialOrd + std::ops::Add<Output = Self> + std::ops::Sub<Output = Self> + std::ops::Mul<Output = Self>
+{
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+}
+
+impl Real for f64 {
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+}
+
+impl Real for f32 {
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+}
+
 /// Tests
 #[cfg(test)]
 mod tests {
@@ -192,5 +590,87 @@ mod tests {
         assert_eq!(term.grade(), Grade::Bivector);
         assert_eq!(term.coefficient, 3.0);
         assert_eq!(term.indices, vec![1, 2]);
+
+        assert_eq!(BladeTerm::new(vec![1, 2, 3, 4], 1.0).grade(), Grade::Quadvector);
+        assert_eq!(BladeTerm::new(vec![1, 2, 3, 4, 5], 1.0).grade(), Grade::Pseudoscalar);
+    }
+
+    #[test]
+    fn test_grade_indices() {
+        assert_eq!(Grade::from_index(3, 3), Grade::Pseudoscalar);
+        assert_eq!(Grade::from_index(3, 5), Grade::Trivector);
+        assert_eq!(Grade::from_index(4, 5), Grade::Quadvector);
+        assert_eq!(Grade::from_index(6, 5), Grade::Multivector);
+        assert_eq!(Grade::Pseudoscalar.to_index(3), Some(3));
+        assert_eq!(Grade::Pseudoscalar.to_index(5), Some(5));
+        assert_eq!(Grade::Multivector.to_index(5), None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_component_access() {
+        let mut term = GATerm::bivector(vec![(1, 2, 4.0), (2, 3, -1.0)]);
+        let components: Vec<(Vec<Index>, f64)> = term.components().map(|(b, c)| (b.indices().iter().map(|&i| i.into()).collect(), *c)).collect();
+        assert_eq!(components, vec![(vec![1, 2], 4.0), (vec![2, 3], -1.0)]);
+        assert_eq!(term.components().len(), 2);
+
+        assert_eq!(term.coefficient(&[2, 3]), Some(&-1.0));
+        assert_eq!(term.coefficient(&[3, 2]), None);
+        assert_eq!(GATerm::scalar(2.0).coefficient(&[]), Some(&2.0));
+
+        term.set_coefficient(&[1, 2], 5.0).unwrap();
+        term.set_coefficient(&[1, 3], 6.0).unwrap();
+        assert_eq!(term, GATerm::bivector(vec![(1, 2, 5.0), (2, 3, -1.0), (1, 3, 6.0)]));
+
+        // Mixing in another grade promotes to a general multivector
+        term.set_coefficient(&[], 1.0).unwrap();
+        assert_eq!(term.grade(), Grade::Multivector);
+        assert_eq!(term.coefficient(&[1, 3]), Some(&6.0));
+        assert_eq!(term.coefficient(&[]), Some(&1.0));
+
+        assert_eq!(term.set_coefficient(&[2, 6], 1.0), Err(IndexError::OutOfRange { index: 6, dimension: 5 }));
+        assert_eq!(term.coefficient(&[2, 6]), None);
+    }
+
+    #[test]
+    fn test_iterator_construction() {
+        let vector: GATerm<f64> = BasisIndex::basis(3).zip([1.0, 2.0, 3.0]).collect();
+        assert_eq!(vector, GATerm::vector(vec![(1, 1.0), (2, 2.0), (3, 3.0)]));
+
+        let doubled: GATerm<f64> = vector.into_iter().map(|term| BladeTerm::from_basis(term.indices, 2.0 * term.coefficient)).collect();
+        assert_eq!(doubled.grade(), Grade::Multivector);
+
+        let total: f64 = (&doubled).into_iter().map(|(_, c)| c).sum();
+        assert_eq!(total, 12.0);
+    }
+
+    #[test]
+    fn test_basis_index_validation() {
+        let e3 = BasisIndex::try_from(3).unwrap();
+        assert_eq!((e3.get(), e3.index(), e3.to_string()), (3, 3, "e3".to_string()));
+        assert_eq!(BasisIndex::try_from(0), Err(IndexError::NotPositive(0)));
+        assert_eq!(BasisIndex::try_from(-2).unwrap_err().to_string(), "basis index -2 is not positive; basis vectors are numbered from e1");
+        assert_eq!(BasisIndex::try_from(7).unwrap_err().to_string(), "basis index e7 outside a 5-dimensional algebra");
+        assert_eq!(BasisIndex::new(7, 8).map(BasisIndex::get), Ok(7));
+        assert_eq!(BasisIndex::new(17, 32).unwrap_err().to_string(), "basis index e17 outside a 16-dimensional algebra");
+        assert_eq!(BasisIndex::new(16, 32).map(BasisIndex::get), Ok(16));
+        assert_eq!(BasisIndex::basis(3).map(Index::from).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        assert_eq!(GATerm::try_vector(vec![(1, 1.0), (9, 2.0)]), Err(IndexError::OutOfRange { index: 9, dimension: 5 }));
+        assert_eq!(GATerm::<f64>::try_bivector(vec![(0, 1, 1.0)]), Err(IndexError::NotPositive(0)));
+        assert!(GATerm::try_trivector(vec![(1, 2, 5, 1.0)]).is_ok());
+        assert_eq!(BladeTerm::try_new(&[1, -1], 1.0), Err(IndexError::NotPositive(-1)));
+
+        // Serialized as plain integers, and checked when read back
+        let term = BladeTerm::new(vec![1, 4], 0.5);
+        let json = serde_json::to_string(&term).unwrap();
+        assert_eq!(json, r#"{"indices":[1,4],"coefficient":0.5}"#);
+        assert_eq!(serde_json::from_str::<BladeTerm<f64>>(&json).unwrap(), term);
+        assert!(serde_json::from_str::<BladeTerm<f64>>(r#"{"indices":[1,6],"coefficient":0.5}"#).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "basis index e6 outside a 5-dimensional algebra")]
+    fn test_integer_constructors_panic_outside_the_algebra() {
+        GATerm::vector(vec![(6, 1.0)]);
+    }
+}
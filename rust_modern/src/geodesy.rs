@@ -0,0 +1,407 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! WGS84 geodesy: geodetic, Earth-centred Earth-fixed and local East-North-Up coordinates
+//!
+//! [`GeodeticCoordinate`] holds latitude, longitude and height above the WGS84
+//! ellipsoid. [`EcefPosition`] is its Cartesian form in the [`Ecef`] frame, and
+//! a [`LocalTangentPlane`] anchored at an origin maps between ECEF and
+//! [`EnuPosition`]s in its [`Enu`] frame. The map is rigid, so it is also
+//! available as a typed [`FrameTransform`].
+//!
+//! Distances and bearings along the ellipsoid use Vincenty's formulae. Bearings
+//! are clockwise from north in `[0, τ)`.
+
+use serde::{Deserialize, Serialize};
+use crate::frames::{Frame, FrameTransform};
+use crate::linalg::{self, Matrix3, Vector3};
+use crate::motor::{Motor, Rotor};
+use crate::si_units::{Angle, Length, TAU};
+
+/// Semi-major axis of the WGS84 ellipsoid (m)
+pub const WGS84_A: f64 = 6_378_137.0;
+
+/// Flattening of the WGS84 ellipsoid
+pub const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Semi-minor axis (m)
+const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
+
+/// First eccentricity squared
+const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F);
+
+/// Iterations before Vincenty's inverse formula is declared non-convergent,
+/// which happens only for nearly antipodal points
+const VINCENTY_ITERATIONS: usize = 200;
+
+/// Earth-centred, Earth-fixed frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ecef;
+
+impl Frame for Ecef {
+    const NAME: &'static str = "ecef";
+}
+
+/// East-North-Up frame of a [`LocalTangentPlane`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Enu;
+
+impl Frame for Enu {
+    const NAME: &'static str = "enu";
+}
+
+/// Latitude, longitude and height above the WGS84 ellipsoid
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeodeticCoordinate {
+    pub latitude: Angle,
+    pub longitude: Angle,
+    pub altitude: Length,
+}
+
+/// Cartesian position in the [`Ecef`] frame
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EcefPosition {
+    pub x: Length,
+    pub y: Length,
+    pub z: Length,
+}
+
+/// Position in the [`Enu`] frame of a [`LocalTangentPlane`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EnuPosition {
+    pub east: Length,
+    pub north: Length,
+    pub up: Length,
+}
+
+/// Shortest path between two points on the ellipsoid
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Geodesic {
+    pub distance: Length,
+    /// Bearing when leaving the first point
+    pub initial_bearing: Angle,
+    /// Bearing when arriving at the second point
+    pub final_bearing: Angle,
+}
+
+impl GeodeticCoordinate {
+    pub fn new(latitude: Angle, longitude: Angle, altitude: Length) -> Self {
+        Self { latitude, longitude, altitude }
+    }
+
+    /// Latitude and longitude in degrees, as charts and receivers report them
+    pub fn from_degrees(latitude: f64, longitude: f64, altitude: Length) -> Self {
+        Self::new(Angle::new(latitude * TAU / 360.0), Angle::new(longitude * TAU / 360.0), altitude)
+    }
+
+    pub fn to_ecef(&self) -> EcefPosition {
+        let (lat, lon, h) = (*self.latitude.value(), *self.longitude.value(), *self.altitude.value());
+        // Prime vertical radius of curvature
+        let n = WGS84_A / (1.0 - WGS84_E2 * lat.sin() * lat.sin()).sqrt();
+        EcefPosition::from_vector([
+            (n + h) * lat.cos() * lon.cos(),
+            (n + h) * lat.cos() * lon.sin(),
+            (n * (1.0 - WGS84_E2) + h) * lat.sin(),
+        ])
+    }
+
+    /// Distance and bearings to `other` along the ellipsoid, ignoring heights;
+    /// `None` when Vincenty's iteration does not converge
+    pub fn inverse(&self, other: &GeodeticCoordinate) -> Option<Geodesic> {
+        let (f, b) = (WGS84_F, WGS84_B);
+        let l = other.longitude.value() - self.longitude.value();
+        let u1 = ((1.0 - f) * self.latitude.value().tan()).atan();
+        let u2 = ((1.0 - f) * other.latitude.value().tan()).atan();
+        let (sin_u1, cos_u1, sin_u2, cos_u2) = (u1.sin(), u1.cos(), u2.sin(), u2.cos());
+
+        let mut lambda = l;
+        for _ in 0..VINCENTY_ITERATIONS {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = (cos_u2 * sin_lambda).hypot(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+            if sin_sigma == 0.0 {
+                let zero = Angle::new(0.0);
+                return Some(Geodesic { distance: Length::new(0.0), initial_bearing: zero, final_bearing: zero });
+            }
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+            // Zero on the equator, where the geodesic is a great circle
+            let cos_2sm = if cos2_alpha != 0.0 { cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha } else { 0.0 };
+            let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+            let previous = lambda;
+            lambda = l + (1.0 - c) * f * sin_alpha * (sigma + c * sin_sigma * (cos_2sm + c * cos_sigma * (-1.0 + 2.0 * cos_2sm * cos_2sm)));
+            if (lambda - previous).abs() > 1e-12 {
+                continue;
+            }
+
+            let (a_coefficient, b_coefficient) = series_coefficients(cos2_alpha);
+            let delta_sigma = sigma_correction(b_coefficient, sin_sigma, cos_sigma, cos_2sm);
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let initial = (cos_u2 * sin_lambda).atan2(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+            let arriving = (cos_u1 * sin_lambda).atan2(-sin_u1 * cos_u2 + cos_u1 * sin_u2 * cos_lambda);
+            return Some(Geodesic {
+                distance: Length::new(b * a_coefficient * (sigma - delta_sigma)),
+                initial_bearing: Angle::new(initial.rem_euclid(TAU)),
+                final_bearing: Angle::new(arriving.rem_euclid(TAU)),
+            });
+        }
+        None
+    }
+
+    pub fn distance_to(&self, other: &GeodeticCoordinate) -> Option<Length> {
+        self.inverse(other).map(|g| g.distance)
+    }
+
+    pub fn bearing_to(&self, other: &GeodeticCoordinate) -> Option<Angle> {
+        self.inverse(other).map(|g| g.initial_bearing)
+    }
+
+    /// Point reached by travelling `distance` along the ellipsoid starting on
+    /// `bearing`, at the same height
+    pub fn destination(&self, bearing: Angle, distance: Length) -> GeodeticCoordinate {
+        let (f, b) = (WGS84_F, WGS84_B);
+        let (sin_a1, cos_a1) = bearing.value().sin_cos();
+        let u1 = ((1.0 - f) * self.latitude.value().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let sigma1 = u1.tan().atan2(cos_a1);
+        let sin_alpha = cos_u1 * sin_a1;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        let (a_coefficient, b_coefficient) = series_coefficients(cos2_alpha);
+
+        let first = distance.value() / (b * a_coefficient);
+        let mut sigma = first;
+        let mut cos_2sm = (2.0 * sigma1 + sigma).cos();
+        for _ in 0..VINCENTY_ITERATIONS {
+            cos_2sm = (2.0 * sigma1 + sigma).cos();
+            let next = first + sigma_correction(b_coefficient, sigma.sin(), sigma.cos(), cos_2sm);
+            let converged = (next - sigma).abs() < 1e-12;
+            sigma = next;
+            if converged {
+                break;
+            }
+        }
+
+        let (sin_sigma, cos_sigma) = sigma.sin_cos();
+        let x = sin_u1 * sin_sigma - cos_u1 * cos_sigma * cos_a1;
+        let latitude = (sin_u1 * cos_sigma + cos_u1 * sin_sigma * cos_a1).atan2((1.0 - f) * sin_alpha.hypot(x));
+        let lambda = (sin_sigma * sin_a1).atan2(cos_u1 * cos_sigma - sin_u1 * sin_sigma * cos_a1);
+        let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+        let l = lambda - (1.0 - c) * f * sin_alpha * (sigma + c * sin_sigma * (cos_2sm + c * cos_sigma * (-1.0 + 2.0 * cos_2sm * cos_2sm)));
+        let longitude = (self.longitude.value() + l + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+        GeodeticCoordinate::new(Angle::new(latitude), Angle::new(longitude), self.altitude)
+    }
+}
+
+/// Vincenty's `A` and `B` series in the squared second eccentricity of the geodesic
+fn series_coefficients(cos2_alpha: f64) -> (f64, f64) {
+    let u2 = cos2_alpha * (WGS84_A * WGS84_A - WGS84_B * WGS84_B) / (WGS84_B * WGS84_B);
+    let a = 1.0 + u2 / 16384.0 * (4096.0 + u2 * (-768.0 + u2 * (320.0 - 175.0 * u2)));
+    let b = u2 / 1024.0 * (256.0 + u2 * (-128.0 + u2 * (74.0 - 47.0 * u2)));
+    (a, b)
+}
+
+/// Vincenty's `Δσ`
+fn sigma_correction(b: f64, sin_sigma: f64, cos_sigma: f64, cos_2sm: f64) -> f64 {
+    let c2 = cos_2sm * cos_2sm;
+    b * sin_sigma
+        * (cos_2sm
+            + b / 4.0
+                * (cos_sigma * (-1.0 + 2.0 * c2) - b / 6.0 * cos_2sm * (-3.0 + 4.0 * sin_sigma * sin_sigma) * (-3.0 + 4.0 * c2)))
+}
+
+impl EcefPosition {
+    pub fn from_vector(v: Vector3) -> Self {
+        Self { x: Length::new(v[0]), y: Length::new(v[1]), z: Length::new(v[2]) }
+    }
+
+    /// Coordinates in metres
+    pub fn vector(&self) -> Vector3 {
+        [*self.x.value(), *self.y.value(), *self.z.value()]
+    }
+
+    /// Closed-form conversion of Zhu (1993), exact to well below a millimetre
+    /// everywhere except within a few kilometres of the Earth's center
+    pub fn to_geodetic(&self) -> GeodeticCoordinate {
+        let [x, y, z] = self.vector();
+        let (a, b, e2) = (WGS84_A, WGS84_B, WGS84_E2);
+        let ep2 = (a * a - b * b) / (b * b);
+        let p = x.hypot(y);
+        if p < 1e-9 {
+            // On the polar axis
+            let latitude = if z >= 0.0 { TAU / 4.0 } else { -TAU / 4.0 };
+            return GeodeticCoordinate::new(Angle::new(latitude), Angle::new(0.0), Length::new(z.abs() - b));
+        }
+
+        let f = 54.0 * b * b * z * z;
+        let g = p * p + (1.0 - e2) * z * z - e2 * (a * a - b * b);
+        let c = e2 * e2 * f * p * p / (g * g * g);
+        let s = (1.0 + c + (c * c + 2.0 * c).sqrt()).cbrt();
+        let k = s + 1.0 + 1.0 / s;
+        let pp = f / (3.0 * k * k * g * g);
+        let q = (1.0 + 2.0 * e2 * e2 * pp).sqrt();
+        let r0 = -pp * e2 * p / (1.0 + q) + (0.5 * a * a * (1.0 + 1.0 / q) - pp * (1.0 - e2) * z * z / (q * (1.0 + q)) - 0.5 * pp * p * p).max(0.0).sqrt();
+        let u = ((p - e2 * r0).powi(2) + z * z).sqrt();
+        let v = ((p - e2 * r0).powi(2) + (1.0 - e2) * z * z).sqrt();
+        let z0 = b * b * z / (a * v);
+        GeodeticCoordinate::new(
+            Angle::new((z + ep2 * z0).atan2(p)),
+            Angle::new(y.atan2(x)),
+            Length::new(u * (1.0 - b * b / (a * v))),
+        )
+    }
+}
+
+impl EnuPosition {
+    pub fn new(east: Length, north: Length, up: Length) -> Self {
+        Self { east, north, up }
+    }
+
+    pub fn from_vector(v: Vector3) -> Self {
+        Self::new(Length::new(v[0]), Length::new(v[1]), Length::new(v[2]))
+    }
+
+    /// Coordinates in metres
+    pub fn vector(&self) -> Vector3 {
+        [*self.east.value(), *self.north.value(), *self.up.value()]
+    }
+
+    /// Straight-line distance, good for the few kilometres a tangent plane spans
+    pub fn distance_to(&self, other: &EnuPosition) -> Length {
+        Length::new(linalg::norm(linalg::sub(other.vector(), self.vector())))
+    }
+
+    /// Bearing of `other` in the horizontal plane, clockwise from north
+    pub fn bearing_to(&self, other: &EnuPosition) -> Angle {
+        let d = linalg::sub(other.vector(), self.vector());
+        Angle::new(d[0].atan2(d[1]).rem_euclid(TAU))
+    }
+}
+
+/// East-North-Up frame tangent to the ellipsoid at an origin
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LocalTangentPlane {
+    origin: GeodeticCoordinate,
+    origin_ecef: Vector3,
+    /// Columns are the east, north and up axes in ECEF
+    axes: Matrix3,
+}
+
+impl LocalTangentPlane {
+    pub fn new(origin: GeodeticCoordinate) -> Self {
+        let (sin_lat, cos_lat) = origin.latitude.value().sin_cos();
+        let (sin_lon, cos_lon) = origin.longitude.value().sin_cos();
+        let axes = [
+            [-sin_lon, -sin_lat * cos_lon, cos_lat * cos_lon],
+            [cos_lon, -sin_lat * sin_lon, cos_lat * sin_lon],
+            [0.0, cos_lat, sin_lat],
+        ];
+        Self { origin, origin_ecef: origin.to_ecef().vector(), axes }
+    }
+
+    pub fn origin(&self) -> GeodeticCoordinate {
+        self.origin
+    }
+
+    pub fn to_enu(&self, position: &EcefPosition) -> EnuPosition {
+        let d = linalg::sub(position.vector(), self.origin_ecef);
+        EnuPosition::from_vector(linalg::mat3_vec(&linalg::mat3_transpose(&self.axes), d))
+    }
+
+    pub fn to_ecef(&self, position: &EnuPosition) -> EcefPosition {
+        EcefPosition::from_vector(linalg::add(self.origin_ecef, linalg::mat3_vec(&self.axes, position.vector())))
+    }
+
+    pub fn from_geodetic(&self, coordinate: &GeodeticCoordinate) -> EnuPosition {
+        self.to_enu(&coordinate.to_ecef())
+    }
+
+    pub fn to_geodetic(&self, position: &EnuPosition) -> GeodeticCoordinate {
+        self.to_ecef(position).to_geodetic()
+    }
+
+    /// The plane's pose in ECEF as a typed rigid transform
+    pub fn ecef_from_enu(&self) -> FrameTransform<Ecef, Enu> {
+        FrameTransform::new(Motor::new(self.origin_ecef, Rotor::from_rotation_matrix(&self.axes)))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        degrees.signum() * (degrees.abs() + minutes / 60.0 + seconds / 3600.0)
+    }
+
+    #[test]
+    fn test_ecef_round_trip() {
+        let equator = GeodeticCoordinate::from_degrees(0.0, 0.0, Length::new(0.0)).to_ecef().vector();
+        assert!(linalg::norm(linalg::sub(equator, [WGS84_A, 0.0, 0.0])) < 1e-6);
+        let pole = GeodeticCoordinate::from_degrees(90.0, 0.0, Length::new(100.0)).to_ecef().vector();
+        assert!(linalg::norm(linalg::sub(pole, [0.0, 0.0, WGS84_B + 100.0])) < 1e-6);
+
+        for (lat, lon, h) in [(45.0, 7.5, 300.0), (-33.9, 151.2, -50.0), (89.9, -120.0, 5000.0), (-60.0, 179.9, 0.0)] {
+            let coordinate = GeodeticCoordinate::from_degrees(lat, lon, Length::new(h));
+            let back = coordinate.to_ecef().to_geodetic();
+            assert!((back.latitude.value() - coordinate.latitude.value()).abs() < 1e-11);
+            assert!((back.longitude.value() - coordinate.longitude.value()).abs() < 1e-11);
+            assert!((back.altitude.value() - h).abs() < 1e-6);
+        }
+        let below_pole = EcefPosition::from_vector([0.0, 0.0, -WGS84_B]).to_geodetic();
+        assert!((below_pole.latitude.value() + TAU / 4.0).abs() < 1e-12 && below_pole.altitude.value().abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_local_tangent_plane() {
+        let origin = GeodeticCoordinate::from_degrees(46.2, 6.1, Length::new(400.0));
+        let plane = LocalTangentPlane::new(origin);
+        assert!(linalg::norm(plane.from_geodetic(&origin).vector()) < 1e-6);
+
+        // A point straight above the origin is up
+        let above = GeodeticCoordinate::new(origin.latitude, origin.longitude, Length::new(500.0));
+        let up = plane.from_geodetic(&above).vector();
+        assert!(linalg::norm(linalg::sub(up, [0.0, 0.0, 100.0])) < 1e-6);
+
+        // A little further north is north, a little further east is east
+        let north = plane.from_geodetic(&GeodeticCoordinate::from_degrees(46.201, 6.1, Length::new(400.0)));
+        assert!(north.north.value() > &100.0 && north.east.value().abs() < 1e-6);
+        let east = plane.from_geodetic(&GeodeticCoordinate::from_degrees(46.2, 6.101, Length::new(400.0)));
+        assert!(east.east.value() > &70.0 && east.north.value().abs() < 0.01);
+        assert!((east.bearing_to(&north).value() - 7.0 * TAU / 8.0).abs() < 0.2);
+
+        let target = EnuPosition::from_vector([120.0, -45.0, 3.0]);
+        let back = plane.to_enu(&plane.to_ecef(&target));
+        assert!(back.distance_to(&target).value() < &1e-6);
+        let geodetic = plane.to_geodetic(&target);
+        assert!(plane.from_geodetic(&geodetic).distance_to(&target).value() < &1e-6);
+
+        // The typed transform agrees with the conversion
+        let via_motor = plane.ecef_from_enu().apply_point(target.vector());
+        assert!(linalg::norm(linalg::sub(via_motor, plane.to_ecef(&target).vector())) < 1e-6);
+    }
+
+    #[test]
+    fn test_vincenty() {
+        // Flinders Peak to Buninyong, Vincenty's (1975) worked example
+        let flinders = GeodeticCoordinate::from_degrees(dms(-37.0, 57.0, 3.72030), dms(144.0, 25.0, 29.52440), Length::new(0.0));
+        let buninyong = GeodeticCoordinate::from_degrees(dms(-37.0, 39.0, 10.15610), dms(143.0, 55.0, 35.38390), Length::new(0.0));
+        let geodesic = flinders.inverse(&buninyong).unwrap();
+        assert!((geodesic.distance.value() - 54_972.271).abs() < 1e-3);
+        let degrees = |a: Angle| a.value() * 360.0 / TAU;
+        assert!((degrees(geodesic.initial_bearing) - dms(306.0, 52.0, 5.37)).abs() < 1e-5);
+        assert!((degrees(geodesic.final_bearing) - dms(307.0, 10.0, 25.07)).abs() < 1e-5);
+
+        let reached = flinders.destination(geodesic.initial_bearing, geodesic.distance);
+        assert!(reached.distance_to(&buninyong).unwrap().value() < &1e-4);
+
+        assert_eq!(flinders.distance_to(&flinders), Some(Length::new(0.0)));
+        // Along the equator the geodesic is the equator itself
+        let a = GeodeticCoordinate::from_degrees(0.0, 0.0, Length::new(0.0));
+        let b = GeodeticCoordinate::from_degrees(0.0, 1.0, Length::new(0.0));
+        assert!((a.distance_to(&b).unwrap().value() - WGS84_A * TAU / 360.0).abs() < 1e-6);
+        assert!((a.bearing_to(&b).unwrap().value() - TAU / 4.0).abs() < 1e-12);
+    }
+}
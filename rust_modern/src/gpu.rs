@@ -0,0 +1,408 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Batch motor application on a GPU through wgpu compute shaders
+//!
+//! [`GpuBatch`] mirrors the functions of [`crate::batch`]: it uploads motor and
+//! point coefficients as `f32` storage buffers, runs one invocation per
+//! element and reads the results back. Shaders compute in `f32`, so results
+//! match the CPU to about 1e-6 of the coordinate magnitude; centre a cloud
+//! near the origin before uploading it when millimetres matter at kilometres.
+//!
+//! Every call pays buffer creation, the upload and a blocking readback, which
+//! dominate for small batches; the GPU only pays off for large clouds. The
+//! `batch_motor_application` group of the benchmark runner, built with
+//! `--features gpu`, times both paths from 10² to 10⁶ points to locate the
+//! crossover on a given machine. Batches larger than one storage binding are
+//! split into several dispatches.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::mpsc;
+
+use wgpu::util::DeviceExt;
+
+use crate::linalg::Vector3;
+use crate::motor::{Motor, Rotor};
+
+/// Motors are two `vec4<f32>`, rotor (scalar, e23, e13, e12) then translation;
+/// points one `vec4<f32>`
+const SHADER: &str = r#"
+struct Motor {
+    rotor: vec4<f32>,
+    translation: vec4<f32>,
+}
+
+struct Params {
+    count: u32,
+    motor_stride: u32,
+    pad0: u32,
+    pad1: u32,
+}
+
+@group(0) @binding(0) var<storage, read> motors: array<Motor>;
+@group(0) @binding(1) var<storage, read> inputs: array<vec4<f32>>;
+@group(0) @binding(2) var<storage, read_write> outputs: array<vec4<f32>>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+// Quaternion (x, y, z, w) of a rotor in GAFRO blade order, and back
+fn quaternion(r: vec4<f32>) -> vec4<f32> {
+    return vec4<f32>(-r.y, r.z, -r.w, r.x);
+}
+
+fn rotor(q: vec4<f32>) -> vec4<f32> {
+    return vec4<f32>(q.w, -q.x, q.y, -q.z);
+}
+
+fn apply_point(m: Motor, p: vec3<f32>) -> vec3<f32> {
+    let q = quaternion(m.rotor);
+    let t = 2.0 * cross(q.xyz, p);
+    return p + q.w * t + cross(q.xyz, t) + m.translation.xyz;
+}
+
+@compute @workgroup_size(64)
+fn transform_points(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.count) {
+        return;
+    }
+    outputs[i] = vec4<f32>(apply_point(motors[i * params.motor_stride], inputs[i].xyz), 0.0);
+}
+
+@compute @workgroup_size(64)
+fn compose_motors(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.count) {
+        return;
+    }
+    let a = motors[i];
+    let qa = quaternion(a.rotor);
+    let qb = quaternion(inputs[2u * i]);
+    let w = qa.w * qb.w - dot(qa.xyz, qb.xyz);
+    let v = qa.w * qb.xyz + qb.w * qa.xyz + cross(qa.xyz, qb.xyz);
+    outputs[2u * i] = rotor(vec4<f32>(v, w));
+    outputs[2u * i + 1u] = vec4<f32>(apply_point(a, inputs[2u * i + 1u].xyz), 0.0);
+}
+"#;
+
+const WORKGROUP_SIZE: usize = 64;
+/// Bytes of one motor, the largest element of any buffer
+const MOTOR_BYTES: usize = 32;
+
+/// Why a GPU batch could not run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GpuError {
+    /// No adapter is available, e.g. on a headless machine without drivers
+    NoAdapter,
+    Device(String),
+    Readback(String),
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuError::NoAdapter => write!(f, "no GPU adapter available"),
+            GpuError::Device(message) => write!(f, "GPU device request failed: {}", message),
+            GpuError::Readback(message) => write!(f, "GPU readback failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for GpuError {}
+
+/// Compute device with the batch pipelines compiled
+pub struct GpuBatch {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    layout: wgpu::BindGroupLayout,
+    transform: wgpu::ComputePipeline,
+    compose: wgpu::ComputePipeline,
+    adapter: String,
+    /// Elements per dispatch, bounded by the binding size and workgroup count limits
+    chunk: usize,
+}
+
+impl GpuBatch {
+    /// Open the default high-performance adapter, blocking until it is ready
+    pub fn new() -> Result<Self, GpuError> {
+        pollster::block_on(Self::request())
+    }
+
+    async fn request() -> Result<Self, GpuError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                force_fallback_adapter: false,
+                compatible_surface: None,
+            })
+            .await
+            .ok_or(GpuError::NoAdapter)?;
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("gafro batch"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: wgpu::Limits::downlevel_defaults(),
+                    memory_hints: wgpu::MemoryHints::Performance,
+                },
+                None,
+            )
+            .await
+            .map_err(|e| GpuError::Device(e.to_string()))?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("gafro batch"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("gafro batch"),
+            entries: &[
+                storage(0, true),
+                storage(1, true),
+                storage(2, false),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("gafro batch"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &module,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        let (transform, compose) = (pipeline("transform_points"), pipeline("compose_motors"));
+
+        let limits = device.limits();
+        let chunk = (limits.max_storage_buffer_binding_size as usize / MOTOR_BYTES)
+            .min(limits.max_compute_workgroups_per_dimension as usize * WORKGROUP_SIZE);
+        let adapter = adapter.get_info().name;
+        Ok(Self { device, queue, layout, transform, compose, adapter, chunk })
+    }
+
+    /// Name of the adapter the batches run on
+    pub fn adapter(&self) -> &str {
+        &self.adapter
+    }
+
+    /// Every point moved by one motor
+    pub fn transform_points(&self, motor: &Motor, points: &[Vector3]) -> Result<Vec<Vector3>, GpuError> {
+        let motor = motor_floats(motor);
+        let mut moved = Vec::with_capacity(points.len());
+        for chunk in points.chunks(self.chunk) {
+            let inputs: Vec<f32> = chunk.iter().flat_map(point_floats).collect();
+            let outputs = self.run(&self.transform, &motor, 0, &inputs, chunk.len(), 4)?;
+            moved.extend(outputs.chunks_exact(4).map(|p| [p[0], p[1], p[2]].map(f64::from)));
+        }
+        Ok(moved)
+    }
+
+    /// Point `i` moved by motor `i`, up to the shorter slice
+    pub fn transform_points_pairwise(&self, motors: &[Motor], points: &[Vector3]) -> Result<Vec<Vector3>, GpuError> {
+        let count = motors.len().min(points.len());
+        let mut moved = Vec::with_capacity(count);
+        for (motors, points) in motors[..count].chunks(self.chunk).zip(points[..count].chunks(self.chunk)) {
+            let motors: Vec<f32> = motors.iter().flat_map(motor_floats).collect();
+            let inputs: Vec<f32> = points.iter().flat_map(point_floats).collect();
+            let outputs = self.run(&self.transform, &motors, 1, &inputs, points.len(), 4)?;
+            moved.extend(outputs.chunks_exact(4).map(|p| [p[0], p[1], p[2]].map(f64::from)));
+        }
+        Ok(moved)
+    }
+
+    /// Products `lhs[i] * rhs[i]`, up to the shorter slice
+    pub fn compose_motors(&self, lhs: &[Motor], rhs: &[Motor]) -> Result<Vec<Motor>, GpuError> {
+        let count = lhs.len().min(rhs.len());
+        let mut composed = Vec::with_capacity(count);
+        for (lhs, rhs) in lhs[..count].chunks(self.chunk).zip(rhs[..count].chunks(self.chunk)) {
+            let motors: Vec<f32> = lhs.iter().flat_map(motor_floats).collect();
+            let inputs: Vec<f32> = rhs.iter().flat_map(motor_floats).collect();
+            let outputs = self.run(&self.compose, &motors, 1, &inputs, lhs.len(), 8)?;
+            composed.extend(outputs.chunks_exact(8).map(|m| {
+                let [scalar, e23, e13, e12, x, y, z, _] = std::array::from_fn(|i| f64::from(m[i]));
+                Motor::new([x, y, z], Rotor::new(scalar, e23, e13, e12))
+            }));
+        }
+        Ok(composed)
+    }
+
+    /// One dispatch of `count` invocations writing `stride` floats each
+    fn run(
+        &self,
+        pipeline: &wgpu::ComputePipeline,
+        motors: &[f32],
+        motor_stride: u32,
+        inputs: &[f32],
+        count: usize,
+        stride: usize,
+    ) -> Result<Vec<f32>, GpuError> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let storage = |label, contents: &[f32]| {
+            self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: &bytes(contents),
+                usage: wgpu::BufferUsages::STORAGE,
+            })
+        };
+        let motors = storage("motors", motors);
+        let inputs = storage("inputs", inputs);
+        let size = (count * stride * std::mem::size_of::<f32>()) as u64;
+        let outputs = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("outputs"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("staging"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let params = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: &[count as u32, motor_stride, 0, 0].iter().flat_map(|v| v.to_ne_bytes()).collect::<Vec<u8>>(),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("gafro batch"),
+            layout: &self.layout,
+            entries: &[&motors, &inputs, &outputs, &params]
+                .iter()
+                .enumerate()
+                .map(|(binding, buffer)| wgpu::BindGroupEntry { binding: binding as u32, resource: buffer.as_entire_binding() })
+                .collect::<Vec<_>>(),
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("gafro batch") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None, timestamp_writes: None });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &group, &[]);
+            pass.dispatch_workgroups(count.div_ceil(WORKGROUP_SIZE) as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&outputs, 0, &staging, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|e| GpuError::Readback(e.to_string()))?
+            .map_err(|e| GpuError::Readback(e.to_string()))?;
+        let values = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        staging.unmap();
+        Ok(values)
+    }
+}
+
+impl fmt::Debug for GpuBatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpuBatch").field("adapter", &self.adapter).field("chunk", &self.chunk).finish()
+    }
+}
+
+fn motor_floats(motor: &Motor) -> [f32; 8] {
+    let (r, t) = (motor.rotor, motor.translation);
+    [r.scalar, r.e23, r.e13, r.e12, t[0], t[1], t[2], 0.0].map(|c| c as f32)
+}
+
+fn point_floats(p: &Vector3) -> [f32; 4] {
+    [p[0] as f32, p[1] as f32, p[2] as f32, 0.0]
+}
+
+fn bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch;
+    use crate::linalg;
+    use crate::primitives::Aabb;
+    use crate::sample::{MotorBounds, Sampler};
+
+    /// The device under test, or `None` on machines without one
+    fn device() -> Option<GpuBatch> {
+        match GpuBatch::new() {
+            Ok(batch) => Some(batch),
+            Err(GpuError::NoAdapter) => {
+                eprintln!("no GPU adapter, skipping");
+                None
+            }
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn test_transforms_match_cpu_batch() {
+        let Some(gpu) = device() else { return };
+        let mut sampler = Sampler::new(15);
+        let bounds = MotorBounds::new(Aabb::new([-5.0; 3], [5.0; 3]));
+        let motors: Vec<Motor> = (0..1000).map(|_| sampler.motor(&bounds)).collect();
+        let points: Vec<Vector3> = (0..1000).map(|_| sampler.point_in_box(&Aabb::new([-5.0; 3], [5.0; 3]))).collect();
+
+        let close = |a: &[Vector3], b: &[Vector3]| a.len() == b.len() && a.iter().zip(b).all(|(p, q)| linalg::norm(linalg::sub(*p, *q)) < 1e-4);
+        assert!(close(&gpu.transform_points(&motors[0], &points).unwrap(), &batch::transform_points(&motors[0], &points)));
+        assert!(close(
+            &gpu.transform_points_pairwise(&motors, &points[..700]).unwrap(),
+            &batch::transform_points_pairwise(&motors, &points[..700])
+        ));
+        assert!(gpu.transform_points(&motors[0], &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_composition_matches_cpu_batch() {
+        let Some(gpu) = device() else { return };
+        let mut sampler = Sampler::new(16);
+        let bounds = MotorBounds::new(Aabb::new([-5.0; 3], [5.0; 3]));
+        let lhs: Vec<Motor> = (0..500).map(|_| sampler.motor(&bounds)).collect();
+        let rhs: Vec<Motor> = (0..500).map(|_| sampler.motor(&bounds)).collect();
+
+        let composed = gpu.compose_motors(&lhs, &rhs).unwrap();
+        for (gpu, cpu) in composed.iter().zip(batch::compose_motors(&lhs, &rhs)) {
+            assert!((gpu.rotor * cpu.rotor.reverse()).angle() < 1e-5);
+            assert!(linalg::norm(linalg::sub(gpu.translation, cpu.translation)) < 1e-4);
+        }
+    }
+}
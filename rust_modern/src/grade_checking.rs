@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::ga_term::{Grade, GATerm, BladeTerm, Index};
+use crate::ga_term::{Grade, GATerm, BladeTerm, Index, ALGEBRA_DIMENSION};
 use crate::grade_indexed::{GradeIndexed, IsGradeIndexed};
 
 /// Compile-time grade checking system
@@ -52,53 +52,145 @@ impl<T1, T2, const G1: u8, const G2: u8> CanGeometricProduct<GradeIndexed<T2, G2
     type Output = GATerm<f64>; // Simplified output type
 }
 
+/// Set of grades stored as a bitset, usable in const contexts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GradeSet(u32);
+
+impl GradeSet {
+    /// Highest grade a set can hold
+    pub const MAX_GRADE: u8 = 31;
+    pub const EMPTY: GradeSet = GradeSet(0);
+
+    pub const fn from_bits(bits: u32) -> Self {
+        GradeSet(bits)
+    }
+
+    pub const fn single(grade: u8) -> Self {
+        GradeSet::EMPTY.with(grade)
+    }
+
+    /// All grades `0..=dimension`
+    pub const fn all(dimension: u8) -> Self {
+        if dimension >= Self::MAX_GRADE {
+            GradeSet(u32::MAX)
+        } else {
+            GradeSet((1u32 << (dimension + 1)) - 1)
+        }
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn with(self, grade: u8) -> Self {
+        if grade > Self::MAX_GRADE {
+            self
+        } else {
+            GradeSet(self.0 | (1 << grade))
+        }
+    }
+
+    pub const fn contains(self, grade: u8) -> bool {
+        grade <= Self::MAX_GRADE && self.0 & (1 << grade) != 0
+    }
+
+    pub const fn union(self, other: GradeSet) -> Self {
+        GradeSet(self.0 | other.0)
+    }
+
+    pub const fn intersection(self, other: GradeSet) -> Self {
+        GradeSet(self.0 & other.0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// The only grade in the set, if it holds exactly one
+    pub const fn single_grade(self) -> Option<u8> {
+        if self.len() == 1 {
+            Some(self.0.trailing_zeros() as u8)
+        } else {
+            None
+        }
+    }
+
+    /// True if every grade is even (or every grade is odd)
+    pub const fn is_homogeneous_parity(self) -> bool {
+        const EVEN: u32 = 0x5555_5555;
+        self.0 & EVEN == 0 || self.0 & !EVEN == 0
+    }
+
+    /// Grades in ascending order
+    pub fn iter(self) -> impl Iterator<Item = u8> {
+        (0..=Self::MAX_GRADE).filter(move |&grade| self.contains(grade))
+    }
+}
+
+impl FromIterator<u8> for GradeSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        iter.into_iter().fold(GradeSet::EMPTY, GradeSet::with)
+    }
+}
+
 /// Grade calculation utilities
 pub mod grade_calc {
     use super::*;
 
     /// Calculate result grades for geometric product
-    pub const fn geometric_product_grades(g1: u8, g2: u8) -> &'static [u8] {
-        // Geometric product can produce multiple grades
-        // |g1 - g2|, |g1 - g2| + 2, ..., g1 + g2
-        match (g1, g2) {
-            (0, g) | (g, 0) => match g {
-                0 => &[0],
-                1 => &[1],
-                2 => &[2],
-                3 => &[3],
-                _ => &[255], // Multivector
-            },
-            (1, 1) => &[0, 2],
-            (1, 2) => &[1, 3],
-            (1, 3) => &[2],
-            (2, 1) => &[1, 3],
-            (2, 2) => &[0, 2],
-            (2, 3) => &[1],
-            (3, 1) => &[2],
-            (3, 2) => &[1],
-            (3, 3) => &[0, 2],
-            _ => &[255], // General multivector case
+    pub const fn geometric_product_grades(g1: u8, g2: u8) -> GradeSet {
+        geometric_product_grades_in(g1, g2, ALGEBRA_DIMENSION)
+    }
+
+    /// Geometric product grades in an algebra of the given dimension
+    ///
+    /// The product of a `g1`-blade and a `g2`-blade has grades
+    /// `|g1 - g2|, |g1 - g2| + 2, ..., min(g1 + g2, 2n - g1 - g2)`.
+    pub const fn geometric_product_grades_in(g1: u8, g2: u8, dimension: u8) -> GradeSet {
+        if g1 > dimension || g2 > dimension || dimension > GradeSet::MAX_GRADE {
+            return GradeSet::EMPTY;
+        }
+        let sum = g1 + g2;
+        let upper = if sum <= dimension { sum } else { 2 * dimension - sum };
+        let mut grade = g1.abs_diff(g2);
+        let mut set = GradeSet::EMPTY;
+        while grade <= upper {
+            set = set.with(grade);
+            grade += 2;
         }
+        set
     }
 
     /// Calculate result grade for outer product
     pub const fn outer_product_grade(g1: u8, g2: u8) -> u8 {
-        let result = g1 + g2;
-        if result <= 3 {
-            result
+        outer_product_grade_in(g1, g2, ALGEBRA_DIMENSION)
+    }
+
+    /// Outer product grade `g1 + g2` in an algebra of the given dimension
+    pub const fn outer_product_grade_in(g1: u8, g2: u8, dimension: u8) -> u8 {
+        let result = g1 as u16 + g2 as u16;
+        if result <= dimension as u16 {
+            result as u8
         } else {
-            255 // Multivector
+            255 // Vanishes: exceeds the pseudoscalar grade
         }
     }
 
     /// Calculate result grade for inner product
     pub const fn inner_product_grade(g1: u8, g2: u8) -> u8 {
-        let result = if g1 >= g2 { g1 - g2 } else { g2 - g1 };
-        if result <= 3 {
-            result
-        } else {
-            255 // Multivector
+        inner_product_grade_in(g1, g2, ALGEBRA_DIMENSION)
+    }
+
+    /// Inner product grade `|g1 - g2|` in an algebra of the given dimension
+    pub const fn inner_product_grade_in(g1: u8, g2: u8, dimension: u8) -> u8 {
+        if g1 > dimension || g2 > dimension {
+            return 255; // Multivector
         }
+        g1.abs_diff(g2)
     }
 }
 
@@ -124,6 +216,10 @@ impl<T1, T2, const G1: u8, const G2: u8> OperationValidator<GradeIndexed<T1, G1>
         true // Inner product is always valid
     }
 
+    pub const fn geometric_product_grades() -> GradeSet {
+        grade_calc::geometric_product_grades(G1, G2)
+    }
+
     pub const fn outer_product_grade() -> u8 {
         grade_calc::outer_product_grade(G1, G2)
     }
@@ -169,9 +265,9 @@ pub mod safe_ops {
         T2: Clone,
     {
         // Placeholder implementation - actual implementation would compute the outer product
-        const RESULT_GRADE: u8 = grade_calc::outer_product_grade(G1, G2);
+        let result_grade = OperationMatrix::<G1, G2>::OUTER_PRODUCT_RESULT;
 
-        match RESULT_GRADE {
+        match result_grade {
             0 => GATerm::scalar(0.0),
             1 => GATerm::vector(vec![]),
             2 => GATerm::bivector(vec![]),
@@ -190,9 +286,9 @@ pub mod safe_ops {
         T2: Clone,
     {
         // Placeholder implementation - actual implementation would compute the inner product
-        const RESULT_GRADE: u8 = grade_calc::inner_product_grade(G1, G2);
+        let result_grade = OperationMatrix::<G1, G2>::INNER_PRODUCT_RESULT;
 
-        match RESULT_GRADE {
+        match result_grade {
             0 => GATerm::scalar(0.0),
             1 => GATerm::vector(vec![]),
             2 => GATerm::bivector(vec![]),
@@ -232,8 +328,16 @@ impl<T, const G: u8> TypeInspector<GradeIndexed<T, G>> {
         G == 3
     }
 
+    pub const fn is_quadvector() -> bool {
+        G == 4
+    }
+
+    pub const fn is_pseudoscalar() -> bool {
+        G == ALGEBRA_DIMENSION
+    }
+
     pub const fn is_multivector() -> bool {
-        G > 3
+        G > ALGEBRA_DIMENSION
     }
 }
 
@@ -246,6 +350,7 @@ impl<const G1: u8, const G2: u8> OperationMatrix<G1, G2> {
     pub const CAN_OUTER_PRODUCT: bool = true;
     pub const CAN_INNER_PRODUCT: bool = true;
 
+    pub const GEOMETRIC_PRODUCT_RESULT: GradeSet = grade_calc::geometric_product_grades(G1, G2);
     pub const OUTER_PRODUCT_RESULT: u8 = grade_calc::outer_product_grade(G1, G2);
     pub const INNER_PRODUCT_RESULT: u8 = grade_calc::inner_product_grade(G1, G2);
 }
@@ -309,6 +414,35 @@ mod tests {
         assert_eq!(grade_calc::outer_product_grade(1, 2), 3);
         assert_eq!(grade_calc::inner_product_grade(2, 1), 1);
         assert_eq!(grade_calc::inner_product_grade(1, 1), 0);
+
+        // Cl(4,1): grades up to the pseudoscalar are representable
+        assert_eq!(grade_calc::outer_product_grade(2, 2), 4);
+        assert_eq!(grade_calc::outer_product_grade(2, 3), 5);
+        assert_eq!(grade_calc::outer_product_grade(3, 3), 255);
+        assert_eq!(grade_calc::outer_product_grade_in(2, 2, 3), 255);
+        assert_eq!(grade_calc::inner_product_grade(5, 1), 4);
+    }
+
+    #[test]
+    fn test_geometric_product_grades() {
+        let grades = |set: GradeSet| set.iter().collect::<Vec<_>>();
+
+        assert_eq!(grades(grade_calc::geometric_product_grades_in(1, 1, 3)), vec![0, 2]);
+        assert_eq!(grades(grade_calc::geometric_product_grades_in(1, 3, 3)), vec![2]);
+        assert_eq!(grades(grade_calc::geometric_product_grades_in(2, 2, 3)), vec![0, 2]);
+        assert_eq!(grades(grade_calc::geometric_product_grades_in(0, 3, 3)), vec![3]);
+
+        // Cl(4,1)
+        assert_eq!(grades(grade_calc::geometric_product_grades(2, 2)), vec![0, 2, 4]);
+        assert_eq!(grades(grade_calc::geometric_product_grades(2, 3)), vec![1, 3, 5]);
+        assert_eq!(grades(grade_calc::geometric_product_grades(1, 5)), vec![4]);
+        assert!(grade_calc::geometric_product_grades(6, 1).is_empty());
+
+        const VV: GradeSet = OperationMatrix::<1, 1>::GEOMETRIC_PRODUCT_RESULT;
+        assert!(VV.contains(0) && VV.contains(2) && VV.is_homogeneous_parity());
+        assert_eq!(GradeSet::single(3).single_grade(), Some(3));
+        assert_eq!([0u8, 2].into_iter().collect::<GradeSet>(), GradeSet::from_bits(0b101));
+        assert_eq!(GradeSet::all(5).len(), 6);
     }
 
     #[test]
@@ -329,11 +463,11 @@ mod tests {
         let s2 = ScalarType::scalar(3.0);
 
         let sum = safe_ops::add(s1, s2);
-        assert_eq!(sum.value, 5.0);
+        assert_eq!(*sum.value(), 5.0);
 
         let s3 = ScalarType::scalar(4.0);
         let product = safe_ops::scalar_multiply(2.0, s3);
-        assert_eq!(product.value, 8.0);
+        assert_eq!(*product.value(), 8.0);
     }
 
     #[test]
@@ -345,6 +479,8 @@ mod tests {
         assert!(!TypeInspector::<S>::is_vector());
         assert!(!TypeInspector::<V>::is_scalar());
         assert!(TypeInspector::<V>::is_vector());
+        assert!(TypeInspector::<crate::grade_indexed::PseudoscalarType<f64>>::is_pseudoscalar());
+        assert!(!TypeInspector::<crate::grade_indexed::QuadvectorType<f64>>::is_multivector());
     }
 
     #[test]
@@ -4,7 +4,9 @@
 
 use std::marker::PhantomData;
 use serde::{Deserialize, Serialize};
-use crate::ga_term::{Grade, Index, BladeTerm};
+use crate::ga_term::{
+    check_bivector, check_trivector, check_vector, checked, BasisIndex, BladeTerm, Grade, Index, IndexError, ALGEBRA_DIMENSION,
+};
 
 /// Grade marker for const generics
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,12 +16,25 @@ pub struct GradeMarker<const G: u8>;
 ///
 /// This provides compile-time grade safety by encoding the grade
 /// in the type system using const generics.
+///
+/// The wrapped value is reached through [`value`](Self::value),
+/// [`value_mut`](Self::value_mut), [`set_value`](Self::set_value) and
+/// [`into_inner`](Self::into_inner). The public `value` field is deprecated and
+/// becomes private with the `private-grade-indexed` feature, which will be the
+/// default once downstream code has moved to the accessors; after that the
+/// representation behind them is free to change.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GradeIndexed<T, const G: u8> {
+    #[cfg(not(feature = "private-grade-indexed"))]
+    #[deprecated(since = "0.1.0", note = "use `value()`, `value_mut()`, `set_value()` or `into_inner()`")]
     pub value: T,
+    #[cfg(feature = "private-grade-indexed")]
+    value: T,
     _phantom: PhantomData<GradeMarker<G>>,
 }
 
+// The constructor and accessors are the only code that touches the field
+#[allow(deprecated)]
 impl<T, const G: u8> GradeIndexed<T, G> {
     pub fn new(value: T) -> Self {
         Self {
@@ -29,29 +44,36 @@ impl<T, const G: u8> GradeIndexed<T, G> {
     }
 
     pub fn grade(&self) -> Grade {
-        match G {
-            0 => Grade::Scalar,
-            1 => Grade::Vector,
-            2 => Grade::Bivector,
-            3 => Grade::Trivector,
-            _ => Grade::Multivector,
-        }
+        Grade::from_index(G, ALGEBRA_DIMENSION)
     }
 
     pub const fn grade_const() -> u8 {
         G
     }
 
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Replace the wrapped value, returning the old one
+    pub fn set_value(&mut self, value: T) -> T {
+        std::mem::replace(&mut self.value, value)
+    }
+
     pub fn into_inner(self) -> T {
         self.value
     }
 
     pub fn as_ref(&self) -> &T {
-        &self.value
+        self.value()
     }
 
     pub fn as_mut(&mut self) -> &mut T {
-        &mut self.value
+        self.value_mut()
     }
 }
 
@@ -63,34 +85,32 @@ impl<T, const G: u8> From<T> for GradeIndexed<T, G> {
 
 impl<T, const G: u8> AsRef<T> for GradeIndexed<T, G> {
     fn as_ref(&self) -> &T {
-        &self.value
+        self.value()
     }
 }
 
 impl<T, const G: u8> AsMut<T> for GradeIndexed<T, G> {
     fn as_mut(&mut self) -> &mut T {
-        &mut self.value
+        self.value_mut()
     }
 }
 
 /// Type aliases for common grades
 pub type ScalarType<T> = GradeIndexed<T, 0>;
-pub type VectorType<T> = GradeIndexed<Vec<(Index, T)>, 1>;
-pub type BivectorType<T> = GradeIndexed<Vec<(Index, Index, T)>, 2>;
-pub type TrivectorType<T> = GradeIndexed<Vec<(Index, Index, Index, T)>, 3>;
+pub type VectorType<T> = GradeIndexed<Vec<(BasisIndex, T)>, 1>;
+pub type BivectorType<T> = GradeIndexed<Vec<(BasisIndex, BasisIndex, T)>, 2>;
+pub type TrivectorType<T> = GradeIndexed<Vec<(BasisIndex, BasisIndex, BasisIndex, T)>, 3>;
+pub type QuadvectorType<T> = GradeIndexed<Vec<BladeTerm<T>>, 4>;
+
+/// Pseudoscalar of an `N`-dimensional algebra (Cl(4,1) by default)
+pub type PseudoscalarType<T, const N: u8 = ALGEBRA_DIMENSION> = GradeIndexed<T, N>;
 
 /// Trait for grade-indexed types
 pub trait IsGradeIndexed {
     const GRADE: u8;
 
     fn grade(&self) -> Grade {
-        match Self::GRADE {
-            0 => Grade::Scalar,
-            1 => Grade::Vector,
-            2 => Grade::Bivector,
-            3 => Grade::Trivector,
-            _ => Grade::Multivector,
-        }
+        Grade::from_index(Self::GRADE, ALGEBRA_DIMENSION)
     }
 }
 
@@ -108,7 +128,7 @@ where
     type Output = GradeIndexed<T, G>;
 
     fn add(self, rhs: Self) -> Self::Output {
-        GradeIndexed::new(self.value + rhs.value)
+        GradeIndexed::new(self.into_inner() + rhs.into_inner())
     }
 }
 
@@ -121,11 +141,14 @@ where
     type Output = GradeIndexed<T, G>;
 
     fn mul(self, rhs: S) -> Self::Output {
-        GradeIndexed::new(self.value * rhs)
+        GradeIndexed::new(self.into_inner() * rhs)
     }
 }
 
 /// Factory functions for grade-indexed types
+///
+/// As with [`GATerm`](crate::ga_term::GATerm), the integer forms panic on an
+/// index outside the default algebra and the `try_` forms report it.
 impl<T> ScalarType<T> {
     pub fn scalar(value: T) -> Self {
         Self::new(value)
@@ -134,22 +157,223 @@ impl<T> ScalarType<T> {
 
 impl<T> VectorType<T> {
     pub fn vector(components: Vec<(Index, T)>) -> Self {
-        Self::new(components)
+        checked(Self::try_vector(components))
+    }
+
+    pub fn try_vector(components: Vec<(Index, T)>) -> Result<Self, IndexError> {
+        check_vector(components).map(Self::new)
     }
 }
 
 impl<T> BivectorType<T> {
     pub fn bivector(components: Vec<(Index, Index, T)>) -> Self {
-        Self::new(components)
+        checked(Self::try_bivector(components))
+    }
+
+    pub fn try_bivector(components: Vec<(Index, Index, T)>) -> Result<Self, IndexError> {
+        check_bivector(components).map(Self::new)
     }
 }
 
 impl<T> TrivectorType<T> {
     pub fn trivector(components: Vec<(Index, Index, Index, T)>) -> Self {
+        checked(Self::try_trivector(components))
+    }
+
+    pub fn try_trivector(components: Vec<(Index, Index, Index, T)>) -> Result<Self, IndexError> {
+        check_trivector(components).map(Self::new)
+    }
+}
+
+impl<T> QuadvectorType<T> {
+    pub fn quadvector(components: Vec<BladeTerm<T>>) -> Self {
+        Self::new(components)
+    }
+}
+
+impl<T, const N: u8> PseudoscalarType<T, N> {
+    pub fn pseudoscalar(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Why a sparse vector does not fit a [`FixedVector`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentError {
+    /// A basis index outside `1..=N`
+    Index(IndexError),
+    /// The same basis vector listed twice
+    Duplicate(BasisIndex),
+    /// A slice with the wrong number of components
+    Length { expected: usize, actual: usize },
+}
+
+impl std::fmt::Display for ComponentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComponentError::Index(error) => write!(f, "{}", error),
+            ComponentError::Duplicate(index) => write!(f, "basis vector {} listed twice", index),
+            ComponentError::Length { expected, actual } => write!(f, "{} components given for a {}-dimensional vector", actual, expected),
+        }
+    }
+}
+
+impl std::error::Error for ComponentError {}
+
+impl From<IndexError> for ComponentError {
+    fn from(error: IndexError) -> Self {
+        ComponentError::Index(error)
+    }
+}
+
+/// Grade-1 vector with exactly `N` components, the coefficients of `e1..eN`
+///
+/// Unlike [`VectorType`], whose sparse list can name any number of basis
+/// vectors, the component count is part of the type: vectors of different
+/// dimensions cannot be added, and `N` must lie in `1..=ALGEBRA_DIMENSION`:
+///
+/// ```compile_fail
+/// use gafro_modern::grade_indexed::FixedVector;
+///
+/// let a = FixedVector::new([1.0, 2.0, 3.0]);
+/// let b = FixedVector::new([1.0, 2.0, 3.0, 4.0, 5.0]);
+/// let _ = a + b;
+/// ```
+///
+/// ```compile_fail
+/// use gafro_modern::grade_indexed::FixedVector;
+///
+/// let _ = FixedVector::new([0.0; 7]);
+/// ```
+///
+/// Sparse vectors convert with [`TryFrom`], which checks the indices at run time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedVector<T, const N: usize> {
+    components: [T; N],
+}
+
+impl<T, const N: usize> FixedVector<T, N> {
+    /// Evaluated on construction, so an unsupported `N` fails to compile
+    const DIMENSION_CHECK: () = assert!(N >= 1 && N <= ALGEBRA_DIMENSION as usize, "vector dimension outside the algebra");
+
+    pub const fn new(components: [T; N]) -> Self {
+        let () = Self::DIMENSION_CHECK;
+        Self { components }
+    }
+
+    pub const fn dimension() -> usize {
+        N
+    }
+
+    pub fn grade(&self) -> Grade {
+        Grade::Vector
+    }
+
+    pub fn components(&self) -> &[T; N] {
+        &self.components
+    }
+
+    pub fn into_components(self) -> [T; N] {
+        self.components
+    }
+
+    /// Coefficient of `e_index`; `None` outside `1..=N`
+    pub fn get(&self, index: Index) -> Option<&T> {
+        usize::try_from(index).ok()?.checked_sub(1).and_then(|i| self.components.get(i))
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> FixedVector<U, N> {
+        FixedVector::new(self.components.map(f))
+    }
+}
+
+impl<T: Copy + Default + std::ops::Add<Output = T> + std::ops::Mul<Output = T>, const N: usize> FixedVector<T, N> {
+    /// Euclidean inner product
+    pub fn dot(&self, other: &Self) -> T {
+        self.components.iter().zip(&other.components).fold(T::default(), |sum, (&a, &b)| sum + a * b)
+    }
+}
+
+impl<T: Default, const N: usize> Default for FixedVector<T, N> {
+    fn default() -> Self {
+        Self::new(std::array::from_fn(|_| T::default()))
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for FixedVector<T, N> {
+    fn from(components: [T; N]) -> Self {
         Self::new(components)
     }
 }
 
+impl<T: Copy, const N: usize> TryFrom<&[T]> for FixedVector<T, N> {
+    type Error = ComponentError;
+
+    fn try_from(components: &[T]) -> Result<Self, ComponentError> {
+        let components = <[T; N]>::try_from(components).map_err(|_| ComponentError::Length { expected: N, actual: components.len() })?;
+        Ok(Self::new(components))
+    }
+}
+
+/// Sparse form listing every component, `e1` first
+impl<T, const N: usize> From<FixedVector<T, N>> for VectorType<T> {
+    fn from(vector: FixedVector<T, N>) -> Self {
+        VectorType::new(BasisIndex::basis(N as u8).zip(vector.components).collect())
+    }
+}
+
+/// Dense form of a sparse vector; basis vectors it does not list are zero
+impl<T: Default, const N: usize> TryFrom<VectorType<T>> for FixedVector<T, N> {
+    type Error = ComponentError;
+
+    fn try_from(vector: VectorType<T>) -> Result<Self, ComponentError> {
+        let mut components: [Option<T>; N] = std::array::from_fn(|_| None);
+        for (index, value) in vector.into_inner() {
+            let index = BasisIndex::new(index.index(), N as u8)?;
+            if components[usize::from(index.get()) - 1].replace(value).is_some() {
+                return Err(ComponentError::Duplicate(index));
+            }
+        }
+        Ok(Self::new(components.map(Option::unwrap_or_default)))
+    }
+}
+
+impl From<FixedVector<f64, 3>> for crate::linalg::Vector3 {
+    fn from(v: FixedVector<f64, 3>) -> Self {
+        v.components
+    }
+}
+
+impl<T, const N: usize> IsGradeIndexed for FixedVector<T, N> {
+    const GRADE: u8 = 1;
+}
+
+impl<T: std::ops::Add<Output = T>, const N: usize> std::ops::Add for FixedVector<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut rhs = rhs.components.into_iter();
+        self.map(|a| a + rhs.next().expect("same dimension"))
+    }
+}
+
+impl<T: std::ops::Sub<Output = T>, const N: usize> std::ops::Sub for FixedVector<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let mut rhs = rhs.components.into_iter();
+        self.map(|a| a - rhs.next().expect("same dimension"))
+    }
+}
+
+impl<T: std::ops::Mul<Output = T> + Copy, const N: usize> std::ops::Mul<T> for FixedVector<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        self.map(|a| a * rhs)
+    }
+}
+
 /// Grade checking utilities
 pub struct GradeChecker<T> {
     _phantom: PhantomData<T>,
@@ -172,8 +396,16 @@ impl<T> GradeChecker<T> {
         G == 3
     }
 
+    pub fn is_quadvector<const G: u8>() -> bool {
+        G == 4
+    }
+
+    pub fn is_pseudoscalar<const G: u8, const N: u8>() -> bool {
+        G == N
+    }
+
     pub fn is_multivector<const G: u8>() -> bool {
-        G > 3
+        G > ALGEBRA_DIMENSION
     }
 }
 
@@ -206,11 +438,21 @@ mod tests {
     fn test_grade_indexed_creation() {
         let scalar: ScalarType<f64> = ScalarType::scalar(3.14);
         assert_eq!(scalar.grade(), Grade::Scalar);
-        assert_eq!(scalar.value, 3.14);
+        assert_eq!(*scalar.value(), 3.14);
 
         let vector: VectorType<f64> = VectorType::vector(vec![(1, 2.0), (2, 3.0)]);
         assert_eq!(vector.grade(), Grade::Vector);
-        assert_eq!(vector.value.len(), 2);
+        assert_eq!(vector.value().len(), 2);
+    }
+
+    #[test]
+    fn test_value_accessors() {
+        let [e1, e2, e3] = [1, 2, 3].map(|i| BasisIndex::try_from(i).unwrap());
+        let mut vector: VectorType<f64> = VectorType::vector(vec![(1, 2.0)]);
+        vector.value_mut().push((e2, 3.0));
+        assert_eq!(vector.set_value(vec![(e3, 1.0)]), vec![(e1, 2.0), (e2, 3.0)]);
+        assert_eq!(vector.value(), &vec![(e3, 1.0)]);
+        assert_eq!(vector.into_inner(), vec![(e3, 1.0)]);
     }
 
     #[test]
@@ -219,12 +461,12 @@ mod tests {
         let s2: ScalarType<f64> = ScalarType::scalar(3.0);
 
         let sum = s1 + s2;
-        assert_eq!(sum.value, 5.0);
+        assert_eq!(*sum.value(), 5.0);
         assert_eq!(sum.grade(), Grade::Scalar);
 
         let s3: ScalarType<f64> = ScalarType::scalar(2.0);
         let product = s3 * 3.0;
-        assert_eq!(product.value, 6.0);
+        assert_eq!(*product.value(), 6.0);
     }
 
     #[test]
@@ -234,6 +476,52 @@ mod tests {
         assert!(GradeChecker::<f64>::is_vector::<1>());
         assert!(GradeChecker::<f64>::is_bivector::<2>());
         assert!(GradeChecker::<f64>::is_trivector::<3>());
+        assert!(GradeChecker::<f64>::is_quadvector::<4>());
+        assert!(GradeChecker::<f64>::is_pseudoscalar::<5, 5>());
+        assert!(GradeChecker::<f64>::is_pseudoscalar::<3, 3>());
+        assert!(!GradeChecker::<f64>::is_multivector::<4>());
+    }
+
+    #[test]
+    fn test_higher_grades() {
+        let quadvector: QuadvectorType<f64> = QuadvectorType::quadvector(vec![BladeTerm::new(vec![1, 2, 3, 4], 1.0)]);
+        assert_eq!(quadvector.grade(), Grade::Quadvector);
+
+        let pseudoscalar: PseudoscalarType<f64> = PseudoscalarType::pseudoscalar(2.0);
+        assert_eq!(pseudoscalar.grade(), Grade::Pseudoscalar);
+        assert_eq!(PseudoscalarType::<f64>::grade_const(), 5);
+    }
+
+    #[test]
+    fn test_fixed_vectors() {
+        let a = FixedVector::new([1.0, 2.0, 3.0]);
+        let b: FixedVector<f64, 3> = [0.5, 0.0, -1.0].into();
+        assert_eq!((a + b) * 2.0, FixedVector::new([3.0, 4.0, 4.0]));
+        assert_eq!((a - b).dot(&a), 0.5 + 4.0 + 12.0);
+        assert_eq!((a.get(3), a.get(0), a.get(4)), (Some(&3.0), None, None));
+        assert_eq!(FixedVector::<f64, 3>::dimension(), 3);
+        assert_eq!(<FixedVector<f64, 3> as IsGradeIndexed>::GRADE, VectorType::<f64>::grade_const());
+
+        let sparse: VectorType<f64> = a.into();
+        assert_eq!(sparse, VectorType::vector(vec![(1, 1.0), (2, 2.0), (3, 3.0)]));
+        assert_eq!(FixedVector::try_from(sparse), Ok(a));
+        assert_eq!(FixedVector::try_from(VectorType::vector(vec![(5, 2.0), (2, 1.0)])), Ok(FixedVector::new([0.0, 1.0, 0.0, 0.0, 2.0])));
+
+        let five = VectorType::vector((1..=5).map(|i| (i, 1.0)).collect());
+        assert_eq!(
+            FixedVector::<f64, 3>::try_from(five),
+            Err(ComponentError::Index(IndexError::OutOfRange { index: 4, dimension: 3 }))
+        );
+        let twice = FixedVector::<f64, 3>::try_from(VectorType::vector(vec![(2, 1.0), (2, 1.0)]));
+        assert_eq!(twice.unwrap_err().to_string(), "basis vector e2 listed twice");
+
+        // Sparse vectors are checked against the algebra when built
+        assert_eq!(VectorType::<f64>::try_vector(vec![(0, 1.0)]), Err(IndexError::NotPositive(0)));
+        assert_eq!(VectorType::<f64>::try_vector(vec![(-3, 1.0)]), Err(IndexError::NotPositive(-3)));
+        assert_eq!(BivectorType::<f64>::try_bivector(vec![(1, 6, 1.0)]), Err(IndexError::OutOfRange { index: 6, dimension: 5 }));
+        assert!(TrivectorType::try_trivector(vec![(1, 2, 3, 1.0)]).is_ok());
+        assert_eq!(FixedVector::<f64, 3>::try_from(&[1.0, 2.0][..]), Err(ComponentError::Length { expected: 3, actual: 2 }));
+        assert_eq!(crate::linalg::Vector3::from(FixedVector::from([1.0, 2.0, 3.0])), [1.0, 2.0, 3.0]);
     }
 
     #[test]
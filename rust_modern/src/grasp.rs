@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Contacts, friction cones and grasp wrench spaces
+//!
+//! A force acting along a line is the line itself scaled by the force
+//! magnitude: in Plücker form the direction is the force and the moment is the
+//! torque about the origin. [`Wrench`] converts both ways with [`Line`].
+//!
+//! Each point contact with Coulomb friction may push along any direction inside
+//! its friction cone, approximated by a pyramid of `cone_edges` unit forces. The
+//! wrenches of all edges span the grasp wrench space; a grasp is in force
+//! closure when their non-negative combinations reach every wrench, which is
+//! tested by solving non-negative least squares for the twelve signed unit
+//! wrenches. Torques are divided by a characteristic length so that forces and
+//! torques are compared in the same unit.
+
+use serde::{Deserialize, Serialize};
+use crate::linalg::{self, dense, Vector3};
+use crate::primitives::{Direction, Line};
+use crate::si_units::{Length, TAU};
+
+/// Residual below which a wrench counts as reachable
+pub const REACHABILITY_TOLERANCE: f64 = 1e-9;
+
+/// Force and torque about the grasp center
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Wrench {
+    pub force: Vector3,
+    pub torque: Vector3,
+}
+
+impl Wrench {
+    pub fn new(force: Vector3, torque: Vector3) -> Self {
+        Self { force, torque }
+    }
+
+    /// The force carried by a line (direction = force, moment = torque)
+    pub fn from_line(line: &Line) -> Self {
+        Self::new(line.direction, line.moment)
+    }
+
+    /// Line of action; `None` for a pure torque
+    pub fn line_of_action(&self) -> Option<Line> {
+        (linalg::norm(self.force) > 0.0).then(|| Line::new(self.force, self.torque))
+    }
+
+    pub fn to_array(&self) -> [f64; 6] {
+        let (f, t) = (self.force, self.torque);
+        [f[0], f[1], f[2], t[0], t[1], t[2]]
+    }
+}
+
+/// Point contact with Coulomb friction
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Contact {
+    pub point: Vector3,
+    /// Direction the finger pushes, into the object
+    pub normal: Direction,
+    /// Friction coefficient μ; the cone half-angle is atan μ
+    pub friction: f64,
+}
+
+impl Contact {
+    /// `None` when `normal` is zero
+    pub fn new(point: Vector3, normal: Vector3, friction: f64) -> Option<Self> {
+        Some(Self { point, normal: Direction::new(normal)?, friction: friction.max(0.0) })
+    }
+
+    /// Edge forces of the friction pyramid, each with unit normal component
+    pub fn cone_edges(&self, edges: usize) -> Vec<Vector3> {
+        let n = self.normal.vector();
+        if self.friction == 0.0 {
+            return vec![n];
+        }
+        // Any tangent basis orthogonal to the normal
+        let helper = if n[0].abs() < 0.9 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+        let t1 = Direction::new(linalg::cross(n, helper)).expect("helper is not parallel to the normal").vector();
+        let t2 = linalg::cross(n, t1);
+        (0..edges.max(3))
+            .map(|k| {
+                let angle = TAU * k as f64 / edges.max(3) as f64;
+                let tangent = linalg::add(linalg::scale(t1, angle.cos()), linalg::scale(t2, angle.sin()));
+                linalg::add(n, linalg::scale(tangent, self.friction))
+            })
+            .collect()
+    }
+}
+
+/// A set of contacts on one object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grasp {
+    pub contacts: Vec<Contact>,
+    /// Point torques are taken about, usually the object's center of mass
+    pub center: Vector3,
+    /// Length dividing torques so they compare with forces, e.g. the object radius
+    pub torque_scale: Length,
+    /// Edges of each linearized friction cone
+    pub cone_edges: usize,
+}
+
+impl Grasp {
+    pub fn new(contacts: Vec<Contact>, center: Vector3, torque_scale: Length) -> Self {
+        Self { contacts, center, torque_scale, cone_edges: 8 }
+    }
+
+    /// Unit-normal-force wrench of every cone edge, contact by contact
+    pub fn wrench_space(&self) -> Vec<Wrench> {
+        let scale = 1.0 / self.torque_scale.value();
+        self.contacts
+            .iter()
+            .flat_map(|contact| {
+                let arm = linalg::sub(contact.point, self.center);
+                contact
+                    .cone_edges(self.cone_edges)
+                    .into_iter()
+                    .map(move |force| Wrench::new(force, linalg::scale(linalg::cross(arm, force), scale)))
+            })
+            .collect()
+    }
+
+    /// Whether the contacts can resist every external wrench
+    pub fn is_force_closure(&self) -> bool {
+        let wrenches = self.wrench_space();
+        (0..12).all(|k| {
+            let mut target = [0.0; 6];
+            target[k % 6] = if k < 6 { 1.0 } else { -1.0 };
+            reach(&wrenches, &target).1 <= REACHABILITY_TOLERANCE
+        })
+    }
+
+    /// Contact forces, one per contact and each inside its friction cone, that
+    /// balance `external` (torque about the grasp center, unscaled); `None` when
+    /// the grasp cannot resist it
+    pub fn resist(&self, external: &Wrench) -> Option<Vec<Vector3>> {
+        let wrenches = self.wrench_space();
+        let scale = 1.0 / self.torque_scale.value();
+        let target = Wrench::new(linalg::scale(external.force, -1.0), linalg::scale(external.torque, -scale)).to_array();
+        let (weights, residual) = reach(&wrenches, &target);
+        if residual > REACHABILITY_TOLERANCE * (1.0 + target.iter().map(|v| v * v).sum::<f64>().sqrt()) {
+            return None;
+        }
+        let edges_per_contact = wrenches.len() / self.contacts.len().max(1);
+        Some(
+            weights
+                .chunks(edges_per_contact)
+                .zip(wrenches.chunks(edges_per_contact))
+                .map(|(w, edges)| edges.iter().zip(w).fold([0.0; 3], |f, (edge, wi)| linalg::add(f, linalg::scale(edge.force, *wi))))
+                .collect(),
+        )
+    }
+}
+
+/// Non-negative weights of `wrenches` best reaching `target`, and the residual
+fn reach(wrenches: &[Wrench], target: &[f64; 6]) -> (Vec<f64>, f64) {
+    let n = wrenches.len();
+    let columns: Vec<[f64; 6]> = wrenches.iter().map(Wrench::to_array).collect();
+    let a: Vec<f64> = (0..6).flat_map(|i| columns.iter().map(move |c| c[i])).collect();
+    dense::nnls(&a, 6, n, target)
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Contacts on the unit sphere pushing towards its center
+    fn sphere_grasp(points: &[Vector3], friction: f64) -> Grasp {
+        let contacts = points.iter().map(|&p| Contact::new(p, linalg::scale(p, -1.0), friction).unwrap()).collect();
+        Grasp::new(contacts, [0.0; 3], Length::new(1.0))
+    }
+
+    #[test]
+    fn test_wrench_lines() {
+        // A unit force along z through (1, 0, 0) twists about -y
+        let wrench = Wrench::from_line(&Line::through([1.0, 0.0, 0.0], [0.0, 0.0, 1.0]));
+        assert_eq!(wrench.torque, [0.0, -1.0, 0.0]);
+        let line = wrench.line_of_action().unwrap();
+        assert!(line.distance_to_point([1.0, 0.0, 5.0]) < 1e-12);
+        assert!(Wrench::new([0.0; 3], [1.0, 0.0, 0.0]).line_of_action().is_none());
+
+        let contact = Contact::new([0.0; 3], [0.0, 0.0, 2.0], 0.5).unwrap();
+        for edge in contact.cone_edges(6) {
+            assert!((edge[2] - 1.0).abs() < 1e-12 && (edge[0].hypot(edge[1]) - 0.5).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_force_closure() {
+        let third = TAU / 3.0;
+        let tripod: Vec<Vector3> = (0..3).map(|k| [(third * k as f64).cos(), (third * k as f64).sin(), 0.0]).collect();
+        assert!(sphere_grasp(&tripod, 0.5).is_force_closure());
+        // Without friction, pushes through the center cannot resist any torque
+        assert!(!sphere_grasp(&tripod, 0.0).is_force_closure());
+        // Two antipodal fingers cannot resist a torque about the line joining them
+        assert!(!sphere_grasp(&[[1.0, 0.0, 0.0], [-1.0, 0.0, 0.0]], 1.0).is_force_closure());
+
+        // Hold the sphere against gravity: contact forces stay in their cones and balance it
+        let grasp = sphere_grasp(&tripod, 0.5);
+        let gravity = Wrench::new([0.0, 0.0, -9.81], [0.0; 3]);
+        let forces = grasp.resist(&gravity).unwrap();
+        let total = forces.iter().fold([0.0; 3], |sum, f| linalg::add(sum, *f));
+        assert!(linalg::norm(linalg::add(total, gravity.force)) < 1e-6);
+        for (force, contact) in forces.iter().zip(&grasp.contacts) {
+            let normal = linalg::dot(*force, contact.normal.vector());
+            let tangential = linalg::norm(linalg::sub(*force, linalg::scale(contact.normal.vector(), normal)));
+            assert!(tangential <= contact.friction * normal + 1e-9);
+        }
+
+        let slippery = sphere_grasp(&tripod, 0.0);
+        assert!(slippery.resist(&gravity).is_none());
+    }
+}
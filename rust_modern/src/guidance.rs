@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Line-of-sight path following
+//!
+//! [`LineOfSight`] steers toward a point a fixed lookahead distance ahead
+//! along the current path segment, so the desired course is the segment
+//! direction corrected by `atan(-e / Δ)` for cross-track error `e` and
+//! lookahead `Δ`. A segment is finished once the vehicle is within the
+//! acceptance distance of its end or has passed it along the track.
+//!
+//! Courses are counter-clockwise from east, as in [`crate::mission`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::linalg::Vector3;
+use crate::si_units::{Angle, Length};
+
+/// Desired course and tracking errors on the active segment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LosCommand {
+    pub course: Angle,
+    /// Signed distance from the segment, positive to its left
+    pub cross_track: Length,
+    /// Index of the segment's start waypoint
+    pub segment: usize,
+    /// Depth of the segment's end waypoint
+    pub depth: Length,
+}
+
+/// Lookahead-based guidance along a list of waypoints
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineOfSight {
+    waypoints: Vec<Vector3>,
+    segment: usize,
+    lookahead: f64,
+    acceptance: f64,
+}
+
+impl LineOfSight {
+    /// Follow `waypoints` (East-North-Up), starting on the first segment
+    pub fn new(waypoints: Vec<Vector3>, lookahead: Length, acceptance: Length) -> Self {
+        assert!(*lookahead.value() > 0.0, "lookahead distance must be positive");
+        Self { waypoints, segment: 0, lookahead: *lookahead.value(), acceptance: *acceptance.value() }
+    }
+
+    pub fn segment(&self) -> usize {
+        self.segment
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.segment + 1 >= self.waypoints.len()
+    }
+
+    /// Command for the current position; `None` once the last waypoint is reached
+    pub fn update(&mut self, position: Vector3) -> Option<LosCommand> {
+        while !self.is_finished() {
+            let (start, end) = (self.waypoints[self.segment], self.waypoints[self.segment + 1]);
+            let (dx, dy) = (end[0] - start[0], end[1] - start[1]);
+            let length = dx.hypot(dy);
+            let (ex, ey) = (position[0] - start[0], position[1] - start[1]);
+            let along = if length > 0.0 { (ex * dx + ey * dy) / length } else { 0.0 };
+            let remaining = (end[0] - position[0]).hypot(end[1] - position[1]);
+            if remaining <= self.acceptance || along >= length {
+                self.segment += 1;
+                continue;
+            }
+            let direction = dy.atan2(dx);
+            let cross_track = (ey * dx - ex * dy) / length;
+            return Some(LosCommand {
+                course: Angle::new(direction + (-cross_track / self.lookahead).atan()),
+                cross_track: Length::new(cross_track),
+                segment: self.segment,
+                depth: Length::new(-end[2]),
+            });
+        }
+        None
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::TAU;
+
+    #[test]
+    fn test_course_corrects_cross_track() {
+        let mut guidance = LineOfSight::new(vec![[0.0, 0.0, -5.0], [100.0, 0.0, -5.0]], Length::new(10.0), Length::new(2.0));
+        // 10 m left of an eastward track: steer 45° to the right
+        let command = guidance.update([20.0, 10.0, -5.0]).unwrap();
+        assert!((command.cross_track.value() - 10.0).abs() < 1e-12);
+        assert!((command.course.value() + TAU / 8.0).abs() < 1e-12);
+        assert_eq!(*command.depth.value(), 5.0);
+        assert!(guidance.update([50.0, 0.0, -5.0]).unwrap().course.value().abs() < 1e-12);
+        assert_eq!(guidance.update([99.0, 0.5, -5.0]), None);
+        assert!(guidance.is_finished());
+    }
+
+    #[test]
+    fn test_follows_planned_path() {
+        use crate::planner::{plan, BathymetryGrid, PlannerConfig};
+        use crate::si_units::Velocity;
+
+        let depths = (0..100).map(|i| Length::new(if i % 10 == 5 && i / 10 < 8 { 2.0 } else { 20.0 })).collect();
+        let grid = BathymetryGrid::new([Length::new(0.0); 2], Length::new(2.0), 10, 10, depths).unwrap();
+        let config = PlannerConfig { travel_depth: Length::new(5.0), clearance: Length::new(1.0), speed: Velocity::new(1.0) };
+        let path = plan(&grid, &config, [1.0, 1.0, 0.0], [19.0, 1.0, 0.0]).unwrap();
+        let mut guidance = LineOfSight::new(path.waypoints.clone(), Length::new(3.0), Length::new(0.5));
+
+        // Unicycle at 1 m/s turning instantly onto the commanded course
+        let mut position = path.waypoints[0];
+        let mut steps = 0;
+        while let Some(command) = guidance.update(position) {
+            let (sin, cos) = command.course.value().sin_cos();
+            position = [position[0] + 0.1 * cos, position[1] + 0.1 * sin, position[2]];
+            assert!(grid.cell_at([position[0], position[1]]).is_some_and(|cell| grid.is_traversable(cell, &config)));
+            steps += 1;
+            assert!(steps < 2000, "guidance did not converge");
+        }
+        let goal = path.waypoints.last().unwrap();
+        assert!((position[0] - goal[0]).hypot(position[1] - goal[1]) < 0.6);
+    }
+}
@@ -0,0 +1,366 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Hydrostatic stability: flotation, metacentric height and righting arms
+//!
+//! A [`Hull`] is built from non-overlapping volume primitives placed by motors
+//! in the body frame (x forward, y left, z up). The primitives are split into
+//! tetrahedra, and the part below a waterplane is clipped exactly, so the
+//! displaced volume and center of buoyancy vary smoothly with heel and draft.
+//! Cylinders are replaced by prisms of equal cross-section.
+//!
+//! [`Stability`] floats the hull at each heel angle with the displacement its
+//! weight requires and measures the righting arm `GZ`, the horizontal distance
+//! from the center of buoyancy to the center of gravity. Positive arms push a
+//! heeled vehicle back upright. A vehicle heavier than the water its hull
+//! displaces when fully submerged is taken as held at depth, its buoyancy
+//! acting at the hull centroid as for an underwater vehicle.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::linalg::{self, Vector3};
+use crate::motor::{Motor, Rotor};
+use crate::si_units::{marine, Angle, Density, Length, Mass, Torque, Volume, TAU};
+
+/// Sides of the prism standing in for a cylinder
+const CYLINDER_SIDES: usize = 48;
+
+/// Heel used to measure the initial slope of the righting-arm curve (rad)
+const SMALL_HEEL: f64 = 1e-3;
+
+/// Volume primitive of a hull, centered on its pose
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HullPrimitive {
+    /// Box with edge lengths along its local x, y and z
+    Box { pose: Motor, size: [Length; 3] },
+    /// Cylinder with its axis along its local z
+    Cylinder { pose: Motor, radius: Length, length: Length },
+}
+
+impl HullPrimitive {
+    fn tetrahedra(&self, out: &mut Vec<[Vector3; 4]>) {
+        match self {
+            HullPrimitive::Box { pose, size } => {
+                let half = size.map(|edge| 0.5 * edge.value());
+                let axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+                // Kuhn triangulation: one tetrahedron per path along the edges
+                for order in [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]] {
+                    let mut corner = half.map(|h| -h);
+                    let mut tetrahedron = [corner; 4];
+                    for (k, axis) in order.into_iter().enumerate() {
+                        corner = linalg::add(corner, linalg::scale(axes[axis], 2.0 * half[axis]));
+                        tetrahedron[k + 1] = corner;
+                    }
+                    out.push(tetrahedron.map(|p| pose.apply_point(p)));
+                }
+            }
+            HullPrimitive::Cylinder { pose, radius, length } => {
+                let step = TAU / CYLINDER_SIDES as f64;
+                // Circumradius giving the polygon the circle's area
+                let r = radius.value() * (TAU / (CYLINDER_SIDES as f64 * step.sin())).sqrt();
+                let h = 0.5 * length.value();
+                let rim = |k: usize, z: f64| {
+                    let angle = k as f64 * step;
+                    pose.apply_point([r * angle.cos(), r * angle.sin(), z])
+                };
+                for k in 0..CYLINDER_SIDES {
+                    let bottom = [pose.apply_point([0.0, 0.0, -h]), rim(k, -h), rim(k + 1, -h)];
+                    let top = [pose.apply_point([0.0, 0.0, h]), rim(k, h), rim(k + 1, h)];
+                    out.extend(prism(bottom, top));
+                }
+            }
+        }
+    }
+}
+
+/// Triangular prism as three tetrahedra; `top[i]` lies above `bottom[i]`
+fn prism(bottom: [Vector3; 3], top: [Vector3; 3]) -> [[Vector3; 4]; 3] {
+    [
+        [bottom[0], bottom[1], bottom[2], top[2]],
+        [bottom[0], bottom[1], top[1], top[2]],
+        [bottom[0], top[0], top[1], top[2]],
+    ]
+}
+
+/// Volume and first moment (volume times centroid) of a tetrahedron
+fn moments(t: &[Vector3; 4]) -> (f64, Vector3) {
+    let [a, b, c, d] = *t;
+    let volume = linalg::dot(linalg::sub(b, a), linalg::cross(linalg::sub(c, a), linalg::sub(d, a))).abs() / 6.0;
+    let sum = linalg::add(linalg::add(a, b), linalg::add(c, d));
+    (volume, linalg::scale(sum, 0.25 * volume))
+}
+
+/// Moments of the part of a tetrahedron with `z <= waterline`
+fn clipped_moments(t: &[Vector3; 4], waterline: f64) -> (f64, Vector3) {
+    let crossing = |i: usize, j: usize| {
+        let (p, q) = (t[i], t[j]);
+        linalg::add(p, linalg::scale(linalg::sub(q, p), (waterline - p[2]) / (q[2] - p[2])))
+    };
+    let (below, above): (Vec<usize>, Vec<usize>) = (0..4).partition(|&i| t[i][2] <= waterline);
+    match below.len() {
+        0 => (0.0, [0.0; 3]),
+        4 => moments(t),
+        1 => {
+            let a = below[0];
+            moments(&[t[a], crossing(a, above[0]), crossing(a, above[1]), crossing(a, above[2])])
+        }
+        3 => {
+            let u = above[0];
+            let (volume, first) = moments(t);
+            let (dry, dry_first) = moments(&[t[u], crossing(below[0], u), crossing(below[1], u), crossing(below[2], u)]);
+            (volume - dry, linalg::sub(first, dry_first))
+        }
+        _ => {
+            let [a, b] = [below[0], below[1]];
+            let [c, d] = [above[0], above[1]];
+            let wedge = prism([t[a], crossing(a, c), crossing(a, d)], [t[b], crossing(b, c), crossing(b, d)]);
+            Hull::sum(wedge.iter().map(moments))
+        }
+    }
+}
+
+/// Waterline and buoyancy of a hull floating at a given heel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flotation {
+    /// Height of the waterplane above the body origin, measured vertically;
+    /// `None` when the hull is fully submerged
+    pub waterline: Option<Length>,
+    /// Center of buoyancy in the body frame
+    pub center_of_buoyancy: [Length; 3],
+}
+
+/// Hull volume as a set of tetrahedra in the body frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hull {
+    tetrahedra: Vec<[Vector3; 4]>,
+    volume: f64,
+    centroid: Vector3,
+}
+
+impl Hull {
+    pub fn new(primitives: &[HullPrimitive]) -> Self {
+        let mut tetrahedra = Vec::new();
+        for primitive in primitives {
+            primitive.tetrahedra(&mut tetrahedra);
+        }
+        let (volume, first) = Self::sum(tetrahedra.iter().map(moments));
+        let centroid = if volume > 0.0 { linalg::scale(first, 1.0 / volume) } else { [0.0; 3] };
+        Self { tetrahedra, volume, centroid }
+    }
+
+    pub fn volume(&self) -> Volume {
+        Volume::new(self.volume)
+    }
+
+    pub fn centroid(&self) -> [Length; 3] {
+        self.centroid.map(Length::new)
+    }
+
+    fn sum(moments: impl Iterator<Item = (f64, Vector3)>) -> (f64, Vector3) {
+        moments.fold((0.0, [0.0; 3]), |(v, m), (dv, dm)| (v + dv, linalg::add(m, dm)))
+    }
+
+    /// Float at `heel` (roll about the body x axis) displacing `displacement`;
+    /// `None` for an empty hull
+    pub fn flotation(&self, heel: Angle, displacement: Volume) -> Option<Flotation> {
+        if self.volume <= 0.0 {
+            return None;
+        }
+        let rotor = Rotor::from_axis_angle([1.0, 0.0, 0.0], *heel.value());
+        let to_body = |p: Vector3| rotor.reverse().apply(p).map(Length::new);
+        let target = *displacement.value();
+        if target >= self.volume {
+            return Some(Flotation { waterline: None, center_of_buoyancy: self.centroid() });
+        }
+
+        let heeled: Vec<[Vector3; 4]> = self.tetrahedra.iter().map(|t| t.map(|p| rotor.apply(p))).collect();
+        let heights = heeled.iter().flatten().map(|p| p[2]);
+        let (mut low, mut high) = heights.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), z| (lo.min(z), hi.max(z)));
+        let submerged = |waterline: f64| Self::sum(heeled.iter().map(|t| clipped_moments(t, waterline)));
+        for _ in 0..200 {
+            let middle = 0.5 * (low + high);
+            if middle <= low || middle >= high {
+                break;
+            }
+            if submerged(middle).0 < target {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let waterline = 0.5 * (low + high);
+        let (volume, first) = submerged(waterline);
+        let center = if volume > 0.0 { linalg::scale(first, 1.0 / volume) } else { [0.0, 0.0, waterline] };
+        Some(Flotation { waterline: Some(Length::new(waterline)), center_of_buoyancy: to_body(center) })
+    }
+}
+
+/// Errors raised by the stability analysis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HydrostaticsError {
+    EmptyHull,
+    NonPositiveMass,
+}
+
+impl fmt::Display for HydrostaticsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HydrostaticsError::EmptyHull => write!(f, "hull has no volume"),
+            HydrostaticsError::NonPositiveMass => write!(f, "vehicle mass must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for HydrostaticsError {}
+
+/// Righting arm and moment at one heel angle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RightingPoint {
+    pub heel: Angle,
+    pub arm: Length,
+    pub moment: Torque,
+}
+
+/// Transverse stability of a hull carrying a given mass
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stability {
+    hull: Hull,
+    mass: Mass,
+    center_of_gravity: Vector3,
+    density: Density,
+}
+
+impl Stability {
+    /// Vehicle of `mass` with its center of gravity in the body frame, in sea water
+    pub fn new(hull: Hull, mass: Mass, center_of_gravity: [Length; 3]) -> Result<Self, HydrostaticsError> {
+        if hull.volume <= 0.0 {
+            return Err(HydrostaticsError::EmptyHull);
+        }
+        if *mass.value() <= 0.0 {
+            return Err(HydrostaticsError::NonPositiveMass);
+        }
+        let center_of_gravity = center_of_gravity.map(|c| *c.value());
+        Ok(Self { hull, mass, center_of_gravity, density: marine::water_density() })
+    }
+
+    pub fn with_density(mut self, density: Density) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Water volume whose weight equals the vehicle's
+    pub fn displacement(&self) -> Volume {
+        Volume::new(self.mass.value() / self.density.value())
+    }
+
+    /// Whether the hull floats at the surface rather than being fully submerged
+    pub fn floats(&self) -> bool {
+        self.displacement().value() < &self.hull.volume
+    }
+
+    /// Volume actually displaced, at most the whole hull
+    fn buoyant_volume(&self) -> f64 {
+        self.displacement().value().min(self.hull.volume)
+    }
+
+    pub fn flotation(&self, heel: Angle) -> Flotation {
+        self.hull.flotation(heel, Volume::new(self.buoyant_volume())).expect("hull volume checked on construction")
+    }
+
+    /// Righting arm `GZ` at `heel`; positive arms oppose the heel for positive angles
+    pub fn righting_arm(&self, heel: Angle) -> Length {
+        let rotor = Rotor::from_axis_angle([1.0, 0.0, 0.0], *heel.value());
+        let buoyancy = rotor.apply(self.flotation(heel).center_of_buoyancy.map(|c| *c.value()));
+        let gravity = rotor.apply(self.center_of_gravity);
+        Length::new(gravity[1] - buoyancy[1])
+    }
+
+    /// Buoyant force times the righting arm
+    pub fn righting_moment(&self, heel: Angle) -> Torque {
+        let buoyancy = self.density.value() * self.buoyant_volume() * marine::gravity::<f64>().value();
+        Torque::new(buoyancy * self.righting_arm(heel).value())
+    }
+
+    /// Initial metacentric height `GM`, the slope of the righting-arm curve at
+    /// zero heel; positive when the upright vehicle is stable
+    pub fn metacentric_height(&self) -> Length {
+        let arm = (self.righting_arm(Angle::new(SMALL_HEEL)).value() - self.righting_arm(Angle::new(-SMALL_HEEL)).value()) / 2.0;
+        Length::new(arm / SMALL_HEEL.sin())
+    }
+
+    /// Righting arms and moments at `steps + 1` heels from zero to `max_heel`
+    pub fn righting_curve(&self, max_heel: Angle, steps: usize) -> Vec<RightingPoint> {
+        let steps = steps.max(1);
+        (0..=steps)
+            .map(|k| {
+                let heel = Angle::new(max_heel.value() * k as f64 / steps as f64);
+                RightingPoint { heel, arm: self.righting_arm(heel), moment: self.righting_moment(heel) }
+            })
+            .collect()
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Box barge 4 m long, 2 m beam and 2 m deep with its keel at z = 0
+    fn barge() -> Hull {
+        let size = [Length::new(4.0), Length::new(2.0), Length::new(2.0)];
+        Hull::new(&[HullPrimitive::Box { pose: Motor::from_translation([0.0, 0.0, 1.0]), size }])
+    }
+
+    #[test]
+    fn test_clipping_is_exact() {
+        let hull = barge();
+        assert!((hull.volume().value() - 16.0).abs() < 1e-12);
+        let level = hull.flotation(Angle::new(0.0), Volume::new(4.0)).unwrap();
+        assert!((level.waterline.unwrap().value() - 0.5).abs() < 1e-9);
+        assert!((level.center_of_buoyancy[2].value() - 0.25).abs() < 1e-9);
+
+        let cylinder = Hull::new(&[HullPrimitive::Cylinder {
+            pose: Motor::identity(),
+            radius: Length::new(0.5),
+            length: Length::new(2.0),
+        }]);
+        assert!((cylinder.volume().value() - 0.25 * TAU / 2.0 * 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_wall_sided_barge() {
+        // 0.5 m draft, KB = 0.25 m, BM = B²/12T = 2/3 m, KG = 0.8 m
+        let mass = Mass::new(4.0 * marine::water_density::<f64>().value());
+        let center_of_gravity = [0.0, 0.0, 0.8].map(Length::new);
+        let stability = Stability::new(barge(), mass, center_of_gravity).unwrap();
+        assert!(stability.floats());
+        let gm = 0.25 + 2.0 / 3.0 - 0.8;
+        assert!((stability.metacentric_height().value() - gm).abs() < 1e-6);
+
+        // Wall-sided formula until the deck edge or bilge leaves its side
+        let heel = TAU / 36.0;
+        let expected = heel.sin() * (gm + 0.5 * (2.0 / 3.0) * heel.tan().powi(2));
+        assert!((stability.righting_arm(Angle::new(heel)).value() - expected).abs() < 1e-8);
+        assert!((stability.righting_arm(Angle::new(-heel)).value() + expected).abs() < 1e-8);
+
+        let curve = stability.righting_curve(Angle::new(TAU / 4.0), 9);
+        assert_eq!(curve.len(), 10);
+        assert_eq!(*curve[0].arm.value(), 0.0);
+        assert!(curve[1].moment.value() > &0.0);
+    }
+
+    #[test]
+    fn test_submerged_vehicle() {
+        // Center of gravity 0.1 m below the hull centroid: GZ = BG sin φ
+        let mass = Mass::new(20.0 * marine::water_density::<f64>().value());
+        let stability = Stability::new(barge(), mass, [0.0, 0.0, 0.9].map(Length::new)).unwrap();
+        assert!(!stability.floats());
+        assert!((stability.metacentric_height().value() - 0.1).abs() < 1e-9);
+        let heel = Angle::new(1.0);
+        assert!((stability.righting_arm(heel).value() - 0.1 * 1f64.sin()).abs() < 1e-12);
+        assert_eq!(Stability::new(Hull::new(&[]), mass, [Length::new(0.0); 3]), Err(HydrostaticsError::EmptyHull));
+    }
+}
@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Orientation estimation from inertial measurements
+//!
+//! The orientation is the rotor mapping body vectors into a world frame whose
+//! `z` axis points up. Gyro rates (rad/s, body frame) are integrated on the rotor
+//! manifold, which is accurate over short horizons but drifts. The accelerometer
+//! measures the specific force, `+g` along world up when the body is not
+//! accelerating, which fixes roll and pitch but says nothing about yaw. A
+//! [`ComplementaryFilter`] pulls the integrated orientation a fraction of the way
+//! towards the accelerometer tilt on every update.
+
+use crate::linalg::{self, Vector3};
+use crate::motor::Rotor;
+
+/// Standard gravity in m/s²
+pub const STANDARD_GRAVITY: f64 = 9.80665;
+
+/// Gyro integration corrected by accelerometer tilt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplementaryFilter {
+    orientation: Rotor,
+    /// Fraction of the way to the accelerometer tilt moved per update, in [0, 1]
+    pub gain: f64,
+    /// Accelerometer readings whose magnitude differs from gravity by more than
+    /// this (m/s²) are treated as linear acceleration and ignored
+    pub acceleration_tolerance: f64,
+}
+
+impl ComplementaryFilter {
+    pub fn new(gain: f64) -> Self {
+        Self {
+            orientation: Rotor::identity(),
+            gain: gain.clamp(0.0, 1.0),
+            acceleration_tolerance: 0.1 * STANDARD_GRAVITY,
+        }
+    }
+
+    /// Start from a known orientation instead of the identity
+    pub fn with_orientation(mut self, orientation: Rotor) -> Self {
+        self.orientation = orientation.normalized();
+        self
+    }
+
+    pub fn orientation(&self) -> Rotor {
+        self.orientation
+    }
+
+    /// Orientation after rotating at body rate `rate` for `dt` seconds
+    pub fn integrate_gyro(&self, rate: Vector3, dt: f64) -> Rotor {
+        (self.orientation * Rotor::from_rotation_vector(linalg::scale(rate, dt))).normalized()
+    }
+
+    /// Orientation whose tilt matches the accelerometer and whose yaw matches `predicted`
+    ///
+    /// Returns `None` while the body is accelerating or the reading is degenerate.
+    pub fn accelerometer_orientation(&self, predicted: Rotor, acceleration: Vector3) -> Option<Rotor> {
+        let magnitude = linalg::norm(acceleration);
+        if magnitude == 0.0 || (magnitude - STANDARD_GRAVITY).abs() > self.acceleration_tolerance {
+            return None;
+        }
+        // The correction turns the measured up direction onto world up about a
+        // horizontal axis, so it leaves yaw unchanged
+        let measured_up = predicted.apply(linalg::scale(acceleration, 1.0 / magnitude));
+        let up = [0.0, 0.0, 1.0];
+        let axis = linalg::cross(measured_up, up);
+        let angle = linalg::norm(axis).atan2(linalg::dot(measured_up, up));
+        Some((Rotor::from_axis_angle(axis, angle) * predicted).normalized())
+    }
+
+    /// Integrate one gyro sample and blend in the accelerometer tilt
+    pub fn update(&mut self, rate: Vector3, acceleration: Vector3, dt: f64) -> Rotor {
+        let predicted = self.integrate_gyro(rate, dt);
+        self.orientation = match self.accelerometer_orientation(predicted, acceleration) {
+            Some(measured) => predicted.slerp(&measured, self.gain).normalized(),
+            None => predicted,
+        };
+        self.orientation
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::TAU;
+
+    #[test]
+    fn test_complementary_filter_removes_tilt_drift() {
+        // A level body read by a gyro with roll and pitch bias
+        let bias = [0.002, -0.001, 0.0];
+        let level = [0.0, 0.0, STANDARD_GRAVITY];
+        let mut filter = ComplementaryFilter::new(0.05);
+        let mut gyro_only = ComplementaryFilter::new(0.0);
+        for _ in 0..6000 {
+            filter.update(bias, level, 0.01);
+            gyro_only.update(bias, level, 0.01);
+        }
+
+        let tilt = |rotor: Rotor| rotor.apply([0.0, 0.0, 1.0])[2].acos();
+        assert!(tilt(gyro_only.orientation()) > 0.1);
+        // Steady-state tilt error is the bias step divided by the gain
+        assert!(tilt(filter.orientation()) < 1e-3);
+
+        // Yaw is left to the gyro: a quarter turn in one second
+        let mut turning = ComplementaryFilter::new(0.5);
+        for _ in 0..100 {
+            turning.update([0.0, 0.0, TAU / 4.0], level, 0.01);
+        }
+        let heading = turning.orientation().apply([1.0, 0.0, 0.0]);
+        assert!((heading[1].atan2(heading[0]) - TAU / 4.0).abs() < 1e-9);
+
+        // Readings far from 1 g are ignored
+        let accelerating = [6.0, 0.0, STANDARD_GRAVITY];
+        assert_eq!(filter.accelerometer_orientation(filter.orientation(), accelerating), None);
+    }
+}
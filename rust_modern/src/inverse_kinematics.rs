@@ -0,0 +1,356 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Closed-form inverse kinematics with a numerical fallback
+//!
+//! An [`IkSolver`] returns every solution branch of one arm geometry in closed
+//! form. Solvers are registered in an [`IkSolverRegistry`] under the robot model
+//! name, and [`IkSolverRegistry::solve`] picks the one matching
+//! [`KinematicChain::name`]. Every branch is checked against the forward
+//! kinematics of the chain, so a solver registered for the wrong geometry yields
+//! nothing rather than wrong coordinates, and branches outside the joint limits
+//! are dropped, as are branches in self-collision when the registry has a
+//! [`SelfCollisionModel`]. The survivors are sorted by distance to the current
+//! coordinates.
+//!
+//! Without a solver for the model, or when no branch survives, the registry
+//! falls back to the damped least-squares [`KinematicChain::inverse`].
+//!
+//! Two solvers are provided:
+//!
+//! - [`Planar2R`]: two revolute joints about z, elbow up and elbow down
+//! - [`SphericalWrist6R`]: a 6R arm whose last three axes meet in a point, solved
+//!   for the wrist centre first and the wrist rotation second, up to eight branches
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::euler::wrap;
+use crate::joints::{Joint, JointLimits};
+use crate::kinematics::{pose_error, IkOptions, IkSolution, KinematicChain, KinematicsError};
+use crate::linalg::{self, Vector3};
+use crate::motor::{Motor, Rotor};
+use crate::self_collision::SelfCollisionModel;
+
+/// Branches closer than this in every coordinate are the same solution
+const DUPLICATE_TOLERANCE: f64 = 1e-9;
+
+const Y: Vector3 = [0.0, 1.0, 0.0];
+const Z: Vector3 = [0.0, 0.0, 1.0];
+
+/// Closed-form inverse kinematics of one arm geometry
+pub trait IkSolver {
+    fn name(&self) -> &str;
+
+    /// Whether the solutions match the target orientation, or only its position
+    fn constrains_orientation(&self) -> bool {
+        true
+    }
+
+    /// All solution branches placing the flange at `target`, unchecked against limits; empty when unreachable
+    fn solve(&self, chain: &KinematicChain, target: &Motor) -> Vec<Vec<f64>>;
+}
+
+/// Angles `θ` and `φ` with `a (cos θ, sin θ) + b (cos(θ + φ), sin(θ + φ)) = (x, y)`,
+/// for both signs of `φ`
+fn two_link(a: f64, b: f64, x: f64, y: f64) -> Vec<(f64, f64)> {
+    let cos = (x * x + y * y - a * a - b * b) / (2.0 * a * b);
+    if !(-1.0..=1.0).contains(&cos) {
+        return Vec::new();
+    }
+    let bend = cos.acos();
+    [bend, -bend]
+        .into_iter()
+        .map(|phi| (y.atan2(x) - (b * phi.sin()).atan2(a + b * phi.cos()), phi))
+        .collect()
+}
+
+/// Planar arm of two revolute joints about z with links along x
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Planar2R {
+    pub l1: f64,
+    pub l2: f64,
+}
+
+impl Planar2R {
+    pub fn new(l1: f64, l2: f64) -> Self {
+        Self { l1, l2 }
+    }
+
+    /// The chain this solver describes, both joints with `limits`
+    pub fn chain(&self, limits: JointLimits) -> KinematicChain {
+        KinematicChain::new()
+            .with_name("planar_2r")
+            .with_joint(Motor::identity(), Joint::revolute(Z, limits))
+            .with_joint(Motor::from_translation([self.l1, 0.0, 0.0]), Joint::revolute(Z, limits))
+            .with_joint(Motor::from_translation([self.l2, 0.0, 0.0]), Joint::Fixed)
+    }
+}
+
+impl IkSolver for Planar2R {
+    fn name(&self) -> &str {
+        "planar_2r"
+    }
+
+    fn constrains_orientation(&self) -> bool {
+        false
+    }
+
+    fn solve(&self, _chain: &KinematicChain, target: &Motor) -> Vec<Vec<f64>> {
+        let [x, y, _] = target.translation;
+        two_link(self.l1, self.l2, x, y).into_iter().map(|(shoulder, elbow)| vec![wrap(shoulder), wrap(elbow)]).collect()
+    }
+}
+
+/// 6R arm with a spherical wrist
+///
+/// Joint 1 turns about z at the base, joints 2 and 3 about y at heights `d1` and
+/// `a2` above the previous joint, and the wrist is z-y-z at `d4` above joint 3,
+/// with the tool `d6` beyond the wrist centre. All links are along z in the zero
+/// configuration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SphericalWrist6R {
+    pub d1: f64,
+    pub a2: f64,
+    pub d4: f64,
+    pub d6: f64,
+}
+
+impl SphericalWrist6R {
+    pub fn new(d1: f64, a2: f64, d4: f64, d6: f64) -> Self {
+        Self { d1, a2, d4, d6 }
+    }
+
+    /// The chain this solver describes, every joint with `limits`
+    pub fn chain(&self, limits: JointLimits) -> KinematicChain {
+        let up = |d: f64| Motor::from_translation([0.0, 0.0, d]);
+        KinematicChain::new()
+            .with_name("spherical_wrist_6r")
+            .with_joint(Motor::identity(), Joint::revolute(Z, limits))
+            .with_joint(up(self.d1), Joint::revolute(Y, limits))
+            .with_joint(up(self.a2), Joint::revolute(Y, limits))
+            .with_joint(up(self.d4), Joint::revolute(Z, limits))
+            .with_joint(Motor::identity(), Joint::revolute(Y, limits))
+            .with_joint(Motor::identity(), Joint::revolute(Z, limits))
+            .with_joint(up(self.d6), Joint::Fixed)
+    }
+
+    /// Z-Y-Z angles of the wrist rotation, both branches away from the singularity
+    fn wrist(rotor: &Rotor) -> Vec<[f64; 3]> {
+        let m = rotor.to_rotation_matrix();
+        let sin5 = m[0][2].hypot(m[1][2]);
+        if sin5 < 1e-12 {
+            // Axes 4 and 6 align and only the sum (or difference) of their angles is defined
+            return if m[2][2] > 0.0 {
+                vec![[0.0, 0.0, m[1][0].atan2(m[0][0])]]
+            } else {
+                vec![[0.0, std::f64::consts::PI, m[1][0].atan2(-m[0][0])]]
+            };
+        }
+        let (q4, q5, q6) = (m[1][2].atan2(m[0][2]), sin5.atan2(m[2][2]), m[2][1].atan2(-m[2][0]));
+        let flip = std::f64::consts::PI;
+        vec![[q4, q5, q6], [q4 + flip, -q5, q6 + flip]]
+    }
+}
+
+impl IkSolver for SphericalWrist6R {
+    fn name(&self) -> &str {
+        "spherical_wrist_6r"
+    }
+
+    fn solve(&self, _chain: &KinematicChain, target: &Motor) -> Vec<Vec<f64>> {
+        let centre = linalg::sub(target.translation, target.rotor.apply([0.0, 0.0, self.d6]));
+        let base = centre[1].atan2(centre[0]);
+        let mut branches = Vec::new();
+        for q1 in [base, base + std::f64::consts::PI] {
+            // Reach in the plane of joints 2 and 3, negative when the arm leans back over the base
+            let reach = centre[0] * q1.cos() + centre[1] * q1.sin();
+            let height = centre[2] - self.d1;
+            for (q2, q3) in two_link(self.a2, self.d4, height, reach) {
+                let arm = Rotor::from_axis_angle(Z, q1) * Rotor::from_axis_angle(Y, q2 + q3);
+                for [q4, q5, q6] in Self::wrist(&(arm.reverse() * target.rotor)) {
+                    branches.push([q1, q2, q3, q4, q5, q6].into_iter().map(wrap).collect());
+                }
+            }
+        }
+        branches
+    }
+}
+
+/// Analytic solvers by robot model, with the numerical solver as fallback
+#[derive(Default)]
+pub struct IkSolverRegistry {
+    solvers: BTreeMap<String, Box<dyn IkSolver>>,
+    /// Settings of the numerical fallback; its tolerance also accepts analytic branches
+    pub options: IkOptions,
+    /// Links of the chains solved, when colliding solutions are to be rejected
+    pub self_collision: Option<SelfCollisionModel>,
+}
+
+impl fmt::Debug for IkSolverRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.solvers.iter().map(|(model, solver)| (model, solver.name()))).finish()
+    }
+}
+
+impl IkSolverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: IkOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Reject solutions in which the links of `model` collide
+    pub fn with_self_collision(mut self, model: SelfCollisionModel) -> Self {
+        self.self_collision = Some(model);
+        self
+    }
+
+    /// Use `solver` for chains named `model`, replacing any previous one
+    pub fn with_solver(mut self, model: &str, solver: impl IkSolver + 'static) -> Self {
+        self.solvers.insert(model.to_string(), Box::new(solver));
+        self
+    }
+
+    pub fn solver(&self, model: &str) -> Option<&dyn IkSolver> {
+        self.solvers.get(model).map(|solver| solver.as_ref())
+    }
+
+    /// Verified branches within the limits that place the end effector at `target`
+    ///
+    /// Sorted by distance to `initial`, which also seeds the numerical fallback.
+    /// The fallback yields a single solution and reports its error when it does
+    /// not converge, or [`KinematicsError::SelfCollision`] when it ends in collision.
+    pub fn solve(&self, chain: &KinematicChain, target: &Motor, initial: &[f64]) -> Result<Vec<IkSolution>, KinematicsError> {
+        if initial.len() != chain.dof() {
+            return Err(KinematicsError::CoordinateCount { expected: chain.dof(), actual: initial.len() });
+        }
+        let mut solutions: Vec<IkSolution> = Vec::new();
+        if let Some(solver) = self.solver(chain.name()) {
+            // Solvers place the flange; a mounted tool moves the target back by its TCP
+            let flange = *target * chain.tcp().inverse();
+            for q in solver.solve(chain, &flange) {
+                if q.len() != chain.dof() || !chain.within_limits(&q)? {
+                    continue;
+                }
+                let error = pose_error(&chain.forward(&q)?, target);
+                let error = if solver.constrains_orientation() { &error[..] } else { &error[3..] };
+                let residual = error.iter().map(|e| e * e).sum::<f64>().sqrt();
+                let duplicate = solutions
+                    .iter()
+                    .any(|s| s.q.iter().zip(&q).all(|(a, b)| (a - b).abs() < DUPLICATE_TOLERANCE));
+                if residual < self.options.tolerance && !duplicate && self.collision_free(chain, &q)? {
+                    solutions.push(IkSolution { q, iterations: 0, residual });
+                }
+            }
+        }
+        if solutions.is_empty() {
+            let solution = chain.inverse(target, initial, &self.options)?;
+            if let Some(model) = &self.self_collision {
+                model.validate(chain, &solution.q)?;
+            }
+            return Ok(vec![solution]);
+        }
+
+        let distance = |q: &[f64]| q.iter().zip(initial).map(|(a, b)| (a - b) * (a - b)).sum::<f64>();
+        solutions.sort_by(|a, b| distance(&a.q).total_cmp(&distance(&b.q)));
+        Ok(solutions)
+    }
+
+    fn collision_free(&self, chain: &KinematicChain, q: &[f64]) -> Result<bool, KinematicsError> {
+        match &self.self_collision {
+            Some(model) => Ok(model.contacts(chain, q)?.is_empty()),
+            None => Ok(true),
+        }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::Sampler;
+    use crate::si_units::{Angle, AngularVelocity, TAU};
+
+    fn limits(range: f64) -> JointLimits {
+        JointLimits::new(Angle::new(-range), Angle::new(range), AngularVelocity::new(2.0))
+    }
+
+    #[test]
+    fn test_planar_2r_branches_and_limits() {
+        let solver = Planar2R::new(0.5, 0.3);
+        let chain = solver.chain(limits(TAU / 2.0));
+        let target = chain.forward(&[0.4, 0.9]).unwrap();
+
+        let registry = IkSolverRegistry::new().with_solver("planar_2r", solver);
+        let solutions = registry.solve(&chain, &target, &[0.3, 1.0]).unwrap();
+        assert_eq!(solutions.len(), 2);
+        assert!(solutions.iter().all(|s| s.iterations == 0 && s.residual < 1e-9));
+        // Nearest branch first: elbow bent the same way as the initial guess
+        assert!((solutions[0].q[0] - 0.4).abs() < 1e-9 && (solutions[0].q[1] - 0.9).abs() < 1e-9);
+        assert!(solutions[1].q[1] < 0.0);
+
+        // An elbow that only bends one way leaves one branch
+        let chain = KinematicChain::new()
+            .with_name("planar_2r")
+            .with_joint(Motor::identity(), Joint::revolute(Z, limits(TAU / 2.0)))
+            .with_joint(
+                Motor::from_translation([0.5, 0.0, 0.0]),
+                Joint::revolute(Z, JointLimits::new(Angle::new(0.0), Angle::new(TAU / 2.0), AngularVelocity::new(2.0))),
+            )
+            .with_joint(Motor::from_translation([0.3, 0.0, 0.0]), Joint::Fixed);
+        let solutions = registry.solve(&chain, &target, &[0.0, 0.0]).unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert!(solutions[0].q[1] > 0.0);
+    }
+
+    #[test]
+    fn test_spherical_wrist_branches_reach_sampled_poses() {
+        let solver = SphericalWrist6R::new(0.4, 0.5, 0.45, 0.1);
+        let chain = solver.chain(limits(TAU / 2.0));
+        let registry = IkSolverRegistry::new().with_solver("spherical_wrist_6r", solver);
+        let joints: Vec<Joint> = chain.joints().copied().collect();
+        let mut sampler = Sampler::new(5);
+        for _ in 0..10 {
+            let goal = sampler.joint_coordinates(&joints);
+            let target = chain.forward(&goal).unwrap();
+
+            let branches = solver.solve(&chain, &target);
+            assert!(branches.len() <= 8);
+            let solutions = registry.solve(&chain, &target, &goal).unwrap();
+            assert!(solutions.len() >= 2, "only {} branches for {:?}", solutions.len(), goal);
+            for solution in &solutions {
+                let reached = chain.forward(&solution.q).unwrap();
+                let error = pose_error(&reached, &target);
+                assert!(error.iter().all(|e| e.abs() < 1e-9), "{:?}", error);
+            }
+            let nearest = &solutions[0].q;
+            assert!(nearest.iter().zip(&goal).all(|(a, b)| (a - b).abs() < 1e-6), "{:?} vs {:?}", nearest, goal);
+        }
+    }
+
+    #[test]
+    fn test_unregistered_model_falls_back_to_numerical_solver() {
+        let chain = SphericalWrist6R::new(0.4, 0.5, 0.45, 0.1).chain(limits(TAU / 2.0)).with_name("custom");
+        let goal = [0.2, 0.3, 0.4, -0.2, 0.5, 0.1];
+        let target = chain.forward(&goal).unwrap();
+        let initial: Vec<f64> = goal.iter().map(|q| q + 0.05).collect();
+
+        let registry = IkSolverRegistry::new().with_solver("spherical_wrist_6r", SphericalWrist6R::new(0.4, 0.5, 0.45, 0.1));
+        let solutions = registry.solve(&chain, &target, &initial).unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert!(solutions[0].iterations > 0 && solutions[0].residual < 1e-9);
+
+        // A solver for a different geometry yields no verified branch and also falls back
+        let registry = IkSolverRegistry::new().with_solver("custom", SphericalWrist6R::new(0.4, 0.6, 0.45, 0.1));
+        assert!(registry.solve(&chain, &target, &initial).unwrap()[0].iterations > 0);
+        assert_eq!(
+            registry.solve(&chain, &target, &[0.0; 5]),
+            Err(KinematicsError::CoordinateCount { expected: 6, actual: 5 })
+        );
+    }
+}
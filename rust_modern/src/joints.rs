@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Joints of articulated chains and their limits
+//!
+//! Limits are typed: positions are [`Angle`]s or [`Length`]s and speeds
+//! [`AngularVelocity`] or [`Velocity`], so a limit given in degrees or rpm is
+//! converted once at construction.
+//!
+//! A [`Joint`] moves its child frame by a motor of its coordinates: one angle
+//! for a revolute joint, one length for a prismatic joint, a rotation vector for
+//! a spherical joint and none for a fixed joint. Coordinates are plain `f64`
+//! (radians, meters) so that a whole chain can be stored in one slice.
+
+use serde::{Deserialize, Serialize};
+use crate::linalg::{self, Vector3};
+use crate::motor::{Motor, Rotor};
+use crate::si_units::{Angle, AngularVelocity, Length, Velocity};
+
+/// Position range and speed limit of a revolute joint
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointLimits {
+    pub lower: Angle,
+    pub upper: Angle,
+    /// Largest allowed speed in either direction
+    pub max_velocity: AngularVelocity,
+}
+
+impl JointLimits {
+    /// Limits between two angles given in any order
+    pub fn new(a: Angle, b: Angle, max_velocity: AngularVelocity) -> Self {
+        let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+        Self { lower, upper, max_velocity }
+    }
+
+    /// Width of the position range
+    pub fn span(&self) -> Angle {
+        Angle::new(self.upper.value() - self.lower.value())
+    }
+
+    pub fn contains(&self, angle: Angle) -> bool {
+        self.lower <= angle && angle <= self.upper
+    }
+
+    /// The nearest angle within the position range
+    pub fn clamp(&self, angle: Angle) -> Angle {
+        Angle::new(angle.value().clamp(*self.lower.value(), *self.upper.value()))
+    }
+
+    pub fn is_velocity_safe(&self, velocity: AngularVelocity) -> bool {
+        velocity.value().abs() <= *self.max_velocity.value()
+    }
+}
+
+/// Position range and speed limit of a prismatic joint
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PrismaticLimits {
+    pub lower: Length,
+    pub upper: Length,
+    /// Largest allowed speed in either direction
+    pub max_velocity: Velocity,
+}
+
+impl PrismaticLimits {
+    /// Limits between two lengths given in any order
+    pub fn new(a: Length, b: Length, max_velocity: Velocity) -> Self {
+        let (lower, upper) = if a <= b { (a, b) } else { (b, a) };
+        Self { lower, upper, max_velocity }
+    }
+
+    pub fn contains(&self, position: Length) -> bool {
+        self.lower <= position && position <= self.upper
+    }
+
+    pub fn clamp(&self, position: Length) -> Length {
+        Length::new(position.value().clamp(*self.lower.value(), *self.upper.value()))
+    }
+}
+
+/// Rotation limit of a spherical joint: a largest angle away from its neutral orientation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SphericalLimits {
+    pub max_angle: Angle,
+    /// Largest allowed angular speed about any axis
+    pub max_velocity: AngularVelocity,
+}
+
+impl SphericalLimits {
+    pub fn new(max_angle: Angle, max_velocity: AngularVelocity) -> Self {
+        Self { max_angle, max_velocity }
+    }
+
+    pub fn contains(&self, rotor: &Rotor) -> bool {
+        rotor.angle() <= *self.max_angle.value()
+    }
+
+    /// The same rotation axis with the angle capped at `max_angle`
+    pub fn clamp(&self, rotor: &Rotor) -> Rotor {
+        let angle = rotor.angle();
+        let max_angle = *self.max_angle.value();
+        if angle <= max_angle {
+            return *rotor;
+        }
+        Rotor::from_rotation_vector(linalg::scale(rotor.rotation_vector(), max_angle / angle))
+    }
+}
+
+/// A joint and the motion of its child frame
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Joint {
+    /// Rotation about a unit axis through the joint origin
+    Revolute { axis: Vector3, limits: JointLimits },
+    /// Translation along a unit axis
+    Prismatic { axis: Vector3, limits: PrismaticLimits },
+    /// Rotation about the joint origin, with the rotation vector as coordinates
+    Spherical { limits: SphericalLimits },
+    Fixed,
+}
+
+impl Joint {
+    /// Revolute joint about `axis`, which is normalized
+    pub fn revolute(axis: Vector3, limits: JointLimits) -> Self {
+        Joint::Revolute { axis: linalg::scale(axis, 1.0 / linalg::norm(axis)), limits }
+    }
+
+    /// Prismatic joint along `axis`, which is normalized
+    pub fn prismatic(axis: Vector3, limits: PrismaticLimits) -> Self {
+        Joint::Prismatic { axis: linalg::scale(axis, 1.0 / linalg::norm(axis)), limits }
+    }
+
+    pub fn spherical(limits: SphericalLimits) -> Self {
+        Joint::Spherical { limits }
+    }
+
+    /// Number of coordinates
+    pub fn dof(&self) -> usize {
+        match self {
+            Joint::Revolute { .. } | Joint::Prismatic { .. } => 1,
+            Joint::Spherical { .. } => 3,
+            Joint::Fixed => 0,
+        }
+    }
+
+    /// Motion of the child frame relative to the joint origin; `q` holds [`dof`](Self::dof) values
+    pub fn motion(&self, q: &[f64]) -> Motor {
+        match self {
+            Joint::Revolute { axis, .. } => Motor::from_rotor(Rotor::from_axis_angle(*axis, q[0])),
+            Joint::Prismatic { axis, .. } => Motor::from_translation(linalg::scale(*axis, q[0])),
+            Joint::Spherical { .. } => Motor::from_rotor(Rotor::from_rotation_vector([q[0], q[1], q[2]])),
+            Joint::Fixed => Motor::identity(),
+        }
+    }
+
+    pub fn contains(&self, q: &[f64]) -> bool {
+        match self {
+            Joint::Revolute { limits, .. } => limits.contains(Angle::new(q[0])),
+            Joint::Prismatic { limits, .. } => limits.contains(Length::new(q[0])),
+            Joint::Spherical { limits } => limits.contains(&Rotor::from_rotation_vector([q[0], q[1], q[2]])),
+            Joint::Fixed => true,
+        }
+    }
+
+    /// Move `q` to the nearest coordinates within the position limits
+    pub fn clamp(&self, q: &mut [f64]) {
+        match self {
+            Joint::Revolute { limits, .. } => q[0] = *limits.clamp(Angle::new(q[0])).value(),
+            Joint::Prismatic { limits, .. } => q[0] = *limits.clamp(Length::new(q[0])).value(),
+            Joint::Spherical { limits } => {
+                let rotor = limits.clamp(&Rotor::from_rotation_vector([q[0], q[1], q[2]]));
+                q.copy_from_slice(&rotor.rotation_vector());
+            }
+            Joint::Fixed => {}
+        }
+    }
+
+    /// Advance `q` by a velocity step `dq`
+    ///
+    /// For a spherical joint `dq` is an angular step about the axes of the joint
+    /// origin frame, applied on the left of the current rotation, so it matches
+    /// the joint's Jacobian columns rather than the rates of the rotation vector.
+    pub fn integrate(&self, q: &mut [f64], dq: &[f64]) {
+        match self {
+            Joint::Revolute { .. } | Joint::Prismatic { .. } => q[0] += dq[0],
+            Joint::Spherical { .. } => {
+                let rotor = Rotor::from_rotation_vector([dq[0], dq[1], dq[2]]) * Rotor::from_rotation_vector([q[0], q[1], q[2]]);
+                q.copy_from_slice(&rotor.rotation_vector());
+            }
+            Joint::Fixed => {}
+        }
+    }
+}
@@ -0,0 +1,579 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Serial kinematic chains with heterogeneous joints
+//!
+//! A [`KinematicChain`] is a sequence of [`Segment`]s. Each one is a fixed motor
+//! from the previous joint frame to the joint origin, followed by the motion of a
+//! [`Joint`]. The coordinates of the whole chain are one slice with
+//! [`Joint::dof`] entries per joint, in chain order, so revolute, prismatic,
+//! spherical and fixed joints mix freely.
+//!
+//! Jacobian columns are ordered (angular, linear) like [`MotorGenerator`], in the
+//! base frame, with the linear part the velocity of the end-effector origin. A
+//! spherical joint contributes three columns, the rotations about the axes of
+//! its origin frame; [`KinematicChain::integrate`] applies steps in that basis.
+//!
+//! [`KinematicChain::inverse`] solves for a target pose by damped least squares,
+//! `δq = Jᵀ (J Jᵀ + λ² I)⁻¹ e`, clamping to the joint limits after every step.
+//! For redundant chains [`Redundancy`] adds secondary objectives in the null space.
+//!
+//! A [`Tool`] mounted on the flange, the frame after the last segment, moves the
+//! end effector to its tool centre point and adds its mass to the chain.
+//!
+//! [`MotorGenerator`]: crate::motor::MotorGenerator
+//! [`Redundancy`]: crate::redundancy::Redundancy
+//! [`Tool`]: crate::tool::Tool
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dynamics::Inertia;
+use crate::joints::Joint;
+use crate::linalg::{self, dense, square, Matrix6, Vector3};
+use crate::motor::Motor;
+use crate::si_units::Acceleration;
+use crate::tool::Tool;
+
+/// A fixed offset to a joint origin and the joint
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    /// Motion from the previous joint frame (or the base) to the joint origin
+    pub origin: Motor,
+    pub joint: Joint,
+}
+
+/// Why a kinematics query failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum KinematicsError {
+    /// The coordinate slice does not match the degrees of freedom of the chain
+    CoordinateCount { expected: usize, actual: usize },
+    /// Inverse kinematics stopped with the pose error still above the tolerance
+    NotConverged { iterations: usize, residual: f64 },
+    /// A link model does not have one link per segment of the chain
+    LinkCount { expected: usize, actual: usize },
+    /// Two links of the chain are closer than the self-collision margin
+    SelfCollision { first: usize, second: usize },
+}
+
+impl fmt::Display for KinematicsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KinematicsError::CoordinateCount { expected, actual } => {
+                write!(f, "chain has {} degrees of freedom but {} coordinates were given", expected, actual)
+            }
+            KinematicsError::NotConverged { iterations, residual } => {
+                write!(f, "inverse kinematics did not converge after {} iterations (residual {:e})", iterations, residual)
+            }
+            KinematicsError::LinkCount { expected, actual } => {
+                write!(f, "link model has {} links but the chain has {} segments", expected, actual)
+            }
+            KinematicsError::SelfCollision { first, second } => write!(f, "links {} and {} collide", first, second),
+        }
+    }
+}
+
+impl std::error::Error for KinematicsError {}
+
+/// Relative size of the smallest squared singular value [`Jacobian::project_null_space`] inverts
+pub const NULL_SPACE_CUTOFF: f64 = 1e-10;
+
+/// Geometric Jacobian: one (angular, linear) column per coordinate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jacobian {
+    pub columns: Vec<[f64; 6]>,
+}
+
+impl Jacobian {
+    /// End-effector twist for coordinate velocities `dq`
+    pub fn twist(&self, dq: &[f64]) -> [f64; 6] {
+        let mut twist = [0.0; 6];
+        for (column, rate) in self.columns.iter().zip(dq) {
+            for (t, c) in twist.iter_mut().zip(column) {
+                *t += c * rate;
+            }
+        }
+        twist
+    }
+
+    /// `J Jᵀ`, the 6×6 manipulability matrix
+    pub fn gram(&self) -> Matrix6 {
+        let mut gram = [[0.0; 6]; 6];
+        for column in &self.columns {
+            for (i, row) in gram.iter_mut().enumerate() {
+                for (j, value) in row.iter_mut().enumerate() {
+                    *value += column[i] * column[j];
+                }
+            }
+        }
+        gram
+    }
+
+    /// Coordinate step `Jᵀ (J Jᵀ + λ² I)⁻¹ e` toward the twist `e`
+    pub fn damped_least_squares(&self, error: [f64; 6], damping: f64) -> Vec<f64> {
+        let mut damped = self.gram();
+        for (i, row) in damped.iter_mut().enumerate() {
+            row[i] += damping * damping;
+        }
+        // Positive definite for λ > 0; a zero λ on a singular chain yields no step
+        let y = square::inverse(&damped).map_or([0.0; 6], |inverse| square::mul_vec(&inverse, &error));
+        self.columns.iter().map(|column| column.iter().zip(&y).map(|(c, y)| c * y).sum()).collect()
+    }
+
+    /// `(I - J⁺ J) z`: the part of the coordinate velocity `z` that leaves the end effector still
+    ///
+    /// `J⁺` is the undamped pseudo-inverse, with directions whose squared singular value
+    /// is below [`NULL_SPACE_CUTOFF`] of the largest treated as unreachable, so the result
+    /// is an exact orthogonal projection even next to a singularity.
+    pub fn project_null_space(&self, z: &[f64]) -> Vec<f64> {
+        let (eigenvalues, vectors) = square::symmetric_eigen(&self.gram());
+        let cutoff = eigenvalues[5] * NULL_SPACE_CUTOFF;
+        let motion = self.twist(z);
+        let mut y = [0.0; 6];
+        for (k, &eigenvalue) in eigenvalues.iter().enumerate() {
+            if eigenvalue <= cutoff || eigenvalue <= 0.0 {
+                continue;
+            }
+            let along = (0..6).map(|i| vectors[i][k] * motion[i]).sum::<f64>() / eigenvalue;
+            y.iter_mut().enumerate().for_each(|(i, y)| *y += vectors[i][k] * along);
+        }
+        self.columns.iter().zip(z).map(|(column, z)| z - column.iter().zip(&y).map(|(c, y)| c * y).sum::<f64>()).collect()
+    }
+
+    /// Yoshikawa manipulability `√det(J Jᵀ)`, zero when the chain cannot move in every direction
+    pub fn manipulability(&self) -> f64 {
+        let (eigenvalues, _) = square::symmetric_eigen(&self.gram());
+        eigenvalues.iter().map(|e| e.max(0.0)).product::<f64>().sqrt()
+    }
+
+    /// Singular values in descending order, the semi-axes of the velocity
+    /// manipulability ellipsoid; `min(6, n)` of them
+    pub fn singular_values(&self) -> Vec<f64> {
+        let n = self.columns.len();
+        let matrix: Vec<f64> = (0..6).flat_map(|i| self.columns.iter().map(move |column| column[i])).collect();
+        dense::svd(&matrix, 6, n).1
+    }
+}
+
+/// Settings of [`KinematicChain::inverse`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IkOptions {
+    pub max_iterations: usize,
+    /// Converged once the pose error norm (rad and m) is below this
+    pub tolerance: f64,
+    /// Damping λ of the least-squares step, trading accuracy near singularities for stability
+    pub damping: f64,
+    /// Largest change of any coordinate in one step (rad or m)
+    pub max_step: f64,
+}
+
+impl Default for IkOptions {
+    fn default() -> Self {
+        Self { max_iterations: 200, tolerance: 1e-9, damping: 1e-3, max_step: 0.5 }
+    }
+}
+
+/// Result of a converged inverse kinematics solve
+#[derive(Debug, Clone, PartialEq)]
+pub struct IkSolution {
+    pub q: Vec<f64>,
+    pub iterations: usize,
+    pub residual: f64,
+}
+
+/// Twist carrying `current` to `target`: the rotation vector of `target R̃_current`
+/// and the difference of the origins
+pub fn pose_error(current: &Motor, target: &Motor) -> [f64; 6] {
+    let rotation = (target.rotor * current.rotor.reverse()).rotation_vector();
+    let translation = linalg::sub(target.translation, current.translation);
+    [rotation[0], rotation[1], rotation[2], translation[0], translation[1], translation[2]]
+}
+
+/// Null-space step at the given coordinates and Jacobian, `None` once no step is wanted
+pub(crate) type SecondaryStep<'a> = dyn Fn(&[f64], &Jacobian) -> Result<Option<Vec<f64>>, KinematicsError> + 'a;
+
+/// Serial chain of segments from a base frame to an end effector
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KinematicChain {
+    /// Robot model the chain describes, used to look up analytic solvers
+    #[serde(default)]
+    name: String,
+    segments: Vec<Segment>,
+    /// Tool mounted on the flange, if any
+    #[serde(default)]
+    tool: Option<Tool>,
+}
+
+impl KinematicChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Append a joint at `origin` relative to the previous joint frame
+    pub fn with_joint(mut self, origin: Motor, joint: Joint) -> Self {
+        self.segments.push(Segment { origin, joint });
+        self
+    }
+
+    pub fn with_tool(mut self, tool: Tool) -> Self {
+        self.tool = Some(tool);
+        self
+    }
+
+    /// Mount `tool` on the flange, or remove it with `None`, returning the tool mounted before
+    pub fn set_tool(&mut self, tool: Option<Tool>) -> Option<Tool> {
+        std::mem::replace(&mut self.tool, tool)
+    }
+
+    pub fn tool(&self) -> Option<&Tool> {
+        self.tool.as_ref()
+    }
+
+    /// The mounted tool, e.g. to attach or release payloads
+    pub fn tool_mut(&mut self) -> Option<&mut Tool> {
+        self.tool.as_mut()
+    }
+
+    /// Tool centre point in the flange frame; the identity without a tool
+    pub fn tcp(&self) -> Motor {
+        self.tool.as_ref().map_or_else(Motor::identity, |tool| tool.tcp)
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn joints(&self) -> impl Iterator<Item = &Joint> {
+        self.segments.iter().map(|s| &s.joint)
+    }
+
+    /// Total number of coordinates
+    pub fn dof(&self) -> usize {
+        self.joints().map(Joint::dof).sum()
+    }
+
+    /// All-zero coordinates: every joint in its neutral position
+    pub fn neutral(&self) -> Vec<f64> {
+        vec![0.0; self.dof()]
+    }
+
+    pub(crate) fn check(&self, q: &[f64]) -> Result<(), KinematicsError> {
+        let expected = self.dof();
+        if q.len() == expected {
+            Ok(())
+        } else {
+            Err(KinematicsError::CoordinateCount { expected, actual: q.len() })
+        }
+    }
+
+    /// Each segment with its slice of `q`
+    fn split<'a, T>(&'a self, q: &'a mut [T]) -> impl Iterator<Item = (&'a Segment, &'a mut [T])> {
+        let mut rest = q;
+        self.segments.iter().map(move |segment| {
+            let (head, tail) = std::mem::take(&mut rest).split_at_mut(segment.joint.dof());
+            rest = tail;
+            (segment, head)
+        })
+    }
+
+    /// Joint origin frames and the frame after each joint, in the base frame
+    pub(crate) fn origin_and_joint_frames(&self, q: &[f64]) -> Result<Vec<(Motor, Motor)>, KinematicsError> {
+        self.check(q)?;
+        let mut frame = Motor::identity();
+        let mut offset = 0;
+        Ok(self
+            .segments
+            .iter()
+            .map(|segment| {
+                let origin = frame * segment.origin;
+                let dof = segment.joint.dof();
+                frame = origin * segment.joint.motion(&q[offset..offset + dof]);
+                offset += dof;
+                (origin, frame)
+            })
+            .collect())
+    }
+
+    /// Frame after each joint, in the base frame
+    pub fn frames(&self, q: &[f64]) -> Result<Vec<Motor>, KinematicsError> {
+        Ok(self.origin_and_joint_frames(q)?.into_iter().map(|(_, frame)| frame).collect())
+    }
+
+    /// Flange pose: the frame after the last segment
+    pub fn flange(&self, q: &[f64]) -> Result<Motor, KinematicsError> {
+        Ok(self.frames(q)?.last().copied().unwrap_or_default())
+    }
+
+    /// End-effector pose: the tool centre point, or the flange without a tool
+    pub fn forward(&self, q: &[f64]) -> Result<Motor, KinematicsError> {
+        Ok(self.flange(q)? * self.tcp())
+    }
+
+    /// Geometric Jacobian at `q`
+    pub fn jacobian(&self, q: &[f64]) -> Result<Jacobian, KinematicsError> {
+        let frames = self.origin_and_joint_frames(q)?;
+        let flange = frames.last().map_or_else(Motor::identity, |(_, frame)| *frame);
+        let end = (flange * self.tcp()).translation;
+        let rotation = |axis: Vector3, origin: &Motor| {
+            let w = origin.apply_direction(axis);
+            let v = linalg::cross(w, linalg::sub(end, origin.translation));
+            [w[0], w[1], w[2], v[0], v[1], v[2]]
+        };
+
+        let mut columns = Vec::with_capacity(q.len());
+        for (segment, (origin, _)) in self.segments.iter().zip(&frames) {
+            match segment.joint {
+                Joint::Revolute { axis, .. } => columns.push(rotation(axis, origin)),
+                Joint::Prismatic { axis, .. } => {
+                    let d = origin.apply_direction(axis);
+                    columns.push([0.0, 0.0, 0.0, d[0], d[1], d[2]]);
+                }
+                Joint::Spherical { .. } => {
+                    columns.extend([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].map(|axis| rotation(axis, origin)))
+                }
+                Joint::Fixed => {}
+            }
+        }
+        Ok(Jacobian { columns })
+    }
+
+    /// Mass properties of the tool and its payloads in the base frame; `None` without mass at the tip
+    pub fn tool_inertia(&self, q: &[f64]) -> Result<Option<Inertia>, KinematicsError> {
+        let flange = self.flange(q)?;
+        Ok(self.tool.as_ref().and_then(|tool| tool.body().inertia()).map(|inertia| inertia.transform(&flange)))
+    }
+
+    /// Coordinate forces (N·m or N) that hold the tool and its payloads still against `gravity`
+    ///
+    /// `gravity` is in the base frame. The chain's own links are not included.
+    pub fn tool_holding_torques(&self, q: &[f64], gravity: [Acceleration; 3]) -> Result<Vec<f64>, KinematicsError> {
+        let Some(inertia) = self.tool_inertia(q)? else {
+            return Ok(self.neutral());
+        };
+        let weight = linalg::scale(gravity.map(|g| *g.value()), *inertia.mass().value());
+        let tcp = self.forward(q)?.translation;
+        let moment = linalg::cross(linalg::sub(inertia.center_of_mass(), tcp), weight);
+        let wrench = [moment[0], moment[1], moment[2], weight[0], weight[1], weight[2]];
+        Ok(self.jacobian(q)?.columns.iter().map(|column| -column.iter().zip(&wrench).map(|(c, w)| c * w).sum::<f64>()).collect())
+    }
+
+    /// Whether every joint is within its position limits
+    pub fn within_limits(&self, q: &[f64]) -> Result<bool, KinematicsError> {
+        self.check(q)?;
+        let mut offset = 0;
+        Ok(self.joints().all(|joint| {
+            let dof = joint.dof();
+            offset += dof;
+            joint.contains(&q[offset - dof..offset])
+        }))
+    }
+
+    /// Move `q` to the nearest coordinates within the joint limits
+    pub fn clamp(&self, q: &mut [f64]) -> Result<(), KinematicsError> {
+        self.check(q)?;
+        self.split(q).for_each(|(segment, q)| segment.joint.clamp(q));
+        Ok(())
+    }
+
+    /// Advance `q` by a step `dq` in the Jacobian's coordinate basis
+    pub fn integrate(&self, q: &mut [f64], dq: &[f64]) -> Result<(), KinematicsError> {
+        self.check(q)?;
+        self.check(dq)?;
+        let mut offset = 0;
+        for (segment, q) in self.split(q) {
+            segment.joint.integrate(q, &dq[offset..offset + q.len()]);
+            offset += q.len();
+        }
+        Ok(())
+    }
+
+    /// Coordinates within the limits placing the end effector at `target`, starting from `initial`
+    pub fn inverse(&self, target: &Motor, initial: &[f64], options: &IkOptions) -> Result<IkSolution, KinematicsError> {
+        self.inverse_with(target, initial, options, None)
+    }
+
+    /// [`inverse`](Self::inverse) with an optional secondary step projected into the null space
+    ///
+    /// `secondary` maps coordinates to a coordinate velocity and yields `None` once it
+    /// is satisfied. The solve keeps stepping after the pose converges until it is, and
+    /// a converged pose counts as a solution even when the iterations run out first.
+    pub(crate) fn inverse_with(
+        &self,
+        target: &Motor,
+        initial: &[f64],
+        options: &IkOptions,
+        secondary: Option<&SecondaryStep<'_>>,
+    ) -> Result<IkSolution, KinematicsError> {
+        let mut q = initial.to_vec();
+        self.clamp(&mut q)?;
+        let mut residual = f64::INFINITY;
+        for iteration in 0..=options.max_iterations {
+            let error = pose_error(&self.forward(&q)?, target);
+            residual = error.iter().map(|e| e * e).sum::<f64>().sqrt();
+            let jacobian = self.jacobian(&q)?;
+            let null = match secondary {
+                Some(secondary) => secondary(&q, &jacobian)?,
+                None => None,
+            };
+            if residual < options.tolerance && null.is_none() {
+                return Ok(IkSolution { q, iterations: iteration, residual });
+            }
+            if iteration == options.max_iterations {
+                break;
+            }
+
+            let mut dq = jacobian.damped_least_squares(error, options.damping);
+            if let Some(null) = null {
+                dq.iter_mut().zip(null).for_each(|(d, n)| *d += n);
+            }
+            let largest = dq.iter().fold(0.0f64, |m, d| m.max(d.abs()));
+            if largest > options.max_step {
+                dq.iter_mut().for_each(|d| *d *= options.max_step / largest);
+            }
+            self.integrate(&mut q, &dq)?;
+            self.clamp(&mut q)?;
+        }
+        if residual < options.tolerance {
+            return Ok(IkSolution { q, iterations: options.max_iterations, residual });
+        }
+        Err(KinematicsError::NotConverged { iterations: options.max_iterations, residual })
+    }
+}
+
+/// Chains shared by the tests of the modules built on kinematic chains
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+    use super::*;
+    use crate::joints::JointLimits;
+    use crate::si_units::{Angle, AngularVelocity, TAU};
+
+    pub(crate) const Z: Vector3 = [0.0, 0.0, 1.0];
+
+    /// Half a turn either way at up to 2 rad/s
+    pub(crate) fn full_turn() -> JointLimits {
+        JointLimits::new(Angle::new(-TAU / 2.0), Angle::new(TAU / 2.0), AngularVelocity::new(2.0))
+    }
+
+    pub(crate) fn revolute(axis: Vector3) -> Joint {
+        Joint::revolute(axis, full_turn())
+    }
+
+    /// Revolute joints about z, the first at the base and each further one a
+    /// link of `links[i]` metres along x, ending in a fixed tool slot
+    pub(crate) fn planar(links: &[f64]) -> KinematicChain {
+        let (chain, last) = links.iter().fold((KinematicChain::new(), Motor::identity()), |(chain, offset), &length| {
+            (chain.with_joint(offset, revolute(Z)), Motor::from_translation([length, 0.0, 0.0]))
+        });
+        chain.with_joint(last, Joint::Fixed)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::test_fixtures::revolute;
+    use crate::joints::{PrismaticLimits, SphericalLimits};
+    use crate::motor::Rotor;
+    use crate::sample::Sampler;
+    use crate::si_units::{Angle, AngularVelocity, Length, Velocity, TAU};
+
+    /// Spherical shoulder, revolute elbow, prismatic forearm, revolute wrist and a fixed tool
+    fn arm() -> KinematicChain {
+        KinematicChain::new()
+            .with_joint(
+                Motor::from_translation([0.0, 0.0, 0.4]),
+                Joint::spherical(SphericalLimits::new(Angle::new(TAU / 4.0), AngularVelocity::new(1.0))),
+            )
+            .with_joint(Motor::from_translation([0.0, 0.0, 0.5]), revolute([0.0, 1.0, 0.0]))
+            .with_joint(
+                Motor::from_translation([0.0, 0.0, 0.3]),
+                Joint::prismatic([0.0, 0.0, 1.0], PrismaticLimits::new(Length::new(0.0), Length::new(0.2), Velocity::new(0.1))),
+            )
+            .with_joint(Motor::identity(), revolute([1.0, 0.0, 0.0]))
+            .with_joint(Motor::from_translation([0.0, 0.0, 0.1]), Joint::Fixed)
+    }
+
+    #[test]
+    fn test_forward_kinematics() {
+        let chain = arm();
+        assert_eq!(chain.dof(), 6);
+        let stretched = chain.forward(&[0.0, 0.0, 0.0, 0.0, 0.1, 0.0]).unwrap();
+        assert!(linalg::norm(linalg::sub(stretched.translation, [0.0, 0.0, 1.4])) < 1e-12);
+
+        // Bending the elbow a quarter turn about y swings the forearm onto +x
+        let bent = chain.forward(&[0.0, 0.0, 0.0, TAU / 4.0, 0.1, 0.0]).unwrap();
+        assert!(linalg::norm(linalg::sub(bent.translation, [0.5, 0.0, 0.9])) < 1e-12);
+
+        assert_eq!(chain.frames(&chain.neutral()).unwrap().len(), 5);
+        assert_eq!(chain.forward(&[0.0; 5]), Err(KinematicsError::CoordinateCount { expected: 6, actual: 5 }));
+    }
+
+    #[test]
+    fn test_jacobian_matches_finite_differences() {
+        let chain = arm();
+        let mut sampler = Sampler::new(3);
+        let joints: Vec<Joint> = chain.joints().copied().collect();
+        let q = sampler.joint_coordinates(&joints);
+        let jacobian = chain.jacobian(&q).unwrap();
+        let pose = chain.forward(&q).unwrap();
+
+        let h = 1e-7;
+        for (k, column) in jacobian.columns.iter().enumerate() {
+            let mut dq = vec![0.0; q.len()];
+            dq[k] = h;
+            let mut moved = q.clone();
+            chain.integrate(&mut moved, &dq).unwrap();
+            let numeric = pose_error(&pose, &chain.forward(&moved).unwrap()).map(|e| e / h);
+            for (n, c) in numeric.iter().zip(column) {
+                assert!((n - c).abs() < 1e-5, "column {}: {:?} vs {:?}", k, numeric, column);
+            }
+        }
+
+        // The singular values span the same ellipsoid as the manipulability
+        let singular = jacobian.singular_values();
+        assert_eq!(singular.len(), 6);
+        assert!((singular.iter().product::<f64>() - jacobian.manipulability()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_inverse_kinematics_reaches_sampled_poses() {
+        let chain = arm();
+        let joints: Vec<Joint> = chain.joints().copied().collect();
+        let mut sampler = Sampler::new(11);
+        for _ in 0..10 {
+            let goal = sampler.joint_coordinates(&joints);
+            let target = chain.forward(&goal).unwrap();
+            let mut initial = goal.clone();
+            initial.iter_mut().for_each(|q| *q += sampler.range(-0.2, 0.2));
+
+            let solution = chain.inverse(&target, &initial, &IkOptions::default()).unwrap();
+            assert!(solution.residual < 1e-9);
+            assert!(chain.within_limits(&solution.q).unwrap());
+            let reached = chain.forward(&solution.q).unwrap();
+            assert!(linalg::norm(linalg::sub(reached.translation, target.translation)) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_unreachable_target_reports_residual() {
+        let chain = arm();
+        let target = Motor::new([3.0, 0.0, 0.0], Rotor::identity());
+        let options = IkOptions { max_iterations: 50, ..IkOptions::default() };
+        match chain.inverse(&target, &chain.neutral(), &options) {
+            Err(KinematicsError::NotConverged { iterations: 50, residual }) => assert!(residual > 1.0),
+            other => panic!("expected no convergence, got {:?}", other),
+        }
+    }
+}
@@ -41,27 +41,145 @@ pub mod grade_indexed;
 pub mod grade_checking;
 pub mod pattern_matching;
 pub mod si_units;
+pub mod linalg;
+pub mod motor;
+pub mod euler;
+pub mod primitives;
+pub mod queries;
+pub mod collision;
+pub mod mesh;
+pub mod sdf;
+pub mod grasp;
+pub mod water_column;
+pub mod tether;
+pub mod sonar;
+pub mod transform;
+pub mod imu;
+pub mod joints;
+pub mod kinematics;
+pub mod inverse_kinematics;
+pub mod redundancy;
+pub mod reachability;
+pub mod safety;
+pub mod self_collision;
+pub mod tool;
+pub mod scene;
+pub mod uvms;
+pub mod sample;
+pub mod frames;
+pub mod geodesy;
+#[cfg(feature = "nmea")]
+pub mod nmea;
+#[cfg(feature = "mavlink")]
+pub mod mavlink;
+pub mod teleop;
+pub mod control_loop;
+pub mod command_limits;
+pub mod mission;
+pub mod mission_plan;
+pub mod cspace;
+pub mod planner;
+pub mod path_primitives;
+pub mod frenet;
+pub mod guidance;
+pub mod occupancy;
+pub mod cloud_export;
+pub mod replay;
+pub mod recorder;
+pub mod pid;
+pub mod station_keeping;
+pub mod signal;
+pub mod spline;
+pub mod topp;
+pub mod energy;
+pub mod propulsion;
+pub mod allocation;
+pub mod dynamics;
+pub mod hydrostatics;
+pub mod disturbance;
+pub mod uncertainty;
+pub mod pose_graph;
+pub mod registration;
+pub mod fitting;
+pub mod streaming;
+pub mod calibration;
+pub mod robust;
+pub mod parity;
+pub mod multivector;
+pub mod clifford;
+pub mod expression;
+pub mod determinism;
+pub mod fixed_point;
+pub mod summation;
+pub mod fast_math;
+pub mod batch;
+pub mod bench;
+pub mod environment;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "ndarray")]
+pub mod ndarray_views;
 
 // Re-export commonly used types and functions
-pub use ga_term::{GATerm, Grade, Scalar, BladeTerm, Index};
-pub use grade_indexed::{GradeIndexed, ScalarType, VectorType, BivectorType, TrivectorType};
-pub use pattern_matching::{match_gaterm, visit_gaterm, GATermVisitor};
+pub use ga_term::{GATerm, Grade, Scalar, BladeTerm, BasisIndex, Index, IndexError};
+pub use grade_indexed::{GradeIndexed, ScalarType, VectorType, BivectorType, TrivectorType, FixedVector};
+pub use pattern_matching::{match_gaterm, visit_gaterm, visit_gaterm_mut, GATermIntoVisitor, GATermVisitor, GATermVisitorMut};
+pub use environment::{build_info, capabilities};
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Prelude module for convenient imports
+///
+/// The top level covers the term types and their operations. Each subsystem
+/// has its own sub-prelude, so `use gafro_modern::prelude::robotics::*` brings
+/// in motors and frames without the unit aliases or the algebra tables.
 pub mod prelude {
-    pub use crate::ga_term::{GATerm, Grade, Scalar, BladeTerm};
-    pub use crate::grade_indexed::{GradeIndexed, ScalarType, VectorType, BivectorType, TrivectorType};
+    pub use crate::ga_term::{GATerm, Grade, Scalar, BladeTerm, BasisIndex};
+    pub use crate::grade_indexed::{GradeIndexed, ScalarType, VectorType, BivectorType, TrivectorType, FixedVector};
     pub use crate::pattern_matching::{match_gaterm, operations};
     pub use crate::grade_checking::{safe_ops, TypeInspector};
+
+    /// Algebras, multivectors, versors and the geometric primitives
+    pub mod cga {
+        pub use crate::clifford::{Algebra, Cga3, Pga3, Tables, Vga3};
+        pub use crate::expression::{Dense, Expr};
+        pub use crate::motor::{Bivector, Motor, Rotor, Translator};
+        pub use crate::multivector::Multivector;
+        pub use crate::primitives::{Aabb, Circle, Direction, Line, Plane, Point, Sphere};
+        pub use crate::queries;
+    }
+
+    /// Rigid-body motion, frames and joints
+    pub mod robotics {
+        pub use crate::euler::{AxisSequence, EulerAngles};
+        pub use crate::frames::{Frame, FrameError, FrameGraph, FrameTransform};
+        pub use crate::joints::{Joint, JointLimits, PrismaticLimits, SphericalLimits};
+        pub use crate::inverse_kinematics::{IkSolver, IkSolverRegistry};
+        pub use crate::kinematics::{IkOptions, KinematicChain, KinematicsError};
+        pub use crate::redundancy::{Redundancy, RedundancyWeights};
+        pub use crate::scene::Scene;
+        pub use crate::self_collision::SelfCollisionModel;
+        pub use crate::tool::Tool;
+        pub use crate::uvms::{Uvms, UvmsInput, UvmsState};
+        pub use crate::motor::{Motor, MotorGenerator, Rotor, ScrewAxis, Translator};
+        pub use crate::transform::{Transform3, TransformError};
+    }
+
+    /// Typed quantities and the unit constructors
+    pub mod units {
+        pub use crate::si_units::{
+            Acceleration, Angle, AngularVelocity, Density, Dimension, Energy, Force, Frequency, Length, Mass, Measure,
+            MomentOfInertia, Power, Pressure, Quantity, Ratio, Temperature, Time, Torque, UnitExt, Velocity, Volume,
+        };
+        pub use crate::si_units::{convert, math};
+        pub use crate::{angle, qty};
+    }
 }
 
 #[cfg(test)]
 mod integration_tests {
     use super::*;
-    use prelude::*;
 
     #[test]
     fn test_cross_language_compatibility() {
@@ -82,19 +200,11 @@ mod integration_tests {
         let v2 = GATerm::vector(vec![(1, 10.0), (2, 20.0), (3, 30.0)]);
         let vector_sum = pattern_matching::operations::add(&v1, &v2).unwrap();
 
-        if let GATerm::Vector(v) = vector_sum {
-            assert_eq!(v[0], (1, 11.0));
-            assert_eq!(v[1], (2, 22.0));
-            assert_eq!(v[2], (3, 33.0));
-        }
+        assert_eq!(vector_sum, GATerm::vector(vec![(1, 11.0), (2, 22.0), (3, 33.0)]));
 
         // Scalar multiplication
         let scaled = pattern_matching::operations::scalar_multiply(2.0, &v1);
-        if let GATerm::Vector(v) = scaled {
-            assert_eq!(v[0], (1, 2.0));
-            assert_eq!(v[1], (2, 4.0));
-            assert_eq!(v[2], (3, 6.0));
-        }
+        assert_eq!(scaled, GATerm::vector(vec![(1, 2.0), (2, 4.0), (3, 6.0)]));
     }
 
     #[test]
@@ -159,4 +269,15 @@ mod integration_tests {
             assert!(!description.is_empty());
         }
     }
+
+    #[test]
+    fn test_sub_preludes() {
+        use prelude::robotics::{Motor, Transform3};
+        use prelude::units::*;
+
+        let shift = 1.5.meters();
+        let motor = prelude::cga::Motor::from_translation([*shift.value(), 0.0, 0.0]);
+        let motor: Motor = motor;
+        assert_eq!(Transform3::from_motor(&motor).apply_point([0.0; 3]), [1.5, 0.0, 0.0]);
+    }
 }
\ No newline at end of file
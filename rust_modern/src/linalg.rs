@@ -0,0 +1,503 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Small fixed-size linear algebra helpers
+//!
+//! Euclidean 3-vectors and square matrices stored as plain arrays, used by the
+//! motor, frame and estimation modules without pulling in a linear algebra crate.
+//! The Jacobi eigen-decomposition and SVD in [`dense`] back the fitting,
+//! calibration and manipulability code; they converge to rounding error on the
+//! small, well-scaled matrices those produce but are not meant for large systems.
+
+/// Euclidean 3-vector
+pub type Vector3 = [f64; 3];
+
+/// Row-major 3×3 matrix
+pub type Matrix3 = [[f64; 3]; 3];
+
+/// Row-major 6×6 matrix, e.g. covariances on the motor tangent space
+pub type Matrix6 = [[f64; 6]; 6];
+
+pub fn add(a: Vector3, b: Vector3) -> Vector3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub fn sub(a: Vector3, b: Vector3) -> Vector3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn scale(a: Vector3, s: f64) -> Vector3 {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+pub fn dot(a: Vector3, b: Vector3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn cross(a: Vector3, b: Vector3) -> Vector3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub fn norm(a: Vector3) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Skew-symmetric matrix `[a]×` such that `[a]× b = a × b`
+pub fn skew(a: Vector3) -> Matrix3 {
+    [[0.0, -a[2], a[1]], [a[2], 0.0, -a[0]], [-a[1], a[0], 0.0]]
+}
+
+pub fn mat3_vec(m: &Matrix3, v: Vector3) -> Vector3 {
+    [dot(m[0], v), dot(m[1], v), dot(m[2], v)]
+}
+
+pub fn mat3_mul(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut result = [[0.0; 3]; 3];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    result
+}
+
+pub fn mat3_transpose(m: &Matrix3) -> Matrix3 {
+    [
+        [m[0][0], m[1][0], m[2][0]],
+        [m[0][1], m[1][1], m[2][1]],
+        [m[0][2], m[1][2], m[2][2]],
+    ]
+}
+
+/// Square matrices of any fixed size
+pub mod square {
+    pub fn identity<const N: usize>() -> [[f64; N]; N] {
+        let mut result = [[0.0; N]; N];
+        for (i, row) in result.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        result
+    }
+
+    pub fn add<const N: usize>(a: &[[f64; N]; N], b: &[[f64; N]; N]) -> [[f64; N]; N] {
+        let mut result = *a;
+        for (row, other) in result.iter_mut().zip(b) {
+            for (value, o) in row.iter_mut().zip(other) {
+                *value += o;
+            }
+        }
+        result
+    }
+
+    pub fn mul<const N: usize>(a: &[[f64; N]; N], b: &[[f64; N]; N]) -> [[f64; N]; N] {
+        let mut result = [[0.0; N]; N];
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..N).map(|k| a[i][k] * b[k][j]).sum();
+            }
+        }
+        result
+    }
+
+    pub fn transpose<const N: usize>(m: &[[f64; N]; N]) -> [[f64; N]; N] {
+        let mut result = [[0.0; N]; N];
+        for (i, row) in m.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                result[j][i] = *value;
+            }
+        }
+        result
+    }
+
+    pub fn mul_vec<const N: usize>(m: &[[f64; N]; N], v: &[f64; N]) -> [f64; N] {
+        let mut result = [0.0; N];
+        for (value, row) in result.iter_mut().zip(m) {
+            *value = row.iter().zip(v).map(|(a, b)| a * b).sum();
+        }
+        result
+    }
+
+    /// Congruence transform `A M Aᵀ`
+    pub fn sandwich<const N: usize>(a: &[[f64; N]; N], m: &[[f64; N]; N]) -> [[f64; N]; N] {
+        mul(&mul(a, m), &transpose(a))
+    }
+
+    /// Inverse by Gauss-Jordan elimination with partial pivoting; `None` if singular
+    pub fn inverse<const N: usize>(m: &[[f64; N]; N]) -> Option<[[f64; N]; N]> {
+        let mut a = *m;
+        let mut inv = identity::<N>();
+        for col in 0..N {
+            let pivot = (col..N).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for k in 0..N {
+                a[col][k] *= scale;
+                inv[col][k] *= scale;
+            }
+            for row in 0..N {
+                if row != col {
+                    let factor = a[row][col];
+                    for k in 0..N {
+                        a[row][k] -= factor * a[col][k];
+                        inv[row][k] -= factor * inv[col][k];
+                    }
+                }
+            }
+        }
+        Some(inv)
+    }
+
+    /// Eigen-decomposition of a symmetric matrix by cyclic Jacobi rotations
+    ///
+    /// Returns eigenvalues in ascending order and the matching unit eigenvectors as columns.
+    pub fn symmetric_eigen<const N: usize>(m: &[[f64; N]; N]) -> ([f64; N], [[f64; N]; N]) {
+        let (values, vectors) = super::dense::symmetric_eigen(m.as_flattened(), N);
+        (std::array::from_fn(|i| values[i]), std::array::from_fn(|i| std::array::from_fn(|j| vectors[i * N + j])))
+    }
+
+    /// Quadratic form `vᵀ M v`
+    pub fn quadratic_form<const N: usize>(m: &[[f64; N]; N], v: &[f64; N]) -> f64 {
+        mul_vec(m, v).iter().zip(v).map(|(a, b)| a * b).sum()
+    }
+}
+
+/// Dynamically sized row-major matrices stored in flat slices
+pub mod dense {
+    /// Solve `A x = b` for symmetric positive-definite `A` (n×n); `None` if not positive definite
+    pub fn cholesky_solve(a: &[f64], n: usize, b: &[f64]) -> Option<Vec<f64>> {
+        let mut l = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..=i {
+                let sum: f64 = (0..j).map(|k| l[i * n + k] * l[j * n + k]).sum();
+                if i == j {
+                    let diagonal = a[i * n + i] - sum;
+                    if diagonal <= 0.0 {
+                        return None;
+                    }
+                    l[i * n + i] = diagonal.sqrt();
+                } else {
+                    l[i * n + j] = (a[i * n + j] - sum) / l[j * n + j];
+                }
+            }
+        }
+
+        // Forward substitution L y = b, then back substitution Lᵀ x = y
+        let mut x = b.to_vec();
+        for i in 0..n {
+            let sum: f64 = (0..i).map(|k| l[i * n + k] * x[k]).sum();
+            x[i] = (x[i] - sum) / l[i * n + i];
+        }
+        for i in (0..n).rev() {
+            let sum: f64 = (i + 1..n).map(|k| l[k * n + i] * x[k]).sum();
+            x[i] = (x[i] - sum) / l[i * n + i];
+        }
+        Some(x)
+    }
+
+    /// Eigen-decomposition of a symmetric n×n matrix by cyclic Jacobi rotations
+    ///
+    /// Returns eigenvalues in ascending order and an n×n matrix holding the
+    /// matching unit eigenvectors as columns.
+    pub fn symmetric_eigen(m: &[f64], n: usize) -> (Vec<f64>, Vec<f64>) {
+        let mut a = m.to_vec();
+        let mut v = vec![0.0; n * n];
+        for i in 0..n {
+            v[i * n + i] = 1.0;
+        }
+        for _sweep in 0..64 {
+            let off_diagonal: f64 = (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))).map(|(i, j)| a[i * n + j] * a[i * n + j]).sum();
+            if off_diagonal < 1e-30 {
+                break;
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    if a[p * n + q].abs() < 1e-300 {
+                        continue;
+                    }
+                    let theta = (a[q * n + q] - a[p * n + p]) / (2.0 * a[p * n + q]);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+                    for row in a.chunks_exact_mut(n).chain(v.chunks_exact_mut(n)) {
+                        let (kp, kq) = (row[p], row[q]);
+                        row[p] = c * kp - s * kq;
+                        row[q] = s * kp + c * kq;
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a[p * n + k], a[q * n + k]);
+                        a[p * n + k] = c * apk - s * aqk;
+                        a[q * n + k] = s * apk + c * aqk;
+                    }
+                }
+            }
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| a[i * n + i].total_cmp(&a[j * n + j]));
+        let values = order.iter().map(|&i| a[i * n + i]).collect();
+        let vectors = (0..n).flat_map(|row| order.iter().map(move |&col| (row, col))).map(|(row, col)| v[row * n + col]).collect();
+        (values, vectors)
+    }
+
+    /// Thin singular value decomposition `A = U Σ Vᵀ` of an m×n matrix by
+    /// one-sided Jacobi rotations
+    ///
+    /// With `k = min(m, n)`, returns `U` (m×k), the `k` singular values in
+    /// descending order and `V` (n×k), both with orthonormal columns wherever
+    /// the singular value is non-zero.
+    pub fn svd(a: &[f64], m: usize, n: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        if m < n {
+            let transpose: Vec<f64> = (0..n).flat_map(|j| (0..m).map(move |i| a[i * n + j])).collect();
+            let (u, values, v) = svd(&transpose, n, m);
+            return (v, values, u);
+        }
+
+        let mut u = a.to_vec();
+        let mut v = vec![0.0; n * n];
+        for i in 0..n {
+            v[i * n + i] = 1.0;
+        }
+        let column_dot = |u: &[f64], p: usize, q: usize| -> f64 { (0..m).map(|i| u[i * n + p] * u[i * n + q]).sum() };
+        for _sweep in 0..64 {
+            let mut rotated = false;
+            for p in 0..n {
+                for q in p + 1..n {
+                    let (alpha, beta, gamma) = (column_dot(&u, p, p), column_dot(&u, q, q), column_dot(&u, p, q));
+                    if gamma.abs() <= 1e-15 * (alpha * beta).sqrt() || gamma.abs() < 1e-300 {
+                        continue;
+                    }
+                    rotated = true;
+                    let zeta = (beta - alpha) / (2.0 * gamma);
+                    let t = zeta.signum() / (zeta.abs() + (zeta * zeta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+                    for row in u.chunks_exact_mut(n).chain(v.chunks_exact_mut(n)) {
+                        let (kp, kq) = (row[p], row[q]);
+                        row[p] = c * kp - s * kq;
+                        row[q] = s * kp + c * kq;
+                    }
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        let norms: Vec<f64> = (0..n).map(|j| column_dot(&u, j, j).sqrt()).collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| norms[j].total_cmp(&norms[i]));
+        let values: Vec<f64> = order.iter().map(|&j| norms[j]).collect();
+        let left = (0..m)
+            .flat_map(|i| order.iter().map(move |&j| (i, j)))
+            .map(|(i, j)| if norms[j] > 0.0 { u[i * n + j] / norms[j] } else { 0.0 })
+            .collect();
+        let right = (0..n).flat_map(|i| order.iter().map(move |&j| (i, j))).map(|(i, j)| v[i * n + j]).collect();
+        (left, values, right)
+    }
+
+    /// Minimum-norm least-squares solution of `A x = b` for an m×n `A`, from
+    /// its SVD. Singular values below `cutoff` times the largest are treated
+    /// as zero, so rank-deficient systems get the smallest `x` among the best fits.
+    pub fn least_squares(a: &[f64], m: usize, n: usize, b: &[f64], cutoff: f64) -> Vec<f64> {
+        let (u, values, v) = svd(a, m, n);
+        let k = values.len();
+        let largest = values.first().copied().unwrap_or(0.0);
+        let mut x = vec![0.0; n];
+        for (j, &value) in values.iter().enumerate() {
+            if value <= cutoff * largest || value == 0.0 {
+                continue;
+            }
+            let along = (0..m).map(|i| u[i * k + j] * b[i]).sum::<f64>() / value;
+            for (i, x) in x.iter_mut().enumerate() {
+                *x += v[i * k + j] * along;
+            }
+        }
+        x
+    }
+
+    /// Non-negative least squares: `x ≥ 0` minimizing `‖A x - b‖` for `A` (m×n),
+    /// by the active-set method of Lawson and Hanson. Returns `x` and the residual norm.
+    pub fn nnls(a: &[f64], m: usize, n: usize, b: &[f64]) -> (Vec<f64>, f64) {
+        const TOLERANCE: f64 = 1e-12;
+        let residual = |x: &[f64]| -> Vec<f64> { (0..m).map(|i| b[i] - (0..n).map(|j| a[i * n + j] * x[j]).sum::<f64>()).collect() };
+        let gradient = |r: &[f64]| -> Vec<f64> { (0..n).map(|j| (0..m).map(|i| a[i * n + j] * r[i]).sum()).collect() };
+        // Unconstrained least squares on the passive columns, zero elsewhere
+        let solve_passive = |passive: &[usize]| -> Option<Vec<f64>> {
+            let k = passive.len();
+            let mut normal = vec![0.0; k * k];
+            let mut rhs = vec![0.0; k];
+            for (p, &cp) in passive.iter().enumerate() {
+                rhs[p] = (0..m).map(|i| a[i * n + cp] * b[i]).sum();
+                for (q, &cq) in passive.iter().enumerate() {
+                    normal[p * k + q] = (0..m).map(|i| a[i * n + cp] * a[i * n + cq]).sum();
+                }
+            }
+            let z = cholesky_solve(&normal, k, &rhs)?;
+            let mut full = vec![0.0; n];
+            for (p, &cp) in passive.iter().enumerate() {
+                full[cp] = z[p];
+            }
+            Some(full)
+        };
+
+        let mut x = vec![0.0; n];
+        let mut passive: Vec<usize> = Vec::new();
+        for _ in 0..3 * n.max(1) {
+            let w = gradient(&residual(&x));
+            let Some(entering) = (0..n)
+                .filter(|j| !passive.contains(j) && w[*j] > TOLERANCE)
+                .max_by(|&i, &j| w[i].total_cmp(&w[j]))
+            else {
+                break;
+            };
+            passive.push(entering);
+            loop {
+                let Some(z) = solve_passive(&passive) else {
+                    // Dependent columns: drop the newest and stop improving
+                    passive.pop();
+                    let r = residual(&x);
+                    return (x, r.iter().map(|v| v * v).sum::<f64>().sqrt());
+                };
+                if passive.iter().all(|&j| z[j] > TOLERANCE) {
+                    x = z;
+                    break;
+                }
+                // Step towards z until the first passive variable reaches zero
+                let alpha = passive
+                    .iter()
+                    .filter(|&&j| z[j] <= TOLERANCE)
+                    .map(|&j| x[j] / (x[j] - z[j]))
+                    .fold(f64::INFINITY, f64::min);
+                for j in 0..n {
+                    x[j] += alpha * (z[j] - x[j]);
+                }
+                passive.retain(|&j| x[j] > TOLERANCE);
+                for j in (0..n).filter(|j| !passive.contains(j)) {
+                    x[j] = 0.0;
+                }
+            }
+        }
+        let r = residual(&x);
+        (x, r.iter().map(|v| v * v).sum::<f64>().sqrt())
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_operations() {
+        let x = [1.0, 0.0, 0.0];
+        let y = [0.0, 1.0, 0.0];
+
+        assert_eq!(cross(x, y), [0.0, 0.0, 1.0]);
+        assert_eq!(dot(x, y), 0.0);
+        assert_eq!(norm([3.0, 4.0, 0.0]), 5.0);
+        assert_eq!(mat3_vec(&skew(x), y), cross(x, y));
+    }
+
+    #[test]
+    fn test_matrix_operations() {
+        let m = [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]];
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+        assert_eq!(mat3_mul(&m, &identity), m);
+        assert_eq!(mat3_transpose(&mat3_transpose(&m)), m);
+        assert_eq!(mat3_vec(&m, [1.0, 0.0, 0.0]), [1.0, 4.0, 7.0]);
+    }
+
+    #[test]
+    fn test_square_inverse() {
+        let m = [[4.0, 1.0, 0.0, 0.0], [1.0, 3.0, 0.0, 0.0], [0.0, 0.0, 2.0, 0.0], [0.0, 0.0, 1.0, 1.0]];
+        let product = square::mul(&m, &square::inverse(&m).unwrap());
+        let identity = square::identity::<4>();
+
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((product[i][j] - identity[i][j]).abs() < 1e-12);
+            }
+        }
+        assert!(square::inverse(&[[1.0, 2.0], [2.0, 4.0]]).is_none());
+        assert_eq!(square::quadratic_form(&[[2.0, 0.0], [0.0, 3.0]], &[1.0, 1.0]), 5.0);
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let m = [[2.0, 1.0, 0.0], [1.0, 2.0, 0.0], [0.0, 0.0, 5.0]];
+        let (values, vectors) = square::symmetric_eigen(&m);
+
+        assert!((values[0] - 1.0).abs() < 1e-12);
+        assert!((values[1] - 3.0).abs() < 1e-12);
+        assert!((values[2] - 5.0).abs() < 1e-12);
+        for (k, value) in values.iter().enumerate() {
+            let column = [vectors[0][k], vectors[1][k], vectors[2][k]];
+            let image = mat3_vec(&m, column);
+            assert!(norm(sub(image, scale(column, *value))) < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_dense_eigen_and_svd() {
+        // Agrees with the fixed-size eigen-decomposition
+        let m = [[4.0, 1.0, -2.0, 0.5], [1.0, 3.0, 0.0, 1.0], [-2.0, 0.0, 5.0, -1.0], [0.5, 1.0, -1.0, 2.0]];
+        let (fixed, _) = square::symmetric_eigen(&m);
+        let (values, vectors) = dense::symmetric_eigen(m.as_flattened(), 4);
+        assert_eq!(values, fixed.to_vec());
+        for (k, value) in values.iter().enumerate() {
+            let column: Vec<f64> = (0..4).map(|i| vectors[i * 4 + k]).collect();
+            for (row, c) in m.iter().zip(&column) {
+                let image: f64 = row.iter().zip(&column).map(|(a, b)| a * b).sum();
+                assert!((image - value * c).abs() < 1e-12);
+            }
+        }
+
+        // A wide 2×3 matrix of rank 2 is rebuilt from its factors
+        let a = [3.0, 2.0, 2.0, 2.0, 3.0, -2.0];
+        let (u, sigma, v) = dense::svd(&a, 2, 3);
+        assert!((sigma[0] - 5.0).abs() < 1e-12 && (sigma[1] - 3.0).abs() < 1e-12);
+        for i in 0..2 {
+            for j in 0..3 {
+                let rebuilt: f64 = (0..2).map(|k| u[i * 2 + k] * sigma[k] * v[j * 2 + k]).sum();
+                assert!((rebuilt - a[i * 3 + j]).abs() < 1e-12);
+            }
+        }
+
+        // Rank-deficient least squares picks the minimum-norm solution
+        let x = dense::least_squares(&[1.0, 1.0, 2.0, 2.0], 2, 2, &[2.0, 4.0], 1e-12);
+        assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cholesky_solve() {
+        let a = [4.0, 2.0, 2.0, 3.0];
+        let x = dense::cholesky_solve(&a, 2, &[2.0, 1.0]).unwrap();
+
+        assert!((x[0] - 0.5).abs() < 1e-12);
+        assert!(x[1].abs() < 1e-12);
+        assert!(dense::cholesky_solve(&[1.0, 2.0, 2.0, 1.0], 2, &[1.0, 1.0]).is_none());
+    }
+
+    #[test]
+    fn test_nnls() {
+        // Unconstrained solution (1, -1) is infeasible; the best x ≥ 0 drops the second column
+        let a = [1.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        let (x, residual) = dense::nnls(&a, 3, 2, &[1.0, -1.0, 0.0]);
+        assert!((x[0] - 0.5).abs() < 1e-12 && x[1] == 0.0);
+        assert!((residual - 1.5f64.sqrt()).abs() < 1e-12);
+
+        let (x, residual) = dense::nnls(&a, 3, 2, &[2.0, 3.0, 5.0]);
+        assert!((x[0] - 2.0).abs() < 1e-12 && (x[1] - 3.0).abs() < 1e-12 && residual < 1e-12);
+    }
+}
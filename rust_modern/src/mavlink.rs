@@ -0,0 +1,484 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! MAVLink 2 setpoints and telemetry for ArduSub and other autopilots
+//!
+//! [`MavlinkEncoder`] packs attitude, velocity and position setpoints given as
+//! the crate's rotors and typed quantities into MAVLink 2 frames, and
+//! [`MavlinkDecoder`] unpacks the autopilot's attitude and position telemetry
+//! from a byte stream into [`Telemetry`].
+//!
+//! The crate works in East-North-Up with Forward-Left-Up bodies; MAVLink uses
+//! North-East-Down with Forward-Right-Down bodies. All conversions happen here,
+//! so nothing outside this module sees NED.
+//!
+//! Only the messages below are understood; others are skipped by the decoder.
+//! Signed frames are accepted but their signatures are not checked.
+
+use std::fmt;
+
+use crate::geodesy::GeodeticCoordinate;
+use crate::linalg::{self, Matrix3, Vector3};
+use crate::motor::Rotor;
+use crate::si_units::{Angle, AngularVelocity, Length, Time, TAU};
+
+/// First byte of every MAVLink 2 frame
+pub const MAVLINK_STX: u8 = 0xFD;
+
+/// Header bytes after the start marker
+const HEADER_LEN: usize = 9;
+
+/// Incompatibility flag marking a signed frame
+const SIGNED: u8 = 0x01;
+
+const SIGNATURE_LEN: usize = 13;
+
+/// Message id and CRC seed of each message this module reads or writes
+const HEARTBEAT: (u32, u8) = (0, 50);
+const ATTITUDE: (u32, u8) = (30, 39);
+const LOCAL_POSITION_NED: (u32, u8) = (32, 185);
+const GLOBAL_POSITION_INT: (u32, u8) = (33, 104);
+const SET_ATTITUDE_TARGET: (u32, u8) = (82, 49);
+const SET_POSITION_TARGET_LOCAL_NED: (u32, u8) = (84, 143);
+
+/// `MAV_FRAME_LOCAL_NED`
+const FRAME_LOCAL_NED: u8 = 1;
+
+/// Errors raised while reading frames
+#[derive(Debug, Clone, PartialEq)]
+pub enum MavlinkError {
+    /// More bytes are needed to complete the frame
+    Incomplete,
+    /// The frame does not start with [`MAVLINK_STX`]
+    BadStart(u8),
+    Checksum { expected: u16, actual: u16 },
+    /// A valid frame of a message this module does not decode
+    UnknownMessage(u32),
+}
+
+impl fmt::Display for MavlinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MavlinkError::Incomplete => write!(f, "incomplete MAVLink frame"),
+            MavlinkError::BadStart(byte) => write!(f, "MAVLink 2 frames start with 0xFD, found 0x{:02X}", byte),
+            MavlinkError::Checksum { expected, actual } => {
+                write!(f, "checksum mismatch: frame says {:04X}, contents give {:04X}", expected, actual)
+            }
+            MavlinkError::UnknownMessage(id) => write!(f, "no decoder for message id {}", id),
+        }
+    }
+}
+
+impl std::error::Error for MavlinkError {}
+
+/// A checked MAVLink 2 frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MavlinkFrame {
+    pub sequence: u8,
+    pub system: u8,
+    pub component: u8,
+    pub message_id: u32,
+    /// Payload with MAVLink 2's trailing-zero truncation undone as far as known
+    pub payload: Vec<u8>,
+}
+
+/// Orientation setpoint with collective thrust
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttitudeSetpoint {
+    /// Body (forward-left-up) orientation in East-North-Up
+    pub orientation: Rotor,
+    /// Collective thrust as a fraction in `[0, 1]`; ArduSub treats 0.5 as neutral
+    pub thrust: f64,
+}
+
+/// Decoded telemetry, converted to East-North-Up and forward-left-up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Telemetry {
+    Attitude {
+        /// Time since the autopilot booted
+        time: Time,
+        orientation: Rotor,
+        /// Body rates about forward, left and up
+        rates: [AngularVelocity; 3],
+    },
+    LocalPosition {
+        time: Time,
+        /// East, north and up of the autopilot's local origin (m)
+        position: Vector3,
+        /// East, north and up (m/s)
+        velocity: Vector3,
+    },
+    GlobalPosition {
+        time: Time,
+        /// Altitude is above mean sea level, as the autopilot reports it
+        coordinate: GeodeticCoordinate,
+        /// Depth-style height above the home position
+        relative_altitude: Length,
+        /// East, north and up (m/s)
+        velocity: Vector3,
+        /// Clockwise from north; `None` when unknown
+        heading: Option<Angle>,
+    },
+}
+
+/// Writes setpoint frames from one system and component to one target
+#[derive(Debug, Clone)]
+pub struct MavlinkEncoder {
+    pub system: u8,
+    pub component: u8,
+    pub target_system: u8,
+    pub target_component: u8,
+    sequence: u8,
+}
+
+impl MavlinkEncoder {
+    /// Ground control station ids (255, 190) talking to the autopilot (1, 1)
+    pub fn new() -> Self {
+        Self { system: 255, component: 190, target_system: 1, target_component: 1, sequence: 0 }
+    }
+
+    /// `HEARTBEAT` from a ground control station
+    pub fn heartbeat(&mut self) -> Vec<u8> {
+        // custom_mode, type = MAV_TYPE_GCS, autopilot = MAV_AUTOPILOT_INVALID, base_mode, system_status, version 3
+        let payload = [0, 0, 0, 0, 6, 8, 0, 0, 3];
+        self.frame(HEARTBEAT, &payload)
+    }
+
+    /// `SET_ATTITUDE_TARGET` holding an orientation, rates left free
+    pub fn attitude_target(&mut self, setpoint: &AttitudeSetpoint, time_boot: Time) -> Vec<u8> {
+        let q = enu_to_ned_rotor(&setpoint.orientation).to_quaternion();
+        let mut payload = Vec::with_capacity(39);
+        put_u32(&mut payload, time_boot_ms(time_boot));
+        for value in q.into_iter().chain([0.0, 0.0, 0.0, setpoint.thrust.clamp(0.0, 1.0)]) {
+            put_f32(&mut payload, value);
+        }
+        // Ignore the three body rates
+        payload.extend([self.target_system, self.target_component, 0b0000_0111]);
+        self.frame(SET_ATTITUDE_TARGET, &payload)
+    }
+
+    /// `SET_POSITION_TARGET_LOCAL_NED` holding a velocity and yaw rate
+    pub fn velocity_target(&mut self, velocity: Vector3, yaw_rate: AngularVelocity, time_boot: Time) -> Vec<u8> {
+        let fields = [[0.0; 3], enu_to_ned(velocity), [0.0; 3]];
+        // Ignore position, acceleration and yaw
+        self.local_ned_target(fields, 0.0, -yaw_rate.value(), 0b0101_1100_0111, time_boot)
+    }
+
+    /// `SET_POSITION_TARGET_LOCAL_NED` holding a position and a heading clockwise from north
+    pub fn position_target(&mut self, position: Vector3, heading: Angle, time_boot: Time) -> Vec<u8> {
+        let fields = [enu_to_ned(position), [0.0; 3], [0.0; 3]];
+        // Ignore velocity, acceleration and yaw rate
+        self.local_ned_target(fields, *heading.value(), 0.0, 0b1001_1111_1000, time_boot)
+    }
+
+    fn local_ned_target(&mut self, fields: [Vector3; 3], yaw: f64, yaw_rate: f64, type_mask: u16, time_boot: Time) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(53);
+        put_u32(&mut payload, time_boot_ms(time_boot));
+        for value in fields.into_iter().flatten().chain([yaw, yaw_rate]) {
+            put_f32(&mut payload, value);
+        }
+        payload.extend(type_mask.to_le_bytes());
+        payload.extend([self.target_system, self.target_component, FRAME_LOCAL_NED]);
+        self.frame(SET_POSITION_TARGET_LOCAL_NED, &payload)
+    }
+
+    fn frame(&mut self, message: (u32, u8), payload: &[u8]) -> Vec<u8> {
+        let frame = encode_frame(self.sequence, self.system, self.component, message, payload);
+        self.sequence = self.sequence.wrapping_add(1);
+        frame
+    }
+}
+
+impl Default for MavlinkEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads telemetry out of a byte stream that may split or corrupt frames
+#[derive(Debug, Clone, Default)]
+pub struct MavlinkDecoder {
+    buffer: Vec<u8>,
+}
+
+impl MavlinkDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `bytes` and return the telemetry of every complete frame; frames
+    /// of other messages are skipped and corrupt ones resynchronized past
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Telemetry> {
+        self.buffer.extend_from_slice(bytes);
+        let mut telemetry = Vec::new();
+        let mut start = 0;
+        while let Some(offset) = self.buffer[start..].iter().position(|b| *b == MAVLINK_STX) {
+            start += offset;
+            match parse_frame(&self.buffer[start..]) {
+                Ok((frame, length)) => {
+                    telemetry.extend(decode_telemetry(&frame).ok());
+                    start += length;
+                }
+                Err(MavlinkError::Incomplete) => break,
+                Err(_) => start += 1,
+            }
+        }
+        // Keep an unfinished frame; drop everything before it
+        let keep = self.buffer[start..].iter().position(|b| *b == MAVLINK_STX).map_or(self.buffer.len(), |o| start + o);
+        self.buffer.drain(..keep);
+        telemetry
+    }
+}
+
+/// Parse the frame at the start of `bytes`, returning it and its length
+pub fn parse_frame(bytes: &[u8]) -> Result<(MavlinkFrame, usize), MavlinkError> {
+    match bytes.first() {
+        None => return Err(MavlinkError::Incomplete),
+        Some(&MAVLINK_STX) => {}
+        Some(&other) => return Err(MavlinkError::BadStart(other)),
+    }
+    if bytes.len() < 1 + HEADER_LEN {
+        return Err(MavlinkError::Incomplete);
+    }
+    let payload_len = bytes[1] as usize;
+    let signed = bytes[2] & SIGNED != 0;
+    let length = 1 + HEADER_LEN + payload_len + 2 + if signed { SIGNATURE_LEN } else { 0 };
+    if bytes.len() < length {
+        return Err(MavlinkError::Incomplete);
+    }
+
+    let message_id = u32::from_le_bytes([bytes[7], bytes[8], bytes[9], 0]);
+    let body = &bytes[1..1 + HEADER_LEN + payload_len];
+    let expected = u16::from_le_bytes([bytes[1 + HEADER_LEN + payload_len], bytes[2 + HEADER_LEN + payload_len]]);
+    let seed = crc_extra(message_id).ok_or(MavlinkError::UnknownMessage(message_id))?;
+    let actual = crc16(body.iter().copied().chain([seed]));
+    if expected != actual {
+        return Err(MavlinkError::Checksum { expected, actual });
+    }
+    let frame = MavlinkFrame {
+        sequence: bytes[4],
+        system: bytes[5],
+        component: bytes[6],
+        message_id,
+        payload: body[HEADER_LEN..].to_vec(),
+    };
+    Ok((frame, length))
+}
+
+/// Telemetry carried by a frame
+pub fn decode_telemetry(frame: &MavlinkFrame) -> Result<Telemetry, MavlinkError> {
+    // Restore the zeros MAVLink 2 strips from the end of payloads
+    let mut p = frame.payload.clone();
+    p.resize(p.len().max(28), 0);
+    let f = |i: usize| f32::from_le_bytes([p[i], p[i + 1], p[i + 2], p[i + 3]]) as f64;
+    let i32_at = |i: usize| i32::from_le_bytes([p[i], p[i + 1], p[i + 2], p[i + 3]]);
+    let i16_at = |i: usize| i16::from_le_bytes([p[i], p[i + 1]]) as f64;
+    let time = Time::new(u32::from_le_bytes([p[0], p[1], p[2], p[3]]) as f64 * 1e-3);
+
+    match frame.message_id {
+        id if id == ATTITUDE.0 => {
+            let ned = Rotor::from_rotation_matrix(&euler_zyx(f(4), f(8), f(12)));
+            let rates = frd_to_flu([f(16), f(20), f(24)]).map(AngularVelocity::new);
+            Ok(Telemetry::Attitude { time, orientation: enu_to_ned_rotor(&ned), rates })
+        }
+        id if id == LOCAL_POSITION_NED.0 => Ok(Telemetry::LocalPosition {
+            time,
+            position: enu_to_ned([f(4), f(8), f(12)]),
+            velocity: enu_to_ned([f(16), f(20), f(24)]),
+        }),
+        id if id == GLOBAL_POSITION_INT.0 => {
+            let heading = u16::from_le_bytes([p[26], p[27]]);
+            let degrees = |v: i32| Angle::new(v as f64 * 1e-7 * TAU / 360.0);
+            Ok(Telemetry::GlobalPosition {
+                time,
+                coordinate: GeodeticCoordinate::new(degrees(i32_at(4)), degrees(i32_at(8)), Length::new(i32_at(12) as f64 * 1e-3)),
+                relative_altitude: Length::new(i32_at(16) as f64 * 1e-3),
+                velocity: enu_to_ned([i16_at(20) * 0.01, i16_at(22) * 0.01, i16_at(24) * 0.01]),
+                heading: (heading != u16::MAX).then(|| Angle::new(heading as f64 * 0.01 * TAU / 360.0)),
+            })
+        }
+        id => Err(MavlinkError::UnknownMessage(id)),
+    }
+}
+
+fn crc_extra(message_id: u32) -> Option<u8> {
+    [HEARTBEAT, ATTITUDE, LOCAL_POSITION_NED, GLOBAL_POSITION_INT, SET_ATTITUDE_TARGET, SET_POSITION_TARGET_LOCAL_NED]
+        .iter()
+        .find(|(id, _)| *id == message_id)
+        .map(|(_, extra)| *extra)
+}
+
+fn encode_frame(sequence: u8, system: u8, component: u8, (message_id, extra): (u32, u8), payload: &[u8]) -> Vec<u8> {
+    // MAVLink 2 drops trailing zeros but always sends at least one byte
+    let used = payload.iter().rposition(|b| *b != 0).map_or(1, |last| last + 1);
+    let id = message_id.to_le_bytes();
+    let mut frame = vec![MAVLINK_STX, used as u8, 0, 0, sequence, system, component, id[0], id[1], id[2]];
+    frame.extend_from_slice(&payload[..used]);
+    let crc = crc16(frame[1..].iter().copied().chain([extra]));
+    frame.extend(crc.to_le_bytes());
+    frame
+}
+
+/// CRC-16/MCRF4XX, the X.25 checksum MAVLink uses
+fn crc16(bytes: impl IntoIterator<Item = u8>) -> u16 {
+    bytes.into_iter().fold(0xFFFF, |crc: u16, byte| {
+        let mut tmp = byte ^ (crc & 0xFF) as u8;
+        tmp ^= tmp << 4;
+        let tmp = tmp as u16;
+        (crc >> 8) ^ (tmp << 8) ^ (tmp << 3) ^ (tmp >> 4)
+    })
+}
+
+fn put_u32(payload: &mut Vec<u8>, value: u32) {
+    payload.extend(value.to_le_bytes());
+}
+
+fn put_f32(payload: &mut Vec<u8>, value: f64) {
+    payload.extend((value as f32).to_le_bytes());
+}
+
+fn time_boot_ms(time: Time) -> u32 {
+    (time.value() * 1e3).round().clamp(0.0, u32::MAX as f64) as u32
+}
+
+/// Swap between East-North-Up and North-East-Down; the map is its own inverse
+fn enu_to_ned(v: Vector3) -> Vector3 {
+    [v[1], v[0], -v[2]]
+}
+
+/// Swap between forward-right-down and forward-left-up body axes
+fn frd_to_flu(v: Vector3) -> Vector3 {
+    [v[0], -v[1], -v[2]]
+}
+
+/// Re-express a body orientation across both axis swaps; its own inverse
+fn enu_to_ned_rotor(rotor: &Rotor) -> Rotor {
+    let world: Matrix3 = [[0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]];
+    let body: Matrix3 = [[1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, -1.0]];
+    let r = linalg::mat3_mul(&linalg::mat3_mul(&world, &rotor.to_rotation_matrix()), &body);
+    Rotor::from_rotation_matrix(&r)
+}
+
+/// Rotation matrix of aerospace roll, pitch and yaw
+fn euler_zyx(roll: f64, pitch: f64, yaw: f64) -> Matrix3 {
+    let rz = Rotor::from_axis_angle([0.0, 0.0, 1.0], yaw);
+    let ry = Rotor::from_axis_angle([0.0, 1.0, 0.0], pitch);
+    let rx = Rotor::from_axis_angle([1.0, 0.0, 0.0], roll);
+    (rz * ry * rx).to_rotation_matrix()
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f32_at(payload: &[u8], i: usize) -> f64 {
+        f32::from_le_bytes([payload[i], payload[i + 1], payload[i + 2], payload[i + 3]]) as f64
+    }
+
+    #[test]
+    fn test_crc_and_framing() {
+        assert_eq!(crc16(*b"123456789"), 0x6F91);
+
+        let mut encoder = MavlinkEncoder::new();
+        let heartbeat = encoder.heartbeat();
+        let (frame, length) = parse_frame(&heartbeat).unwrap();
+        assert_eq!(length, heartbeat.len());
+        assert_eq!((frame.sequence, frame.system, frame.component, frame.message_id), (0, 255, 190, 0));
+        assert_eq!(parse_frame(&encoder.heartbeat()).unwrap().0.sequence, 1);
+
+        let mut corrupt = heartbeat.clone();
+        corrupt[12] ^= 0xFF;
+        assert!(matches!(parse_frame(&corrupt), Err(MavlinkError::Checksum { .. })));
+        assert_eq!(parse_frame(&heartbeat[..8]), Err(MavlinkError::Incomplete));
+        assert_eq!(parse_frame(&[0xFE, 0]), Err(MavlinkError::BadStart(0xFE)));
+    }
+
+    #[test]
+    fn test_setpoints_use_ned() {
+        let mut encoder = MavlinkEncoder::new();
+        // Facing east in ENU is a yaw of 90° in NED
+        let setpoint = AttitudeSetpoint { orientation: Rotor::identity(), thrust: 0.5 };
+        let (frame, _) = parse_frame(&encoder.attitude_target(&setpoint, Time::new(1.5))).unwrap();
+        assert_eq!(frame.message_id, 82);
+        assert_eq!(u32::from_le_bytes(frame.payload[..4].try_into().unwrap()), 1500);
+        let q = [4, 8, 12, 16].map(|i| f32_at(&frame.payload, i));
+        let half = (TAU / 8.0).sin();
+        assert!((q[0] - (TAU / 8.0).cos()).abs() < 1e-6 && q[1].abs() < 1e-6 && q[2].abs() < 1e-6 && (q[3] - half).abs() < 1e-6);
+        assert_eq!(f32_at(&frame.payload, 32), 0.5);
+        assert_eq!(&frame.payload[36..], &[1, 1, 7]);
+
+        let (frame, _) = parse_frame(&encoder.velocity_target([1.0, 2.0, -0.5], AngularVelocity::new(0.1), Time::new(0.0))).unwrap();
+        assert_eq!(frame.message_id, 84);
+        let velocity = [16, 20, 24].map(|i| f32_at(&frame.payload, i));
+        assert_eq!(velocity, [2.0, 1.0, 0.5]);
+        assert!((f32_at(&frame.payload, 44) + 0.1).abs() < 1e-7);
+        assert_eq!(u16::from_le_bytes([frame.payload[48], frame.payload[49]]), 0x5C7);
+
+        // Zero setpoints are truncated on the wire but parse back to their full length
+        let (frame, _) = parse_frame(&encoder.position_target([0.0; 3], Angle::new(0.0), Time::new(0.0))).unwrap();
+        assert_eq!(frame.payload.len(), 53);
+        assert_eq!(&frame.payload[50..], &[1, 1, FRAME_LOCAL_NED]);
+    }
+
+    #[test]
+    fn test_decode_telemetry_stream() {
+        // Autopilot at 30 m depth, heading east, rolling right at 0.2 rad/s
+        let mut attitude = Vec::new();
+        put_u32(&mut attitude, 1000);
+        for value in [0.0, 0.0, TAU / 4.0, 0.2, 0.0, 0.0] {
+            put_f32(&mut attitude, value);
+        }
+        let mut local = Vec::new();
+        put_u32(&mut local, 1010);
+        for value in [10.0, 20.0, 30.0, 1.0, 0.0, 0.0] {
+            put_f32(&mut local, value);
+        }
+        let mut global = Vec::new();
+        put_u32(&mut global, 1020);
+        for value in [481_173_000i32, 115_166_667, -30_000, -30_000] {
+            global.extend(value.to_le_bytes());
+        }
+        for value in [100i16, 0, 0] {
+            global.extend(value.to_le_bytes());
+        }
+        global.extend(9000u16.to_le_bytes());
+
+        let mut stream = vec![0x00, 0x42];
+        stream.extend(encode_frame(0, 1, 1, ATTITUDE, &attitude));
+        stream.extend(encode_frame(1, 1, 1, HEARTBEAT, &[0, 0, 0, 0, 12, 3, 0, 0, 3]));
+        stream.extend(encode_frame(2, 1, 1, LOCAL_POSITION_NED, &local));
+        stream.extend(encode_frame(3, 1, 1, GLOBAL_POSITION_INT, &global));
+
+        // Feed it in awkward pieces
+        let mut decoder = MavlinkDecoder::new();
+        let mut telemetry = Vec::new();
+        for chunk in stream.chunks(7) {
+            telemetry.extend(decoder.push(chunk));
+        }
+        assert_eq!(telemetry.len(), 3);
+
+        let Telemetry::Attitude { time, orientation, rates } = telemetry[0] else {
+            panic!("expected attitude");
+        };
+        assert_eq!(*time.value(), 1.0);
+        // NED yaw of 90° faces east, the ENU identity
+        assert!(orientation.angle().abs() < 1e-6);
+        assert!((rates[0].value() - 0.2).abs() < 1e-7 && rates[1].value().abs() < 1e-12);
+
+        let Telemetry::LocalPosition { position, velocity, .. } = telemetry[1] else {
+            panic!("expected local position");
+        };
+        assert_eq!(position, [20.0, 10.0, -30.0]);
+        assert_eq!(velocity, [0.0, 1.0, 0.0]);
+
+        let Telemetry::GlobalPosition { coordinate, relative_altitude, velocity, heading, .. } = telemetry[2] else {
+            panic!("expected global position");
+        };
+        assert!((coordinate.latitude.value() * 360.0 / TAU - 48.1173).abs() < 1e-9);
+        assert_eq!((*coordinate.altitude.value(), *relative_altitude.value()), (-30.0, -30.0));
+        assert_eq!(velocity, [0.0, 1.0, 0.0]);
+        assert!((heading.unwrap().value() - TAU / 4.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,318 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Triangle meshes for collision checking
+//!
+//! A [`TriangleMesh`] is a triangle soup: no shared vertices or adjacency, just
+//! what distance queries need. [`MeshCollider`] puts the triangles in a
+//! [`Bvh`] for point and capsule distances, and [`TriangleMesh::planar_faces`]
+//! merges coplanar triangles into [`Plane`]s for the CGA queries.
+//!
+//! STL (ASCII and binary) and Wavefront OBJ files are read with the
+//! `mesh-import` feature. Only geometry is imported; normals, texture
+//! coordinates and materials are ignored, and OBJ polygons are split into fans.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use crate::collision::{Bvh, Capsule, Nearest, Shape};
+use crate::linalg::{self, Vector3};
+use crate::motor::Motor;
+use crate::primitives::{Aabb, Plane};
+
+/// Triangles as vertex triples, counter-clockwise seen from outside
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TriangleMesh {
+    pub triangles: Vec<[Vector3; 3]>,
+}
+
+/// Coplanar triangles of a mesh and the plane they lie in
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanarFace {
+    /// Oriented along the triangles' winding
+    pub plane: Plane,
+    /// Indices into [`TriangleMesh::triangles`]
+    pub triangles: Vec<usize>,
+}
+
+impl TriangleMesh {
+    pub fn new(triangles: Vec<[Vector3; 3]>) -> Self {
+        Self { triangles }
+    }
+
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Bounds of all vertices; `None` for an empty mesh
+    pub fn bounds(&self) -> Option<Aabb> {
+        let vertices: Vec<Vector3> = self.triangles.iter().flatten().copied().collect();
+        Aabb::from_points(&vertices)
+    }
+
+    /// The mesh moved by `motor`, e.g. from its model frame into the world
+    pub fn transformed(&self, motor: &Motor) -> Self {
+        Self::new(self.triangles.iter().map(|t| t.map(|v| motor.apply_point(v))).collect())
+    }
+
+    /// Triangles as collision shapes, in mesh order
+    pub fn shapes(&self) -> Vec<Shape> {
+        self.triangles.iter().map(|t| Shape::Triangle(*t)).collect()
+    }
+
+    /// Total surface area
+    pub fn area(&self) -> f64 {
+        self.triangles.iter().map(|&[a, b, c]| 0.5 * linalg::norm(linalg::cross(linalg::sub(b, a), linalg::sub(c, a)))).sum()
+    }
+
+    /// Group triangles lying in the same oriented plane
+    ///
+    /// Two triangles share a face when their unit normals differ by at most
+    /// `tolerance` and their offsets from the origin by at most `tolerance`
+    /// metres. Degenerate (zero-area) triangles are skipped.
+    pub fn planar_faces(&self, tolerance: f64) -> Vec<PlanarFace> {
+        let mut faces: Vec<PlanarFace> = Vec::new();
+        for (index, &[a, b, c]) in self.triangles.iter().enumerate() {
+            let Some(plane) = Plane::from_points(a, b, c) else { continue };
+            let matching = faces.iter_mut().find(|face| {
+                linalg::norm(linalg::sub(face.plane.normal, plane.normal)) <= tolerance
+                    && (face.plane.distance - plane.distance).abs() <= tolerance
+            });
+            match matching {
+                Some(face) => face.triangles.push(index),
+                None => faces.push(PlanarFace { plane, triangles: vec![index] }),
+            }
+        }
+        faces
+    }
+}
+
+/// Distance queries against a mesh
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshCollider {
+    bvh: Bvh,
+}
+
+impl MeshCollider {
+    pub fn new(mesh: &TriangleMesh) -> Self {
+        Self { bvh: Bvh::build(mesh.shapes()) }
+    }
+
+    /// Closest triangle to `point`; `None` for an empty mesh
+    pub fn distance_to_point(&self, point: Vector3) -> Option<Nearest> {
+        self.bvh.nearest(&Capsule::point(point))
+    }
+
+    /// Closest triangle to the capsule surface, negative when it cuts the capsule
+    pub fn distance_to_capsule(&self, capsule: &Capsule) -> Option<Nearest> {
+        self.bvh.nearest(capsule)
+    }
+
+    pub fn bvh(&self) -> &Bvh {
+        &self.bvh
+    }
+}
+
+/// Errors raised while reading mesh files
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshError {
+    Io(String),
+    /// A malformed line of a text format
+    Parse { line: usize, message: String },
+    /// A binary STL shorter than its triangle count requires
+    Truncated { expected: usize, actual: usize },
+    UnknownFormat(String),
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshError::Io(message) => write!(f, "could not read mesh: {}", message),
+            MeshError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            MeshError::Truncated { expected, actual } => {
+                write!(f, "binary STL needs {} bytes, file has {}", expected, actual)
+            }
+            MeshError::UnknownFormat(name) => write!(f, "unknown mesh format for '{}' (expected .stl or .obj)", name),
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+/// Read an `.stl` or `.obj` file, choosing the parser by extension
+#[cfg(feature = "mesh-import")]
+pub fn load(path: impl AsRef<std::path::Path>) -> Result<TriangleMesh, MeshError> {
+    let path = path.as_ref();
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+    let bytes = std::fs::read(path).map_err(|e| MeshError::Io(format!("{}: {}", path.display(), e)))?;
+    match extension.as_deref() {
+        Some("stl") => parse_stl(&bytes),
+        Some("obj") => parse_obj(&String::from_utf8_lossy(&bytes)),
+        _ => Err(MeshError::UnknownFormat(path.display().to_string())),
+    }
+}
+
+/// Parse binary or ASCII STL
+///
+/// Binary headers may also start with `solid`, so a file is only read as text
+/// when its size does not match its binary triangle count and it mentions a facet.
+#[cfg(feature = "mesh-import")]
+pub fn parse_stl(bytes: &[u8]) -> Result<TriangleMesh, MeshError> {
+    let binary_size = (bytes.len() >= 84).then(|| 84 + 50 * u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize);
+    let ascii = bytes.starts_with(b"solid") && binary_size != Some(bytes.len()) && bytes.windows(5).any(|w| w == b"facet");
+    if ascii {
+        parse_ascii_stl(&String::from_utf8_lossy(bytes))
+    } else {
+        parse_binary_stl(bytes)
+    }
+}
+
+#[cfg(feature = "mesh-import")]
+fn parse_binary_stl(bytes: &[u8]) -> Result<TriangleMesh, MeshError> {
+    if bytes.len() < 84 {
+        return Err(MeshError::Truncated { expected: 84, actual: bytes.len() });
+    }
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    let expected = 84 + 50 * count;
+    if bytes.len() < expected {
+        return Err(MeshError::Truncated { expected, actual: bytes.len() });
+    }
+    let float = |offset: usize| f32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]) as f64;
+    let triangles = (0..count)
+        .map(|i| {
+            // Skip the 12-byte facet normal; the vertices follow
+            let base = 84 + 50 * i + 12;
+            std::array::from_fn(|v| std::array::from_fn(|k| float(base + 12 * v + 4 * k)))
+        })
+        .collect();
+    Ok(TriangleMesh::new(triangles))
+}
+
+#[cfg(feature = "mesh-import")]
+fn parse_ascii_stl(text: &str) -> Result<TriangleMesh, MeshError> {
+    let mut vertices = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() == Some("vertex") {
+            vertices.push(parse_vector(tokens, number + 1)?);
+        }
+    }
+    if vertices.len() % 3 != 0 {
+        return Err(MeshError::Parse { line: text.lines().count(), message: "vertex count is not a multiple of 3".into() });
+    }
+    Ok(TriangleMesh::new(vertices.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect()))
+}
+
+/// Parse Wavefront OBJ vertices and faces
+#[cfg(feature = "mesh-import")]
+pub fn parse_obj(text: &str) -> Result<TriangleMesh, MeshError> {
+    let mut vertices: Vec<Vector3> = Vec::new();
+    let mut triangles = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line_number = number + 1;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => vertices.push(parse_vector(tokens, line_number)?),
+            Some("f") => {
+                let corners = tokens
+                    .map(|token| obj_index(token, vertices.len(), line_number).map(|i| vertices[i]))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if corners.len() < 3 {
+                    return Err(MeshError::Parse { line: line_number, message: "face with fewer than 3 vertices".into() });
+                }
+                triangles.extend((1..corners.len() - 1).map(|k| [corners[0], corners[k], corners[k + 1]]));
+            }
+            _ => {}
+        }
+    }
+    Ok(TriangleMesh::new(triangles))
+}
+
+/// Zero-based vertex index of an OBJ face corner (`v`, `v/vt`, `v//vn`, ...);
+/// negative indices count back from the latest vertex
+#[cfg(feature = "mesh-import")]
+fn obj_index(token: &str, count: usize, line: usize) -> Result<usize, MeshError> {
+    let error = |message: String| MeshError::Parse { line, message };
+    let field = token.split('/').next().unwrap_or(token);
+    let index: i64 = field.parse().map_err(|_| error(format!("invalid vertex index '{}'", field)))?;
+    let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+    if (0..count as i64).contains(&resolved) {
+        Ok(resolved as usize)
+    } else {
+        Err(error(format!("vertex index {} out of range (1..={})", index, count)))
+    }
+}
+
+#[cfg(feature = "mesh-import")]
+fn parse_vector<'a>(mut tokens: impl Iterator<Item = &'a str>, line: usize) -> Result<Vector3, MeshError> {
+    let mut v = [0.0; 3];
+    for value in v.iter_mut() {
+        let token = tokens.next().ok_or_else(|| MeshError::Parse { line, message: "expected 3 coordinates".into() })?;
+        *value = token.parse().map_err(|_| MeshError::Parse { line, message: format!("invalid number '{}'", token) })?;
+    }
+    Ok(v)
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit cube as twelve outward-facing triangles
+    fn cube() -> TriangleMesh {
+        let v = |i: usize| [(i & 1) as f64, ((i >> 1) & 1) as f64, ((i >> 2) & 1) as f64];
+        let quads = [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]];
+        TriangleMesh::new(quads.iter().flat_map(|q| [[v(q[0]), v(q[1]), v(q[2])], [v(q[0]), v(q[2]), v(q[3])]]).collect())
+    }
+
+    #[test]
+    fn test_mesh_distances_and_faces() {
+        let mesh = cube();
+        assert!((mesh.area() - 6.0).abs() < 1e-12);
+
+        let faces = mesh.planar_faces(1e-9);
+        assert_eq!(faces.len(), 6);
+        assert!(faces.iter().all(|face| face.triangles.len() == 2));
+        // Winding gives outward normals: the top face is z = 1 facing up
+        let top = faces.iter().find(|face| face.plane.normal[2] > 0.5).unwrap();
+        assert!((top.plane.distance - 1.0).abs() < 1e-12);
+
+        let collider = MeshCollider::new(&mesh.transformed(&Motor::from_translation([0.0, 0.0, -1.0])));
+        let nearest = collider.distance_to_point([0.5, 0.5, 2.0]).unwrap();
+        assert!((nearest.distance.value() - 2.0).abs() < 1e-12);
+
+        let link = Capsule::new([-1.0, 0.5, 0.5], [3.0, 0.5, 0.5], 0.25);
+        assert!((collider.distance_to_capsule(&link).unwrap().distance.value() - 0.25).abs() < 1e-6);
+    }
+
+    #[cfg(feature = "mesh-import")]
+    #[test]
+    fn test_stl_and_obj_import() {
+        let ascii = "solid tri\n facet normal 0 0 1\n  outer loop\n   vertex 0 0 0\n   vertex 1 0 0\n   vertex 0 1 0\n  endloop\n endfacet\nendsolid tri\n";
+        let mesh = parse_stl(ascii.as_bytes()).unwrap();
+        assert_eq!(mesh.triangles, vec![[[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]]);
+
+        // Binary STL with a header that also starts with "solid"
+        let mut binary = b"solid but binary".to_vec();
+        binary.resize(80, 0);
+        binary.extend(1u32.to_le_bytes());
+        for value in [0.0f32, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 2.0, 0.0] {
+            binary.extend(value.to_le_bytes());
+        }
+        binary.extend([0, 0]);
+        assert_eq!(parse_stl(&binary).unwrap().triangles, vec![[[0.0; 3], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]]]);
+        assert_eq!(parse_stl(&binary[..100]), Err(MeshError::Truncated { expected: 134, actual: 100 }));
+
+        // A quad with texture and normal indices, then a relative triangle
+        let obj = "# square\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\nf 1/1/1 2/2/1 3/3/1 4/4/1\nf -4 -3 -1\n";
+        let mesh = parse_obj(obj).unwrap();
+        assert_eq!(mesh.len(), 3);
+        assert!((mesh.area() - 1.5).abs() < 1e-12);
+        assert!(matches!(parse_obj("v 0 0 0\nf 1 2 3\n"), Err(MeshError::Parse { line: 2, .. })));
+    }
+}
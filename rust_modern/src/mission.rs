@@ -0,0 +1,474 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Mission execution as a behavior tree
+//!
+//! A [`MissionDefinition`] is a tree of [`Node`]s loaded with serde. Composite
+//! nodes decide which task runs: a sequence runs its children in order until
+//! one fails, a fallback tries them in order until one succeeds, and a repeat
+//! restarts its child a number of times. Leaves are [`Task`]s: go to a
+//! waypoint, hold a depth, or fly a lawnmower survey.
+//!
+//! [`Mission::tick`] is called once per control cycle with the vehicle position
+//! and returns the [`Setpoint`] for the guidance and control layers. Composite
+//! nodes remember which child is running, so a finished task is never revisited
+//! unless a repeat restarts it. A task with a timeout fails once it has run that
+//! long, which lets a fallback take over.
+//!
+//! Every task start and end is recorded as a [`Transition`] whose canonical line
+//! has a fixed format, so logs of two runs can be compared with `diff`.
+//!
+//! Positions are East-North-Up in meters; depth is measured down from `z = 0`.
+//! Waypoint plans in latitude and longitude compile into definitions through
+//! [`crate::mission_plan`].
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use crate::linalg::{self, Vector3};
+use crate::si_units::{Angle, Length, Time, Velocity};
+
+/// A named tree of tasks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MissionDefinition {
+    pub name: String,
+    pub root: Node,
+}
+
+impl MissionDefinition {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Behavior tree node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Node {
+    /// Children in order; fails as soon as one fails
+    Sequence(Vec<Node>),
+    /// Children in order; succeeds as soon as one succeeds
+    Fallback(Vec<Node>),
+    /// Run the child `times` times, or forever when `None`; fails when it fails
+    Repeat { times: Option<u32>, child: Box<Node> },
+    Task(Task),
+}
+
+/// Leaf of the tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    /// Name used in the transition log
+    pub name: String,
+    #[serde(default)]
+    pub timeout: Option<Time>,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Travel to a point; done within `tolerance`
+    Goto { position: Vector3, speed: Velocity, tolerance: Length },
+    /// Hold the horizontal position where the task started at `depth`; done after
+    /// staying within `tolerance` of it for `duration` without interruption
+    HoldDepth { depth: Length, duration: Time, tolerance: Length },
+    Survey(Lawnmower),
+}
+
+/// Back-and-forth survey pattern
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Lawnmower {
+    /// Start of the first leg
+    pub start: Vector3,
+    /// Direction of the first leg, counter-clockwise from east
+    pub heading: Angle,
+    pub leg_length: Length,
+    /// Distance between legs; later legs lie to the right of the first
+    pub spacing: Length,
+    pub legs: u32,
+    pub speed: Velocity,
+    pub tolerance: Length,
+}
+
+impl Lawnmower {
+    /// Ends of every leg in order, starting with the start of the first
+    pub fn waypoints(&self) -> Vec<Vector3> {
+        let (sin, cos) = self.heading.value().sin_cos();
+        let along = [cos, sin, 0.0];
+        let right = [sin, -cos, 0.0];
+        let mut waypoints = Vec::with_capacity(2 * self.legs as usize);
+        for leg in 0..self.legs {
+            let leg_start = linalg::add(self.start, linalg::scale(right, leg as f64 * self.spacing.value()));
+            let leg_end = linalg::add(leg_start, linalg::scale(along, *self.leg_length.value()));
+            if leg % 2 == 0 {
+                waypoints.extend([leg_start, leg_end]);
+            } else {
+                waypoints.extend([leg_end, leg_start]);
+            }
+        }
+        waypoints
+    }
+}
+
+/// Result of ticking a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Status {
+    Running,
+    Success,
+    Failure,
+}
+
+/// Command for the guidance and control layers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Setpoint {
+    /// Travel toward `position` at `speed`
+    Waypoint { position: Vector3, speed: Velocity },
+    /// Station-keep at `position`
+    Hold { position: Vector3 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskEvent {
+    Started,
+    Succeeded,
+    TimedOut,
+    /// Stopped from outside the tree, e.g. by a safety fault
+    Aborted,
+}
+
+impl fmt::Display for TaskEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TaskEvent::Started => "started",
+            TaskEvent::Succeeded => "succeeded",
+            TaskEvent::TimedOut => "timed_out",
+            TaskEvent::Aborted => "aborted",
+        })
+    }
+}
+
+/// A task starting or ending
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    pub time: Time,
+    pub task: String,
+    pub event: TaskEvent,
+}
+
+impl Transition {
+    /// `"<seconds, 3 decimals> <task> <event>"`
+    pub fn canonical(&self) -> String {
+        format!("{:.3} {} {}", self.time.value(), self.task, self.event)
+    }
+}
+
+/// Running state of a node
+#[derive(Debug, Clone)]
+enum Exec {
+    Sequence { children: Vec<Exec>, current: usize },
+    Fallback { children: Vec<Exec>, current: usize },
+    Repeat { times: Option<u32>, done: u32, child: Box<Exec> },
+    Task { task: Task, progress: Option<Progress> },
+}
+
+/// What a started task has done so far
+#[derive(Debug, Clone)]
+struct Progress {
+    started: f64,
+    /// Survey waypoints, or the single hold point of a depth hold
+    waypoints: Vec<Vector3>,
+    next: usize,
+    /// Since when a depth hold has been within tolerance
+    settled_since: Option<f64>,
+}
+
+struct TickContext<'a> {
+    time: f64,
+    position: Vector3,
+    setpoint: Option<Setpoint>,
+    log: &'a mut Vec<Transition>,
+}
+
+impl Exec {
+    fn new(node: &Node) -> Self {
+        match node {
+            Node::Sequence(children) => Exec::Sequence { children: children.iter().map(Exec::new).collect(), current: 0 },
+            Node::Fallback(children) => Exec::Fallback { children: children.iter().map(Exec::new).collect(), current: 0 },
+            Node::Repeat { times, child } => Exec::Repeat { times: *times, done: 0, child: Box::new(Exec::new(child)) },
+            Node::Task(task) => Exec::Task { task: task.clone(), progress: None },
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Exec::Sequence { children, current } | Exec::Fallback { children, current } => {
+                children.iter_mut().for_each(Exec::reset);
+                *current = 0;
+            }
+            Exec::Repeat { done, child, .. } => {
+                child.reset();
+                *done = 0;
+            }
+            Exec::Task { progress, .. } => *progress = None,
+        }
+    }
+
+    /// Name of the task that has started and not yet finished
+    fn running_task(&self) -> Option<&str> {
+        match self {
+            Exec::Sequence { children, current } | Exec::Fallback { children, current } => {
+                children.get(*current).and_then(Exec::running_task)
+            }
+            Exec::Repeat { child, .. } => child.running_task(),
+            Exec::Task { task, progress } => progress.as_ref().map(|_| task.name.as_str()),
+        }
+    }
+
+    fn tick(&mut self, context: &mut TickContext) -> Status {
+        match self {
+            Exec::Sequence { children, current } => {
+                while let Some(child) = children.get_mut(*current) {
+                    match child.tick(context) {
+                        Status::Success => *current += 1,
+                        status => return status,
+                    }
+                }
+                Status::Success
+            }
+            Exec::Fallback { children, current } => {
+                while let Some(child) = children.get_mut(*current) {
+                    match child.tick(context) {
+                        Status::Failure => *current += 1,
+                        status => return status,
+                    }
+                }
+                Status::Failure
+            }
+            Exec::Repeat { times, done, child } => match child.tick(context) {
+                Status::Success => {
+                    *done += 1;
+                    if times.is_some_and(|times| *done >= times) {
+                        return Status::Success;
+                    }
+                    // Restart on the next tick so an instant child cannot spin
+                    child.reset();
+                    Status::Running
+                }
+                status => status,
+            },
+            Exec::Task { task, progress } => Self::tick_task(task, progress, context),
+        }
+    }
+
+    fn tick_task(task: &Task, progress: &mut Option<Progress>, context: &mut TickContext) -> Status {
+        let (time, position) = (context.time, context.position);
+        let progress = progress.get_or_insert_with(|| {
+            context.log.push(Transition { time: Time::new(time), task: task.name.clone(), event: TaskEvent::Started });
+            let waypoints = match &task.action {
+                Action::Goto { position, .. } => vec![*position],
+                Action::HoldDepth { depth, .. } => vec![[position[0], position[1], -depth.value()]],
+                Action::Survey(pattern) => pattern.waypoints(),
+            };
+            Progress { started: time, waypoints, next: 0, settled_since: None }
+        });
+        let mut finish = |event| {
+            context.log.push(Transition { time: Time::new(time), task: task.name.clone(), event });
+        };
+        if task.timeout.is_some_and(|timeout| time - progress.started >= *timeout.value()) {
+            finish(TaskEvent::TimedOut);
+            return Status::Failure;
+        }
+
+        match &task.action {
+            Action::Goto { speed, tolerance, .. } | Action::Survey(Lawnmower { speed, tolerance, .. }) => {
+                while let Some(waypoint) = progress.waypoints.get(progress.next) {
+                    if linalg::norm(linalg::sub(*waypoint, position)) > *tolerance.value() {
+                        context.setpoint = Some(Setpoint::Waypoint { position: *waypoint, speed: *speed });
+                        return Status::Running;
+                    }
+                    progress.next += 1;
+                }
+                finish(TaskEvent::Succeeded);
+                Status::Success
+            }
+            Action::HoldDepth { duration, tolerance, .. } => {
+                let target = progress.waypoints[0];
+                if (position[2] - target[2]).abs() <= *tolerance.value() {
+                    let since = *progress.settled_since.get_or_insert(time);
+                    if time - since >= *duration.value() {
+                        finish(TaskEvent::Succeeded);
+                        return Status::Success;
+                    }
+                } else {
+                    progress.settled_since = None;
+                }
+                context.setpoint = Some(Setpoint::Hold { position: target });
+                Status::Running
+            }
+        }
+    }
+}
+
+/// Output of one mission tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MissionStep {
+    pub status: Status,
+    /// `None` once the mission has finished
+    pub setpoint: Option<Setpoint>,
+}
+
+/// A mission being executed
+#[derive(Debug, Clone)]
+pub struct Mission {
+    pub name: String,
+    root: Exec,
+    status: Status,
+    log: Vec<Transition>,
+}
+
+impl Mission {
+    pub fn new(definition: &MissionDefinition) -> Self {
+        Self { name: definition.name.clone(), root: Exec::new(&definition.root), status: Status::Running, log: Vec::new() }
+    }
+
+    /// Advance the mission with the vehicle's current position. Once the tree
+    /// has succeeded or failed, later ticks repeat that status without a setpoint.
+    pub fn tick(&mut self, time: Time, position: Vector3) -> MissionStep {
+        if self.status != Status::Running {
+            return MissionStep { status: self.status, setpoint: None };
+        }
+        let mut context = TickContext { time: *time.value(), position, setpoint: None, log: &mut self.log };
+        self.status = self.root.tick(&mut context);
+        let setpoint = if self.status == Status::Running { context.setpoint } else { None };
+        MissionStep { status: self.status, setpoint }
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Fail the mission now, logging the running task as aborted. Later ticks
+    /// return no setpoint; a recovery mission has to be started afresh.
+    pub fn abort(&mut self, time: Time) {
+        if self.status != Status::Running {
+            return;
+        }
+        if let Some(task) = self.root.running_task() {
+            self.log.push(Transition { time, task: task.to_string(), event: TaskEvent::Aborted });
+        }
+        self.status = Status::Failure;
+    }
+
+    pub fn transitions(&self) -> &[Transition] {
+        &self.log
+    }
+
+    /// Canonical lines of every transition so far, one per line
+    pub fn canonical_log(&self) -> String {
+        self.log.iter().map(|t| t.canonical() + "\n").collect()
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Move straight toward the setpoint at its speed, or stay put
+    fn simulate(mission: &mut Mission, mut position: Vector3, dt: f64, steps: usize) -> Vector3 {
+        for step in 0..steps {
+            let output = mission.tick(Time::new(step as f64 * dt), position);
+            let target = match output.setpoint {
+                Some(Setpoint::Waypoint { position, .. }) | Some(Setpoint::Hold { position }) => position,
+                None => break,
+            };
+            let offset = linalg::sub(target, position);
+            let distance = linalg::norm(offset);
+            let reach = match output.setpoint {
+                Some(Setpoint::Waypoint { speed, .. }) => speed.value() * dt,
+                _ => 0.5 * dt,
+            };
+            position = if distance <= reach { target } else { linalg::add(position, linalg::scale(offset, reach / distance)) };
+        }
+        position
+    }
+
+    #[test]
+    fn test_lawnmower_waypoints() {
+        let pattern = Lawnmower {
+            start: [0.0, 0.0, -5.0],
+            heading: Angle::new(0.0),
+            leg_length: Length::new(10.0),
+            spacing: Length::new(2.0),
+            legs: 3,
+            speed: Velocity::new(1.0),
+            tolerance: Length::new(0.1),
+        };
+        let expected = [[0.0, 0.0], [10.0, 0.0], [10.0, -2.0], [0.0, -2.0], [0.0, -4.0], [10.0, -4.0]];
+        let waypoints = pattern.waypoints();
+        assert_eq!(waypoints.len(), 6);
+        for (w, e) in waypoints.iter().zip(expected) {
+            assert!((w[0] - e[0]).abs() < 1e-12 && (w[1] - e[1]).abs() < 1e-12 && w[2] == -5.0);
+        }
+    }
+
+    #[test]
+    fn test_mission_from_json() {
+        let json = r#"{
+            "name": "survey",
+            "root": {"sequence": [
+                {"task": {"name": "dive", "action": {"hold_depth": {
+                    "depth": 2.0, "duration": 1.0, "tolerance": 0.1}}}},
+                {"task": {"name": "transit", "action": {"goto": {
+                    "position": [4.0, 0.0, -2.0], "speed": 2.0, "tolerance": 0.05}}}},
+                {"task": {"name": "lanes", "action": {"survey": {
+                    "start": [4.0, 0.0, -2.0], "heading": 0.0, "leg_length": 4.0,
+                    "spacing": 1.0, "legs": 2, "speed": 2.0, "tolerance": 0.05}}}}
+            ]}
+        }"#;
+        let definition = MissionDefinition::from_json(json).unwrap();
+        let mut mission = Mission::new(&definition);
+
+        let end = simulate(&mut mission, [0.0, 0.0, 0.0], 0.5, 100);
+        assert_eq!(mission.status(), Status::Success);
+        assert_eq!(end, [4.0, -1.0, -2.0]);
+        assert_eq!(mission.tick(Time::new(100.0), end), MissionStep { status: Status::Success, setpoint: None });
+        // Dive 2 m at 0.5 m/s, settle 1 s, transit 4 m and survey 9 m at 2 m/s
+        assert_eq!(
+            mission.canonical_log(),
+            "0.000 dive started\n5.000 dive succeeded\n5.000 transit started\n7.000 transit succeeded\n\
+             7.000 lanes started\n11.500 lanes succeeded\n"
+        );
+    }
+
+    #[test]
+    fn test_timeout_falls_back() {
+        let goto = |name: &str, x: f64, timeout: Option<f64>| {
+            Node::Task(Task {
+                name: name.into(),
+                timeout: timeout.map(Time::new),
+                action: Action::Goto { position: [x, 0.0, 0.0], speed: Velocity::new(1.0), tolerance: Length::new(0.01) },
+            })
+        };
+        let definition = MissionDefinition {
+            name: "abort".into(),
+            root: Node::Fallback(vec![goto("far", 100.0, Some(3.0)), goto("home", 0.0, None)]),
+        };
+        let mut mission = Mission::new(&definition);
+        simulate(&mut mission, [0.0; 3], 1.0, 20);
+        assert_eq!(mission.status(), Status::Success);
+        let events: Vec<_> = mission.transitions().iter().map(|t| (t.task.as_str(), t.event)).collect();
+        assert_eq!(
+            events,
+            [("far", TaskEvent::Started), ("far", TaskEvent::TimedOut), ("home", TaskEvent::Started), ("home", TaskEvent::Succeeded)]
+        );
+
+        // A repeat restarts its child on the tick after each success
+        let definition = MissionDefinition { name: "twice".into(), root: Node::Repeat { times: Some(2), child: Box::new(goto("stay", 0.0, None)) } };
+        let mut mission = Mission::new(&definition);
+        assert_eq!(mission.tick(Time::new(0.0), [0.0; 3]).status, Status::Running);
+        assert_eq!(mission.tick(Time::new(1.0), [0.0; 3]).status, Status::Success);
+        assert_eq!(mission.transitions().len(), 4);
+    }
+}
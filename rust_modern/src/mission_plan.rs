@@ -0,0 +1,398 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Waypoint mission plans: file format, validation and compilation
+//!
+//! A [`MissionPlan`] is the file a planner exchanges with the vehicle: named
+//! waypoints in latitude, longitude (degrees) and depth, with optional speeds,
+//! tolerances, timeouts and an action per waypoint. The JSON schema is
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "name": "harbour survey",
+//!   "defaults": {"speed": 1.0, "tolerance": 2.0},          // m/s, m
+//!   "waypoints": [
+//!     {"name": "entry", "latitude": 43.1, "longitude": 5.9, "depth": 3.0,
+//!      "speed": 0.8, "tolerance": 1.0, "timeout": 600.0,   // optional; s
+//!      "action": "pass"},                                  // default
+//!     {"latitude": 43.1, "longitude": 5.91, "depth": 5.0,
+//!      "action": {"hold": {"duration": 30.0}}},
+//!     {"latitude": 43.1, "longitude": 5.92, "depth": 5.0,
+//!      "action": {"survey": {"bearing": 90.0, "leg_length": 50.0,
+//!                            "spacing": 5.0, "legs": 6}}}   // bearing in degrees from north
+//!   ]
+//! }
+//! ```
+//!
+//! Quantities are bare SI numbers. The types only derive serde, so YAML or any
+//! other serde format reads the same schema with the matching crate.
+//!
+//! [`MissionPlan::validate`] checks the plan against typed [`PlanLimits`],
+//! reporting errors that make it unusable and warnings about legs the vehicle
+//! may not complete. [`MissionPlan::compile`] turns a valid plan into a
+//! [`MissionDefinition`] in the East-North-Up frame of a [`LocalTangentPlane`]
+//! at the first waypoint.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geodesy::{GeodeticCoordinate, LocalTangentPlane};
+use crate::linalg::{self, Vector3};
+use crate::mission::{Action, Lawnmower, MissionDefinition, Node, Task};
+use crate::si_units::{Angle, Length, Time, Velocity, TAU};
+
+/// Schema version written by this crate
+pub const PLAN_VERSION: u32 = 1;
+
+/// Serialized mission plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MissionPlan {
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub defaults: PlanDefaults,
+    pub waypoints: Vec<PlanWaypoint>,
+}
+
+/// Values used where a waypoint leaves them out
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlanDefaults {
+    pub speed: Velocity,
+    pub tolerance: Length,
+}
+
+impl Default for PlanDefaults {
+    fn default() -> Self {
+        Self { speed: Velocity::new(1.0), tolerance: Length::new(2.0) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanWaypoint {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Degrees north
+    pub latitude: f64,
+    /// Degrees east
+    pub longitude: f64,
+    pub depth: Length,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed: Option<Velocity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<Length>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Time>,
+    #[serde(default)]
+    pub action: WaypointAction,
+}
+
+/// What the vehicle does on reaching a waypoint
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaypointAction {
+    /// Continue to the next waypoint
+    #[default]
+    Pass,
+    /// Hold depth at the waypoint for `duration`
+    Hold { duration: Time },
+    /// Lawnmower survey starting at the waypoint; `bearing` of the first leg in
+    /// degrees clockwise from north, later legs to its right
+    Survey { bearing: f64, leg_length: Length, spacing: Length, legs: u32 },
+}
+
+/// Vehicle limits a plan is checked against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlanLimits {
+    pub max_depth: Length,
+    pub max_speed: Velocity,
+    /// Longest leg between consecutive waypoints, e.g. the acoustic tracking range
+    pub max_leg: Length,
+    /// Total path length the vehicle's energy allows, if known
+    #[serde(default)]
+    pub range: Option<Length>,
+}
+
+/// Problem found while validating a plan
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanIssue {
+    UnsupportedVersion(u32),
+    NoWaypoints,
+    /// Latitude or longitude out of range or not finite
+    InvalidCoordinate { waypoint: usize },
+    DepthOutOfRange { waypoint: usize, depth: Length },
+    SpeedOutOfRange { waypoint: usize, speed: Velocity },
+    NonPositiveTolerance { waypoint: usize },
+    /// Survey with no legs or non-positive dimensions
+    InvalidSurvey { waypoint: usize },
+    /// Warning: the leg into `waypoint` is longer than the limit
+    LongLeg { waypoint: usize, length: Length },
+    /// Warning: the leg into `waypoint` takes longer than its timeout at its speed
+    TimeoutTooShort { waypoint: usize, needed: Time },
+    /// Warning: the whole path is longer than the vehicle's range
+    ExceedsRange { length: Length },
+}
+
+impl PlanIssue {
+    /// Whether the plan cannot be executed; other issues are warnings
+    pub fn is_error(&self) -> bool {
+        !matches!(self, PlanIssue::LongLeg { .. } | PlanIssue::TimeoutTooShort { .. } | PlanIssue::ExceedsRange { .. })
+    }
+}
+
+impl fmt::Display for PlanIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanIssue::UnsupportedVersion(version) => {
+                write!(f, "plan version {} is not supported (expected {})", version, PLAN_VERSION)
+            }
+            PlanIssue::NoWaypoints => write!(f, "plan has no waypoints"),
+            PlanIssue::InvalidCoordinate { waypoint } => write!(f, "waypoint {}: invalid latitude or longitude", waypoint),
+            PlanIssue::DepthOutOfRange { waypoint, depth } => {
+                write!(f, "waypoint {}: depth {} m outside the vehicle's range", waypoint, depth.value())
+            }
+            PlanIssue::SpeedOutOfRange { waypoint, speed } => {
+                write!(f, "waypoint {}: speed {} m/s outside the vehicle's range", waypoint, speed.value())
+            }
+            PlanIssue::NonPositiveTolerance { waypoint } => write!(f, "waypoint {}: tolerance must be positive", waypoint),
+            PlanIssue::InvalidSurvey { waypoint } => write!(f, "waypoint {}: survey needs legs of positive size", waypoint),
+            PlanIssue::LongLeg { waypoint, length } => {
+                write!(f, "waypoint {}: {:.1} m leg exceeds the longest allowed", waypoint, length.value())
+            }
+            PlanIssue::TimeoutTooShort { waypoint, needed } => {
+                write!(f, "waypoint {}: leg needs {:.1} s, longer than its timeout", waypoint, needed.value())
+            }
+            PlanIssue::ExceedsRange { length } => write!(f, "{:.1} m path exceeds the vehicle's range", length.value()),
+        }
+    }
+}
+
+/// Errors raised while loading or compiling a plan
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanError {
+    Parse(String),
+    /// Validation found errors; warnings found alongside are included
+    Invalid(Vec<PlanIssue>),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::Parse(e) => write!(f, "parsing plan: {}", e),
+            PlanError::Invalid(issues) => {
+                let errors: Vec<String> = issues.iter().filter(|i| i.is_error()).map(|i| i.to_string()).collect();
+                write!(f, "invalid plan: {}", errors.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+/// Strictly positive and not NaN
+fn positive(value: f64) -> bool {
+    value > 0.0
+}
+
+impl MissionPlan {
+    pub fn new(name: &str, waypoints: Vec<PlanWaypoint>) -> Self {
+        Self { version: PLAN_VERSION, name: name.into(), defaults: PlanDefaults::default(), waypoints }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, PlanError> {
+        serde_json::from_str(json).map_err(|e| PlanError::Parse(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("plans always serialize")
+    }
+
+    fn speed(&self, waypoint: &PlanWaypoint) -> Velocity {
+        waypoint.speed.unwrap_or(self.defaults.speed)
+    }
+
+    fn tolerance(&self, waypoint: &PlanWaypoint) -> Length {
+        waypoint.tolerance.unwrap_or(self.defaults.tolerance)
+    }
+
+    /// Local frame of the plan, at the surface above the first waypoint
+    pub fn frame(&self) -> Option<LocalTangentPlane> {
+        let first = self.waypoints.first()?;
+        Some(LocalTangentPlane::new(GeodeticCoordinate::from_degrees(first.latitude, first.longitude, Length::new(0.0))))
+    }
+
+    /// Waypoint positions in the plan's frame, `z` up from the surface
+    fn positions(&self, frame: &LocalTangentPlane) -> Vec<Vector3> {
+        self.waypoints
+            .iter()
+            .map(|w| {
+                let surface = GeodeticCoordinate::from_degrees(w.latitude, w.longitude, Length::new(0.0));
+                let enu = frame.from_geodetic(&surface);
+                [*enu.east.value(), *enu.north.value(), -w.depth.value()]
+            })
+            .collect()
+    }
+
+    /// Every issue found, errors and warnings, in waypoint order
+    pub fn validate(&self, limits: &PlanLimits) -> Vec<PlanIssue> {
+        let mut issues = Vec::new();
+        if self.version != PLAN_VERSION {
+            issues.push(PlanIssue::UnsupportedVersion(self.version));
+        }
+        if self.waypoints.is_empty() {
+            issues.push(PlanIssue::NoWaypoints);
+            return issues;
+        }
+        for (index, waypoint) in self.waypoints.iter().enumerate() {
+            if !(waypoint.latitude.abs() <= 90.0 && waypoint.longitude.abs() <= 180.0) {
+                issues.push(PlanIssue::InvalidCoordinate { waypoint: index });
+            }
+            let depth = *waypoint.depth.value();
+            if !(0.0..=*limits.max_depth.value()).contains(&depth) {
+                issues.push(PlanIssue::DepthOutOfRange { waypoint: index, depth: waypoint.depth });
+            }
+            let speed = self.speed(waypoint);
+            if !(positive(*speed.value()) && speed.value() <= limits.max_speed.value()) {
+                issues.push(PlanIssue::SpeedOutOfRange { waypoint: index, speed });
+            }
+            if !positive(*self.tolerance(waypoint).value()) {
+                issues.push(PlanIssue::NonPositiveTolerance { waypoint: index });
+            }
+            if let WaypointAction::Survey { leg_length, spacing, legs, .. } = waypoint.action {
+                if legs == 0 || !positive(*leg_length.value()) || !positive(*spacing.value()) {
+                    issues.push(PlanIssue::InvalidSurvey { waypoint: index });
+                }
+            }
+        }
+        if issues.iter().any(PlanIssue::is_error) {
+            return issues;
+        }
+
+        let frame = self.frame().expect("plan has waypoints");
+        let positions = self.positions(&frame);
+        let mut total = 0.0;
+        for (index, waypoint) in self.waypoints.iter().enumerate() {
+            let leg = match index {
+                0 => 0.0,
+                _ => linalg::norm(linalg::sub(positions[index], positions[index - 1])),
+            };
+            total += leg;
+            if leg > *limits.max_leg.value() {
+                issues.push(PlanIssue::LongLeg { waypoint: index, length: Length::new(leg) });
+            }
+            let needed = leg / self.speed(waypoint).value();
+            if waypoint.timeout.is_some_and(|timeout| needed > *timeout.value()) {
+                issues.push(PlanIssue::TimeoutTooShort { waypoint: index, needed: Time::new(needed) });
+            }
+            if let WaypointAction::Survey { leg_length, spacing, legs, .. } = waypoint.action {
+                total += legs as f64 * leg_length.value() + (legs - 1) as f64 * spacing.value();
+            }
+        }
+        if limits.range.is_some_and(|range| total > *range.value()) {
+            issues.push(PlanIssue::ExceedsRange { length: Length::new(total) });
+        }
+        issues
+    }
+
+    /// Behavior tree visiting the waypoints in order, with the frame it is
+    /// expressed in and any warnings
+    pub fn compile(&self, limits: &PlanLimits) -> Result<(MissionDefinition, LocalTangentPlane, Vec<PlanIssue>), PlanError> {
+        let issues = self.validate(limits);
+        if issues.iter().any(PlanIssue::is_error) {
+            return Err(PlanError::Invalid(issues));
+        }
+        let frame = self.frame().expect("validated plans have waypoints");
+        let positions = self.positions(&frame);
+        let mut tasks = Vec::new();
+        for (index, (waypoint, position)) in self.waypoints.iter().zip(positions).enumerate() {
+            let name = waypoint.name.clone().unwrap_or_else(|| format!("waypoint {}", index));
+            let (speed, tolerance) = (self.speed(waypoint), self.tolerance(waypoint));
+            let task = |name: String, action: Action| Node::Task(Task { name, timeout: waypoint.timeout, action });
+            match waypoint.action {
+                WaypointAction::Pass => tasks.push(task(name, Action::Goto { position, speed, tolerance })),
+                WaypointAction::Hold { duration } => {
+                    tasks.push(task(name.clone(), Action::Goto { position, speed, tolerance }));
+                    tasks.push(task(format!("{} hold", name), Action::HoldDepth { depth: waypoint.depth, duration, tolerance }));
+                }
+                WaypointAction::Survey { bearing, leg_length, spacing, legs } => {
+                    let heading = Angle::new(TAU / 4.0 - bearing * TAU / 360.0);
+                    let pattern = Lawnmower { start: position, heading, leg_length, spacing, legs, speed, tolerance };
+                    tasks.push(task(name, Action::Survey(pattern)));
+                }
+            }
+        }
+        Ok((MissionDefinition { name: self.name.clone(), root: Node::Sequence(tasks) }, frame, issues))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mission::{Mission, Setpoint, Status};
+
+    const PLAN: &str = r#"{
+        "version": 1,
+        "name": "harbour",
+        "defaults": {"speed": 1.5, "tolerance": 1.0},
+        "waypoints": [
+            {"name": "entry", "latitude": 43.1, "longitude": 5.9, "depth": 2.0},
+            {"latitude": 43.1005, "longitude": 5.9, "depth": 5.0, "timeout": 20.0,
+             "action": {"hold": {"duration": 30.0}}},
+            {"latitude": 43.1005, "longitude": 5.9005, "depth": 5.0,
+             "action": {"survey": {"bearing": 90.0, "leg_length": 40.0, "spacing": 5.0, "legs": 4}}}
+        ]
+    }"#;
+
+    fn limits() -> PlanLimits {
+        PlanLimits { max_depth: Length::new(100.0), max_speed: Velocity::new(2.0), max_leg: Length::new(50.0), range: None }
+    }
+
+    #[test]
+    fn test_round_trip_and_validation() {
+        let plan = MissionPlan::from_json(PLAN).unwrap();
+        assert_eq!(MissionPlan::from_json(&plan.to_json()).unwrap(), plan);
+        assert_eq!(plan.waypoints[0].action, WaypointAction::Pass);
+
+        // 0.0005° of latitude is about 55.5 m, covered in 37 s at 1.5 m/s
+        let issues = plan.validate(&limits());
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert!(matches!(issues[0], PlanIssue::LongLeg { waypoint: 1, length } if (length.value() - 55.5).abs() < 0.5));
+        assert!(matches!(issues[1], PlanIssue::TimeoutTooShort { waypoint: 1, .. }));
+        assert!(!issues.iter().any(PlanIssue::is_error));
+        let limited = PlanLimits { range: Some(Length::new(100.0)), ..limits() };
+        assert!(matches!(plan.validate(&limited).last(), Some(PlanIssue::ExceedsRange { .. })));
+
+        let mut bad = plan.clone();
+        bad.waypoints[2].depth = Length::new(150.0);
+        bad.waypoints[0].speed = Some(Velocity::new(3.0));
+        let error = bad.compile(&limits()).unwrap_err();
+        let PlanError::Invalid(issues) = &error else { panic!("{}", error) };
+        assert_eq!(issues.iter().filter(|i| i.is_error()).count(), 2);
+        assert!(error.to_string().contains("waypoint 2: depth 150 m"));
+        assert!(matches!(MissionPlan::from_json("{}"), Err(PlanError::Parse(_))));
+    }
+
+    #[test]
+    fn test_compiled_mission_runs() {
+        let plan = MissionPlan::from_json(PLAN).unwrap();
+        let (definition, frame, _) = plan.compile(&limits()).unwrap();
+        assert!((frame.origin().latitude.value() - 43.1 * TAU / 360.0).abs() < 1e-12);
+        let Node::Sequence(tasks) = &definition.root else { panic!() };
+        assert_eq!(tasks.len(), 4);
+        let Node::Task(Task { action: Action::Survey(pattern), .. }) = &tasks[3] else { panic!() };
+        // Bearing 90° is due east, counter-clockwise angle zero
+        assert!(pattern.heading.value().abs() < 1e-12);
+
+        let mut mission = Mission::new(&definition);
+        let step = mission.tick(Time::new(0.0), [0.0, 0.0, -2.0]);
+        assert_eq!(step.status, Status::Running);
+        let Some(Setpoint::Waypoint { position, speed }) = mission.tick(Time::new(0.1), [0.0, 0.0, -2.0]).setpoint else {
+            panic!()
+        };
+        assert!(position[1] > 55.0 && position[2] == -5.0 && *speed.value() == 1.5);
+        assert_eq!(mission.transitions().last().unwrap().task, "waypoint 1");
+    }
+}
@@ -0,0 +1,738 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Rigid-body motions: rotors, translators and motors
+//!
+//! Rotors use the GAFRO blade layout (scalar, e23, e13, e12) and the convention
+//! `R = cos(θ/2) - sin(θ/2) B`. A motor is the product `M = T R`, stored as its
+//! rotor and the translation of its translator, and maps a point `p` to
+//! `R p R̃ + t`.
+
+use serde::{Deserialize, Serialize};
+use crate::determinism::settle;
+use crate::linalg::{self, square, Matrix3, Vector3};
+use crate::primitives::Line;
+use crate::si_units::{Angle, Length};
+
+/// Bivector generator of a rotor (rotation plane scaled by the angle)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Bivector {
+    pub e23: f64,
+    pub e13: f64,
+    pub e12: f64,
+}
+
+impl Bivector {
+    pub fn new(e23: f64, e13: f64, e12: f64) -> Self {
+        Self { e23, e13, e12 }
+    }
+
+    /// Bivector dual to a rotation vector (axis scaled by angle)
+    pub fn from_rotation_vector(v: Vector3) -> Self {
+        Self::new(v[0], -v[1], v[2])
+    }
+
+    /// Rotation vector dual to this bivector
+    pub fn rotation_vector(&self) -> Vector3 {
+        [self.e23, -self.e13, self.e12]
+    }
+
+    pub fn norm(&self) -> f64 {
+        linalg::norm(self.rotation_vector())
+    }
+}
+
+/// Rotor (even element of grades 0 and 2) representing a rotation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rotor {
+    pub scalar: f64,
+    pub e23: f64,
+    pub e13: f64,
+    pub e12: f64,
+}
+
+impl Default for Rotor {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Rotor {
+    pub fn new(scalar: f64, e23: f64, e13: f64, e12: f64) -> Self {
+        Self { scalar, e23, e13, e12 }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Rotor for a right-handed rotation of `angle` radians about `axis`
+    pub fn from_axis_angle(axis: Vector3, angle: f64) -> Self {
+        let n = linalg::norm(axis);
+        if n == 0.0 {
+            return Self::identity();
+        }
+        Self::from_rotation_vector(linalg::scale(axis, angle / n))
+    }
+
+    /// Exponential map of a rotation vector (axis scaled by angle)
+    pub fn from_rotation_vector(v: Vector3) -> Self {
+        let theta = linalg::norm(v);
+        let half = 0.5 * theta;
+        // sin(θ/2)/θ, expanded near zero
+        let k = if theta < 1e-8 { 0.5 - theta * theta / 48.0 } else { half.sin() / theta };
+        settle(Self::from_quaternion([half.cos(), k * v[0], k * v[1], k * v[2]]))
+    }
+
+    /// Exponential map `exp(-B/2)` of a bivector generator
+    pub fn exp(generator: Bivector) -> Self {
+        Self::from_rotation_vector(generator.rotation_vector())
+    }
+
+    /// Logarithm of the rotor as a rotation vector, taking the shortest rotation
+    pub fn rotation_vector(&self) -> Vector3 {
+        let [mut w, mut x, mut y, mut z] = self.to_quaternion();
+        if w < 0.0 {
+            w = -w;
+            x = -x;
+            y = -y;
+            z = -z;
+        }
+        let s = (x * x + y * y + z * z).sqrt();
+        let theta = 2.0 * s.atan2(w);
+        // θ / sin(θ/2), expanded near zero
+        let k = if s < 1e-8 { 2.0 / w } else { theta / s };
+        settle([k * x, k * y, k * z])
+    }
+
+    /// Logarithm of the rotor as a bivector generator
+    pub fn log(&self) -> Bivector {
+        Bivector::from_rotation_vector(self.rotation_vector())
+    }
+
+    /// Rotation angle in radians, in [0, τ/2]
+    pub fn angle(&self) -> f64 {
+        linalg::norm(self.rotation_vector())
+    }
+
+    /// Quaternion `[w, x, y, z]` of the same rotation
+    pub fn to_quaternion(&self) -> [f64; 4] {
+        [self.scalar, -self.e23, self.e13, -self.e12]
+    }
+
+    /// Rotor from a quaternion `[w, x, y, z]`
+    pub fn from_quaternion(q: [f64; 4]) -> Self {
+        Self::new(q[0], -q[1], q[2], -q[3])
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::new(self.scalar, -self.e23, -self.e13, -self.e12)
+    }
+
+    pub fn norm(&self) -> f64 {
+        (self.scalar * self.scalar + self.e23 * self.e23 + self.e13 * self.e13 + self.e12 * self.e12).sqrt()
+    }
+
+    pub fn normalized(&self) -> Self {
+        let n = self.norm();
+        settle(Self::new(self.scalar / n, self.e23 / n, self.e13 / n, self.e12 / n))
+    }
+
+    /// Sandwich product `R v R̃` on a Euclidean vector
+    pub fn apply(&self, v: Vector3) -> Vector3 {
+        let [w, x, y, z] = self.to_quaternion();
+        let u = [x, y, z];
+        let t = linalg::scale(linalg::cross(u, v), 2.0);
+        linalg::add(linalg::add(v, linalg::scale(t, w)), linalg::cross(u, t))
+    }
+
+    pub fn to_rotation_matrix(&self) -> Matrix3 {
+        let [w, x, y, z] = self.to_quaternion();
+        [
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+        ]
+    }
+
+    /// Rotor from a proper rotation matrix
+    pub fn from_rotation_matrix(m: &Matrix3) -> Self {
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let q = if trace > 0.0 {
+            let s = 2.0 * (trace + 1.0).sqrt();
+            [0.25 * s, (m[2][1] - m[1][2]) / s, (m[0][2] - m[2][0]) / s, (m[1][0] - m[0][1]) / s]
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = 2.0 * (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt();
+            [(m[2][1] - m[1][2]) / s, 0.25 * s, (m[0][1] + m[1][0]) / s, (m[0][2] + m[2][0]) / s]
+        } else if m[1][1] > m[2][2] {
+            let s = 2.0 * (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt();
+            [(m[0][2] - m[2][0]) / s, (m[0][1] + m[1][0]) / s, 0.25 * s, (m[1][2] + m[2][1]) / s]
+        } else {
+            let s = 2.0 * (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt();
+            [(m[1][0] - m[0][1]) / s, (m[0][2] + m[2][0]) / s, (m[1][2] + m[2][1]) / s, 0.25 * s]
+        };
+        Self::from_quaternion(q).normalized()
+    }
+
+    /// Distance to the nearest unit rotor, `|‖R‖ - 1|`
+    pub fn manifold_distance(&self) -> f64 {
+        (self.norm() - 1.0).abs()
+    }
+
+    pub fn health(&self) -> VersorHealth {
+        let m = self.to_rotation_matrix();
+        let gram = linalg::mat3_mul(&linalg::mat3_transpose(&m), &m);
+        let orthogonality_error = gram
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, value)| value - if i == j { 1.0 } else { 0.0 }))
+            .map(|value| value * value)
+            .sum::<f64>()
+            .sqrt();
+        // Singular values of the matrix are the square roots of the Gram eigenvalues
+        let (values, _) = square::symmetric_eigen(&gram);
+        VersorHealth {
+            norm: self.norm(),
+            manifold_distance: self.manifold_distance(),
+            orthogonality_error,
+            condition_number: (values[2] / values[0]).sqrt(),
+        }
+    }
+
+    /// Project back onto the unit rotors after accumulated drift
+    pub fn renormalize(&mut self) {
+        *self = self.normalized();
+    }
+
+    /// Spherical interpolation from `self` (t = 0) to `other` (t = 1)
+    pub fn slerp(&self, other: &Rotor, t: f64) -> Rotor {
+        let delta = (self.reverse() * *other).rotation_vector();
+        *self * Rotor::from_rotation_vector(linalg::scale(delta, t))
+    }
+}
+
+impl std::ops::Mul for Rotor {
+    type Output = Rotor;
+
+    fn mul(self, rhs: Rotor) -> Rotor {
+        let [w1, x1, y1, z1] = self.to_quaternion();
+        let [w2, x2, y2, z2] = rhs.to_quaternion();
+        let product = Rotor::from_quaternion([
+            w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2,
+            w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2,
+            w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2,
+            w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+        ]);
+        if cfg!(all(debug_assertions, feature = "versor-drift-check")) {
+            warn_on_drift(&product);
+        }
+        product
+    }
+}
+
+/// Normalization drift beyond which a composed versor counts as unhealthy
+pub const DRIFT_TOLERANCE: f64 = 1e-9;
+
+/// Numerical health of a rotor or the rotational part of a motor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VersorHealth {
+    pub norm: f64,
+    /// Distance to the versor manifold, `|‖R‖ - 1|`
+    pub manifold_distance: f64,
+    /// Frobenius norm of `MᵀM - I` for the rotation matrix `M`
+    pub orthogonality_error: f64,
+    /// Ratio of largest to smallest singular value of the rotation matrix
+    pub condition_number: f64,
+}
+
+impl VersorHealth {
+    pub fn is_healthy(&self, tolerance: f64) -> bool {
+        self.manifold_distance <= tolerance && self.orthogonality_error <= tolerance
+    }
+}
+
+/// Report the first composition that drifts past [`DRIFT_TOLERANCE`]
+fn warn_on_drift(rotor: &Rotor) {
+    static WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    let drift = rotor.manifold_distance();
+    if drift > DRIFT_TOLERANCE && !WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+        eprintln!(
+            "Warning: rotor composition drifted {:.3e} from unit norm (tolerance {:.0e}); call renormalize()",
+            drift, DRIFT_TOLERANCE
+        );
+    }
+}
+
+/// Iterations after which [`average_rotors`] returns its current estimate
+pub const AVERAGING_MAX_ITERATIONS: usize = 64;
+
+/// Tangent step below which [`average_rotors`] has converged
+pub const AVERAGING_TOLERANCE: f64 = 1e-12;
+
+/// Errors raised when averaging rotors
+#[derive(Debug, Clone, PartialEq)]
+pub enum AveragingError {
+    /// There is nothing to average
+    Empty,
+    /// The number of weights differs from the number of rotors
+    WeightCount { rotors: usize, weights: usize },
+    /// Weights must be finite, non-negative and not all zero
+    InvalidWeights,
+}
+
+impl std::fmt::Display for AveragingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AveragingError::Empty => write!(f, "cannot average an empty set of rotors"),
+            AveragingError::WeightCount { rotors, weights } => {
+                write!(f, "{} weights given for {} rotors", weights, rotors)
+            }
+            AveragingError::InvalidWeights => write!(f, "weights must be finite, non-negative and not all zero"),
+        }
+    }
+}
+
+impl std::error::Error for AveragingError {}
+
+/// Weighted intrinsic (Karcher) mean of rotors
+///
+/// Averages the logarithms of the rotors relative to the current estimate and
+/// moves the estimate along the mean tangent step until it stops changing. Sign
+/// ambiguity (`R` and `-R` are the same rotation) is handled by taking the
+/// shortest rotation, so unlike averaging angles or quaternion components this
+/// is correct across the ±τ/2 wrap. `None` weighs all rotors equally.
+pub fn average_rotors(rotors: &[Rotor], weights: Option<&[f64]>) -> Result<Rotor, AveragingError> {
+    if rotors.is_empty() {
+        return Err(AveragingError::Empty);
+    }
+    let uniform = vec![1.0; rotors.len()];
+    let weights = weights.unwrap_or(&uniform);
+    if weights.len() != rotors.len() {
+        return Err(AveragingError::WeightCount { rotors: rotors.len(), weights: weights.len() });
+    }
+    let total: f64 = weights.iter().sum();
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) || total <= 0.0 {
+        return Err(AveragingError::InvalidWeights);
+    }
+
+    // Start from the heaviest rotor so the first step stays inside the cluster
+    let heaviest = (0..rotors.len()).fold(0, |best, i| if weights[i] > weights[best] { i } else { best });
+    let mut mean = rotors[heaviest].normalized();
+    for _ in 0..AVERAGING_MAX_ITERATIONS {
+        let step = rotors.iter().zip(weights).fold([0.0; 3], |step, (rotor, weight)| {
+            linalg::add(step, linalg::scale((mean.reverse() * *rotor).rotation_vector(), weight / total))
+        });
+        mean = (mean * Rotor::from_rotation_vector(step)).normalized();
+        if linalg::norm(step) < AVERAGING_TOLERANCE {
+            break;
+        }
+    }
+    Ok(mean)
+}
+
+/// Translator `T = 1 - ½ t e∞`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Translator {
+    pub translation: Vector3,
+}
+
+impl Translator {
+    pub fn new(translation: Vector3) -> Self {
+        Self { translation }
+    }
+
+    /// Coefficients of the e1∞, e2∞, e3∞ blades
+    pub fn blades(&self) -> Vector3 {
+        linalg::scale(self.translation, -0.5)
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::new(linalg::scale(self.translation, -1.0))
+    }
+}
+
+/// Composition `T R` of pure translator and rotor parts
+impl std::ops::Mul<Rotor> for Translator {
+    type Output = Motor;
+
+    fn mul(self, rhs: Rotor) -> Motor {
+        Motor::new(self.translation, rhs)
+    }
+}
+
+/// Composition `R T`: translate first, then rotate
+impl std::ops::Mul<Translator> for Rotor {
+    type Output = Motor;
+
+    fn mul(self, rhs: Translator) -> Motor {
+        Motor::new(self.apply(rhs.translation), self)
+    }
+}
+
+/// Motor generator in twist coordinates: rotation vector followed by translation part
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct MotorGenerator {
+    pub rotation: Vector3,
+    pub translation: Vector3,
+}
+
+impl MotorGenerator {
+    pub fn new(rotation: Vector3, translation: Vector3) -> Self {
+        Self { rotation, translation }
+    }
+
+    pub fn to_array(&self) -> [f64; 6] {
+        let (r, t) = (self.rotation, self.translation);
+        [r[0], r[1], r[2], t[0], t[1], t[2]]
+    }
+
+    pub fn from_array(a: [f64; 6]) -> Self {
+        Self::new([a[0], a[1], a[2]], [a[3], a[4], a[5]])
+    }
+
+    pub fn scaled(&self, s: f64) -> Self {
+        Self::new(linalg::scale(self.rotation, s), linalg::scale(self.translation, s))
+    }
+}
+
+/// Motor `M = T R` representing a rigid-body motion
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Motor {
+    pub rotor: Rotor,
+    pub translation: Vector3,
+}
+
+impl Default for Motor {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Motor {
+    /// Motor `T R` from a translation and a rotor
+    pub fn new(translation: Vector3, rotor: Rotor) -> Self {
+        Self { rotor, translation }
+    }
+
+    pub fn identity() -> Self {
+        Self::new([0.0; 3], Rotor::identity())
+    }
+
+    pub fn from_rotor(rotor: Rotor) -> Self {
+        Self::new([0.0; 3], rotor)
+    }
+
+    pub fn from_translation(translation: Vector3) -> Self {
+        Self::new(translation, Rotor::identity())
+    }
+
+    pub fn translator(&self) -> Translator {
+        Translator::new(self.translation)
+    }
+
+    /// Inverse motion (the reverse `M̃` for a normalized motor)
+    pub fn inverse(&self) -> Self {
+        let rotor = self.rotor.reverse();
+        Self::new(linalg::scale(rotor.apply(self.translation), -1.0), rotor)
+    }
+
+    /// Health of the rotational part; the translation cannot leave the manifold
+    pub fn health(&self) -> VersorHealth {
+        self.rotor.health()
+    }
+
+    pub fn renormalize(&mut self) {
+        self.rotor.renormalize();
+    }
+
+    /// Apply the motion to a Euclidean point
+    pub fn apply_point(&self, p: Vector3) -> Vector3 {
+        linalg::add(self.rotor.apply(p), self.translation)
+    }
+
+    /// Apply the rotational part to a direction vector
+    pub fn apply_direction(&self, v: Vector3) -> Vector3 {
+        self.rotor.apply(v)
+    }
+
+    /// Exponential map of a twist (screw motion)
+    pub fn exp(generator: &MotorGenerator) -> Self {
+        let rotor = Rotor::from_rotation_vector(generator.rotation);
+        let translation = left_jacobian_apply(generator.rotation, generator.translation);
+        settle(Self::new(translation, rotor))
+    }
+
+    /// Logarithm of the motor as a twist
+    pub fn log(&self) -> MotorGenerator {
+        let rotation = self.rotor.rotation_vector();
+        let translation = left_jacobian_inverse_apply(rotation, self.translation);
+        settle(MotorGenerator::new(rotation, translation))
+    }
+
+    /// Screw interpolation from `self` (t = 0) to `other` (t = 1)
+    pub fn interpolate(&self, other: &Motor, t: f64) -> Motor {
+        let delta = (self.inverse() * *other).log();
+        *self * Motor::exp(&delta.scaled(t))
+    }
+
+    /// Screw axis, angle and translation along the axis (Chasles' theorem)
+    ///
+    /// A pure translation has angle zero and an axis through the origin along
+    /// the translation. Returns `None` for the identity, whose axis is undefined.
+    pub fn screw_axis(&self) -> Option<ScrewAxis> {
+        const EPSILON: f64 = 1e-12;
+        let rotation = self.rotor.rotation_vector();
+        let theta = linalg::norm(rotation);
+        if theta < EPSILON {
+            let distance = linalg::norm(self.translation);
+            return (distance >= EPSILON).then(|| ScrewAxis {
+                axis: Line::through([0.0; 3], linalg::scale(self.translation, 1.0 / distance)),
+                angle: Angle::new(0.0),
+                translation: Length::new(distance),
+            });
+        }
+
+        let direction = linalg::scale(rotation, 1.0 / theta);
+        let along = linalg::dot(self.translation, direction);
+        let across = linalg::sub(self.translation, linalg::scale(direction, along));
+        // The point c ⊥ n on the axis solves (I - R) c = t⊥
+        let cot = 1.0 / (0.5 * theta).tan();
+        let center = linalg::scale(linalg::add(across, linalg::scale(linalg::cross(direction, across), cot)), 0.5);
+        Some(ScrewAxis {
+            axis: Line::through(center, direction),
+            angle: Angle::new(theta),
+            translation: Length::new(along),
+        })
+    }
+
+    /// Adjoint matrix acting on twists ordered (rotation, translation)
+    pub fn adjoint(&self) -> [[f64; 6]; 6] {
+        let r = self.rotor.to_rotation_matrix();
+        let tr = linalg::mat3_mul(&linalg::skew(self.translation), &r);
+        let mut adjoint = [[0.0; 6]; 6];
+        for i in 0..3 {
+            for j in 0..3 {
+                adjoint[i][j] = r[i][j];
+                adjoint[i + 3][j + 3] = r[i][j];
+                adjoint[i + 3][j] = tr[i][j];
+            }
+        }
+        adjoint
+    }
+}
+
+/// Chasles decomposition of a motor: a rotation about a line and a translation along it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrewAxis {
+    /// Screw axis with unit direction, oriented so the rotation is right-handed
+    pub axis: Line,
+    /// Rotation about the axis, in [0, τ/2]
+    pub angle: Angle,
+    /// Translation along the axis direction, signed
+    pub translation: Length,
+}
+
+impl ScrewAxis {
+    /// Pure rotation about the axis line, `T_c R T̃_c` for a point `c` on the axis
+    pub fn rotation(&self) -> Motor {
+        let rotor = Rotor::from_axis_angle(self.axis.direction, *self.angle.value());
+        let center = self.axis.closest_point_to_origin();
+        Motor::new(linalg::sub(center, rotor.apply(center)), rotor)
+    }
+
+    /// Pure translation along the axis
+    pub fn translator(&self) -> Translator {
+        Translator::new(linalg::scale(self.axis.direction, *self.translation.value()))
+    }
+
+    /// Recompose the motor; the two parts commute
+    pub fn motor(&self) -> Motor {
+        Motor::from_translation(self.translator().translation) * self.rotation()
+    }
+
+    /// Translation per radian of rotation; `None` for a pure translation
+    pub fn pitch(&self) -> Option<f64> {
+        let angle = *self.angle.value();
+        (angle > 0.0).then(|| *self.translation.value() / angle)
+    }
+}
+
+impl std::ops::Mul for Motor {
+    type Output = Motor;
+
+    /// Composition: `(a * b)` applies `b` first, then `a`
+    fn mul(self, rhs: Motor) -> Motor {
+        Motor::new(self.apply_point(rhs.translation), self.rotor * rhs.rotor)
+    }
+}
+
+/// `V ω v` with `V = I + A[ω]× + B[ω]×²`, the SO(3) left Jacobian
+fn left_jacobian_apply(omega: Vector3, v: Vector3) -> Vector3 {
+    let theta2 = linalg::dot(omega, omega);
+    let theta = theta2.sqrt();
+    let (a, b) = if theta < 1e-6 {
+        (0.5 - theta2 / 24.0, 1.0 / 6.0 - theta2 / 120.0)
+    } else {
+        ((1.0 - theta.cos()) / theta2, (theta - theta.sin()) / (theta2 * theta))
+    };
+    let wv = linalg::cross(omega, v);
+    let wwv = linalg::cross(omega, wv);
+    linalg::add(v, linalg::add(linalg::scale(wv, a), linalg::scale(wwv, b)))
+}
+
+/// `V⁻¹ v` with `V⁻¹ = I - ½[ω]× + C[ω]×²`
+fn left_jacobian_inverse_apply(omega: Vector3, v: Vector3) -> Vector3 {
+    let theta2 = linalg::dot(omega, omega);
+    let theta = theta2.sqrt();
+    let c = if theta < 1e-6 {
+        1.0 / 12.0 + theta2 / 720.0
+    } else {
+        (1.0 - theta * theta.sin() / (2.0 * (1.0 - theta.cos()))) / theta2
+    };
+    let wv = linalg::cross(omega, v);
+    let wwv = linalg::cross(omega, wv);
+    linalg::add(v, linalg::add(linalg::scale(wv, -0.5), linalg::scale(wwv, c)))
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::TAU;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_rotor_rotation() {
+        let rotor = Rotor::from_axis_angle([0.0, 0.0, 1.0], TAU / 4.0);
+        assert_close(rotor.apply([1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+        assert!(rotor.e12 < 0.0); // R = cos(θ/2) - sin(θ/2) e12
+        assert!((rotor.angle() - TAU / 4.0).abs() < 1e-12);
+
+        let m = rotor.to_rotation_matrix();
+        assert_close(linalg::mat3_vec(&m, [1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+
+        let back = Rotor::from_rotation_matrix(&m);
+        assert_close(back.rotation_vector(), rotor.rotation_vector());
+    }
+
+    #[test]
+    fn test_rotor_exp_log() {
+        let generator = Bivector::new(0.3, -0.2, 0.5);
+        let rotor = Rotor::exp(generator);
+        let log = rotor.log();
+
+        assert!((rotor.norm() - 1.0).abs() < 1e-12);
+        assert_close(log.rotation_vector(), generator.rotation_vector());
+    }
+
+    #[test]
+    fn test_motor_composition_and_inverse() {
+        let a = Motor::new([1.0, 2.0, 3.0], Rotor::from_axis_angle([0.0, 1.0, 0.0], 0.7));
+        let b = Motor::new([-0.5, 0.0, 2.0], Rotor::from_axis_angle([1.0, 1.0, 0.0], 1.1));
+        let p = [0.3, -0.4, 0.9];
+
+        assert_close((a * b).apply_point(p), a.apply_point(b.apply_point(p)));
+        assert_close((a * a.inverse()).apply_point(p), p);
+    }
+
+    #[test]
+    fn test_motor_exp_log_and_interpolation() {
+        let generator = MotorGenerator::new([0.1, -0.4, 0.8], [1.0, 0.5, -2.0]);
+        let motor = Motor::exp(&generator);
+        let log = motor.log();
+
+        assert_close(log.rotation, generator.rotation);
+        assert_close(log.translation, generator.translation);
+
+        let start = Motor::identity();
+        let end = Motor::new([2.0, 0.0, 0.0], Rotor::identity());
+        assert_close(start.interpolate(&end, 0.5).translation, [1.0, 0.0, 0.0]);
+        assert_close(start.interpolate(&motor, 1.0).apply_point([1.0, 1.0, 1.0]), motor.apply_point([1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn test_average_rotors() {
+        // Headings of 170° and -170° average to 180°, not to 0° as their angles do
+        let z = [0.0, 0.0, 1.0];
+        let rotors = [Rotor::from_axis_angle(z, TAU * 170.0 / 360.0), Rotor::from_axis_angle(z, -TAU * 170.0 / 360.0)];
+        let mean = average_rotors(&rotors, None).unwrap();
+        assert_close(mean.apply([1.0, 0.0, 0.0]), [-1.0, 0.0, 0.0]);
+
+        let weighted = average_rotors(&rotors, Some(&[3.0, 1.0])).unwrap();
+        assert!((weighted.angle() - TAU * 175.0 / 360.0).abs() < 1e-9);
+
+        // A symmetric cluster averages to its center
+        let center = Rotor::from_axis_angle([1.0, 2.0, 3.0], 0.8);
+        let spread: Vec<Rotor> = [[0.1, 0.0, 0.0], [-0.1, 0.0, 0.0], [0.0, 0.2, 0.0], [0.0, -0.2, 0.0]]
+            .iter()
+            .map(|v| center * Rotor::from_rotation_vector(*v))
+            .collect();
+        let mean = average_rotors(&spread, None).unwrap();
+        assert!((mean.reverse() * center).angle() < 1e-9);
+
+        assert_eq!(average_rotors(&[], None), Err(AveragingError::Empty));
+        assert_eq!(
+            average_rotors(&rotors, Some(&[1.0])),
+            Err(AveragingError::WeightCount { rotors: 2, weights: 1 })
+        );
+        assert_eq!(average_rotors(&rotors, Some(&[0.0, 0.0])), Err(AveragingError::InvalidWeights));
+    }
+
+    #[test]
+    fn test_screw_axis() {
+        // Quarter turn about the vertical line through (1, 2, 0), rising 0.5 m
+        let axis = Line::through([1.0, 2.0, 0.0], [0.0, 0.0, 1.0]);
+        let rotor = Rotor::from_axis_angle([0.0, 0.0, 1.0], TAU / 4.0);
+        let about_axis = Motor::new(linalg::sub([1.0, 2.0, 0.0], rotor.apply([1.0, 2.0, 0.0])), rotor);
+        let motor = Translator::new([0.0, 0.0, 0.5]) * Rotor::identity() * about_axis;
+
+        let screw = motor.screw_axis().unwrap();
+        assert!((screw.angle.value() - TAU / 4.0).abs() < 1e-12);
+        assert!((screw.translation.value() - 0.5).abs() < 1e-12);
+        assert_close(screw.axis.direction, axis.direction);
+        assert_close(screw.axis.moment, axis.moment);
+        assert!((screw.pitch().unwrap() - 0.5 / (TAU / 4.0)).abs() < 1e-12);
+
+        // The parts recompose to the motor for a general motion
+        let general = Motor::new([0.3, -1.2, 2.0], Rotor::from_axis_angle([1.0, -2.0, 0.5], 2.0));
+        let p = [0.7, 0.1, -0.4];
+        let screw = general.screw_axis().unwrap();
+        assert_close(screw.motor().apply_point(p), general.apply_point(p));
+        assert_close(screw.rotation().apply_point(screw.axis.closest_point_to_origin()), screw.axis.closest_point_to_origin());
+
+        let translation = Motor::from_translation([0.0, 3.0, 4.0]).screw_axis().unwrap();
+        assert_eq!((*translation.angle.value(), *translation.translation.value()), (0.0, 5.0));
+        assert_eq!(translation.pitch(), None);
+        assert!(Motor::identity().screw_axis().is_none());
+
+        // R T translates first
+        assert_close((rotor * Translator::new([1.0, 0.0, 0.0])).apply_point([0.0; 3]), [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_versor_health() {
+        let healthy = Rotor::from_axis_angle([1.0, 2.0, 3.0], 0.4);
+        let health = healthy.health();
+        assert!(health.is_healthy(1e-12));
+        assert!((health.condition_number - 1.0).abs() < 1e-9);
+
+        // Compose with a slightly scaled rotor until the drift compounds
+        let step = Rotor::new(healthy.scalar * (1.0 + 1e-7), healthy.e23, healthy.e13, healthy.e12);
+        let mut motor = Motor::from_rotor(Rotor::identity());
+        for _ in 0..100 {
+            motor = motor * Motor::new([0.1, 0.0, 0.0], step);
+        }
+        let drifted = motor.health();
+        assert!(!drifted.is_healthy(DRIFT_TOLERANCE));
+        assert!(drifted.manifold_distance > 1e-6 && drifted.condition_number > 1.0);
+
+        motor.renormalize();
+        assert!(motor.health().is_healthy(1e-12));
+    }
+}
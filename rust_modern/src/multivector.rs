@@ -0,0 +1,552 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! General multivectors with a sparse-dense hybrid representation
+//!
+//! A multivector starts as a sparse list of blades keyed by bitmask and switches
+//! to a dense array of all `2^n` coefficients once the fraction of non-zero
+//! blades crosses [`DensityPolicy::to_dense`]; pruning below
+//! [`DensityPolicy::to_sparse`] switches it back. The representation is an
+//! implementation detail: every operation behaves the same in either form.
+
+use serde::{Deserialize, Serialize};
+use crate::ga_term::{BasisIndex, Blade, BladeTerm, GATerm, Index, ALGEBRA_DIMENSION};
+use crate::parity::Coefficient;
+
+/// Bitmask of basis vectors in a blade; bit `i - 1` stands for `e_i`
+pub type BladeMask = u32;
+
+/// Largest dimension the bitmask representation supports
+pub const MAX_DIMENSION: u8 = 16;
+
+/// Canonical bitmask of a blade, with the sign of sorting its indices and squaring
+/// repeated vectors (Euclidean metric); `None` if an index is outside `1..=dimension`
+pub fn blade_mask<I: Copy + Into<Index>>(indices: &[I], dimension: u8) -> Option<(BladeMask, bool)> {
+    let mut mask: BladeMask = 0;
+    let mut negate = false;
+    for &index in indices {
+        let index: Index = index.into();
+        if index < 1 || index > dimension as Index {
+            return None;
+        }
+        let bit = 1 << (index - 1);
+        // Move e_index left past every higher vector already in the blade
+        negate ^= (mask & !((bit << 1) - 1)).count_ones() % 2 == 1;
+        mask ^= bit;
+    }
+    Some((mask, negate))
+}
+
+/// Indices of the blade encoded by `mask`, ascending
+pub fn mask_indices(mask: BladeMask) -> Vec<Index> {
+    (0..BladeMask::BITS as Index).filter(|bit| mask & (1 << bit) != 0).map(|bit| bit + 1).collect()
+}
+
+/// Sign of the Euclidean geometric product of two canonical blades
+pub fn product_sign(lhs: BladeMask, rhs: BladeMask) -> bool {
+    let mut a = lhs >> 1;
+    let mut swaps = 0;
+    while a != 0 {
+        swaps += (a & rhs).count_ones();
+        a >>= 1;
+    }
+    swaps % 2 == 1
+}
+
+/// Thresholds, as fractions of the `2^n` blades, for switching representation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DensityPolicy {
+    pub to_dense: f64,
+    pub to_sparse: f64,
+}
+
+impl Default for DensityPolicy {
+    fn default() -> Self {
+        Self { to_dense: 0.25, to_sparse: 0.125 }
+    }
+}
+
+impl DensityPolicy {
+    pub fn always_sparse() -> Self {
+        Self { to_dense: f64::INFINITY, to_sparse: f64::INFINITY }
+    }
+
+    pub fn always_dense() -> Self {
+        Self { to_dense: 0.0, to_sparse: -1.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Storage<T> {
+    /// Blades sorted by mask, no zero coefficients
+    Sparse(Vec<(BladeMask, T)>),
+    /// One coefficient per mask
+    Dense(Vec<T>),
+}
+
+/// Multivector of an `n`-dimensional Euclidean algebra
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Multivector<T> {
+    dimension: u8,
+    policy: DensityPolicy,
+    storage: Storage<T>,
+}
+
+impl<T: Coefficient> Default for Multivector<T> {
+    fn default() -> Self {
+        Self::zero(ALGEBRA_DIMENSION)
+    }
+}
+
+impl<T: Coefficient> Multivector<T> {
+    pub fn zero(dimension: u8) -> Self {
+        assert!(dimension <= MAX_DIMENSION, "dimension {} exceeds {}", dimension, MAX_DIMENSION);
+        Self {
+            dimension,
+            policy: DensityPolicy::default(),
+            storage: Storage::Sparse(Vec::new()),
+        }
+    }
+
+    pub fn scalar(value: T) -> Self {
+        let mut result = Self::zero(ALGEBRA_DIMENSION);
+        result.add_blade(0, value);
+        result
+    }
+
+    pub fn with_policy(mut self, policy: DensityPolicy) -> Self {
+        self.policy = policy;
+        self.rebalance();
+        self
+    }
+
+    /// Build from blade terms; dimensions past [`MAX_DIMENSION`] and terms
+    /// naming indices outside the algebra are rejected
+    pub fn from_terms(dimension: u8, terms: &[BladeTerm<T>]) -> Option<Self> {
+        if dimension > MAX_DIMENSION {
+            return None;
+        }
+        let mut result = Self::zero(dimension);
+        for term in terms {
+            let (mask, negate) = blade_mask(&term.indices, dimension)?;
+            result.add_blade(mask, if negate { -term.coefficient } else { term.coefficient });
+        }
+        result.rebalance();
+        Some(result)
+    }
+
+    pub fn from_gaterm(dimension: u8, term: &GATerm<T>) -> Option<Self> {
+        Self::from_terms(dimension, &term.clone().into_blade_terms())
+    }
+
+    pub fn dimension(&self) -> u8 {
+        self.dimension
+    }
+
+    pub fn is_dense(&self) -> bool {
+        matches!(self.storage, Storage::Dense(_))
+    }
+
+    /// All `2^n` coefficients indexed by mask, when stored densely
+    #[cfg(feature = "ndarray")]
+    pub(crate) fn dense_coefficients(&self) -> Option<&[T]> {
+        match &self.storage {
+            Storage::Sparse(_) => None,
+            Storage::Dense(values) => Some(values),
+        }
+    }
+
+    /// Multivector from all `2^n` coefficients indexed by mask
+    #[cfg(feature = "ndarray")]
+    pub(crate) fn from_coefficients(dimension: u8, values: Vec<T>) -> Self {
+        let mut result = Self::zero(dimension);
+        assert_eq!(values.len(), result.blade_count(), "expected one coefficient per blade");
+        result.storage = Storage::Dense(values);
+        result.rebalance();
+        result
+    }
+
+    fn blade_count(&self) -> usize {
+        1 << self.dimension
+    }
+
+    /// Number of non-zero blades
+    pub fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Sparse(terms) => terms.len(),
+            Storage::Dense(values) => values.iter().filter(|value| **value != T::default()).count(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Coefficient of the blade `e_{i1} e_{i2} ...`
+    pub fn get(&self, indices: &[Index]) -> T {
+        let Some((mask, negate)) = blade_mask(indices, self.dimension) else { return T::default() };
+        let value = self.get_mask(mask);
+        if negate { -value } else { value }
+    }
+
+    pub fn get_mask(&self, mask: BladeMask) -> T {
+        match &self.storage {
+            Storage::Sparse(terms) => terms
+                .binary_search_by_key(&mask, |(m, _)| *m)
+                .map(|i| terms[i].1)
+                .unwrap_or_default(),
+            Storage::Dense(values) => values.get(mask as usize).copied().unwrap_or_default(),
+        }
+    }
+
+    fn add_blade(&mut self, mask: BladeMask, value: T) {
+        if value == T::default() {
+            return;
+        }
+        match &mut self.storage {
+            Storage::Sparse(terms) => match terms.binary_search_by_key(&mask, |(m, _)| *m) {
+                Ok(i) => {
+                    terms[i].1 = terms[i].1 + value;
+                    if terms[i].1 == T::default() {
+                        terms.remove(i);
+                    }
+                }
+                Err(i) => terms.insert(i, (mask, value)),
+            },
+            Storage::Dense(values) => values[mask as usize] = values[mask as usize] + value,
+        }
+    }
+
+    /// Non-zero blades as (mask, coefficient), ascending by mask
+    pub fn blades(&self) -> Vec<(BladeMask, T)> {
+        match &self.storage {
+            Storage::Sparse(terms) => terms.clone(),
+            Storage::Dense(values) => values
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| **value != T::default())
+                .map(|(mask, value)| (mask as BladeMask, *value))
+                .collect(),
+        }
+    }
+
+    /// Non-zero blades as terms, ordered by grade then indices
+    pub fn terms(&self) -> Vec<BladeTerm<T>> {
+        let mut blades = self.blades();
+        blades.sort_by_key(|(mask, _)| (mask.count_ones(), mask_indices(*mask)));
+        let basis = |mask| mask_indices(mask).into_iter().map(|i| BasisIndex::new(i, self.dimension).expect("blade lies in the algebra")).collect();
+        blades.into_iter().map(|(mask, value)| BladeTerm::from_basis(basis(mask), value)).collect()
+    }
+
+    pub fn into_gaterm(self) -> GATerm<T> {
+        GATerm::Multivector(self.terms())
+    }
+
+    /// Switch representation according to the density policy
+    pub fn rebalance(&mut self) {
+        let density = self.len() as f64 / self.blade_count() as f64;
+        let blade_count = self.blade_count();
+        match &self.storage {
+            Storage::Sparse(terms) if density >= self.policy.to_dense => {
+                let mut values = vec![T::default(); blade_count];
+                for (mask, value) in terms {
+                    values[*mask as usize] = *value;
+                }
+                self.storage = Storage::Dense(values);
+            }
+            Storage::Dense(_) if density < self.policy.to_sparse => {
+                self.storage = Storage::Sparse(self.blades());
+            }
+            _ => {}
+        }
+    }
+
+    /// In-place addition
+    pub fn add_assign(&mut self, other: &Multivector<T>) {
+        assert_eq!(self.dimension, other.dimension, "multivectors from different algebras");
+        match (&mut self.storage, &other.storage) {
+            (Storage::Dense(values), Storage::Dense(others)) => {
+                for (value, other) in values.iter_mut().zip(others) {
+                    *value = *value + *other;
+                }
+            }
+            _ => {
+                for (mask, value) in other.blades() {
+                    self.add_blade(mask, value);
+                }
+            }
+        }
+        self.rebalance();
+    }
+
+    pub fn scale(&self, factor: T) -> Multivector<T> {
+        let mut result = self.clone();
+        match &mut result.storage {
+            Storage::Sparse(terms) => {
+                for (_, value) in terms.iter_mut() {
+                    *value = *value * factor;
+                }
+                terms.retain(|(_, value)| *value != T::default());
+            }
+            Storage::Dense(values) => {
+                for value in values.iter_mut() {
+                    *value = *value * factor;
+                }
+            }
+        }
+        result
+    }
+
+    /// Euclidean geometric product
+    pub fn geometric_product(&self, other: &Multivector<T>) -> Multivector<T> {
+        assert_eq!(self.dimension, other.dimension, "multivectors from different algebras");
+        let mut result = Multivector { dimension: self.dimension, policy: self.policy, storage: Storage::Sparse(Vec::new()) };
+        let (lhs, rhs) = (self.blades(), other.blades());
+
+        // Accumulate densely when the output could be large; sparse otherwise
+        if lhs.len() * rhs.len() >= (self.blade_count() as f64 * self.policy.to_dense) as usize {
+            result.storage = Storage::Dense(vec![T::default(); self.blade_count()]);
+        }
+        for (a, x) in &lhs {
+            for (b, y) in &rhs {
+                let product = *x * *y;
+                result.add_blade(a ^ b, if product_sign(*a, *b) { -product } else { product });
+            }
+        }
+        result.rebalance();
+        result
+    }
+
+    pub fn reverse(&self) -> Multivector<T> {
+        let mut result = self.clone();
+        let flip = |mask: BladeMask| {
+            let k = mask.count_ones();
+            (k * k.saturating_sub(1) / 2) % 2 == 1
+        };
+        match &mut result.storage {
+            Storage::Sparse(terms) => {
+                for (mask, value) in terms.iter_mut() {
+                    if flip(*mask) {
+                        *value = -*value;
+                    }
+                }
+            }
+            Storage::Dense(values) => {
+                for (mask, value) in values.iter_mut().enumerate() {
+                    if flip(mask as BladeMask) {
+                        *value = -*value;
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<T: Coefficient + PartialOrd> Multivector<T> {
+    /// Drop coefficients with |c| < epsilon, then rebalance
+    pub fn prune(&mut self, epsilon: T) {
+        let keep = |value: &T| *value >= epsilon || *value <= -epsilon;
+        match &mut self.storage {
+            Storage::Sparse(terms) => terms.retain(|(_, value)| keep(value)),
+            Storage::Dense(values) => {
+                for value in values.iter_mut() {
+                    if !keep(value) {
+                        *value = T::default();
+                    }
+                }
+            }
+        }
+        self.rebalance();
+    }
+}
+
+impl<T: Coefficient> PartialEq for Multivector<T> {
+    /// Equal if they hold the same blades, regardless of representation
+    fn eq(&self, other: &Self) -> bool {
+        self.dimension == other.dimension && self.blades() == other.blades()
+    }
+}
+
+impl<T: Coefficient> std::ops::Add for Multivector<T> {
+    type Output = Multivector<T>;
+
+    fn add(mut self, rhs: Multivector<T>) -> Multivector<T> {
+        self.add_assign(&rhs);
+        self
+    }
+}
+
+impl<T: Coefficient> std::ops::Mul for &Multivector<T> {
+    type Output = Multivector<T>;
+
+    fn mul(self, rhs: &Multivector<T>) -> Multivector<T> {
+        self.geometric_product(rhs)
+    }
+}
+
+/// Accumulates `(blade, coefficient)` pairs; blades may repeat and need not be sorted
+///
+/// Panics if a blade names an index outside the algebra.
+impl<T: Coefficient, B: AsRef<[Index]>> Extend<(B, T)> for Multivector<T> {
+    fn extend<I: IntoIterator<Item = (B, T)>>(&mut self, iter: I) {
+        for (blade, value) in iter {
+            self.add_indices(blade.as_ref(), value);
+        }
+        self.rebalance();
+    }
+}
+
+impl<'a, T: Coefficient> Extend<(Blade<'a>, T)> for Multivector<T> {
+    fn extend<I: IntoIterator<Item = (Blade<'a>, T)>>(&mut self, iter: I) {
+        for (blade, value) in iter {
+            self.add_indices(blade.indices(), value);
+        }
+        self.rebalance();
+    }
+}
+
+impl<T: Coefficient> Extend<BladeTerm<T>> for Multivector<T> {
+    fn extend<I: IntoIterator<Item = BladeTerm<T>>>(&mut self, iter: I) {
+        for term in iter {
+            self.add_indices(&term.indices, term.coefficient);
+        }
+        self.rebalance();
+    }
+}
+
+impl<T: Coefficient> Multivector<T> {
+    fn add_indices<I: Copy + Into<Index> + std::fmt::Debug>(&mut self, indices: &[I], value: T) {
+        let Some((mask, negate)) = blade_mask(indices, self.dimension) else {
+            panic!("blade {:?} outside a {}-dimensional algebra", indices, self.dimension);
+        };
+        self.add_blade(mask, if negate { -value } else { value });
+    }
+}
+
+/// Collects into the default algebra of dimension [`ALGEBRA_DIMENSION`]
+impl<T: Coefficient, B: AsRef<[Index]>> FromIterator<(B, T)> for Multivector<T> {
+    fn from_iter<I: IntoIterator<Item = (B, T)>>(iter: I) -> Self {
+        let mut result = Self::default();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<'a, T: Coefficient> FromIterator<(Blade<'a>, T)> for Multivector<T> {
+    fn from_iter<I: IntoIterator<Item = (Blade<'a>, T)>>(iter: I) -> Self {
+        let mut result = Self::default();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<T: Coefficient> FromIterator<BladeTerm<T>> for Multivector<T> {
+    fn from_iter<I: IntoIterator<Item = BladeTerm<T>>>(iter: I) -> Self {
+        let mut result = Self::default();
+        result.extend(iter);
+        result
+    }
+}
+
+/// Iterates over the non-zero blades as terms, ordered by grade then indices
+impl<T: Coefficient> IntoIterator for Multivector<T> {
+    type Item = BladeTerm<T>;
+    type IntoIter = std::vec::IntoIter<BladeTerm<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.terms().into_iter()
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(dimension: u8, components: &[(Index, f64)]) -> Multivector<f64> {
+        let terms: Vec<BladeTerm<f64>> = components.iter().map(|&(i, c)| BladeTerm::new(vec![i], c)).collect();
+        Multivector::from_terms(dimension, &terms).unwrap()
+    }
+
+    #[test]
+    fn test_blade_masks() {
+        assert_eq!(blade_mask(&[1, 2], 3), Some((0b011, false)));
+        assert_eq!(blade_mask(&[2, 1], 3), Some((0b011, true)));
+        assert_eq!(blade_mask(&[3, 1, 2], 3), Some((0b111, false)));
+        assert_eq!(blade_mask(&[1, 1], 3), Some((0, false)));
+        assert_eq!(blade_mask(&[4], 3), None);
+        assert_eq!(mask_indices(0b101), vec![1, 3]);
+        assert!(product_sign(0b010, 0b001));
+        assert!(!product_sign(0b001, 0b010));
+    }
+
+    #[test]
+    fn test_automatic_switching() {
+        let a = vector(5, &[(1, 1.0), (2, 2.0)]);
+        assert!(!a.is_dense());
+
+        // Product of two generic vectors stays sparse (scalar + bivectors)
+        let b = vector(5, &[(1, 3.0), (3, 1.0)]);
+        let ab = &a * &b;
+        assert!(!ab.is_dense());
+        assert_eq!(ab.get(&[]), 3.0);
+        assert_eq!(ab.get(&[1, 2]), -6.0);
+        assert_eq!(ab.get(&[2, 1]), 6.0);
+
+        // A full multivector crosses the threshold
+        let terms: Vec<BladeTerm<f64>> = (0..32u32).map(|mask| BladeTerm::new(mask_indices(mask), 1.0 + mask as f64)).collect();
+        let mut full = Multivector::from_terms(5, &terms).unwrap();
+        assert!(full.is_dense());
+        assert_eq!(full.len(), 32);
+
+        // Pruning most of it switches back
+        full.prune(30.0);
+        assert!(!full.is_dense());
+        assert_eq!(full.terms().len(), 3);
+    }
+
+    #[test]
+    fn test_representations_agree() {
+        let terms: Vec<BladeTerm<f64>> = (0..16u32).map(|mask| BladeTerm::new(mask_indices(mask), 0.5 - mask as f64 / 7.0)).collect();
+        let sparse = Multivector::from_terms(4, &terms).unwrap().with_policy(DensityPolicy::always_sparse());
+        let dense = sparse.clone().with_policy(DensityPolicy::always_dense());
+        assert!(!sparse.is_dense() && dense.is_dense());
+
+        assert_eq!(&sparse * &sparse, &dense * &dense);
+        assert_eq!(sparse.reverse(), dense.reverse());
+        assert_eq!(sparse.clone() + dense.clone(), dense.scale(2.0));
+    }
+
+    #[test]
+    fn test_gaterm_roundtrip() {
+        let term = GATerm::bivector(vec![(2, 1, 1.0), (1, 3, 2.0)]);
+        let multivector = Multivector::from_gaterm(3, &term).unwrap();
+
+        assert_eq!(multivector.get(&[1, 2]), -1.0);
+        assert_eq!(Multivector::from_gaterm(MAX_DIMENSION + 1, &term), None);
+        assert_eq!(
+            multivector.into_gaterm(),
+            GATerm::multivector(vec![BladeTerm::new(vec![1, 2], -1.0), BladeTerm::new(vec![1, 3], 2.0)])
+        );
+    }
+
+    #[test]
+    fn test_collect() {
+        let points = [[1.0, 2.0, 0.0], [0.5, 0.0, -1.0]];
+        let sum: Multivector<f64> = points
+            .iter()
+            .flat_map(|p| p.iter().enumerate().map(|(i, c)| ([i as Index + 1], *c)))
+            .collect();
+        assert_eq!(sum.dimension(), ALGEBRA_DIMENSION);
+        assert_eq!(sum.get(&[1]), 1.5);
+        assert_eq!(sum.get(&[3]), -1.0);
+
+        // Components of any term feed straight into a multivector, and back out
+        let term = GATerm::bivector(vec![(2, 1, 1.0), (1, 3, 2.0)]);
+        let multivector: Multivector<f64> = term.components().map(|(blade, c)| (blade, *c)).collect();
+        let terms: Vec<BladeTerm<f64>> = multivector.into_iter().collect();
+        assert_eq!(terms, vec![BladeTerm::new(vec![1, 2], -1.0), BladeTerm::new(vec![1, 3], 2.0)]);
+    }
+}
@@ -0,0 +1,188 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! ndarray views of multivector coefficients, Jacobians and inertia operators
+//!
+//! Multivector coefficients become a 1D array of all `2^n` blades indexed by
+//! [`BladeMask`], so `e1` is entry 1, `e2` entry 2 and `e12` entry 3. A dense
+//! [`Multivector`] is viewed in place; a sparse one, or a [`GATerm`], is
+//! expanded into a new array.
+//!
+//! A [`Jacobian`] is viewed in place as the 6×n matrix whose columns are its
+//! (angular, linear) columns, ready for an SVD or a least-squares solve from
+//! ndarray-linalg or similar crates. Inertia operators are small and computed on
+//! demand, so they are returned as owned 3×3 and 6×6 arrays.
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, ShapeBuilder};
+
+use crate::dynamics::Inertia;
+use crate::ga_term::GATerm;
+use crate::kinematics::Jacobian;
+use crate::linalg::Vector3;
+use crate::multivector::{BladeMask, Multivector};
+use crate::parity::Coefficient;
+
+impl<T: Coefficient> Multivector<T> {
+    /// All `2^n` coefficients indexed by blade mask
+    pub fn to_array(&self) -> Array1<T> {
+        if let Some(values) = self.dense_coefficients() {
+            return Array1::from(values.to_vec());
+        }
+        let mut values = Array1::from_elem(1 << self.dimension(), T::default());
+        for (mask, value) in self.blades() {
+            values[mask as usize] = value;
+        }
+        values
+    }
+
+    /// Coefficients viewed in place, if the multivector is stored densely
+    pub fn dense_view(&self) -> Option<ArrayView1<'_, T>> {
+        self.dense_coefficients().map(ArrayView1::from)
+    }
+
+    /// Multivector from `2^dimension` coefficients indexed by blade mask;
+    /// `None` if the length does not match
+    pub fn from_array(dimension: u8, values: ArrayView1<'_, T>) -> Option<Self> {
+        if dimension > crate::multivector::MAX_DIMENSION || values.len() != 1 << dimension {
+            return None;
+        }
+        Some(Self::from_coefficients(dimension, values.to_vec()))
+    }
+}
+
+impl<T: Coefficient> GATerm<T> {
+    /// Coefficients in the `dimension`-dimensional algebra, indexed by blade
+    /// mask; `None` if the term names indices outside it
+    pub fn to_array(&self, dimension: u8) -> Option<Array1<T>> {
+        Multivector::from_gaterm(dimension, self).map(|multivector| multivector.to_array())
+    }
+}
+
+/// Blade mask of each entry of a coefficient array, for labelling its rows
+pub fn blade_masks(dimension: u8) -> impl Iterator<Item = BladeMask> {
+    0..(1 << dimension)
+}
+
+impl Jacobian {
+    /// The 6×n matrix of the columns, viewed in place
+    pub fn view(&self) -> ArrayView2<'_, f64> {
+        ArrayView2::from_shape((6, self.columns.len()).f(), self.columns.as_flattened())
+            .expect("columns are contiguous [f64; 6]")
+    }
+
+    /// Owned copy of [`Jacobian::view`]
+    pub fn to_array(&self) -> Array2<f64> {
+        self.view().to_owned()
+    }
+}
+
+impl Inertia {
+    /// Tensor about the center of mass
+    pub fn central_array(&self) -> Array2<f64> {
+        matrix_array(&self.central_tensor())
+    }
+
+    /// Tensor about `point`
+    pub fn tensor_array(&self, point: Vector3) -> Array2<f64> {
+        matrix_array(&self.tensor_about(point))
+    }
+
+    /// Spatial inertia about `point`, see [`Inertia::spatial_about`]
+    pub fn spatial_array(&self, point: Vector3) -> Array2<f64> {
+        matrix_array(&self.spatial_about(point))
+    }
+}
+
+/// Fixed-size square matrix, such as a [`crate::linalg::Matrix3`] or
+/// [`crate::linalg::Matrix6`], as an array
+pub fn matrix_array<const N: usize>(matrix: &[[f64; N]; N]) -> Array2<f64> {
+    Array2::from_shape_fn((N, N), |(i, j)| matrix[i][j])
+}
+
+/// Fixed-size square matrix from an array; `None` unless it is N×N
+pub fn array_matrix<const N: usize>(array: ArrayView2<'_, f64>) -> Option<[[f64; N]; N]> {
+    if array.dim() != (N, N) {
+        return None;
+    }
+    Some(std::array::from_fn(|i| std::array::from_fn(|j| array[[i, j]])))
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ga_term::BladeTerm;
+    use crate::joints::{Joint, JointLimits, PrismaticLimits};
+    use crate::kinematics::KinematicChain;
+    use crate::linalg::square;
+    use crate::motor::{Motor, MotorGenerator, Rotor};
+    use crate::multivector::DensityPolicy;
+    use crate::si_units::{Angle, AngularVelocity, Length, Mass, Velocity, TAU};
+
+    #[test]
+    fn test_coefficient_arrays_round_trip() {
+        let terms = [BladeTerm::new(vec![], 2.0), BladeTerm::new(vec![1], -1.0), BladeTerm::new(vec![2, 1], 3.0)];
+        let sparse = Multivector::from_terms(3, &terms).unwrap().with_policy(DensityPolicy::always_sparse());
+        let array = sparse.to_array();
+        assert_eq!(array.to_vec(), vec![2.0, -1.0, 0.0, -3.0, 0.0, 0.0, 0.0, 0.0]);
+        assert!(sparse.dense_view().is_none());
+
+        let dense = sparse.clone().with_policy(DensityPolicy::always_dense());
+        assert_eq!(dense.dense_view().unwrap(), array.view());
+        let back = Multivector::from_array(3, array.view()).unwrap();
+        assert_eq!(back.blades(), sparse.blades());
+        assert!(Multivector::from_array(2, array.view()).is_none());
+
+        let term = GATerm::Multivector(terms.to_vec());
+        assert_eq!(term.to_array(3), Some(array));
+        assert_eq!(blade_masks(3).count(), 8);
+    }
+
+    #[test]
+    fn test_jacobian_view_matches_columns() {
+        let limits = JointLimits::new(Angle::new(-TAU / 2.0), Angle::new(TAU / 2.0), AngularVelocity::new(2.0));
+        let chain = KinematicChain::new()
+            .with_joint(Motor::identity(), Joint::revolute([0.0, 0.0, 1.0], limits))
+            .with_joint(Motor::from_translation([0.0, 0.0, 0.5]), Joint::revolute([0.0, 1.0, 0.0], limits))
+            .with_joint(
+                Motor::from_translation([0.4, 0.0, 0.0]),
+                Joint::prismatic([1.0, 0.0, 0.0], PrismaticLimits::new(Length::new(0.0), Length::new(0.2), Velocity::new(0.1))),
+            );
+        let jacobian = chain.jacobian(&[0.3, -0.6, 0.1]).unwrap();
+        let view = jacobian.view();
+        assert_eq!(view.dim(), (6, 3));
+        for (k, column) in jacobian.columns.iter().enumerate() {
+            for (i, value) in column.iter().enumerate() {
+                assert_eq!(view[[i, k]], *value);
+            }
+        }
+        // The view's product with joint rates is the end-effector twist
+        let dq = ndarray::arr1(&[0.5, -1.0, 2.0]);
+        let twist = view.dot(&dq);
+        for (found, expected) in twist.iter().zip(jacobian.twist(&dq.to_vec())) {
+            assert!((found - expected).abs() < 1e-12);
+        }
+        let gram = array_matrix::<6>(view.dot(&view.t()).view()).unwrap();
+        for (found, expected) in gram.iter().flatten().zip(jacobian.gram().iter().flatten()) {
+            assert!((found - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_inertia_arrays() {
+        let motor = Motor::new([0.5, -1.0, 2.0], Rotor::from_axis_angle([1.0, 2.0, 3.0], 0.7));
+        let body = Inertia::solid_box(Mass::new(2.0), [Length::new(0.3), Length::new(0.5), Length::new(1.0)]).transform(&motor);
+        assert_eq!(array_matrix::<3>(body.central_array().view()), Some(body.central_tensor()));
+        assert_eq!(array_matrix::<3>(body.tensor_array([1.0, 0.0, 0.0]).view()), Some(body.tensor_about([1.0, 0.0, 0.0])));
+
+        let spatial = body.spatial_array([0.0; 3]);
+        assert_eq!(spatial.dim(), (6, 6));
+        let twist = MotorGenerator::new([0.3, -1.2, 0.8], [1.5, 0.2, -0.7]);
+        let xi = Array1::from(twist.to_array().to_vec());
+        let energy = 0.5 * xi.dot(&spatial.dot(&xi));
+        assert!((energy - body.kinetic_energy(&twist).value()).abs() < 1e-12);
+        assert_eq!(matrix_array(&square::identity::<6>()), Array2::<f64>::eye(6));
+        assert!(array_matrix::<3>(spatial.view()).is_none());
+    }
+}
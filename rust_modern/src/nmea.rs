@@ -0,0 +1,493 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! NMEA 0183 sentences from GNSS receivers
+//!
+//! [`parse_sentence`] checks one `$..GGA`, `$..RMC` or `$..VTG` line against
+//! its checksum and decodes it into a [`Sentence`] with typed fields; any
+//! talker (`GP`, `GN`, `GL`, ...) is accepted. An [`NmeaDecoder`] reads a stream
+//! of sentences and turns them into [`GpsReading`]s stamped with the latest UTC
+//! time and date and tagged with the [`Gps`] frame, ready for sensor fusion.
+//!
+//! GGA altitudes are above mean sea level; adding the reported geoid separation
+//! gives the WGS84 height the [`GeodeticCoordinate`] expects.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::frames::Frame;
+use crate::geodesy::GeodeticCoordinate;
+use crate::linalg::Vector3;
+use crate::si_units::{Angle, Length, Time, Velocity, TAU};
+
+/// One knot in m/s
+const KNOT: f64 = 1852.0 / 3600.0;
+
+/// GNSS antenna frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gps;
+
+impl Frame for Gps {
+    const NAME: &'static str = "gps";
+}
+
+/// Errors raised while parsing a sentence
+#[derive(Debug, Clone, PartialEq)]
+pub enum NmeaError {
+    /// The line does not start with `$`
+    MissingStart,
+    Checksum { expected: u8, actual: u8 },
+    /// A well-formed sentence of a type this parser does not decode
+    Unsupported(String),
+    /// A field is missing or malformed
+    Field { index: usize, message: String },
+}
+
+impl fmt::Display for NmeaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NmeaError::MissingStart => write!(f, "NMEA sentences start with '$'"),
+            NmeaError::Checksum { expected, actual } => {
+                write!(f, "checksum mismatch: sentence says {:02X}, contents give {:02X}", expected, actual)
+            }
+            NmeaError::Unsupported(kind) => write!(f, "unsupported sentence type '{}'", kind),
+            NmeaError::Field { index, message } => write!(f, "field {}: {}", index, message),
+        }
+    }
+}
+
+impl std::error::Error for NmeaError {}
+
+/// Calendar date in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Days since 1970-01-01, by Howard Hinnant's civil calendar algorithm
+    pub fn days_since_epoch(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+/// UTC time of day, with the date once a sentence has carried it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timestamp {
+    pub date: Option<Date>,
+    /// Time since UTC midnight
+    pub time_of_day: Time,
+}
+
+impl Timestamp {
+    /// Time since the Unix epoch; `None` without a date
+    pub fn unix_time(&self) -> Option<Time> {
+        let date = self.date?;
+        Some(Time::new(date.days_since_epoch() as f64 * 86_400.0 + self.time_of_day.value()))
+    }
+}
+
+/// Receiver fix quality reported by GGA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixQuality {
+    Invalid,
+    Gps,
+    Differential,
+    Pps,
+    RtkFixed,
+    RtkFloat,
+    DeadReckoning,
+    Manual,
+    Simulation,
+}
+
+impl FixQuality {
+    fn from_code(code: u8) -> Option<Self> {
+        Some(match code {
+            0 => FixQuality::Invalid,
+            1 => FixQuality::Gps,
+            2 => FixQuality::Differential,
+            3 => FixQuality::Pps,
+            4 => FixQuality::RtkFixed,
+            5 => FixQuality::RtkFloat,
+            6 => FixQuality::DeadReckoning,
+            7 => FixQuality::Manual,
+            8 => FixQuality::Simulation,
+            _ => return None,
+        })
+    }
+}
+
+/// Speed and true course over ground
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroundVelocity {
+    pub speed: Velocity,
+    /// Clockwise from true north in `[0, τ)`
+    pub course: Angle,
+}
+
+impl GroundVelocity {
+    /// East, north and up components in m/s
+    pub fn enu(&self) -> Vector3 {
+        let (sin, cos) = self.course.value().sin_cos();
+        [self.speed.value() * sin, self.speed.value() * cos, 0.0]
+    }
+}
+
+/// A decoded sentence
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sentence {
+    /// Fix data
+    Gga {
+        time: Time,
+        /// `None` without a fix
+        position: Option<GeodeticCoordinate>,
+        quality: FixQuality,
+        satellites: u8,
+        hdop: Option<f64>,
+    },
+    /// Recommended minimum data
+    Rmc {
+        time: Time,
+        date: Date,
+        /// `false` when the receiver flags the data as void
+        valid: bool,
+        position: Option<GeodeticCoordinate>,
+        velocity: Option<GroundVelocity>,
+    },
+    /// Course and speed over ground
+    Vtg { velocity: Option<GroundVelocity> },
+}
+
+/// Value of type `T` measured in frame `F` at a time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stamped<T, F: Frame = Gps> {
+    pub value: T,
+    /// `None` until a sentence has carried the time
+    pub timestamp: Option<Timestamp>,
+    _frame: PhantomData<F>,
+}
+
+impl<T, F: Frame> Stamped<T, F> {
+    pub fn new(value: T, timestamp: Option<Timestamp>) -> Self {
+        Self { value, timestamp, _frame: PhantomData }
+    }
+
+    pub fn frame(&self) -> &'static str {
+        F::NAME
+    }
+}
+
+/// Measurement decoded from the sentence stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum GpsReading {
+    Position(Stamped<GeodeticCoordinate>),
+    Velocity(Stamped<GroundVelocity>),
+}
+
+/// Stateful decoder carrying the date and time between sentences
+#[derive(Debug, Clone, Default)]
+pub struct NmeaDecoder {
+    date: Option<Date>,
+    time: Option<Time>,
+}
+
+impl NmeaDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Latest time and date seen
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.time.map(|time_of_day| Timestamp { date: self.date, time_of_day })
+    }
+
+    /// Decode one line into the readings it carries; fixes flagged invalid carry none
+    pub fn decode(&mut self, line: &str) -> Result<Vec<GpsReading>, NmeaError> {
+        let mut readings = Vec::new();
+        match parse_sentence(line)? {
+            Sentence::Gga { time, position, quality, .. } => {
+                self.advance(time);
+                if let (Some(position), true) = (position, quality != FixQuality::Invalid) {
+                    readings.push(GpsReading::Position(Stamped::new(position, self.timestamp())));
+                }
+            }
+            Sentence::Rmc { time, date, valid, velocity, .. } => {
+                self.date = Some(date);
+                self.time = Some(time);
+                // RMC positions have no altitude, so positions come from GGA only
+                if valid {
+                    readings.extend(velocity.map(|v| GpsReading::Velocity(Stamped::new(v, self.timestamp()))));
+                }
+            }
+            Sentence::Vtg { velocity } => {
+                readings.extend(velocity.map(|v| GpsReading::Velocity(Stamped::new(v, self.timestamp()))));
+            }
+        }
+        Ok(readings)
+    }
+
+    /// Move to a GGA time, rolling the date over at midnight
+    fn advance(&mut self, time: Time) {
+        if let (Some(previous), Some(date)) = (self.time, self.date) {
+            if time.value() + 43_200.0 < *previous.value() {
+                let next = date.days_since_epoch() + 1;
+                self.date = Some(date_from_days(next));
+            }
+        }
+        self.time = Some(time);
+    }
+}
+
+/// Inverse of [`Date::days_since_epoch`]
+fn date_from_days(days: i64) -> Date {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+    Date { year, month, day }
+}
+
+/// Parse and checksum one NMEA 0183 sentence
+pub fn parse_sentence(line: &str) -> Result<Sentence, NmeaError> {
+    let body = line.trim().strip_prefix('$').ok_or(NmeaError::MissingStart)?;
+    let body = match body.split_once('*') {
+        Some((body, checksum)) => {
+            let expected = u8::from_str_radix(checksum, 16)
+                .map_err(|_| NmeaError::Field { index: 0, message: format!("bad checksum '{}'", checksum) })?;
+            let actual = body.bytes().fold(0, |sum, b| sum ^ b);
+            if expected != actual {
+                return Err(NmeaError::Checksum { expected, actual });
+            }
+            body
+        }
+        None => body,
+    };
+
+    let fields: Vec<&str> = body.split(',').collect();
+    let kind = fields[0].get(2..).unwrap_or("");
+    let fields = Fields(&fields);
+    match kind {
+        "GGA" => {
+            let altitude = fields.number(9)?.map(|msl| msl + fields.number(11).ok().flatten().unwrap_or(0.0));
+            let quality = fields.required(6)?;
+            let quality = quality
+                .parse()
+                .ok()
+                .and_then(FixQuality::from_code)
+                .ok_or_else(|| fields.error(6, format!("unknown fix quality '{}'", quality)))?;
+            Ok(Sentence::Gga {
+                time: fields.time(1)?,
+                position: fields.position(2, altitude.unwrap_or(0.0))?,
+                quality,
+                satellites: fields.number(7)?.unwrap_or(0.0) as u8,
+                hdop: fields.number(8)?,
+            })
+        }
+        "RMC" => Ok(Sentence::Rmc {
+            time: fields.time(1)?,
+            date: fields.date(9)?,
+            valid: fields.required(2)? == "A",
+            position: fields.position(3, 0.0)?,
+            velocity: fields.velocity(fields.number(7)?.map(|knots| knots * KNOT), 8)?,
+        }),
+        "VTG" => {
+            // Prefer km/h, which carries more resolution than knots
+            let speed = match fields.number(7)? {
+                Some(kmh) => Some(kmh / 3.6),
+                None => fields.number(5)?.map(|knots| knots * KNOT),
+            };
+            Ok(Sentence::Vtg { velocity: fields.velocity(speed, 1)? })
+        }
+        _ => Err(NmeaError::Unsupported(fields.0[0].to_string())),
+    }
+}
+
+/// Comma-separated fields of a sentence, numbered from the address field
+struct Fields<'a>(&'a [&'a str]);
+
+impl Fields<'_> {
+    fn error(&self, index: usize, message: String) -> NmeaError {
+        NmeaError::Field { index, message }
+    }
+
+    fn get(&self, index: usize) -> &str {
+        self.0.get(index).copied().unwrap_or("")
+    }
+
+    fn required(&self, index: usize) -> Result<&str, NmeaError> {
+        Some(self.get(index)).filter(|f| !f.is_empty()).ok_or_else(|| self.error(index, "missing".to_string()))
+    }
+
+    fn number(&self, index: usize) -> Result<Option<f64>, NmeaError> {
+        match self.get(index) {
+            "" => Ok(None),
+            field => field.parse().map(Some).map_err(|_| self.error(index, format!("'{}' is not a number", field))),
+        }
+    }
+
+    /// `hhmmss.ss` as time since midnight
+    fn time(&self, index: usize) -> Result<Time, NmeaError> {
+        let field = self.required(index)?;
+        let digits = |range: std::ops::Range<usize>| field.get(range).and_then(|d| d.parse::<f64>().ok());
+        match (digits(0..2), digits(2..4), digits(4..field.len())) {
+            (Some(h), Some(m), Some(s)) if field.len() >= 6 => Ok(Time::new(h * 3600.0 + m * 60.0 + s)),
+            _ => Err(self.error(index, format!("'{}' is not hhmmss", field))),
+        }
+    }
+
+    /// `ddmmyy`, with two-digit years from 1980 to 2079
+    fn date(&self, index: usize) -> Result<Date, NmeaError> {
+        let field = self.required(index)?;
+        let digits = |range: std::ops::Range<usize>| field.get(range).and_then(|d| d.parse::<u8>().ok());
+        match (digits(0..2), digits(2..4), digits(4..6)) {
+            (Some(day), Some(month), Some(yy)) if field.len() == 6 && (1..=12).contains(&month) && (1..=31).contains(&day) => {
+                let year = if yy < 80 { 2000 } else { 1900 } + yy as i32;
+                Ok(Date { year, month, day })
+            }
+            _ => Err(self.error(index, format!("'{}' is not ddmmyy", field))),
+        }
+    }
+
+    /// Latitude `ddmm.mm`, hemisphere, longitude `dddmm.mm`, hemisphere
+    fn position(&self, index: usize, altitude: f64) -> Result<Option<GeodeticCoordinate>, NmeaError> {
+        let (Some(latitude), Some(longitude)) = (self.angle(index, 2, "N", "S")?, self.angle(index + 2, 3, "E", "W")?) else {
+            return Ok(None);
+        };
+        Ok(Some(GeodeticCoordinate::new(latitude, longitude, Length::new(altitude))))
+    }
+
+    fn angle(&self, index: usize, degree_digits: usize, positive: &str, negative: &str) -> Result<Option<Angle>, NmeaError> {
+        let field = self.get(index);
+        if field.is_empty() {
+            return Ok(None);
+        }
+        let parsed = field.get(..degree_digits).and_then(|d| d.parse::<f64>().ok()).zip(field.get(degree_digits..).and_then(|m| m.parse::<f64>().ok()));
+        let Some((degrees, minutes)) = parsed else {
+            return Err(self.error(index, format!("'{}' is not degrees and minutes", field)));
+        };
+        let sign = match self.get(index + 1) {
+            h if h == positive => 1.0,
+            h if h == negative => -1.0,
+            h => return Err(self.error(index + 1, format!("unknown hemisphere '{}'", h))),
+        };
+        Ok(Some(Angle::new(sign * (degrees + minutes / 60.0) * TAU / 360.0)))
+    }
+
+    /// Ground velocity from a speed in m/s and a true course in degrees at `course`
+    fn velocity(&self, speed: Option<f64>, course: usize) -> Result<Option<GroundVelocity>, NmeaError> {
+        let Some(speed) = speed else {
+            return Ok(None);
+        };
+        // Receivers leave the course empty when stationary
+        let course = self.number(course)?.unwrap_or(0.0);
+        Ok(Some(GroundVelocity { speed: Velocity::new(speed), course: Angle::new((course * TAU / 360.0).rem_euclid(TAU)) }))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GGA: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+    const RMC: &str = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+    const VTG: &str = "$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*48";
+
+    fn degrees(angle: Angle) -> f64 {
+        angle.value() * 360.0 / TAU
+    }
+
+    #[test]
+    fn test_parse_sentences() {
+        let Sentence::Gga { time, position, quality, satellites, hdop } = parse_sentence(GGA).unwrap() else {
+            panic!("expected GGA");
+        };
+        assert_eq!(*time.value(), 12.0 * 3600.0 + 35.0 * 60.0 + 19.0);
+        let position = position.unwrap();
+        assert!((degrees(position.latitude) - (48.0 + 7.038 / 60.0)).abs() < 1e-12);
+        assert!((degrees(position.longitude) - (11.0 + 31.0 / 60.0)).abs() < 1e-12);
+        // Mean sea level altitude plus geoid separation
+        assert!((position.altitude.value() - 592.3).abs() < 1e-9);
+        assert_eq!((quality, satellites, hdop), (FixQuality::Gps, 8, Some(0.9)));
+
+        let Sentence::Rmc { date, valid, velocity, .. } = parse_sentence(RMC).unwrap() else {
+            panic!("expected RMC");
+        };
+        assert_eq!(date, Date { year: 1994, month: 3, day: 23 });
+        assert!(valid);
+        let velocity = velocity.unwrap();
+        assert!((velocity.speed.value() - 22.4 * KNOT).abs() < 1e-12);
+        assert!((degrees(velocity.course) - 84.4).abs() < 1e-9);
+
+        let Sentence::Vtg { velocity } = parse_sentence(VTG).unwrap() else {
+            panic!("expected VTG");
+        };
+        let velocity = velocity.unwrap();
+        assert!((velocity.speed.value() - 10.2 / 3.6).abs() < 1e-12);
+        let [east, north, _] = velocity.enu();
+        assert!(east > 0.0 && north > 0.0 && (east.hypot(north) - 10.2 / 3.6).abs() < 1e-12);
+
+        // A receiver without a fix yet
+        let Sentence::Gga { position, quality, .. } = parse_sentence("$GNGGA,001043.00,,,,,0,00,99.99,,,,,,*7E").unwrap() else {
+            panic!("expected GGA");
+        };
+        assert_eq!((position, quality), (None, FixQuality::Invalid));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_sentence("GPGGA,123519"), Err(NmeaError::MissingStart));
+        assert!(matches!(parse_sentence(&GGA.replace("*47", "*48")), Err(NmeaError::Checksum { expected: 0x48, actual: 0x47 })));
+        assert!(matches!(parse_sentence("$GPGSV,3,1,11"), Err(NmeaError::Unsupported(kind)) if kind == "GPGSV"));
+        assert!(matches!(parse_sentence("$GPGGA,123519,48x7.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"), Err(NmeaError::Field { index: 2, .. })));
+        assert!(matches!(parse_sentence("$GPGGA,123519,4807.038,Q,01131.000,E,1,08,0.9,545.4,M,46.9,M,,"), Err(NmeaError::Field { index: 3, .. })));
+    }
+
+    #[test]
+    fn test_decoder_stamps_readings() {
+        let mut decoder = NmeaDecoder::new();
+        // VTG carries no time, so its reading is unstamped until one arrives
+        let early = decoder.decode(VTG).unwrap();
+        assert!(matches!(&early[..], [GpsReading::Velocity(v)] if v.timestamp.is_none()));
+
+        assert_eq!(decoder.decode(RMC).unwrap().len(), 1);
+        let readings = decoder.decode(GGA).unwrap();
+        let [GpsReading::Position(fix)] = &readings[..] else {
+            panic!("expected one position, got {:?}", readings);
+        };
+        assert_eq!(fix.frame(), "gps");
+        let stamp = fix.timestamp.unwrap();
+        assert_eq!(stamp.date, Some(Date { year: 1994, month: 3, day: 23 }));
+        // 1994-03-23T12:35:19Z
+        assert_eq!(stamp.unix_time(), Some(Time::new(764_426_119.0)));
+
+        // Crossing midnight advances the date
+        let gga_at = |time: &str| {
+            let body = format!("GPGGA,{},4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,", time);
+            let checksum = body.bytes().fold(0u8, |sum, b| sum ^ b);
+            format!("${}*{:02X}", body, checksum)
+        };
+        decoder.decode(&gga_at("235959")).unwrap();
+        let readings = decoder.decode(&gga_at("000001")).unwrap();
+        let [GpsReading::Position(fix)] = &readings[..] else {
+            panic!("expected one position");
+        };
+        assert_eq!(fix.timestamp.unwrap().date, Some(Date { year: 1994, month: 3, day: 24 }));
+        assert_eq!(date_from_days(Date { year: 2024, month: 2, day: 29 }.days_since_epoch()), Date { year: 2024, month: 2, day: 29 });
+    }
+}
@@ -0,0 +1,305 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! 2D occupancy grids built from range readings
+//!
+//! An [`OccupancyGrid<F>`] covers a horizontal rectangle of the map frame `F`
+//! with square cells, each holding the log-odds that it is occupied. Every
+//! [`Reading`] is cast as a ray from the sensor through the grid: cells the ray
+//! crosses become more likely free, and the cell holding the echo more likely
+//! occupied. Readings at the sensor's maximum range are misses and only clear
+//! cells. Log-odds are clamped so a cell can change its mind after the scene
+//! does.
+//!
+//! Rays are projected onto the map's x-y plane, so tilted beams shorten. The
+//! grid can block cells of a [`BathymetryGrid`] for the planner, and exports as
+//! JSON through serde or as a PGM image.
+
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::frames::{Frame, FrameTransform};
+use crate::planner::BathymetryGrid;
+use crate::si_units::Length;
+use crate::sonar::Reading;
+
+/// Log-odds increments and limits
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LogOddsModel {
+    /// Added to the cell holding an echo
+    pub hit: f64,
+    /// Added to cells a ray passes through; negative
+    pub miss: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Default for LogOddsModel {
+    fn default() -> Self {
+        // Hits 70 % and misses 40 % likely occupied
+        Self { hit: (0.7f64 / 0.3).ln(), miss: (0.4f64 / 0.6).ln(), min: -4.0, max: 4.0 }
+    }
+}
+
+/// Classification of a cell against probability thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellState {
+    Unknown,
+    Free,
+    Occupied,
+}
+
+/// Log-odds occupancy over a horizontal grid in frame `F`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct OccupancyGrid<F: Frame> {
+    /// x and y of the corner of cell `(0, 0)`
+    origin: [f64; 2],
+    resolution: f64,
+    columns: usize,
+    rows: usize,
+    /// Row-major from the minimum corner
+    log_odds: Vec<f64>,
+    model: LogOddsModel,
+    #[serde(skip)]
+    _frame: PhantomData<F>,
+}
+
+impl<F: Frame> OccupancyGrid<F> {
+    /// Unknown grid of `columns × rows` cells of side `resolution`
+    pub fn new(origin: [Length; 2], resolution: Length, columns: usize, rows: usize) -> Self {
+        assert!(*resolution.value() > 0.0, "grid resolution must be positive");
+        Self {
+            origin: origin.map(|o| *o.value()),
+            resolution: *resolution.value(),
+            columns,
+            rows,
+            log_odds: vec![0.0; columns * rows],
+            model: LogOddsModel::default(),
+            _frame: PhantomData,
+        }
+    }
+
+    pub fn with_model(mut self, model: LogOddsModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn resolution(&self) -> Length {
+        Length::new(self.resolution)
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// Cell `(column, row)` containing the map point `(x, y)`
+    pub fn cell_at(&self, point: [f64; 2]) -> Option<(usize, usize)> {
+        let [column, row] = self.grid_coordinates(point).map(f64::floor);
+        (column >= 0.0 && row >= 0.0 && (column as usize) < self.columns && (row as usize) < self.rows)
+            .then_some((column as usize, row as usize))
+    }
+
+    pub fn cell_center(&self, (column, row): (usize, usize)) -> [f64; 2] {
+        [
+            self.origin[0] + (column as f64 + 0.5) * self.resolution,
+            self.origin[1] + (row as f64 + 0.5) * self.resolution,
+        ]
+    }
+
+    fn grid_coordinates(&self, point: [f64; 2]) -> [f64; 2] {
+        [(point[0] - self.origin[0]) / self.resolution, (point[1] - self.origin[1]) / self.resolution]
+    }
+
+    /// Occupancy probability of the cell at `point`; `None` outside the grid
+    pub fn probability(&self, point: [f64; 2]) -> Option<f64> {
+        let (column, row) = self.cell_at(point)?;
+        Some(1.0 - 1.0 / (1.0 + self.log_odds[row * self.columns + column].exp()))
+    }
+
+    /// `Occupied` at or above `occupied`, `Free` at or below `free`
+    pub fn state(&self, point: [f64; 2], free: f64, occupied: f64) -> CellState {
+        match self.probability(point) {
+            Some(p) if p >= occupied => CellState::Occupied,
+            Some(p) if p <= free => CellState::Free,
+            _ => CellState::Unknown,
+        }
+    }
+
+    /// Cells the segment `from → to` passes through, in order, including both
+    /// end cells; cells outside the grid are skipped
+    pub fn traverse(&self, from: [f64; 2], to: [f64; 2]) -> Vec<(usize, usize)> {
+        let (a, b) = (self.grid_coordinates(from), self.grid_coordinates(to));
+        let mut cell = a.map(|c| c.floor() as i64);
+        let end = b.map(|c| c.floor() as i64);
+        let delta = [b[0] - a[0], b[1] - a[1]];
+        let step = delta.map(|d| if d > 0.0 { 1 } else { -1 });
+        // Ray parameter of the next boundary crossing per axis, and between crossings
+        let mut next = [0, 1].map(|i| {
+            if delta[i] == 0.0 {
+                f64::INFINITY
+            } else {
+                let boundary = if delta[i] > 0.0 { cell[i] as f64 + 1.0 } else { cell[i] as f64 };
+                (boundary - a[i]) / delta[i]
+            }
+        });
+        let spacing = delta.map(|d| if d == 0.0 { f64::INFINITY } else { 1.0 / d.abs() });
+
+        let mut cells = Vec::new();
+        let limit = (end[0] - cell[0]).abs() + (end[1] - cell[1]).abs();
+        for _ in 0..=limit {
+            if cell[0] >= 0 && cell[1] >= 0 && (cell[0] as usize) < self.columns && (cell[1] as usize) < self.rows {
+                cells.push((cell[0] as usize, cell[1] as usize));
+            }
+            if cell == end {
+                break;
+            }
+            let axis = if next[0] < next[1] { 0 } else { 1 };
+            cell[axis] += step[axis];
+            next[axis] += spacing[axis];
+        }
+        cells
+    }
+
+    /// Cast one reading taken by a sensor at `sensor` in the map frame. Ranges
+    /// at or beyond `max_range` are misses.
+    pub fn integrate<S: Frame>(&mut self, sensor: &FrameTransform<F, S>, reading: &Reading, max_range: Length) {
+        let echo = reading.range < max_range;
+        let range = reading.range.value().min(*max_range.value());
+        let origin = sensor.apply_point([0.0; 3]);
+        let end = sensor.apply_point(Reading::new(Length::new(range), reading.bearing, reading.elevation).position());
+        let cells = self.traverse([origin[0], origin[1]], [end[0], end[1]]);
+        let end_cell = self.cell_at([end[0], end[1]]);
+        for cell in cells {
+            let hit = echo && Some(cell) == end_cell;
+            let index = cell.1 * self.columns + cell.0;
+            let update = if hit { self.model.hit } else { self.model.miss };
+            self.log_odds[index] = (self.log_odds[index] + update).clamp(self.model.min, self.model.max);
+        }
+    }
+
+    /// Cast every reading of a scan from one sensor pose
+    pub fn integrate_scan<S: Frame>(&mut self, sensor: &FrameTransform<F, S>, readings: &[Reading], max_range: Length) {
+        for reading in readings {
+            self.integrate(sensor, reading, max_range);
+        }
+    }
+
+    /// Centers of the cells at least `threshold` likely to be occupied
+    pub fn occupied_cells(&self, threshold: f64) -> Vec<[f64; 2]> {
+        let limit = (threshold / (1.0 - threshold)).ln();
+        (0..self.log_odds.len())
+            .filter(|&i| self.log_odds[i] >= limit)
+            .map(|i| self.cell_center((i % self.columns, i / self.columns)))
+            .collect()
+    }
+
+    /// Mark as no-go every planner cell whose center lies within `margin` of a
+    /// cell at least `threshold` likely to be occupied, so paths keep clear of
+    /// obstacles. Both grids must share frame `F`.
+    pub fn block_in(&self, grid: &mut BathymetryGrid, threshold: f64, margin: Length) {
+        let reach = 0.5 * self.resolution + margin.value().max(0.0);
+        let occupied = self.occupied_cells(threshold);
+        grid.add_no_go_where(|p| occupied.iter().any(|c| (p[0] - c[0]).abs() <= reach && (p[1] - c[1]).abs() <= reach));
+    }
+
+    /// Binary PGM image, north (maximum y) up: occupied black, free white and
+    /// unknown grey, as map servers expect
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut image = format!("P5\n{} {}\n255\n", self.columns, self.rows).into_bytes();
+        for row in (0..self.rows).rev() {
+            for column in 0..self.columns {
+                let log_odds = self.log_odds[row * self.columns + column];
+                let probability = 1.0 - 1.0 / (1.0 + log_odds.exp());
+                image.push(if log_odds == 0.0 { 205 } else { (255.0 * (1.0 - probability)).round() as u8 });
+            }
+        }
+        image
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geodesy::Enu;
+    use crate::guidance::LineOfSight;
+    use crate::motor::{Motor, Rotor};
+    use crate::planner::{plan, PlannerConfig};
+    use crate::si_units::{Angle, Velocity, TAU};
+
+    struct Sensor;
+    impl Frame for Sensor {
+        const NAME: &'static str = "sensor";
+    }
+
+    #[test]
+    fn test_ray_traversal() {
+        let grid = OccupancyGrid::<Enu>::new([Length::new(0.0); 2], Length::new(1.0), 10, 10);
+        assert_eq!(grid.traverse([0.5, 0.5], [3.5, 0.5]), [(0, 0), (1, 0), (2, 0), (3, 0)]);
+        let diagonal = grid.traverse([0.5, 0.5], [2.7, 1.2]);
+        assert_eq!(diagonal.first(), Some(&(0, 0)));
+        assert_eq!(diagonal.last(), Some(&(2, 1)));
+        // Consecutive cells always share an edge
+        for pair in diagonal.windows(2) {
+            assert_eq!(pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1), 1);
+        }
+        assert_eq!(grid.traverse([-2.5, 0.5], [1.5, 0.5]), [(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn test_scan_updates_and_export() {
+        let mut grid = OccupancyGrid::<Enu>::new([Length::new(0.0); 2], Length::new(0.5), 20, 20);
+        let sensor = FrameTransform::<Enu, Sensor>::new(Motor::from_translation([1.0, 5.0, 0.0]));
+        let wall = Reading::new(Length::new(6.2), Angle::new(0.0), Angle::new(0.0));
+        let miss = Reading::new(Length::new(10.0), Angle::new(TAU / 8.0), Angle::new(0.0));
+        for _ in 0..3 {
+            grid.integrate_scan(&sensor, &[wall, miss], Length::new(8.0));
+        }
+        assert_eq!(grid.state([7.2, 5.0], 0.3, 0.7), CellState::Occupied);
+        assert_eq!(grid.state([4.0, 5.0], 0.3, 0.7), CellState::Free);
+        assert_eq!(grid.state([4.0, 8.0], 0.3, 0.7), CellState::Free);
+        assert_eq!(grid.state([9.0, 1.0], 0.3, 0.7), CellState::Unknown);
+        assert_eq!(grid.occupied_cells(0.7), [[7.25, 5.25]]);
+
+        let pgm = grid.to_pgm();
+        assert!(pgm.starts_with(b"P5\n20 20\n255\n"));
+        assert_eq!(pgm.len(), 13 + 400);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<OccupancyGrid<Enu>>(&json).unwrap(), grid);
+    }
+
+    #[test]
+    fn test_perceive_plan_act() {
+        // A wall across x = 10 from y = 0 to 16 seen by a rotating sonar at two stations
+        let resolution = Length::new(1.0);
+        let mut map = OccupancyGrid::<Enu>::new([Length::new(0.0); 2], resolution, 20, 20);
+        for station in [[5.0, 4.0], [5.0, 12.0]] {
+            let sensor = FrameTransform::<Enu, Sensor>::new(Motor::new([station[0], station[1], 0.0], Rotor::identity()));
+            for k in -40..=40 {
+                let bearing = k as f64 * TAU / 320.0;
+                let hit_y = station[1] + 5.0 * bearing.tan();
+                let range = if (0.0..16.0).contains(&hit_y) { 5.0 / bearing.cos() + 0.2 } else { 30.0 };
+                map.integrate(&sensor, &Reading::new(Length::new(range), Angle::new(bearing), Angle::new(0.0)), Length::new(25.0));
+            }
+        }
+
+        let depths = vec![Length::new(50.0); 400];
+        let mut grid = BathymetryGrid::new([Length::new(0.0); 2], resolution, 20, 20, depths).unwrap();
+        map.block_in(&mut grid, 0.6, Length::new(1.0));
+        let config = PlannerConfig { travel_depth: Length::new(5.0), clearance: Length::new(1.0), speed: Velocity::new(1.0) };
+        let path = plan(&grid, &config, [5.5, 8.5, 0.0], [15.5, 8.5, 0.0]).unwrap();
+        assert!(path.waypoints.iter().any(|w| w[1] > 17.0), "{:?}", path.waypoints);
+
+        let mut guidance = LineOfSight::new(path.waypoints, Length::new(2.0), Length::new(0.5));
+        let mut position = [5.5, 8.5, -5.0];
+        while let Some(command) = guidance.update(position) {
+            let (sin, cos) = command.course.value().sin_cos();
+            position = [position[0] + 0.1 * cos, position[1] + 0.1 * sin, position[2]];
+            assert!(map.state([position[0], position[1]], 0.3, 0.6) != CellState::Occupied);
+        }
+        assert!((position[0] - 15.5).hypot(position[1] - 8.5) < 0.6);
+    }
+}
@@ -0,0 +1,293 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Statically typed even and odd multivectors
+//!
+//! The geometric product preserves parity: even × even and odd × odd are even,
+//! mixed products are odd. [`Even`] and [`Odd`] encode this in the type system
+//! so that, for example, the product of two vectors is known to be a rotor-like
+//! element without a runtime grade check. Products use the Euclidean metric.
+
+use std::ops::{Add, Mul, Neg};
+
+use serde::{Deserialize, Serialize};
+use crate::ga_term::{BladeTerm, GATerm, Grade, Index};
+use crate::grade_indexed::VectorType;
+use crate::motor::Rotor;
+
+/// Coefficient types the blade products can work with
+pub trait Coefficient: Copy + Default + PartialEq + Add<Output = Self> + Neg<Output = Self> + Mul<Output = Self> {}
+
+impl<T> Coefficient for T where T: Copy + Default + PartialEq + Add<Output = T> + Neg<Output = T> + Mul<Output = T> {}
+
+/// Product of two basis blades under the Euclidean metric
+///
+/// Returns the canonical (sorted) indices of the result and whether its sign flips.
+pub fn blade_product<I: Copy + Ord>(lhs: &[I], rhs: &[I]) -> (Vec<I>, bool) {
+    let mut indices: Vec<I> = lhs.iter().chain(rhs).copied().collect();
+    let mut negate = false;
+
+    // Bubble sort counting transpositions; adjacent duplicates square to +1
+    let mut i = 0;
+    while i + 1 < indices.len() {
+        if indices[i] > indices[i + 1] {
+            indices.swap(i, i + 1);
+            negate = !negate;
+            i = i.saturating_sub(1);
+        } else if indices[i] == indices[i + 1] {
+            indices.drain(i..i + 2);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+    (indices, negate)
+}
+
+/// Geometric product of two blade sums, combining like terms
+pub fn multiply_terms<T: Coefficient>(lhs: &[BladeTerm<T>], rhs: &[BladeTerm<T>]) -> Vec<BladeTerm<T>> {
+    let mut result: Vec<BladeTerm<T>> = Vec::new();
+    for a in lhs {
+        for b in rhs {
+            let (indices, negate) = blade_product(&a.indices, &b.indices);
+            let product = a.coefficient * b.coefficient;
+            let coefficient = if negate { -product } else { product };
+            match result.iter_mut().find(|term| term.indices == indices) {
+                Some(term) => term.coefficient = term.coefficient + coefficient,
+                None => result.push(BladeTerm::from_basis(indices, coefficient)),
+            }
+        }
+    }
+    result.retain(|term| term.coefficient != T::default());
+    result.sort_by(|a, b| (a.indices.len(), &a.indices).cmp(&(b.indices.len(), &b.indices)));
+    result
+}
+
+fn reverse_terms<T: Coefficient>(terms: &[BladeTerm<T>]) -> Vec<BladeTerm<T>> {
+    terms
+        .iter()
+        .map(|term| {
+            // Reversion flips the sign of grades 2 and 3 (mod 4)
+            let k = term.indices.len();
+            let coefficient = if (k * k.saturating_sub(1) / 2) % 2 == 1 { -term.coefficient } else { term.coefficient };
+            BladeTerm::from_basis(term.indices.clone(), coefficient)
+        })
+        .collect()
+}
+
+fn grade_part<T: Clone>(terms: &[BladeTerm<T>], grade: usize) -> Vec<BladeTerm<T>> {
+    terms.iter().filter(|term| term.indices.len() == grade).cloned().collect()
+}
+
+fn scalar_part<T: Coefficient>(terms: &[BladeTerm<T>]) -> T {
+    terms
+        .iter()
+        .filter(|term| term.indices.is_empty())
+        .fold(T::default(), |acc, term| acc + term.coefficient)
+}
+
+fn has_parity<T>(terms: &[BladeTerm<T>], odd: bool) -> bool {
+    terms.iter().all(|term| (term.indices.len() % 2 == 1) == odd)
+}
+
+/// Even-grade multivector: scalar + bivector + quadvector + ...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Even<T> {
+    terms: Vec<BladeTerm<T>>,
+}
+
+/// Odd-grade multivector: vector + trivector + ...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Odd<T> {
+    terms: Vec<BladeTerm<T>>,
+}
+
+macro_rules! impl_parity_type {
+    ($name:ident, $odd:expr) => {
+        impl<T> $name<T> {
+            /// Build from blade terms, `None` if any term has the wrong parity
+            pub fn from_terms(terms: Vec<BladeTerm<T>>) -> Option<Self> {
+                if has_parity(&terms, $odd) {
+                    Some(Self { terms })
+                } else {
+                    None
+                }
+            }
+
+            pub fn terms(&self) -> &[BladeTerm<T>] {
+                &self.terms
+            }
+
+            pub fn into_terms(self) -> Vec<BladeTerm<T>> {
+                self.terms
+            }
+
+            /// Grades present in this element
+            pub fn grades(&self) -> Vec<Grade> {
+                let mut grades: Vec<Grade> = self.terms.iter().map(BladeTerm::grade).collect();
+                grades.sort();
+                grades.dedup();
+                grades
+            }
+
+            pub fn into_gaterm(self) -> GATerm<T> {
+                GATerm::Multivector(self.terms)
+            }
+        }
+
+        impl<T: Coefficient> $name<T> {
+            pub fn reverse(&self) -> Self {
+                Self { terms: reverse_terms(&self.terms) }
+            }
+
+            /// Terms of exactly the given grade
+            pub fn grade_part(&self, grade: usize) -> Vec<BladeTerm<T>> {
+                grade_part(&self.terms, grade)
+            }
+        }
+
+        impl<T: Coefficient> TryFrom<GATerm<T>> for $name<T> {
+            type Error = GATerm<T>;
+
+            fn try_from(term: GATerm<T>) -> Result<Self, GATerm<T>> {
+                Self::from_terms(term.clone().into_blade_terms()).ok_or(term)
+            }
+        }
+    };
+}
+
+impl_parity_type!(Even, false);
+impl_parity_type!(Odd, true);
+
+impl<T: Coefficient> Even<T> {
+    pub fn scalar(value: T) -> Self {
+        Self { terms: vec![BladeTerm::from_basis(vec![], value)] }
+    }
+
+    pub fn scalar_part(&self) -> T {
+        scalar_part(&self.terms)
+    }
+
+    pub fn bivector_part(&self) -> Vec<BladeTerm<T>> {
+        grade_part(&self.terms, 2)
+    }
+}
+
+impl<T: Coefficient> Odd<T> {
+    pub fn vector_part(&self) -> Vec<BladeTerm<T>> {
+        grade_part(&self.terms, 1)
+    }
+}
+
+impl<T> From<VectorType<T>> for Odd<T> {
+    fn from(vector: VectorType<T>) -> Self {
+        Self {
+            terms: vector.into_inner().into_iter().map(|(i, c)| BladeTerm::from_basis(vec![i], c)).collect(),
+        }
+    }
+}
+
+macro_rules! impl_parity_product {
+    ($lhs:ident * $rhs:ident => $out:ident) => {
+        impl<T: Coefficient> Mul<$rhs<T>> for $lhs<T> {
+            type Output = $out<T>;
+
+            fn mul(self, rhs: $rhs<T>) -> $out<T> {
+                $out { terms: multiply_terms(&self.terms, &rhs.terms) }
+            }
+        }
+
+        impl<T: Coefficient> Mul<&$rhs<T>> for &$lhs<T> {
+            type Output = $out<T>;
+
+            fn mul(self, rhs: &$rhs<T>) -> $out<T> {
+                $out { terms: multiply_terms(&self.terms, &rhs.terms) }
+            }
+        }
+    };
+}
+
+impl_parity_product!(Even * Even => Even);
+impl_parity_product!(Even * Odd => Odd);
+impl_parity_product!(Odd * Even => Odd);
+impl_parity_product!(Odd * Odd => Even);
+
+/// Geometric product of two vectors, statically known to be even
+pub fn vector_product<T: Coefficient>(lhs: &VectorType<T>, rhs: &VectorType<T>) -> Even<T> {
+    Odd::from(lhs.clone()) * Odd::from(rhs.clone())
+}
+
+impl From<Rotor> for Even<f64> {
+    fn from(rotor: Rotor) -> Self {
+        let terms = vec![
+            BladeTerm::new(vec![], rotor.scalar),
+            BladeTerm::new(vec![1, 2], rotor.e12),
+            BladeTerm::new(vec![1, 3], rotor.e13),
+            BladeTerm::new(vec![2, 3], rotor.e23),
+        ];
+        Self { terms: terms.into_iter().filter(|term| term.coefficient != 0.0).collect() }
+    }
+}
+
+impl From<&Even<f64>> for Rotor {
+    /// Keeps the scalar and e12, e13, e23 parts; higher even grades are dropped
+    fn from(even: &Even<f64>) -> Self {
+        let part = |indices: &[Index]| {
+            even.terms.iter().filter(|term| term.indices == indices).map(|term| term.coefficient).sum()
+        };
+        Rotor::new(part(&[]), part(&[2, 3]), part(&[1, 3]), part(&[1, 2]))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blade_product_signs() {
+        assert_eq!(blade_product(&[1], &[2]), (vec![1, 2], false));
+        assert_eq!(blade_product(&[2], &[1]), (vec![1, 2], true));
+        assert_eq!(blade_product(&[1, 2], &[1, 2]), (vec![], true));
+        assert_eq!(blade_product(&[1, 2, 3], &[3]), (vec![1, 2], false));
+    }
+
+    #[test]
+    fn test_vector_product_is_even() {
+        let a = VectorType::vector(vec![(1, 1.0), (2, 2.0)]);
+        let b = VectorType::vector(vec![(1, 3.0), (2, -1.0)]);
+
+        // ab = a·b + a∧b
+        let ab: Even<f64> = vector_product(&a, &b);
+        assert_eq!(ab.scalar_part(), 1.0);
+        assert_eq!(ab.bivector_part(), vec![BladeTerm::new(vec![1, 2], -7.0)]);
+        assert_eq!(ab.grades(), vec![Grade::Scalar, Grade::Bivector]);
+
+        let aba: Odd<f64> = &ab * &Odd::from(a.clone());
+        assert!(aba.terms().iter().all(|term| term.grade() == Grade::Vector));
+    }
+
+    #[test]
+    fn test_rotor_from_vectors() {
+        let s = std::f64::consts::FRAC_1_SQRT_2;
+        let a = VectorType::vector(vec![(1, 1.0)]);
+        let b = VectorType::vector(vec![(1, s), (2, s)]);
+
+        // The rotor ba rotates by twice the angle from a to b
+        let rotor = Rotor::from(&vector_product(&b, &a));
+        let rotated = rotor.apply([1.0, 0.0, 0.0]);
+        assert!((rotated[0]).abs() < 1e-12 && (rotated[1] - 1.0).abs() < 1e-12);
+
+        let even = Even::from(rotor);
+        let product = &even * &even.reverse();
+        assert!((product.scalar_part() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_try_from_gaterm() {
+        assert!(Even::try_from(GATerm::bivector(vec![(1, 2, 1.0)])).is_ok());
+        assert!(Even::try_from(GATerm::vector(vec![(1, 1.0)])).is_err());
+        assert!(Odd::try_from(GATerm::trivector(vec![(1, 2, 3, 1.0)])).is_ok());
+    }
+}
@@ -0,0 +1,579 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Dubins and Reeds-Shepp paths between planar poses
+//!
+//! A vehicle that cannot turn tighter than a [`TurningRadius`] moves between
+//! two [`PlanarPose`]s along arcs of that radius and straight lines. Dubins
+//! paths drive forward only and are the shortest of six words (`LSL`, `RSR`,
+//! `LSR`, `RSL`, `RLR`, `LRL`); Reeds-Shepp paths may also reverse and are the
+//! shortest of the 48 words of Reeds and Shepp (1990), in the formulation of
+//! OMPL's `ReedsSheppStateSpace`.
+//!
+//! Curvature is piecewise constant: `±1/r` on arcs and zero on straights, so
+//! it jumps where segments meet. [`CurvedPath`] answers pose, gear and
+//! curvature queries by arc length and samples the path for guidance.
+//!
+//! Positions are East-North in meters and headings counter-clockwise from
+//! east, as in [`crate::mission`].
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::euler::wrap;
+use crate::si_units::{Angle, AngularVelocity, Curvature, Length, Velocity, TAU};
+
+/// Turning radius or pose that cannot define a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathError {
+    NonPositiveRadius(f64),
+    NonFinitePose,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::NonPositiveRadius(radius) => write!(f, "turning radius must be positive, got {}", radius),
+            PathError::NonFinitePose => write!(f, "pose coordinates must be finite"),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Position and heading in the horizontal plane
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlanarPose {
+    pub east: Length,
+    pub north: Length,
+    /// Counter-clockwise from east
+    pub heading: Angle,
+}
+
+impl PlanarPose {
+    pub fn new(east: Length, north: Length, heading: Angle) -> Self {
+        Self { east, north, heading }
+    }
+
+    fn raw(&self) -> [f64; 3] {
+        [*self.east.value(), *self.north.value(), *self.heading.value()]
+    }
+
+    fn from_raw([east, north, heading]: [f64; 3]) -> Self {
+        Self::new(Length::new(east), Length::new(north), Angle::new(wrap(heading)))
+    }
+}
+
+/// Smallest radius a vehicle can turn on
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TurningRadius(Length);
+
+impl TurningRadius {
+    pub fn new(radius: Length) -> Result<Self, PathError> {
+        if *radius.value() > 0.0 && radius.value().is_finite() {
+            Ok(Self(radius))
+        } else {
+            Err(PathError::NonPositiveRadius(*radius.value()))
+        }
+    }
+
+    /// Radius of a vehicle at `speed` turning at its largest yaw rate
+    pub fn from_speed(speed: Velocity, yaw_rate: AngularVelocity) -> Result<Self, PathError> {
+        Self::new(Length::new(speed.value().abs() / yaw_rate.value().abs()))
+    }
+
+    pub fn radius(&self) -> Length {
+        self.0
+    }
+
+    pub fn max_curvature(&self) -> Curvature {
+        Curvature::new(1.0 / self.0.value())
+    }
+}
+
+/// Turning direction of a segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Steering {
+    Left,
+    Straight,
+    Right,
+}
+
+impl Steering {
+    /// Heading change per unit length at unit radius
+    fn sign(self) -> f64 {
+        match self {
+            Steering::Left => 1.0,
+            Steering::Straight => 0.0,
+            Steering::Right => -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Gear {
+    Forward,
+    Reverse,
+}
+
+/// One arc or straight of a path
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PathSegment {
+    pub steering: Steering,
+    pub gear: Gear,
+    /// Distance travelled, never negative
+    pub length: Length,
+}
+
+/// Arcs and straights leaving a start pose
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurvedPath {
+    start: PlanarPose,
+    radius: TurningRadius,
+    segments: Vec<PathSegment>,
+}
+
+impl CurvedPath {
+    /// Path from `start` along `segments` given as steering and signed length
+    /// in units of the radius, negative when reversing
+    fn from_normalized(start: PlanarPose, radius: TurningRadius, segments: &[(Steering, f64)]) -> Self {
+        let r = *radius.radius().value();
+        let segments = segments
+            .iter()
+            .map(|(steering, length)| PathSegment {
+                steering: *steering,
+                gear: if *length < 0.0 { Gear::Reverse } else { Gear::Forward },
+                length: Length::new(length.abs() * r),
+            })
+            .collect();
+        Self { start, radius, segments }
+    }
+
+    pub fn start(&self) -> PlanarPose {
+        self.start
+    }
+
+    pub fn end(&self) -> PlanarPose {
+        self.pose_at(self.length())
+    }
+
+    pub fn radius(&self) -> TurningRadius {
+        self.radius
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Steering letters of the segments, such as `"LSR"`
+    pub fn word(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment.steering {
+                Steering::Left => 'L',
+                Steering::Straight => 'S',
+                Steering::Right => 'R',
+            })
+            .collect()
+    }
+
+    pub fn length(&self) -> Length {
+        Length::new(self.segments.iter().map(|segment| segment.length.value()).sum())
+    }
+
+    pub fn has_reverse(&self) -> bool {
+        self.segments.iter().any(|segment| segment.gear == Gear::Reverse)
+    }
+
+    /// Segment at arc length `s`, clamped to the path, and the distance into it
+    fn locate(&self, s: Length) -> (usize, f64) {
+        let mut s = s.value().max(0.0);
+        for (index, segment) in self.segments.iter().enumerate() {
+            if s < *segment.length.value() || index + 1 == self.segments.len() {
+                return (index, s.min(*segment.length.value()));
+            }
+            s -= segment.length.value();
+        }
+        (0, 0.0)
+    }
+
+    /// Pose after travelling `s` along the path, clamped to its ends
+    pub fn pose_at(&self, s: Length) -> PlanarPose {
+        let r = *self.radius.radius().value();
+        let (last, into) = self.locate(s);
+        let mut pose = self.start.raw();
+        for (index, segment) in self.segments[..=last].iter().enumerate() {
+            let distance = if index == last { into } else { *segment.length.value() };
+            let signed = if segment.gear == Gear::Reverse { -distance } else { distance };
+            pose = advance(pose, segment.steering, signed, r);
+        }
+        PlanarPose::from_raw(pose)
+    }
+
+    /// Signed curvature of the heading at `s`, positive turning left; zero on
+    /// straights and independent of the gear
+    pub fn curvature_at(&self, s: Length) -> Curvature {
+        match self.segments.get(self.locate(s).0) {
+            Some(segment) => Curvature::new(segment.steering.sign() / self.radius.radius().value()),
+            None => Curvature::new(0.0),
+        }
+    }
+
+    pub fn gear_at(&self, s: Length) -> Gear {
+        self.segments.get(self.locate(s).0).map_or(Gear::Forward, |segment| segment.gear)
+    }
+
+    /// Poses every `step` of arc length, both ends included
+    pub fn sample(&self, step: Length) -> Vec<PlanarPose> {
+        let (step, length) = (*step.value(), *self.length().value());
+        assert!(step > 0.0, "sampling step must be positive");
+        let count = (length / step).ceil().max(1.0) as usize;
+        (0..=count).map(|k| self.pose_at(Length::new((k as f64 * step).min(length)))).collect()
+    }
+}
+
+/// Pose after moving `distance` (negative in reverse) with `steering` on radius `r`
+fn advance([x, y, heading]: [f64; 3], steering: Steering, distance: f64, r: f64) -> [f64; 3] {
+    match steering {
+        Steering::Straight => [x + distance * heading.cos(), y + distance * heading.sin(), heading],
+        turn => {
+            let sign = turn.sign();
+            let end = heading + sign * distance / r;
+            [x + sign * r * (end.sin() - heading.sin()), y - sign * r * (end.cos() - heading.cos()), end]
+        }
+    }
+}
+
+/// Goal in the start's frame, scaled to unit radius
+fn normalize(start: &PlanarPose, goal: &PlanarPose, radius: &TurningRadius) -> Result<[f64; 3], PathError> {
+    let ([x0, y0, h0], [x1, y1, h1]) = (start.raw(), goal.raw());
+    if ![x0, y0, h0, x1, y1, h1].iter().all(|v| v.is_finite()) {
+        return Err(PathError::NonFinitePose);
+    }
+    let r = *radius.radius().value();
+    let (sin, cos) = h0.sin_cos();
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    Ok([(cos * dx + sin * dy) / r, (-sin * dx + cos * dy) / r, h1 - h0])
+}
+
+/// Shortest forward-only path from `start` to `goal`
+pub fn dubins(start: PlanarPose, goal: PlanarPose, radius: TurningRadius) -> Result<CurvedPath, PathError> {
+    use Steering::{Left as L, Right as R, Straight as S};
+
+    let [x, y, phi] = normalize(&start, &goal, &radius)?;
+    let d = x.hypot(y);
+    let theta = if d > 0.0 { y.atan2(x) } else { 0.0 };
+    let (alpha, beta) = ((-theta).rem_euclid(TAU), (phi - theta).rem_euclid(TAU));
+    let (sa, ca, sb, cb) = (alpha.sin(), alpha.cos(), beta.sin(), beta.cos());
+    let cab = (alpha - beta).cos();
+    let m = |angle: f64| angle.rem_euclid(TAU);
+
+    let mut candidates: Vec<[(Steering, f64); 3]> = Vec::with_capacity(6);
+    let lsl = 2.0 + d * d - 2.0 * cab + 2.0 * d * (sa - sb);
+    if lsl >= 0.0 {
+        let tmp = (cb - ca).atan2(d + sa - sb);
+        candidates.push([(L, m(tmp - alpha)), (S, lsl.sqrt()), (L, m(beta - tmp))]);
+    }
+    let rsr = 2.0 + d * d - 2.0 * cab + 2.0 * d * (sb - sa);
+    if rsr >= 0.0 {
+        let tmp = (ca - cb).atan2(d - sa + sb);
+        candidates.push([(R, m(alpha - tmp)), (S, rsr.sqrt()), (R, m(tmp - beta))]);
+    }
+    let lsr = -2.0 + d * d + 2.0 * cab + 2.0 * d * (sa + sb);
+    if lsr >= 0.0 {
+        let p = lsr.sqrt();
+        let tmp = (-ca - cb).atan2(d + sa + sb) - (-2.0f64).atan2(p);
+        candidates.push([(L, m(tmp - alpha)), (S, p), (R, m(tmp - beta))]);
+    }
+    let rsl = -2.0 + d * d + 2.0 * cab - 2.0 * d * (sa + sb);
+    if rsl >= 0.0 {
+        let p = rsl.sqrt();
+        let tmp = (ca + cb).atan2(d - sa - sb) - 2.0f64.atan2(p);
+        candidates.push([(R, m(alpha - tmp)), (S, p), (L, m(beta - tmp))]);
+    }
+    let rlr = (6.0 - d * d + 2.0 * cab + 2.0 * d * (sa - sb)) / 8.0;
+    if rlr.abs() <= 1.0 {
+        let p = m(TAU - rlr.acos());
+        let t = m(alpha - (ca - cb).atan2(d - sa + sb) + p / 2.0);
+        candidates.push([(R, t), (L, p), (R, m(alpha - beta - t + p))]);
+    }
+    let lrl = (6.0 - d * d + 2.0 * cab + 2.0 * d * (sb - sa)) / 8.0;
+    if lrl.abs() <= 1.0 {
+        let p = m(TAU - lrl.acos());
+        let t = m(-alpha - (ca - cb).atan2(d + sa - sb) + p / 2.0);
+        candidates.push([(L, t), (R, p), (L, m(beta - alpha - t + p))]);
+    }
+
+    let total = |word: &[(Steering, f64); 3]| word.iter().map(|(_, length)| length).sum::<f64>();
+    let best = candidates
+        .iter()
+        .min_by(|a, b| total(a).total_cmp(&total(b)))
+        .expect("LSL or RSR always exists");
+    Ok(CurvedPath::from_normalized(start, radius, best))
+}
+
+/// Tolerance on the sign tests of the Reeds-Shepp formulas
+const RS_ZERO: f64 = 1e-12;
+
+fn polar(x: f64, y: f64) -> (f64, f64) {
+    (x.hypot(y), y.atan2(x))
+}
+
+fn tau_omega(u: f64, v: f64, xi: f64, eta: f64, phi: f64) -> (f64, f64) {
+    let delta = wrap(u - v);
+    let a = u.sin() - delta.sin();
+    let b = u.cos() - delta.cos() - 1.0;
+    let t1 = (eta * a - xi * b).atan2(xi * a + eta * b);
+    let t2 = 2.0 * (delta.cos() - v.cos() - u.cos()) + 3.0;
+    let tau = if t2 < 0.0 { wrap(t1 + TAU / 2.0) } else { wrap(t1) };
+    (tau, wrap(tau - u + v - phi))
+}
+
+// The base words, with `p` forward and `m` reverse; every other word is one of
+// these under time flip, reflection or reversal of the path
+
+fn lp_sp_lp(x: f64, y: f64, phi: f64) -> Option<[f64; 3]> {
+    let (u, t) = polar(x - phi.sin(), y - 1.0 + phi.cos());
+    let v = wrap(phi - t);
+    (t >= -RS_ZERO && v >= -RS_ZERO).then_some([t, u, v])
+}
+
+fn lp_sp_rp(x: f64, y: f64, phi: f64) -> Option<[f64; 3]> {
+    let (u1, t1) = polar(x + phi.sin(), y - 1.0 - phi.cos());
+    let u1 = u1 * u1;
+    if u1 < 4.0 {
+        return None;
+    }
+    let u = (u1 - 4.0).sqrt();
+    let t = wrap(t1 + 2.0f64.atan2(u));
+    let v = wrap(t - phi);
+    (t >= -RS_ZERO && v >= -RS_ZERO).then_some([t, u, v])
+}
+
+fn lp_rm_l(x: f64, y: f64, phi: f64) -> Option<[f64; 3]> {
+    let (u1, theta) = polar(x - phi.sin(), y - 1.0 + phi.cos());
+    if u1 > 4.0 {
+        return None;
+    }
+    let u = -2.0 * (0.25 * u1).asin();
+    let t = wrap(theta + 0.5 * u + TAU / 2.0);
+    let v = wrap(phi - t + u);
+    (t >= -RS_ZERO && u <= RS_ZERO).then_some([t, u, v])
+}
+
+fn lp_rup_lum_rm(x: f64, y: f64, phi: f64) -> Option<[f64; 3]> {
+    let (xi, eta) = (x + phi.sin(), y - 1.0 - phi.cos());
+    let rho = 0.25 * (2.0 + xi.hypot(eta));
+    if rho > 1.0 {
+        return None;
+    }
+    let u = rho.acos();
+    let (t, v) = tau_omega(u, -u, xi, eta, phi);
+    (t >= -RS_ZERO && v <= RS_ZERO).then_some([t, u, v])
+}
+
+fn lp_rum_lum_rp(x: f64, y: f64, phi: f64) -> Option<[f64; 3]> {
+    let (xi, eta) = (x + phi.sin(), y - 1.0 - phi.cos());
+    let rho = (20.0 - xi * xi - eta * eta) / 16.0;
+    if !(0.0..=1.0).contains(&rho) {
+        return None;
+    }
+    let u = -rho.acos();
+    if u < -TAU / 4.0 {
+        return None;
+    }
+    let (t, v) = tau_omega(u, u, xi, eta, phi);
+    (t >= -RS_ZERO && v >= -RS_ZERO).then_some([t, u, v])
+}
+
+fn lp_rm_sm_lm(x: f64, y: f64, phi: f64) -> Option<[f64; 3]> {
+    let (rho, theta) = polar(x - phi.sin(), y - 1.0 + phi.cos());
+    if rho < 2.0 {
+        return None;
+    }
+    let r = (rho * rho - 4.0).sqrt();
+    let u = 2.0 - r;
+    let t = wrap(theta + r.atan2(-2.0));
+    let v = wrap(phi - TAU / 4.0 - t);
+    (t >= -RS_ZERO && u <= RS_ZERO && v <= RS_ZERO).then_some([t, u, v])
+}
+
+fn lp_rm_sm_rm(x: f64, y: f64, phi: f64) -> Option<[f64; 3]> {
+    let (xi, eta) = (x + phi.sin(), y - 1.0 - phi.cos());
+    let (rho, theta) = polar(-eta, xi);
+    if rho < 2.0 {
+        return None;
+    }
+    let (t, u) = (theta, 2.0 - rho);
+    let v = wrap(t + TAU / 4.0 - phi);
+    (t >= -RS_ZERO && u <= RS_ZERO && v <= RS_ZERO).then_some([t, u, v])
+}
+
+fn lp_rm_s_lm_rp(x: f64, y: f64, phi: f64) -> Option<[f64; 3]> {
+    let (xi, eta) = (x + phi.sin(), y - 1.0 - phi.cos());
+    let (rho, _) = polar(xi, eta);
+    if rho < 2.0 {
+        return None;
+    }
+    let u = 4.0 - (rho * rho - 4.0).sqrt();
+    if u > RS_ZERO {
+        return None;
+    }
+    let t = wrap(((4.0 - u) * xi - 2.0 * eta).atan2(-2.0 * xi + (u - 4.0) * eta));
+    let v = wrap(t - phi);
+    (t >= -RS_ZERO && v >= -RS_ZERO).then_some([t, u, v])
+}
+
+/// Swap left and right turns, for the reflected words
+fn reflect(word: &[(Steering, f64)]) -> Vec<(Steering, f64)> {
+    word.iter()
+        .map(|(steering, length)| {
+            let mirrored = match steering {
+                Steering::Left => Steering::Right,
+                Steering::Right => Steering::Left,
+                Steering::Straight => Steering::Straight,
+            };
+            (mirrored, *length)
+        })
+        .collect()
+}
+
+fn negate(word: &[(Steering, f64)]) -> Vec<(Steering, f64)> {
+    word.iter().map(|(steering, length)| (*steering, -length)).collect()
+}
+
+/// Every Reeds-Shepp word reaching `(x, y, phi)` from the origin at unit radius
+fn reeds_shepp_words(x: f64, y: f64, phi: f64) -> Vec<Vec<(Steering, f64)>> {
+    use Steering::{Left as L, Right as R, Straight as S};
+    const QUARTER: f64 = TAU / 4.0;
+
+    type Base = fn(f64, f64, f64) -> Option<[f64; 3]>;
+    type Word = fn([f64; 3]) -> Vec<(Steering, f64)>;
+    // Base solver, whether it is applied to the reversed path, and how its
+    // solution spells a left-first word
+    let families: [(Base, bool, Word); 11] = [
+        (lp_sp_lp, false, |[t, u, v]| vec![(L, t), (S, u), (L, v)]),
+        (lp_sp_rp, false, |[t, u, v]| vec![(L, t), (S, u), (R, v)]),
+        (lp_rm_l, false, |[t, u, v]| vec![(L, t), (R, u), (L, v)]),
+        (lp_rm_l, true, |[t, u, v]| vec![(L, v), (R, u), (L, t)]),
+        (lp_rup_lum_rm, false, |[t, u, v]| vec![(L, t), (R, u), (L, -u), (R, v)]),
+        (lp_rum_lum_rp, false, |[t, u, v]| vec![(L, t), (R, u), (L, u), (R, v)]),
+        (lp_rm_sm_lm, false, |[t, u, v]| vec![(L, t), (R, -QUARTER), (S, u), (L, v)]),
+        (lp_rm_sm_rm, false, |[t, u, v]| vec![(L, t), (R, -QUARTER), (S, u), (R, v)]),
+        (lp_rm_sm_lm, true, |[t, u, v]| vec![(L, v), (S, u), (R, -QUARTER), (L, t)]),
+        (lp_rm_sm_rm, true, |[t, u, v]| vec![(R, v), (S, u), (R, -QUARTER), (L, t)]),
+        (lp_rm_s_lm_rp, false, |[t, u, v]| vec![(L, t), (R, -QUARTER), (S, u), (L, -QUARTER), (R, v)]),
+    ];
+
+    let (sin, cos) = phi.sin_cos();
+    let (xb, yb) = (x * cos + y * sin, x * sin - y * cos);
+    let mut words = Vec::new();
+    for (base, backwards, spell) in &families {
+        let (px, py) = if *backwards { (xb, yb) } else { (x, y) };
+        // Plain, time-flipped, reflected, and both
+        for (flip, mirror) in [(false, false), (true, false), (false, true), (true, true)] {
+            let (sx, sy, sphi) = (if flip { -px } else { px }, if mirror { -py } else { py }, if flip != mirror { -phi } else { phi });
+            if let Some(solution) = base(sx, sy, sphi) {
+                let mut word = spell(solution);
+                if flip {
+                    word = negate(&word);
+                }
+                if mirror {
+                    word = reflect(&word);
+                }
+                words.push(word);
+            }
+        }
+    }
+    words
+}
+
+/// Shortest path from `start` to `goal`, reversing where that is shorter
+pub fn reeds_shepp(start: PlanarPose, goal: PlanarPose, radius: TurningRadius) -> Result<CurvedPath, PathError> {
+    let [x, y, phi] = normalize(&start, &goal, &radius)?;
+    let total = |word: &Vec<(Steering, f64)>| word.iter().map(|(_, length)| length.abs()).sum::<f64>();
+    let best = reeds_shepp_words(x, y, wrap(phi))
+        .into_iter()
+        .min_by(|a, b| total(a).total_cmp(&total(b)))
+        .expect("some CSC word always exists");
+    Ok(CurvedPath::from_normalized(start, radius, &best))
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::Sampler;
+
+    fn pose(east: f64, north: f64, heading: f64) -> PlanarPose {
+        PlanarPose::new(Length::new(east), Length::new(north), Angle::new(heading))
+    }
+
+    fn assert_reaches(path: &CurvedPath, goal: &PlanarPose) {
+        let [x, y, h] = path.end().raw();
+        let [gx, gy, gh] = goal.raw();
+        assert!((x - gx).abs() < 1e-6 && (y - gy).abs() < 1e-6 && wrap(h - gh).abs() < 1e-6, "{} {:?} → {:?}", path.word(), path.end(), goal);
+    }
+
+    #[test]
+    fn test_dubins_known_paths() {
+        let radius = TurningRadius::new(Length::new(2.0)).unwrap();
+        let straight = dubins(pose(0.0, 0.0, 0.0), pose(10.0, 0.0, 0.0), radius).unwrap();
+        assert!((straight.length().value() - 10.0).abs() < 1e-9);
+        assert!(!straight.has_reverse());
+
+        // A U-turn onto the parallel lane one diameter to the left is a half circle
+        let u_turn = dubins(pose(0.0, 0.0, 0.0), pose(0.0, 4.0, TAU / 2.0), radius).unwrap();
+        assert!((u_turn.length().value() - TAU / 2.0 * 2.0).abs() < 1e-9, "{:?}", u_turn);
+        assert_eq!(*u_turn.curvature_at(Length::new(1.0)).value(), 0.5);
+        assert_eq!(*radius.max_curvature().value(), 0.5);
+
+        let sampled = u_turn.sample(Length::new(0.5));
+        assert_eq!(sampled[0], u_turn.start());
+        assert!(sampled.iter().all(|p| (p.east.value().hypot(p.north.value() - 2.0) - 2.0).abs() < 1e-9));
+        assert_eq!(TurningRadius::new(Length::new(0.0)), Err(PathError::NonPositiveRadius(0.0)));
+        let from_rate = TurningRadius::from_speed(Velocity::new(1.5), AngularVelocity::new(0.5)).unwrap();
+        assert_eq!(*from_rate.radius().value(), 3.0);
+    }
+
+    #[test]
+    fn test_random_paths_reach_goal() {
+        let mut sampler = Sampler::new(7);
+        let radius = TurningRadius::new(Length::new(1.5)).unwrap();
+        for _ in 0..500 {
+            let start = pose(sampler.range(-5.0, 5.0), sampler.range(-5.0, 5.0), sampler.range(-TAU / 2.0, TAU / 2.0));
+            let goal = pose(sampler.range(-5.0, 5.0), sampler.range(-5.0, 5.0), sampler.range(-TAU / 2.0, TAU / 2.0));
+            let forward = dubins(start, goal, radius).unwrap();
+            let reversing = reeds_shepp(start, goal, radius).unwrap();
+            assert_reaches(&forward, &goal);
+            assert_reaches(&reversing, &goal);
+            assert!(!forward.has_reverse());
+            // Reversing can only help, and neither beats the straight line
+            let chord = (goal.east.value() - start.east.value()).hypot(goal.north.value() - start.north.value());
+            assert!(*reversing.length().value() <= forward.length().value() + 1e-9);
+            assert!(*reversing.length().value() >= chord - 1e-9);
+            // Reeds-Shepp paths are symmetric under swapping the ends
+            let back = reeds_shepp(goal, start, radius).unwrap();
+            assert!((back.length().value() - reversing.length().value()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_reeds_shepp_reverses() {
+        let radius = TurningRadius::new(Length::new(1.0)).unwrap();
+        let backing = reeds_shepp(pose(0.0, 0.0, 0.0), pose(-3.0, 0.0, 0.0), radius).unwrap();
+        assert!((backing.length().value() - 3.0).abs() < 1e-9);
+        assert_eq!(backing.gear_at(Length::new(1.0)), Gear::Reverse);
+        let forward = dubins(pose(0.0, 0.0, 0.0), pose(-3.0, 0.0, 0.0), radius).unwrap();
+        // Forward only, the vehicle loops around once
+        assert!((forward.length().value() - (3.0 + TAU)).abs() < 1e-9, "{:?}", forward);
+
+        // Parallel parking: sideways needs reversal to be short
+        let parking = reeds_shepp(pose(0.0, 0.0, 0.0), pose(0.0, 1.0, 0.0), radius).unwrap();
+        assert!(parking.has_reverse(), "{:?}", parking);
+        assert_reaches(&parking, &pose(0.0, 1.0, 0.0));
+        assert_eq!(reeds_shepp(pose(f64::NAN, 0.0, 0.0), pose(0.0, 1.0, 0.0), radius), Err(PathError::NonFinitePose));
+    }
+}
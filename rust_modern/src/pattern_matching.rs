@@ -2,8 +2,9 @@
 //
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::ga_term::{GATerm, Grade, Scalar, BladeTerm, Index};
+use crate::ga_term::{GATerm, Grade, Scalar, BladeTerm, BasisIndex, Real};
 use crate::grade_indexed::GradeIndexed;
+use crate::summation::{sum_with, Summation};
 
 /// Pattern matching utilities using Rust's match expressions
 ///
@@ -21,9 +22,9 @@ pub fn match_gaterm<T, R, SF, VF, BF, TF, MF>(
 ) -> R
 where
     SF: FnOnce(&Scalar<T>) -> R,
-    VF: FnOnce(&Vec<(Index, T)>) -> R,
-    BF: FnOnce(&Vec<(Index, Index, T)>) -> R,
-    TF: FnOnce(&Vec<(Index, Index, Index, T)>) -> R,
+    VF: FnOnce(&Vec<(BasisIndex, T)>) -> R,
+    BF: FnOnce(&Vec<(BasisIndex, BasisIndex, T)>) -> R,
+    TF: FnOnce(&Vec<(BasisIndex, BasisIndex, BasisIndex, T)>) -> R,
     MF: FnOnce(&Vec<BladeTerm<T>>) -> R,
 {
     match term {
@@ -38,9 +39,9 @@ where
 /// Simplified visitor pattern for GATerm
 pub trait GATermVisitor<T, R> {
     fn visit_scalar(&self, scalar: &Scalar<T>) -> R;
-    fn visit_vector(&self, vector: &Vec<(Index, T)>) -> R;
-    fn visit_bivector(&self, bivector: &Vec<(Index, Index, T)>) -> R;
-    fn visit_trivector(&self, trivector: &Vec<(Index, Index, Index, T)>) -> R;
+    fn visit_vector(&self, vector: &Vec<(BasisIndex, T)>) -> R;
+    fn visit_bivector(&self, bivector: &Vec<(BasisIndex, BasisIndex, T)>) -> R;
+    fn visit_trivector(&self, trivector: &Vec<(BasisIndex, BasisIndex, BasisIndex, T)>) -> R;
     fn visit_multivector(&self, multivector: &Vec<BladeTerm<T>>) -> R;
 }
 
@@ -55,78 +56,150 @@ pub fn visit_gaterm<T, R, V: GATermVisitor<T, R>>(term: &GATerm<T>, visitor: &V)
     }
 }
 
+/// Visitor that can change the components of a GATerm in place
+///
+/// Coefficients can be rescaled and components pushed, removed or reordered,
+/// but the grade of the term stays the same.
+pub trait GATermVisitorMut<T, R> {
+    fn visit_scalar_mut(&mut self, scalar: &mut Scalar<T>) -> R;
+    fn visit_vector_mut(&mut self, vector: &mut Vec<(BasisIndex, T)>) -> R;
+    fn visit_bivector_mut(&mut self, bivector: &mut Vec<(BasisIndex, BasisIndex, T)>) -> R;
+    fn visit_trivector_mut(&mut self, trivector: &mut Vec<(BasisIndex, BasisIndex, BasisIndex, T)>) -> R;
+    fn visit_multivector_mut(&mut self, multivector: &mut Vec<BladeTerm<T>>) -> R;
+}
+
+/// Apply a mutating visitor to a GATerm
+pub fn visit_gaterm_mut<T, R, V: GATermVisitorMut<T, R>>(term: &mut GATerm<T>, visitor: &mut V) -> R {
+    match term {
+        GATerm::Scalar(scalar) => visitor.visit_scalar_mut(scalar),
+        GATerm::Vector(vector) => visitor.visit_vector_mut(vector),
+        GATerm::Bivector(bivector) => visitor.visit_bivector_mut(bivector),
+        GATerm::Trivector(trivector) => visitor.visit_trivector_mut(trivector),
+        GATerm::Multivector(multivector) => visitor.visit_multivector_mut(multivector),
+    }
+}
+
+/// Visitor that takes ownership of the components of a GATerm
+///
+/// Passes that rebuild a term, possibly of another grade or coefficient type,
+/// can reuse its allocations instead of cloning them.
+pub trait GATermIntoVisitor<T, R> {
+    fn visit_scalar(&mut self, scalar: Scalar<T>) -> R;
+    fn visit_vector(&mut self, vector: Vec<(BasisIndex, T)>) -> R;
+    fn visit_bivector(&mut self, bivector: Vec<(BasisIndex, BasisIndex, T)>) -> R;
+    fn visit_trivector(&mut self, trivector: Vec<(BasisIndex, BasisIndex, BasisIndex, T)>) -> R;
+    fn visit_multivector(&mut self, multivector: Vec<BladeTerm<T>>) -> R;
+}
+
+impl<T> GATerm<T> {
+    /// Consume the term, handing its components to an owning visitor
+    pub fn into_visit<R, V: GATermIntoVisitor<T, R>>(self, visitor: &mut V) -> R {
+        match self {
+            GATerm::Scalar(scalar) => visitor.visit_scalar(scalar),
+            GATerm::Vector(vector) => visitor.visit_vector(vector),
+            GATerm::Bivector(bivector) => visitor.visit_bivector(bivector),
+            GATerm::Trivector(trivector) => visitor.visit_trivector(trivector),
+            GATerm::Multivector(multivector) => visitor.visit_multivector(multivector),
+        }
+    }
+}
+
 /// Type-safe operations using pattern matching
 pub mod operations {
     use super::*;
 
-    /// Addition of two GA terms (same grade only)
-    pub fn add<T>(lhs: &GATerm<T>, rhs: &GATerm<T>) -> Option<GATerm<T>>
+    /// Accumulate `src` into `dst`, adding coefficients of matching blades
+    fn merge_components<C: Clone>(dst: &mut Vec<C>, src: &[C], same_blade: impl Fn(&C, &C) -> bool, accumulate: impl Fn(&mut C, &C)) {
+        for component in src {
+            match dst.iter_mut().find(|existing| same_blade(existing, component)) {
+                Some(existing) => accumulate(existing, component),
+                None => dst.push(component.clone()),
+            }
+        }
+    }
+
+    /// In-place addition of a GA term of the same grade
+    ///
+    /// Returns `false`, leaving `lhs` untouched, if the grades differ.
+    pub fn add_assign<T>(lhs: &mut GATerm<T>, rhs: &GATerm<T>) -> bool
     where
-        T: Clone + std::ops::Add<Output = T> + Default,
+        T: Clone + std::ops::Add<Output = T>,
     {
-        // Check if both terms have the same grade
-        if lhs.grade() != rhs.grade() {
-            return None; // Cannot add different grades
+        fn sum<T: Clone + std::ops::Add<Output = T>>(a: &mut T, b: &T) {
+            *a = a.clone() + b.clone();
         }
 
         match (lhs, rhs) {
-            (GATerm::Scalar(s1), GATerm::Scalar(s2)) => {
-                Some(GATerm::scalar(s1.value.clone() + s2.value.clone()))
-            }
+            (GATerm::Scalar(s1), GATerm::Scalar(s2)) => sum(&mut s1.value, &s2.value),
             (GATerm::Vector(v1), GATerm::Vector(v2)) => {
-                let mut result = v1.clone();
-                for (idx, coeff) in v2.iter() {
-                    if let Some((_, existing_coeff)) = result.iter_mut().find(|(i, _)| i == idx) {
-                        *existing_coeff = existing_coeff.clone() + coeff.clone();
-                    } else {
-                        result.push((*idx, coeff.clone()));
-                    }
-                }
-                Some(GATerm::vector(result))
+                merge_components(v1, v2, |a, b| a.0 == b.0, |a, b| sum(&mut a.1, &b.1))
             }
             (GATerm::Bivector(b1), GATerm::Bivector(b2)) => {
-                let mut result = b1.clone();
-                for (i1, i2, coeff) in b2.iter() {
-                    if let Some((_, _, existing_coeff)) = result
-                        .iter_mut()
-                        .find(|(j1, j2, _)| j1 == i1 && j2 == i2)
-                    {
-                        *existing_coeff = existing_coeff.clone() + coeff.clone();
-                    } else {
-                        result.push((*i1, *i2, coeff.clone()));
-                    }
-                }
-                Some(GATerm::bivector(result))
-            }
-            (GATerm::Trivector(t1), GATerm::Trivector(t2)) => {
-                let mut result = t1.clone();
-                for (i1, i2, i3, coeff) in t2.iter() {
-                    if let Some((_, _, _, existing_coeff)) = result
-                        .iter_mut()
-                        .find(|(j1, j2, j3, _)| j1 == i1 && j2 == i2 && j3 == i3)
-                    {
-                        *existing_coeff = existing_coeff.clone() + coeff.clone();
-                    } else {
-                        result.push((*i1, *i2, *i3, coeff.clone()));
-                    }
-                }
-                Some(GATerm::trivector(result))
+                merge_components(b1, b2, |a, b| (a.0, a.1) == (b.0, b.1), |a, b| sum(&mut a.2, &b.2))
             }
+            (GATerm::Trivector(t1), GATerm::Trivector(t2)) => merge_components(
+                t1,
+                t2,
+                |a, b| (a.0, a.1, a.2) == (b.0, b.1, b.2),
+                |a, b| sum(&mut a.3, &b.3),
+            ),
             (GATerm::Multivector(m1), GATerm::Multivector(m2)) => {
-                let mut result = m1.clone();
-                for term in m2.iter() {
-                    if let Some(existing_term) = result
-                        .iter_mut()
-                        .find(|t| t.indices == term.indices)
-                    {
-                        existing_term.coefficient = existing_term.coefficient.clone() + term.coefficient.clone();
-                    } else {
-                        result.push(term.clone());
-                    }
-                }
-                Some(GATerm::multivector(result))
+                merge_components(m1, m2, |a, b| a.indices == b.indices, |a, b| sum(&mut a.coefficient, &b.coefficient))
             }
-            _ => None,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Addition consuming the left operand, avoiding a copy of its components
+    pub fn add_owned<T>(mut lhs: GATerm<T>, rhs: &GATerm<T>) -> Option<GATerm<T>>
+    where
+        T: Clone + std::ops::Add<Output = T>,
+    {
+        add_assign(&mut lhs, rhs).then_some(lhs)
+    }
+
+    /// Addition of two GA terms (same grade only)
+    pub fn add<T>(lhs: &GATerm<T>, rhs: &GATerm<T>) -> Option<GATerm<T>>
+    where
+        T: Clone + std::ops::Add<Output = T> + Default,
+    {
+        if std::mem::discriminant(lhs) != std::mem::discriminant(rhs) {
+            return None; // Cannot add different grades
+        }
+        add_owned(lhs.clone(), rhs)
+    }
+
+    /// Difference of two GA terms (same grade only)
+    pub fn sub<T>(lhs: &GATerm<T>, rhs: &GATerm<T>) -> Option<GATerm<T>>
+    where
+        T: Clone + std::ops::Sub<Output = T> + Default,
+    {
+        combinators::zip_with(lhs, rhs, combinators::ZipPolicy::Union, |a, b| a.clone() - b.clone())
+    }
+
+    /// Euclidean scalar product of two terms of the same grade
+    ///
+    /// Sums the products of coefficients of matching blades, treating the basis
+    /// as orthonormal with positive signature. Returns `None` if the grades differ.
+    pub fn dot<T: Real>(lhs: &GATerm<T>, rhs: &GATerm<T>) -> Option<T> {
+        let products = combinators::zip_with(lhs, rhs, combinators::ZipPolicy::Intersection, |a, b| *a * *b)?;
+        Some(combinators::sum_by(&products, Summation::default(), |c| *c))
+    }
+
+    /// In-place scalar multiplication
+    pub fn scalar_multiply_assign<T, S>(scalar: S, term: &mut GATerm<T>)
+    where
+        T: Clone + std::ops::Mul<S, Output = T>,
+        S: Clone,
+    {
+        let scale = |coeff: &mut T| *coeff = coeff.clone() * scalar.clone();
+        match term {
+            GATerm::Scalar(s) => scale(&mut s.value),
+            GATerm::Vector(v) => v.iter_mut().for_each(|(_, coeff)| scale(coeff)),
+            GATerm::Bivector(b) => b.iter_mut().for_each(|(_, _, coeff)| scale(coeff)),
+            GATerm::Trivector(t) => t.iter_mut().for_each(|(_, _, _, coeff)| scale(coeff)),
+            GATerm::Multivector(m) => m.iter_mut().for_each(|term| scale(&mut term.coefficient)),
         }
     }
 
@@ -136,88 +209,97 @@ pub mod operations {
         T: Clone + std::ops::Mul<S, Output = T>,
         S: Clone,
     {
+        let mut result = term.clone();
+        scalar_multiply_assign(scalar, &mut result);
+        result
+    }
+
+    /// Get norm of a GA term
+    pub fn norm<T: Real>(term: &GATerm<T>) -> T {
+        norm_with(term, Summation::default())
+    }
+
+    /// Norm with an explicit summation mode for the sum of squares
+    pub fn norm_with<T: Real>(term: &GATerm<T>, mode: Summation) -> T {
         match term {
-            GATerm::Scalar(s) => GATerm::scalar(s.value.clone() * scalar),
-            GATerm::Vector(v) => {
-                let result: Vec<(Index, T)> = v
-                    .iter()
-                    .map(|(idx, coeff)| (*idx, coeff.clone() * scalar.clone()))
-                    .collect();
-                GATerm::vector(result)
-            }
-            GATerm::Bivector(b) => {
-                let result: Vec<(Index, Index, T)> = b
-                    .iter()
-                    .map(|(i1, i2, coeff)| (*i1, *i2, coeff.clone() * scalar.clone()))
-                    .collect();
-                GATerm::bivector(result)
-            }
-            GATerm::Trivector(t) => {
-                let result: Vec<(Index, Index, Index, T)> = t
-                    .iter()
-                    .map(|(i1, i2, i3, coeff)| (*i1, *i2, *i3, coeff.clone() * scalar.clone()))
-                    .collect();
-                GATerm::trivector(result)
-            }
-            GATerm::Multivector(m) => {
-                let result: Vec<BladeTerm<T>> = m
-                    .iter()
-                    .map(|term| BladeTerm::new(
-                        term.indices.clone(),
-                        term.coefficient.clone() * scalar.clone()
-                    ))
-                    .collect();
-                GATerm::multivector(result)
+            GATerm::Scalar(s) => s.value.abs(),
+            _ => combinators::sum_by(term, mode, |coeff| *coeff * *coeff).sqrt(),
+        }
+    }
+
+    /// Sort blade indices into ascending order, returning the sign flip, or `None`
+    /// if an index repeats (the wedge product of a vector with itself vanishes)
+    fn canonical_blade(mut indices: Vec<BasisIndex>) -> Option<(Vec<BasisIndex>, bool)> {
+        let mut negate = false;
+        for i in 1..indices.len() {
+            let mut j = i;
+            while j > 0 && indices[j - 1] > indices[j] {
+                indices.swap(j - 1, j);
+                negate = !negate;
+                j -= 1;
             }
         }
+        if indices.windows(2).any(|pair| pair[0] == pair[1]) {
+            None
+        } else {
+            Some((indices, negate))
+        }
     }
 
-    /// Get norm of a GA term
-    pub fn norm<T>(term: &GATerm<T>) -> T
+    fn simplify_with<T, P>(term: &GATerm<T>, keep: P) -> GATerm<T>
     where
-        T: Clone + std::ops::Add<Output = T> + std::ops::Mul<Output = T> + From<f64>,
-        f64: From<T>,
+        T: Copy + std::ops::Add<Output = T> + std::ops::Neg<Output = T>,
+        P: Fn(&T) -> bool,
     {
+        let blades: Vec<(Vec<BasisIndex>, T)> = match term {
+            GATerm::Scalar(s) => return GATerm::scalar(s.value),
+            GATerm::Vector(v) => v.iter().map(|&(i, c)| (vec![i], c)).collect(),
+            GATerm::Bivector(b) => b.iter().map(|&(i, j, c)| (vec![i, j], c)).collect(),
+            GATerm::Trivector(t) => t.iter().map(|&(i, j, k, c)| (vec![i, j, k], c)).collect(),
+            GATerm::Multivector(m) => m.iter().map(|term| (term.indices.clone(), term.coefficient)).collect(),
+        };
+
+        // Canonical order: by grade, then lexicographically by indices
+        let mut merged: std::collections::BTreeMap<(usize, Vec<BasisIndex>), T> = std::collections::BTreeMap::new();
+        for (indices, coefficient) in blades {
+            let Some((indices, negate)) = canonical_blade(indices) else { continue };
+            let coefficient = if negate { -coefficient } else { coefficient };
+            merged
+                .entry((indices.len(), indices))
+                .and_modify(|existing| *existing = *existing + coefficient)
+                .or_insert(coefficient);
+        }
+        let blades = merged.into_iter().filter(|(_, c)| keep(c)).map(|((_, indices), c)| (indices, c));
+
         match term {
-            GATerm::Scalar(s) => {
-                let val: f64 = s.value.clone().into();
-                T::from(val.abs())
-            }
-            GATerm::Vector(v) => {
-                let sum: T = v
-                    .iter()
-                    .map(|(_, coeff)| coeff.clone() * coeff.clone())
-                    .fold(T::from(0.0), |acc, x| acc + x);
-                let sum_f64: f64 = sum.into();
-                T::from(sum_f64.sqrt())
-            }
-            GATerm::Bivector(b) => {
-                let sum: T = b
-                    .iter()
-                    .map(|(_, _, coeff)| coeff.clone() * coeff.clone())
-                    .fold(T::from(0.0), |acc, x| acc + x);
-                let sum_f64: f64 = sum.into();
-                T::from(sum_f64.sqrt())
-            }
-            GATerm::Trivector(t) => {
-                let sum: T = t
-                    .iter()
-                    .map(|(_, _, _, coeff)| coeff.clone() * coeff.clone())
-                    .fold(T::from(0.0), |acc, x| acc + x);
-                let sum_f64: f64 = sum.into();
-                T::from(sum_f64.sqrt())
-            }
-            GATerm::Multivector(m) => {
-                let sum: T = m
-                    .iter()
-                    .map(|term| term.coefficient.clone() * term.coefficient.clone())
-                    .fold(T::from(0.0), |acc, x| acc + x);
-                let sum_f64: f64 = sum.into();
-                T::from(sum_f64.sqrt())
-            }
+            GATerm::Scalar(_) => unreachable!("scalars are returned above"),
+            GATerm::Vector(_) => GATerm::Vector(blades.map(|(i, c)| (i[0], c)).collect()),
+            GATerm::Bivector(_) => GATerm::Bivector(blades.map(|(i, c)| (i[0], i[1], c)).collect()),
+            GATerm::Trivector(_) => GATerm::Trivector(blades.map(|(i, c)| (i[0], i[1], i[2], c)).collect()),
+            GATerm::Multivector(_) => GATerm::multivector(blades.map(|(i, c)| BladeTerm::from_basis(i, c)).collect()),
         }
     }
 
+    /// Canonicalize a GA term, pruning coefficients with |c| < epsilon
+    ///
+    /// Blade indices are sorted (flipping the sign for odd permutations), blades with
+    /// a repeated index are dropped, duplicate blades are merged and the result is
+    /// ordered by grade and indices. Scalars are returned unchanged.
+    pub fn simplify<T>(term: &GATerm<T>, epsilon: T) -> GATerm<T>
+    where
+        T: Copy + PartialOrd + std::ops::Add<Output = T> + std::ops::Neg<Output = T>,
+    {
+        simplify_with(term, |c| *c >= epsilon || *c <= -epsilon)
+    }
+
+    /// Canonicalize a GA term, pruning only coefficients that are exactly zero
+    pub fn simplify_exact<T>(term: &GATerm<T>) -> GATerm<T>
+    where
+        T: Copy + PartialEq + Default + std::ops::Add<Output = T> + std::ops::Neg<Output = T>,
+    {
+        simplify_with(term, |c| *c != T::default())
+    }
+
     /// Convert GA term to string representation
     pub fn to_string<T>(term: &GATerm<T>) -> String
     where
@@ -228,21 +310,21 @@ pub mod operations {
             GATerm::Vector(v) => {
                 let components: Vec<String> = v
                     .iter()
-                    .map(|(idx, coeff)| format!("e{}:{}", idx, coeff))
+                    .map(|(idx, coeff)| format!("{}:{}", idx, coeff))
                     .collect();
                 format!("Vector({})", components.join(", "))
             }
             GATerm::Bivector(b) => {
                 let components: Vec<String> = b
                     .iter()
-                    .map(|(i1, i2, coeff)| format!("e{}e{}:{}", i1, i2, coeff))
+                    .map(|(i1, i2, coeff)| format!("{}{}:{}", i1, i2, coeff))
                     .collect();
                 format!("Bivector({})", components.join(", "))
             }
             GATerm::Trivector(t) => {
                 let components: Vec<String> = t
                     .iter()
-                    .map(|(i1, i2, i3, coeff)| format!("e{}e{}e{}:{}", i1, i2, i3, coeff))
+                    .map(|(i1, i2, i3, coeff)| format!("{}{}{}:{}", i1, i2, i3, coeff))
                     .collect();
                 format!("Trivector({})", components.join(", "))
             }
@@ -250,7 +332,7 @@ pub mod operations {
                 let components: Vec<String> = m
                     .iter()
                     .map(|term| {
-                        let indices: Vec<String> = term.indices.iter().map(|i| format!("e{}", i)).collect();
+                        let indices: Vec<String> = term.indices.iter().map(BasisIndex::to_string).collect();
                         format!("{}:{}", indices.join(""), term.coefficient)
                     })
                     .collect();
@@ -273,30 +355,30 @@ pub mod combinators {
         match term {
             GATerm::Scalar(s) => GATerm::scalar(f(&s.value)),
             GATerm::Vector(v) => {
-                let result: Vec<(Index, U)> = v
+                let result: Vec<(BasisIndex, U)> = v
                     .iter()
                     .map(|(idx, coeff)| (*idx, f(coeff)))
                     .collect();
-                GATerm::vector(result)
+                GATerm::Vector(result)
             }
             GATerm::Bivector(b) => {
-                let result: Vec<(Index, Index, U)> = b
+                let result: Vec<(BasisIndex, BasisIndex, U)> = b
                     .iter()
                     .map(|(i1, i2, coeff)| (*i1, *i2, f(coeff)))
                     .collect();
-                GATerm::bivector(result)
+                GATerm::Bivector(result)
             }
             GATerm::Trivector(t) => {
-                let result: Vec<(Index, Index, Index, U)> = t
+                let result: Vec<(BasisIndex, BasisIndex, BasisIndex, U)> = t
                     .iter()
                     .map(|(i1, i2, i3, coeff)| (*i1, *i2, *i3, f(coeff)))
                     .collect();
-                GATerm::trivector(result)
+                GATerm::Trivector(result)
             }
             GATerm::Multivector(m) => {
                 let result: Vec<BladeTerm<U>> = m
                     .iter()
-                    .map(|term| BladeTerm::new(term.indices.clone(), f(&term.coefficient)))
+                    .map(|term| BladeTerm::from_basis(term.indices.clone(), f(&term.coefficient)))
                     .collect();
                 GATerm::multivector(result)
             }
@@ -318,28 +400,28 @@ pub mod combinators {
                 }
             }
             GATerm::Vector(v) => {
-                let result: Vec<(Index, T)> = v
+                let result: Vec<(BasisIndex, T)> = v
                     .iter()
                     .filter(|(_, coeff)| predicate(coeff))
                     .map(|(idx, coeff)| (*idx, coeff.clone()))
                     .collect();
-                GATerm::vector(result)
+                GATerm::Vector(result)
             }
             GATerm::Bivector(b) => {
-                let result: Vec<(Index, Index, T)> = b
+                let result: Vec<(BasisIndex, BasisIndex, T)> = b
                     .iter()
                     .filter(|(_, _, coeff)| predicate(coeff))
                     .map(|(i1, i2, coeff)| (*i1, *i2, coeff.clone()))
                     .collect();
-                GATerm::bivector(result)
+                GATerm::Bivector(result)
             }
             GATerm::Trivector(t) => {
-                let result: Vec<(Index, Index, Index, T)> = t
+                let result: Vec<(BasisIndex, BasisIndex, BasisIndex, T)> = t
                     .iter()
                     .filter(|(_, _, _, coeff)| predicate(coeff))
                     .map(|(i1, i2, i3, coeff)| (*i1, *i2, *i3, coeff.clone()))
                     .collect();
-                GATerm::trivector(result)
+                GATerm::Trivector(result)
             }
             GATerm::Multivector(m) => {
                 let result: Vec<BladeTerm<T>> = m
@@ -352,18 +434,73 @@ pub mod combinators {
         }
     }
 
+    /// Which blades [`zip_with`] visits
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ZipPolicy {
+        /// Blades of either term; a blade missing from one side reads as `T::default()`
+        Union,
+        /// Only blades present in both terms
+        Intersection,
+    }
+
+    /// Combine two terms of the same grade blade by blade
+    ///
+    /// Blades are matched on their indices as stored. The result lists the blades
+    /// of `lhs` in order, followed under [`ZipPolicy::Union`] by those only in
+    /// `rhs`. Returns `None` if the grades differ.
+    pub fn zip_with<T, U, F>(lhs: &GATerm<T>, rhs: &GATerm<T>, policy: ZipPolicy, f: F) -> Option<GATerm<U>>
+    where
+        T: Default,
+        F: Fn(&T, &T) -> U,
+    {
+        if std::mem::discriminant(lhs) != std::mem::discriminant(rhs) {
+            return None;
+        }
+        let zero = T::default();
+        let union = policy == ZipPolicy::Union;
+        let mut blades: Vec<_> = lhs
+            .components()
+            .filter_map(|(blade, a)| match rhs.components().find(|(other, _)| *other == blade) {
+                Some((_, b)) => Some((blade, f(a, b))),
+                None => union.then(|| (blade, f(a, &zero))),
+            })
+            .collect();
+        if union {
+            let rhs_only = rhs.components().filter(|(blade, _)| lhs.components().all(|(other, _)| other != *blade));
+            blades.extend(rhs_only.map(|(blade, b)| (blade, f(&zero, b))));
+        }
+
+        let blades = blades.into_iter();
+        Some(match lhs {
+            GATerm::Scalar(_) => GATerm::scalar(blades.map(|(_, c)| c).next()?),
+            GATerm::Vector(_) => GATerm::Vector(blades.map(|(b, c)| (b.indices()[0], c)).collect()),
+            GATerm::Bivector(_) => {
+                GATerm::Bivector(blades.map(|(b, c)| (b.indices()[0], b.indices()[1], c)).collect())
+            }
+            GATerm::Trivector(_) => {
+                GATerm::Trivector(blades.map(|(b, c)| (b.indices()[0], b.indices()[1], b.indices()[2], c)).collect())
+            }
+            GATerm::Multivector(_) => {
+                GATerm::multivector(blades.map(|(b, c)| BladeTerm::from_basis(b.indices().to_vec(), c)).collect())
+            }
+        })
+    }
+
     /// Fold over GA term components
     pub fn fold<T, Acc, F>(term: &GATerm<T>, initial: Acc, f: F) -> Acc
     where
         F: Fn(Acc, &T) -> Acc,
     {
-        match term {
-            GATerm::Scalar(s) => f(initial, &s.value),
-            GATerm::Vector(v) => v.iter().fold(initial, |acc, (_, coeff)| f(acc, coeff)),
-            GATerm::Bivector(b) => b.iter().fold(initial, |acc, (_, _, coeff)| f(acc, coeff)),
-            GATerm::Trivector(t) => t.iter().fold(initial, |acc, (_, _, _, coeff)| f(acc, coeff)),
-            GATerm::Multivector(m) => m.iter().fold(initial, |acc, term| f(acc, &term.coefficient)),
-        }
+        term.components().fold(initial, |acc, (_, coeff)| f(acc, coeff))
+    }
+
+    /// Sum `f` over the coefficients, in the given summation mode
+    pub fn sum_by<T, F>(term: &GATerm<T>, mode: Summation, f: F) -> T
+    where
+        T: Real,
+        F: Fn(&T) -> T,
+    {
+        sum_with(term.components().map(|(_, coeff)| f(coeff)), mode)
     }
 }
 
@@ -401,6 +538,106 @@ mod tests {
         assert_eq!(vector_result, "Got vector with 2 components");
     }
 
+    /// Rescales every coefficient and drops those below a threshold
+    struct ScaleAndPrune {
+        factor: f64,
+        threshold: f64,
+        pruned: usize,
+    }
+
+    impl ScaleAndPrune {
+        fn retain<C>(&mut self, components: &mut Vec<C>, coeff: impl Fn(&mut C) -> &mut f64) {
+            let before = components.len();
+            components.iter_mut().for_each(|c| *coeff(c) *= self.factor);
+            components.retain_mut(|c| coeff(c).abs() >= self.threshold);
+            self.pruned += before - components.len();
+        }
+    }
+
+    impl GATermVisitorMut<f64, ()> for ScaleAndPrune {
+        fn visit_scalar_mut(&mut self, scalar: &mut Scalar<f64>) {
+            scalar.value *= self.factor;
+        }
+        fn visit_vector_mut(&mut self, vector: &mut Vec<(BasisIndex, f64)>) {
+            self.retain(vector, |c| &mut c.1);
+        }
+        fn visit_bivector_mut(&mut self, bivector: &mut Vec<(BasisIndex, BasisIndex, f64)>) {
+            self.retain(bivector, |c| &mut c.2);
+        }
+        fn visit_trivector_mut(&mut self, trivector: &mut Vec<(BasisIndex, BasisIndex, BasisIndex, f64)>) {
+            self.retain(trivector, |c| &mut c.3);
+        }
+        fn visit_multivector_mut(&mut self, multivector: &mut Vec<BladeTerm<f64>>) {
+            self.retain(multivector, |c| &mut c.coefficient);
+        }
+    }
+
+    /// Rewrites any term as a list of blades, moving the coefficients
+    struct IntoBlades;
+
+    impl<T> GATermIntoVisitor<T, Vec<BladeTerm<T>>> for IntoBlades {
+        fn visit_scalar(&mut self, scalar: Scalar<T>) -> Vec<BladeTerm<T>> {
+            vec![BladeTerm::from_basis(vec![], scalar.value)]
+        }
+        fn visit_vector(&mut self, vector: Vec<(BasisIndex, T)>) -> Vec<BladeTerm<T>> {
+            vector.into_iter().map(|(i, c)| BladeTerm::from_basis(vec![i], c)).collect()
+        }
+        fn visit_bivector(&mut self, bivector: Vec<(BasisIndex, BasisIndex, T)>) -> Vec<BladeTerm<T>> {
+            bivector.into_iter().map(|(i, j, c)| BladeTerm::from_basis(vec![i, j], c)).collect()
+        }
+        fn visit_trivector(&mut self, trivector: Vec<(BasisIndex, BasisIndex, BasisIndex, T)>) -> Vec<BladeTerm<T>> {
+            trivector.into_iter().map(|(i, j, k, c)| BladeTerm::from_basis(vec![i, j, k], c)).collect()
+        }
+        fn visit_multivector(&mut self, multivector: Vec<BladeTerm<T>>) -> Vec<BladeTerm<T>> {
+            multivector
+        }
+    }
+
+    #[test]
+    fn test_mutable_and_owning_visitors() {
+        let mut pass = ScaleAndPrune { factor: 1e-3, threshold: 1e-6, pruned: 0 };
+        let mut bivector = GATerm::bivector(vec![(1, 2, 2000.0), (2, 3, 1e-4)]);
+        visit_gaterm_mut(&mut bivector, &mut pass);
+        assert_eq!(bivector, GATerm::bivector(vec![(1, 2, 2.0)]));
+        let mut scalar = GATerm::scalar(500.0);
+        visit_gaterm_mut(&mut scalar, &mut pass);
+        assert_eq!(scalar, GATerm::scalar(0.5));
+        assert_eq!(pass.pruned, 1);
+
+        // Owned coefficients are moved, so non-Clone types pass through
+        let term = GATerm::trivector(vec![(1, 2, 3, String::from("volume"))]);
+        let blades = term.into_visit(&mut IntoBlades);
+        assert_eq!(blades, vec![BladeTerm::new(vec![1, 2, 3], String::from("volume"))]);
+        assert_eq!(GATerm::scalar(1.5).into_visit(&mut IntoBlades), vec![BladeTerm::new(vec![], 1.5)]);
+    }
+
+    #[test]
+    fn test_simplify() {
+        let bivector = GATerm::bivector(vec![(2, 1, 1.0), (1, 2, 3.0), (1, 1, 5.0), (2, 3, 1e-12)]);
+        assert_eq!(simplify(&bivector, 1e-9), GATerm::bivector(vec![(1, 2, 2.0)]));
+        assert_eq!(simplify_exact(&bivector), GATerm::bivector(vec![(1, 2, 2.0), (2, 3, 1e-12)]));
+
+        let multivector = GATerm::multivector(vec![
+            BladeTerm::new(vec![3, 1], 1.0),
+            BladeTerm::new(vec![], 2.0),
+            BladeTerm::new(vec![1, 3], 1.0),
+            BladeTerm::new(vec![2], 4.0),
+        ]);
+        assert_eq!(
+            simplify_exact(&multivector),
+            GATerm::multivector(vec![BladeTerm::new(vec![], 2.0), BladeTerm::new(vec![2], 4.0)])
+        );
+
+        // Repeated additions stay bounded once simplified
+        let mut accumulated = GATerm::vector(vec![(1, 1.0)]);
+        for _ in 0..10 {
+            let step = GATerm::vector(vec![(2, 0.5), (1, -0.1)]);
+            accumulated = simplify(&add(&accumulated, &step).unwrap(), 1e-12);
+        }
+        // e1 cancels to rounding error and is pruned
+        assert_eq!(accumulated, GATerm::vector(vec![(2, 5.0)]));
+    }
+
     #[test]
     fn test_addition() {
         let s1 = GATerm::scalar(2.0);
@@ -442,8 +679,32 @@ mod tests {
     #[test]
     fn test_norm() {
         let vector = GATerm::vector(vec![(1, 3.0), (2, 4.0)]);
-        let n = norm(&vector);
+        let n: f64 = norm(&vector);
         assert!((n - 5.0).abs() < 1e-10);
+        assert_eq!(norm(&GATerm::scalar(-2.0f32)), 2.0);
+
+        // Ill-conditioned: the unit components vanish next to 1e16 in a naive sum
+        let mut components = vec![(1, 1e8)];
+        components.extend((0..1000).map(|i| (1 + i % 5, 1.0)));
+        let wide = GATerm::vector(components);
+        assert_eq!(norm_with(&wide, Summation::Naive), 1e8);
+        assert!((norm_with(&wide, Summation::Compensated) - (1e8 + 5e-6)).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_in_place_operations() {
+        let mut accumulator = GATerm::bivector(vec![(1, 2, 1.0)]);
+        assert!(add_assign(&mut accumulator, &GATerm::bivector(vec![(1, 2, 2.0), (2, 3, 1.0)])));
+        assert_eq!(accumulator, GATerm::bivector(vec![(1, 2, 3.0), (2, 3, 1.0)]));
+
+        assert!(!add_assign(&mut accumulator, &GATerm::scalar(1.0)));
+        assert_eq!(accumulator, GATerm::bivector(vec![(1, 2, 3.0), (2, 3, 1.0)]));
+
+        scalar_multiply_assign(2.0, &mut accumulator);
+        assert_eq!(accumulator, GATerm::bivector(vec![(1, 2, 6.0), (2, 3, 2.0)]));
+
+        let sum = add_owned(GATerm::scalar(1.0), &GATerm::scalar(2.0));
+        assert_eq!(sum, Some(GATerm::scalar(3.0)));
     }
 
     #[test]
@@ -477,4 +738,27 @@ mod tests {
         let sum = combinators::fold(&vector, 0.0, |acc, x| acc + x);
         assert_eq!(sum, 9.0);
     }
+
+    #[test]
+    fn test_zip_with() {
+        use combinators::{zip_with, ZipPolicy};
+
+        let a = GATerm::bivector(vec![(1, 2, 2.0), (2, 3, 3.0)]);
+        let b = GATerm::bivector(vec![(2, 3, 4.0), (1, 3, 1.0)]);
+        let union = zip_with(&a, &b, ZipPolicy::Union, |x, y| x * y + 1.0).unwrap();
+        assert_eq!(union, GATerm::bivector(vec![(1, 2, 1.0), (2, 3, 13.0), (1, 3, 1.0)]));
+        let both = zip_with(&a, &b, ZipPolicy::Intersection, |x, y| (x, y) == (&3.0, &4.0)).unwrap();
+        assert_eq!(both, GATerm::bivector(vec![(2, 3, true)]));
+        assert!(zip_with(&a, &GATerm::scalar(1.0), ZipPolicy::Union, |x, y| x + y).is_none());
+
+        let m1 = GATerm::multivector(vec![BladeTerm::new(vec![], 1.0), BladeTerm::new(vec![1, 2, 3, 4], 2.0)]);
+        let m2 = GATerm::multivector(vec![BladeTerm::new(vec![1, 2, 3, 4], 0.5)]);
+        assert_eq!(
+            sub(&m1, &m2).unwrap(),
+            GATerm::multivector(vec![BladeTerm::new(vec![], 1.0), BladeTerm::new(vec![1, 2, 3, 4], 1.5)])
+        );
+        assert_eq!(dot(&m1, &m2), Some(1.0));
+        assert_eq!(sub(&GATerm::scalar(3.0), &GATerm::scalar(1.0)), Some(GATerm::scalar(2.0)));
+        assert_eq!(dot(&GATerm::vector(vec![(1, 2.0), (2, 3.0)]), &GATerm::vector(vec![(2, 5.0)])), Some(15.0));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,265 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! PID controllers with dimension-checked gains
+//!
+//! A [`Pid<In, Out>`] turns an error in `In` into a command in `Out`. Each gain
+//! is typed by the pair it converts between, so the proportional gain of a
+//! depth controller is a `ProportionalGain<Length, Force>` (newtons per meter)
+//! and cannot be handed to a heading controller. The integral gain is in `Out`
+//! per `In`·s and the derivative gain in `Out`·s per `In`.
+//!
+//! The derivative term can be low-pass filtered with a time constant, and the
+//! output clamped to typed limits. When clamped, the integrator is protected
+//! from windup by an [`AntiWindup`] strategy.
+
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use crate::si_units::{Measure, Time};
+
+/// Marker of the proportional term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Proportional {}
+/// Marker of the integral term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integral {}
+/// Marker of the derivative term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Derivative {}
+
+/// Gain of one PID term mapping `In` to `Out`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Gain<In, Out, Term> {
+    value: f64,
+    #[serde(skip)]
+    _types: PhantomData<fn(In, Term) -> Out>,
+}
+
+impl<In, Out, Term> Gain<In, Out, Term> {
+    /// Gain in SI units of the term
+    pub const fn new(value: f64) -> Self {
+        Self { value, _types: PhantomData }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+impl<In, Out, Term> Clone for Gain<In, Out, Term> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<In, Out, Term> Copy for Gain<In, Out, Term> {}
+
+impl<In, Out, Term> PartialEq for Gain<In, Out, Term> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+/// `Out` per `In`
+pub type ProportionalGain<In, Out> = Gain<In, Out, Proportional>;
+/// `Out` per `In`·s
+pub type IntegralGain<In, Out> = Gain<In, Out, Integral>;
+/// `Out`·s per `In`
+pub type DerivativeGain<In, Out> = Gain<In, Out, Derivative>;
+
+/// How the integrator behaves while the output is saturated
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AntiWindup {
+    /// Integrate regardless
+    None,
+    /// Stop integrating errors that push further into saturation
+    Conditional,
+    /// Bleed the integrator by the saturation excess with this tracking time constant
+    BackCalculation(Time),
+}
+
+/// PID controller from error `In` to command `Out`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pid<In, Out> {
+    pub kp: ProportionalGain<In, Out>,
+    pub ki: IntegralGain<In, Out>,
+    pub kd: DerivativeGain<In, Out>,
+    limits: Option<(Out, Out)>,
+    derivative_filter: Option<Time>,
+    anti_windup: AntiWindup,
+    /// Error integral, in `In`·s
+    integral: f64,
+    /// Filtered error rate, in `In`/s
+    rate: f64,
+    previous_error: Option<f64>,
+    /// Whether the most recent command was clamped
+    saturated: bool,
+}
+
+impl<In: Measure, Out: Measure> Pid<In, Out> {
+    /// Unlimited controller without derivative filtering
+    ///
+    /// Gains must match the controller's input and output:
+    /// ```compile_fail
+    /// use gafro_modern::pid::{Pid, ProportionalGain, IntegralGain, DerivativeGain};
+    /// use gafro_modern::si_units::{Angle, Force, Length};
+    ///
+    /// let heading_kp = ProportionalGain::<Angle, Force>::new(5.0);
+    /// let depth = Pid::<Length, Force>::new(heading_kp, IntegralGain::new(0.0), DerivativeGain::new(0.0));
+    /// ```
+    pub fn new(kp: ProportionalGain<In, Out>, ki: IntegralGain<In, Out>, kd: DerivativeGain<In, Out>) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            limits: None,
+            derivative_filter: None,
+            anti_windup: AntiWindup::Conditional,
+            integral: 0.0,
+            rate: 0.0,
+            previous_error: None,
+            saturated: false,
+        }
+    }
+
+    /// Clamp the command to `[min, max]`
+    pub fn with_limits(mut self, min: Out, max: Out) -> Self {
+        assert!(min.raw() <= max.raw(), "lower output limit above upper");
+        self.limits = Some((min, max));
+        self
+    }
+
+    /// First-order low-pass on the error rate with time constant `tau`
+    pub fn with_derivative_filter(mut self, tau: Time) -> Self {
+        self.derivative_filter = Some(tau);
+        self
+    }
+
+    pub fn with_anti_windup(mut self, anti_windup: AntiWindup) -> Self {
+        self.anti_windup = anti_windup;
+        self
+    }
+
+    /// Forget the integral and derivative history
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.rate = 0.0;
+        self.previous_error = None;
+        self.saturated = false;
+    }
+
+    /// Command for the error `setpoint - measurement` after a step of `dt`. A
+    /// non-positive `dt` leaves the state unchanged.
+    pub fn update(&mut self, setpoint: In, measurement: In, dt: Time) -> Out {
+        let error = setpoint.raw() - measurement.raw();
+        let dt = *dt.value();
+        if dt <= 0.0 {
+            let command = self.kp.value * error + self.ki.value * self.integral + self.kd.value * self.rate;
+            return Out::from_raw(self.saturate(command));
+        }
+
+        let raw_rate = self.previous_error.map_or(0.0, |previous| (error - previous) / dt);
+        self.rate = match self.derivative_filter {
+            Some(tau) => self.rate + dt / (tau.value() + dt) * (raw_rate - self.rate),
+            None => raw_rate,
+        };
+        self.previous_error = Some(error);
+
+        let proportional_derivative = self.kp.value * error + self.kd.value * self.rate;
+        let integral = self.integral + error * dt;
+        let command = proportional_derivative + self.ki.value * integral;
+        let saturated = self.saturate(command);
+        let excess = saturated - command;
+        self.saturated = excess != 0.0;
+
+        self.integral = match self.anti_windup {
+            AntiWindup::Conditional if excess != 0.0 && excess * self.ki.value * error < 0.0 => self.integral,
+            AntiWindup::BackCalculation(tracking) if self.ki.value != 0.0 => {
+                integral + excess / self.ki.value * dt / tracking.value()
+            }
+            _ => integral,
+        };
+        Out::from_raw(saturated)
+    }
+
+    /// Integral term of the most recent command
+    pub fn integral_term(&self) -> Out {
+        Out::from_raw(self.ki.value * self.integral)
+    }
+
+    /// Whether the most recent command was clamped to a limit
+    pub fn is_saturated(&self) -> bool {
+        self.saturated
+    }
+
+    fn saturate(&self, command: f64) -> f64 {
+        match self.limits {
+            Some((min, max)) => command.clamp(min.raw(), max.raw()),
+            None => command,
+        }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::{Force, Length};
+
+    fn depth_controller(ki: f64) -> Pid<Length, Force> {
+        Pid::new(ProportionalGain::new(100.0), IntegralGain::new(ki), DerivativeGain::new(0.0))
+    }
+
+    #[test]
+    fn test_proportional_and_integral() {
+        let mut pid = depth_controller(10.0);
+        let dt = Time::new(0.1);
+        // 0.5 m error: 50 N proportional plus 10·0.05 N integral
+        let command = pid.update(Length::new(10.0), Length::new(9.5), dt);
+        assert!((command.value() - 50.5).abs() < 1e-12);
+        assert!((pid.integral_term().value() - 0.5).abs() < 1e-12);
+        // A zero step changes nothing
+        assert_eq!(pid.update(Length::new(10.0), Length::new(9.5), Time::new(0.0)), command);
+        pid.reset();
+        assert_eq!(*pid.integral_term().value(), 0.0);
+    }
+
+    #[test]
+    fn test_anti_windup() {
+        let dt = Time::new(0.1);
+        let run = |anti_windup: AntiWindup| {
+            let mut pid = depth_controller(50.0).with_limits(Force::new(-20.0), Force::new(20.0)).with_anti_windup(anti_windup);
+            // Saturated for 10 s, then the error reverses
+            for _ in 0..100 {
+                assert_eq!(*pid.update(Length::new(1.0), Length::new(0.0), dt).value(), 20.0);
+            }
+            assert!(pid.is_saturated());
+            let windup = *pid.integral_term().value();
+            let recovery = *pid.update(Length::new(0.0), Length::new(0.1), dt).value();
+            (windup, recovery)
+        };
+
+        let (windup, recovery) = run(AntiWindup::None);
+        assert!(windup > 490.0 && recovery == 20.0);
+        let (windup, recovery) = run(AntiWindup::Conditional);
+        assert!(windup < 1.0 && recovery < 0.0);
+        let (windup, recovery) = run(AntiWindup::BackCalculation(Time::new(0.5)));
+        assert!(windup < 100.0 && recovery < 20.0);
+    }
+
+    #[test]
+    fn test_derivative_filter() {
+        let kd = DerivativeGain::<Length, Force>::new(1.0);
+        let mut raw = Pid::new(ProportionalGain::new(0.0), IntegralGain::new(0.0), kd);
+        let mut filtered = raw.clone().with_derivative_filter(Time::new(0.09));
+        let dt = Time::new(0.01);
+        raw.update(Length::new(0.0), Length::new(0.0), dt);
+        filtered.update(Length::new(0.0), Length::new(0.0), dt);
+        // A 1 cm setpoint step: the raw rate is 1 m/s, filtered by dt/(τ+dt) = 0.1
+        assert!((raw.update(Length::new(0.01), Length::new(0.0), dt).value() - 1.0).abs() < 1e-12);
+        assert!((filtered.update(Length::new(0.01), Length::new(0.0), dt).value() - 0.1).abs() < 1e-12);
+    }
+}
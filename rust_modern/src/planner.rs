@@ -0,0 +1,487 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Global path planning over a 2.5D bathymetry grid
+//!
+//! A [`BathymetryGrid`] stores the seafloor depth of square cells in the
+//! horizontal East-North plane, with optional no-go cells and a water current
+//! per cell. [`plan`] runs A* over the 8-connected cells that leave the
+//! vehicle enough clearance at its travel depth. Edges cost their travel time
+//! at the vehicle's speed through the water, so head currents lengthen a route
+//! and cross currents too strong to crab against block it.
+//!
+//! The cell path is then pulled taut: a run of cells is replaced by a straight
+//! segment whenever the segment stays on traversable cells and is not slower.
+//! The resulting waypoints feed [`crate::guidance::LineOfSight`]. Surface
+//! vehicles with a minimum turning radius can instead follow the Dubins legs
+//! [`smooth`] lays through them.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::euler::wrap;
+use crate::linalg::Vector3;
+use crate::path_primitives::{dubins, CurvedPath, PlanarPose, TurningRadius};
+use crate::si_units::{Angle, Length, Time, Velocity};
+
+/// Errors raised while building a grid or planning
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlannerError {
+    /// Cell size must be positive and the grid non-empty
+    InvalidGrid,
+    /// `depths` or `currents` does not hold one entry per cell
+    SizeMismatch { expected: usize, found: usize },
+    /// Start or goal lies outside the grid or on a blocked cell
+    BlockedEndpoint,
+    NoPath,
+    /// The turn onto this leg leaves the traversable cells
+    TurnBlocked { leg: usize },
+}
+
+impl fmt::Display for PlannerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlannerError::InvalidGrid => write!(f, "grid needs cells of positive size"),
+            PlannerError::SizeMismatch { expected, found } => write!(f, "expected {} cells, found {}", expected, found),
+            PlannerError::BlockedEndpoint => write!(f, "start or goal is outside the grid or not traversable"),
+            PlannerError::NoPath => write!(f, "no traversable path between start and goal"),
+            PlannerError::TurnBlocked { leg } => write!(f, "turn onto leg {} leaves traversable water", leg),
+        }
+    }
+}
+
+impl std::error::Error for PlannerError {}
+
+/// Seafloor depths, no-go cells and currents on a regular grid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BathymetryGrid {
+    /// East and north of the corner of cell `(0, 0)`
+    origin: [f64; 2],
+    cell_size: f64,
+    columns: usize,
+    rows: usize,
+    /// Seafloor depth per cell, row-major from the south-west; NaN is unsurveyed
+    depths: Vec<f64>,
+    no_go: Vec<bool>,
+    /// East and north current per cell
+    currents: Vec<[f64; 2]>,
+}
+
+/// Vehicle parameters used by the planner
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlannerConfig {
+    /// Depth the path is flown at
+    pub travel_depth: Length,
+    /// Water required between the vehicle and the seafloor
+    pub clearance: Length,
+    /// Speed through the water
+    pub speed: Velocity,
+}
+
+/// Planned route with its travel time
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedPath {
+    /// East-North-Up waypoints at the travel depth, start and goal included
+    pub waypoints: Vec<Vector3>,
+    pub length: Length,
+    pub duration: Time,
+}
+
+impl BathymetryGrid {
+    /// Grid of `columns × rows` cells, `depths` row-major from the south-west corner
+    pub fn new(origin: [Length; 2], cell_size: Length, columns: usize, rows: usize, depths: Vec<Length>) -> Result<Self, PlannerError> {
+        if cell_size.value().is_nan() || *cell_size.value() <= 0.0 || columns == 0 || rows == 0 {
+            return Err(PlannerError::InvalidGrid);
+        }
+        if depths.len() != columns * rows {
+            return Err(PlannerError::SizeMismatch { expected: columns * rows, found: depths.len() });
+        }
+        Ok(Self {
+            origin: origin.map(|o| *o.value()),
+            cell_size: *cell_size.value(),
+            columns,
+            rows,
+            depths: depths.into_iter().map(|d| *d.value()).collect(),
+            no_go: vec![false; columns * rows],
+            currents: vec![[0.0; 2]; columns * rows],
+        })
+    }
+
+    pub fn cell_size(&self) -> Length {
+        Length::new(self.cell_size)
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// Cell `(column, row)` containing an East-North point
+    pub fn cell_at(&self, point: [f64; 2]) -> Option<(usize, usize)> {
+        let column = ((point[0] - self.origin[0]) / self.cell_size).floor();
+        let row = ((point[1] - self.origin[1]) / self.cell_size).floor();
+        (column >= 0.0 && row >= 0.0 && (column as usize) < self.columns && (row as usize) < self.rows)
+            .then_some((column as usize, row as usize))
+    }
+
+    pub fn cell_center(&self, (column, row): (usize, usize)) -> [f64; 2] {
+        [
+            self.origin[0] + (column as f64 + 0.5) * self.cell_size,
+            self.origin[1] + (row as f64 + 0.5) * self.cell_size,
+        ]
+    }
+
+    pub fn depth(&self, (column, row): (usize, usize)) -> Length {
+        Length::new(self.depths[row * self.columns + column])
+    }
+
+    /// Forbid every cell whose center lies within `radius` of `center`
+    pub fn add_no_go_circle(&mut self, center: [Length; 2], radius: Length) {
+        let (center, radius) = (center.map(|c| *c.value()), *radius.value());
+        self.add_no_go_where(|p| (p[0] - center[0]).hypot(p[1] - center[1]) <= radius);
+    }
+
+    /// Forbid every cell whose center lies inside a polygon of East-North vertices
+    pub fn add_no_go_polygon(&mut self, vertices: &[[Length; 2]]) {
+        let vertices: Vec<[f64; 2]> = vertices.iter().map(|v| v.map(|c| *c.value())).collect();
+        self.add_no_go_where(|p| {
+            // Even-odd rule
+            let mut inside = false;
+            for (i, a) in vertices.iter().enumerate() {
+                let b = vertices[(i + 1) % vertices.len()];
+                if (a[1] > p[1]) != (b[1] > p[1]) && p[0] < a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]) {
+                    inside = !inside;
+                }
+            }
+            inside
+        });
+    }
+
+    /// Forbid every cell whose East-North center satisfies `inside`, e.g.
+    /// obstacles from an [`crate::occupancy::OccupancyGrid`]
+    pub fn add_no_go_where(&mut self, inside: impl Fn([f64; 2]) -> bool) {
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                if inside(self.cell_center((column, row))) {
+                    self.no_go[row * self.columns + column] = true;
+                }
+            }
+        }
+    }
+
+    /// Current over one cell, east and north
+    pub fn set_current(&mut self, (column, row): (usize, usize), current: [Velocity; 2]) {
+        self.currents[row * self.columns + column] = current.map(|c| *c.value());
+    }
+
+    /// Same current everywhere
+    pub fn set_uniform_current(&mut self, current: [Velocity; 2]) {
+        self.currents.fill(current.map(|c| *c.value()));
+    }
+
+    /// Currents per cell, row-major like the depths
+    pub fn set_currents(&mut self, currents: Vec<[Velocity; 2]>) -> Result<(), PlannerError> {
+        if currents.len() != self.currents.len() {
+            return Err(PlannerError::SizeMismatch { expected: self.currents.len(), found: currents.len() });
+        }
+        self.currents = currents.into_iter().map(|c| c.map(|v| *v.value())).collect();
+        Ok(())
+    }
+
+    /// Whether the vehicle may occupy a cell
+    pub fn is_traversable(&self, (column, row): (usize, usize), config: &PlannerConfig) -> bool {
+        let index = row * self.columns + column;
+        let needed = config.travel_depth.value() + config.clearance.value();
+        !self.no_go[index] && self.depths[index] >= needed
+    }
+
+    /// Time to cross `distance` along the unit `direction` through a cell's
+    /// current; `None` when the current cannot be stemmed
+    fn crossing_time(&self, cell: (usize, usize), direction: [f64; 2], distance: f64, speed: f64) -> Option<f64> {
+        let current = self.currents[cell.1 * self.columns + cell.0];
+        let along = current[0] * direction[0] + current[1] * direction[1];
+        let across = current[0] * direction[1] - current[1] * direction[0];
+        let ground = along + (speed * speed - across * across).sqrt();
+        (ground > 0.0).then(|| distance / ground)
+    }
+
+    /// Time along the straight segment `a → b`, cell by cell; `None` if it
+    /// touches a blocked cell or an unstemmable current
+    fn segment_time(&self, a: [f64; 2], b: [f64; 2], config: &PlannerConfig) -> Option<f64> {
+        let delta = [b[0] - a[0], b[1] - a[1]];
+        let length = delta[0].hypot(delta[1]);
+        if length == 0.0 {
+            return Some(0.0);
+        }
+        let direction = [delta[0] / length, delta[1] / length];
+        // Quarter-cell samples cannot skip a cell corner by more than that much
+        let samples = (4.0 * length / self.cell_size).ceil().max(1.0) as usize;
+        let step = length / samples as f64;
+        let mut time = 0.0;
+        for k in 0..samples {
+            let s = (k as f64 + 0.5) * step;
+            let cell = self.cell_at([a[0] + direction[0] * s, a[1] + direction[1] * s])?;
+            if !self.is_traversable(cell, config) {
+                return None;
+            }
+            time += self.crossing_time(cell, direction, step, *config.speed.value())?;
+        }
+        Some(time)
+    }
+}
+
+/// Open-list entry ordered by smallest estimated total time
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    estimate: f64,
+    index: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate).then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Fastest route from `start` to `goal` (East-North-Up; the heights are replaced
+/// by the travel depth)
+pub fn plan(grid: &BathymetryGrid, config: &PlannerConfig, start: Vector3, goal: Vector3) -> Result<PlannedPath, PlannerError> {
+    let endpoints = [start, goal].map(|p| [p[0], p[1]]);
+    let cells = endpoints.map(|p| grid.cell_at(p).filter(|cell| grid.is_traversable(*cell, config)));
+    let [Some(start_cell), Some(goal_cell)] = cells else {
+        return Err(PlannerError::BlockedEndpoint);
+    };
+
+    let speed = *config.speed.value();
+    let fastest_current = grid.currents.iter().map(|c| c[0].hypot(c[1])).fold(0.0, f64::max);
+    let goal_center = grid.cell_center(goal_cell);
+    let heuristic = |cell: (usize, usize)| {
+        let p = grid.cell_center(cell);
+        (p[0] - goal_center[0]).hypot(p[1] - goal_center[1]) / (speed + fastest_current)
+    };
+    let index = |(column, row): (usize, usize)| row * grid.columns + column;
+    let cell_of = |index: usize| (index % grid.columns, index / grid.columns);
+
+    let mut time = vec![f64::INFINITY; grid.depths.len()];
+    let mut parent = vec![usize::MAX; grid.depths.len()];
+    let mut open = BinaryHeap::new();
+    time[index(start_cell)] = 0.0;
+    open.push(Candidate { estimate: heuristic(start_cell), index: index(start_cell) });
+    while let Some(Candidate { index: current, estimate }) = open.pop() {
+        let cell = cell_of(current);
+        if current == index(goal_cell) {
+            break;
+        }
+        if estimate > time[current] + heuristic(cell) {
+            continue;
+        }
+        for (dc, dr) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+            let (column, row) = (cell.0 as isize + dc, cell.1 as isize + dr);
+            if column < 0 || row < 0 || column as usize >= grid.columns || row as usize >= grid.rows {
+                continue;
+            }
+            let next = (column as usize, row as usize);
+            if !grid.is_traversable(next, config) {
+                continue;
+            }
+            // No corner cutting between two blocked cells
+            if dc != 0 && dr != 0
+                && (!grid.is_traversable((next.0, cell.1), config) || !grid.is_traversable((cell.0, next.1), config))
+            {
+                continue;
+            }
+            let Some(step) = grid.segment_time(grid.cell_center(cell), grid.cell_center(next), config) else {
+                continue;
+            };
+            let candidate = time[current] + step;
+            if candidate < time[index(next)] {
+                time[index(next)] = candidate;
+                parent[index(next)] = current;
+                open.push(Candidate { estimate: candidate + heuristic(next), index: index(next) });
+            }
+        }
+    }
+    if !time[index(goal_cell)].is_finite() {
+        return Err(PlannerError::NoPath);
+    }
+
+    let mut chain = vec![index(goal_cell)];
+    while let Some(&last) = chain.last() {
+        match parent[last] {
+            usize::MAX => break,
+            previous => chain.push(previous),
+        }
+    }
+    chain.reverse();
+    let mut points: Vec<[f64; 2]> = chain.into_iter().map(|i| grid.cell_center(cell_of(i))).collect();
+    points[0] = endpoints[0];
+    *points.last_mut().expect("path has the goal") = endpoints[1];
+    if points.len() == 1 {
+        points.push(endpoints[1]);
+    }
+    let points = shortcut(grid, config, points);
+
+    let mut duration = 0.0;
+    let mut length = 0.0;
+    for pair in points.windows(2) {
+        duration += grid.segment_time(pair[0], pair[1], config).ok_or(PlannerError::NoPath)?;
+        length += (pair[1][0] - pair[0][0]).hypot(pair[1][1] - pair[0][1]);
+    }
+    let z = -config.travel_depth.value();
+    Ok(PlannedPath {
+        waypoints: points.into_iter().map(|p| [p[0], p[1], z]).collect(),
+        length: Length::new(length),
+        duration: Time::new(duration),
+    })
+}
+
+/// Dubins legs through the waypoints of `path`, leaving the start at
+/// `heading` and turning no tighter than `radius`. Interior waypoints are
+/// crossed along the bisector of their legs and the goal along the last leg.
+pub fn smooth(grid: &BathymetryGrid, config: &PlannerConfig, path: &PlannedPath, heading: Angle, radius: TurningRadius) -> Result<Vec<CurvedPath>, PlannerError> {
+    let points = &path.waypoints;
+    let direction = |from: usize| (points[from + 1][1] - points[from][1]).atan2(points[from + 1][0] - points[from][0]);
+    let poses: Vec<PlanarPose> = (0..points.len())
+        .map(|k| {
+            let heading = match k {
+                0 => *heading.value(),
+                k if k + 1 == points.len() => direction(k - 1),
+                k => {
+                    let (incoming, outgoing) = (direction(k - 1), direction(k));
+                    incoming + 0.5 * wrap(outgoing - incoming)
+                }
+            };
+            PlanarPose::new(Length::new(points[k][0]), Length::new(points[k][1]), Angle::new(heading))
+        })
+        .collect();
+
+    let step = Length::new(0.25 * grid.cell_size);
+    poses
+        .windows(2)
+        .enumerate()
+        .map(|(leg, pair)| {
+            let curve = dubins(pair[0], pair[1], radius).map_err(|_| PlannerError::TurnBlocked { leg })?;
+            let clear = curve.sample(step).iter().all(|pose| {
+                grid.cell_at([*pose.east.value(), *pose.north.value()]).is_some_and(|cell| grid.is_traversable(cell, config))
+            });
+            if clear {
+                Ok(curve)
+            } else {
+                Err(PlannerError::TurnBlocked { leg })
+            }
+        })
+        .collect()
+}
+
+/// Replace runs of points by straight segments that are feasible and no slower
+fn shortcut(grid: &BathymetryGrid, config: &PlannerConfig, points: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    let leg_times: Vec<f64> = points
+        .windows(2)
+        .map(|pair| grid.segment_time(pair[0], pair[1], config).unwrap_or(f64::INFINITY))
+        .collect();
+    let mut result = vec![points[0]];
+    let mut anchor = 0;
+    while anchor < points.len() - 1 {
+        let mut reach = anchor + 1;
+        let mut along = leg_times[anchor];
+        for candidate in anchor + 2..points.len() {
+            along += leg_times[candidate - 1];
+            match grid.segment_time(points[anchor], points[candidate], config) {
+                Some(direct) if direct <= along + 1e-9 => reach = candidate,
+                _ => {}
+            }
+        }
+        result.push(points[reach]);
+        anchor = reach;
+    }
+    result
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 20 × 10 grid of 1 m cells, 30 m deep, with a ridge at column 10 open only at the top
+    fn ridge() -> BathymetryGrid {
+        let depths = (0..200)
+            .map(|i| {
+                let (column, row) = (i % 20, i / 20);
+                Length::new(if column == 10 && row < 8 { 5.0 } else { 30.0 })
+            })
+            .collect();
+        BathymetryGrid::new([Length::new(0.0); 2], Length::new(1.0), 20, 10, depths).unwrap()
+    }
+
+    fn config() -> PlannerConfig {
+        PlannerConfig { travel_depth: Length::new(10.0), clearance: Length::new(2.0), speed: Velocity::new(1.0) }
+    }
+
+    #[test]
+    fn test_routes_around_shallows_and_no_go() {
+        let mut grid = ridge();
+        let path = plan(&grid, &config(), [2.5, 2.5, 0.0], [17.5, 2.5, 0.0]).unwrap();
+        assert!(path.waypoints.iter().all(|w| w[2] == -10.0));
+        // Through the gap in rows 8 and 9
+        assert!(path.waypoints.iter().any(|w| w[1] > 8.0));
+        assert!(path.waypoints.len() <= 4, "{:?}", path.waypoints);
+        assert!((path.duration.value() - path.length.value()).abs() < 1e-9);
+
+        // A shallow vehicle passes straight over the ridge
+        let shallow = PlannerConfig { travel_depth: Length::new(1.0), ..config() };
+        let direct = plan(&grid, &shallow, [2.5, 2.5, 0.0], [17.5, 2.5, 0.0]).unwrap();
+        assert_eq!(direct.waypoints.len(), 2);
+        assert!((direct.length.value() - 15.0).abs() < 1e-12);
+
+        grid.add_no_go_circle([Length::new(10.5), Length::new(9.0)], Length::new(1.5));
+        assert_eq!(plan(&grid, &config(), [2.5, 2.5, 0.0], [17.5, 2.5, 0.0]), Err(PlannerError::NoPath));
+        assert_eq!(plan(&grid, &config(), [10.5, 2.5, 0.0], [17.5, 2.5, 0.0]), Err(PlannerError::BlockedEndpoint));
+    }
+
+    #[test]
+    fn test_smoothed_route_turns_within_radius() {
+        let grid = ridge();
+        let path = plan(&grid, &config(), [2.5, 2.5, 0.0], [17.5, 2.5, 0.0]).unwrap();
+        let radius = TurningRadius::new(Length::new(0.5)).unwrap();
+        let legs = smooth(&grid, &config(), &path, Angle::new(0.0), radius).unwrap();
+        assert_eq!(legs.len(), path.waypoints.len() - 1);
+        for (leg, pair) in legs.iter().zip(path.waypoints.windows(2)) {
+            let (start, end) = (leg.start(), leg.end());
+            assert!((start.east.value() - pair[0][0]).abs() < 1e-9 && (end.east.value() - pair[1][0]).abs() < 1e-6);
+            assert!(leg.curvature_at(Length::new(0.1)).value().abs() <= 2.0);
+        }
+
+        // A wide turn swings out over the ridge
+        let wide = TurningRadius::new(Length::new(8.0)).unwrap();
+        assert!(matches!(smooth(&grid, &config(), &path, Angle::new(0.0), wide), Err(PlannerError::TurnBlocked { .. })));
+    }
+
+    #[test]
+    fn test_current_costs() {
+        // Open water with a strong westward current along the southern half
+        let depths = vec![Length::new(30.0); 200];
+        let mut grid = BathymetryGrid::new([Length::new(0.0); 2], Length::new(1.0), 20, 10, depths).unwrap();
+        let current = [Velocity::new(-0.8), Velocity::new(0.0)];
+        grid.set_currents((0..200).map(|i| if i / 20 < 5 { current } else { [Velocity::new(0.0); 2] }).collect()).unwrap();
+        let path = plan(&grid, &config(), [0.5, 0.5, 0.0], [19.5, 0.5, 0.0]).unwrap();
+        // Detouring north through slack water beats stemming 0.8 m/s
+        assert!(path.waypoints.iter().any(|w| w[1] > 5.0));
+        assert!(path.duration.value() < &(19.0 / 0.2));
+
+        grid.set_uniform_current([Velocity::new(0.0), Velocity::new(2.0)]);
+        assert_eq!(plan(&grid, &config(), [0.5, 0.5, 0.0], [19.5, 0.5, 0.0]), Err(PlannerError::NoPath));
+        let grid_err = BathymetryGrid::new([Length::new(0.0); 2], Length::new(1.0), 2, 2, vec![]);
+        assert_eq!(grid_err, Err(PlannerError::SizeMismatch { expected: 4, found: 0 }));
+    }
+}
@@ -0,0 +1,455 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Least-squares pose-graph optimization on motors
+//!
+//! Variables are motors updated on the right, `X ← X exp(δ)`. Residuals are
+//! expressed in the motor tangent space through the bivector log map, with the
+//! inverse right Jacobian approximated to first order as `I + ½ ad(e)`.
+
+use std::fmt;
+
+use crate::linalg::{self, dense, square, Matrix3, Matrix6, Vector3};
+use crate::motor::{Motor, MotorGenerator};
+use crate::summation::{sum_with, Accumulator, Summation};
+
+/// Factor connecting pose variables to measurements
+#[derive(Debug, Clone, PartialEq)]
+pub enum Factor {
+    /// Absolute pose measurement `Z ≈ X`
+    Prior { pose: usize, measurement: Motor, information: Matrix6 },
+    /// Relative pose measurement `Z ≈ X_from⁻¹ X_to`
+    RelativePose { from: usize, to: usize, measurement: Motor, information: Matrix6 },
+    /// Known landmark observed in the pose frame, `z ≈ X⁻¹ p`
+    PointObservation { pose: usize, landmark: Vector3, measured: Vector3, information: Matrix3 },
+}
+
+/// Residual, information and per-variable Jacobian blocks of one factor
+struct Linearization {
+    residual: Vec<f64>,
+    information: Vec<f64>,
+    blocks: Vec<(usize, Vec<[f64; 6]>)>,
+}
+
+impl Linearization {
+    fn cost(&self) -> f64 {
+        let n = self.residual.len();
+        let mut cost = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                cost += self.residual[i] * self.information[i * n + j] * self.residual[j];
+            }
+        }
+        0.5 * cost
+    }
+}
+
+impl Factor {
+    fn linearize(&self, poses: &[Motor]) -> Linearization {
+        match self {
+            Factor::Prior { pose, measurement, information } => {
+                let error = (measurement.inverse() * poses[*pose]).log();
+                Linearization {
+                    residual: error.to_array().to_vec(),
+                    information: information.concat(),
+                    blocks: vec![(*pose, inverse_right_jacobian(&error).to_vec())],
+                }
+            }
+            Factor::RelativePose { from, to, measurement, information } => {
+                let relative = poses[*from].inverse() * poses[*to];
+                let error = (measurement.inverse() * relative).log();
+                let jr_inv = inverse_right_jacobian(&error);
+                let transported = square::mul(&jr_inv, &relative.inverse().adjoint());
+                let j_from = transported.map(|row| row.map(|value| -value));
+                Linearization {
+                    residual: error.to_array().to_vec(),
+                    information: information.concat(),
+                    blocks: vec![(*from, j_from.to_vec()), (*to, jr_inv.to_vec())],
+                }
+            }
+            Factor::PointObservation { pose, landmark, measured, information } => {
+                let local = poses[*pose].inverse().apply_point(*landmark);
+                let skew = linalg::skew(local);
+                let mut jacobian = vec![[0.0; 6]; 3];
+                for (i, row) in jacobian.iter_mut().enumerate() {
+                    row[..3].copy_from_slice(&skew[i]);
+                    row[3 + i] = -1.0;
+                }
+                Linearization {
+                    residual: linalg::sub(local, *measured).to_vec(),
+                    information: information.concat(),
+                    blocks: vec![(*pose, jacobian)],
+                }
+            }
+        }
+    }
+
+    fn poses(&self) -> Vec<usize> {
+        match self {
+            Factor::Prior { pose, .. } | Factor::PointObservation { pose, .. } => vec![*pose],
+            Factor::RelativePose { from, to, .. } => vec![*from, *to],
+        }
+    }
+}
+
+/// `ad(ξ)` for twists ordered (rotation, translation)
+fn small_adjoint(xi: &MotorGenerator) -> Matrix6 {
+    let w = linalg::skew(xi.rotation);
+    let v = linalg::skew(xi.translation);
+    let mut ad = [[0.0; 6]; 6];
+    for i in 0..3 {
+        for j in 0..3 {
+            ad[i][j] = w[i][j];
+            ad[i + 3][j + 3] = w[i][j];
+            ad[i + 3][j] = v[i][j];
+        }
+    }
+    ad
+}
+
+/// First-order inverse right Jacobian `I + ½ ad(ξ)` of the motor log map
+fn inverse_right_jacobian(xi: &MotorGenerator) -> Matrix6 {
+    let half = small_adjoint(&xi.scaled(0.5));
+    square::add(&square::identity::<6>(), &half)
+}
+
+/// Solver variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    GaussNewton,
+    LevenbergMarquardt,
+}
+
+/// Optimizer settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizerOptions {
+    pub method: Method,
+    pub max_iterations: usize,
+    /// Stop once the step norm or relative cost decrease falls below this value
+    pub tolerance: f64,
+    pub initial_lambda: f64,
+    /// Accumulation mode for costs and the normal equations
+    pub summation: Summation,
+}
+
+impl Default for OptimizerOptions {
+    fn default() -> Self {
+        Self {
+            method: Method::LevenbergMarquardt,
+            max_iterations: 50,
+            tolerance: 1e-10,
+            initial_lambda: 1e-4,
+            summation: Summation::default(),
+        }
+    }
+}
+
+/// Summary of an optimization run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizationReport {
+    pub iterations: usize,
+    pub initial_cost: f64,
+    pub final_cost: f64,
+    pub converged: bool,
+}
+
+/// Errors raised while building or solving a pose graph
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoseGraphError {
+    UnknownPose(usize),
+    NoFreeVariables,
+    SingularSystem,
+}
+
+impl fmt::Display for PoseGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoseGraphError::UnknownPose(id) => write!(f, "unknown pose {}", id),
+            PoseGraphError::NoFreeVariables => write!(f, "all poses are fixed"),
+            PoseGraphError::SingularSystem => write!(f, "normal equations are singular; is the graph anchored?"),
+        }
+    }
+}
+
+impl std::error::Error for PoseGraphError {}
+
+/// Factor graph over motor-valued pose variables
+#[derive(Debug, Clone, Default)]
+pub struct PoseGraph {
+    poses: Vec<Motor>,
+    fixed: Vec<bool>,
+    factors: Vec<Factor>,
+}
+
+impl PoseGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pose variable with its initial estimate, returning its id
+    pub fn add_pose(&mut self, initial: Motor) -> usize {
+        self.poses.push(initial);
+        self.fixed.push(false);
+        self.poses.len() - 1
+    }
+
+    /// Hold a pose constant during optimization
+    pub fn fix(&mut self, pose: usize) -> Result<(), PoseGraphError> {
+        *self.fixed.get_mut(pose).ok_or(PoseGraphError::UnknownPose(pose))? = true;
+        Ok(())
+    }
+
+    pub fn add_factor(&mut self, factor: Factor) -> Result<(), PoseGraphError> {
+        if let Some(&id) = factor.poses().iter().find(|&&id| id >= self.poses.len()) {
+            return Err(PoseGraphError::UnknownPose(id));
+        }
+        self.factors.push(factor);
+        Ok(())
+    }
+
+    pub fn add_prior(&mut self, pose: usize, measurement: Motor, information: Matrix6) -> Result<(), PoseGraphError> {
+        self.add_factor(Factor::Prior { pose, measurement, information })
+    }
+
+    pub fn add_relative_pose(
+        &mut self,
+        from: usize,
+        to: usize,
+        measurement: Motor,
+        information: Matrix6,
+    ) -> Result<(), PoseGraphError> {
+        self.add_factor(Factor::RelativePose { from, to, measurement, information })
+    }
+
+    pub fn add_point_observation(
+        &mut self,
+        pose: usize,
+        landmark: Vector3,
+        measured: Vector3,
+        information: Matrix3,
+    ) -> Result<(), PoseGraphError> {
+        self.add_factor(Factor::PointObservation { pose, landmark, measured, information })
+    }
+
+    pub fn pose(&self, id: usize) -> Option<&Motor> {
+        self.poses.get(id)
+    }
+
+    pub fn poses(&self) -> &[Motor] {
+        &self.poses
+    }
+
+    pub fn factors(&self) -> &[Factor] {
+        &self.factors
+    }
+
+    /// Total cost `½ Σ eᵀ Ω e`
+    pub fn cost(&self) -> f64 {
+        Self::cost_of(&self.factors, &self.poses, Summation::default())
+    }
+
+    fn cost_of(factors: &[Factor], poses: &[Motor], mode: Summation) -> f64 {
+        sum_with(factors.iter().map(|factor| factor.linearize(poses).cost()), mode)
+    }
+
+    /// Build the normal equations `H δ = -g` over the free variables
+    fn normal_equations(&self, columns: &[Option<usize>], n: usize, mode: Summation) -> (Vec<f64>, Vec<f64>) {
+        let mut h = vec![Accumulator::new(mode); n * n];
+        let mut g = vec![Accumulator::new(mode); n];
+
+        for factor in &self.factors {
+            let lin = factor.linearize(&self.poses);
+            let dim = lin.residual.len();
+            // Ω r and Ω J for every block
+            let weighted_residual: Vec<f64> = (0..dim)
+                .map(|i| (0..dim).map(|j| lin.information[i * dim + j] * lin.residual[j]).sum())
+                .collect();
+            let weighted_blocks: Vec<Vec<[f64; 6]>> = lin
+                .blocks
+                .iter()
+                .map(|(_, jac)| {
+                    (0..dim)
+                        .map(|i| std::array::from_fn(|l| (0..dim).map(|j| lin.information[i * dim + j] * jac[j][l]).sum()))
+                        .collect()
+                })
+                .collect();
+
+            for (a, jac_a) in &lin.blocks {
+                let Some(col_a) = columns[*a] else { continue };
+                for k in 0..6 {
+                    g[col_a + k].add(jac_a.iter().zip(&weighted_residual).map(|(row, r)| row[k] * r).sum());
+                }
+                for ((b, _), weighted_b) in lin.blocks.iter().zip(&weighted_blocks) {
+                    let Some(col_b) = columns[*b] else { continue };
+                    for k in 0..6 {
+                        for l in 0..6 {
+                            let value: f64 = jac_a.iter().zip(weighted_b).map(|(row, w)| row[k] * w[l]).sum();
+                            h[(col_a + k) * n + col_b + l].add(value);
+                        }
+                    }
+                }
+            }
+        }
+        let value = |sums: Vec<Accumulator<f64>>| sums.iter().map(Accumulator::value).collect();
+        (value(h), value(g))
+    }
+
+    fn retract(&self, columns: &[Option<usize>], step: &[f64]) -> Vec<Motor> {
+        self.poses
+            .iter()
+            .zip(columns)
+            .map(|(pose, column)| match column {
+                Some(c) => {
+                    let delta: [f64; 6] = step[*c..*c + 6].try_into().expect("six-element block");
+                    *pose * Motor::exp(&MotorGenerator::from_array(delta))
+                }
+                None => *pose,
+            })
+            .collect()
+    }
+
+    /// Minimize the total cost, updating the pose estimates in place
+    pub fn optimize(&mut self, options: &OptimizerOptions) -> Result<OptimizationReport, PoseGraphError> {
+        let mut columns = Vec::with_capacity(self.poses.len());
+        let mut n = 0;
+        for &fixed in &self.fixed {
+            columns.push(if fixed { None } else { Some(n) });
+            n += if fixed { 0 } else { 6 };
+        }
+        if n == 0 {
+            return Err(PoseGraphError::NoFreeVariables);
+        }
+
+        let initial_cost = Self::cost_of(&self.factors, &self.poses, options.summation);
+        let mut cost = initial_cost;
+        let mut lambda = match options.method {
+            Method::GaussNewton => 0.0,
+            Method::LevenbergMarquardt => options.initial_lambda,
+        };
+        let mut report = OptimizationReport { iterations: 0, initial_cost, final_cost: cost, converged: false };
+
+        while report.iterations < options.max_iterations && !report.converged {
+            report.iterations += 1;
+            let (h, g) = self.normal_equations(&columns, n, options.summation);
+            let rhs: Vec<f64> = g.iter().map(|value| -value).collect();
+
+            loop {
+                let mut damped = h.clone();
+                for i in 0..n {
+                    damped[i * n + i] += lambda * h[i * n + i].max(1e-9);
+                }
+                let step = match dense::cholesky_solve(&damped, n, &rhs) {
+                    Some(step) => step,
+                    None if options.method == Method::LevenbergMarquardt && lambda < 1e10 => {
+                        lambda = (lambda * 10.0).max(1e-6);
+                        continue;
+                    }
+                    None => return Err(PoseGraphError::SingularSystem),
+                };
+
+                let candidate = self.retract(&columns, &step);
+                let candidate_cost = Self::cost_of(&self.factors, &candidate, options.summation);
+                let step_norm = step.iter().map(|value| value * value).sum::<f64>().sqrt();
+
+                if candidate_cost <= cost || options.method == Method::GaussNewton {
+                    let decrease = cost - candidate_cost;
+                    self.poses = candidate;
+                    cost = candidate_cost;
+                    lambda /= 10.0;
+                    report.converged = step_norm < options.tolerance || decrease.abs() <= options.tolerance * cost.max(1e-300);
+                    break;
+                }
+
+                lambda *= 10.0;
+                if lambda > 1e10 {
+                    // No descent direction left: we are at a minimum to numerical precision
+                    report.converged = true;
+                    break;
+                }
+            }
+        }
+
+        report.final_cost = cost;
+        Ok(report)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motor::Rotor;
+    use crate::si_units::TAU;
+
+    fn information6(weight: f64) -> Matrix6 {
+        let mut information = square::identity::<6>();
+        for row in information.iter_mut() {
+            for value in row.iter_mut() {
+                *value *= weight;
+            }
+        }
+        information
+    }
+
+    #[test]
+    fn test_loop_closure_square() {
+        let quarter_turn = Motor::new([1.0, 0.0, 0.0], Rotor::from_axis_angle([0.0, 0.0, 1.0], TAU / 4.0));
+        let drifted = Motor::new([1.05, 0.02, 0.0], Rotor::from_axis_angle([0.0, 0.0, 1.0], TAU / 4.0 + 0.05));
+
+        let mut graph = PoseGraph::new();
+        let mut estimate = Motor::identity();
+        let mut ids = vec![graph.add_pose(estimate)];
+        for _ in 0..3 {
+            estimate = estimate * drifted;
+            ids.push(graph.add_pose(estimate));
+        }
+        graph.fix(ids[0]).unwrap();
+
+        for pair in ids.windows(2) {
+            graph.add_relative_pose(pair[0], pair[1], quarter_turn, information6(1.0)).unwrap();
+        }
+        graph.add_relative_pose(ids[3], ids[0], quarter_turn, information6(1.0)).unwrap();
+
+        let report = graph.optimize(&OptimizerOptions::default()).unwrap();
+        assert!(report.final_cost < 1e-12, "{:?}", report);
+        assert!(report.final_cost < report.initial_cost);
+
+        let corner = graph.pose(ids[2]).unwrap().translation;
+        assert!((corner[0] - 1.0).abs() < 1e-6 && (corner[1] - 1.0).abs() < 1e-6, "{:?}", corner);
+    }
+
+    #[test]
+    fn test_point_observations_localize_pose() {
+        let truth = Motor::new([0.5, -0.3, 0.2], Rotor::from_axis_angle([0.2, 0.1, 1.0], 0.4));
+        let landmarks = [[2.0, 0.0, 0.0], [0.0, 3.0, 0.0], [0.0, 0.0, 1.5], [1.0, 1.0, 1.0]];
+
+        let mut graph = PoseGraph::new();
+        let pose = graph.add_pose(Motor::identity());
+        for landmark in landmarks {
+            let measured = truth.inverse().apply_point(landmark);
+            graph.add_point_observation(pose, landmark, measured, square::identity::<3>()).unwrap();
+        }
+
+        let options = OptimizerOptions { method: Method::GaussNewton, ..OptimizerOptions::default() };
+        let report = graph.optimize(&options).unwrap();
+        assert!(report.converged);
+
+        let estimate = graph.pose(pose).unwrap();
+        let error = (truth.inverse() * *estimate).log();
+        assert!(error.to_array().iter().all(|value| value.abs() < 1e-8), "{:?}", error);
+    }
+
+    #[test]
+    fn test_graph_errors() {
+        let mut graph = PoseGraph::new();
+        let a = graph.add_pose(Motor::identity());
+
+        assert_eq!(
+            graph.add_relative_pose(a, 7, Motor::identity(), information6(1.0)),
+            Err(PoseGraphError::UnknownPose(7))
+        );
+        graph.fix(a).unwrap();
+        assert_eq!(graph.optimize(&OptimizerOptions::default()), Err(PoseGraphError::NoFreeVariables));
+    }
+}
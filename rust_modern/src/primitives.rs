@@ -0,0 +1,289 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Conformal geometric primitives
+//!
+//! Primitives are stored by their Euclidean parameters and expose the
+//! coefficients of the corresponding CGA blades in GAFRO's layout, so values can
+//! be compared with the C++ library without building full multivectors. Points
+//! follow GAFRO's embedding `P = e0 + x + ½x² e∞`.
+
+use serde::{Deserialize, Serialize};
+use crate::linalg::{self, Vector3};
+use crate::motor::Motor;
+
+/// Unit direction, a free vector `d e∞` with its magnitude removed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Direction(Vector3);
+
+impl Direction {
+    /// Normalize `v`; `None` for the zero vector
+    pub fn new(v: Vector3) -> Option<Self> {
+        let n = linalg::norm(v);
+        (n > 0.0 && n.is_finite()).then(|| Self(linalg::scale(v, 1.0 / n)))
+    }
+
+    pub fn vector(&self) -> Vector3 {
+        self.0
+    }
+}
+
+/// Conformal point `P = e0 + x + ½x² e∞`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub position: Vector3,
+}
+
+impl Point {
+    pub fn new(position: Vector3) -> Self {
+        Self { position }
+    }
+
+    /// Coefficients on `e0, e1, e2, e3, e∞`
+    pub fn blades(&self) -> [f64; 5] {
+        let x = self.position;
+        [1.0, x[0], x[1], x[2], 0.5 * linalg::dot(x, x)]
+    }
+
+    /// Inner product with another point, `-½‖a - b‖²`
+    pub fn inner(&self, other: &Point) -> f64 {
+        let d = linalg::sub(self.position, other.position);
+        -0.5 * linalg::dot(d, d)
+    }
+
+    pub fn transformed(&self, motor: &Motor) -> Self {
+        Self::new(motor.apply_point(self.position))
+    }
+}
+
+/// Line `L = P₁ ∧ P₂ ∧ e∞`, stored in Plücker coordinates
+///
+/// `direction` points from `P₁` to `P₂` and `moment` is `x × direction` for any
+/// point `x` on the line. Neither is normalized unless the line was.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Line {
+    pub direction: Vector3,
+    pub moment: Vector3,
+}
+
+impl Line {
+    pub fn new(direction: Vector3, moment: Vector3) -> Self {
+        Self { direction, moment }
+    }
+
+    /// Line through `point` along `direction`
+    pub fn through(point: Vector3, direction: Vector3) -> Self {
+        Self::new(direction, linalg::cross(point, direction))
+    }
+
+    /// Line through two points, `P₁ ∧ P₂ ∧ e∞`
+    pub fn from_points(p1: Vector3, p2: Vector3) -> Self {
+        Self::through(p1, linalg::sub(p2, p1))
+    }
+
+    /// Coefficients of the blades `e01∞, e02∞, e12∞, e03∞, e13∞, e23∞`
+    pub fn blades(&self) -> [f64; 6] {
+        let (d, m) = (self.direction, self.moment);
+        [d[0], d[1], m[2], d[2], -m[1], m[0]]
+    }
+
+    /// Line with a unit direction
+    pub fn normalized(&self) -> Self {
+        let n = linalg::norm(self.direction);
+        Self::new(linalg::scale(self.direction, 1.0 / n), linalg::scale(self.moment, 1.0 / n))
+    }
+
+    /// Point on the line closest to the origin
+    pub fn closest_point_to_origin(&self) -> Vector3 {
+        let d = self.direction;
+        linalg::scale(linalg::cross(d, self.moment), 1.0 / linalg::dot(d, d))
+    }
+
+    /// Euclidean distance from `point` to the line
+    pub fn distance_to_point(&self, point: Vector3) -> f64 {
+        let line = self.normalized();
+        linalg::norm(linalg::sub(line.moment, linalg::cross(point, line.direction)))
+    }
+
+    /// The line moved by `motor`
+    pub fn transformed(&self, motor: &Motor) -> Self {
+        let direction = motor.apply_direction(self.direction);
+        let point = motor.apply_point(self.closest_point_to_origin());
+        Self::through(point, direction)
+    }
+}
+
+/// Plane of the points `x` with `normal · x = distance`, `P₁ ∧ P₂ ∧ P₃ ∧ e∞`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Plane {
+    /// Unit normal
+    pub normal: Vector3,
+    /// Signed distance of the plane from the origin along the normal
+    pub distance: f64,
+}
+
+impl Plane {
+    /// Plane with the given normal (normalized here) and offset from the origin
+    pub fn new(normal: Vector3, distance: f64) -> Self {
+        Self { normal: linalg::scale(normal, 1.0 / linalg::norm(normal)), distance }
+    }
+
+    /// Plane through `point` with the given normal
+    pub fn through(point: Vector3, normal: Vector3) -> Self {
+        let normal = linalg::scale(normal, 1.0 / linalg::norm(normal));
+        Self { normal, distance: linalg::dot(normal, point) }
+    }
+
+    /// Plane through three points, oriented by `(p2 - p1) × (p3 - p1)`;
+    /// `None` when they are collinear
+    pub fn from_points(p1: Vector3, p2: Vector3, p3: Vector3) -> Option<Self> {
+        let normal = linalg::cross(linalg::sub(p2, p1), linalg::sub(p3, p1));
+        (linalg::norm(normal) > 0.0).then(|| Self::through(p1, normal))
+    }
+
+    /// Coefficients of the blades `e012∞, e013∞, e023∞, e123∞`
+    pub fn blades(&self) -> [f64; 4] {
+        let n = self.normal;
+        [n[2], -n[1], n[0], self.distance]
+    }
+
+    /// Orthogonal projection of `point` onto the plane
+    pub fn project(&self, point: Vector3) -> Vector3 {
+        let offset = linalg::dot(self.normal, point) - self.distance;
+        linalg::sub(point, linalg::scale(self.normal, offset))
+    }
+}
+
+/// Sphere with a center and a non-negative radius
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Sphere {
+    pub center: Vector3,
+    pub radius: f64,
+}
+
+impl Sphere {
+    pub fn new(center: Vector3, radius: f64) -> Self {
+        Self { center, radius: radius.abs() }
+    }
+
+    /// Coefficients of the dual sphere `C - ½r² e∞` on `e0, e1, e2, e3, e∞`
+    pub fn dual_blades(&self) -> [f64; 5] {
+        let c = self.center;
+        [1.0, c[0], c[1], c[2], 0.5 * (linalg::dot(c, c) - self.radius * self.radius)]
+    }
+}
+
+/// Circle, the intersection of a sphere with a plane or another sphere
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Circle {
+    pub center: Vector3,
+    /// Unit normal of the circle's plane
+    pub normal: Vector3,
+    pub radius: f64,
+}
+
+/// Axis-aligned box between two corners
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Aabb {
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl Aabb {
+    /// Box spanned by two opposite corners in any order
+    pub fn new(a: Vector3, b: Vector3) -> Self {
+        Self {
+            min: [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])],
+            max: [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])],
+        }
+    }
+
+    pub fn center(&self) -> Vector3 {
+        linalg::scale(linalg::add(self.min, self.max), 0.5)
+    }
+
+    /// Edge lengths along x, y and z
+    pub fn extents(&self) -> Vector3 {
+        linalg::sub(self.max, self.min)
+    }
+
+    pub fn contains(&self, point: Vector3) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    /// Smallest box holding all `points`; `None` when there are none
+    pub fn from_points(points: &[Vector3]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        Some(rest.iter().fold(Self::new(*first, *first), |b, p| b.union(&Self::new(*p, *p))))
+    }
+
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: std::array::from_fn(|i| self.min[i].min(other.min[i])),
+            max: std::array::from_fn(|i| self.max[i].max(other.max[i])),
+        }
+    }
+
+    /// Box grown by `margin` on every side
+    pub fn expanded(&self, margin: f64) -> Self {
+        Self::new(linalg::sub(self.min, [margin; 3]), linalg::add(self.max, [margin; 3]))
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    /// Euclidean distance between the boxes, zero when they overlap
+    pub fn distance_to_box(&self, other: &Aabb) -> f64 {
+        let gap: Vector3 = std::array::from_fn(|i| (other.min[i] - self.max[i]).max(self.min[i] - other.max[i]).max(0.0));
+        linalg::norm(gap)
+    }
+
+    /// Distance from `point` to the box surface, negative inside
+    pub fn signed_distance(&self, point: Vector3) -> f64 {
+        let d: Vector3 = std::array::from_fn(|i| (self.min[i] - point[i]).max(point[i] - self.max[i]));
+        let outside = linalg::norm(d.map(|x| x.max(0.0)));
+        outside + d[0].max(d[1]).max(d[2]).min(0.0)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_blades_and_distance() {
+        // The z axis shifted to x = 1: P₁ ∧ P₂ ∧ e∞ has e03∞ = 1 and e13∞ = x₁ z₂ - z₁ x₂ = 1
+        let line = Line::from_points([1.0, 0.0, 0.0], [1.0, 0.0, 1.0]);
+        assert_eq!(line.blades(), [0.0, 0.0, 0.0, 1.0, 1.0, 0.0]);
+        assert_eq!(line.closest_point_to_origin(), [1.0, 0.0, 0.0]);
+        assert!((line.distance_to_point([4.0, 4.0, -2.0]) - 5.0).abs() < 1e-12);
+
+        // Any point on the line gives the same Plücker coordinates
+        let shifted = Line::through([1.0, 0.0, 5.0], [0.0, 0.0, 1.0]);
+        assert_eq!(shifted, line);
+    }
+
+    #[test]
+    fn test_plane_and_sphere_blades() {
+        // P₁ ∧ P₂ ∧ P₃ ∧ e∞ for the plane z = 2
+        let plane = Plane::from_points([0.0, 0.0, 2.0], [1.0, 0.0, 2.0], [0.0, 1.0, 2.0]).unwrap();
+        assert_eq!(plane.blades(), [1.0, 0.0, 0.0, 2.0]);
+        assert_eq!(plane.project([3.0, -1.0, 7.0]), [3.0, -1.0, 2.0]);
+        assert!(Plane::from_points([0.0; 3], [1.0, 1.0, 1.0], [2.0, 2.0, 2.0]).is_none());
+
+        // A point on the sphere has zero inner product with the dual sphere
+        let sphere = Sphere::new([1.0, 2.0, 2.0], 3.0);
+        let [_, x, y, z, inf] = sphere.dual_blades();
+        let p: Vector3 = [1.0, 2.0, 5.0];
+        let inner = linalg::dot(p, [x, y, z]) - inf - 0.5 * linalg::dot(p, p);
+        assert!(inner.abs() < 1e-12);
+
+        // Points are spheres of radius zero
+        let point = Point::new([1.0, 2.0, 2.0]);
+        assert_eq!(point.blades(), Sphere::new(point.position, 0.0).dual_blades());
+        assert_eq!(point.inner(&Point::new([1.0, 2.0, 5.0])), -4.5);
+    }
+}
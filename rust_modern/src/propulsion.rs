@@ -0,0 +1,244 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Propeller performance curves and thruster power draw
+//!
+//! A [`Propeller`] is described by its open-water curves: thrust and torque
+//! coefficients `KT` and `KQ` tabulated against the advance ratio
+//! `J = Va / (n D)`, for shaft speed `n` in revolutions per second, diameter `D`
+//! and inflow speed `Va`. Thrust is `ρ n² D⁴ KT(J)`, torque `ρ n² D⁵ KQ(J)` and
+//! shaft power `2π n Q`. A [`Thruster`] adds the motor efficiency to reach the
+//! electrical power, which feeds [`crate::energy::Battery`] endurance estimates.
+//!
+//! Curves are measured for forward rotation; reverse rotation mirrors them,
+//! which is exact for the symmetric blades common on vehicle thrusters.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::si_units::{marine, Density, Force, Frequency, Length, Power, Ratio, Torque, Velocity, TAU};
+
+/// Shaft speed from revolutions per minute
+pub fn rpm(value: f64) -> Frequency {
+    Frequency::new(value / 60.0)
+}
+
+/// Errors raised while building propulsion models
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropulsionError {
+    /// A curve needs at least one point
+    EmptyCurve,
+    /// Advance ratios must be finite and strictly increasing
+    UnsortedCurve,
+    /// Zero motor efficiency would draw unbounded power
+    ZeroEfficiency,
+}
+
+impl fmt::Display for PropulsionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropulsionError::EmptyCurve => write!(f, "performance curve has no points"),
+            PropulsionError::UnsortedCurve => write!(f, "advance ratios must be finite and strictly increasing"),
+            PropulsionError::ZeroEfficiency => write!(f, "motor efficiency must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for PropulsionError {}
+
+/// Coefficient against advance ratio, linear between points and held beyond them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<(f64, f64)>", into = "Vec<(f64, f64)>")]
+pub struct PerformanceCurve {
+    points: Vec<(f64, f64)>,
+}
+
+impl PerformanceCurve {
+    pub fn new(points: Vec<(f64, f64)>) -> Result<Self, PropulsionError> {
+        if points.is_empty() {
+            return Err(PropulsionError::EmptyCurve);
+        }
+        if points.iter().any(|(j, k)| !j.is_finite() || !k.is_finite()) || points.windows(2).any(|w| w[1].0 <= w[0].0) {
+            return Err(PropulsionError::UnsortedCurve);
+        }
+        Ok(Self { points })
+    }
+
+    pub fn at(&self, advance_ratio: f64) -> f64 {
+        let upper = self.points.partition_point(|(j, _)| *j < advance_ratio);
+        match upper {
+            0 => self.points[0].1,
+            n if n == self.points.len() => self.points[n - 1].1,
+            n => {
+                let ((j0, k0), (j1, k1)) = (self.points[n - 1], self.points[n]);
+                k0 + (k1 - k0) * (advance_ratio - j0) / (j1 - j0)
+            }
+        }
+    }
+}
+
+impl TryFrom<Vec<(f64, f64)>> for PerformanceCurve {
+    type Error = PropulsionError;
+
+    fn try_from(points: Vec<(f64, f64)>) -> Result<Self, PropulsionError> {
+        Self::new(points)
+    }
+}
+
+impl From<PerformanceCurve> for Vec<(f64, f64)> {
+    fn from(curve: PerformanceCurve) -> Self {
+        curve.points
+    }
+}
+
+/// Open-water characteristics of a propeller
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Propeller {
+    pub diameter: Length,
+    pub thrust_coefficient: PerformanceCurve,
+    pub torque_coefficient: PerformanceCurve,
+}
+
+/// Thrust, torque and power at one shaft speed and inflow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperatingPoint {
+    pub speed: Frequency,
+    pub advance_ratio: f64,
+    pub thrust: Force,
+    pub torque: Torque,
+    pub shaft_power: Power,
+    pub electrical_power: Power,
+}
+
+/// Propeller driven by a motor with a speed limit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Thruster {
+    pub propeller: Propeller,
+    /// Shaft over electrical power
+    pub motor_efficiency: Ratio,
+    pub max_speed: Frequency,
+    pub density: Density,
+}
+
+impl Thruster {
+    pub fn new(propeller: Propeller, motor_efficiency: Ratio, max_speed: Frequency) -> Result<Self, PropulsionError> {
+        if motor_efficiency == Ratio::ZERO {
+            return Err(PropulsionError::ZeroEfficiency);
+        }
+        Ok(Self { propeller, motor_efficiency, max_speed, density: marine::water_density() })
+    }
+
+    pub fn with_density(mut self, density: Density) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Performance at shaft `speed` (clamped to the limit) with inflow `advance`
+    pub fn operating_point(&self, speed: Frequency, advance: Velocity) -> OperatingPoint {
+        let limit = *self.max_speed.value();
+        let n = speed.value().clamp(-limit, limit);
+        let d = *self.propeller.diameter.value();
+        let rho = *self.density.value();
+        let advance_ratio = if n == 0.0 { 0.0 } else { advance.value() / (n.abs() * d) };
+        let kt = self.propeller.thrust_coefficient.at(advance_ratio);
+        let kq = self.propeller.torque_coefficient.at(advance_ratio);
+        let thrust = n.signum() * rho * n * n * d.powi(4) * kt;
+        let torque = n.signum() * rho * n * n * d.powi(5) * kq;
+        let shaft_power = (TAU * n * torque).max(0.0);
+        OperatingPoint {
+            speed: Frequency::new(n),
+            advance_ratio,
+            thrust: Force::new(thrust),
+            torque: Torque::new(torque),
+            shaft_power: Power::new(shaft_power),
+            electrical_power: Power::new(shaft_power / self.motor_efficiency.value()),
+        }
+    }
+
+    /// Shaft speed producing `thrust` with inflow `advance`; `None` beyond the
+    /// thrust available at the speed limit
+    pub fn speed_for_thrust(&self, thrust: Force, advance: Velocity) -> Option<Frequency> {
+        let target = *thrust.value();
+        let sign = if target < 0.0 { -1.0 } else { 1.0 };
+        let thrust_at = |n: f64| *self.operating_point(Frequency::new(sign * n), advance).thrust.value() * sign;
+        let (mut low, mut high) = (0.0, *self.max_speed.value());
+        if thrust_at(high) < target.abs() {
+            return None;
+        }
+        for _ in 0..100 {
+            let middle = 0.5 * (low + high);
+            if thrust_at(middle) < target.abs() {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Some(Frequency::new(sign * high))
+    }
+
+    /// Operating point delivering `thrust`, if reachable
+    pub fn operating_point_for_thrust(&self, thrust: Force, advance: Velocity) -> Option<OperatingPoint> {
+        self.speed_for_thrust(thrust, advance).map(|speed| self.operating_point(speed, advance))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::energy::Battery;
+    use crate::si_units::{Energy, Time};
+
+    /// Curves in the shape of a Wageningen B4-70 at pitch ratio 1
+    fn thruster() -> Thruster {
+        let propeller = Propeller {
+            diameter: Length::new(0.1),
+            thrust_coefficient: PerformanceCurve::new(vec![(0.0, 0.45), (0.5, 0.30), (1.0, 0.10), (1.2, 0.0)]).unwrap(),
+            torque_coefficient: PerformanceCurve::new(vec![(0.0, 0.060), (0.5, 0.045), (1.0, 0.022), (1.2, 0.010)]).unwrap(),
+        };
+        Thruster::new(propeller, Ratio::new(0.8).unwrap(), rpm(3000.0)).unwrap().with_density(Density::new(1000.0))
+    }
+
+    #[test]
+    fn test_curves_and_operating_point() {
+        assert_eq!(PerformanceCurve::new(vec![(0.5, 0.1), (0.5, 0.2)]), Err(PropulsionError::UnsortedCurve));
+        assert!(serde_json::from_str::<PerformanceCurve>("[]").is_err());
+        let thruster = thruster();
+        assert!((thruster.propeller.thrust_coefficient.at(0.25) - 0.375).abs() < 1e-12);
+
+        // Bollard pull at 1800 rpm: 30 rev/s
+        let bollard = thruster.operating_point(rpm(1800.0), Velocity::new(0.0));
+        assert!((bollard.thrust.value() - 1000.0 * 900.0 * 1e-4 * 0.45).abs() < 1e-9);
+        let shaft = TAU * 30.0 * 1000.0 * 900.0 * 1e-5 * 0.06;
+        assert!((bollard.shaft_power.value() - shaft).abs() < 1e-9);
+        assert!((bollard.electrical_power.value() - shaft / 0.8).abs() < 1e-9);
+
+        let reverse = thruster.operating_point(rpm(-1800.0), Velocity::new(0.0));
+        assert_eq!(*reverse.thrust.value(), -bollard.thrust.value());
+        assert_eq!(reverse.electrical_power, bollard.electrical_power);
+        // Requests beyond the speed limit are clamped
+        assert_eq!(thruster.operating_point(rpm(9000.0), Velocity::new(0.0)).speed, rpm(3000.0));
+    }
+
+    #[test]
+    fn test_thrust_request_and_endurance() {
+        let thruster = thruster();
+        let advance = Velocity::new(1.0);
+        let point = thruster.operating_point_for_thrust(Force::new(20.0), advance).unwrap();
+        assert!((point.thrust.value() - 20.0).abs() < 1e-6);
+        assert!(point.advance_ratio > 0.0 && point.speed.value() < thruster.max_speed.value());
+        assert!(thruster.speed_for_thrust(Force::new(-20.0), Velocity::new(0.0)).unwrap().value() < &0.0);
+        assert_eq!(thruster.speed_for_thrust(Force::new(1e4), advance), None);
+
+        // Two thrusters plus a 20 W hotel load on a 500 Wh pack
+        let load = Power::new(2.0 * point.electrical_power.value() + 20.0);
+        let battery = Battery::new(Energy::new(1.8e6), Ratio::ONE);
+        let endurance = battery.endurance(load).unwrap();
+        assert!((endurance.value() * load.value() - 1.8e6).abs() < 1e-3);
+        let range = battery.range(load, advance).unwrap();
+        assert_eq!(range.value(), endurance.value());
+        assert!(Time::new(3600.0) < endurance);
+    }
+}
@@ -0,0 +1,388 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Distance and incidence queries between primitives
+//!
+//! Every pair of point, line, plane and sphere has a query. Distances are
+//! returned as [`Length`] and angles as [`Angle`]; signed distances are positive
+//! on the side a plane normal points to and outside a sphere. Decisions between
+//! touching and crossing cases (parallel, tangent, coincident) use
+//! [`INCIDENCE_TOLERANCE`].
+
+use crate::linalg::{self, Vector3};
+use crate::primitives::{Circle, Line, Plane, Sphere};
+use crate::si_units::{Angle, Length};
+
+/// Distance (m) and sine of angles below which primitives count as touching or parallel
+pub const INCIDENCE_TOLERANCE: f64 = 1e-9;
+
+pub fn point_point_distance(a: Vector3, b: Vector3) -> Length {
+    Length::new(linalg::norm(linalg::sub(a, b)))
+}
+
+pub fn point_line_distance(point: Vector3, line: &Line) -> Length {
+    Length::new(line.distance_to_point(point))
+}
+
+/// Signed distance, positive on the side the normal points to
+pub fn point_plane_distance(point: Vector3, plane: &Plane) -> Length {
+    Length::new(linalg::dot(plane.normal, point) - plane.distance)
+}
+
+/// Signed distance to the surface, negative inside
+pub fn point_sphere_distance(point: Vector3, sphere: &Sphere) -> Length {
+    Length::new(linalg::norm(linalg::sub(point, sphere.center)) - sphere.radius)
+}
+
+/// Whether `point` lies inside the sphere or on its surface
+pub fn point_in_sphere(point: Vector3, sphere: &Sphere) -> bool {
+    *point_sphere_distance(point, sphere).value() <= INCIDENCE_TOLERANCE
+}
+
+/// How two lines lie relative to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineRelation {
+    Coincident,
+    Parallel,
+    Intersecting,
+    Skew,
+}
+
+/// Shortest segment between two lines and the angle between them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommonNormal {
+    /// Foot of the common normal on the first line
+    pub on_first: Vector3,
+    /// Foot of the common normal on the second line
+    pub on_second: Vector3,
+    pub distance: Length,
+    /// Angle between the line directions, in [0, τ/2]
+    pub angle: Angle,
+    pub relation: LineRelation,
+}
+
+impl CommonNormal {
+    /// The common normal as a line from the first foot to the second; `None` when
+    /// the lines meet
+    pub fn line(&self) -> Option<Line> {
+        (*self.distance.value() > INCIDENCE_TOLERANCE).then(|| Line::from_points(self.on_first, self.on_second))
+    }
+}
+
+/// Common normal of two lines; parallel lines use the foot through the first
+/// line's point closest to the origin
+pub fn line_line(first: &Line, second: &Line) -> CommonNormal {
+    let (a, b) = (first.normalized(), second.normalized());
+    let (pa, pb) = (a.closest_point_to_origin(), b.closest_point_to_origin());
+    let cross = linalg::cross(a.direction, b.direction);
+    let sine = linalg::norm(cross);
+    let angle = Angle::new(sine.atan2(linalg::dot(a.direction, b.direction)));
+
+    let (on_first, on_second) = if sine < INCIDENCE_TOLERANCE {
+        let offset = linalg::sub(pa, pb);
+        (pa, linalg::add(pb, linalg::scale(b.direction, linalg::dot(offset, b.direction))))
+    } else {
+        // Feet at pa + s a and pb + t b with the connecting segment orthogonal to both
+        let w = linalg::sub(pa, pb);
+        let (ab, aw, bw) = (linalg::dot(a.direction, b.direction), linalg::dot(a.direction, w), linalg::dot(b.direction, w));
+        let denominator = 1.0 - ab * ab;
+        let s = (ab * bw - aw) / denominator;
+        let t = (bw - ab * aw) / denominator;
+        (linalg::add(pa, linalg::scale(a.direction, s)), linalg::add(pb, linalg::scale(b.direction, t)))
+    };
+
+    let distance = linalg::norm(linalg::sub(on_second, on_first));
+    let relation = match (sine < INCIDENCE_TOLERANCE, distance <= INCIDENCE_TOLERANCE) {
+        (true, true) => LineRelation::Coincident,
+        (true, false) => LineRelation::Parallel,
+        (false, true) => LineRelation::Intersecting,
+        (false, false) => LineRelation::Skew,
+    };
+    CommonNormal { on_first, on_second, distance: Length::new(distance), angle, relation }
+}
+
+/// Where a line meets a plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinePlaneIntersection {
+    /// The line crosses the plane at `point`, `angle` away from the plane itself
+    Point { point: Vector3, angle: Angle },
+    /// The line runs parallel to the plane at the given signed distance
+    Parallel { distance: Length },
+    /// The line lies in the plane
+    Contained,
+}
+
+pub fn line_plane(line: &Line, plane: &Plane) -> LinePlaneIntersection {
+    let line = line.normalized();
+    let point = line.closest_point_to_origin();
+    let along = linalg::dot(plane.normal, line.direction);
+    let offset = *point_plane_distance(point, plane).value();
+    if along.abs() < INCIDENCE_TOLERANCE {
+        return if offset.abs() <= INCIDENCE_TOLERANCE {
+            LinePlaneIntersection::Contained
+        } else {
+            LinePlaneIntersection::Parallel { distance: Length::new(offset) }
+        };
+    }
+    LinePlaneIntersection::Point {
+        point: linalg::sub(point, linalg::scale(line.direction, offset / along)),
+        angle: Angle::new(along.abs().asin()),
+    }
+}
+
+/// Where a line meets a sphere
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineSphereIntersection {
+    /// The line passes the surface at the given distance
+    Disjoint { distance: Length },
+    Tangent(Vector3),
+    /// Entry and exit points, ordered along the line direction
+    Secant(Vector3, Vector3),
+}
+
+pub fn line_sphere(line: &Line, sphere: &Sphere) -> LineSphereIntersection {
+    let line = line.normalized();
+    let point = line.closest_point_to_origin();
+    let foot = linalg::add(point, linalg::scale(line.direction, linalg::dot(linalg::sub(sphere.center, point), line.direction)));
+    let miss = linalg::norm(linalg::sub(sphere.center, foot));
+    if (miss - sphere.radius).abs() <= INCIDENCE_TOLERANCE {
+        LineSphereIntersection::Tangent(foot)
+    } else if miss > sphere.radius {
+        LineSphereIntersection::Disjoint { distance: Length::new(miss - sphere.radius) }
+    } else {
+        let half_chord = (sphere.radius * sphere.radius - miss * miss).sqrt();
+        LineSphereIntersection::Secant(
+            linalg::sub(foot, linalg::scale(line.direction, half_chord)),
+            linalg::add(foot, linalg::scale(line.direction, half_chord)),
+        )
+    }
+}
+
+/// Where two planes meet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanePlaneIntersection {
+    /// Intersection line, directed along `first.normal × second.normal`, and the
+    /// dihedral angle between the normals
+    Line { line: Line, angle: Angle },
+    /// Parallel planes; the distance is signed along the first plane's normal
+    Parallel { distance: Length },
+    Coincident,
+}
+
+pub fn plane_plane(first: &Plane, second: &Plane) -> PlanePlaneIntersection {
+    let direction = linalg::cross(first.normal, second.normal);
+    let sine = linalg::norm(direction);
+    let cosine = linalg::dot(first.normal, second.normal);
+    if sine < INCIDENCE_TOLERANCE {
+        let distance = second.distance * cosine.signum() - first.distance;
+        return if distance.abs() <= INCIDENCE_TOLERANCE {
+            PlanePlaneIntersection::Coincident
+        } else {
+            PlanePlaneIntersection::Parallel { distance: Length::new(distance) }
+        };
+    }
+    // Point on both planes in the span of the normals
+    let point = linalg::scale(
+        linalg::add(
+            linalg::cross(linalg::scale(second.normal, first.distance), direction),
+            linalg::cross(direction, linalg::scale(first.normal, second.distance)),
+        ),
+        1.0 / (sine * sine),
+    );
+    PlanePlaneIntersection::Line { line: Line::through(point, direction), angle: Angle::new(sine.atan2(cosine)) }
+}
+
+/// Where a sphere meets a plane or another sphere
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SphereIntersection {
+    /// No contact; the gap between the surfaces, negative when one sphere
+    /// encloses the other
+    Disjoint { gap: Length },
+    Tangent(Vector3),
+    Circle(Circle),
+    /// Identical spheres
+    Coincident,
+}
+
+impl SphereIntersection {
+    pub fn intersects(&self) -> bool {
+        !matches!(self, SphereIntersection::Disjoint { .. })
+    }
+}
+
+pub fn sphere_plane(sphere: &Sphere, plane: &Plane) -> SphereIntersection {
+    let offset = *point_plane_distance(sphere.center, plane).value();
+    let foot = plane.project(sphere.center);
+    let gap = offset.abs() - sphere.radius;
+    if gap.abs() <= INCIDENCE_TOLERANCE {
+        SphereIntersection::Tangent(foot)
+    } else if gap > 0.0 {
+        SphereIntersection::Disjoint { gap: Length::new(gap) }
+    } else {
+        let radius = (sphere.radius * sphere.radius - offset * offset).sqrt();
+        SphereIntersection::Circle(Circle { center: foot, normal: plane.normal, radius })
+    }
+}
+
+pub fn sphere_sphere(first: &Sphere, second: &Sphere) -> SphereIntersection {
+    let between = linalg::sub(second.center, first.center);
+    let d = linalg::norm(between);
+    let (r1, r2) = (first.radius, second.radius);
+    if d <= INCIDENCE_TOLERANCE && (r1 - r2).abs() <= INCIDENCE_TOLERANCE {
+        return SphereIntersection::Coincident;
+    }
+    // Outside each other the gap is d - r1 - r2; nested it is |r1 - r2| - d, reported negative
+    let outer_gap = d - r1 - r2;
+    let inner_gap = (r1 - r2).abs() - d;
+    if outer_gap > INCIDENCE_TOLERANCE {
+        return SphereIntersection::Disjoint { gap: Length::new(outer_gap) };
+    }
+    if inner_gap > INCIDENCE_TOLERANCE {
+        return SphereIntersection::Disjoint { gap: Length::new(-inner_gap) };
+    }
+
+    let normal = linalg::scale(between, 1.0 / d);
+    // Distance from the first center to the radical plane
+    let along = (d * d + r1 * r1 - r2 * r2) / (2.0 * d);
+    let center = linalg::add(first.center, linalg::scale(normal, along));
+    if outer_gap.abs() <= INCIDENCE_TOLERANCE || inner_gap.abs() <= INCIDENCE_TOLERANCE {
+        return SphereIntersection::Tangent(center);
+    }
+    SphereIntersection::Circle(Circle { center, normal, radius: (r1 * r1 - along * along).sqrt() })
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::TAU;
+
+    const EPSILON: f64 = 1e-12;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_point_queries() {
+        let plane = Plane::new([0.0, 0.0, 2.0], 1.0);
+        let line = Line::through([0.0, 1.0, 0.0], [1.0, 0.0, 0.0]);
+        let sphere = Sphere::new([1.0, 1.0, 1.0], 2.0);
+
+        // (point, distance to origin, to plane z = 1, to line y = 1 along x, to sphere)
+        let table: [(Vector3, f64, f64, f64, f64); 5] = [
+            ([0.0, 0.0, 0.0], 0.0, -1.0, 1.0, 3f64.sqrt() - 2.0),
+            ([0.0, 0.0, 1.0], 1.0, 0.0, 2f64.sqrt(), 2f64.sqrt() - 2.0),
+            ([5.0, 1.0, 0.0], 26f64.sqrt(), -1.0, 0.0, 17f64.sqrt() - 2.0),
+            ([1.0, 1.0, 3.0], 11f64.sqrt(), 2.0, 3.0, 0.0),
+            ([1.0, 4.0, 5.0], 42f64.sqrt(), 4.0, 34f64.sqrt(), 5.0 - 2.0),
+        ];
+        for (point, origin, to_plane, to_line, to_sphere) in table {
+            assert!((point_point_distance(point, [0.0; 3]).value() - origin).abs() < EPSILON, "{:?}", point);
+            assert!((point_plane_distance(point, &plane).value() - to_plane).abs() < EPSILON, "{:?}", point);
+            assert!((point_line_distance(point, &line).value() - to_line).abs() < EPSILON, "{:?}", point);
+            assert!((point_sphere_distance(point, &sphere).value() - to_sphere).abs() < EPSILON, "{:?}", point);
+            assert_eq!(point_in_sphere(point, &sphere), to_sphere <= 0.0, "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn test_line_queries() {
+        let x_axis = Line::through([0.0; 3], [1.0, 0.0, 0.0]);
+
+        // (second line, relation, distance, angle)
+        let table = [
+            (Line::through([5.0, 0.0, 0.0], [-2.0, 0.0, 0.0]), LineRelation::Coincident, 0.0, TAU / 2.0),
+            (Line::through([0.0, 3.0, 0.0], [1.0, 0.0, 0.0]), LineRelation::Parallel, 3.0, 0.0),
+            (Line::through([2.0, 0.0, 0.0], [0.0, 1.0, 1.0]), LineRelation::Intersecting, 0.0, TAU / 4.0),
+            (Line::through([0.0, 0.0, 2.0], [1.0, 1.0, 0.0]), LineRelation::Skew, 2.0, TAU / 8.0),
+        ];
+        for (line, relation, distance, angle) in table {
+            let normal = line_line(&x_axis, &line);
+            assert_eq!(normal.relation, relation, "{:?}", line);
+            assert!((normal.distance.value() - distance).abs() < 1e-9, "{:?}", line);
+            assert!((normal.angle.value() - angle).abs() < 1e-9, "{:?}", line);
+            assert_eq!(normal.line().is_some(), distance > 0.0);
+        }
+        let skew = line_line(&x_axis, &Line::through([3.0, 0.0, 2.0], [1.0, 1.0, 0.0]));
+        assert_close(skew.on_first, [3.0, 0.0, 0.0]);
+        assert_close(skew.on_second, [3.0, 0.0, 2.0]);
+
+        let floor = Plane::new([0.0, 0.0, 1.0], 0.0);
+        match line_plane(&Line::through([1.0, 1.0, 1.0], [1.0, 0.0, -1.0]), &floor) {
+            LinePlaneIntersection::Point { point, angle } => {
+                assert_close(point, [2.0, 1.0, 0.0]);
+                assert!((angle.value() - TAU / 8.0).abs() < 1e-9);
+            }
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(line_plane(&x_axis, &floor), LinePlaneIntersection::Contained);
+        assert_eq!(
+            line_plane(&Line::through([0.0, 0.0, -2.0], [0.0, 1.0, 0.0]), &floor),
+            LinePlaneIntersection::Parallel { distance: Length::new(-2.0) }
+        );
+
+        let ball = Sphere::new([0.0, 0.0, 1.0], 1.0);
+        assert_eq!(line_sphere(&x_axis, &ball), LineSphereIntersection::Tangent([0.0; 3]));
+        assert_eq!(
+            line_sphere(&Line::through([0.0, 0.0, 4.0], [0.0, 1.0, 0.0]), &ball),
+            LineSphereIntersection::Disjoint { distance: Length::new(2.0) }
+        );
+        match line_sphere(&Line::through([0.0, 0.0, 5.0], [0.0, 0.0, -1.0]), &ball) {
+            LineSphereIntersection::Secant(entry, exit) => {
+                assert_close(entry, [0.0, 0.0, 2.0]);
+                assert_close(exit, [0.0, 0.0, 0.0]);
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_plane_and_sphere_queries() {
+        let floor = Plane::new([0.0, 0.0, 1.0], 0.0);
+        match plane_plane(&floor, &Plane::new([1.0, 0.0, 0.0], 2.0)) {
+            PlanePlaneIntersection::Line { line, angle } => {
+                assert!((angle.value() - TAU / 4.0).abs() < 1e-12);
+                assert!(line.distance_to_point([2.0, 7.0, 0.0]) < 1e-12);
+                assert_close(line.normalized().direction, [0.0, 1.0, 0.0]);
+            }
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(plane_plane(&floor, &Plane::new([0.0, 0.0, -1.0], 0.0)), PlanePlaneIntersection::Coincident);
+        assert_eq!(
+            plane_plane(&floor, &Plane::new([0.0, 0.0, -2.0], 3.0)),
+            PlanePlaneIntersection::Parallel { distance: Length::new(-3.0) }
+        );
+
+        // (sphere, intersects the floor z = 0, circle radius)
+        let table = [
+            (Sphere::new([0.0, 0.0, 3.0], 1.0), false, None),
+            (Sphere::new([0.0, 0.0, 1.0], 1.0), true, None),
+            (Sphere::new([0.0, 0.0, -3.0], 5.0), true, Some(4.0)),
+        ];
+        for (sphere, intersects, radius) in table {
+            let contact = sphere_plane(&sphere, &floor);
+            assert_eq!(contact.intersects(), intersects, "{:?}", sphere);
+            match contact {
+                SphereIntersection::Circle(circle) => assert!((circle.radius - radius.unwrap()).abs() < EPSILON),
+                _ => assert!(radius.is_none()),
+            }
+        }
+
+        let unit = Sphere::new([0.0; 3], 1.0);
+        assert_eq!(sphere_sphere(&unit, &Sphere::new([0.0, 0.0, 3.0], 1.0)), SphereIntersection::Disjoint { gap: Length::new(1.0) });
+        assert_eq!(sphere_sphere(&unit, &Sphere::new([0.0; 3], 0.5)), SphereIntersection::Disjoint { gap: Length::new(-0.5) });
+        assert_eq!(sphere_sphere(&unit, &Sphere::new([2.0, 0.0, 0.0], 1.0)), SphereIntersection::Tangent([1.0, 0.0, 0.0]));
+        assert_eq!(sphere_sphere(&unit, &unit), SphereIntersection::Coincident);
+        match sphere_sphere(&unit, &Sphere::new([1.0, 0.0, 0.0], 1.0)) {
+            SphereIntersection::Circle(circle) => {
+                assert_close(circle.center, [0.5, 0.0, 0.0]);
+                assert!((circle.radius - 0.75f64.sqrt()).abs() < EPSILON);
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Reachability checks to run before inverse kinematics or planning
+//!
+//! [`KinematicChain::can_reach`] answers whether the end effector can plausibly
+//! reach the position of a target pose, and if not, why, as [`Unreachable`]
+//! values a caller can act on. Three checks run from cheapest to dearest:
+//!
+//! 1. Workspace radius: no configuration places the end effector farther from
+//!    the first joint than the sum of the link offsets, the prismatic travel
+//!    and the tool offset, whatever the limits.
+//! 2. Joint limits: configurations sampled within the limits are refined
+//!    toward the target by clamped damped least squares on position alone.
+//!    Failing to get within the tolerance is a heuristic verdict, not a proof;
+//!    the best configuration is returned as a seed for the full solve.
+//! 3. Obstacle clearance: the signed distance from the target to the nearest
+//!    obstacle of any [`ClearanceQuery`] must exceed the required clearance.
+//!
+//! Orientation is left to [`KinematicChain::inverse`]. [`ReachReport::violations`]
+//! gives the failed checks as [`SafetyViolation`](crate::safety::SafetyViolation)s.
+
+use std::fmt;
+
+use crate::collision::{Capsule, ClearanceQuery};
+use crate::joints::Joint;
+use crate::kinematics::{Jacobian, KinematicChain};
+use crate::linalg::{self, Vector3};
+use crate::motor::Motor;
+use crate::sample::Sampler;
+use crate::si_units::Length;
+
+/// A reason the target cannot be reached
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unreachable {
+    /// Farther from the first joint than the fully stretched chain reaches
+    BeyondWorkspace { distance: Length, reach: Length },
+    /// No configuration found within the joint limits came closer than `position_error`
+    JointLimits { position_error: Length, tolerance: Length },
+    /// The target is closer to an obstacle than the required clearance
+    Obstructed { clearance: Length, required: Length },
+}
+
+impl fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unreachable::BeyondWorkspace { distance, reach } => {
+                write!(f, "target is {} m from the first joint but the chain reaches {} m", distance.value(), reach.value())
+            }
+            Unreachable::JointLimits { position_error, .. } => {
+                write!(f, "no configuration within the joint limits came closer than {} m", position_error.value())
+            }
+            Unreachable::Obstructed { clearance, required } => {
+                write!(f, "target has {} m of clearance to the nearest obstacle but needs {} m", clearance.value(), required.value())
+            }
+        }
+    }
+}
+
+/// Settings of [`KinematicChain::can_reach_with`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReachOptions {
+    /// Configurations sampled within the joint limits
+    pub samples: usize,
+    /// Best samples refined toward the target
+    pub refined: usize,
+    pub iterations: usize,
+    /// Position error below which the target counts as reached
+    pub tolerance: Length,
+    /// Clearance the target needs from every obstacle
+    pub clearance: Length,
+    pub seed: u64,
+}
+
+impl Default for ReachOptions {
+    fn default() -> Self {
+        Self { samples: 64, refined: 4, iterations: 100, tolerance: Length::new(1e-3), clearance: Length::new(0.0), seed: 1 }
+    }
+}
+
+/// Outcome of the reachability checks
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReachReport {
+    /// Distance from the first joint's origin to the target
+    pub distance: Length,
+    /// Radius of the workspace around that origin
+    pub reach: Length,
+    /// Smallest position error found within the joint limits, unless the
+    /// target is beyond the workspace
+    pub position_error: Option<Length>,
+    /// Coordinates achieving `position_error`, a seed for inverse kinematics
+    pub seed: Option<Vec<f64>>,
+    /// Signed distance from the target to the nearest obstacle, when obstacles were given
+    pub clearance: Option<Length>,
+    /// Empty when every check passed
+    pub reasons: Vec<Unreachable>,
+}
+
+impl ReachReport {
+    pub fn is_reachable(&self) -> bool {
+        self.reasons.is_empty()
+    }
+}
+
+impl KinematicChain {
+    /// Radius around the first joint's origin that contains every end-effector position
+    pub fn workspace_radius(&self) -> Length {
+        let links: f64 = self.segments().iter().skip(1).map(|segment| linalg::norm(segment.origin.translation)).sum();
+        let travel: f64 = self
+            .joints()
+            .map(|joint| match joint {
+                Joint::Prismatic { limits, .. } => limits.lower.value().abs().max(limits.upper.value().abs()),
+                _ => 0.0,
+            })
+            .sum();
+        Length::new(links + travel + linalg::norm(self.tcp().translation))
+    }
+
+    /// Reachability of `target`'s position with the default options and no obstacles
+    pub fn can_reach(&self, target: &Motor) -> ReachReport {
+        self.can_reach_with(target, &ReachOptions::default(), None)
+    }
+
+    /// Reachability of `target`'s position, also checking its clearance to `obstacles`
+    pub fn can_reach_with(&self, target: &Motor, options: &ReachOptions, obstacles: Option<&dyn ClearanceQuery>) -> ReachReport {
+        let center = self.segments().first().map_or([0.0; 3], |segment| segment.origin.translation);
+        let distance = Length::new(linalg::norm(linalg::sub(target.translation, center)));
+        let reach = self.workspace_radius();
+        let mut report = ReachReport { distance, reach, position_error: None, seed: None, clearance: None, reasons: Vec::new() };
+
+        if *distance.value() > reach.value() + options.tolerance.value() {
+            report.reasons.push(Unreachable::BeyondWorkspace { distance, reach });
+        } else {
+            let (q, error) = self.nearest_within_limits(target.translation, options);
+            if error > *options.tolerance.value() {
+                report.reasons.push(Unreachable::JointLimits { position_error: Length::new(error), tolerance: options.tolerance });
+            }
+            report.position_error = Some(Length::new(error));
+            report.seed = Some(q);
+        }
+
+        if let Some(clearance) = obstacles.and_then(|obstacles| obstacles.clearance(&Capsule::point(target.translation))) {
+            if clearance.value() < options.clearance.value() {
+                report.reasons.push(Unreachable::Obstructed { clearance, required: options.clearance });
+            }
+            report.clearance = Some(clearance);
+        }
+        report
+    }
+
+    /// Configuration within the limits whose end effector is closest to
+    /// `point`, found from the best samples, and its distance
+    fn nearest_within_limits(&self, point: Vector3, options: &ReachOptions) -> (Vec<f64>, f64) {
+        let joints: Vec<Joint> = self.joints().copied().collect();
+        let mut sampler = Sampler::new(options.seed);
+        let error = |q: &[f64]| {
+            let position = self.forward(q).expect("coordinates match the chain").translation;
+            linalg::norm(linalg::sub(point, position))
+        };
+        let mut candidates: Vec<(f64, Vec<f64>)> = std::iter::once(self.neutral())
+            .chain((0..options.samples).map(|_| sampler.joint_coordinates(&joints)))
+            .map(|mut q| {
+                self.clamp(&mut q).expect("coordinates match the chain");
+                (error(&q), q)
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut best = candidates[0].clone();
+        for (_, mut q) in candidates.into_iter().take(options.refined.max(1)) {
+            for _ in 0..options.iterations {
+                let position = self.forward(&q).expect("coordinates match the chain").translation;
+                let offset = linalg::sub(point, position);
+                if linalg::norm(offset) < *options.tolerance.value() {
+                    break;
+                }
+                // Position rows only, so the orientation is free
+                let jacobian = self.jacobian(&q).expect("coordinates match the chain");
+                let linear = Jacobian { columns: jacobian.columns.iter().map(|c| [0.0, 0.0, 0.0, c[3], c[4], c[5]]).collect() };
+                let mut dq = linear.damped_least_squares([0.0, 0.0, 0.0, offset[0], offset[1], offset[2]], 1e-2);
+                let largest = dq.iter().fold(0.0f64, |m, d| m.max(d.abs()));
+                if largest > 0.2 {
+                    dq.iter_mut().for_each(|d| *d *= 0.2 / largest);
+                }
+                self.integrate(&mut q, &dq).expect("coordinates match the chain");
+                self.clamp(&mut q).expect("coordinates match the chain");
+            }
+            let refined = error(&q);
+            if refined < best.0 {
+                best = (refined, q);
+            }
+        }
+        (best.1, best.0)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::{Bvh, Shape};
+    use crate::primitives::Sphere;
+    use crate::joints::{JointLimits, PrismaticLimits};
+    use crate::si_units::{Angle, AngularVelocity, Velocity, TAU};
+
+    /// Planar arm: two 1 m links about z, the elbow limited to ±90°, then a 0.2 m prismatic stroke
+    fn arm() -> KinematicChain {
+        let limits = |half: f64| JointLimits::new(Angle::new(-half), Angle::new(half), AngularVelocity::new(1.0));
+        KinematicChain::new()
+            .with_joint(Motor::identity(), Joint::revolute([0.0, 0.0, 1.0], limits(TAU / 2.0)))
+            .with_joint(Motor::from_translation([1.0, 0.0, 0.0]), Joint::revolute([0.0, 0.0, 1.0], limits(TAU / 4.0)))
+            .with_joint(
+                Motor::from_translation([1.0, 0.0, 0.0]),
+                Joint::prismatic([1.0, 0.0, 0.0], PrismaticLimits::new(Length::new(0.0), Length::new(0.2), Velocity::new(0.1))),
+            )
+    }
+
+    #[test]
+    fn test_reachable_target_gives_seed() {
+        let chain = arm();
+        assert_eq!(*chain.workspace_radius().value(), 2.2);
+        let target = Motor::from_translation([0.5, 1.6, 0.0]);
+        let report = chain.can_reach(&target);
+        assert!(report.is_reachable(), "{:?}", report.reasons);
+        let seed = report.seed.unwrap();
+        assert!(chain.within_limits(&seed).unwrap());
+        assert!(linalg::norm(linalg::sub(chain.forward(&seed).unwrap().translation, target.translation)) < 1e-3);
+        assert!(report.position_error.unwrap() < Length::new(1e-3));
+    }
+
+    #[test]
+    fn test_unreachable_reasons() {
+        let chain = arm();
+        let far = chain.can_reach(&Motor::from_translation([3.0, 0.0, 0.0]));
+        assert_eq!(far.reasons, vec![Unreachable::BeyondWorkspace { distance: Length::new(3.0), reach: Length::new(2.2) }]);
+        assert!(far.seed.is_none());
+
+        // Close to the shoulder needs the elbow folded past its ±90° limit
+        let near = chain.can_reach(&Motor::from_translation([0.3, 0.0, 0.0]));
+        match near.reasons.as_slice() {
+            [Unreachable::JointLimits { position_error, .. }] => assert!(*position_error.value() > 0.5, "{:?}", position_error),
+            other => panic!("unexpected reasons {:?}", other),
+        }
+        // Off the plane of the arm
+        assert!(matches!(chain.can_reach(&Motor::from_translation([1.0, 0.0, 0.5])).reasons[..], [Unreachable::JointLimits { .. }]));
+
+        let obstacles = Bvh::build(vec![Shape::Sphere(Sphere::new([1.5, 1.0, 0.0], 0.3))]);
+        let options = ReachOptions { clearance: Length::new(0.05), ..ReachOptions::default() };
+        let blocked = chain.can_reach_with(&Motor::from_translation([1.5, 1.2, 0.0]), &options, Some(&obstacles));
+        assert_eq!(blocked.reasons.len(), 1);
+        assert!(matches!(blocked.reasons[0], Unreachable::Obstructed { clearance, .. } if (*clearance.value() + 0.1).abs() < 1e-12));
+        assert!(blocked.reasons[0].to_string().contains("clearance"));
+        let violations = blocked.violations();
+        assert_eq!((violations[0].joint, violations[0].limit), (None, 0.05));
+        assert!((violations[0].margin - 0.15).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,270 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Timestamped data recording for control loops
+//!
+//! A [`Recorder`] holds named channels, each a [`RingBuffer`] allocated once at
+//! registration. Registering a channel returns a typed [`Channel`] handle, so a
+//! channel of poses cannot be fed a velocity. Recording moves the value into
+//! its slot without allocating, overwriting the oldest sample when the buffer is
+//! full; the number of overwritten samples is kept so gaps are visible.
+//!
+//! Serialization happens only in [`Recorder::flush`], which drains every
+//! channel into a time-ordered [`ReplayLog`] for storage or replay.
+
+use std::any::Any;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::Serialize;
+
+use crate::replay::{Record, ReplayError, ReplayLog};
+use crate::si_units::Time;
+
+/// Fixed-capacity buffer that overwrites its oldest entry when full
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    slots: Vec<T>,
+    capacity: usize,
+    /// Index of the oldest entry once full
+    head: usize,
+    overwritten: u64,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "ring buffer capacity must be positive");
+        Self { slots: Vec::with_capacity(capacity), capacity, head: 0, overwritten: 0 }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.slots.len() < self.capacity {
+            self.slots.push(value);
+        } else {
+            self.slots[self.head] = value;
+            self.head = (self.head + 1) % self.capacity;
+            self.overwritten += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Entries lost to overwriting since creation
+    pub fn overwritten(&self) -> u64 {
+        self.overwritten
+    }
+
+    /// Entries from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.slots[self.head..].iter().chain(&self.slots[..self.head])
+    }
+
+    pub fn latest(&self) -> Option<&T> {
+        match self.head {
+            0 => self.slots.last(),
+            head => self.slots.get(head - 1),
+        }
+    }
+
+    /// Remove and return every entry from oldest to newest, keeping the allocation
+    pub fn drain(&mut self) -> Vec<T> {
+        self.slots.rotate_left(self.head);
+        self.head = 0;
+        self.slots.drain(..).collect()
+    }
+}
+
+/// Typed handle to a registered channel
+#[derive(Debug)]
+pub struct Channel<T> {
+    index: usize,
+    _type: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Channel<T> {}
+
+/// Errors raised by the recorder
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecorderError {
+    DuplicateChannel(String),
+    /// A value could not be serialized while flushing
+    Serialize(ReplayError),
+}
+
+impl fmt::Display for RecorderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecorderError::DuplicateChannel(name) => write!(f, "channel '{}' is already registered", name),
+            RecorderError::Serialize(e) => write!(f, "flushing: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RecorderError {}
+
+/// Channel storage with its value type erased
+trait Stored: Any {
+    fn name(&self) -> &str;
+    fn overwritten(&self) -> u64;
+    fn drain_records(&mut self, records: &mut Vec<Record>) -> Result<(), ReplayError>;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn as_any(&self) -> &dyn Any;
+}
+
+struct Storage<T> {
+    name: String,
+    buffer: RingBuffer<(Time, T)>,
+}
+
+impl<T: Serialize + 'static> Stored for Storage<T> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn overwritten(&self) -> u64 {
+        self.buffer.overwritten()
+    }
+
+    fn drain_records(&mut self, records: &mut Vec<Record>) -> Result<(), ReplayError> {
+        for (time, value) in self.buffer.drain() {
+            records.push(Record::new(time, &self.name, &value)?);
+        }
+        Ok(())
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Named, typed ring-buffer channels
+#[derive(Default)]
+pub struct Recorder {
+    channels: Vec<Box<dyn Stored>>,
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.channels.iter().map(|c| c.name())).finish()
+    }
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocate a channel holding the latest `capacity` samples
+    pub fn register<T: Serialize + 'static>(&mut self, name: &str, capacity: usize) -> Result<Channel<T>, RecorderError> {
+        if self.channels.iter().any(|c| c.name() == name) {
+            return Err(RecorderError::DuplicateChannel(name.into()));
+        }
+        self.channels.push(Box::new(Storage::<T> { name: name.into(), buffer: RingBuffer::new(capacity) }));
+        Ok(Channel { index: self.channels.len() - 1, _type: PhantomData })
+    }
+
+    pub fn record<T: Serialize + 'static>(&mut self, channel: Channel<T>, time: Time, value: T) {
+        self.storage_mut(channel).buffer.push((time, value));
+    }
+
+    /// Samples of a channel from oldest to newest
+    pub fn samples<T: Serialize + 'static>(&self, channel: Channel<T>) -> impl Iterator<Item = &(Time, T)> + '_ {
+        self.storage(channel).buffer.iter()
+    }
+
+    pub fn latest<T: Serialize + 'static>(&self, channel: Channel<T>) -> Option<&(Time, T)> {
+        self.storage(channel).buffer.latest()
+    }
+
+    /// Samples lost to overwriting on each channel, by name
+    pub fn overwritten(&self) -> Vec<(&str, u64)> {
+        self.channels.iter().map(|c| (c.name(), c.overwritten())).collect()
+    }
+
+    /// Empty every channel into a time-ordered log
+    pub fn flush(&mut self) -> Result<ReplayLog, RecorderError> {
+        let mut records = Vec::new();
+        for channel in &mut self.channels {
+            channel.drain_records(&mut records).map_err(RecorderError::Serialize)?;
+        }
+        Ok(ReplayLog::new(records))
+    }
+
+    fn storage<T: 'static>(&self, channel: Channel<T>) -> &Storage<T> {
+        self.channels[channel.index].as_any().downcast_ref().expect("channel handle from another recorder")
+    }
+
+    fn storage_mut<T: 'static>(&mut self, channel: Channel<T>) -> &mut Storage<T> {
+        self.channels[channel.index].as_any_mut().downcast_mut().expect("channel handle from another recorder")
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motor::{Motor, Rotor};
+    use crate::si_units::Velocity;
+
+    #[test]
+    fn test_ring_buffer_overwrites_oldest() {
+        let mut ring = RingBuffer::new(3);
+        assert_eq!(ring.latest(), None);
+        for i in 0..5 {
+            ring.push(i);
+        }
+        assert_eq!((ring.len(), ring.overwritten()), (3, 2));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!(ring.latest(), Some(&4));
+        assert_eq!(ring.drain(), [2, 3, 4]);
+        assert!(ring.is_empty() && ring.capacity() == 3);
+        ring.push(7);
+        assert_eq!(ring.latest(), Some(&7));
+    }
+
+    #[test]
+    fn test_recorder_flushes_time_ordered_log() {
+        let mut recorder = Recorder::new();
+        let speed = recorder.register::<Velocity>("speed", 2).unwrap();
+        let pose = recorder.register::<Motor>("pose", 8).unwrap();
+        assert_eq!(recorder.register::<Velocity>("speed", 2).unwrap_err(), RecorderError::DuplicateChannel("speed".into()));
+
+        for step in 0..3 {
+            let t = Time::new(step as f64 * 0.1);
+            recorder.record(speed, t, Velocity::new(step as f64));
+            recorder.record(pose, t, Motor::new([step as f64, 0.0, 0.0], Rotor::identity()));
+        }
+        assert_eq!(*recorder.latest(speed).unwrap().1.value(), 2.0);
+        assert_eq!(recorder.samples(pose).count(), 3);
+        assert_eq!(recorder.overwritten(), [("speed", 1), ("pose", 0)]);
+
+        let log = recorder.flush().unwrap();
+        let channels: Vec<_> = log.records().iter().map(|r| r.channel.as_str()).collect();
+        assert_eq!(channels, ["pose", "speed", "pose", "speed", "pose"]);
+        let poses: Vec<Motor> = log.channel("pose").map(|r| r.decode().unwrap()).collect();
+        assert_eq!(poses[2].apply_point([0.0; 3]), [2.0, 0.0, 0.0]);
+        assert_eq!(log.channel("speed").next().unwrap().decode::<f64>().unwrap(), 1.0);
+        assert_eq!(recorder.samples(speed).count(), 0);
+    }
+}
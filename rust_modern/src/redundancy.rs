@@ -0,0 +1,282 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Null-space redundancy resolution
+//!
+//! A chain with more than six coordinates can move its joints without moving
+//! the end effector. [`Redundancy`] spends that freedom on secondary objectives,
+//! each a gradient in coordinate space projected through `I - J⁺ J` so that it
+//! does not disturb the primary task, which is solved by damped least squares:
+//!
+//! - joint-limit avoidance pulls every coordinate toward the middle of its range
+//! - manipulability maximization climbs the gradient of `√det(J Jᵀ)`, away from
+//!   singular configurations
+//!
+//! The objectives are weighted by [`Ratio`]s, so a weight outside `[0, 1]` is
+//! rejected when it is built, and [`Redundancy::gain`] scales their sum.
+//!
+//! [`Redundancy::joint_velocities`] is the resolved-rate control law for an
+//! end-effector twist, and [`Redundancy::inverse`] is
+//! [`KinematicChain::inverse`] with the same null-space term, continuing after
+//! the pose converges until the secondary step dies out.
+
+use serde::{Deserialize, Serialize};
+
+use crate::joints::Joint;
+use crate::kinematics::{IkOptions, IkSolution, Jacobian, KinematicChain, KinematicsError};
+use crate::motor::Motor;
+use crate::si_units::Ratio;
+
+/// Coordinate step of the finite-difference manipulability gradient
+const GRADIENT_STEP: f64 = 1e-6;
+
+/// Relative weights of the secondary objectives
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct RedundancyWeights {
+    /// Pull toward the middle of the joint ranges
+    pub joint_limits: Ratio,
+    /// Push along the manipulability gradient
+    pub manipulability: Ratio,
+}
+
+impl RedundancyWeights {
+    pub fn new(joint_limits: Ratio, manipulability: Ratio) -> Self {
+        Self { joint_limits, manipulability }
+    }
+
+    pub fn joint_limits() -> Self {
+        Self::new(Ratio::ONE, Ratio::ZERO)
+    }
+
+    pub fn manipulability() -> Self {
+        Self::new(Ratio::ZERO, Ratio::ONE)
+    }
+}
+
+/// Secondary objectives of a redundant chain and how hard to pursue them
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Redundancy {
+    pub weights: RedundancyWeights,
+    /// Coordinate velocity per unit of the weighted gradient
+    pub gain: f64,
+    /// Damping λ of the primary step in [`joint_velocities`](Self::joint_velocities)
+    pub damping: f64,
+    /// [`inverse`](Self::inverse) stops once the projected secondary step is shorter than this
+    pub tolerance: f64,
+}
+
+impl Default for Redundancy {
+    fn default() -> Self {
+        Self { weights: RedundancyWeights::default(), gain: 1.0, damping: 1e-3, tolerance: 1e-6 }
+    }
+}
+
+impl Redundancy {
+    pub fn new(weights: RedundancyWeights) -> Self {
+        Self { weights, ..Self::default() }
+    }
+
+    pub fn with_gain(mut self, gain: f64) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f64) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Weighted gradient of the secondary objectives at `q`, before projection
+    pub fn gradient(&self, chain: &KinematicChain, q: &[f64]) -> Result<Vec<f64>, KinematicsError> {
+        let mut gradient = vec![0.0; q.len()];
+        let limits = self.weights.joint_limits.value();
+        if limits > 0.0 {
+            let toward_middle = joint_limit_gradient(chain, q)?;
+            gradient.iter_mut().zip(toward_middle).for_each(|(g, d)| *g += limits * d);
+        }
+        let manipulability = self.weights.manipulability.value();
+        if manipulability > 0.0 {
+            let uphill = manipulability_gradient(chain, q)?;
+            gradient.iter_mut().zip(uphill).for_each(|(g, d)| *g += manipulability * d);
+        }
+        gradient.iter_mut().for_each(|g| *g *= self.gain);
+        Ok(gradient)
+    }
+
+    /// The secondary step at `q` projected into the null space of `jacobian`
+    pub fn null_space_step(&self, chain: &KinematicChain, q: &[f64], jacobian: &Jacobian) -> Result<Vec<f64>, KinematicsError> {
+        Ok(jacobian.project_null_space(&self.gradient(chain, q)?))
+    }
+
+    /// Coordinate velocities producing the end-effector `twist` while pursuing the secondary objectives
+    pub fn joint_velocities(&self, chain: &KinematicChain, q: &[f64], twist: [f64; 6]) -> Result<Vec<f64>, KinematicsError> {
+        let jacobian = chain.jacobian(q)?;
+        let primary = jacobian.damped_least_squares(twist, self.damping);
+        let secondary = self.null_space_step(chain, q, &jacobian)?;
+        Ok(primary.iter().zip(secondary).map(|(p, s)| p + s).collect())
+    }
+
+    /// Coordinates within the limits placing the end effector at `target`, improved by the secondary objectives
+    ///
+    /// The primary step uses the damping of `options`, not [`damping`](Self::damping).
+    pub fn inverse(
+        &self,
+        chain: &KinematicChain,
+        target: &Motor,
+        initial: &[f64],
+        options: &IkOptions,
+    ) -> Result<IkSolution, KinematicsError> {
+        let secondary = |q: &[f64], jacobian: &Jacobian| {
+            let step = jacobian.project_null_space(&self.gradient(chain, q)?);
+            let length = step.iter().map(|s| s * s).sum::<f64>().sqrt();
+            Ok((length >= self.tolerance).then_some(step))
+        };
+        chain.inverse_with(target, initial, options, Some(&secondary))
+    }
+}
+
+/// Gradient of `-½ Σ ((q - mid) / half-range)²`, zero at the middle of every range
+///
+/// A spherical joint is centred on its neutral orientation with the largest
+/// angle as half-range; fixed joints have no coordinates.
+pub fn joint_limit_gradient(chain: &KinematicChain, q: &[f64]) -> Result<Vec<f64>, KinematicsError> {
+    if q.len() != chain.dof() {
+        return Err(KinematicsError::CoordinateCount { expected: chain.dof(), actual: q.len() });
+    }
+    let toward = |q: f64, lower: f64, upper: f64| {
+        let half = (upper - lower) / 2.0;
+        if half > 0.0 { (lower + half - q) / (half * half) } else { 0.0 }
+    };
+    let mut gradient = Vec::with_capacity(q.len());
+    let mut coordinates = q.iter().copied();
+    for joint in chain.joints() {
+        match joint {
+            Joint::Revolute { limits, .. } => {
+                gradient.extend(coordinates.next().map(|q| toward(q, *limits.lower.value(), *limits.upper.value())))
+            }
+            Joint::Prismatic { limits, .. } => {
+                gradient.extend(coordinates.next().map(|q| toward(q, *limits.lower.value(), *limits.upper.value())))
+            }
+            Joint::Spherical { limits } => {
+                let max = *limits.max_angle.value();
+                gradient.extend(coordinates.by_ref().take(3).map(|q| toward(q, -max, max)))
+            }
+            Joint::Fixed => {}
+        }
+    }
+    Ok(gradient)
+}
+
+/// Forward-difference gradient of [`Jacobian::manipulability`], in the Jacobian's coordinate basis
+pub fn manipulability_gradient(chain: &KinematicChain, q: &[f64]) -> Result<Vec<f64>, KinematicsError> {
+    let here = chain.jacobian(q)?.manipulability();
+    (0..q.len())
+        .map(|k| {
+            let mut step = vec![0.0; q.len()];
+            step[k] = GRADIENT_STEP;
+            let mut moved = q.to_vec();
+            chain.integrate(&mut moved, &step)?;
+            Ok((chain.jacobian(&moved)?.manipulability() - here) / GRADIENT_STEP)
+        })
+        .collect()
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::joints::JointLimits;
+    use crate::kinematics::pose_error;
+    use crate::linalg::Vector3;
+    use crate::si_units::{Angle, AngularVelocity, TAU};
+
+    const Y: Vector3 = [0.0, 1.0, 0.0];
+    const Z: Vector3 = [0.0, 0.0, 1.0];
+
+    /// Seven revolute joints alternating z and y, like a light-weight arm with an elbow roll
+    fn arm() -> KinematicChain {
+        let limits = JointLimits::new(Angle::new(-TAU / 3.0), Angle::new(TAU / 3.0), AngularVelocity::new(2.0));
+        let up = |d: f64| Motor::from_translation([0.0, 0.0, d]);
+        KinematicChain::new()
+            .with_name("redundant_7r")
+            .with_joint(Motor::identity(), Joint::revolute(Z, limits))
+            .with_joint(up(0.34), Joint::revolute(Y, limits))
+            .with_joint(up(0.2), Joint::revolute(Z, limits))
+            .with_joint(up(0.2), Joint::revolute(Y, limits))
+            .with_joint(up(0.2), Joint::revolute(Z, limits))
+            .with_joint(up(0.2), Joint::revolute(Y, limits))
+            .with_joint(Motor::identity(), Joint::revolute(Z, limits))
+            .with_joint(up(0.126), Joint::Fixed)
+    }
+
+    fn norm(v: &[f64]) -> f64 {
+        v.iter().map(|x| x * x).sum::<f64>().sqrt()
+    }
+
+    #[test]
+    fn test_null_space_step_leaves_end_effector_still() {
+        let chain = arm();
+        let q = [0.3, 0.6, -0.2, -0.9, 0.4, 0.7, -0.1];
+        let redundancy = Redundancy::new(RedundancyWeights::new(Ratio::ONE, Ratio::new(0.5).unwrap()));
+        let jacobian = chain.jacobian(&q).unwrap();
+        let step = redundancy.null_space_step(&chain, &q, &jacobian).unwrap();
+        assert!(norm(&step) > 1e-3);
+        assert!(norm(&jacobian.twist(&step)) < 1e-12 * norm(&step).max(1.0));
+
+        let twist = [0.0, 0.1, 0.0, 0.05, 0.0, -0.02];
+        let dq = redundancy.joint_velocities(&chain, &q, twist).unwrap();
+        let reached = jacobian.twist(&dq);
+        assert!(reached.iter().zip(twist).all(|(a, b)| (a - b).abs() < 1e-5), "{:?}", reached);
+    }
+
+    #[test]
+    fn test_joint_limit_avoidance_centres_the_solution() {
+        let chain = arm();
+        let target = chain.forward(&[0.9, 0.8, 1.2, -1.0, 0.6, 0.9, 0.5]).unwrap();
+        let initial = [0.8, 0.7, 1.1, -0.9, 0.5, 0.8, 0.4];
+        let options = IkOptions { max_iterations: 2000, ..IkOptions::default() };
+
+        let plain = chain.inverse(&target, &initial, &options).unwrap();
+        let redundancy = Redundancy::new(RedundancyWeights::joint_limits()).with_gain(0.5);
+        let centred = redundancy.inverse(&chain, &target, &initial, &options).unwrap();
+        assert!(pose_error(&chain.forward(&centred.q).unwrap(), &target).iter().all(|e| e.abs() < 1e-9));
+        assert!(centred.iterations > plain.iterations);
+        assert!(norm(&centred.q) < norm(&plain.q), "{:?} vs {:?}", centred.q, plain.q);
+    }
+
+    #[test]
+    fn test_manipulability_climbs_away_from_singularity() {
+        let chain = arm();
+        let mut q = vec![0.1, 0.3, 0.8, 0.25, -0.6, 0.2, 0.0];
+        let start = chain.forward(&q).unwrap();
+        let before = chain.jacobian(&q).unwrap().manipulability();
+        let redundancy = Redundancy::new(RedundancyWeights::manipulability()).with_gain(5.0);
+        for _ in 0..50 {
+            let dq = redundancy.joint_velocities(&chain, &q, [0.0; 6]).unwrap();
+            let dq: Vec<f64> = dq.iter().map(|v| v * 0.01).collect();
+            chain.integrate(&mut q, &dq).unwrap();
+        }
+        let after = chain.jacobian(&q).unwrap().manipulability();
+        assert!(after > before, "{} -> {}", before, after);
+        let drift = pose_error(&chain.forward(&q).unwrap(), &start);
+        assert!(norm(&drift) < 1e-3, "{:?}", drift);
+    }
+
+    #[test]
+    fn test_weights_are_checked_fractions() {
+        assert!(Ratio::new(1.5).is_err());
+        let redundancy = Redundancy::new(RedundancyWeights::default());
+        let q = [0.3, 0.6, -0.2, -0.9, 0.4, 0.7, -0.1];
+        assert_eq!(redundancy.gradient(&arm(), &q).unwrap(), vec![0.0; 7]);
+        assert_eq!(
+            joint_limit_gradient(&arm(), &q[..6]),
+            Err(KinematicsError::CoordinateCount { expected: 7, actual: 6 })
+        );
+    }
+}
@@ -0,0 +1,371 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Point cloud registration by iterative closest points
+//!
+//! [`icp`] estimates the motor `M` carrying a source cloud onto a target cloud,
+//! `q ≈ M p`. Each iteration pairs every moved source point with its nearest
+//! target point, drops pairs farther apart than the correspondence gate and
+//! takes one Gauss-Newton step on the motor, updated on the left as
+//! `M ← exp(δ) M` with `δ` in the twist order of [`MotorGenerator`].
+//!
+//! Residuals use the conformal distances: point-to-point pairs minimize
+//! `-2 P·Q = ‖p - q‖²` of the conformal points, point-to-plane pairs the signed
+//! distance to the plane through `q` with the target normal there (see
+//! [`estimate_normals`]). Point-to-plane converges in far fewer iterations on
+//! structured scenes but cannot fix motion along a flat surface.
+//!
+//! Every iteration is recorded in the [`IcpReport`], together with the
+//! smallest eigenvalue of the final normal equations, which is near zero when
+//! the scene leaves a motion unconstrained.
+
+use std::fmt;
+
+use crate::linalg::{self, dense, square, Matrix6, Vector3};
+use crate::motor::{Motor, MotorGenerator};
+use crate::primitives::{Plane, Point};
+use crate::queries;
+use crate::si_units::{Length, Ratio};
+
+/// Error metric minimized by [`icp`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcpMetric {
+    PointToPoint,
+    /// Needs target normals
+    PointToPlane,
+}
+
+/// ICP settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IcpOptions {
+    pub metric: IcpMetric,
+    pub max_iterations: usize,
+    /// Pairs farther apart than this are ignored
+    pub max_correspondence_distance: Length,
+    /// Stop once a step moves the cloud by less than this (rad and m)
+    pub tolerance: f64,
+    /// Fewest pairs an iteration may use
+    pub min_correspondences: usize,
+}
+
+impl Default for IcpOptions {
+    fn default() -> Self {
+        Self {
+            metric: IcpMetric::PointToPoint,
+            max_iterations: 50,
+            max_correspondence_distance: Length::new(1.0),
+            tolerance: 1e-9,
+            min_correspondences: 6,
+        }
+    }
+}
+
+impl IcpOptions {
+    pub fn with_metric(mut self, metric: IcpMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    pub fn with_max_correspondence_distance(mut self, distance: Length) -> Self {
+        self.max_correspondence_distance = distance;
+        self
+    }
+}
+
+/// Diagnostics of one ICP iteration, measured before its step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IcpIteration {
+    pub correspondences: usize,
+    /// Root-mean-square residual over the pairs used
+    pub rms: Length,
+    /// Norm of the twist taken
+    pub step: f64,
+}
+
+/// Outcome of an ICP run
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcpReport {
+    /// Motor carrying the source onto the target
+    pub motor: Motor,
+    pub iterations: Vec<IcpIteration>,
+    pub converged: bool,
+    /// Residual at the returned motor
+    pub rms: Length,
+    /// Fraction of source points paired at the returned motor
+    pub fitness: Ratio,
+    /// Smallest eigenvalue of the normal equations at the returned motor,
+    /// divided by the pair count
+    pub conditioning: f64,
+}
+
+/// Errors raised by [`icp`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum IcpError {
+    EmptyCloud,
+    /// Point-to-plane needs one normal per target point
+    MissingNormals,
+    TooFewCorrespondences { found: usize, needed: usize },
+    /// The pairs do not constrain every motion
+    Degenerate,
+}
+
+impl fmt::Display for IcpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IcpError::EmptyCloud => write!(f, "source and target clouds must not be empty"),
+            IcpError::MissingNormals => write!(f, "point-to-plane ICP needs one normal per target point"),
+            IcpError::TooFewCorrespondences { found, needed } => {
+                write!(f, "{} correspondences within the gate, {} needed", found, needed)
+            }
+            IcpError::Degenerate => write!(f, "correspondences leave the motion unconstrained"),
+        }
+    }
+}
+
+impl std::error::Error for IcpError {}
+
+/// Squared distance from the conformal inner product `P·Q = -½‖p - q‖²`
+fn squared_distance(p: Vector3, q: Vector3) -> f64 {
+    -2.0 * Point::new(p).inner(&Point::new(q))
+}
+
+/// Index of and squared distance to the point of `cloud` closest to `p`
+fn nearest(cloud: &[Vector3], p: Vector3) -> (usize, f64) {
+    cloud
+        .iter()
+        .enumerate()
+        .map(|(i, &q)| (i, squared_distance(p, q)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .expect("cloud is not empty")
+}
+
+/// Unit normals from the covariance of each point's `neighbours` nearest
+/// points (itself included), oriented toward `viewpoint`
+pub fn estimate_normals(cloud: &[Vector3], neighbours: usize, viewpoint: Vector3) -> Vec<Vector3> {
+    cloud
+        .iter()
+        .map(|&p| {
+            let mut by_distance: Vec<(f64, Vector3)> = cloud.iter().map(|&q| (squared_distance(p, q), q)).collect();
+            by_distance.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let neighbourhood: Vec<Vector3> = by_distance.iter().take(neighbours.max(3)).map(|&(_, q)| q).collect();
+            let count = neighbourhood.len() as f64;
+            let mean = linalg::scale(neighbourhood.iter().fold([0.0; 3], |sum, &q| linalg::add(sum, q)), 1.0 / count);
+            let mut covariance = [[0.0; 3]; 3];
+            for q in &neighbourhood {
+                let d = linalg::sub(*q, mean);
+                for i in 0..3 {
+                    for j in 0..3 {
+                        covariance[i][j] += d[i] * d[j] / count;
+                    }
+                }
+            }
+            let (_, vectors) = square::symmetric_eigen(&covariance);
+            let normal = [vectors[0][0], vectors[1][0], vectors[2][0]];
+            if linalg::dot(normal, linalg::sub(viewpoint, p)) < 0.0 {
+                linalg::scale(normal, -1.0)
+            } else {
+                normal
+            }
+        })
+        .collect()
+}
+
+/// Normal equations `H δ = -g` of the gated pairs at `motor`, with the pair
+/// count and residual sum of squares
+struct Linearization {
+    h: Matrix6,
+    g: [f64; 6],
+    pairs: usize,
+    squared_error: f64,
+}
+
+fn linearize(
+    source: &[Vector3],
+    target: &[Vector3],
+    normals: Option<&[Vector3]>,
+    motor: &Motor,
+    gate: f64,
+) -> Linearization {
+    let mut lin = Linearization { h: [[0.0; 6]; 6], g: [0.0; 6], pairs: 0, squared_error: 0.0 };
+    let mut accumulate = |row: [f64; 6], residual: f64| {
+        for i in 0..6 {
+            lin.g[i] += row[i] * residual;
+            for j in 0..6 {
+                lin.h[i][j] += row[i] * row[j];
+            }
+        }
+        lin.squared_error += residual * residual;
+    };
+
+    let mut pairs = 0;
+    for &p in source {
+        let moved = motor.apply_point(p);
+        let (index, distance2) = nearest(target, moved);
+        if distance2 > gate * gate {
+            continue;
+        }
+        pairs += 1;
+        let q = target[index];
+        // d(exp(δ) p)/dδ = [-[p]×  I] for δ = (ω, v)
+        match normals {
+            Some(normals) => {
+                let n = normals[index];
+                let residual = *queries::point_plane_distance(moved, &Plane::through(q, n)).value();
+                let w = linalg::cross(moved, n);
+                accumulate([w[0], w[1], w[2], n[0], n[1], n[2]], residual);
+            }
+            None => {
+                let skew = linalg::skew(moved);
+                for axis in 0..3 {
+                    let mut row = [0.0; 6];
+                    for k in 0..3 {
+                        row[k] = -skew[axis][k];
+                    }
+                    row[3 + axis] = 1.0;
+                    accumulate(row, moved[axis] - q[axis]);
+                }
+            }
+        }
+    }
+    lin.pairs = pairs;
+    lin
+}
+
+/// Register `source` onto `target` starting from `initial`
+///
+/// `target_normals` are only read by [`IcpMetric::PointToPlane`].
+pub fn icp(
+    source: &[Vector3],
+    target: &[Vector3],
+    target_normals: Option<&[Vector3]>,
+    initial: Motor,
+    options: &IcpOptions,
+) -> Result<IcpReport, IcpError> {
+    if source.is_empty() || target.is_empty() {
+        return Err(IcpError::EmptyCloud);
+    }
+    let normals = match options.metric {
+        IcpMetric::PointToPoint => None,
+        IcpMetric::PointToPlane => match target_normals {
+            Some(normals) if normals.len() == target.len() => Some(normals),
+            _ => return Err(IcpError::MissingNormals),
+        },
+    };
+    let gate = *options.max_correspondence_distance.value();
+    let needed = options.min_correspondences.max(1);
+    let usable = |lin: &Linearization| {
+        (lin.pairs >= needed).then_some(()).ok_or(IcpError::TooFewCorrespondences { found: lin.pairs, needed })
+    };
+
+    let mut motor = initial;
+    let mut iterations = Vec::new();
+    let mut converged = false;
+    let mut lin = linearize(source, target, normals, &motor, gate);
+    while iterations.len() < options.max_iterations && !converged {
+        usable(&lin)?;
+        let rhs: Vec<f64> = lin.g.iter().map(|value| -value).collect();
+        let step = dense::cholesky_solve(&lin.h.concat(), 6, &rhs).ok_or(IcpError::Degenerate)?;
+        let delta = MotorGenerator::from_array(step.clone().try_into().expect("six-element step"));
+        let step_norm = step.iter().map(|value| value * value).sum::<f64>().sqrt();
+        iterations.push(IcpIteration {
+            correspondences: lin.pairs,
+            rms: Length::new((lin.squared_error / lin.pairs as f64).sqrt()),
+            step: step_norm,
+        });
+        motor = Motor::exp(&delta) * motor;
+        converged = step_norm < options.tolerance;
+        lin = linearize(source, target, normals, &motor, gate);
+    }
+    usable(&lin)?;
+
+    let (eigenvalues, _) = square::symmetric_eigen(&lin.h);
+    Ok(IcpReport {
+        motor,
+        iterations,
+        converged,
+        rms: Length::new((lin.squared_error / lin.pairs as f64).sqrt()),
+        fitness: Ratio::saturating(lin.pairs as f64 / source.len() as f64),
+        conditioning: eigenvalues[0] / lin.pairs as f64,
+    })
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motor::Rotor;
+    use crate::sample::Sampler;
+
+    /// Points on the floor and two walls of a room corner, away from the edges
+    fn corner(sampler: &mut Sampler, count: usize) -> Vec<Vector3> {
+        (0..count)
+            .map(|i| {
+                let (a, b) = (sampler.range(0.5, 3.0), sampler.range(0.5, 3.0));
+                match i % 3 {
+                    0 => [a, b, 0.0],
+                    1 => [a, 0.0, b],
+                    _ => [0.0, a, b],
+                }
+            })
+            .collect()
+    }
+
+    fn truth() -> Motor {
+        Motor::new([0.12, -0.08, 0.05], Rotor::from_axis_angle([0.3, -0.2, 1.0], 0.1))
+    }
+
+    fn motor_error(estimate: &Motor, truth: &Motor) -> f64 {
+        let error = (estimate.inverse() * *truth).log();
+        linalg::norm(error.rotation) + linalg::norm(error.translation)
+    }
+
+    #[test]
+    fn test_point_to_point_recovers_motor() {
+        let mut sampler = Sampler::new(7);
+        let source = corner(&mut sampler, 300);
+        let target: Vec<Vector3> = source.iter().map(|&p| truth().apply_point(p)).collect();
+        let report = icp(&source, &target, None, Motor::identity(), &IcpOptions::default()).unwrap();
+        assert!(report.converged, "{:?}", report.iterations);
+        assert!(motor_error(&report.motor, &truth()) < 1e-6, "{:?}", report.motor);
+        assert!(*report.rms.value() < 1e-6);
+        assert_eq!(report.fitness, Ratio::ONE);
+        assert!(report.iterations[0].rms > report.iterations[1].rms);
+        assert!(report.conditioning > 0.1);
+    }
+
+    #[test]
+    fn test_point_to_plane_on_resampled_surfaces() {
+        // The second scan samples the same walls at different places
+        let mut sampler = Sampler::new(11);
+        let source = corner(&mut sampler, 300);
+        let target: Vec<Vector3> = corner(&mut sampler, 600).iter().map(|&p| truth().apply_point(p)).collect();
+        let normals = estimate_normals(&target, 8, truth().apply_point([1.0, 1.0, 1.0]));
+        let floor = truth().apply_direction([0.0, 0.0, 1.0]);
+        assert!((linalg::dot(normals[0], floor) - 1.0).abs() < 1e-6);
+
+        let options = IcpOptions::default()
+            .with_metric(IcpMetric::PointToPlane)
+            .with_max_correspondence_distance(Length::new(0.3));
+        let report = icp(&source, &target, Some(&normals), Motor::identity(), &options).unwrap();
+        assert!(report.converged && report.iterations.len() < 10, "{:?}", report.iterations);
+        assert!(motor_error(&report.motor, &truth()) < 1e-6, "{:?}", report.motor);
+
+        assert_eq!(icp(&source, &target, None, Motor::identity(), &options), Err(IcpError::MissingNormals));
+    }
+
+    #[test]
+    fn test_degenerate_and_unmatched_scenes() {
+        let mut sampler = Sampler::new(3);
+        let floor: Vec<Vector3> = (0..100).map(|_| [sampler.range(0.0, 3.0), sampler.range(0.0, 3.0), 0.0]).collect();
+        let normals = vec![[0.0, 0.0, 1.0]; floor.len()];
+        let options = IcpOptions::default().with_metric(IcpMetric::PointToPlane);
+        // A flat floor cannot fix sliding within it
+        assert_eq!(icp(&floor, &floor, Some(&normals), Motor::identity(), &options), Err(IcpError::Degenerate));
+
+        let far = Motor::from_translation([0.0, 0.0, 5.0]);
+        let error = icp(&floor, &floor, None, far, &IcpOptions::default()).unwrap_err();
+        assert_eq!(error, IcpError::TooFewCorrespondences { found: 0, needed: 6 });
+        assert_eq!(icp(&[], &floor, None, Motor::identity(), &IcpOptions::default()), Err(IcpError::EmptyCloud));
+    }
+}
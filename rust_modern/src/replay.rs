@@ -0,0 +1,354 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Replay of recorded logs as regression tests
+//!
+//! A [`ReplayLog`] is a time-ordered list of [`Record`]s, stored as JSON lines
+//! of the form `{"time": 12.5, "channel": "imu", "value": ...}`. Values are
+//! kept as JSON until a consumer decodes them with [`Record::decode`], so one
+//! log can carry channels of any serde type.
+//!
+//! [`ReplayHarness`] splits the channels into inputs and expected outputs. Input
+//! records are fed to the system under test in log order at their recorded
+//! timestamps; at each expected record the system's current output on that
+//! channel is compared with the recording, number by number, within the
+//! channel's [`Tolerance`]. Nothing reads the wall clock: time only advances
+//! through the records, and an optional [`SimulatedClock`] is moved along with
+//! them for code built on [`crate::control_loop`]. Running under a determinism
+//! mode makes the comparison independent of the platform's last-bit rounding.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::control_loop::{Clock, SimulatedClock};
+use crate::determinism::{with_determinism_mode, Rounding};
+use crate::si_units::Time;
+
+/// One timestamped value on a named channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub time: Time,
+    pub channel: String,
+    pub value: Value,
+}
+
+impl Record {
+    pub fn new<T: Serialize>(time: Time, channel: &str, value: &T) -> Result<Self, ReplayError> {
+        let value = serde_json::to_value(value).map_err(|e| ReplayError::Decode { channel: channel.into(), message: e.to_string() })?;
+        Ok(Self { time, channel: channel.into(), value })
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T, ReplayError> {
+        T::deserialize(&self.value).map_err(|e| ReplayError::Decode { channel: self.channel.clone(), message: e.to_string() })
+    }
+}
+
+/// Errors raised while loading or replaying a log
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayError {
+    /// A line of the log is not a record; lines count from 1
+    Parse { line: usize, message: String },
+    /// A value does not have the type its consumer expects
+    Decode { channel: String, message: String },
+    /// The system under test rejected an input
+    Feed { time: Time, channel: String, message: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Parse { line, message } => write!(f, "log line {}: {}", line, message),
+            ReplayError::Decode { channel, message } => write!(f, "channel '{}': {}", channel, message),
+            ReplayError::Feed { time, channel, message } => {
+                write!(f, "feeding '{}' at {} s: {}", channel, time.value(), message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Records in time order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayLog {
+    records: Vec<Record>,
+}
+
+impl ReplayLog {
+    /// Sort records by time, keeping the recorded order of equal timestamps
+    pub fn new(mut records: Vec<Record>) -> Self {
+        records.sort_by(|a, b| a.time.value().total_cmp(b.time.value()));
+        Self { records }
+    }
+
+    /// Parse JSON lines, skipping blank lines
+    pub fn from_json_lines(text: &str) -> Result<Self, ReplayError> {
+        let records = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| serde_json::from_str(line).map_err(|e| ReplayError::Parse { line: i + 1, message: e.to_string() }))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(records))
+    }
+
+    pub fn to_json_lines(&self) -> String {
+        self.records
+            .iter()
+            .map(|r| serde_json::to_string(r).expect("records always serialize") + "\n")
+            .collect()
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Records of one channel
+    pub fn channel<'a>(&'a self, channel: &'a str) -> impl Iterator<Item = &'a Record> + 'a {
+        self.records.iter().filter(move |r| r.channel == channel)
+    }
+}
+
+/// Allowed difference between a recorded and a replayed number: `absolute + relative·|recorded|`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Tolerance {
+    pub fn absolute(absolute: f64) -> Self {
+        Self { absolute, relative: 0.0 }
+    }
+
+    pub fn allows(&self, recorded: f64, replayed: f64) -> bool {
+        (recorded - replayed).abs() <= self.absolute + self.relative * recorded.abs()
+    }
+}
+
+/// Estimation or control code driven by a log
+pub trait Replayable {
+    /// Consume one input record
+    fn feed(&mut self, record: &Record) -> Result<(), String>;
+
+    /// Current output on `channel`, or `None` if the system has none
+    fn output(&mut self, time: Time, channel: &str) -> Option<Value>;
+}
+
+/// A replayed value that differs from the recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub time: Time,
+    pub channel: String,
+    /// Location inside the value, e.g. `.position[2]`; empty for the whole value
+    pub path: String,
+    pub recorded: Value,
+    /// `None` when the system produced no output
+    pub replayed: Option<Value>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let replayed = self.replayed.as_ref().map_or("nothing".to_string(), Value::to_string);
+        write!(f, "{:.3} {}{}: recorded {}, replayed {}", self.time.value(), self.channel, self.path, self.recorded, replayed)
+    }
+}
+
+/// Outcome of a replay
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayReport {
+    pub fed: usize,
+    pub checked: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ReplayReport {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Feeds logs to a [`Replayable`] and checks its outputs
+#[derive(Debug, Clone, Default)]
+pub struct ReplayHarness {
+    expected: BTreeMap<String, Tolerance>,
+    determinism: Option<Rounding>,
+    clock: Option<SimulatedClock>,
+}
+
+impl ReplayHarness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treat records on `channel` as recorded results instead of inputs
+    pub fn expect(mut self, channel: &str, tolerance: Tolerance) -> Self {
+        self.expected.insert(channel.into(), tolerance);
+        self
+    }
+
+    /// Replay inside a determinism mode
+    pub fn with_determinism(mut self, rounding: Rounding) -> Self {
+        self.determinism = Some(rounding);
+        self
+    }
+
+    /// Move `clock` to each record's time before it is handled
+    pub fn with_clock(mut self, clock: SimulatedClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    pub fn run(&self, log: &ReplayLog, system: &mut impl Replayable) -> Result<ReplayReport, ReplayError> {
+        with_determinism_mode(self.determinism, || {
+            let mut report = ReplayReport::default();
+            for record in log.records() {
+                if let Some(clock) = &self.clock {
+                    clock.advance(Time::new(record.time.value() - clock.now().value()));
+                }
+                match self.expected.get(&record.channel) {
+                    None => {
+                        system.feed(record).map_err(|message| ReplayError::Feed {
+                            time: record.time,
+                            channel: record.channel.clone(),
+                            message,
+                        })?;
+                        report.fed += 1;
+                    }
+                    Some(tolerance) => {
+                        report.checked += 1;
+                        let mut mismatch = |path: String, recorded: &Value, replayed: Option<&Value>| {
+                            report.mismatches.push(Mismatch {
+                                time: record.time,
+                                channel: record.channel.clone(),
+                                path,
+                                recorded: recorded.clone(),
+                                replayed: replayed.cloned(),
+                            });
+                        };
+                        match system.output(record.time, &record.channel) {
+                            Some(replayed) => compare(&record.value, &replayed, tolerance, String::new(), &mut mismatch),
+                            None => mismatch(String::new(), &record.value, None),
+                        }
+                    }
+                }
+            }
+            Ok(report)
+        })
+    }
+}
+
+/// Walk two values together, reporting numbers out of tolerance and any difference in shape
+fn compare(recorded: &Value, replayed: &Value, tolerance: &Tolerance, path: String, mismatch: &mut impl FnMut(String, &Value, Option<&Value>)) {
+    match (recorded, replayed) {
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64().unwrap_or(f64::NAN), b.as_f64().unwrap_or(f64::NAN));
+            if !tolerance.allows(a, b) {
+                mismatch(path, recorded, Some(replayed));
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                compare(a, b, tolerance, format!("{}[{}]", path, i), mismatch);
+            }
+        }
+        (Value::Object(a), Value::Object(b)) if a.len() == b.len() && a.keys().all(|k| b.contains_key(k)) => {
+            for (key, value) in a {
+                compare(value, &b[key], tolerance, format!("{}.{}", path, key), mismatch);
+            }
+        }
+        _ if recorded == replayed => {}
+        _ => mismatch(path, recorded, Some(replayed)),
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::{self, Vector3};
+
+    /// Dead reckoning from velocity measurements
+    #[derive(Default)]
+    struct Integrator {
+        position: Vector3,
+        velocity: Vector3,
+        last: Option<f64>,
+    }
+
+    impl Replayable for Integrator {
+        fn feed(&mut self, record: &Record) -> Result<(), String> {
+            match record.channel.as_str() {
+                "velocity" => {
+                    let time = *record.time.value();
+                    if let Some(last) = self.last {
+                        self.position = linalg::add(self.position, linalg::scale(self.velocity, time - last));
+                    }
+                    self.velocity = record.decode().map_err(|e| e.to_string())?;
+                    self.last = Some(time);
+                    Ok(())
+                }
+                other => Err(format!("unexpected channel {}", other)),
+            }
+        }
+
+        fn output(&mut self, _time: Time, channel: &str) -> Option<Value> {
+            (channel == "position").then(|| serde_json::json!({ "position": self.position }))
+        }
+    }
+
+    const LOG: &str = r#"
+{"time": 0.0, "channel": "velocity", "value": [1.0, 0.0, 0.0]}
+{"time": 1.0, "channel": "velocity", "value": [0.0, 2.0, 0.0]}
+{"time": 1.0, "channel": "position", "value": {"position": [1.0, 0.0, 0.0]}}
+{"time": 2.0, "channel": "velocity", "value": [0.0, 0.0, 0.0]}
+{"time": 2.0, "channel": "position", "value": {"position": [1.0, 2.0, 0.0]}}
+"#;
+
+    #[test]
+    fn test_log_round_trip() {
+        let log = ReplayLog::from_json_lines(LOG).unwrap();
+        assert_eq!(log.records().len(), 5);
+        assert_eq!(log.channel("position").count(), 2);
+        assert_eq!(log.records()[1].decode::<Vector3>().unwrap(), [0.0, 2.0, 0.0]);
+        assert!(log.records()[2].decode::<Vector3>().is_err());
+        assert_eq!(ReplayLog::from_json_lines(&log.to_json_lines()).unwrap(), log);
+
+        let error = ReplayLog::from_json_lines("\n{\"time\": 0.0}\n").unwrap_err();
+        assert!(matches!(error, ReplayError::Parse { line: 2, .. }));
+
+        // Out-of-order records are put in time order
+        let record = |t: f64| Record::new(Time::new(t), "velocity", &[0.0; 3]).unwrap();
+        let log = ReplayLog::new(vec![record(2.0), record(1.0)]);
+        assert_eq!(*log.records()[0].time.value(), 1.0);
+    }
+
+    #[test]
+    fn test_replay_checks_outputs() {
+        let log = ReplayLog::from_json_lines(LOG).unwrap();
+        let clock = SimulatedClock::new();
+        let harness = ReplayHarness::new()
+            .expect("position", Tolerance::absolute(1e-9))
+            .with_determinism(Rounding::DecimalPlaces(9))
+            .with_clock(clock.clone());
+        let report = harness.run(&log, &mut Integrator::default()).unwrap();
+        assert!(report.passed());
+        assert_eq!((report.fed, report.checked), (3, 2));
+        assert_eq!(*clock.now().value(), 2.0);
+
+        // A regression in the integrator shows up as a located mismatch
+        let tampered = LOG.replace("[1.0, 2.0, 0.0]", "[1.0, 2.5, 0.0]");
+        let report = harness.run(&ReplayLog::from_json_lines(&tampered).unwrap(), &mut Integrator::default()).unwrap();
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].to_string(), "2.000 position.position[1]: recorded 2.5, replayed 2.0");
+
+        // Without the expectation, recorded positions are inputs the integrator rejects
+        let error = ReplayHarness::new().run(&log, &mut Integrator::default()).unwrap_err();
+        assert!(matches!(error, ReplayError::Feed { channel, .. } if channel == "position"));
+    }
+}
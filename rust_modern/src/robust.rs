@@ -0,0 +1,181 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Robust losses and iteratively reweighted least squares
+//!
+//! Least squares lets one bad measurement pull the whole estimate. A robust
+//! loss grows more slowly than the square for large residuals, and IRLS
+//! minimizes it by repeatedly solving a weighted least-squares problem whose
+//! weights come from the previous solution's residuals.
+//!
+//! Residuals are normalized by an inlier threshold before the loss sees them,
+//! so `u = 1` is the boundary between inliers and outliers for every loss.
+//! Callers keep the threshold in the residual's own unit (see
+//! [`crate::calibration::HandEyeThresholds`]).
+
+use serde::{Deserialize, Serialize};
+
+/// Loss applied to normalized residuals `u = |r| / threshold`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RobustLoss {
+    /// Ordinary least squares
+    Squared,
+    /// Quadratic inside the threshold, linear outside
+    Huber,
+    /// Tukey's biweight: outliers beyond the threshold get zero weight. Being
+    /// redescending, it needs a start close to the answer, so [`irls`] first
+    /// converges with Huber weights.
+    Tukey,
+    /// Logarithmic growth; never rejects outright
+    Cauchy,
+}
+
+impl RobustLoss {
+    /// Loss value `ρ(u)`, equal to `u²/2` near zero for all losses
+    pub fn rho(&self, u: f64) -> f64 {
+        let u = u.abs();
+        match self {
+            RobustLoss::Squared => 0.5 * u * u,
+            RobustLoss::Huber if u <= 1.0 => 0.5 * u * u,
+            RobustLoss::Huber => u - 0.5,
+            RobustLoss::Tukey if u < 1.0 => (1.0 - (1.0 - u * u).powi(3)) / 6.0,
+            RobustLoss::Tukey => 1.0 / 6.0,
+            RobustLoss::Cauchy => 0.5 * (1.0 + u * u).ln(),
+        }
+    }
+
+    /// IRLS weight `ρ'(u) / u`
+    pub fn weight(&self, u: f64) -> f64 {
+        let u = u.abs();
+        match self {
+            RobustLoss::Squared => 1.0,
+            RobustLoss::Huber if u <= 1.0 => 1.0,
+            RobustLoss::Huber => 1.0 / u,
+            RobustLoss::Tukey if u < 1.0 => (1.0 - u * u).powi(2),
+            RobustLoss::Tukey => 0.0,
+            RobustLoss::Cauchy => 1.0 / (1.0 + u * u),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IrlsOptions {
+    pub max_iterations: usize,
+    /// Stop once no weight changes by more than this
+    pub weight_tolerance: f64,
+}
+
+impl Default for IrlsOptions {
+    fn default() -> Self {
+        Self { max_iterations: 20, weight_tolerance: 1e-6 }
+    }
+}
+
+/// Result of IRLS with the final per-residual weights
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobustFit<M> {
+    pub model: M,
+    /// Normalized residuals of the final model
+    pub residuals: Vec<f64>,
+    pub weights: Vec<f64>,
+    /// Sum of `ρ(u)` over the residuals
+    pub cost: f64,
+    /// Reweighted solves after the initial unweighted one
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl<M> RobustFit<M> {
+    /// Whether each residual lies within its threshold
+    pub fn inliers(&self) -> Vec<bool> {
+        self.residuals.iter().map(|u| u.abs() <= 1.0).collect()
+    }
+}
+
+/// Minimize `Σ ρ(uᵢ)` by IRLS
+///
+/// `solve` fits a model to per-residual weights, starting from all ones;
+/// `residuals` returns the normalized residuals of a model, one per weight.
+pub fn irls<M, E>(
+    count: usize,
+    loss: RobustLoss,
+    options: &IrlsOptions,
+    mut solve: impl FnMut(&[f64]) -> Result<M, E>,
+    mut residuals: impl FnMut(&M) -> Vec<f64>,
+) -> Result<RobustFit<M>, E> {
+    let mut weights = vec![1.0; count];
+    let mut model = solve(&weights)?;
+    let mut current = residuals(&model);
+    // Redescending losses start from the Huber solution
+    let mut stage = if loss == RobustLoss::Tukey { RobustLoss::Huber } else { loss };
+    let mut iterations = 0;
+    let mut converged = loss == RobustLoss::Squared;
+    while !converged && iterations < options.max_iterations {
+        let next: Vec<f64> = current.iter().map(|u| stage.weight(*u)).collect();
+        let change = next.iter().zip(&weights).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max);
+        if change <= options.weight_tolerance && iterations > 0 {
+            if stage == loss {
+                converged = true;
+                break;
+            }
+            stage = loss;
+            continue;
+        }
+        weights = next;
+        model = solve(&weights)?;
+        current = residuals(&model);
+        iterations += 1;
+    }
+    let cost = current.iter().map(|u| loss.rho(*u)).sum();
+    Ok(RobustFit { model, residuals: current, weights, cost, iterations, converged })
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_losses_agree_near_zero() {
+        for loss in [RobustLoss::Squared, RobustLoss::Huber, RobustLoss::Tukey, RobustLoss::Cauchy] {
+            assert!((loss.rho(1e-3) - 0.5e-6).abs() < 1e-11, "{:?}", loss);
+            assert!((loss.weight(1e-3) - 1.0).abs() < 1e-5, "{:?}", loss);
+        }
+        assert_eq!(RobustLoss::Huber.weight(4.0), 0.25);
+        assert_eq!(RobustLoss::Tukey.weight(1.5), 0.0);
+        assert_eq!(RobustLoss::Huber.rho(3.0), 2.5);
+    }
+
+    #[test]
+    fn test_irls_line_fit() {
+        // y = 2x + 1 with one gross outlier
+        let mut points: Vec<(f64, f64)> = (0..10).map(|i| (i as f64, 2.0 * i as f64 + 1.0)).collect();
+        points[7].1 += 30.0;
+        let solve = |weights: &[f64]| -> Result<(f64, f64), ()> {
+            let (mut sw, mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            for ((x, y), w) in points.iter().zip(weights) {
+                sw += w;
+                sx += w * x;
+                sy += w * y;
+                sxx += w * x * x;
+                sxy += w * x * y;
+            }
+            let slope = (sw * sxy - sx * sy) / (sw * sxx - sx * sx);
+            Ok((slope, (sy - slope * sx) / sw))
+        };
+        let threshold = 0.5;
+        let residuals = |(slope, intercept): &(f64, f64)| {
+            points.iter().map(|(x, y)| (y - slope * x - intercept) / threshold).collect()
+        };
+
+        let plain = irls(points.len(), RobustLoss::Squared, &IrlsOptions::default(), solve, residuals).unwrap();
+        assert!((plain.model.0 - 2.0).abs() > 0.1 && plain.iterations == 0);
+
+        let fit = irls(points.len(), RobustLoss::Tukey, &IrlsOptions::default(), solve, residuals).unwrap();
+        assert!(fit.converged);
+        assert!((fit.model.0 - 2.0).abs() < 1e-9 && (fit.model.1 - 1.0).abs() < 1e-9);
+        assert_eq!(fit.weights[7], 0.0);
+        assert_eq!(fit.inliers().iter().filter(|inlier| !**inlier).count(), 1);
+    }
+}
@@ -0,0 +1,690 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Safety reports, watchdogs and command gating
+//!
+//! A [`SafetyViolation`] says which joint broke which limit, the value that
+//! was attempted and the limit with their unit, and the margin by which the
+//! limit was missed, so a caller can explain a refusal rather than print a
+//! string assembled at the failure site. Violations serialize to JSON with the
+//! unit as its symbol, the layout the shared test runner reads back from a
+//! test's outputs.
+//!
+//! Joint limits are checked by [`KinematicChain::limit_violations`] and
+//! [`KinematicChain::velocity_violations`]; reachability failures convert
+//! through [`ReachReport::violations`].
+//!
+//! A [`SafetySupervisor`] is the safety state of a control stack. Heartbeat
+//! [`Watchdog`]s must be fed within their timeout; a silent one, a reported
+//! [`Fault`] or an emergency stop moves the supervisor out of
+//! [`SafetyState::Running`], and it stays out until an explicit
+//! [`reset`](SafetySupervisor::reset) finds every heartbeat fresh and the
+//! emergency stop released. Until then [`SafetySupervisor::gate`] replaces
+//! every command with its [`SafeStop`]: zero twist, zero thrust, zero joint
+//! velocity. Each change is a [`SafetyEvent`] with a canonical log line, and
+//! a [`SafetyHook`] such as [`Mission`] reacts to it by aborting.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::allocation::Allocation;
+use crate::grasp::Wrench;
+use crate::joints::Joint;
+use crate::kinematics::{KinematicChain, KinematicsError};
+use crate::linalg;
+use crate::mission::Mission;
+use crate::motor::Rotor;
+use crate::reachability::{ReachReport, Unreachable};
+use crate::si_units::{Force, Time};
+use crate::teleop::TwistCommand;
+
+/// Which limit was broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitKind {
+    /// Lower end of a revolute or prismatic joint's range
+    Lower,
+    /// Upper end of a revolute or prismatic joint's range
+    Upper,
+    /// Largest rotation angle of a spherical joint
+    Rotation,
+    /// Largest joint speed
+    Velocity,
+    /// Radius of the chain's workspace
+    Workspace,
+    /// Position error at which a target counts as reached
+    Tolerance,
+    /// Clearance a target needs from every obstacle
+    Clearance,
+}
+
+impl LimitKind {
+    /// What the attempted value measures
+    pub fn quantity(self) -> &'static str {
+        match self {
+            LimitKind::Lower | LimitKind::Upper => "position",
+            LimitKind::Rotation => "rotation",
+            LimitKind::Velocity => "speed",
+            LimitKind::Workspace => "distance",
+            LimitKind::Tolerance => "position error",
+            LimitKind::Clearance => "clearance",
+        }
+    }
+
+    /// Name of the limit
+    pub fn label(self) -> &'static str {
+        match self {
+            LimitKind::Lower => "lower limit",
+            LimitKind::Upper => "upper limit",
+            LimitKind::Rotation => "rotation limit",
+            LimitKind::Velocity => "velocity limit",
+            LimitKind::Workspace => "reach",
+            LimitKind::Tolerance => "tolerance",
+            LimitKind::Clearance => "required clearance",
+        }
+    }
+
+    /// Whether the limit is a minimum, so a violation lies below it
+    pub fn is_minimum(self) -> bool {
+        matches!(self, LimitKind::Lower | LimitKind::Clearance)
+    }
+}
+
+/// Unit of the attempted value and the limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitUnit {
+    #[serde(rename = "rad")]
+    Radian,
+    #[serde(rename = "m")]
+    Meter,
+    #[serde(rename = "rad/s")]
+    RadianPerSecond,
+    #[serde(rename = "m/s")]
+    MeterPerSecond,
+}
+
+impl LimitUnit {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            LimitUnit::Radian => "rad",
+            LimitUnit::Meter => "m",
+            LimitUnit::RadianPerSecond => "rad/s",
+            LimitUnit::MeterPerSecond => "m/s",
+        }
+    }
+}
+
+/// A limit broken by an attempted value
+///
+/// Displays as one sentence with three decimals unless the formatter gives a
+/// precision, e.g. `joint 1: position 1.800 rad above upper limit 1.571 rad by 0.229 rad`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SafetyViolation {
+    /// Index of the joint among the chain's segments; `None` for a target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub joint: Option<usize>,
+    /// Index of the joint's first coordinate in the chain's coordinates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinate: Option<usize>,
+    pub kind: LimitKind,
+    pub attempted: f64,
+    pub limit: f64,
+    pub unit: LimitUnit,
+    /// How far `attempted` lies on the wrong side of `limit`; positive
+    pub margin: f64,
+}
+
+impl SafetyViolation {
+    /// Violation of `limit` by `attempted`, with the margin from the kind's side of the limit
+    pub fn new(kind: LimitKind, attempted: f64, limit: f64, unit: LimitUnit) -> Self {
+        let margin = if kind.is_minimum() { limit - attempted } else { attempted - limit };
+        Self { joint: None, coordinate: None, kind, attempted, limit, unit, margin }
+    }
+
+    /// The same violation attributed to a joint and its first coordinate
+    pub fn with_joint(mut self, joint: usize, coordinate: usize) -> Self {
+        self.joint = Some(joint);
+        self.coordinate = Some(coordinate);
+        self
+    }
+}
+
+impl fmt::Display for SafetyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(3);
+        let unit = self.unit.symbol();
+        match self.joint {
+            Some(joint) => write!(f, "joint {}: ", joint)?,
+            None => write!(f, "target: ")?,
+        }
+        write!(
+            f,
+            "{} {:.p$} {} {} {} {:.p$} {} by {:.p$} {}",
+            self.kind.quantity(),
+            self.attempted,
+            unit,
+            if self.kind.is_minimum() { "below" } else { "above" },
+            self.kind.label(),
+            self.limit,
+            unit,
+            self.margin,
+            unit,
+            p = precision
+        )
+    }
+}
+
+impl From<&Unreachable> for SafetyViolation {
+    fn from(reason: &Unreachable) -> Self {
+        match reason {
+            Unreachable::BeyondWorkspace { distance, reach } => {
+                SafetyViolation::new(LimitKind::Workspace, *distance.value(), *reach.value(), LimitUnit::Meter)
+            }
+            Unreachable::JointLimits { position_error, tolerance } => {
+                SafetyViolation::new(LimitKind::Tolerance, *position_error.value(), *tolerance.value(), LimitUnit::Meter)
+            }
+            Unreachable::Obstructed { clearance, required } => {
+                SafetyViolation::new(LimitKind::Clearance, *clearance.value(), *required.value(), LimitUnit::Meter)
+            }
+        }
+    }
+}
+
+impl ReachReport {
+    /// The failed checks as violations, in the order of `reasons`
+    pub fn violations(&self) -> Vec<SafetyViolation> {
+        self.reasons.iter().map(SafetyViolation::from).collect()
+    }
+}
+
+impl KinematicChain {
+    /// Position limits that `q` breaks, one per offending joint
+    pub fn limit_violations(&self, q: &[f64]) -> Result<Vec<SafetyViolation>, KinematicsError> {
+        self.check(q)?;
+        let mut violations = Vec::new();
+        let mut offset = 0;
+        for (index, joint) in self.joints().enumerate() {
+            let coordinates = &q[offset..offset + joint.dof()];
+            let violation = match joint {
+                Joint::Revolute { limits, .. } => {
+                    range_violation(coordinates[0], *limits.lower.value(), *limits.upper.value(), LimitUnit::Radian)
+                }
+                Joint::Prismatic { limits, .. } => {
+                    range_violation(coordinates[0], *limits.lower.value(), *limits.upper.value(), LimitUnit::Meter)
+                }
+                Joint::Spherical { limits } => {
+                    let angle = Rotor::from_rotation_vector([coordinates[0], coordinates[1], coordinates[2]]).angle();
+                    (angle > *limits.max_angle.value())
+                        .then(|| SafetyViolation::new(LimitKind::Rotation, angle, *limits.max_angle.value(), LimitUnit::Radian))
+                }
+                Joint::Fixed => None,
+            };
+            violations.extend(violation.map(|v| v.with_joint(index, offset)));
+            offset += joint.dof();
+        }
+        Ok(violations)
+    }
+
+    /// Speed limits that the joint rates `dq` break, one per offending joint
+    ///
+    /// A spherical joint's rates are its angular velocity, limited in norm.
+    pub fn velocity_violations(&self, dq: &[f64]) -> Result<Vec<SafetyViolation>, KinematicsError> {
+        self.check(dq)?;
+        let mut violations = Vec::new();
+        let mut offset = 0;
+        for (index, joint) in self.joints().enumerate() {
+            let rates = &dq[offset..offset + joint.dof()];
+            let (speed, limit, unit) = match joint {
+                Joint::Revolute { limits, .. } => (rates[0].abs(), *limits.max_velocity.value(), LimitUnit::RadianPerSecond),
+                Joint::Prismatic { limits, .. } => (rates[0].abs(), *limits.max_velocity.value(), LimitUnit::MeterPerSecond),
+                Joint::Spherical { limits } => {
+                    (linalg::norm([rates[0], rates[1], rates[2]]), *limits.max_velocity.value(), LimitUnit::RadianPerSecond)
+                }
+                Joint::Fixed => (0.0, 0.0, LimitUnit::RadianPerSecond),
+            };
+            if speed > limit {
+                violations.push(SafetyViolation::new(LimitKind::Velocity, speed, limit, unit).with_joint(index, offset));
+            }
+            offset += joint.dof();
+        }
+        Ok(violations)
+    }
+
+}
+
+/// Violation of a closed range by `value`, if it lies outside
+fn range_violation(value: f64, lower: f64, upper: f64, unit: LimitUnit) -> Option<SafetyViolation> {
+    if value < lower {
+        Some(SafetyViolation::new(LimitKind::Lower, value, lower, unit))
+    } else if value > upper {
+        Some(SafetyViolation::new(LimitKind::Upper, value, upper, unit))
+    } else {
+        None
+    }
+}
+
+/// Heartbeat that must be fed within its timeout
+///
+/// Timing starts at the first check, so a heartbeat that never arrives
+/// expires one timeout after the supervisor first looks at it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watchdog {
+    pub name: String,
+    pub timeout: Time,
+    last_fed: Option<Time>,
+    /// Whether the current silence has already been reported
+    tripped: bool,
+}
+
+impl Watchdog {
+    pub fn new(name: &str, timeout: Time) -> Self {
+        Self { name: name.to_string(), timeout, last_fed: None, tripped: false }
+    }
+
+    pub fn feed(&mut self, now: Time) {
+        self.last_fed = Some(now);
+        self.tripped = false;
+    }
+
+    /// Time until the watchdog expires; negative once it has
+    pub fn remaining(&self, now: Time) -> Time {
+        let last = self.last_fed.unwrap_or(now);
+        Time::new(last.value() + self.timeout.value() - now.value())
+    }
+
+    pub fn is_expired(&self, now: Time) -> bool {
+        *self.remaining(now).value() < 0.0
+    }
+}
+
+/// Operating state of a supervised control stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyState {
+    /// Commands pass through
+    Running,
+    /// Commands are stopped until a reset
+    Faulted,
+    /// Commands are stopped until the stop is released and the supervisor reset
+    EmergencyStop,
+}
+
+impl fmt::Display for SafetyState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SafetyState::Running => "running",
+            SafetyState::Faulted => "faulted",
+            SafetyState::EmergencyStop => "emergency_stop",
+        })
+    }
+}
+
+/// Why the supervisor stopped commands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fault {
+    /// A watchdog was not fed within its timeout
+    HeartbeatLost { watchdog: String, silent: Time, timeout: Time },
+    /// A limit was broken
+    Violation(SafetyViolation),
+    EmergencyStop,
+    /// Any other fault, described by the component that raised it
+    External(String),
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fault::HeartbeatLost { watchdog, silent, timeout } => {
+                write!(f, "heartbeat '{}' silent for {:.3} s (timeout {:.3} s)", watchdog, silent.value(), timeout.value())
+            }
+            Fault::Violation(violation) => write!(f, "{}", violation),
+            Fault::EmergencyStop => f.write_str("emergency stop"),
+            Fault::External(reason) => f.write_str(reason),
+        }
+    }
+}
+
+/// Why the supervisor refused to resume
+#[derive(Debug, Clone, PartialEq)]
+pub enum SafetyError {
+    /// No watchdog has this name
+    UnknownWatchdog(String),
+    /// The emergency stop has not been released
+    EmergencyStopEngaged,
+    /// These watchdogs are still silent
+    HeartbeatsLost(Vec<String>),
+}
+
+impl fmt::Display for SafetyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SafetyError::UnknownWatchdog(name) => write!(f, "no watchdog named '{}'", name),
+            SafetyError::EmergencyStopEngaged => write!(f, "emergency stop is still engaged"),
+            SafetyError::HeartbeatsLost(names) => write!(f, "heartbeats still lost: {}", names.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for SafetyError {}
+
+/// A state change or a fault of the supervisor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafetyEvent {
+    pub time: Time,
+    /// State after the event
+    pub state: SafetyState,
+    /// `None` for a reset or a release
+    pub fault: Option<Fault>,
+}
+
+impl SafetyEvent {
+    /// `"<seconds, 3 decimals> <state>[ <fault>]"`
+    pub fn canonical(&self) -> String {
+        match &self.fault {
+            Some(fault) => format!("{:.3} {} {}", self.time.value(), self.state, fault),
+            None => format!("{:.3} {}", self.time.value(), self.state),
+        }
+    }
+}
+
+/// Component that reacts to safety events
+pub trait SafetyHook {
+    fn on_safety_event(&mut self, event: &SafetyEvent);
+}
+
+/// A mission aborts on the first event that stops commands
+impl SafetyHook for Mission {
+    fn on_safety_event(&mut self, event: &SafetyEvent) {
+        if event.state != SafetyState::Running {
+            self.abort(event.time);
+        }
+    }
+}
+
+/// Command with a safe value to send instead while stopped
+pub trait SafeStop {
+    fn stopped(&self) -> Self;
+}
+
+impl SafeStop for TwistCommand {
+    fn stopped(&self) -> Self {
+        TwistCommand::zero()
+    }
+}
+
+impl SafeStop for Allocation {
+    fn stopped(&self) -> Self {
+        Allocation {
+            thrusts: vec![Force::new(0.0); self.thrusts.len()],
+            achieved: Wrench::new([0.0; 3], [0.0; 3]),
+            saturated: false,
+        }
+    }
+}
+
+/// Joint velocity commands
+impl SafeStop for Vec<f64> {
+    fn stopped(&self) -> Self {
+        vec![0.0; self.len()]
+    }
+}
+
+/// Watchdogs, latched faults and the emergency stop of a control stack
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetySupervisor {
+    watchdogs: Vec<Watchdog>,
+    state: SafetyState,
+    /// Faults since the last reset
+    faults: Vec<Fault>,
+    /// Whether the emergency stop is pressed
+    stop_engaged: bool,
+    log: Vec<SafetyEvent>,
+}
+
+impl Default for SafetySupervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SafetySupervisor {
+    /// Running supervisor without watchdogs
+    pub fn new() -> Self {
+        Self { watchdogs: Vec::new(), state: SafetyState::Running, faults: Vec::new(), stop_engaged: false, log: Vec::new() }
+    }
+
+    pub fn with_watchdog(mut self, name: &str, timeout: Time) -> Self {
+        self.watchdogs.push(Watchdog::new(name, timeout));
+        self
+    }
+
+    pub fn state(&self) -> SafetyState {
+        self.state
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state == SafetyState::Running
+    }
+
+    /// Faults since the last reset, oldest first
+    pub fn faults(&self) -> &[Fault] {
+        &self.faults
+    }
+
+    pub fn watchdogs(&self) -> &[Watchdog] {
+        &self.watchdogs
+    }
+
+    pub fn events(&self) -> &[SafetyEvent] {
+        &self.log
+    }
+
+    /// Canonical lines of every event so far, one per line
+    pub fn canonical_log(&self) -> String {
+        self.log.iter().map(|e| e.canonical() + "\n").collect()
+    }
+
+    /// Record a heartbeat
+    pub fn feed(&mut self, name: &str, now: Time) -> Result<(), SafetyError> {
+        let watchdog = self
+            .watchdogs
+            .iter_mut()
+            .find(|w| w.name == name)
+            .ok_or_else(|| SafetyError::UnknownWatchdog(name.to_string()))?;
+        watchdog.feed(now);
+        Ok(())
+    }
+
+    fn event(&mut self, time: Time, fault: Option<Fault>) -> SafetyEvent {
+        let event = SafetyEvent { time, state: self.state, fault };
+        self.log.push(event.clone());
+        event
+    }
+
+    /// Latch a fault, stopping commands unless already stopped
+    pub fn report(&mut self, now: Time, fault: Fault) -> SafetyEvent {
+        if self.state == SafetyState::Running {
+            self.state = SafetyState::Faulted;
+        }
+        self.faults.push(fault.clone());
+        self.event(now, Some(fault))
+    }
+
+    /// Check the watchdogs, faulting once for each that has newly gone silent
+    pub fn update(&mut self, now: Time) -> Vec<SafetyEvent> {
+        let mut lost = Vec::new();
+        for watchdog in &mut self.watchdogs {
+            let last = *watchdog.last_fed.get_or_insert(now);
+            if watchdog.is_expired(now) && !watchdog.tripped {
+                watchdog.tripped = true;
+                lost.push(Fault::HeartbeatLost {
+                    watchdog: watchdog.name.clone(),
+                    silent: Time::new(now.value() - last.value()),
+                    timeout: watchdog.timeout,
+                });
+            }
+        }
+        lost.into_iter().map(|fault| self.report(now, fault)).collect()
+    }
+
+    /// Press the emergency stop
+    pub fn emergency_stop(&mut self, now: Time) -> SafetyEvent {
+        self.stop_engaged = true;
+        self.state = SafetyState::EmergencyStop;
+        self.faults.push(Fault::EmergencyStop);
+        self.event(now, Some(Fault::EmergencyStop))
+    }
+
+    /// Release the emergency stop; commands stay stopped until a reset
+    pub fn release(&mut self, now: Time) -> Option<SafetyEvent> {
+        if !self.stop_engaged {
+            return None;
+        }
+        self.stop_engaged = false;
+        self.state = SafetyState::Faulted;
+        Some(self.event(now, None))
+    }
+
+    /// Clear the latched faults and resume, if the emergency stop is released
+    /// and every heartbeat is fresh
+    pub fn reset(&mut self, now: Time) -> Result<SafetyEvent, SafetyError> {
+        if self.stop_engaged {
+            return Err(SafetyError::EmergencyStopEngaged);
+        }
+        let lost: Vec<String> = self.watchdogs.iter().filter(|w| w.is_expired(now)).map(|w| w.name.clone()).collect();
+        if !lost.is_empty() {
+            return Err(SafetyError::HeartbeatsLost(lost));
+        }
+        self.faults.clear();
+        self.state = SafetyState::Running;
+        Ok(self.event(now, None))
+    }
+
+    /// `command` while running, its safe stop otherwise
+    pub fn gate<C: SafeStop>(&self, command: C) -> C {
+        if self.is_running() {
+            command
+        } else {
+            command.stopped()
+        }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::joints::{JointLimits, PrismaticLimits, SphericalLimits};
+    use crate::motor::Motor;
+    use crate::si_units::{Angle, AngularVelocity, Length, Velocity, TAU};
+
+    fn chain() -> KinematicChain {
+        KinematicChain::new()
+            .with_joint(
+                Motor::identity(),
+                Joint::revolute([0.0, 0.0, 1.0], JointLimits::new(Angle::new(-TAU / 4.0), Angle::new(TAU / 4.0), AngularVelocity::new(1.0))),
+            )
+            .with_joint(Motor::from_translation([1.0, 0.0, 0.0]), Joint::Fixed)
+            .with_joint(
+                Motor::identity(),
+                Joint::prismatic([1.0, 0.0, 0.0], PrismaticLimits::new(Length::new(0.0), Length::new(0.2), Velocity::new(0.1))),
+            )
+            .with_joint(Motor::identity(), Joint::spherical(SphericalLimits::new(Angle::new(0.5), AngularVelocity::new(2.0))))
+    }
+
+    #[test]
+    fn test_limit_violations_name_joint_and_margin() {
+        let chain = chain();
+        assert!(chain.limit_violations(&[0.0, 0.1, 0.0, 0.0, 0.3]).unwrap().is_empty());
+        let violations = chain.limit_violations(&[2.0, -0.05, 0.0, 0.0, 0.8]).unwrap();
+        assert_eq!(violations.len(), 3);
+        assert_eq!((violations[0].joint, violations[0].coordinate, violations[0].kind), (Some(0), Some(0), LimitKind::Upper));
+        assert!((violations[0].margin - (2.0 - TAU / 4.0)).abs() < 1e-12);
+        assert_eq!((violations[1].joint, violations[1].coordinate, violations[1].kind), (Some(2), Some(1), LimitKind::Lower));
+        assert!((violations[1].margin - 0.05).abs() < 1e-12);
+        assert_eq!(violations[1].unit, LimitUnit::Meter);
+        assert_eq!((violations[2].joint, violations[2].coordinate, violations[2].kind), (Some(3), Some(2), LimitKind::Rotation));
+        assert!((violations[2].margin - 0.3).abs() < 1e-12);
+        assert_eq!(violations[1].to_string(), "joint 2: position -0.050 m below lower limit 0.000 m by 0.050 m");
+        assert_eq!(format!("{:.1}", violations[0]), "joint 0: position 2.0 rad above upper limit 1.6 rad by 0.4 rad");
+
+        let speeds = chain.velocity_violations(&[-1.5, 0.05, 1.2, 1.2, 1.2]).unwrap();
+        assert_eq!(speeds.len(), 2);
+        assert_eq!((speeds[0].kind, speeds[0].attempted, speeds[0].unit), (LimitKind::Velocity, 1.5, LimitUnit::RadianPerSecond));
+        assert_eq!(speeds[1].joint, Some(3));
+        assert!(chain.limit_violations(&[0.0]).is_err());
+    }
+
+    #[test]
+    fn test_violation_json_layout() {
+        let violation = SafetyViolation::new(LimitKind::Velocity, 0.3, 0.1, LimitUnit::MeterPerSecond).with_joint(2, 1);
+        let json = serde_json::to_value(violation).unwrap();
+        assert_eq!(json["kind"], "velocity");
+        assert_eq!(json["unit"], "m/s");
+        assert_eq!(json["joint"], 2);
+        assert_eq!(serde_json::from_value::<SafetyViolation>(json).unwrap(), violation);
+
+        let target = SafetyViolation::new(LimitKind::Clearance, -0.1, 0.05, LimitUnit::Meter);
+        let json = serde_json::to_string(&target).unwrap();
+        assert!(!json.contains("joint"));
+        assert!((target.margin - 0.15).abs() < 1e-12);
+        assert_eq!(target.to_string(), "target: clearance -0.100 m below required clearance 0.050 m by 0.150 m");
+    }
+
+    #[test]
+    fn test_watchdog_faults_and_gates_commands() {
+        let mut supervisor = SafetySupervisor::new().with_watchdog("thrusters", Time::new(0.5)).with_watchdog("joystick", Time::new(1.0));
+        let twist = TwistCommand { linear: [Velocity::new(1.0), Velocity::new(0.0), Velocity::new(0.0)], ..TwistCommand::zero() };
+        assert!(supervisor.update(Time::new(0.0)).is_empty());
+        supervisor.feed("thrusters", Time::new(0.4)).unwrap();
+        assert!(supervisor.update(Time::new(0.8)).is_empty());
+        assert_eq!(supervisor.gate(twist), twist);
+
+        let events = supervisor.update(Time::new(1.2));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].canonical(), "1.200 faulted heartbeat 'thrusters' silent for 0.800 s (timeout 0.500 s)");
+        assert!(supervisor.update(Time::new(1.3)).is_empty(), "a silence is reported once");
+        assert_eq!(supervisor.gate(twist), TwistCommand::zero());
+        assert_eq!(supervisor.gate(vec![0.3, -0.2]), vec![0.0, 0.0]);
+        assert_eq!(supervisor.feed("gps", Time::new(1.3)), Err(SafetyError::UnknownWatchdog("gps".to_string())));
+
+        supervisor.feed("thrusters", Time::new(1.4)).unwrap();
+        assert_eq!(supervisor.reset(Time::new(1.4)), Err(SafetyError::HeartbeatsLost(vec!["joystick".to_string()])));
+        supervisor.feed("joystick", Time::new(1.5)).unwrap();
+        supervisor.reset(Time::new(1.5)).unwrap();
+        assert!(supervisor.is_running() && supervisor.faults().is_empty());
+        assert_eq!(supervisor.gate(twist), twist);
+    }
+
+    #[test]
+    fn test_emergency_stop_aborts_mission() {
+        use crate::mission::{MissionDefinition, Status, TaskEvent};
+
+        let definition = MissionDefinition::from_json(
+            r#"{ "name": "inspect", "root": { "task": { "name": "out", "action": { "goto": { "position": [10.0, 0.0, 0.0], "speed": 1.0, "tolerance": 0.5 } } } } }"#,
+        )
+        .unwrap();
+        let mut mission = Mission::new(&definition);
+        let mut supervisor = SafetySupervisor::new();
+        assert!(mission.tick(Time::new(0.0), [0.0; 3]).setpoint.is_some());
+
+        let event = supervisor.emergency_stop(Time::new(1.0));
+        mission.on_safety_event(&event);
+        assert_eq!(mission.status(), Status::Failure);
+        assert_eq!(mission.transitions().last().map(|t| (t.task.as_str(), t.event)), Some(("out", TaskEvent::Aborted)));
+        assert!(mission.tick(Time::new(1.1), [0.0; 3]).setpoint.is_none());
+
+        assert_eq!(supervisor.reset(Time::new(2.0)), Err(SafetyError::EmergencyStopEngaged));
+        assert_eq!(supervisor.release(Time::new(2.0)).map(|e| e.state), Some(SafetyState::Faulted));
+        supervisor.reset(Time::new(2.5)).unwrap();
+        assert_eq!(supervisor.canonical_log(), "1.000 emergency_stop emergency stop\n2.000 faulted\n2.500 running\n");
+
+        let violation = SafetyViolation::new(LimitKind::Velocity, 0.3, 0.1, LimitUnit::MeterPerSecond).with_joint(2, 1);
+        let event = supervisor.report(Time::new(3.0), Fault::Violation(violation));
+        assert_eq!(event.state, SafetyState::Faulted);
+        assert!(event.canonical().ends_with("joint 2: speed 0.300 m/s above velocity limit 0.100 m/s by 0.200 m/s"));
+    }
+}
@@ -0,0 +1,234 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Seeded random sampling of geometric objects
+//!
+//! A [`Sampler`] owns a small xoshiro256** generator, so planners, tests and
+//! benchmarks get reproducible draws from a single `u64` seed without an external
+//! random number crate. Every distribution is uniform with respect to the natural
+//! measure of its space: area on the sphere for unit vectors, the Haar measure
+//! for rotors, volume for points.
+
+use serde::{Deserialize, Serialize};
+use crate::joints::{Joint, JointLimits};
+use crate::linalg::{self, Vector3};
+use crate::motor::{Motor, Rotor};
+use crate::primitives::{Aabb, Sphere};
+use crate::si_units::{Angle, TAU};
+
+/// Region motors are drawn from: a translation box and a largest rotation angle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MotorBounds {
+    pub translation: Aabb,
+    /// Rotation angles are drawn from [0, max_angle], capped at τ/2
+    pub max_angle: Angle,
+}
+
+impl MotorBounds {
+    /// Any rotation with a translation inside `translation`
+    pub fn new(translation: Aabb) -> Self {
+        Self { translation, max_angle: Angle::new(TAU / 2.0) }
+    }
+
+    pub fn with_max_angle(mut self, max_angle: Angle) -> Self {
+        self.max_angle = Angle::new(max_angle.value().clamp(0.0, TAU / 2.0));
+        self
+    }
+}
+
+/// Reproducible source of random geometric samples
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sampler {
+    state: [u64; 4],
+}
+
+impl Sampler {
+    /// Generator whose stream depends only on `seed`
+    pub fn new(seed: u64) -> Self {
+        // Expand the seed with SplitMix64 so nearby seeds give unrelated streams
+        let mut x = seed;
+        let state = std::array::from_fn(|_| {
+            x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        });
+        Self { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Uniform on [0, 1)
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform on [low, high)
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.uniform()
+    }
+
+    /// Standard normal by the Box-Muller transform
+    pub fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.uniform();
+        (-2.0 * u.ln()).sqrt() * (TAU * self.uniform()).cos()
+    }
+
+    /// Uniform on the unit sphere
+    pub fn unit_vector(&mut self) -> Vector3 {
+        loop {
+            let v = [self.normal(), self.normal(), self.normal()];
+            let n = linalg::norm(v);
+            if n > 1e-12 {
+                return linalg::scale(v, 1.0 / n);
+            }
+        }
+    }
+
+    /// Uniform on SO(3) (Shoemake's subgroup algorithm)
+    pub fn rotor(&mut self) -> Rotor {
+        let (u1, u2, u3) = (self.uniform(), TAU * self.uniform(), TAU * self.uniform());
+        let (a, b) = ((1.0 - u1).sqrt(), u1.sqrt());
+        Rotor::from_quaternion([b * u3.cos(), a * u2.sin(), a * u2.cos(), b * u3.sin()]).normalized()
+    }
+
+    /// Uniform on the rotations of at most `max_angle`
+    pub fn rotor_within(&mut self, max_angle: Angle) -> Rotor {
+        let max_angle = max_angle.value().clamp(0.0, TAU / 2.0);
+        // The Haar density of the rotation angle is proportional to sin²(θ/2)
+        let ceiling = (max_angle / 2.0).sin().powi(2);
+        let angle = loop {
+            let angle = self.range(0.0, max_angle);
+            if self.uniform() * ceiling <= (angle / 2.0).sin().powi(2) {
+                break angle;
+            }
+        };
+        Rotor::from_axis_angle(self.unit_vector(), angle)
+    }
+
+    pub fn motor(&mut self, bounds: &MotorBounds) -> Motor {
+        let rotor = self.rotor_within(bounds.max_angle);
+        Motor::new(self.point_in_box(&bounds.translation), rotor)
+    }
+
+    pub fn point_in_box(&mut self, bounds: &Aabb) -> Vector3 {
+        std::array::from_fn(|i| self.range(bounds.min[i], bounds.max[i]))
+    }
+
+    /// Uniform in the ball bounded by `sphere`
+    pub fn point_in_sphere(&mut self, sphere: &Sphere) -> Vector3 {
+        let radius = sphere.radius * self.uniform().cbrt();
+        linalg::add(sphere.center, linalg::scale(self.unit_vector(), radius))
+    }
+
+    /// Uniform on the surface of `sphere`
+    pub fn point_on_sphere(&mut self, sphere: &Sphere) -> Vector3 {
+        linalg::add(sphere.center, linalg::scale(self.unit_vector(), sphere.radius))
+    }
+
+    /// One angle per joint, uniform within its position limits
+    pub fn joint_configuration(&mut self, limits: &[JointLimits]) -> Vec<Angle> {
+        limits.iter().map(|l| Angle::new(self.range(*l.lower.value(), *l.upper.value()))).collect()
+    }
+
+    /// Coordinates of a chain of joints, uniform within each joint's position limits
+    pub fn joint_coordinates(&mut self, joints: &[Joint]) -> Vec<f64> {
+        let mut q = Vec::new();
+        for joint in joints {
+            match joint {
+                Joint::Revolute { limits, .. } => q.push(self.range(*limits.lower.value(), *limits.upper.value())),
+                Joint::Prismatic { limits, .. } => q.push(self.range(*limits.lower.value(), *limits.upper.value())),
+                Joint::Spherical { limits } => q.extend(self.rotor_within(limits.max_angle).rotation_vector()),
+                Joint::Fixed => {}
+            }
+        }
+        q
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::AngularVelocity;
+
+    #[test]
+    fn test_seeded_streams() {
+        let mut a = Sampler::new(42);
+        let mut b = Sampler::new(42);
+        let mut c = Sampler::new(43);
+        let draws: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        assert_eq!(draws, (0..8).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(draws, (0..8).map(|_| c.next_u64()).collect::<Vec<_>>());
+
+        let mean = (0..10000).map(|_| a.uniform()).sum::<f64>() / 10000.0;
+        assert!((mean - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_uniform_rotations() {
+        let mut sampler = Sampler::new(7);
+        let n = 20000;
+        let (mut within_quarter, mut mean_image) = (0, [0.0; 3]);
+        for _ in 0..n {
+            let rotor = sampler.rotor();
+            assert!((rotor.norm() - 1.0).abs() < 1e-12);
+            if rotor.angle() <= TAU / 4.0 {
+                within_quarter += 1;
+            }
+            mean_image = linalg::add(mean_image, rotor.apply([1.0, 0.0, 0.0]));
+        }
+        // Under the Haar measure P(θ ≤ α) = (α - sin α) / π
+        let expected = (TAU / 4.0 - 1.0) / (TAU / 2.0);
+        assert!((within_quarter as f64 / n as f64 - expected).abs() < 0.01);
+        assert!(linalg::norm(mean_image) / (n as f64) < 0.02);
+
+        let small = Angle::new(0.1);
+        assert!((0..1000).all(|_| sampler.rotor_within(small).angle() <= 0.1 + 1e-12));
+    }
+
+    #[test]
+    fn test_bounded_samples() {
+        let mut sampler = Sampler::new(3);
+        let bounds = Aabb::new([-1.0, 0.0, 2.0], [1.0, 0.5, 3.0]);
+        let sphere = Sphere::new([1.0, 1.0, 1.0], 2.0);
+        let motor_bounds = MotorBounds::new(bounds).with_max_angle(Angle::new(0.5));
+        let limits = [
+            JointLimits::new(Angle::new(-1.0), Angle::new(1.0), AngularVelocity::new(2.0)),
+            JointLimits::new(Angle::new(0.0), Angle::new(TAU / 4.0), AngularVelocity::new(2.0)),
+        ];
+
+        let mut inner_half = 0;
+        for _ in 0..2000 {
+            assert!(bounds.contains(sampler.point_in_box(&bounds)));
+            let p = sampler.point_in_sphere(&sphere);
+            let r = linalg::norm(linalg::sub(p, sphere.center));
+            assert!(r <= sphere.radius);
+            if r <= sphere.radius / 2.0 {
+                inner_half += 1;
+            }
+            let on = sampler.point_on_sphere(&sphere);
+            assert!((linalg::norm(linalg::sub(on, sphere.center)) - sphere.radius).abs() < 1e-12);
+
+            let motor = sampler.motor(&motor_bounds);
+            assert!(bounds.contains(motor.translation) && motor.rotor.angle() <= 0.5 + 1e-12);
+            let configuration = sampler.joint_configuration(&limits);
+            assert!(configuration.iter().zip(&limits).all(|(q, l)| l.contains(*q)));
+        }
+        // The inner half-radius ball holds an eighth of the volume
+        assert!((inner_half as f64 / 2000.0 - 0.125).abs() < 0.03);
+    }
+}
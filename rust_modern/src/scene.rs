@@ -0,0 +1,394 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Several kinematic chains and static obstacles in one world
+//!
+//! A [`Scene`] places every robot's base on a frame of a shared [`FrameGraph`],
+//! so a manipulator can sit on a moving vehicle frame while a second arm stands
+//! on a fixed one, and all geometry is compared in the world frame. Each robot
+//! keeps its own coordinates and [`SelfCollisionModel`]; the link capsules of
+//! that model are also what other robots and the obstacles are tested against.
+//!
+//! Obstacles are world-frame [`Shape`]s held in a [`Bvh`]. [`Scene::contacts`]
+//! combines self-collision of every robot, link pairs across robots and links
+//! against obstacles into one list. Base poses use the latest sample of every
+//! frame-graph edge.
+
+use std::fmt;
+
+use crate::collision::{Bvh, Capsule, Shape};
+use crate::frames::{FrameError, FrameGraph};
+use crate::kinematics::{KinematicChain, KinematicsError};
+use crate::motor::Motor;
+use crate::self_collision::{SelfCollisionModel, SelfContact};
+use crate::si_units::Length;
+
+/// Why a scene query failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneError {
+    UnknownRobot(String),
+    DuplicateRobot(String),
+    /// The robot's base frame cannot be placed in the world frame
+    Frame(FrameError),
+    Kinematics { robot: String, error: KinematicsError },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::UnknownRobot(name) => write!(f, "no robot named '{}' in the scene", name),
+            SceneError::DuplicateRobot(name) => write!(f, "a robot named '{}' is already in the scene", name),
+            SceneError::Frame(error) => write!(f, "{}", error),
+            SceneError::Kinematics { robot, error } => write!(f, "robot '{}': {}", robot, error),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+/// A chain mounted on a frame of the scene, with its current coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneRobot {
+    name: String,
+    base_frame: String,
+    chain: KinematicChain,
+    model: SelfCollisionModel,
+    q: Vec<f64>,
+}
+
+impl SceneRobot {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn base_frame(&self) -> &str {
+        &self.base_frame
+    }
+
+    pub fn chain(&self) -> &KinematicChain {
+        &self.chain
+    }
+
+    pub fn collision_model(&self) -> &SelfCollisionModel {
+        &self.model
+    }
+
+    pub fn configuration(&self) -> &[f64] {
+        &self.q
+    }
+
+    fn error(&self, error: KinematicsError) -> SceneError {
+        SceneError::Kinematics { robot: self.name.clone(), error }
+    }
+}
+
+/// Link of a robot, named for reports
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkRef {
+    pub robot: String,
+    /// Link index in the robot's [`SelfCollisionModel::links`]
+    pub link: usize,
+}
+
+/// Closest link pair between two robots
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotDistance {
+    pub first: LinkRef,
+    pub second: LinkRef,
+    /// Signed distance between the link surfaces
+    pub distance: Length,
+}
+
+/// Closest obstacle to a robot
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObstacleDistance {
+    pub link: LinkRef,
+    /// Index in [`Scene::obstacles`]
+    pub obstacle: usize,
+    pub distance: Length,
+}
+
+/// Geometry closer than allowed somewhere in the scene
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneContact {
+    /// Two links of one robot, closer than its own self-collision margin
+    SelfCollision { robot: String, contact: SelfContact },
+    Robots(RobotDistance),
+    Obstacle(ObstacleDistance),
+}
+
+/// Robots and obstacles sharing a world frame
+#[derive(Debug, Clone)]
+pub struct Scene {
+    world: String,
+    frames: FrameGraph,
+    robots: Vec<SceneRobot>,
+    obstacles: Bvh,
+}
+
+impl Scene {
+    pub fn new(world: &str) -> Self {
+        let mut frames = FrameGraph::new();
+        frames.add_frame(world);
+        Self { world: world.to_string(), frames, robots: Vec::new(), obstacles: Bvh::build(Vec::new()) }
+    }
+
+    pub fn world(&self) -> &str {
+        &self.world
+    }
+
+    pub fn frames(&self) -> &FrameGraph {
+        &self.frames
+    }
+
+    /// The frame graph, to place robot bases and move vehicles
+    pub fn frames_mut(&mut self) -> &mut FrameGraph {
+        &mut self.frames
+    }
+
+    /// Add a robot on `base_frame` in its neutral configuration
+    pub fn add_robot(
+        &mut self,
+        name: &str,
+        base_frame: &str,
+        chain: KinematicChain,
+        model: SelfCollisionModel,
+    ) -> Result<(), SceneError> {
+        if self.robot(name).is_some() {
+            return Err(SceneError::DuplicateRobot(name.to_string()));
+        }
+        self.frames.add_frame(base_frame);
+        let q = chain.neutral();
+        self.robots.push(SceneRobot { name: name.to_string(), base_frame: base_frame.to_string(), chain, model, q });
+        Ok(())
+    }
+
+    pub fn robot(&self, name: &str) -> Option<&SceneRobot> {
+        self.robots.iter().find(|robot| robot.name == name)
+    }
+
+    fn get(&self, name: &str) -> Result<&SceneRobot, SceneError> {
+        self.robot(name).ok_or_else(|| SceneError::UnknownRobot(name.to_string()))
+    }
+
+    /// Robots in the order they were added
+    pub fn robots(&self) -> impl Iterator<Item = &SceneRobot> + '_ {
+        self.robots.iter()
+    }
+
+    pub fn set_configuration(&mut self, name: &str, q: &[f64]) -> Result<(), SceneError> {
+        let robot = self
+            .robots
+            .iter_mut()
+            .find(|robot| robot.name == name)
+            .ok_or_else(|| SceneError::UnknownRobot(name.to_string()))?;
+        if q.len() != robot.chain.dof() {
+            return Err(robot.error(KinematicsError::CoordinateCount { expected: robot.chain.dof(), actual: q.len() }));
+        }
+        robot.q = q.to_vec();
+        Ok(())
+    }
+
+    pub fn add_obstacle(&mut self, shape: Shape) {
+        let mut shapes = self.obstacles.shapes().to_vec();
+        shapes.push(shape);
+        self.obstacles = Bvh::build(shapes);
+    }
+
+    pub fn obstacles(&self) -> &[Shape] {
+        self.obstacles.shapes()
+    }
+
+    /// `world_T_base` of a robot
+    pub fn base_pose(&self, name: &str) -> Result<Motor, SceneError> {
+        let robot = self.get(name)?;
+        self.frames.lookup_latest(&self.world, &robot.base_frame).map_err(SceneError::Frame)
+    }
+
+    /// End-effector pose of a robot in the world frame
+    pub fn end_effector(&self, name: &str) -> Result<Motor, SceneError> {
+        let robot = self.get(name)?;
+        let tip = robot.chain.forward(&robot.q).map_err(|error| robot.error(error))?;
+        Ok(self.base_pose(name)? * tip)
+    }
+
+    /// Link capsules of a robot in the world frame
+    pub fn links(&self, name: &str) -> Result<Vec<Capsule>, SceneError> {
+        let robot = self.get(name)?;
+        let base = self.base_pose(name)?;
+        let links = robot.model.links(&robot.chain, &robot.q).map_err(|error| robot.error(error))?;
+        Ok(links.iter().map(|link| link.transformed(&base)).collect())
+    }
+
+    /// Closest link pair between two different robots
+    pub fn distance_between(&self, first: &str, second: &str) -> Result<Option<RobotDistance>, SceneError> {
+        let (a, b) = (self.links(first)?, self.links(second)?);
+        let mut closest: Option<RobotDistance> = None;
+        for (i, link_a) in a.iter().enumerate() {
+            for (j, link_b) in b.iter().enumerate() {
+                let distance = link_a.distance_to_capsule(link_b);
+                if closest.as_ref().is_none_or(|c| distance < c.distance) {
+                    closest = Some(RobotDistance {
+                        first: LinkRef { robot: first.to_string(), link: i },
+                        second: LinkRef { robot: second.to_string(), link: j },
+                        distance,
+                    });
+                }
+            }
+        }
+        Ok(closest)
+    }
+
+    /// Closest obstacle to any link of a robot; `None` without obstacles
+    pub fn obstacle_distance(&self, name: &str) -> Result<Option<ObstacleDistance>, SceneError> {
+        let links = self.links(name)?;
+        Ok(self
+            .obstacles
+            .nearest_batch(&links)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(link, nearest)| nearest.map(|nearest| (link, nearest)))
+            .min_by(|(_, a), (_, b)| a.distance.value().total_cmp(b.distance.value()))
+            .map(|(link, nearest)| ObstacleDistance {
+                link: LinkRef { robot: name.to_string(), link },
+                obstacle: nearest.index,
+                distance: nearest.distance,
+            }))
+    }
+
+    /// Every self-collision, and every link pair across robots or against an obstacle closer than `margin`
+    pub fn contacts(&self, margin: Length) -> Result<Vec<SceneContact>, SceneError> {
+        let mut contacts = Vec::new();
+        let mut links = Vec::with_capacity(self.robots.len());
+        for robot in &self.robots {
+            let own = robot.model.contacts(&robot.chain, &robot.q).map_err(|error| robot.error(error))?;
+            contacts.extend(own.into_iter().map(|contact| SceneContact::SelfCollision { robot: robot.name.clone(), contact }));
+            links.push(self.links(&robot.name)?);
+        }
+
+        for (a, first) in self.robots.iter().enumerate() {
+            for (b, second) in self.robots.iter().enumerate().skip(a + 1) {
+                for (i, link_a) in links[a].iter().enumerate() {
+                    for (j, link_b) in links[b].iter().enumerate() {
+                        let distance = link_a.distance_to_capsule(link_b);
+                        if distance < margin {
+                            contacts.push(SceneContact::Robots(RobotDistance {
+                                first: LinkRef { robot: first.name.clone(), link: i },
+                                second: LinkRef { robot: second.name.clone(), link: j },
+                                distance,
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (robot, links) in self.robots.iter().zip(&links) {
+            for (i, link) in links.iter().enumerate() {
+                for nearest in self.obstacles.within(link, margin).into_iter().filter(|nearest| nearest.distance < margin) {
+                    contacts.push(SceneContact::Obstacle(ObstacleDistance {
+                        link: LinkRef { robot: robot.name.clone(), link: i },
+                        obstacle: nearest.index,
+                        distance: nearest.distance,
+                    }));
+                }
+            }
+        }
+        Ok(contacts)
+    }
+
+    pub fn is_collision_free(&self, margin: Length) -> Result<bool, SceneError> {
+        Ok(self.contacts(margin)?.is_empty())
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinematics::test_fixtures::{planar, Z};
+    use crate::linalg;
+    use crate::motor::Rotor;
+    use crate::primitives::{Aabb, Sphere};
+    use crate::si_units::{Time, TAU};
+
+    /// Two revolute joints about z with links of 0.5 m along x
+    fn arm() -> (KinematicChain, SelfCollisionModel) {
+        let chain = planar(&[0.5, 0.5]);
+        let model = SelfCollisionModel::new(&chain, Length::new(0.05));
+        (chain, model)
+    }
+
+    /// Two arms 2.5 m apart facing each other
+    fn dual_arm() -> Scene {
+        let mut scene = Scene::new("world");
+        let facing = Motor::new([2.5, 0.0, 0.0], Rotor::from_axis_angle(Z, TAU / 2.0));
+        scene.frames_mut().set_static_transform("world", "left_base", Motor::identity()).unwrap();
+        scene.frames_mut().set_static_transform("world", "right_base", facing).unwrap();
+        let (chain, model) = arm();
+        scene.add_robot("left", "left_base", chain.clone(), model.clone()).unwrap();
+        scene.add_robot("right", "right_base", chain, model).unwrap();
+        scene
+    }
+
+    #[test]
+    fn test_cross_robot_distance() {
+        let mut scene = dual_arm();
+        let closest = scene.distance_between("left", "right").unwrap().unwrap();
+        assert!((closest.distance.value() - 0.4).abs() < 1e-12);
+        assert_eq!((closest.first.link, closest.second.link), (2, 2));
+        assert!(scene.is_collision_free(Length::new(0.3)).unwrap());
+
+        let contacts = scene.contacts(Length::new(0.45)).unwrap();
+        assert!(matches!(&contacts[..], [SceneContact::Robots(RobotDistance { .. })]));
+
+        assert!(linalg::norm(linalg::sub(scene.end_effector("right").unwrap().translation, [1.5, 0.0, 0.0])) < 1e-12);
+        scene.set_configuration("right", &[0.0, TAU / 4.0]).unwrap();
+        assert!(linalg::norm(linalg::sub(scene.end_effector("right").unwrap().translation, [2.0, -0.5, 0.0])) < 1e-12);
+        assert!((scene.distance_between("left", "right").unwrap().unwrap().distance.value() - 0.9).abs() < 1e-12);
+        assert!(matches!(
+            scene.set_configuration("left", &[0.0]),
+            Err(SceneError::Kinematics { error: KinematicsError::CoordinateCount { expected: 2, actual: 1 }, .. })
+        ));
+    }
+
+    #[test]
+    fn test_obstacles_and_self_collision_combine() {
+        let mut scene = dual_arm();
+        scene.add_obstacle(Shape::Sphere(Sphere::new([0.5, 0.5, 0.0], 0.2)));
+        scene.add_obstacle(Shape::Box(Aabb::new([-1.0, -1.0, -0.5], [4.0, 1.5, -0.2])));
+        let nearest = scene.obstacle_distance("left").unwrap().unwrap();
+        assert_eq!(nearest.obstacle, 1);
+        assert!((nearest.distance.value() - 0.15).abs() < 1e-9);
+
+        // The left arm swings through the sphere and the right one folds onto its base
+        scene.set_configuration("left", &[TAU / 8.0, 0.0]).unwrap();
+        scene.set_configuration("right", &[0.0, 3.0]).unwrap();
+        let contacts = scene.contacts(Length::new(0.0)).unwrap();
+        assert!(contacts.iter().any(|c| matches!(c, SceneContact::Obstacle(o) if o.link.robot == "left" && o.obstacle == 0)));
+        assert!(contacts.iter().any(|c| matches!(c, SceneContact::SelfCollision { robot, .. } if robot == "right")));
+        assert!(contacts.iter().all(|c| !matches!(c, SceneContact::Robots(_))));
+        assert!(!scene.is_collision_free(Length::new(0.0)).unwrap());
+        assert_eq!(scene.contacts(Length::new(0.0)).unwrap(), contacts);
+    }
+
+    #[test]
+    fn test_moving_vehicle_carries_its_arm() {
+        let mut scene = Scene::new("world");
+        let (chain, model) = arm();
+        scene.frames_mut().set_transform("world", "vehicle", Motor::identity(), Time::new(0.0)).unwrap();
+        scene.frames_mut().set_static_transform("vehicle", "arm_base", Motor::from_translation([0.0, 0.0, -0.3])).unwrap();
+        scene.add_robot("arm", "arm_base", chain, model).unwrap();
+        scene.add_obstacle(Shape::Sphere(Sphere::new([3.0, 0.0, -0.3], 0.5)));
+        let before = scene.obstacle_distance("arm").unwrap().unwrap().distance;
+
+        scene.frames_mut().set_transform("world", "vehicle", Motor::from_translation([1.0, 0.0, 0.0]), Time::new(1.0)).unwrap();
+        let after = scene.obstacle_distance("arm").unwrap().unwrap().distance;
+        assert!((before.value() - after.value() - 1.0).abs() < 1e-12);
+        assert!((scene.end_effector("arm").unwrap().translation[0] - 2.0).abs() < 1e-12);
+
+        assert_eq!(scene.add_robot("arm", "arm_base", arm().0, arm().1), Err(SceneError::DuplicateRobot("arm".into())));
+        assert_eq!(scene.links("boat"), Err(SceneError::UnknownRobot("boat".into())));
+    }
+}
@@ -0,0 +1,260 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Signed distance fields
+//!
+//! A [`SignedDistanceField`] samples the distance to a set of obstacles on a
+//! regular grid once, so later queries cost a trilinear interpolation instead of
+//! a hierarchy traversal. Values are negative inside obstacles. Shapes carry
+//! their own sign; triangle meshes are signed by their generalized winding
+//! number, so only closed meshes have a meaningful inside.
+//!
+//! Interpolated distances are exact at grid points and within about one cell
+//! diagonal elsewhere, which is the margin planners should add when using the
+//! field through [`ClearanceQuery`].
+
+use std::fmt;
+
+use crate::collision::{Bvh, Capsule, ClearanceQuery, Shape};
+use crate::linalg::{self, Vector3};
+use crate::mesh::{MeshCollider, TriangleMesh};
+use crate::primitives::{Aabb, Direction};
+use crate::si_units::{Length, TAU};
+
+/// Largest number of grid samples a field may allocate
+pub const MAX_SDF_SAMPLES: usize = 1 << 27;
+
+/// Errors raised while building a field
+#[derive(Debug, Clone, PartialEq)]
+pub enum SdfError {
+    /// The resolution must be positive and finite
+    InvalidResolution,
+    /// Nothing to measure distances to
+    NoObstacles,
+    /// The grid would need more than [`MAX_SDF_SAMPLES`] samples
+    TooLarge { samples: usize },
+}
+
+impl fmt::Display for SdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SdfError::InvalidResolution => write!(f, "grid resolution must be positive and finite"),
+            SdfError::NoObstacles => write!(f, "a distance field needs at least one obstacle"),
+            SdfError::TooLarge { samples } => {
+                write!(f, "grid needs {} samples, more than the limit of {}", samples, MAX_SDF_SAMPLES)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SdfError {}
+
+/// Distances sampled on a regular grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedDistanceField {
+    origin: Vector3,
+    resolution: f64,
+    /// Number of samples along x, y and z
+    dims: [usize; 3],
+    /// Samples with x varying fastest
+    values: Vec<f64>,
+}
+
+impl SignedDistanceField {
+    /// Sample the distance to `shapes` over `bounds` every `resolution`
+    pub fn from_shapes(shapes: &[Shape], bounds: &Aabb, resolution: Length) -> Result<Self, SdfError> {
+        if shapes.is_empty() {
+            return Err(SdfError::NoObstacles);
+        }
+        let bvh = Bvh::build(shapes.to_vec());
+        Self::sample(bounds, resolution, |p| *bvh.nearest(&Capsule::point(p)).expect("non-empty").distance.value())
+    }
+
+    /// Sample the distance to a closed mesh, negative inside it
+    pub fn from_mesh(mesh: &TriangleMesh, bounds: &Aabb, resolution: Length) -> Result<Self, SdfError> {
+        if mesh.is_empty() {
+            return Err(SdfError::NoObstacles);
+        }
+        let collider = MeshCollider::new(mesh);
+        Self::sample(bounds, resolution, |p| {
+            let distance = *collider.distance_to_point(p).expect("non-empty").distance.value();
+            if winding_number(mesh, p) > 0.5 {
+                -distance
+            } else {
+                distance
+            }
+        })
+    }
+
+    fn sample(bounds: &Aabb, resolution: Length, distance: impl Fn(Vector3) -> f64) -> Result<Self, SdfError> {
+        let resolution = *resolution.value();
+        if !(resolution > 0.0 && resolution.is_finite()) {
+            return Err(SdfError::InvalidResolution);
+        }
+        let extents = bounds.extents();
+        let dims: [usize; 3] = std::array::from_fn(|i| (extents[i] / resolution).ceil() as usize + 1);
+        let samples = dims.iter().try_fold(1usize, |n, &d| n.checked_mul(d)).unwrap_or(usize::MAX);
+        if samples > MAX_SDF_SAMPLES {
+            return Err(SdfError::TooLarge { samples });
+        }
+
+        let mut values = Vec::with_capacity(samples);
+        for k in 0..dims[2] {
+            for j in 0..dims[1] {
+                for i in 0..dims[0] {
+                    let offset = linalg::scale([i as f64, j as f64, k as f64], resolution);
+                    values.push(distance(linalg::add(bounds.min, offset)));
+                }
+            }
+        }
+        Ok(Self { origin: bounds.min, resolution, dims, values })
+    }
+
+    pub fn resolution(&self) -> Length {
+        Length::new(self.resolution)
+    }
+
+    pub fn dims(&self) -> [usize; 3] {
+        self.dims
+    }
+
+    /// Region covered by the samples
+    pub fn bounds(&self) -> Aabb {
+        let far = self.dims.map(|d| (d - 1) as f64 * self.resolution);
+        Aabb::new(self.origin, linalg::add(self.origin, far))
+    }
+
+    /// Interpolated distance at `point`; `None` outside the grid
+    pub fn distance(&self, point: Vector3) -> Option<Length> {
+        self.interpolate(point).map(Length::new)
+    }
+
+    /// Direction of steepest distance increase, pointing away from the nearest
+    /// obstacle; `None` outside the grid or where the field is flat
+    pub fn gradient(&self, point: Vector3) -> Option<Direction> {
+        let h = 0.5 * self.resolution;
+        let bounds = self.bounds();
+        let mut g = [0.0; 3];
+        for (axis, component) in g.iter_mut().enumerate() {
+            // Central differences, one-sided at the grid border
+            let (mut lo, mut hi) = (point, point);
+            lo[axis] = (point[axis] - h).max(bounds.min[axis]);
+            hi[axis] = (point[axis] + h).min(bounds.max[axis]);
+            *component = (self.interpolate(hi)? - self.interpolate(lo)?) / (hi[axis] - lo[axis]);
+        }
+        Direction::new(g)
+    }
+
+    fn value(&self, i: usize, j: usize, k: usize) -> f64 {
+        self.values[i + self.dims[0] * (j + self.dims[1] * k)]
+    }
+
+    fn interpolate(&self, point: Vector3) -> Option<f64> {
+        let mut cell = [0usize; 3];
+        let mut t = [0.0; 3];
+        for axis in 0..3 {
+            let u = (point[axis] - self.origin[axis]) / self.resolution;
+            let last = (self.dims[axis] - 1) as f64;
+            if !(0.0..=last).contains(&u) {
+                return None;
+            }
+            // Points on the far face use the last cell
+            let base = u.floor().min((last - 1.0).max(0.0));
+            cell[axis] = base as usize;
+            t[axis] = u - base;
+        }
+        let [i, j, k] = cell;
+        let next = |axis: usize, index: usize| (index + 1).min(self.dims[axis] - 1);
+        let (i1, j1, k1) = (next(0, i), next(1, j), next(2, k));
+        let lerp = |a: f64, b: f64, s: f64| a + (b - a) * s;
+        let x00 = lerp(self.value(i, j, k), self.value(i1, j, k), t[0]);
+        let x10 = lerp(self.value(i, j1, k), self.value(i1, j1, k), t[0]);
+        let x01 = lerp(self.value(i, j, k1), self.value(i1, j, k1), t[0]);
+        let x11 = lerp(self.value(i, j1, k1), self.value(i1, j1, k1), t[0]);
+        Some(lerp(lerp(x00, x10, t[1]), lerp(x01, x11, t[1]), t[2]))
+    }
+}
+
+impl ClearanceQuery for SignedDistanceField {
+    /// Smallest interpolated distance along the link, sampled at least once per
+    /// cell, minus its radius
+    fn clearance(&self, link: &Capsule) -> Option<Length> {
+        let length = linalg::norm(linalg::sub(link.end, link.start));
+        let steps = (length / self.resolution).ceil().max(1.0) as usize;
+        let mut nearest = f64::INFINITY;
+        for s in 0..=steps {
+            let point = linalg::add(link.start, linalg::scale(linalg::sub(link.end, link.start), s as f64 / steps as f64));
+            nearest = nearest.min(self.interpolate(point)?);
+        }
+        Some(Length::new(nearest - link.radius))
+    }
+}
+
+/// Generalized winding number of a triangle mesh around `point`: 1 inside a
+/// closed, outward-oriented mesh and 0 outside
+fn winding_number(mesh: &TriangleMesh, point: Vector3) -> f64 {
+    // Van Oosterom and Strackee's solid angle of each triangle
+    let solid_angle: f64 = mesh
+        .triangles
+        .iter()
+        .map(|t| {
+            let [a, b, c] = t.map(|v| linalg::sub(v, point));
+            let (la, lb, lc) = (linalg::norm(a), linalg::norm(b), linalg::norm(c));
+            let numerator = linalg::dot(a, linalg::cross(b, c));
+            let denominator = la * lb * lc + linalg::dot(a, b) * lc + linalg::dot(a, c) * lb + linalg::dot(b, c) * la;
+            2.0 * numerator.atan2(denominator)
+        })
+        .sum();
+    solid_angle / (2.0 * TAU)
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::Sphere;
+
+    #[test]
+    fn test_sphere_field() {
+        let shapes = [Shape::Sphere(Sphere::new([0.0; 3], 1.0))];
+        let bounds = Aabb::new([-2.0; 3], [2.0; 3]);
+        let sdf = SignedDistanceField::from_shapes(&shapes, &bounds, Length::new(0.1)).unwrap();
+        assert_eq!(sdf.dims(), [41, 41, 41]);
+
+        for point in [[0.0, 0.0, 0.0], [1.5, 0.0, 0.0], [0.3, -0.8, 1.1], [2.0, 2.0, 2.0]] {
+            let exact = linalg::norm(point) - 1.0;
+            // Trilinear interpolation of a cone-like field errs by a fraction of a cell
+            assert!((sdf.distance(point).unwrap().value() - exact).abs() < 0.05, "{:?}", point);
+        }
+        assert!(sdf.distance([2.5, 0.0, 0.0]).is_none());
+
+        let gradient = sdf.gradient([0.6, 0.8, 0.0]).unwrap().vector();
+        assert!(linalg::norm(linalg::sub(gradient, [0.6, 0.8, 0.0])) < 0.05);
+
+        let link = Capsule::new([-1.5, 1.5, 0.0], [1.5, 1.5, 0.0], 0.2);
+        let clearance = *sdf.clearance(&link).unwrap().value();
+        assert!((clearance - 0.3).abs() < 0.05);
+        assert!(sdf.clearance(&Capsule::point([5.0; 3])).is_none());
+    }
+
+    #[test]
+    fn test_mesh_field_and_errors() {
+        // Unit cube centred on the origin with outward winding
+        let v = |i: usize| [(i & 1) as f64 - 0.5, ((i >> 1) & 1) as f64 - 0.5, ((i >> 2) & 1) as f64 - 0.5];
+        let quads = [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]];
+        let cube = TriangleMesh::new(quads.iter().flat_map(|q| [[v(q[0]), v(q[1]), v(q[2])], [v(q[0]), v(q[2]), v(q[3])]]).collect());
+        assert!((winding_number(&cube, [0.1, 0.2, 0.0]) - 1.0).abs() < 1e-9);
+        assert!(winding_number(&cube, [2.0, 0.0, 0.0]).abs() < 1e-9);
+
+        let bounds = Aabb::new([-1.0; 3], [1.0; 3]);
+        let sdf = SignedDistanceField::from_mesh(&cube, &bounds, Length::new(0.25)).unwrap();
+        assert!((sdf.distance([0.0; 3]).unwrap().value() + 0.5).abs() < 1e-9);
+        assert!((sdf.distance([1.0, 0.0, 0.0]).unwrap().value() - 0.5).abs() < 1e-9);
+
+        assert_eq!(SignedDistanceField::from_shapes(&[], &bounds, Length::new(0.1)), Err(SdfError::NoObstacles));
+        assert_eq!(SignedDistanceField::from_mesh(&cube, &bounds, Length::new(0.0)), Err(SdfError::InvalidResolution));
+        let huge = Aabb::new([-1e3; 3], [1e3; 3]);
+        assert!(matches!(SignedDistanceField::from_mesh(&cube, &huge, Length::new(0.01)), Err(SdfError::TooLarge { .. })));
+    }
+}
@@ -0,0 +1,314 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Self-collision checking for articulated chains
+//!
+//! Every segment of a [`KinematicChain`] is one link: a [`Capsule`] from the
+//! previous joint frame (or the base) to the frame after the segment's joint,
+//! so a segment with no offset is a sphere around its joint. A mounted
+//! [`Tool`] adds one more link from the flange to its tool centre point, with
+//! the tool's radius, so changing grippers needs no new model. Links are tested
+//! pairwise, except for the pairs marked in a symmetric exclusion matrix.
+//!
+//! [`SelfCollisionModel::new`] excludes the pairs that touch in every
+//! configuration: neighbours, and links separated only by revolute or
+//! spherical segments without offset, such as the three axes of a spherical
+//! wrist. Further pairs can be excluded or re-included by hand.
+//!
+//! The model validates single configurations, the branches of
+//! [`IkSolverRegistry::solve`], and joint-space paths sampled at a fixed step
+//! by [`SelfCollisionModel::check_path`].
+//!
+//! [`IkSolverRegistry::solve`]: crate::inverse_kinematics::IkSolverRegistry::solve
+//! [`Tool`]: crate::tool::Tool
+
+use serde::{Deserialize, Serialize};
+
+use crate::collision::Capsule;
+use crate::joints::Joint;
+use crate::kinematics::{KinematicChain, KinematicsError};
+use crate::si_units::Length;
+
+/// Two links closer than the margin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelfContact {
+    /// Segment indices of the links, the lower first
+    pub links: (usize, usize),
+    /// Signed distance between the link surfaces
+    pub distance: Length,
+}
+
+/// First contact found along a joint-space path
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathContact {
+    /// Index of the waypoint the offending step starts from
+    pub waypoint: usize,
+    /// Fraction of the step toward the next waypoint, in `[0, 1]`
+    pub fraction: f64,
+    pub q: Vec<f64>,
+    pub contact: SelfContact,
+}
+
+/// Link radii and the pairs that are never checked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfCollisionModel {
+    radii: Vec<f64>,
+    /// Row-major `(n + 1) × (n + 1)`, symmetric; the last row is the tool link
+    excluded: Vec<bool>,
+    /// Links closer than this count as colliding
+    pub margin: Length,
+}
+
+impl SelfCollisionModel {
+    /// One link of `radius` per segment of `chain`, with the always-touching pairs excluded
+    ///
+    /// The tool link, index [`len`](Self::len), gets the same exclusions as any
+    /// link after the last segment.
+    pub fn new(chain: &KinematicChain, radius: Length) -> Self {
+        let count = chain.segments().len();
+        let slots = count + 1;
+        let mut model =
+            Self { radii: vec![*radius.value(); count], excluded: vec![false; slots * slots], margin: Length::new(0.0) };
+        let zero_length: Vec<bool> = chain
+            .segments()
+            .iter()
+            .map(|segment| segment.origin.translation == [0.0; 3] && !matches!(segment.joint, Joint::Prismatic { .. }))
+            .collect();
+        for first in 0..slots {
+            for second in first + 1..slots {
+                if zero_length[first + 1..second].iter().all(|&zero| zero) {
+                    model = model.exclude(first, second);
+                }
+            }
+        }
+        model
+    }
+
+    /// Number of segment links, one per segment of the chain the model was built for
+    pub fn len(&self) -> usize {
+        self.radii.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.radii.is_empty()
+    }
+
+    pub fn with_radius(mut self, link: usize, radius: Length) -> Self {
+        self.radii[link] = radius.value().abs();
+        self
+    }
+
+    pub fn with_margin(mut self, margin: Length) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Never check links `first` and `second` against each other
+    pub fn exclude(mut self, first: usize, second: usize) -> Self {
+        self.set_excluded(first, second, true);
+        self
+    }
+
+    /// Check links `first` and `second` against each other even if excluded by default
+    pub fn include(mut self, first: usize, second: usize) -> Self {
+        self.set_excluded(first, second, false);
+        self
+    }
+
+    fn set_excluded(&mut self, first: usize, second: usize, excluded: bool) {
+        let n = self.len() + 1;
+        self.excluded[first * n + second] = excluded;
+        self.excluded[second * n + first] = excluded;
+    }
+
+    pub fn is_excluded(&self, first: usize, second: usize) -> bool {
+        first == second || self.excluded[first * (self.len() + 1) + second]
+    }
+
+    /// Link pairs that are checked, the lower index first, including the tool link
+    pub fn checked_pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let slots = self.len() + 1;
+        (0..slots)
+            .flat_map(move |first| (first + 1..slots).map(move |second| (first, second)))
+            .filter(|&(first, second)| !self.is_excluded(first, second))
+    }
+
+    /// The link capsules at `q` in the base frame, followed by the tool link when a tool is mounted
+    pub fn links(&self, chain: &KinematicChain, q: &[f64]) -> Result<Vec<Capsule>, KinematicsError> {
+        if chain.segments().len() != self.len() {
+            return Err(KinematicsError::LinkCount { expected: self.len(), actual: chain.segments().len() });
+        }
+        let mut start = [0.0; 3];
+        let mut links: Vec<Capsule> = chain
+            .frames(q)?
+            .iter()
+            .zip(&self.radii)
+            .map(|(frame, &radius)| {
+                let link = Capsule::new(start, frame.translation, radius);
+                start = frame.translation;
+                link
+            })
+            .collect();
+        if let Some(tool) = chain.tool() {
+            let tcp = chain.forward(q)?.translation;
+            links.push(Capsule::new(start, tcp, *tool.radius.value()));
+        }
+        Ok(links)
+    }
+
+    /// Checked pairs among `links`, which may lack the tool link
+    fn pairs_present(&self, links: &[Capsule]) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let present = links.len();
+        self.checked_pairs().filter(move |&(_, second)| second < present)
+    }
+
+    /// Every checked pair closer than the margin at `q`
+    pub fn contacts(&self, chain: &KinematicChain, q: &[f64]) -> Result<Vec<SelfContact>, KinematicsError> {
+        let links = self.links(chain, q)?;
+        Ok(self
+            .pairs_present(&links)
+            .map(|(first, second)| SelfContact { links: (first, second), distance: links[first].distance_to_capsule(&links[second]) })
+            .filter(|contact| contact.distance < self.margin)
+            .collect())
+    }
+
+    /// Smallest signed distance between checked links at `q`; `None` when no pair is checked
+    pub fn clearance(&self, chain: &KinematicChain, q: &[f64]) -> Result<Option<Length>, KinematicsError> {
+        let links = self.links(chain, q)?;
+        Ok(self
+            .pairs_present(&links)
+            .map(|(first, second)| links[first].distance_to_capsule(&links[second]))
+            .min_by(|a, b| a.value().total_cmp(b.value())))
+    }
+
+    /// `Ok` when no checked pair is closer than the margin at `q`, else the closest pair as an error
+    pub fn validate(&self, chain: &KinematicChain, q: &[f64]) -> Result<(), KinematicsError> {
+        let closest = self.contacts(chain, q)?.into_iter().min_by(|a, b| a.distance.value().total_cmp(b.distance.value()));
+        match closest {
+            Some(SelfContact { links: (first, second), .. }) => Err(KinematicsError::SelfCollision { first, second }),
+            None => Ok(()),
+        }
+    }
+
+    /// First configuration in collision on the straight joint-space steps between `waypoints`
+    ///
+    /// Each step is sampled so that no coordinate moves more than `max_step`
+    /// (rad or m) between samples; both ends of every step are checked.
+    pub fn check_path(
+        &self,
+        chain: &KinematicChain,
+        waypoints: &[Vec<f64>],
+        max_step: f64,
+    ) -> Result<Option<PathContact>, KinematicsError> {
+        let Some(first) = waypoints.first() else {
+            return Ok(None);
+        };
+        if let Some(contact) = self.contacts(chain, first)?.into_iter().next() {
+            return Ok(Some(PathContact { waypoint: 0, fraction: 0.0, q: first.clone(), contact }));
+        }
+        for (waypoint, step) in waypoints.windows(2).enumerate() {
+            let (from, to) = (&step[0], &step[1]);
+            if from.len() != to.len() {
+                return Err(KinematicsError::CoordinateCount { expected: from.len(), actual: to.len() });
+            }
+            let largest = from.iter().zip(to).fold(0.0f64, |m, (a, b)| m.max((b - a).abs()));
+            let samples = ((largest / max_step).ceil() as usize).max(1);
+            for sample in 1..=samples {
+                let fraction = sample as f64 / samples as f64;
+                let q: Vec<f64> = from.iter().zip(to).map(|(a, b)| a + (b - a) * fraction).collect();
+                if let Some(contact) = self.contacts(chain, &q)?.into_iter().next() {
+                    return Ok(Some(PathContact { waypoint, fraction, q, contact }));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inverse_kinematics::{IkSolver, IkSolverRegistry, Planar2R, SphericalWrist6R};
+    use crate::kinematics::test_fixtures::{full_turn, planar};
+
+    /// Three revolute joints about z with links of 0.5 m along x
+    fn planar_3r() -> KinematicChain {
+        planar(&[0.5, 0.5, 0.5])
+    }
+
+    #[test]
+    fn test_default_exclusions() {
+        let chain = planar_3r();
+        let model = SelfCollisionModel::new(&chain, Length::new(0.05));
+        // The base link is a sphere around the first joint; link 4 is the tool slot
+        assert_eq!(model.checked_pairs().collect::<Vec<_>>(), vec![(0, 2), (0, 3), (0, 4), (1, 3), (1, 4), (2, 4)]);
+
+        let wrist = SphericalWrist6R::new(0.4, 0.5, 0.45, 0.1).chain(full_turn());
+        let model = SelfCollisionModel::new(&wrist, Length::new(0.05));
+        assert!(model.is_excluded(3, 6) && model.is_excluded(4, 5));
+        assert!(!model.is_excluded(2, 6) && !model.is_excluded(1, 3));
+        assert!(model.clone().exclude(1, 3).is_excluded(3, 1));
+        assert!(!model.include(3, 6).is_excluded(3, 6));
+    }
+
+    #[test]
+    fn test_folded_arm_collides() {
+        let chain = planar_3r();
+        let model = SelfCollisionModel::new(&chain, Length::new(0.05));
+        assert!(model.validate(&chain, &[0.0, 0.0, 0.0]).is_ok());
+        assert!((model.clearance(&chain, &[0.0; 3]).unwrap().unwrap().value() - 0.4).abs() < 1e-12);
+
+        // Folding both elbows brings the last link back across the first
+        let folded = [0.0, 2.5, 2.5];
+        let contacts = model.contacts(&chain, &folded).unwrap();
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].links, (1, 3));
+        assert_eq!(model.validate(&chain, &folded), Err(KinematicsError::SelfCollision { first: 1, second: 3 }));
+
+        assert!(model.clone().with_margin(Length::new(0.3)).validate(&chain, &[0.0; 3]).is_ok());
+        assert!(model.clone().with_margin(Length::new(0.45)).validate(&chain, &[0.0; 3]).is_err());
+
+        assert_eq!(
+            model.links(&Planar2R::new(0.5, 0.5).chain(full_turn()), &[0.0, 0.0]),
+            Err(KinematicsError::LinkCount { expected: 4, actual: 3 })
+        );
+    }
+
+    #[test]
+    fn test_path_check_finds_first_contact() {
+        let chain = planar_3r();
+        let model = SelfCollisionModel::new(&chain, Length::new(0.05));
+        let clear = vec![vec![0.0, 0.0, 0.0], vec![0.5, 1.0, -1.0]];
+        assert_eq!(model.check_path(&chain, &clear, 0.05).unwrap(), None);
+
+        // Both ends are free, but the sweep through the fold is not
+        let through = vec![vec![0.0, 0.0, 0.0], vec![0.0, 2.5, 2.5], vec![0.0, 0.0, 0.0]];
+        assert!(model.validate(&chain, &through[2]).is_ok());
+        let contact = model.check_path(&chain, &through, 0.05).unwrap().unwrap();
+        assert_eq!(contact.waypoint, 0);
+        assert!(contact.fraction > 0.5 && contact.fraction < 1.0);
+        assert!(*contact.contact.distance.value() < 0.0);
+        assert_eq!(model.contacts(&chain, &contact.q).unwrap()[0], contact.contact);
+    }
+
+    #[test]
+    fn test_ik_registry_drops_colliding_branches() {
+        let solver = SphericalWrist6R::new(0.4, 0.5, 0.45, 0.1);
+        let chain = solver.chain(full_turn());
+        // Half of the branches reach back with the wrist over the upper arm
+        let goal = [0.2, -0.4, 2.5, 0.3, 0.8, -0.2];
+        let target = chain.forward(&goal).unwrap();
+
+        let registry = IkSolverRegistry::new().with_solver("spherical_wrist_6r", solver);
+        let all = registry.solve(&chain, &target, &goal).unwrap();
+        let model = SelfCollisionModel::new(&chain, Length::new(0.12));
+        let free: Vec<_> = all.iter().filter(|s| model.validate(&chain, &s.q).is_ok()).cloned().collect();
+        assert!(!free.is_empty() && free.len() < all.len());
+
+        let registry = registry.with_self_collision(model);
+        assert_eq!(registry.solve(&chain, &target, &goal).unwrap(), free);
+        assert!(solver.solve(&chain, &target).len() >= all.len());
+    }
+}
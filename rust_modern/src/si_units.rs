@@ -9,6 +9,7 @@
 //!
 //! Mathematical Convention: Uses τ (tau = 2π) instead of π for all angular calculations.
 
+use std::fmt;
 use std::marker::PhantomData;
 use std::ops::{Add, Sub, Mul, Div, AddAssign, SubAssign, MulAssign, DivAssign, Neg};
 use serde::{Deserialize, Serialize};
@@ -47,8 +48,91 @@ pub type EnergyDim = Dimension<1, 2, -2, 0, 0, 0, 0>;       // kg⋅m²/s²
 pub type PowerDim = Dimension<1, 2, -3, 0, 0, 0, 0>;        // kg⋅m²/s³
 pub type AngularVelocityDim = Dimension<0, 0, -1, 0, 0, 0, 0>; // rad/s (dimensionless/time)
 
-/// Quantity struct with compile-time unit checking
+impl<const M: i8, const L: i8, const Ti: i8, const C: i8, const Te: i8, const A: i8, const Lu: i8>
+    Dimension<M, L, Ti, C, Te, A, Lu>
+{
+    pub const fn exponents() -> DimensionExponents {
+        DimensionExponents([M, L, Ti, C, Te, A, Lu])
+    }
+}
+
+/// Runtime form of a dimension, printed as a symbol expression with its name:
+/// `kg·m²·s⁻³ (power)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DimensionExponents(pub [i8; 7]);
+
+impl DimensionExponents {
+    /// Base unit symbols in exponent order
+    pub const SYMBOLS: [&'static str; 7] = ["kg", "m", "s", "A", "K", "mol", "cd"];
+    /// Base quantity names in exponent order
+    pub const BASES: [&'static str; 7] = ["mass", "length", "time", "current", "temperature", "amount", "luminosity"];
+
+    /// Common name of the dimension, if it has one
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self.0 {
+            [0, 0, 0, 0, 0, 0, 0] => "dimensionless",
+            [1, 0, 0, 0, 0, 0, 0] => "mass",
+            [0, 1, 0, 0, 0, 0, 0] => "length",
+            [0, 0, 1, 0, 0, 0, 0] => "time",
+            [0, 0, 0, 1, 0, 0, 0] => "current",
+            [0, 0, 0, 0, 1, 0, 0] => "temperature",
+            [0, 2, 0, 0, 0, 0, 0] => "area",
+            [0, 3, 0, 0, 0, 0, 0] => "volume",
+            [0, -1, 0, 0, 0, 0, 0] => "curvature",
+            [0, 0, -1, 0, 0, 0, 0] => "frequency or angular velocity",
+            [0, 1, -1, 0, 0, 0, 0] => "velocity",
+            [0, 1, -2, 0, 0, 0, 0] => "acceleration",
+            [1, 1, -1, 0, 0, 0, 0] => "momentum",
+            [1, 1, -2, 0, 0, 0, 0] => "force",
+            [1, 2, -2, 0, 0, 0, 0] => "energy or torque",
+            [1, 2, -3, 0, 0, 0, 0] => "power",
+            [1, -1, -2, 0, 0, 0, 0] => "pressure",
+            [1, -3, 0, 0, 0, 0, 0] => "density",
+            [1, 2, 0, 0, 0, 0, 0] => "moment of inertia",
+            _ => return None,
+        })
+    }
+
+    /// Product of base symbols with superscript exponents, `1` when dimensionless
+    pub fn symbol(&self) -> String {
+        let factors: Vec<String> = self
+            .0
+            .iter()
+            .zip(Self::SYMBOLS)
+            .filter(|(exponent, _)| **exponent != 0)
+            .map(|(exponent, symbol)| match exponent {
+                1 => symbol.to_string(),
+                _ => format!("{}{}", symbol, superscript(*exponent)),
+            })
+            .collect();
+        if factors.is_empty() { "1".to_string() } else { factors.join("·") }
+    }
+}
+
+impl fmt::Display for DimensionExponents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{} ({})", self.symbol(), name),
+            None => f.write_str(&self.symbol()),
+        }
+    }
+}
+
+/// Exponent in Unicode superscript digits
+pub fn superscript(exponent: i8) -> String {
+    exponent
+        .to_string()
+        .chars()
+        .map(|c| match c {
+            '-' => '⁻',
+            digit => ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'][digit.to_digit(10).unwrap_or(0) as usize],
+        })
+        .collect()
+}
+
+/// Quantity struct with compile-time unit checking; serialized as its bare value
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Quantity<
     T,
     const MASS: i8,
@@ -60,6 +144,7 @@ pub struct Quantity<
     const LUMINOSITY: i8,
 > {
     value: T,
+    #[serde(skip)]
     _dimension: PhantomData<Dimension<MASS, LENGTH, TIME, CURRENT, TEMPERATURE, AMOUNT, LUMINOSITY>>,
 }
 
@@ -93,6 +178,11 @@ impl<T, const M: i8, const L: i8, const Ti: i8, const C: i8, const Te: i8, const
     pub const fn is_dimensionless() -> bool {
         M == 0 && L == 0 && Ti == 0 && C == 0 && Te == 0 && A == 0 && Lu == 0
     }
+
+    /// Dimension of this quantity type, printable as e.g. `kg·m·s⁻² (force)`
+    pub const fn dimension() -> DimensionExponents {
+        DimensionExponents([M, L, Ti, C, Te, A, Lu])
+    }
 }
 
 // Implement From<T> for dimensionless quantities
@@ -102,6 +192,22 @@ impl<T> From<T> for Quantity<T, 0, 0, 0, 0, 0, 0, 0> {
     }
 }
 
+/// Const constructors for angles, in radians under the tau convention
+impl Quantity<f64, 0, 0, 0, 0, 0, 0, 0> {
+    pub const fn from_radians(radians: f64) -> Self {
+        Self::new(radians)
+    }
+
+    pub const fn from_degrees(degrees: f64) -> Self {
+        Self::new(degrees * (TAU / 360.0))
+    }
+
+    /// Angle from a fraction of a full turn: `from_turns(0.25)` is a quarter turn, τ/4
+    pub const fn from_turns(turns: f64) -> Self {
+        Self::new(turns * TAU)
+    }
+}
+
 // Arithmetic operations for same dimensions
 impl<T, const M: i8, const L: i8, const Ti: i8, const C: i8, const Te: i8, const A: i8, const Lu: i8>
     Add for Quantity<T, M, L, Ti, C, Te, A, Lu>
@@ -228,6 +334,115 @@ where
     }
 }
 
+/// `f64` quantities of any dimension, for code that is generic over the dimension
+/// but must not change it (filters, controllers)
+pub trait Measure: Copy {
+    fn raw(self) -> f64;
+    fn from_raw(value: f64) -> Self;
+}
+
+impl<const M: i8, const L: i8, const Ti: i8, const C: i8, const Te: i8, const A: i8, const Lu: i8> Measure
+    for Quantity<f64, M, L, Ti, C, Te, A, Lu>
+{
+    fn raw(self) -> f64 {
+        self.value
+    }
+
+    fn from_raw(value: f64) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Fraction in `[0, 1]`: efficiencies, duty cycles, states of charge
+///
+/// Unlike a [`DimensionlessQ`], which may hold any value (angles, gains), a
+/// ratio can only be built through checked constructors, so an efficiency of
+/// 85 (percent meant, fraction read) is rejected instead of multiplying power
+/// by 85. Serialized as the bare fraction and validated when deserialized.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Ratio(f64);
+
+/// Value outside `[0, 1]` (or `[0, 100]` for percentages)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RatioError(pub f64);
+
+impl fmt::Display for RatioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a fraction between 0 and 1", self.0)
+    }
+}
+
+impl std::error::Error for RatioError {}
+
+impl Ratio {
+    pub const ZERO: Ratio = Ratio(0.0);
+    pub const ONE: Ratio = Ratio(1.0);
+
+    pub fn new(fraction: f64) -> Result<Self, RatioError> {
+        if (0.0..=1.0).contains(&fraction) { Ok(Self(fraction)) } else { Err(RatioError(fraction)) }
+    }
+
+    pub fn from_percent(percent: f64) -> Result<Self, RatioError> {
+        Self::new(percent / 100.0).map_err(|_| RatioError(percent))
+    }
+
+    /// Clamp into `[0, 1]`; NaN becomes zero
+    pub fn saturating(fraction: f64) -> Self {
+        Self(if fraction.is_nan() { 0.0 } else { fraction.clamp(0.0, 1.0) })
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    pub fn percent(self) -> f64 {
+        self.0 * 100.0
+    }
+
+    /// `1 - self`
+    pub fn complement(self) -> Self {
+        Self(1.0 - self.0)
+    }
+
+    /// This fraction of a quantity
+    pub fn of<Q: Measure>(self, quantity: Q) -> Q {
+        Q::from_raw(self.0 * quantity.raw())
+    }
+}
+
+impl Mul for Ratio {
+    type Output = Ratio;
+
+    /// Chained fractions, e.g. motor efficiency times propeller efficiency
+    fn mul(self, rhs: Ratio) -> Ratio {
+        Ratio(self.0 * rhs.0)
+    }
+}
+
+impl TryFrom<f64> for Ratio {
+    type Error = RatioError;
+
+    fn try_from(fraction: f64) -> Result<Self, RatioError> {
+        Self::new(fraction)
+    }
+}
+
+impl From<Ratio> for f64 {
+    fn from(ratio: Ratio) -> f64 {
+        ratio.0
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} %", precision, self.percent()),
+            None => write!(f, "{} %", self.percent()),
+        }
+    }
+}
+
 /// Type aliases for common quantities
 pub type DimensionlessQ<T = f64> = Quantity<T, 0, 0, 0, 0, 0, 0, 0>;
 pub type Mass<T = f64> = Quantity<T, 1, 0, 0, 0, 0, 0, 0>;
@@ -239,6 +454,17 @@ pub type Force<T = f64> = Quantity<T, 1, 1, -2, 0, 0, 0, 0>;
 pub type Energy<T = f64> = Quantity<T, 1, 2, -2, 0, 0, 0, 0>;
 pub type Power<T = f64> = Quantity<T, 1, 2, -3, 0, 0, 0, 0>;
 pub type AngularVelocity<T = f64> = Quantity<T, 0, 0, -1, 0, 0, 0, 0>;
+pub type Frequency<T = f64> = Quantity<T, 0, 0, -1, 0, 0, 0, 0>;
+pub type Temperature<T = f64> = Quantity<T, 0, 0, 0, 0, 1, 0, 0>;
+pub type Volume<T = f64> = Quantity<T, 0, 3, 0, 0, 0, 0, 0>;
+pub type Density<T = f64> = Quantity<T, 1, -3, 0, 0, 0, 0, 0>;
+pub type Pressure<T = f64> = Quantity<T, 1, -1, -2, 0, 0, 0, 0>;
+pub type MomentOfInertia<T = f64> = Quantity<T, 1, 2, 0, 0, 0, 0, 0>;
+pub type Torque<T = f64> = Quantity<T, 1, 2, -2, 0, 0, 0, 0>;
+pub type Curvature<T = f64> = Quantity<T, 0, -1, 0, 0, 0, 0, 0>;
+
+/// Plane angle in radians (dimensionless, tau convention)
+pub type Angle<T = f64> = DimensionlessQ<T>;
 
 /// Unit construction functions
 pub mod units {
@@ -296,6 +522,11 @@ pub mod units {
         Time::new(value * 3600.0)
     }
 
+    // Frequency units
+    pub fn hertz<T>(value: T) -> Frequency<T> {
+        Frequency::new(value)
+    }
+
     // Mass units
     pub fn kilograms<T>(value: T) -> Mass<T> {
         Mass::new(value)
@@ -426,6 +657,7 @@ pub mod units {
 /// Mathematical functions with units
 pub mod math {
     use super::*;
+    use crate::determinism::settle;
 
     /// Trigonometric functions (dimensionless input)
     pub fn sin<T>(angle: DimensionlessQ<T>) -> T
@@ -434,7 +666,7 @@ pub mod math {
         f64: Into<T>,
     {
         let angle_f64: f64 = angle.into_value().into();
-        angle_f64.sin().into()
+        settle(angle_f64.sin()).into()
     }
 
     pub fn cos<T>(angle: DimensionlessQ<T>) -> T
@@ -443,7 +675,7 @@ pub mod math {
         f64: Into<T>,
     {
         let angle_f64: f64 = angle.into_value().into();
-        angle_f64.cos().into()
+        settle(angle_f64.cos()).into()
     }
 
     pub fn tan<T>(angle: DimensionlessQ<T>) -> T
@@ -452,7 +684,7 @@ pub mod math {
         f64: Into<T>,
     {
         let angle_f64: f64 = angle.into_value().into();
-        angle_f64.tan().into()
+        settle(angle_f64.tan()).into()
     }
 
     /// Square root (requires even dimension powers - simplified version)
@@ -462,7 +694,7 @@ pub mod math {
         f64: Into<T>,
     {
         let value_f64: f64 = quantity.into_value().into();
-        Length::new(value_f64.sqrt().into())
+        Length::new(settle(value_f64.sqrt()).into())
     }
 
     /// Absolute value
@@ -519,8 +751,9 @@ pub mod convert {
 pub mod marine {
     use super::*;
 
-    /// Water density at standard conditions (kg/m³)
-    pub fn water_density<T>() -> Quantity<T, 1, -3, 0, 0, 0, 0, 0>
+    /// Water density at standard conditions (kg/m³); see
+    /// [`crate::water_column::WaterColumn`] for density varying with depth
+    pub fn water_density<T>() -> Density<T>
     where
         T: From<f64>,
     {
@@ -536,15 +769,16 @@ pub mod marine {
     }
 
     /// Atmospheric pressure at sea level (Pa)
-    pub fn atmospheric_pressure<T>() -> Quantity<T, 1, -1, -2, 0, 0, 0, 0>
+    pub fn atmospheric_pressure<T>() -> Pressure<T>
     where
         T: From<f64>,
     {
         Quantity::new(T::from(101325.0))
     }
 
-    /// Calculate buoyancy force
-    pub fn buoyancy_force<T>(volume: Quantity<T, 0, 3, 0, 0, 0, 0, 0>) -> Force<T>
+    /// Calculate buoyancy force; see [`crate::hydrostatics`] for where it acts
+    /// on a heeled hull
+    pub fn buoyancy_force<T>(volume: Volume<T>) -> Force<T>
     where
         T: Mul<T, Output = T> + From<f64>,
     {
@@ -552,7 +786,7 @@ pub mod marine {
     }
 
     /// Calculate hydrostatic pressure at depth
-    pub fn pressure_at_depth<T>(depth: Length<T>) -> Quantity<T, 1, -1, -2, 0, 0, 0, 0>
+    pub fn pressure_at_depth<T>(depth: Length<T>) -> Pressure<T>
     where
         T: Add<T, Output = T> + Mul<T, Output = T> + From<f64>,
     {
@@ -574,6 +808,9 @@ pub trait UnitExt<T> {
     fn minutes(self) -> Time<T>;
     fn hours(self) -> Time<T>;
 
+    // Frequency
+    fn hertz(self) -> Frequency<T>;
+
     // Mass
     fn kilograms(self) -> Mass<T>;
     fn grams(self) -> Mass<T>;
@@ -596,6 +833,8 @@ impl UnitExt<f64> for f64 {
     fn minutes(self) -> Time<f64> { units::minutes(self) }
     fn hours(self) -> Time<f64> { units::hours(self) }
 
+    fn hertz(self) -> Frequency<f64> { units::hertz(self) }
+
     fn kilograms(self) -> Mass<f64> { units::kilograms(self) }
     fn grams(self) -> Mass<f64> { units::grams(self) }
     fn tons(self) -> Mass<f64> { units::tons(self) }
@@ -616,6 +855,8 @@ impl UnitExt<f32> for f32 {
     fn minutes(self) -> Time<f32> { units::minutes(self) }
     fn hours(self) -> Time<f32> { units::hours(self) }
 
+    fn hertz(self) -> Frequency<f32> { units::hertz(self) }
+
     fn kilograms(self) -> Mass<f32> { units::kilograms(self) }
     fn grams(self) -> Mass<f32> { units::grams(self) }
     fn tons(self) -> Mass<f32> { units::tons(self) }
@@ -625,10 +866,246 @@ impl UnitExt<f32> for f32 {
     fn turns(self) -> DimensionlessQ<f32> { units::turns(self) }
 }
 
+/// A unit symbol as a factor to SI base units and a dimension
+///
+/// [`qty!`](crate::qty) combines these in constant evaluation, so the dimension of
+/// a literal is known to the type checker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    /// Value of one of this unit in SI base units
+    pub scale: f64,
+    /// Exponents in [`DimensionExponents`] order
+    pub dimension: [i8; 7],
+}
+
+impl Unit {
+    pub const fn new(scale: f64, dimension: [i8; 7]) -> Self {
+        Self { scale, dimension }
+    }
+
+    pub const fn mul(self, rhs: Unit) -> Unit {
+        let mut dimension = self.dimension;
+        let mut i = 0;
+        while i < 7 {
+            dimension[i] += rhs.dimension[i];
+            i += 1;
+        }
+        Unit::new(self.scale * rhs.scale, dimension)
+    }
+
+    pub const fn div(self, rhs: Unit) -> Unit {
+        self.mul(rhs.recip())
+    }
+
+    pub const fn recip(self) -> Unit {
+        let mut dimension = self.dimension;
+        let mut i = 0;
+        while i < 7 {
+            dimension[i] = -dimension[i];
+            i += 1;
+        }
+        Unit::new(1.0 / self.scale, dimension)
+    }
+
+    pub const fn powi(self, exponent: i8) -> Unit {
+        let factor = if exponent < 0 { self.recip() } else { self };
+        let mut result = Unit::new(1.0, [0; 7]);
+        let mut n = 0;
+        while n < exponent.unsigned_abs() {
+            result = result.mul(factor);
+            n += 1;
+        }
+        result
+    }
+}
+
+/// Unit symbols accepted by [`qty!`](crate::qty)
+#[allow(non_upper_case_globals)]
+pub mod symbols {
+    use super::{Unit, TAU};
+
+    const fn base(index: usize, scale: f64) -> Unit {
+        let mut dimension = [0; 7];
+        dimension[index] = 1;
+        Unit::new(scale, dimension)
+    }
+
+    pub const m: Unit = base(1, 1.0);
+    pub const cm: Unit = base(1, 0.01);
+    pub const mm: Unit = base(1, 0.001);
+    pub const km: Unit = base(1, 1000.0);
+
+    pub const s: Unit = base(2, 1.0);
+    pub const ms: Unit = base(2, 0.001);
+    pub const min: Unit = base(2, 60.0);
+    pub const h: Unit = base(2, 3600.0);
+
+    pub const kg: Unit = base(0, 1.0);
+    pub const g: Unit = base(0, 0.001);
+    /// Metric ton
+    pub const t: Unit = base(0, 1000.0);
+
+    pub const A: Unit = base(3, 1.0);
+    pub const K: Unit = base(4, 1.0);
+    pub const mol: Unit = base(5, 1.0);
+    pub const cd: Unit = base(6, 1.0);
+
+    pub const Hz: Unit = s.powi(-1);
+    pub const N: Unit = kg.mul(m).div(s.powi(2));
+    pub const kN: Unit = Unit::new(1000.0, N.dimension);
+    pub const J: Unit = N.mul(m);
+    pub const kJ: Unit = Unit::new(1000.0, J.dimension);
+    pub const W: Unit = J.div(s);
+    pub const kW: Unit = Unit::new(1000.0, W.dimension);
+    pub const hp: Unit = Unit::new(745.7, W.dimension);
+    pub const Pa: Unit = N.div(m.powi(2));
+    pub const kPa: Unit = Unit::new(1000.0, Pa.dimension);
+    pub const bar: Unit = Unit::new(100_000.0, Pa.dimension);
+    /// Liter
+    pub const L: Unit = Unit::new(0.001, [0, 3, 0, 0, 0, 0, 0]);
+    /// Knot, nautical mile per hour
+    pub const kn: Unit = Unit::new(0.514444, [0, 1, -1, 0, 0, 0, 0]);
+
+    // Angles are dimensionless, in radians (tau convention)
+    pub const rad: Unit = Unit::new(1.0, [0; 7]);
+    pub const deg: Unit = Unit::new(TAU / 360.0, [0; 7]);
+    pub const turn: Unit = Unit::new(TAU, [0; 7]);
+    pub const rpm: Unit = Unit::new(TAU / 60.0, [0, 0, -1, 0, 0, 0, 0]);
+}
+
+/// A quantity from a number and a unit expression: `qty!(9.81 m/s^2)`
+///
+/// Units are the symbols in [`si_units::symbols`](crate::si_units::symbols),
+/// combined left to right with `*` and `/` and raised to integer powers with `^`.
+/// The dimension is worked out at compile time, so the result has the matching
+/// [`Quantity`](crate::si_units::Quantity) type and the value is scaled to SI:
+///
+/// ```
+/// use gafro_modern::qty;
+/// use gafro_modern::si_units::{Acceleration, Pressure, Velocity};
+///
+/// let g: Acceleration = qty!(9.81 m/s^2);
+/// let speed: Velocity = qty!(36 km/h);
+/// let depth_pressure: Pressure = qty!(2.5 bar);
+/// assert_eq!(*speed.value(), 10.0);
+/// # let _ = (g, depth_pressure);
+/// ```
+///
+/// Unknown symbols and dimension mismatches do not compile:
+///
+/// ```compile_fail
+/// use gafro_modern::qty;
+/// use gafro_modern::si_units::Velocity;
+///
+/// let speed: Velocity = qty!(9.81 m/s^2);
+/// ```
+#[macro_export]
+macro_rules! qty {
+    (@factor $u:ident ^ $p:literal $($rest:tt)*) => {
+        $crate::qty!(@rest ($crate::si_units::symbols::$u.powi($p)) $($rest)*)
+    };
+    (@factor $u:ident $($rest:tt)*) => {
+        $crate::qty!(@rest ($crate::si_units::symbols::$u) $($rest)*)
+    };
+    (@rest ($acc:expr)) => { $acc };
+    (@rest ($acc:expr) * $u:ident ^ $p:literal $($rest:tt)*) => {
+        $crate::qty!(@rest ($acc.mul($crate::si_units::symbols::$u.powi($p))) $($rest)*)
+    };
+    (@rest ($acc:expr) * $u:ident $($rest:tt)*) => {
+        $crate::qty!(@rest ($acc.mul($crate::si_units::symbols::$u)) $($rest)*)
+    };
+    (@rest ($acc:expr) / $u:ident ^ $p:literal $($rest:tt)*) => {
+        $crate::qty!(@rest ($acc.div($crate::si_units::symbols::$u.powi($p))) $($rest)*)
+    };
+    (@rest ($acc:expr) / $u:ident $($rest:tt)*) => {
+        $crate::qty!(@rest ($acc.div($crate::si_units::symbols::$u)) $($rest)*)
+    };
+    ($value:literal $($unit:tt)+) => {{
+        const UNIT: $crate::si_units::Unit = $crate::qty!(@factor $($unit)+);
+        $crate::si_units::Quantity::<
+            f64,
+            { UNIT.dimension[0] },
+            { UNIT.dimension[1] },
+            { UNIT.dimension[2] },
+            { UNIT.dimension[3] },
+            { UNIT.dimension[4] },
+            { UNIT.dimension[5] },
+            { UNIT.dimension[6] },
+        >::new($value as f64 * UNIT.scale)
+    }};
+}
+
+/// An [`Angle`] constant from a number in `deg`, `rad` or `tau` (turns)
+///
+/// The conversion happens at compile time, so angles can appear in `const`
+/// items such as joint limits:
+///
+/// ```
+/// use gafro_modern::{angle, qty};
+/// use gafro_modern::joints::JointLimits;
+/// use gafro_modern::si_units::{Angle, TAU};
+///
+/// const ELBOW: JointLimits = JointLimits {
+///     lower: angle!(-135 deg),
+///     upper: angle!(0.375 tau),
+///     max_velocity: qty!(30 rpm),
+/// };
+/// const HEADING: Angle = angle!(1.2 rad);
+/// assert!((ELBOW.upper.value() - 0.375 * TAU).abs() < 1e-12);
+/// # let _ = HEADING;
+/// ```
+#[macro_export]
+macro_rules! angle {
+    ($value:literal deg) => {{
+        const ANGLE: $crate::si_units::Angle = $crate::si_units::Angle::from_degrees($value as f64);
+        ANGLE
+    }};
+    ($value:literal rad) => {{
+        const ANGLE: $crate::si_units::Angle = $crate::si_units::Angle::from_radians($value as f64);
+        ANGLE
+    }};
+    ($value:literal tau) => {{
+        const ANGLE: $crate::si_units::Angle = $crate::si_units::Angle::from_turns($value as f64);
+        ANGLE
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_qty_literals() {
+        let g: Acceleration = crate::qty!(9.81 m/s^2);
+        assert_eq!(*g.value(), 9.81);
+        let speed: Velocity = crate::qty!(36 km/h);
+        assert!((speed.value() - 10.0).abs() < 1e-12);
+        let force: Force = crate::qty!(2 kg*m/s^2);
+        let torque: Torque = crate::qty!(5 N*m);
+        let density: Density = crate::qty!(1025 kg/m^3);
+        let spin: AngularVelocity = crate::qty!(60 rpm);
+        let heading: Angle = crate::qty!(-90 deg);
+        let flow: Quantity<f64, 0, 3, -1, 0, 0, 0, 0> = crate::qty!(6 L/min);
+        let pressure: Pressure = crate::qty!(1.5 bar);
+        assert_eq!((*force.value(), *torque.value(), *density.value()), (2.0, 5.0, 1025.0));
+        assert!((spin.value() - TAU).abs() < 1e-12);
+        assert!((heading.value() + TAU / 4.0).abs() < 1e-12);
+        assert!((flow.value() - 1e-4).abs() < 1e-15);
+        assert_eq!(*pressure.value(), 150_000.0);
+        assert_eq!(symbols::s.powi(-2).dimension, [0, 0, -2, 0, 0, 0, 0]);
+        assert_eq!(symbols::Hz.mul(symbols::s), Unit::new(1.0, [0; 7]));
+    }
+
+    #[test]
+    fn test_angle_literals() {
+        const QUARTER: Angle = crate::angle!(0.25 tau);
+        assert_eq!(*QUARTER.value(), TAU / 4.0);
+        assert_eq!(crate::angle!(90 deg), QUARTER);
+        assert_eq!(crate::angle!(-1.5 rad), Angle::from_radians(-1.5));
+        assert!((Angle::from_degrees(180.0).value() - PI).abs() < 1e-15);
+        assert!((crate::angle!(45 deg).value() - units::degrees(45.0).value()).abs() < 1e-15);
+    }
+
     #[test]
     fn test_basic_units() {
         let length = units::meters(5.0);
@@ -701,4 +1178,30 @@ mod tests {
         let quarter_circle = 90.0.degrees();
         assert!((quarter_circle.value() - TAU / 4.0).abs() < 1e-10);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_dimension_display() {
+        assert_eq!(Power::<f64>::dimension().to_string(), "kg·m²·s⁻³ (power)");
+        assert_eq!(Pressure::<f64>::dimension().to_string(), "kg·m⁻¹·s⁻² (pressure)");
+        assert_eq!(Angle::<f64>::dimension().to_string(), "1 (dimensionless)");
+        assert_eq!(DimensionExponents([0, 1, -3, 0, 0, 0, 0]).to_string(), "m·s⁻³");
+        assert_eq!(superscript(-12), "⁻¹²");
+        assert_eq!(VelocityDim::exponents(), Velocity::<f64>::dimension());
+    }
+
+    #[test]
+    fn test_ratio() {
+        let efficiency = Ratio::new(0.85).unwrap();
+        assert_eq!(Ratio::new(85.0), Err(RatioError(85.0)));
+        assert_eq!(Ratio::from_percent(85.0).unwrap(), efficiency);
+        assert!(Ratio::new(f64::NAN).is_err());
+        assert_eq!(Ratio::saturating(1.3), Ratio::ONE);
+        assert!((efficiency.complement().value() - 0.15).abs() < 1e-12);
+        assert!(((efficiency * efficiency).value() - 0.7225).abs() < 1e-12);
+        assert!((efficiency.of(Power::new(200.0)).value() - 170.0).abs() < 1e-12);
+        assert_eq!(format!("{:.1}", efficiency), "85.0 %");
+
+        assert_eq!(serde_json::to_string(&efficiency).unwrap(), "0.85");
+        assert!(serde_json::from_str::<Ratio>("1.5").is_err());
+    }
+}
@@ -0,0 +1,279 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Filters, differentiators and integrators for typed signals
+//!
+//! Every block keeps the dimension of its signal: a low-pass on a [`Length`]
+//! returns a `Length`, a differentiator of a `Length` returns a [`Velocity`],
+//! and integrating a `Velocity` gives back a `Length`. The pairs are declared
+//! by [`TimeDerivative`].
+//!
+//! [`LowPass`], [`Differentiator`] and [`Integrator`] take the step `dt` on
+//! every sample and so tolerate irregular sampling. [`Biquad`] second-order
+//! sections (low-pass and notch) are designed for a fixed sample rate.
+
+use std::marker::PhantomData;
+
+use crate::si_units::{
+    Acceleration, Angle, AngularVelocity, Energy, Frequency, Length, Measure, Power, Quantity, Time, Velocity, TAU,
+};
+
+/// Quantity whose rate of change has a known type
+pub trait TimeDerivative: Measure {
+    type Rate: Measure;
+}
+
+macro_rules! impl_time_derivative {
+    ($($quantity:ty => $rate:ty),* $(,)?) => {
+        $(impl TimeDerivative for $quantity {
+            type Rate = $rate;
+        })*
+    };
+}
+
+/// Angular acceleration in rad/s²
+pub type AngularAcceleration<T = f64> = Quantity<T, 0, 0, -2, 0, 0, 0, 0>;
+
+/// Jerk in m/s³
+pub type Jerk<T = f64> = Quantity<T, 0, 1, -3, 0, 0, 0, 0>;
+
+/// Angular jerk in rad/s³
+pub type AngularJerk<T = f64> = Quantity<T, 0, 0, -3, 0, 0, 0, 0>;
+
+impl_time_derivative! {
+    Length => Velocity,
+    Velocity => Acceleration,
+    Acceleration => Jerk,
+    Angle => AngularVelocity,
+    AngularVelocity => AngularAcceleration,
+    AngularAcceleration => AngularJerk,
+    Energy => Power,
+}
+
+/// First-order low-pass `1 / (τs + 1)`, discretized exactly for each step
+#[derive(Debug, Clone, PartialEq)]
+pub struct LowPass<Q> {
+    tau: f64,
+    state: Option<Q>,
+}
+
+impl<Q: Measure> LowPass<Q> {
+    pub fn new(time_constant: Time) -> Self {
+        Self { tau: *time_constant.value(), state: None }
+    }
+
+    /// Low-pass with a −3 dB point at `cutoff`
+    pub fn from_cutoff(cutoff: Frequency) -> Self {
+        Self::new(Time::new(1.0 / (TAU * cutoff.value())))
+    }
+
+    /// Filtered value; the first sample passes through unchanged
+    pub fn update(&mut self, input: Q, dt: Time) -> Q {
+        let output = match self.state {
+            None => input,
+            Some(state) => {
+                let alpha = 1.0 - (-dt.value() / self.tau).exp();
+                Q::from_raw(state.raw() + alpha * (input.raw() - state.raw()))
+            }
+        };
+        self.state = Some(output);
+        output
+    }
+
+    pub fn value(&self) -> Option<Q> {
+        self.state
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Rate of change filtered by `s / (τs + 1)`, so noise is not amplified without bound
+#[derive(Debug, Clone, PartialEq)]
+pub struct Differentiator<Q: TimeDerivative> {
+    tau: f64,
+    previous: Option<f64>,
+    rate: f64,
+    _quantity: PhantomData<Q>,
+}
+
+impl<Q: TimeDerivative> Differentiator<Q> {
+    /// Filter time constant; zero gives the plain backward difference
+    pub fn new(time_constant: Time) -> Self {
+        Self { tau: time_constant.value().max(0.0), previous: None, rate: 0.0, _quantity: PhantomData }
+    }
+
+    /// Rate estimate, zero until two samples have arrived. Non-positive steps
+    /// return the previous estimate.
+    pub fn update(&mut self, input: Q, dt: Time) -> Q::Rate {
+        let dt = *dt.value();
+        if dt > 0.0 {
+            if let Some(previous) = self.previous {
+                self.rate = (self.tau * self.rate + (input.raw() - previous)) / (self.tau + dt);
+            }
+            self.previous = Some(input.raw());
+        }
+        Q::Rate::from_raw(self.rate)
+    }
+
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.rate = 0.0;
+    }
+}
+
+/// Trapezoidal integral of a rate back into its quantity
+#[derive(Debug, Clone, PartialEq)]
+pub struct Integrator<Q: TimeDerivative> {
+    total: f64,
+    previous: Option<f64>,
+    _quantity: PhantomData<Q>,
+}
+
+impl<Q: TimeDerivative> Integrator<Q> {
+    pub fn new(initial: Q) -> Self {
+        Self { total: initial.raw(), previous: None, _quantity: PhantomData }
+    }
+
+    /// Integral so far; the first sample only sets the starting rate
+    pub fn update(&mut self, rate: Q::Rate, dt: Time) -> Q {
+        if let Some(previous) = self.previous {
+            self.total += 0.5 * (previous + rate.raw()) * dt.value();
+        }
+        self.previous = Some(rate.raw());
+        Q::from_raw(self.total)
+    }
+
+    pub fn value(&self) -> Q {
+        Q::from_raw(self.total)
+    }
+}
+
+/// Second-order section at a fixed sample rate (bilinear transform designs)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Biquad<Q> {
+    /// Feed-forward b0..b2 and feedback a1, a2, normalized by a0
+    b: [f64; 3],
+    a: [f64; 2],
+    state: Option<[f64; 2]>,
+    _quantity: PhantomData<Q>,
+}
+
+impl<Q: Measure> Biquad<Q> {
+    fn design(center: Frequency, quality: f64, sample_rate: Frequency, numerator: impl Fn(f64) -> [f64; 3]) -> Self {
+        let nyquist = 0.5 * sample_rate.value();
+        assert!(*center.value() > 0.0 && *center.value() < nyquist, "filter frequency must lie below Nyquist");
+        let w0 = TAU * center.value() / sample_rate.value();
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * quality);
+        let a0 = 1.0 + alpha;
+        Self {
+            b: numerator(cos).map(|b| b / a0),
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            state: None,
+            _quantity: PhantomData,
+        }
+    }
+
+    /// Second-order low-pass; `quality` 1/√2 gives the Butterworth response
+    pub fn low_pass(cutoff: Frequency, quality: f64, sample_rate: Frequency) -> Self {
+        Self::design(cutoff, quality, sample_rate, |cos| [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0])
+    }
+
+    /// Notch removing `center`; its width is `center / quality`
+    pub fn notch(center: Frequency, quality: f64, sample_rate: Frequency) -> Self {
+        Self::design(center, quality, sample_rate, |cos| [1.0, -2.0 * cos, 1.0])
+    }
+
+    /// Gain at zero frequency
+    pub fn dc_gain(&self) -> f64 {
+        self.b.iter().sum::<f64>() / (1.0 + self.a[0] + self.a[1])
+    }
+
+    /// Filter one sample. The first sample primes the state as if the input had
+    /// always held that value, avoiding a start-up transient.
+    pub fn update(&mut self, input: Q) -> Q {
+        let [b0, b1, b2] = self.b;
+        let [a1, a2] = self.a;
+        let x = input.raw();
+        let [z1, z2] = *self.state.get_or_insert_with(|| {
+            let y = self.b.iter().sum::<f64>() / (1.0 + a1 + a2) * x;
+            let z2 = b2 * x - a2 * y;
+            [b1 * x - a1 * y + z2, z2]
+        });
+        let y = b0 * x + z1;
+        self.state = Some([b1 * x - a1 * y + z2, b2 * x - a2 * y]);
+        Q::from_raw(y)
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_pass_step() {
+        let mut filter = LowPass::<Length>::new(Time::new(0.5));
+        filter.update(Length::new(0.0), Time::new(0.0));
+        // Irregular steps summing to one time constant
+        let mut output = Length::new(0.0);
+        for dt in [0.1, 0.25, 0.15] {
+            output = filter.update(Length::new(1.0), Time::new(dt));
+        }
+        assert!((output.value() - (1.0 - (-1.0f64).exp())).abs() < 1e-12);
+
+        let filter = LowPass::<Length>::from_cutoff(Frequency::new(1.0 / TAU));
+        assert!((filter.tau - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_differentiate_and_integrate() {
+        let dt = Time::new(0.01);
+        let mut differentiator = Differentiator::<Length>::new(Time::new(0.05));
+        let mut integrator = Integrator::<Length>::new(Length::new(1.0));
+        let mut rate = Velocity::new(0.0);
+        let mut position = Length::new(0.0);
+        for step in 0..200 {
+            let t = step as f64 * 0.01;
+            rate = differentiator.update(Length::new(2.0 * t), dt);
+            position = integrator.update(Velocity::new(2.0 * t), dt);
+        }
+        // Ramp slope is recovered once the filter settles
+        assert!((rate.value() - 2.0).abs() < 1e-6);
+        // 1 m plus t² at t = 1.99 s, exact for the trapezoid rule
+        assert!((position.value() - (1.0 + 1.99f64.powi(2))).abs() < 1e-9);
+        assert_eq!(differentiator.update(Length::new(100.0), Time::new(0.0)), rate);
+    }
+
+    #[test]
+    fn test_biquads() {
+        let rate = Frequency::new(100.0);
+        let mut low_pass = Biquad::<Angle>::low_pass(Frequency::new(5.0), 1.0 / 2f64.sqrt(), rate);
+        assert!((low_pass.dc_gain() - 1.0).abs() < 1e-12);
+        // Primed with the first sample, a constant input passes untouched
+        assert!((low_pass.update(Angle::new(0.3)).value() - 0.3).abs() < 1e-12);
+        assert!((low_pass.update(Angle::new(0.3)).value() - 0.3).abs() < 1e-12);
+
+        // Wave-induced 2 Hz oscillation on a depth signal
+        let amplitude = |frequency: f64| {
+            let mut notch = Biquad::<Length>::notch(Frequency::new(2.0), 2.0, rate);
+            let mut peak = 0.0f64;
+            for n in 0..2000 {
+                let output = notch.update(Length::new((TAU * frequency * n as f64 / 100.0).sin()));
+                if n >= 1500 {
+                    peak = peak.max(output.value().abs());
+                }
+            }
+            peak
+        };
+        assert!(amplitude(2.0) < 1e-3);
+        assert!(amplitude(10.0) > 0.9);
+    }
+}
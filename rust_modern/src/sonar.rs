@@ -0,0 +1,324 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Sonar beams and acoustic range readings
+//!
+//! A [`Reading`] is a range along a bearing and elevation in the sensor frame,
+//! whose x axis is the beam axis and z axis points up. The echo may come from
+//! anywhere inside the beam, so the angular uncertainty of each reading is that
+//! of a uniform distribution across the beam width, `width / √12`, added to the
+//! range noise. [`Sonar`] carries the beam geometry and the mount on the
+//! vehicle, and turns readings into uncertain points or conformal points in the
+//! vehicle frame.
+//!
+//! Echo travel times become ranges through the mean sound speed of the
+//! [`WaterColumn`] along the straight ray, which refraction bends only slightly
+//! over the short ranges of vehicle sonars.
+//!
+//! [`EchoVoxels`] aggregates readings taken from known vehicle poses into a
+//! sparse voxel map of echo and pass-through counts.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use crate::linalg::{self, Matrix3, Vector3};
+use crate::motor::Motor;
+use crate::primitives::Point;
+use crate::si_units::{Angle, Length, Time, Velocity};
+use crate::uncertainty::{UncertainPoint, UncertainPose};
+use crate::water_column::WaterColumn;
+
+/// Speed of sound used when no water column is known (m/s)
+pub const NOMINAL_SOUND_SPEED: f64 = 1500.0;
+
+/// Range along a bearing (about z, positive to the left) and an elevation
+/// (positive up) in the sensor frame
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Reading {
+    pub range: Length,
+    pub bearing: Angle,
+    pub elevation: Angle,
+}
+
+impl Reading {
+    pub fn new(range: Length, bearing: Angle, elevation: Angle) -> Self {
+        Self { range, bearing, elevation }
+    }
+
+    /// Reading from the two-way travel time of an echo
+    pub fn from_echo(travel_time: Time, sound_speed: Velocity, bearing: Angle, elevation: Angle) -> Self {
+        Self::new(Length::new(0.5 * travel_time.value() * sound_speed.value()), bearing, elevation)
+    }
+
+    /// Unit vector along the reading in the sensor frame
+    pub fn direction(&self) -> Vector3 {
+        let (b, e) = (*self.bearing.value(), *self.elevation.value());
+        [e.cos() * b.cos(), e.cos() * b.sin(), e.sin()]
+    }
+
+    /// Echo position in the sensor frame
+    pub fn position(&self) -> Vector3 {
+        linalg::scale(self.direction(), *self.range.value())
+    }
+}
+
+/// Single-beam or scanning sonar mounted on a vehicle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Sonar {
+    /// Sensor frame in the vehicle frame
+    pub mount: Motor,
+    /// Beam width across bearings
+    pub horizontal_width: Angle,
+    /// Beam width across elevations
+    pub vertical_width: Angle,
+    pub min_range: Length,
+    /// Readings at or beyond this range mean no echo
+    pub max_range: Length,
+    /// Standard deviation of the range
+    pub range_sigma: Length,
+}
+
+impl Sonar {
+    pub fn new(mount: Motor, horizontal_width: Angle, vertical_width: Angle, max_range: Length) -> Self {
+        Self {
+            mount,
+            horizontal_width,
+            vertical_width,
+            min_range: Length::new(0.0),
+            max_range,
+            range_sigma: Length::new(0.05),
+        }
+    }
+
+    /// Whether `reading` is an echo rather than a miss or blanking
+    pub fn accepts(&self, reading: &Reading) -> bool {
+        reading.range >= self.min_range && reading.range < self.max_range
+    }
+
+    /// Echo position and its uncertainty in the sensor frame
+    pub fn sensor_point(&self, reading: &Reading) -> UncertainPoint {
+        let r = *reading.range.value();
+        let (b, e) = (*reading.bearing.value(), *reading.elevation.value());
+        let (sb, cb, se, ce) = (b.sin(), b.cos(), e.sin(), e.cos());
+        // Partial derivatives of the position with respect to range, bearing and elevation
+        let columns = [
+            ([ce * cb, ce * sb, se], *self.range_sigma.value()),
+            ([-r * ce * sb, r * ce * cb, 0.0], self.horizontal_width.value() / 12f64.sqrt()),
+            ([-r * se * cb, -r * se * sb, r * ce], self.vertical_width.value() / 12f64.sqrt()),
+        ];
+        let mut covariance: Matrix3 = [[0.0; 3]; 3];
+        for (column, sigma) in columns {
+            for (i, row) in covariance.iter_mut().enumerate() {
+                for (j, value) in row.iter_mut().enumerate() {
+                    *value += sigma * sigma * column[i] * column[j];
+                }
+            }
+        }
+        UncertainPoint::new(reading.position(), covariance)
+    }
+
+    /// Echo position and its uncertainty in the vehicle frame; `None` when the
+    /// reading is not an echo
+    pub fn vehicle_point(&self, reading: &Reading) -> Option<UncertainPoint> {
+        self.accepts(reading)
+            .then(|| UncertainPose::certain(self.mount).apply_point(&self.sensor_point(reading)))
+    }
+
+    /// Reading from the two-way travel time of an echo, with the vehicle at
+    /// `vehicle` in the world frame (z up, surface at z = 0) and the sound
+    /// speed averaged along the ray through `water`
+    pub fn reading_from_echo(&self, water: &WaterColumn, vehicle: &Motor, travel_time: Time, bearing: Angle, elevation: Angle) -> Reading {
+        let sensor = *vehicle * self.mount;
+        let depth = -sensor.apply_point([0.0; 3])[2];
+        let unit = Reading::new(Length::new(1.0), bearing, elevation);
+        let descent = -sensor.apply_direction(unit.direction())[2];
+
+        // The end depth depends on the range, so refine both a few times
+        let mut reading = Reading::from_echo(travel_time, water.sound_speed(Length::new(depth)), bearing, elevation);
+        for _ in 0..4 {
+            let end = depth + descent * reading.range.value();
+            let speed = water.mean_sound_speed(Length::new(depth), Length::new(end));
+            reading = Reading::from_echo(travel_time, speed, bearing, elevation);
+        }
+        reading
+    }
+
+    /// Echo as a conformal point in the vehicle frame
+    pub fn cga_point(&self, reading: &Reading) -> Option<Point> {
+        self.accepts(reading).then(|| Point::new(reading.position()).transformed(&self.mount))
+    }
+}
+
+/// Echo and pass-through counts of one voxel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoxelCounts {
+    pub echoes: u32,
+    pub passes: u32,
+}
+
+impl VoxelCounts {
+    /// Fraction of the beams through this voxel that returned from it
+    pub fn occupancy(&self) -> f64 {
+        self.echoes as f64 / (self.echoes + self.passes).max(1) as f64
+    }
+}
+
+/// Sparse voxel map of sonar evidence in the world frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EchoVoxels {
+    resolution: f64,
+    /// Ordered so iteration and serialization are reproducible
+    voxels: BTreeMap<[i64; 3], VoxelCounts>,
+}
+
+impl EchoVoxels {
+    /// `resolution` must be positive
+    pub fn new(resolution: Length) -> Self {
+        Self { resolution: *resolution.value(), voxels: BTreeMap::new() }
+    }
+
+    pub fn resolution(&self) -> Length {
+        Length::new(self.resolution)
+    }
+
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// Voxel containing `point`
+    pub fn key(&self, point: Vector3) -> [i64; 3] {
+        point.map(|x| (x / self.resolution).floor() as i64)
+    }
+
+    pub fn center(&self, key: [i64; 3]) -> Vector3 {
+        key.map(|k| (k as f64 + 0.5) * self.resolution)
+    }
+
+    pub fn counts(&self, point: Vector3) -> VoxelCounts {
+        self.voxels.get(&self.key(point)).copied().unwrap_or_default()
+    }
+
+    /// Add one reading taken with the vehicle at `vehicle`. Voxels along the
+    /// beam axis short of the echo count a pass; voxels on the arc the echo may
+    /// have come from, across the horizontal beam width, count an echo. A miss
+    /// counts passes up to the maximum range.
+    pub fn integrate(&mut self, sonar: &Sonar, vehicle: &Motor, reading: &Reading) {
+        let sensor = *vehicle * sonar.mount;
+        let origin = sensor.apply_point([0.0; 3]);
+        let echo = sonar.accepts(reading);
+        let range = if echo { *reading.range.value() } else { *sonar.max_range.value() };
+
+        let direction = sensor.apply_direction(reading.direction());
+        let mut passed = BTreeSet::new();
+        let steps = (2.0 * range / self.resolution).ceil() as usize;
+        for s in 0..steps {
+            let along = s as f64 * 0.5 * self.resolution;
+            if echo && along > range - self.resolution {
+                break;
+            }
+            passed.insert(self.key(linalg::add(origin, linalg::scale(direction, along))));
+        }
+
+        let mut echoed = BTreeSet::new();
+        if echo {
+            let half_width = 0.5 * sonar.horizontal_width.value();
+            let arc_steps = (2.0 * half_width * range / self.resolution).ceil().max(1.0) as usize;
+            for s in 0..=arc_steps {
+                let bearing = reading.bearing.value() - half_width + 2.0 * half_width * s as f64 / arc_steps as f64;
+                let ray = Reading::new(reading.range, Angle::new(bearing), reading.elevation);
+                echoed.insert(self.key(sensor.apply_point(ray.position())));
+            }
+        }
+        for key in passed.difference(&echoed) {
+            self.voxels.entry(*key).or_default().passes += 1;
+        }
+        for key in echoed {
+            self.voxels.entry(key).or_default().echoes += 1;
+        }
+    }
+
+    /// Centers of the voxels whose occupancy is at least `threshold`
+    pub fn occupied(&self, threshold: f64) -> Vec<Vector3> {
+        self.voxels
+            .iter()
+            .filter(|(_, counts)| counts.echoes > 0 && counts.occupancy() >= threshold)
+            .map(|(key, _)| self.center(*key))
+            .collect()
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::TAU;
+
+    fn forward_sonar() -> Sonar {
+        // Looking forward from 1 m ahead of the vehicle origin, 6° by 12° beam
+        let mount = Motor::from_translation([1.0, 0.0, 0.0]);
+        Sonar::new(mount, Angle::new(TAU / 60.0), Angle::new(TAU / 30.0), Length::new(50.0))
+    }
+
+    #[test]
+    fn test_reading_conversions() {
+        let echo = Reading::from_echo(Time::new(0.02), Velocity::new(NOMINAL_SOUND_SPEED), Angle::new(TAU / 4.0), Angle::new(0.0));
+        assert!((echo.range.value() - 15.0).abs() < 1e-12);
+        let p = echo.position();
+        assert!(p[0].abs() < 1e-12 && (p[1] - 15.0).abs() < 1e-12);
+
+        let sonar = forward_sonar();
+        let ahead = Reading::new(Length::new(10.0), Angle::new(0.0), Angle::new(0.0));
+        let point = sonar.vehicle_point(&ahead).unwrap();
+        assert!(linalg::norm(linalg::sub(point.mean, [11.0, 0.0, 0.0])) < 1e-12);
+        // Range noise along the beam, beam width across it
+        let lateral = 10.0 * (TAU / 60.0) / 12f64.sqrt();
+        assert!((point.covariance[0][0] - 0.05 * 0.05).abs() < 1e-12);
+        assert!((point.covariance[1][1] - lateral * lateral).abs() < 1e-12);
+        assert!(point.covariance[2][2] > point.covariance[1][1]);
+        assert!(point.covariance[0][1].abs() < 1e-12);
+
+        let cga = sonar.cga_point(&ahead).unwrap();
+        assert_eq!(cga.blades(), [1.0, 11.0, 0.0, 0.0, 60.5]);
+
+        // Looking straight down through a uniform column uses its sound speed
+        let water = WaterColumn::default();
+        let down = Sonar::new(Motor::identity(), Angle::new(0.1), Angle::new(0.1), Length::new(100.0));
+        let vehicle = Motor::from_translation([0.0, 0.0, -20.0]);
+        let elevation = Angle::new(-TAU / 4.0);
+        let sounding = down.reading_from_echo(&water, &vehicle, Time::new(0.04), Angle::new(0.0), elevation);
+        let speed = *water.mean_sound_speed(Length::new(20.0), Length::new(20.0 + sounding.range.value())).value();
+        assert!((sounding.range.value() - 0.02 * speed).abs() < 1e-9);
+        assert!((speed - water.sound_speed(Length::new(20.0)).value()).abs() < 1.0);
+
+        let miss = Reading::new(Length::new(50.0), Angle::new(0.0), Angle::new(0.0));
+        assert!(sonar.vehicle_point(&miss).is_none() && sonar.cga_point(&miss).is_none());
+    }
+
+    #[test]
+    fn test_echo_voxels() {
+        let sonar = forward_sonar();
+        let mut map = EchoVoxels::new(Length::new(0.5));
+        // Sweep across a wall at x = 11 from the origin
+        for k in -10..=10 {
+            let bearing = Angle::new(0.02 * k as f64);
+            let range = Length::new(10.0 / bearing.value().cos());
+            map.integrate(&sonar, &Motor::identity(), &Reading::new(range, bearing, Angle::new(0.0)));
+        }
+        let occupied = map.occupied(0.5);
+        assert!(!occupied.is_empty());
+        assert!(occupied.iter().all(|c| (c[0] - 11.0).abs() <= 0.5), "{:?}", occupied);
+
+        let between = map.counts([6.0, 0.1, 0.1]);
+        assert!(between.passes > 0 && between.echoes == 0);
+        assert_eq!(map.counts([30.0, 0.0, 0.0]), VoxelCounts::default());
+
+        // A miss clears the whole beam
+        map.integrate(&sonar, &Motor::identity(), &Reading::new(Length::new(50.0), Angle::new(TAU / 4.0), Angle::new(0.0)));
+        assert!(map.counts([1.1, 40.0, 0.1]).passes == 1);
+    }
+}
@@ -0,0 +1,586 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Polynomials and B-splines in time with typed values
+//!
+//! A [`Polynomial<Q>`] is a function of time whose value is a `Q`; coefficient
+//! `k` is stored in SI units of `Q` per sᵏ, so the coefficients of a position
+//! polynomial are a position, a velocity, half an acceleration and so on. Its
+//! [`Polynomial::derivative`] is a `Polynomial<Q::Rate>` by [`TimeDerivative`],
+//! so differentiating a `Polynomial<Length>` twice yields accelerations and
+//! mixing up the orders does not compile. [`Polynomial::cubic`] and
+//! [`Polynomial::quintic`] meet position, velocity and (for the quintic)
+//! acceleration at both ends of a segment.
+//!
+//! A [`BSpline<Q, N>`] traces `N` coordinates of `Q` over a knot vector in
+//! seconds, by de Boor's algorithm. Its derivative is again a B-spline, one
+//! degree lower, in `Q::Rate`. Arc length is the integral of the speed, the
+//! norm of the `N` rates, by Gauss-Legendre quadrature on every knot span;
+//! [`BSpline::time_at_length`] inverts it to walk a path at a given spacing.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::si_units::{Measure, Time};
+use crate::signal::TimeDerivative;
+
+/// Quadrature pieces per knot span of [`BSpline::length_between`]
+const SPAN_PIECES: usize = 4;
+
+/// Five-point Gauss-Legendre nodes on `[-1, 1]` and their weights
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+/// Curve definitions that do not describe a function of time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplineError {
+    /// Segments must last a positive time
+    NonPositiveDuration(f64),
+    /// A B-spline of degree `p` needs at least `p + 1` control points
+    TooFewControlPoints { needed: usize, given: usize },
+    /// A B-spline needs as many knots as control points plus degree plus one
+    KnotCount { expected: usize, given: usize },
+    /// Knots must not decrease
+    DecreasingKnots,
+}
+
+impl fmt::Display for SplineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplineError::NonPositiveDuration(duration) => write!(f, "duration {} s is not positive", duration),
+            SplineError::TooFewControlPoints { needed, given } => {
+                write!(f, "spline needs {} control points, got {}", needed, given)
+            }
+            SplineError::KnotCount { expected, given } => write!(f, "spline needs {} knots, got {}", expected, given),
+            SplineError::DecreasingKnots => write!(f, "knots are not in non-decreasing order"),
+        }
+    }
+}
+
+impl std::error::Error for SplineError {}
+
+fn check_duration(duration: Time) -> Result<f64, SplineError> {
+    let duration = *duration.value();
+    if duration > 0.0 && duration.is_finite() {
+        Ok(duration)
+    } else {
+        Err(SplineError::NonPositiveDuration(duration))
+    }
+}
+
+fn horner(coefficients: &[f64], t: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |sum, c| sum * t + c)
+}
+
+fn differentiate(coefficients: &[f64]) -> Vec<f64> {
+    coefficients.iter().enumerate().skip(1).map(|(k, c)| k as f64 * c).collect()
+}
+
+/// Real roots in `[a, b]`, isolated between the roots of the derivative
+/// where the polynomial is monotone, then bisected
+fn roots_between(coefficients: &[f64], a: f64, b: f64) -> Vec<f64> {
+    let degree = coefficients.iter().rposition(|c| *c != 0.0).unwrap_or(0);
+    if degree == 0 {
+        return Vec::new();
+    }
+    let mut breaks = vec![a];
+    breaks.extend(roots_between(&differentiate(&coefficients[..=degree]), a, b));
+    breaks.push(b);
+
+    let mut roots = Vec::new();
+    for pair in breaks.windows(2) {
+        let (mut low, mut high) = (pair[0], pair[1]);
+        let (f_low, f_high) = (horner(coefficients, low), horner(coefficients, high));
+        if f_low == 0.0 {
+            roots.push(low);
+            continue;
+        }
+        if f_low.signum() == f_high.signum() {
+            continue;
+        }
+        for _ in 0..100 {
+            let middle = 0.5 * (low + high);
+            if middle <= low || middle >= high {
+                break;
+            }
+            if horner(coefficients, middle).signum() == f_low.signum() {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        roots.push(0.5 * (low + high));
+    }
+    roots
+}
+
+/// Position, rate and rate of rate at one end of a segment
+#[derive(Clone, Copy)]
+pub struct BoundaryState<Q: TimeDerivative>
+where
+    Q::Rate: TimeDerivative,
+{
+    pub value: Q,
+    pub rate: Q::Rate,
+    pub second_rate: <Q::Rate as TimeDerivative>::Rate,
+}
+
+impl<Q: TimeDerivative> BoundaryState<Q>
+where
+    Q::Rate: TimeDerivative,
+{
+    pub fn new(value: Q, rate: Q::Rate, second_rate: <Q::Rate as TimeDerivative>::Rate) -> Self {
+        Self { value, rate, second_rate }
+    }
+
+    /// At rest at `value`
+    pub fn rest(value: Q) -> Self {
+        Self::new(value, Measure::from_raw(0.0), Measure::from_raw(0.0))
+    }
+}
+
+// The rates are only known to be `Measure`, so compare and print their SI values
+impl<Q: TimeDerivative> PartialEq for BoundaryState<Q>
+where
+    Q::Rate: TimeDerivative,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value.raw() == other.value.raw()
+            && self.rate.raw() == other.rate.raw()
+            && self.second_rate.raw() == other.second_rate.raw()
+    }
+}
+
+impl<Q: TimeDerivative> fmt::Debug for BoundaryState<Q>
+where
+    Q::Rate: TimeDerivative,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundaryState")
+            .field("value", &self.value.raw())
+            .field("rate", &self.rate.raw())
+            .field("second_rate", &self.second_rate.raw())
+            .finish()
+    }
+}
+
+/// Polynomial in the time since the start of a segment, valued in `Q`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Polynomial<Q> {
+    /// Coefficient `k` in SI units of `Q` per sᵏ
+    coefficients: Vec<f64>,
+    duration: f64,
+    #[serde(skip)]
+    _quantity: PhantomData<fn() -> Q>,
+}
+
+impl<Q: Measure> Polynomial<Q> {
+    /// Polynomial from SI coefficients, constant term first, over `[0, duration]`
+    pub fn from_coefficients(coefficients: Vec<f64>, duration: Time) -> Result<Self, SplineError> {
+        Ok(Self { coefficients, duration: check_duration(duration)?, _quantity: PhantomData })
+    }
+
+    pub fn constant(value: Q, duration: Time) -> Result<Self, SplineError> {
+        Self::from_coefficients(vec![value.raw()], duration)
+    }
+
+    /// Coefficients in SI units, constant term first
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficients.iter().rposition(|c| *c != 0.0).unwrap_or(0)
+    }
+
+    pub fn duration(&self) -> Time {
+        Time::new(self.duration)
+    }
+
+    /// Value `t` after the start; extrapolated outside `[0, duration]`
+    pub fn value(&self, t: Time) -> Q {
+        Q::from_raw(horner(&self.coefficients, *t.value()))
+    }
+
+    /// Total variation between two times: the distance travelled, counting
+    /// every reversal, for a position polynomial
+    pub fn distance(&self, from: Time, to: Time) -> Q {
+        let (a, b) = (from.value().min(*to.value()), from.value().max(*to.value()));
+        let mut breaks = vec![a];
+        breaks.extend(roots_between(&differentiate(&self.coefficients), a, b));
+        breaks.push(b);
+        let values: Vec<f64> = breaks.iter().map(|t| horner(&self.coefficients, *t)).collect();
+        Q::from_raw(values.windows(2).map(|pair| (pair[1] - pair[0]).abs()).sum())
+    }
+}
+
+impl<Q: TimeDerivative> Polynomial<Q> {
+    pub fn derivative(&self) -> Polynomial<Q::Rate> {
+        Polynomial { coefficients: differentiate(&self.coefficients), duration: self.duration, _quantity: PhantomData }
+    }
+
+    /// Rate of change `t` after the start
+    pub fn rate(&self, t: Time) -> Q::Rate {
+        Measure::from_raw(horner(&differentiate(&self.coefficients), *t.value()))
+    }
+
+    /// Cubic from `start` to `end` with the given rates at both ends
+    pub fn cubic(start: Q, start_rate: Q::Rate, end: Q, end_rate: Q::Rate, duration: Time) -> Result<Self, SplineError> {
+        let t = check_duration(duration)?;
+        let (v0, v1) = (start_rate.raw(), end_rate.raw());
+        let h = end.raw() - start.raw();
+        let coefficients = vec![
+            start.raw(),
+            v0,
+            (3.0 * h - (2.0 * v0 + v1) * t) / (t * t),
+            (-2.0 * h + (v0 + v1) * t) / (t * t * t),
+        ];
+        Self::from_coefficients(coefficients, duration)
+    }
+}
+
+impl<Q: TimeDerivative> Polynomial<Q>
+where
+    Q::Rate: TimeDerivative,
+{
+    /// Rate of the rate of change `t` after the start
+    pub fn second_rate(&self, t: Time) -> <Q::Rate as TimeDerivative>::Rate {
+        Measure::from_raw(horner(&differentiate(&differentiate(&self.coefficients)), *t.value()))
+    }
+
+    /// Quintic meeting value, rate and rate of rate at both ends
+    pub fn quintic(start: &BoundaryState<Q>, end: &BoundaryState<Q>, duration: Time) -> Result<Self, SplineError> {
+        let t = check_duration(duration)?;
+        let (v0, v1) = (start.rate.raw(), end.rate.raw());
+        let (a0, a1) = (start.second_rate.raw(), end.second_rate.raw());
+        let h = end.value.raw() - start.value.raw();
+        let coefficients = vec![
+            start.value.raw(),
+            v0,
+            0.5 * a0,
+            (20.0 * h - (8.0 * v1 + 12.0 * v0) * t - (3.0 * a0 - a1) * t * t) / (2.0 * t.powi(3)),
+            (-30.0 * h + (14.0 * v1 + 16.0 * v0) * t + (3.0 * a0 - 2.0 * a1) * t * t) / (2.0 * t.powi(4)),
+            (12.0 * h - 6.0 * (v1 + v0) * t + (a1 - a0) * t * t) / (2.0 * t.powi(5)),
+        ];
+        Self::from_coefficients(coefficients, duration)
+    }
+}
+
+/// B-spline of `N` coordinates in `Q`, parametrized by time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct BSpline<Q, const N: usize> {
+    degree: usize,
+    /// In seconds, non-decreasing
+    knots: Vec<f64>,
+    /// In SI units of `Q`
+    #[serde(with = "control_points")]
+    control_points: Vec<[f64; N]>,
+    #[serde(skip)]
+    _quantity: PhantomData<fn() -> Q>,
+}
+
+/// Serde support for `Vec<[f64; N]>`, which serde only derives for fixed sizes
+mod control_points {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, const N: usize>(points: &[[f64; N]], serializer: S) -> Result<S::Ok, S::Error> {
+        points.iter().map(|point| point.to_vec()).collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<Vec<[f64; N]>, D::Error> {
+        Vec::<Vec<f64>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|point| {
+                let length = point.len();
+                point.try_into().map_err(|_| serde::de::Error::invalid_length(length, &"one value per coordinate"))
+            })
+            .collect()
+    }
+}
+
+impl<Q: Measure, const N: usize> BSpline<Q, N> {
+    /// Spline of `degree` over `knots`, which must number the control points
+    /// plus the degree plus one
+    pub fn new(degree: usize, knots: Vec<Time>, control_points: Vec<[Q; N]>) -> Result<Self, SplineError> {
+        if control_points.len() <= degree {
+            return Err(SplineError::TooFewControlPoints { needed: degree + 1, given: control_points.len() });
+        }
+        let expected = control_points.len() + degree + 1;
+        if knots.len() != expected {
+            return Err(SplineError::KnotCount { expected, given: knots.len() });
+        }
+        let knots: Vec<f64> = knots.iter().map(|knot| *knot.value()).collect();
+        if knots.windows(2).any(|pair| matches!(pair[0].partial_cmp(&pair[1]), None | Some(Ordering::Greater))) {
+            return Err(SplineError::DecreasingKnots);
+        }
+        let n = control_points.len();
+        check_duration(Time::new(knots[n] - knots[degree]))?;
+        Ok(Self {
+            degree,
+            knots,
+            control_points: control_points.iter().map(|point| point.map(Measure::raw)).collect(),
+            _quantity: PhantomData,
+        })
+    }
+
+    /// Spline through its first and last control points over `[0, duration]`,
+    /// with uniformly spaced interior knots
+    pub fn clamped(degree: usize, control_points: Vec<[Q; N]>, duration: Time) -> Result<Self, SplineError> {
+        let t = check_duration(duration)?;
+        if control_points.len() <= degree {
+            return Err(SplineError::TooFewControlPoints { needed: degree + 1, given: control_points.len() });
+        }
+        let spans = control_points.len() - degree;
+        let knots = (0..control_points.len() + degree + 1)
+            .map(|i| Time::new(t * (i.saturating_sub(degree).min(spans)) as f64 / spans as f64))
+            .collect();
+        Self::new(degree, knots, control_points)
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn knots(&self) -> Vec<Time> {
+        self.knots.iter().copied().map(Time::new).collect()
+    }
+
+    pub fn control_points(&self) -> Vec<[Q; N]> {
+        self.control_points.iter().map(|point| point.map(Q::from_raw)).collect()
+    }
+
+    /// Times the spline is defined over
+    pub fn domain(&self) -> (Time, Time) {
+        (Time::new(self.knots[self.degree]), Time::new(self.knots[self.control_points.len()]))
+    }
+
+    fn clamp(&self, t: f64) -> f64 {
+        t.clamp(self.knots[self.degree], self.knots[self.control_points.len()])
+    }
+
+    /// Index `k` of the knot span `[knots[k], knots[k + 1])` holding `t`
+    fn span(&self, t: f64) -> usize {
+        let n = self.control_points.len();
+        (self.degree..n).rev().find(|&k| self.knots[k] <= t && self.knots[k] < self.knots[k + 1]).unwrap_or(self.degree)
+    }
+
+    fn evaluate(&self, t: f64) -> [f64; N] {
+        let t = self.clamp(t);
+        let p = self.degree;
+        let k = self.span(t);
+        let mut d: Vec<[f64; N]> = self.control_points[k - p..=k].to_vec();
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let i = j + k - p;
+                let denominator = self.knots[i + p + 1 - r] - self.knots[i];
+                let alpha = if denominator > 0.0 { (t - self.knots[i]) / denominator } else { 0.0 };
+                d[j] = std::array::from_fn(|c| (1.0 - alpha) * d[j - 1][c] + alpha * d[j][c]);
+            }
+        }
+        d[p]
+    }
+
+    /// Value at `t`, clamped to the domain
+    pub fn value(&self, t: Time) -> [Q; N] {
+        self.evaluate(*t.value()).map(Q::from_raw)
+    }
+}
+
+impl<Q: TimeDerivative, const N: usize> BSpline<Q, N> {
+    /// Spline of the rates, one degree lower
+    pub fn derivative(&self) -> BSpline<Q::Rate, N> {
+        let p = self.degree;
+        if p == 0 {
+            return BSpline {
+                degree: 0,
+                knots: self.knots.clone(),
+                control_points: vec![[0.0; N]; self.control_points.len()],
+                _quantity: PhantomData,
+            };
+        }
+        let control_points = self
+            .control_points
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                let width = self.knots[i + p + 1] - self.knots[i + 1];
+                std::array::from_fn(|c| if width > 0.0 { p as f64 * (pair[1][c] - pair[0][c]) / width } else { 0.0 })
+            })
+            .collect();
+        BSpline { degree: p - 1, knots: self.knots[1..self.knots.len() - 1].to_vec(), control_points, _quantity: PhantomData }
+    }
+
+    /// Rates at `t`, clamped to the domain
+    pub fn rate(&self, t: Time) -> [Q::Rate; N] {
+        self.derivative().value(t)
+    }
+
+    /// Length of the path between two times, the integral of the speed, in `Q`
+    pub fn length_between(&self, from: Time, to: Time) -> Q {
+        let (a, b) = (self.clamp(*from.value()), self.clamp(*to.value()));
+        let (a, b, sign) = if a <= b { (a, b, 1.0) } else { (b, a, -1.0) };
+        let derivative = self.derivative();
+        let speed = |t: f64| derivative.evaluate(t).iter().map(|r| r * r).sum::<f64>().sqrt();
+
+        let mut breaks = vec![a];
+        breaks.extend(self.knots.iter().copied().filter(|knot| a < *knot && *knot < b));
+        breaks.push(b);
+        breaks.dedup();
+        let mut length = 0.0;
+        for span in breaks.windows(2) {
+            let width = (span[1] - span[0]) / SPAN_PIECES as f64;
+            for piece in 0..SPAN_PIECES {
+                let middle = span[0] + (piece as f64 + 0.5) * width;
+                length += GAUSS_LEGENDRE.iter().map(|(x, w)| w * speed(middle + 0.5 * width * x)).sum::<f64>() * 0.5 * width;
+            }
+        }
+        Q::from_raw(sign * length)
+    }
+
+    /// Length of the whole path
+    pub fn length(&self) -> Q {
+        let (start, end) = self.domain();
+        self.length_between(start, end)
+    }
+
+    /// Time at which the path has covered `length` from its start, clamped to the domain
+    pub fn time_at_length(&self, length: Q) -> Time {
+        let (start, end) = self.domain();
+        let (mut low, mut high) = (*start.value(), *end.value());
+        if length.raw() <= 0.0 {
+            return start;
+        }
+        if length.raw() >= self.length().raw() {
+            return end;
+        }
+        for _ in 0..60 {
+            let middle = 0.5 * (low + high);
+            if self.length_between(start, Time::new(middle)).raw() < length.raw() {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Time::new(0.5 * (low + high))
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::{Acceleration, Angle, AngularVelocity, Length, Velocity};
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn test_quintic_meets_boundary_states() {
+        let start = BoundaryState::new(Length::new(1.0), Velocity::new(0.5), Acceleration::new(-0.2));
+        let end = BoundaryState::new(Length::new(4.0), Velocity::new(-0.3), Acceleration::new(0.1));
+        let duration = Time::new(2.5);
+        let quintic = Polynomial::quintic(&start, &end, duration).unwrap();
+        assert_eq!(quintic.degree(), 5);
+
+        for (t, state) in [(Time::new(0.0), &start), (duration, &end)] {
+            let value: Length = quintic.value(t);
+            let rate: Velocity = quintic.rate(t);
+            let second: Acceleration = quintic.second_rate(t);
+            assert!(close(*value.value(), *state.value.value(), 1e-12));
+            assert!(close(*rate.value(), *state.rate.value(), 1e-12));
+            assert!(close(*second.value(), *state.second_rate.value(), 1e-12));
+        }
+
+        // The derivative polynomial agrees with finite differences of the value
+        let velocity: Polynomial<Velocity> = quintic.derivative();
+        let h = 1e-6;
+        for t in [0.3, 1.1, 2.0] {
+            let numeric = (quintic.value(Time::new(t + h)).value() - quintic.value(Time::new(t - h)).value()) / (2.0 * h);
+            assert!(close(numeric, *velocity.value(Time::new(t)).value(), 1e-8));
+            assert_eq!(velocity.value(Time::new(t)), quintic.rate(Time::new(t)));
+        }
+
+        // Rest to rest, the quintic's peak rate is 15/8 of the mean
+        let heading = Polynomial::quintic(&BoundaryState::rest(Angle::new(0.0)), &BoundaryState::rest(Angle::new(1.0)), Time::new(2.0)).unwrap();
+        let peak: AngularVelocity = heading.rate(Time::new(1.0));
+        assert!(close(*peak.value(), 15.0 / 16.0, 1e-12));
+    }
+
+    #[test]
+    fn test_cubic_and_distance() {
+        let cubic = Polynomial::cubic(Length::new(0.0), Velocity::new(2.0), Length::new(0.0), Velocity::new(2.0), Time::new(3.0)).unwrap();
+        assert!(close(*cubic.value(Time::new(3.0)).value(), 0.0, 1e-12));
+        assert!(close(*cubic.rate(Time::new(3.0)).value(), 2.0, 1e-12));
+
+        // It goes out, comes back past the start and returns: the distance counts every leg
+        let samples: Vec<f64> = (0..=3000).map(|i| *cubic.value(Time::new(i as f64 * 1e-3)).value()).collect();
+        let travelled: f64 = samples.windows(2).map(|pair| (pair[1] - pair[0]).abs()).sum();
+        let distance = cubic.distance(Time::new(0.0), Time::new(3.0));
+        assert!(close(*distance.value(), travelled, 1e-6), "{:?} vs {}", distance, travelled);
+        assert_eq!(cubic.distance(Time::new(3.0), Time::new(0.0)), distance);
+
+        assert_eq!(Polynomial::constant(Length::new(1.0), Time::new(0.0)), Err(SplineError::NonPositiveDuration(0.0)));
+    }
+
+    #[test]
+    fn test_b_spline_value_rate_and_length() {
+        let points = [[0.0, 0.0], [1.0, 2.0], [3.0, 2.0], [4.0, 0.0], [6.0, 1.0]].map(|p| p.map(Length::new));
+        let spline = BSpline::clamped(3, points.to_vec(), Time::new(4.0)).unwrap();
+        assert_eq!(spline.domain(), (Time::new(0.0), Time::new(4.0)));
+        assert_eq!(spline.value(Time::new(0.0)), points[0]);
+        let end = spline.value(Time::new(4.0));
+        assert!(close(*end[0].value(), 6.0, 1e-12) && close(*end[1].value(), 1.0, 1e-12));
+
+        let velocity: BSpline<Velocity, 2> = spline.derivative();
+        assert_eq!(velocity.degree(), 2);
+        let h = 1e-6;
+        for t in [0.4, 1.0, 2.7, 3.9] {
+            let (ahead, behind) = (spline.value(Time::new(t + h)), spline.value(Time::new(t - h)));
+            let rate = spline.rate(Time::new(t));
+            for c in 0..2 {
+                assert!(close((ahead[c].value() - behind[c].value()) / (2.0 * h), *rate[c].value(), 1e-7));
+            }
+        }
+
+        // Collinear, evenly spaced control points make a straight path at constant speed
+        let line = BSpline::clamped(2, (0..5).map(|i| [Length::new(i as f64), Length::new(0.5 * i as f64)]).collect(), Time::new(2.0)).unwrap();
+        let straight = 4.0 * 1.25f64.sqrt();
+        assert!(close(*line.length().value(), straight, 1e-9));
+        let halfway = line.time_at_length(Length::new(0.5 * straight));
+        let middle = line.value(halfway);
+        assert!(close(*middle[0].value(), 2.0, 1e-9) && close(*middle[1].value(), 1.0, 1e-9));
+
+        // The curved spline's length matches a fine polyline
+        let polyline: f64 = (0..4000)
+            .map(|i| {
+                let (a, b) = (spline.value(Time::new(i as f64 * 1e-3)), spline.value(Time::new((i + 1) as f64 * 1e-3)));
+                (0..2).map(|c| (b[c].value() - a[c].value()).powi(2)).sum::<f64>().sqrt()
+            })
+            .sum();
+        assert!(close(*spline.length().value(), polyline, 1e-5));
+    }
+
+    #[test]
+    fn test_invalid_splines() {
+        let points = vec![[Length::new(0.0)], [Length::new(1.0)]];
+        assert_eq!(BSpline::clamped(2, points.clone(), Time::new(1.0)), Err(SplineError::TooFewControlPoints { needed: 3, given: 2 }));
+        let knots = [0.0, 0.0, 1.0].map(Time::new).to_vec();
+        assert_eq!(BSpline::new(1, knots, points.clone()), Err(SplineError::KnotCount { expected: 4, given: 3 }));
+        let knots = [0.0, 1.0, 0.5, 1.0].map(Time::new).to_vec();
+        assert_eq!(BSpline::new(1, knots, points.clone()), Err(SplineError::DecreasingKnots));
+        let knots = [0.0, 1.0, 1.0, 2.0].map(Time::new).to_vec();
+        assert_eq!(BSpline::new(1, knots, points), Err(SplineError::NonPositiveDuration(0.0)));
+    }
+}
@@ -0,0 +1,367 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Heading hold, depth hold and station keeping
+//!
+//! Ready-made controllers for the setpoints a [`crate::mission::Mission`]
+//! produces, each built from typed [`Pid`] loops. [`HeadingHold`] turns a
+//! heading error into a yaw torque and [`DepthHold`] a depth error into a heave
+//! force; [`StationKeeping`] adds surge and sway loops on the position error
+//! rotated into the body frame and splits the resulting wrench among the
+//! thrusters with a [`ThrustAllocator`].
+//!
+//! Saturation is handled at both levels: each loop clamps its command to its
+//! PID limits with anti-windup, and the allocator clamps and redistributes
+//! thrust. Both are reported in the [`Telemetry`] each loop records per update,
+//! whose canonical line has a fixed format so runs can be compared with `diff`.
+//!
+//! Positions are East-North-Up in meters, headings counter-clockwise from east
+//! and depth is measured down from `z = 0`, as in [`crate::mission`]. Heading
+//! errors are wrapped to `(-π, π]`, so the vehicle always turns the short way.
+//! The wrench assumes a vehicle that is kept level by its own stability.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::allocation::{Allocation, ThrustAllocator};
+use crate::euler::wrap;
+use crate::grasp::Wrench;
+use crate::linalg::Vector3;
+use crate::pid::Pid;
+use crate::si_units::{Angle, Force, Length, Measure, Time, Torque};
+
+/// One loop's setpoint, measurement and command at an update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Telemetry {
+    /// Time since the controller started
+    pub time: Time,
+    pub channel: String,
+    /// In the loop's input unit (m or rad)
+    pub setpoint: f64,
+    pub measurement: f64,
+    /// In the loop's output unit (N or N·m)
+    pub command: f64,
+    pub saturated: bool,
+}
+
+impl Telemetry {
+    /// `"<seconds, 3 decimals> <channel> <setpoint> <measurement> <command> <ok|saturated>"`,
+    /// with the values to 4 decimals
+    pub fn canonical(&self) -> String {
+        let state = if self.saturated { "saturated" } else { "ok" };
+        format!("{:.3} {} {:.4} {:.4} {:.4} {}", self.time.value(), self.channel, self.setpoint, self.measurement, self.command, state)
+    }
+}
+
+impl fmt::Display for Telemetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.canonical())
+    }
+}
+
+/// Update of a PID loop and its telemetry record
+fn track<In: Measure, Out: Measure>(pid: &mut Pid<In, Out>, channel: &str, time: f64, setpoint: In, measurement: In, dt: Time) -> (Out, Telemetry) {
+    let command = pid.update(setpoint, measurement, dt);
+    let telemetry = Telemetry {
+        time: Time::new(time),
+        channel: channel.to_string(),
+        setpoint: setpoint.raw(),
+        measurement: measurement.raw(),
+        command: command.raw(),
+        saturated: pid.is_saturated(),
+    };
+    (command, telemetry)
+}
+
+/// Yaw torque holding a heading
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadingHold {
+    pid: Pid<Angle, Torque>,
+    setpoint: Angle,
+    elapsed: f64,
+}
+
+impl HeadingHold {
+    pub fn new(pid: Pid<Angle, Torque>, setpoint: Angle) -> Self {
+        Self { pid, setpoint, elapsed: 0.0 }
+    }
+
+    pub fn setpoint(&self) -> Angle {
+        self.setpoint
+    }
+
+    pub fn set_setpoint(&mut self, heading: Angle) {
+        self.setpoint = heading;
+    }
+
+    /// Forget the loop history and restart the telemetry clock
+    pub fn reset(&mut self) {
+        self.pid.reset();
+        self.elapsed = 0.0;
+    }
+
+    /// Yaw torque, counter-clockwise positive, for the measured `heading`
+    pub fn update(&mut self, heading: Angle, dt: Time) -> (Torque, Telemetry) {
+        self.elapsed += dt.value().max(0.0);
+        let measured = *heading.value();
+        // Move the setpoint next to the measurement so the error takes the short way round
+        let setpoint = Angle::new(measured + wrap(self.setpoint.value() - measured));
+        let (torque, mut telemetry) = track(&mut self.pid, "heading", self.elapsed, setpoint, heading, dt);
+        telemetry.setpoint = wrap(*self.setpoint.value());
+        (torque, telemetry)
+    }
+}
+
+/// Heave force holding a depth
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthHold {
+    /// Acts on the height `z = -depth`, so a positive command pushes up
+    pid: Pid<Length, Force>,
+    setpoint: Length,
+    trim: Force,
+    elapsed: f64,
+}
+
+impl DepthHold {
+    /// Depth loop whose PID pushes up for a positive command
+    pub fn new(pid: Pid<Length, Force>, setpoint: Length) -> Self {
+        Self { pid, setpoint, trim: Force::new(0.0), elapsed: 0.0 }
+    }
+
+    /// Constant heave force added to the loop, positive up, to cancel a known net buoyancy
+    pub fn with_trim(mut self, trim: Force) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    pub fn setpoint(&self) -> Length {
+        self.setpoint
+    }
+
+    pub fn set_setpoint(&mut self, depth: Length) {
+        self.setpoint = depth;
+    }
+
+    /// Forget the loop history and restart the telemetry clock
+    pub fn reset(&mut self) {
+        self.pid.reset();
+        self.elapsed = 0.0;
+    }
+
+    /// Heave force, positive up, for the measured `depth`
+    pub fn update(&mut self, depth: Length, dt: Time) -> (Force, Telemetry) {
+        self.elapsed += dt.value().max(0.0);
+        let height = |depth: Length| Length::new(-depth.value());
+        let (force, mut telemetry) = track(&mut self.pid, "depth", self.elapsed, height(self.setpoint), height(depth), dt);
+        let force = Force::new(force.value() + self.trim.value());
+        telemetry.setpoint = *self.setpoint.value();
+        telemetry.measurement = *depth.value();
+        telemetry.command = *force.value();
+        (force, telemetry)
+    }
+}
+
+/// Commands of one station-keeping update
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StationCommand {
+    /// Demanded wrench about the body origin, in the body frame
+    pub wrench: Wrench,
+    /// Thruster split of the wrench, when thrusters are configured
+    pub allocation: Option<Allocation>,
+    /// Surge, sway, heading and depth records, then the allocator's when present
+    pub telemetry: Vec<Telemetry>,
+}
+
+impl StationCommand {
+    /// Canonical lines of every record, newline-terminated
+    pub fn canonical(&self) -> String {
+        self.telemetry.iter().map(|t| t.canonical() + "\n").collect()
+    }
+}
+
+/// Holds a position, heading and depth with surge, sway, yaw and heave loops
+#[derive(Debug, Clone, PartialEq)]
+pub struct StationKeeping {
+    surge: Pid<Length, Force>,
+    sway: Pid<Length, Force>,
+    heading: HeadingHold,
+    depth: DepthHold,
+    /// Horizontal target, East-North
+    target: [f64; 2],
+    allocator: Option<ThrustAllocator>,
+    elapsed: f64,
+}
+
+impl StationKeeping {
+    /// Hold at the depth and heading of the given loops, at the origin until [`set_target`](Self::set_target)
+    pub fn new(surge: Pid<Length, Force>, sway: Pid<Length, Force>, heading: HeadingHold, depth: DepthHold) -> Self {
+        Self { surge, sway, heading, depth, target: [0.0; 2], allocator: None, elapsed: 0.0 }
+    }
+
+    /// Split every wrench among these thrusters
+    pub fn with_allocator(mut self, allocator: ThrustAllocator) -> Self {
+        self.allocator = Some(allocator);
+        self
+    }
+
+    /// Hold at `position` (East-North-Up) facing `heading`
+    pub fn set_target(&mut self, position: Vector3, heading: Angle) {
+        self.target = [position[0], position[1]];
+        self.depth.set_setpoint(Length::new(-position[2]));
+        self.heading.set_setpoint(heading);
+    }
+
+    /// Target position, East-North-Up
+    pub fn target(&self) -> Vector3 {
+        [self.target[0], self.target[1], -self.depth.setpoint().value()]
+    }
+
+    pub fn heading(&self) -> &HeadingHold {
+        &self.heading
+    }
+
+    pub fn depth(&self) -> &DepthHold {
+        &self.depth
+    }
+
+    /// Forget every loop's history and restart the telemetry clock
+    pub fn reset(&mut self) {
+        self.surge.reset();
+        self.sway.reset();
+        self.heading.reset();
+        self.depth.reset();
+        self.elapsed = 0.0;
+    }
+
+    /// Commands for the measured `position` (East-North-Up) and `heading`
+    pub fn update(&mut self, position: Vector3, heading: Angle, dt: Time) -> StationCommand {
+        self.elapsed += dt.value().max(0.0);
+        let (east, north) = (self.target[0] - position[0], self.target[1] - position[1]);
+        let (sin, cos) = heading.value().sin_cos();
+        let ahead = Length::new(cos * east + sin * north);
+        let left = Length::new(-sin * east + cos * north);
+
+        let zero = Length::new(0.0);
+        let (surge, surge_telemetry) = track(&mut self.surge, "surge", self.elapsed, ahead, zero, dt);
+        let (sway, sway_telemetry) = track(&mut self.sway, "sway", self.elapsed, left, zero, dt);
+        let (yaw, heading_telemetry) = self.heading.update(heading, dt);
+        let (heave, depth_telemetry) = self.depth.update(Length::new(-position[2]), dt);
+        let mut telemetry = vec![surge_telemetry, sway_telemetry, heading_telemetry, depth_telemetry];
+
+        let wrench = Wrench::new([*surge.value(), *sway.value(), *heave.value()], [0.0, 0.0, *yaw.value()]);
+        let allocation = self.allocator.as_ref().map(|allocator| allocator.allocate(&wrench));
+        if let Some(allocation) = &allocation {
+            let demand = wrench.to_array().iter().map(|w| w * w).sum::<f64>().sqrt();
+            let achieved = allocation.achieved.to_array().iter().map(|w| w * w).sum::<f64>().sqrt();
+            telemetry.push(Telemetry {
+                time: Time::new(self.elapsed),
+                channel: "thrust".to_string(),
+                setpoint: demand,
+                measurement: achieved,
+                command: allocation.thrusts.iter().map(|t| t.value().abs()).fold(0.0, f64::max),
+                saturated: allocation.saturated,
+            });
+        }
+        StationCommand { wrench, allocation, telemetry }
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocation::ThrusterPlacement;
+    use crate::pid::{DerivativeGain, IntegralGain, ProportionalGain};
+    use crate::si_units::TAU;
+
+    fn heading_hold(setpoint: f64) -> HeadingHold {
+        let pid = Pid::new(ProportionalGain::new(40.0), IntegralGain::new(2.0), DerivativeGain::new(30.0))
+            .with_limits(Torque::new(-20.0), Torque::new(20.0));
+        HeadingHold::new(pid, Angle::new(setpoint))
+    }
+
+    fn position_loop() -> Pid<Length, Force> {
+        Pid::new(ProportionalGain::new(60.0), IntegralGain::new(2.0), DerivativeGain::new(120.0))
+            .with_limits(Force::new(-40.0), Force::new(40.0))
+    }
+
+    fn depth_hold(setpoint: f64) -> DepthHold {
+        DepthHold::new(position_loop(), Length::new(setpoint))
+    }
+
+    #[test]
+    fn test_heading_error_takes_the_short_way() {
+        let mut hold = heading_hold(TAU / 2.0 - 0.05);
+        let (torque, telemetry) = hold.update(Angle::new(-TAU / 2.0 + 0.05), Time::new(0.1));
+        // 0.1 rad clockwise, not 6.18 rad counter-clockwise
+        assert!(*torque.value() < 0.0 && *torque.value() > -20.0, "{:?}", torque);
+        assert!(!telemetry.saturated);
+        assert_eq!(telemetry.canonical(), "0.100 heading 3.0916 -3.0916 -4.0200 ok");
+
+        let mut hold = heading_hold(TAU / 4.0);
+        let (torque, telemetry) = hold.update(Angle::new(0.0), Time::new(0.1));
+        assert_eq!(*torque.value(), 20.0);
+        assert!(telemetry.saturated && telemetry.canonical().ends_with(" saturated"));
+    }
+
+    #[test]
+    fn test_depth_hold_pushes_toward_the_setpoint() {
+        let mut hold = depth_hold(10.0).with_trim(Force::new(-5.0));
+        let (force, telemetry) = hold.update(Length::new(9.5), Time::new(0.1));
+        // Too shallow: push down, on top of the trim
+        assert!(*force.value() < -5.0);
+        assert_eq!((telemetry.setpoint, telemetry.measurement, telemetry.command), (10.0, 9.5, *force.value()));
+        let (force, _) = hold.update(Length::new(10.5), Time::new(0.1));
+        assert!(*force.value() > 0.0);
+    }
+
+    #[test]
+    fn test_station_keeping_settles_on_target() {
+        let h = std::f64::consts::FRAC_1_SQRT_2;
+        let max = Force::new(15.0);
+        let allocator = ThrustAllocator::new(vec![
+            ThrusterPlacement::new("front_left", [0.4, 0.3, 0.0], [h, -h, 0.0], max),
+            ThrusterPlacement::new("front_right", [0.4, -0.3, 0.0], [h, h, 0.0], max),
+            ThrusterPlacement::new("rear_left", [-0.4, 0.3, 0.0], [h, h, 0.0], max),
+            ThrusterPlacement::new("rear_right", [-0.4, -0.3, 0.0], [h, -h, 0.0], max),
+            ThrusterPlacement::new("vertical", [0.0, 0.0, 0.0], [0.0, 0.0, 1.0], max),
+        ])
+        .unwrap();
+        let mut controller = StationKeeping::new(position_loop(), position_loop(), heading_hold(0.0), depth_hold(0.0))
+            .with_allocator(allocator);
+        controller.set_target([5.0, -3.0, -4.0], Angle::new(TAU / 4.0));
+        assert_eq!(controller.target(), [5.0, -3.0, -4.0]);
+
+        // Planar vehicle of 50 kg and 8 kg·m² in yaw with linear drag, driven by the achieved wrench
+        let (mass, inertia, drag, yaw_drag) = (50.0, 8.0, 20.0, 5.0);
+        let (mut position, mut velocity, mut heading, mut rate) = ([0.0; 3], [0.0; 3], 0.0, 0.0);
+        let dt = Time::new(0.05);
+        let mut saturated = false;
+        for _ in 0..1200 {
+            let command = controller.update(position, Angle::new(heading), dt);
+            let achieved = command.allocation.as_ref().unwrap().achieved;
+            saturated |= command.allocation.unwrap().saturated;
+            let (sin, cos) = f64::sin_cos(heading);
+            let force = achieved.force;
+            let world = [cos * force[0] - sin * force[1], sin * force[0] + cos * force[1], force[2]];
+            for i in 0..3 {
+                velocity[i] += (world[i] - drag * velocity[i]) / mass * 0.05;
+                position[i] += velocity[i] * 0.05;
+            }
+            rate += (achieved.torque[2] - yaw_drag * rate) / inertia * 0.05;
+            heading = wrap(heading + rate * 0.05);
+        }
+        assert!(saturated, "the initial approach should saturate the thrusters");
+        let error: f64 = position.iter().zip([5.0, -3.0, -4.0]).map(|(p, t)| (p - t) * (p - t)).sum::<f64>().sqrt();
+        assert!(error < 0.05, "{:?}", position);
+        assert!((heading - TAU / 4.0).abs() < 0.01, "{}", heading);
+
+        let command = controller.update(position, Angle::new(heading), dt);
+        let canonical = command.canonical();
+        let channels: Vec<&str> = canonical.lines().map(|line| line.split(' ').nth(1).unwrap()).collect();
+        assert_eq!(channels, ["surge", "sway", "heading", "depth", "thrust"]);
+        assert!(canonical.starts_with("60.050 surge "));
+    }
+}
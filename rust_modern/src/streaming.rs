@@ -0,0 +1,485 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Streaming accumulators for online estimation
+//!
+//! Each accumulator takes one item at a time and keeps a fixed-size summary
+//! (running sums of outer products), so memory does not grow with the stream.
+//! Once the stream is in, the summary is turned into a fitted object:
+//!
+//! - [`PointMoments`]: mean and covariance of points, as an [`UncertainPoint`],
+//!   and the best-fitting plane;
+//! - [`SurfaceMoments`]: the algebraic sphere and quadric fits of
+//!   [`crate::fitting`];
+//! - [`RotorMoments`]: the mean rotation of a stream of rotors;
+//! - [`Multivector`]: the sum of its blade terms, held in at most `2^n`
+//!   coefficients.
+//!
+//! [`Accumulate::merge`] combines accumulators fed disjoint parts of a stream,
+//! so a large batch can be split across threads and reduced; the result equals
+//! that of one accumulator fed everything, up to rounding.
+//!
+//! The summaries are kept about the first point pushed, not the origin, so
+//! points far from the origin do not lose precision to the large raw moments.
+
+use crate::fitting::{self, FitError, Normalization, QuadricFit, SphereFit};
+use crate::ga_term::BladeTerm;
+use crate::linalg::{self, square, Matrix3, Vector3};
+use crate::motor::{AveragingError, Rotor};
+use crate::multivector::Multivector;
+use crate::parity::Coefficient;
+use crate::primitives::Plane;
+use crate::si_units::Length;
+use crate::uncertainty::UncertainPoint;
+
+/// Second-smallest scatter eigenvalue, relative to the largest, below which the
+/// points are collinear and do not determine a plane
+const COLLINEAR_RATIO: f64 = 1e-12;
+
+/// Constant-memory summary of a stream of items
+pub trait Accumulate<Item>: Sized {
+    fn push(&mut self, item: Item);
+
+    /// Absorb an accumulator fed another part of the stream
+    fn merge(&mut self, other: Self);
+
+    fn push_all(&mut self, items: impl IntoIterator<Item = Item>) {
+        items.into_iter().for_each(|item| self.push(item));
+    }
+}
+
+/// Merge accumulators of the parts of a stream, e.g. one per thread
+pub fn merge_all<Item, A: Accumulate<Item> + Default>(parts: impl IntoIterator<Item = A>) -> A {
+    parts.into_iter().fold(A::default(), |mut total, part| {
+        total.merge(part);
+        total
+    })
+}
+
+fn outer(a: Vector3, b: Vector3) -> Matrix3 {
+    std::array::from_fn(|i| std::array::from_fn(|j| a[i] * b[j]))
+}
+
+/// Count, mean and scatter of points, updated by Welford's method and merged by Chan's
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PointMoments {
+    count: usize,
+    mean: Vector3,
+    /// `Σ (p - mean)(p - mean)ᵀ`
+    scatter: Matrix3,
+}
+
+impl PointMoments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<Vector3> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Sample covariance, dividing by `n - 1`; `None` below two points
+    pub fn covariance(&self) -> Option<Matrix3> {
+        (self.count > 1).then(|| self.scatter.map(|row| row.map(|x| x / (self.count - 1) as f64)))
+    }
+
+    /// Mean with the sample covariance
+    pub fn uncertain_point(&self) -> Option<UncertainPoint> {
+        Some(UncertainPoint::new(self.mean, self.covariance()?))
+    }
+
+    /// Plane through the mean minimizing the summed squared distances, normal
+    /// along the direction of least spread
+    pub fn plane(&self) -> Result<Plane, FitError> {
+        if self.count < 3 {
+            return Err(FitError::TooFewPoints { needed: 3, given: self.count });
+        }
+        if self.scatter.iter().flatten().any(|x| !x.is_finite()) {
+            return Err(FitError::NonFinite);
+        }
+        let (values, vectors) = square::symmetric_eigen(&self.scatter);
+        if values[1] <= COLLINEAR_RATIO * values[2] {
+            return Err(FitError::Degenerate);
+        }
+        Ok(Plane::through(self.mean, [vectors[0][0], vectors[1][0], vectors[2][0]]))
+    }
+}
+
+impl Accumulate<Vector3> for PointMoments {
+    fn push(&mut self, point: Vector3) {
+        self.count += 1;
+        let delta = linalg::sub(point, self.mean);
+        self.mean = linalg::add(self.mean, linalg::scale(delta, 1.0 / self.count as f64));
+        self.scatter = square::add(&self.scatter, &outer(delta, linalg::sub(point, self.mean)));
+    }
+
+    fn merge(&mut self, other: Self) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other;
+            return;
+        }
+        let (a, b) = (self.count as f64, other.count as f64);
+        let delta = linalg::sub(other.mean, self.mean);
+        let correction = outer(delta, linalg::scale(delta, a * b / (a + b)));
+        self.count += other.count;
+        self.mean = linalg::add(self.mean, linalg::scale(delta, b / (a + b)));
+        self.scatter = square::add(&square::add(&self.scatter, &other.scatter), &correction);
+    }
+}
+
+/// Factors of the quadric monomials `x², y², z², xy, xz, yz, x, y, z, 1`,
+/// with factor 3 the constant 1
+const MONOMIALS: [(usize, usize); 10] = [(0, 0), (1, 1), (2, 2), (0, 1), (0, 2), (1, 2), (0, 3), (1, 3), (2, 3), (3, 3)];
+
+fn monomials(p: Vector3) -> [f64; 10] {
+    let f = [p[0], p[1], p[2], 1.0];
+    MONOMIALS.map(|(a, b)| f[a] * f[b])
+}
+
+/// Matrix carrying the monomials of `p` to those of `(p - offset) / scale`
+fn monomial_map(offset: Vector3, scale: f64) -> [[f64; 10]; 10] {
+    // Each factor of the mapped point is linear in the original factors
+    let mut factor = [[0.0; 4]; 4];
+    for i in 0..3 {
+        factor[i][i] = 1.0 / scale;
+        factor[i][3] = -offset[i] / scale;
+    }
+    factor[3][3] = 1.0;
+    let position = |j: usize, l: usize| MONOMIALS.iter().position(|&m| m == (j.min(l), j.max(l))).expect("every factor pair is a monomial");
+
+    let mut map = [[0.0; 10]; 10];
+    for (row, &(a, b)) in map.iter_mut().zip(&MONOMIALS) {
+        for j in 0..4 {
+            for l in 0..4 {
+                row[position(j, l)] += factor[a][j] * factor[b][l];
+            }
+        }
+    }
+    map
+}
+
+/// Scatter of the quadric monomials of points, enough to fit spheres and quadrics
+///
+/// Fits match [`fitting::fit_sphere`] and [`fitting::fit_quadric`] on exact
+/// data. The points are normalized by their RMS rather than mean distance from
+/// the centroid, so noisy fits differ slightly, and the reported RMS is the
+/// first-order distance `√(Σ f² / Σ ‖∇f‖²)` since exact distances need the points.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SurfaceMoments {
+    /// First point pushed, which the monomials are taken about
+    origin: Option<Vector3>,
+    scatter: [[f64; 10]; 10],
+}
+
+impl SurfaceMoments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn count(&self) -> usize {
+        self.scatter[9][9] as usize
+    }
+
+    /// Monomial scatter of the normalized points, with the normalization and
+    /// the covariance of the normalized points
+    fn normalized(&self, needed: usize) -> Result<([[f64; 10]; 10], Normalization, Matrix3), FitError> {
+        let count = self.count();
+        if count < needed {
+            return Err(FitError::TooFewPoints { needed, given: count });
+        }
+        if self.scatter.iter().flatten().any(|x| !x.is_finite()) {
+            return Err(FitError::NonFinite);
+        }
+        let n = self.scatter[9][9];
+        let mean: Vector3 = std::array::from_fn(|i| self.scatter[6 + i][9] / n);
+        let covariance: Matrix3 = std::array::from_fn(|i| std::array::from_fn(|j| self.scatter[6 + i][6 + j] / n - mean[i] * mean[j]));
+        let scale = (covariance[0][0] + covariance[1][1] + covariance[2][2]).max(0.0).sqrt();
+        if scale == 0.0 {
+            return Err(FitError::Degenerate);
+        }
+        let origin = self.origin.expect("points were pushed");
+        let normalization = Normalization { centroid: linalg::add(origin, mean), scale };
+        let covariance = covariance.map(|row| row.map(|x| x / (scale * scale)));
+        Ok((square::sandwich(&monomial_map(mean, scale), &self.scatter), normalization, covariance))
+    }
+
+    /// First-order RMS distance of the normalized points from `pᵀ A p + g·p + c = 0`,
+    /// scaled back to length, given `Σ f² = kᵀ S k`
+    fn rms(squared: f64, quadratic: Matrix3, gradient: Vector3, covariance: &Matrix3, count: usize, scale: f64) -> Length {
+        // Σ ‖2 A p + g‖² over centered points with covariance C is n (4 tr(Aᵀ A C) + ‖g‖²)
+        let aa = linalg::mat3_mul(&linalg::mat3_transpose(&quadratic), &quadratic);
+        let trace: f64 = (0..3).map(|i| linalg::mat3_mul(&aa, covariance)[i][i]).sum();
+        let slope = count as f64 * (4.0 * trace + linalg::dot(gradient, gradient));
+        Length::new(if slope > 0.0 { scale * (squared.max(0.0) / slope).sqrt() } else { f64::INFINITY })
+    }
+
+    /// Sphere through at least four points, in the least-squares sense
+    pub fn fit_sphere(&self) -> Result<SphereFit, FitError> {
+        let (scatter, normalization, covariance) = self.normalized(4)?;
+        // Rows [‖p‖², x, y, z, 1] are linear in the monomials
+        let mut select = [[0.0; 10]; 5];
+        select[0][..3].copy_from_slice(&[1.0; 3]);
+        for i in 0..4 {
+            select[1 + i][6 + i] = 1.0;
+        }
+        let sphere_scatter: Vec<f64> = (0..5)
+            .flat_map(|i| (0..5).map(move |j| (i, j)))
+            .map(|(i, j)| (0..10).flat_map(|a| (0..10).map(move |b| (a, b))).map(|(a, b)| select[i][a] * scatter[a][b] * select[j][b]).sum())
+            .collect();
+        let k = fitting::smallest_eigenvector(&sphere_scatter, 5)?;
+        let sphere = fitting::sphere_from_coefficients(&k, &normalization)?;
+
+        let squared: f64 = (0..5).flat_map(|i| (0..5).map(move |j| (i, j))).map(|(i, j)| k[i] * sphere_scatter[i * 5 + j] * k[j]).sum();
+        let quadratic = [[k[0], 0.0, 0.0], [0.0, k[0], 0.0], [0.0, 0.0, k[0]]];
+        let rms = Self::rms(squared, quadratic, [k[1], k[2], k[3]], &covariance, self.count(), normalization.scale);
+        Ok(SphereFit { sphere, rms })
+    }
+
+    /// General quadric through at least nine points, in the least-squares sense
+    pub fn fit_quadric(&self) -> Result<QuadricFit, FitError> {
+        let (scatter, normalization, covariance) = self.normalized(9)?;
+        let k = fitting::smallest_eigenvector(scatter.as_flattened(), 10)?;
+        let quadric = fitting::quadric_from_coefficients(&k, &normalization);
+
+        let k10: [f64; 10] = std::array::from_fn(|i| k[i]);
+        let squared = square::quadratic_form(&scatter, &k10);
+        let quadratic = [
+            [k[0], 0.5 * k[3], 0.5 * k[4]],
+            [0.5 * k[3], k[1], 0.5 * k[5]],
+            [0.5 * k[4], 0.5 * k[5], k[2]],
+        ];
+        let rms = Self::rms(squared, quadratic, [k[6], k[7], k[8]], &covariance, self.count(), normalization.scale);
+        Ok(QuadricFit { quadric, rms })
+    }
+}
+
+impl Accumulate<Vector3> for SurfaceMoments {
+    fn push(&mut self, point: Vector3) {
+        let origin = *self.origin.get_or_insert(point);
+        let row = monomials(linalg::sub(point, origin));
+        for (i, line) in self.scatter.iter_mut().enumerate() {
+            for (j, value) in line.iter_mut().enumerate() {
+                *value += row[i] * row[j];
+            }
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        let Some(theirs) = other.origin else {
+            return;
+        };
+        let Some(ours) = self.origin else {
+            *self = other;
+            return;
+        };
+        // Their points about our origin are p - ours = (p - theirs) - (ours - theirs)
+        let moved = square::sandwich(&monomial_map(linalg::sub(ours, theirs), 1.0), &other.scatter);
+        self.scatter = square::add(&self.scatter, &moved);
+    }
+}
+
+/// Weighted sum `Σ w q qᵀ` of rotor quaternions, whose principal eigenvector is
+/// the mean rotation
+///
+/// This is the chordal mean (Markley et al.), which needs no iteration and no
+/// sign alignment since `q qᵀ = (-q)(-q)ᵀ`. It agrees with the intrinsic mean of
+/// [`crate::motor::average_rotors`] to second order in the spread of the rotors.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RotorMoments {
+    scatter: [[f64; 4]; 4],
+    weight: f64,
+    count: usize,
+    invalid_weight: bool,
+}
+
+impl RotorMoments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Add a rotor with a finite, non-negative weight
+    pub fn push_weighted(&mut self, rotor: Rotor, weight: f64) {
+        if !weight.is_finite() || weight < 0.0 {
+            self.invalid_weight = true;
+            return;
+        }
+        let q = rotor.normalized().to_quaternion();
+        self.scatter = square::add(&self.scatter, &std::array::from_fn(|i| std::array::from_fn(|j| weight * q[i] * q[j])));
+        self.weight += weight;
+        self.count += 1;
+    }
+
+    pub fn mean(&self) -> Result<Rotor, AveragingError> {
+        if self.invalid_weight || (self.count > 0 && self.weight <= 0.0) {
+            return Err(AveragingError::InvalidWeights);
+        }
+        if self.count == 0 {
+            return Err(AveragingError::Empty);
+        }
+        let (_, vectors) = square::symmetric_eigen(&self.scatter);
+        Ok(Rotor::from_quaternion(std::array::from_fn(|i| vectors[i][3])).normalized())
+    }
+}
+
+impl Accumulate<Rotor> for RotorMoments {
+    fn push(&mut self, rotor: Rotor) {
+        self.push_weighted(rotor, 1.0);
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.scatter = square::add(&self.scatter, &other.scatter);
+        self.weight += other.weight;
+        self.count += other.count;
+        self.invalid_weight |= other.invalid_weight;
+    }
+}
+
+/// Terms add into the blade coefficients, so memory is bounded by the `2^n`
+/// blades of the algebra however many terms arrive
+impl<T: Coefficient> Accumulate<BladeTerm<T>> for Multivector<T> {
+    fn push(&mut self, term: BladeTerm<T>) {
+        self.extend(std::iter::once(term));
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.add_assign(&other);
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitting::{fit_quadric, fit_sphere};
+    use crate::ga_term::Index;
+    use crate::motor::{average_rotors, Motor};
+    use crate::primitives::Sphere;
+    use crate::sample::Sampler;
+
+    /// Accumulate `points` in `parts` chunks and merge them
+    fn in_parts<A: Accumulate<Vector3> + Default>(points: &[Vector3], parts: usize) -> A {
+        merge_all(points.chunks(points.len().div_ceil(parts)).map(|chunk| {
+            let mut part = A::default();
+            part.push_all(chunk.iter().copied());
+            part
+        }))
+    }
+
+    #[test]
+    fn test_point_moments_match_batch_statistics() {
+        let mut sampler = Sampler::new(5);
+        let points: Vec<Vector3> = (0..200)
+            .map(|_| [1e6 + 3.0 * sampler.normal(), -2e6 + 0.5 * sampler.normal(), 40.0 + 0.01 * sampler.normal()])
+            .collect();
+        let n = points.len() as f64;
+        let mean = linalg::scale(points.iter().fold([0.0; 3], |sum, p| linalg::add(sum, *p)), 1.0 / n);
+        let covariance: Matrix3 = std::array::from_fn(|i| {
+            std::array::from_fn(|j| points.iter().map(|p| (p[i] - mean[i]) * (p[j] - mean[j])).sum::<f64>() / (n - 1.0))
+        });
+
+        let single: PointMoments = in_parts(&points, 1);
+        let merged: PointMoments = in_parts(&points, 7);
+        for moments in [single, merged] {
+            assert_eq!(moments.count(), 200);
+            assert!(linalg::norm(linalg::sub(moments.mean().unwrap(), mean)) < 1e-8);
+            let found = moments.uncertain_point().unwrap().covariance;
+            assert!(found.iter().flatten().zip(covariance.iter().flatten()).all(|(a, b)| (a - b).abs() < 1e-8));
+            // Least spread is along z
+            assert!(moments.plane().unwrap().normal[2].abs() > 0.999);
+        }
+
+        let mut line = PointMoments::new();
+        line.push_all((0..5).map(|i| [i as f64, 2.0 * i as f64, 0.0]));
+        assert_eq!(line.plane(), Err(FitError::Degenerate));
+        assert!(PointMoments::new().covariance().is_none());
+        assert_eq!(PointMoments::new().plane(), Err(FitError::TooFewPoints { needed: 3, given: 0 }));
+    }
+
+    #[test]
+    fn test_surface_moments_fit_like_batch_fits() {
+        let truth = Sphere::new([10.0, -4.0, 2.5], 0.8);
+        let mut sampler = Sampler::new(7);
+        let exact: Vec<Vector3> = (0..60).map(|_| linalg::add(truth.center, linalg::scale(sampler.unit_vector(), truth.radius))).collect();
+        for parts in [1, 4] {
+            let fit = in_parts::<SurfaceMoments>(&exact, parts).fit_sphere().unwrap();
+            assert!(linalg::norm(linalg::sub(fit.sphere.center, truth.center)) < 1e-8);
+            assert!((fit.sphere.radius - truth.radius).abs() < 1e-8 && *fit.rms.value() < 1e-6);
+        }
+
+        let noisy: Vec<Vector3> = exact.iter().map(|p| linalg::add(*p, std::array::from_fn(|_| 1e-3 * sampler.normal()))).collect();
+        let (batch, streamed) = (fit_sphere(&noisy).unwrap(), in_parts::<SurfaceMoments>(&noisy, 3).fit_sphere().unwrap());
+        assert!(linalg::norm(linalg::sub(batch.sphere.center, streamed.sphere.center)) < 1e-4);
+        assert!((batch.rms.value() - streamed.rms.value()).abs() < 5e-4);
+
+        let motor = Motor::new([1.0, 2.0, -3.0], Rotor::from_axis_angle([1.0, 1.0, 0.0], 0.6));
+        let ellipsoid: Vec<Vector3> = (0..40)
+            .map(|_| {
+                let u = sampler.unit_vector();
+                motor.apply_point([0.5 * u[0], u[1], 2.0 * u[2]])
+            })
+            .collect();
+        let (batch, streamed) = (fit_quadric(&ellipsoid).unwrap(), in_parts::<SurfaceMoments>(&ellipsoid, 5).fit_quadric().unwrap());
+        assert!(*streamed.rms.value() < 1e-6);
+        assert!(linalg::norm(linalg::sub(streamed.quadric.center().unwrap(), batch.quadric.center().unwrap())) < 1e-8);
+        for (a, b) in streamed.quadric.semi_axes().unwrap().iter().zip(batch.quadric.semi_axes().unwrap()) {
+            assert!((a.value() - b.value()).abs() < 1e-8);
+        }
+
+        let mut few = SurfaceMoments::new();
+        few.push_all(exact[..3].iter().copied());
+        assert_eq!(few.fit_sphere().unwrap_err(), FitError::TooFewPoints { needed: 4, given: 3 });
+        few.push([f64::NAN, 0.0, 0.0]);
+        assert_eq!(few.fit_sphere().unwrap_err(), FitError::NonFinite);
+    }
+
+    #[test]
+    fn test_rotor_and_multivector_streams() {
+        let mut sampler = Sampler::new(11);
+        let center = Rotor::from_axis_angle([0.0, 0.0, 1.0], 3.0);
+        // Half the rotors with a flipped sign, which the mean must ignore
+        let rotors: Vec<Rotor> = (0..100)
+            .map(|i| {
+                let r = center * Rotor::from_rotation_vector(linalg::scale(sampler.unit_vector(), 0.05));
+                if i % 2 == 0 {
+                    r
+                } else {
+                    Rotor::new(-r.scalar, -r.e23, -r.e13, -r.e12)
+                }
+            })
+            .collect();
+        let mut first = RotorMoments::new();
+        let mut second = RotorMoments::new();
+        first.push_all(rotors[..30].iter().copied());
+        second.push_all(rotors[30..].iter().copied());
+        first.merge(second);
+        let streamed = first.mean().unwrap();
+        let intrinsic = average_rotors(&rotors, None).unwrap();
+        assert_eq!(first.count(), 100);
+        assert!((streamed.reverse() * intrinsic).angle() < 1e-4);
+        assert_eq!(RotorMoments::new().mean(), Err(AveragingError::Empty));
+        let mut invalid = RotorMoments::new();
+        invalid.push_weighted(center, -1.0);
+        assert_eq!(invalid.mean(), Err(AveragingError::InvalidWeights));
+
+        let terms = |range: std::ops::Range<usize>| range.map(|i| BladeTerm::new(vec![1 + (i % 3) as Index, 1 + ((i + 1) % 3) as Index], 1.0));
+        let mut sum = Multivector::<f64>::default();
+        let mut part = Multivector::<f64>::default();
+        sum.push_all(terms(0..500));
+        part.push_all(terms(500..1000));
+        sum.merge(part);
+        assert_eq!(sum, terms(0..1000).collect::<Multivector<f64>>());
+        assert!(sum.len() <= 1 << sum.dimension());
+    }
+}
@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compensated summation for long accumulations
+//!
+//! Adding many terms of different magnitude loses the low-order bits of the small
+//! ones. [`Summation::Compensated`] tracks the lost bits in a second accumulator
+//! (Neumaier's variant of Kahan summation), keeping the error independent of the
+//! number of terms. The `compensated-summation` feature makes it the default.
+
+use crate::ga_term::Real;
+
+/// How an accumulation adds up its terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Summation {
+    Naive,
+    Compensated,
+}
+
+impl Default for Summation {
+    fn default() -> Self {
+        if cfg!(feature = "compensated-summation") {
+            Summation::Compensated
+        } else {
+            Summation::Naive
+        }
+    }
+}
+
+/// Running sum in the chosen summation mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Accumulator<T> {
+    mode: Summation,
+    sum: T,
+    compensation: T,
+}
+
+impl<T: Real> Accumulator<T> {
+    pub fn new(mode: Summation) -> Self {
+        Self { mode, sum: T::default(), compensation: T::default() }
+    }
+
+    pub fn add(&mut self, value: T) {
+        match self.mode {
+            Summation::Naive => self.sum = self.sum + value,
+            Summation::Compensated => {
+                let total = self.sum + value;
+                // Recover the low-order bits lost by the larger operand
+                self.compensation = self.compensation
+                    + if self.sum.abs() >= value.abs() {
+                        (self.sum - total) + value
+                    } else {
+                        (value - total) + self.sum
+                    };
+                self.sum = total;
+            }
+        }
+    }
+
+    pub fn value(&self) -> T {
+        self.sum + self.compensation
+    }
+}
+
+impl<T: Real> Extend<T> for Accumulator<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value);
+        }
+    }
+}
+
+/// Sum of `values` in the given mode
+pub fn sum_with<T: Real>(values: impl IntoIterator<Item = T>, mode: Summation) -> T {
+    let mut accumulator = Accumulator::new(mode);
+    accumulator.extend(values);
+    accumulator.value()
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellation() {
+        let values = [1.0, 1e100, 1.0, -1e100];
+        assert_eq!(sum_with(values, Summation::Naive), 0.0);
+        assert_eq!(sum_with(values, Summation::Compensated), 2.0);
+    }
+
+    #[test]
+    fn test_many_small_terms() {
+        // Each term is below half an ulp of 1.0, so a naive sum never moves
+        let values = std::iter::once(1.0).chain(std::iter::repeat_n(1e-16, 1_000_000));
+        let naive = sum_with(values.clone(), Summation::Naive);
+        let compensated = sum_with(values, Summation::Compensated);
+
+        assert_eq!(naive, 1.0);
+        assert!((compensated - (1.0 + 1e-10)).abs() < 1e-15, "{}", compensated);
+    }
+}
@@ -0,0 +1,256 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Joystick and gamepad teleoperation
+//!
+//! Input devices report axes as plain numbers in `[-1, 1]`. [`Teleop`] is the
+//! single place where those numbers become physical commands: each binding
+//! shapes its input with a deadband and an exponential curve, and the shaped
+//! value is multiplied by the typed limit of the body axis it drives. Linear
+//! axes can only be limited by a [`Velocity`] and rotational ones by an
+//! [`AngularVelocity`], so a stick can never be scaled by the wrong unit.
+//!
+//! Body axes follow the crate's forward-left-up convention.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use crate::si_units::{AngularVelocity, Velocity};
+
+/// Body axis driven by an input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TwistAxis {
+    /// Forward
+    Surge,
+    /// Left
+    Sway,
+    /// Up
+    Heave,
+    Roll,
+    Pitch,
+    Yaw,
+}
+
+impl TwistAxis {
+    /// Component within the linear or angular triple
+    fn component(&self) -> usize {
+        match self {
+            TwistAxis::Surge | TwistAxis::Roll => 0,
+            TwistAxis::Sway | TwistAxis::Pitch => 1,
+            TwistAxis::Heave | TwistAxis::Yaw => 2,
+        }
+    }
+
+    fn is_linear(&self) -> bool {
+        matches!(self, TwistAxis::Surge | TwistAxis::Sway | TwistAxis::Heave)
+    }
+}
+
+/// Shaping of one normalized input
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AxisCurve {
+    /// Inputs with magnitude below this are zero; the rest is rescaled so the
+    /// output still starts at zero and reaches one
+    pub deadband: f64,
+    /// Blend between linear (0) and cubic (1) response for fine control near center
+    pub expo: f64,
+    pub inverted: bool,
+}
+
+impl AxisCurve {
+    pub fn linear() -> Self {
+        Self { deadband: 0.0, expo: 0.0, inverted: false }
+    }
+
+    pub fn with_deadband(mut self, deadband: f64) -> Self {
+        self.deadband = deadband.clamp(0.0, 0.99);
+        self
+    }
+
+    pub fn with_expo(mut self, expo: f64) -> Self {
+        self.expo = expo.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn inverted(mut self) -> Self {
+        self.inverted = !self.inverted;
+        self
+    }
+
+    /// Shaped value in `[-1, 1]`; inputs outside the range are clamped
+    pub fn shape(&self, input: f64) -> f64 {
+        let x = input.clamp(-1.0, 1.0);
+        let magnitude = ((x.abs() - self.deadband) / (1.0 - self.deadband)).max(0.0);
+        let curved = (1.0 - self.expo) * magnitude + self.expo * magnitude.powi(3);
+        let signed = curved.copysign(x);
+        if self.inverted { -signed } else { signed }
+    }
+}
+
+impl Default for AxisCurve {
+    fn default() -> Self {
+        Self::linear()
+    }
+}
+
+/// One input driving one body axis
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AxisBinding {
+    /// Index into the device's axis array
+    pub input: usize,
+    pub axis: TwistAxis,
+    pub curve: AxisCurve,
+}
+
+/// Body velocity command
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TwistCommand {
+    /// Forward, left and up
+    pub linear: [Velocity; 3],
+    /// About forward, left and up
+    pub angular: [AngularVelocity; 3],
+}
+
+impl TwistCommand {
+    pub fn zero() -> Self {
+        Self { linear: [Velocity::new(0.0); 3], angular: [AngularVelocity::new(0.0); 3] }
+    }
+
+    /// Twist coordinates in SI units ordered (rotation, translation), as used by motors
+    pub fn to_array(&self) -> [f64; 6] {
+        let (v, w) = (self.linear.map(|q| *q.value()), self.angular.map(|q| *q.value()));
+        [w[0], w[1], w[2], v[0], v[1], v[2]]
+    }
+}
+
+/// Errors raised while mapping device input
+#[derive(Debug, Clone, PartialEq)]
+pub enum TeleopError {
+    /// A binding reads an axis the device did not report
+    MissingInput { input: usize, available: usize },
+    /// The device reported NaN or infinity
+    NonFinite { input: usize },
+}
+
+impl fmt::Display for TeleopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TeleopError::MissingInput { input, available } => {
+                write!(f, "binding reads input {} but the device reports {} axes", input, available)
+            }
+            TeleopError::NonFinite { input } => write!(f, "input {} is not finite", input),
+        }
+    }
+}
+
+impl std::error::Error for TeleopError {}
+
+/// Maps device axes to body velocity commands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Teleop {
+    /// Full-stick speed along forward, left and up
+    pub max_linear: [Velocity; 3],
+    /// Full-stick rate about forward, left and up
+    pub max_angular: [AngularVelocity; 3],
+    pub bindings: Vec<AxisBinding>,
+    /// Fraction of the limits applied, e.g. for a precision mode
+    pub gain: f64,
+}
+
+impl Teleop {
+    /// No bindings; every axis is limited to the given speed or rate
+    pub fn new(max_speed: Velocity, max_rate: AngularVelocity) -> Self {
+        Self { max_linear: [max_speed; 3], max_angular: [max_rate; 3], bindings: Vec::new(), gain: 1.0 }
+    }
+
+    pub fn with_max_speed(mut self, axis: TwistAxis, speed: Velocity) -> Self {
+        assert!(axis.is_linear(), "{:?} is a rotational axis; limit it with an angular velocity", axis);
+        self.max_linear[axis.component()] = speed;
+        self
+    }
+
+    pub fn with_max_rate(mut self, axis: TwistAxis, rate: AngularVelocity) -> Self {
+        assert!(!axis.is_linear(), "{:?} is a linear axis; limit it with a velocity", axis);
+        self.max_angular[axis.component()] = rate;
+        self
+    }
+
+    pub fn bind(mut self, input: usize, axis: TwistAxis, curve: AxisCurve) -> Self {
+        self.bindings.push(AxisBinding { input, axis, curve });
+        self
+    }
+
+    pub fn with_gain(mut self, gain: f64) -> Self {
+        self.gain = gain.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Command for one sample of device axes. Several bindings on the same body
+    /// axis add up, and the sum never exceeds that axis's limit.
+    pub fn command(&self, inputs: &[f64]) -> Result<TwistCommand, TeleopError> {
+        let mut shaped = [[0.0; 3]; 2];
+        for binding in &self.bindings {
+            let input = *inputs
+                .get(binding.input)
+                .ok_or(TeleopError::MissingInput { input: binding.input, available: inputs.len() })?;
+            if !input.is_finite() {
+                return Err(TeleopError::NonFinite { input: binding.input });
+            }
+            shaped[usize::from(!binding.axis.is_linear())][binding.axis.component()] += binding.curve.shape(input);
+        }
+
+        let scaled = |shaped: f64, limit: f64| self.gain * shaped.clamp(-1.0, 1.0) * limit;
+        Ok(TwistCommand {
+            linear: std::array::from_fn(|i| Velocity::new(scaled(shaped[0][i], *self.max_linear[i].value()))),
+            angular: std::array::from_fn(|i| AngularVelocity::new(scaled(shaped[1][i], *self.max_angular[i].value()))),
+        })
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axis_curve() {
+        let curve = AxisCurve::linear().with_deadband(0.1).with_expo(0.5);
+        assert_eq!(curve.shape(0.05), 0.0);
+        assert_eq!(curve.shape(-0.1), 0.0);
+        assert_eq!(curve.shape(1.0), 1.0);
+        assert_eq!(curve.shape(-3.0), -1.0);
+        // Halfway past the deadband: 0.5·0.5 + 0.5·0.125
+        assert!((curve.shape(0.55) - 0.3125).abs() < 1e-12);
+        assert!((curve.inverted().shape(0.55) + 0.3125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_command_scaling() {
+        let teleop = Teleop::new(Velocity::new(1.0), AngularVelocity::new(0.5))
+            .with_max_speed(TwistAxis::Heave, Velocity::new(0.3))
+            .bind(0, TwistAxis::Surge, AxisCurve::linear())
+            .bind(1, TwistAxis::Yaw, AxisCurve::linear().inverted())
+            .bind(2, TwistAxis::Heave, AxisCurve::linear())
+            .bind(3, TwistAxis::Heave, AxisCurve::linear());
+
+        let command = teleop.command(&[0.5, 1.0, 0.8, 0.8]).unwrap();
+        assert_eq!(*command.linear[0].value(), 0.5);
+        assert_eq!(*command.angular[2].value(), -0.5);
+        // Two triggers on heave saturate at its own limit
+        assert_eq!(*command.linear[2].value(), 0.3);
+        assert_eq!(command.to_array(), [0.0, 0.0, -0.5, 0.5, 0.0, 0.3]);
+
+        let slow = teleop.clone().with_gain(0.2).command(&[1.0, 0.0, 0.0, 0.0]).unwrap();
+        assert!((slow.linear[0].value() - 0.2).abs() < 1e-12);
+
+        assert_eq!(teleop.command(&[0.0, 0.0]), Err(TeleopError::MissingInput { input: 2, available: 2 }));
+        assert_eq!(teleop.command(&[f64::NAN, 0.0, 0.0, 0.0]), Err(TeleopError::NonFinite { input: 0 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "rotational axis")]
+    fn test_linear_limit_on_rotational_axis() {
+        let _ = Teleop::new(Velocity::new(1.0), AngularVelocity::new(1.0)).with_max_speed(TwistAxis::Yaw, Velocity::new(1.0));
+    }
+}
@@ -0,0 +1,337 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Lumped-mass tethers for marine vehicles
+//!
+//! The tether between a ship's fairlead and a vehicle's attachment point is cut
+//! into equal elastic segments. Each interior node carries one segment's weight
+//! in water and the normal drag of the current on the segment beyond it; the
+//! two ends take half a segment each. At equilibrium the tension changes from
+//! node to node by exactly the node load, so the whole shape follows from the
+//! pull at the vehicle end. [`Tether::solve`] shoots from the vehicle with a
+//! guessed pull and corrects it by Newton's method until the far end lands on
+//! the fairlead, warm-starting from the last solution so calling it every
+//! control step is cheap once the tether has settled.
+//!
+//! The world frame has z pointing up with the surface at z = 0; the current is
+//! given in m/s in that frame. Buoyancy and drag use the density of the
+//! tether's [`WaterColumn`] at each node's depth.
+
+use serde::{Deserialize, Serialize};
+use crate::grasp::Wrench;
+use crate::linalg::{self, square, Vector3};
+use crate::motor::Motor;
+use crate::si_units::{marine, Force, Length, Quantity, Volume, TAU};
+use crate::water_column::WaterColumn;
+
+/// Mass per unit length (kg/m)
+pub type LinearDensity<T = f64> = Quantity<T, 1, -1, 0, 0, 0, 0, 0>;
+
+/// Newton iterations before [`Tether::solve`] gives up
+pub const MAX_TETHER_ITERATIONS: usize = 50;
+
+/// Gap between the far end and the fairlead, relative to the tether length,
+/// accepted as equilibrium
+pub const CLOSURE_TOLERANCE: f64 = 1e-9;
+
+/// Physical description of a tether
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TetherSpec {
+    /// Unstretched length
+    pub length: Length,
+    pub diameter: Length,
+    /// Mass per metre in air
+    pub linear_density: LinearDensity,
+    /// Axial stiffness EA
+    pub axial_stiffness: Force,
+    /// Drag coefficient of the cable in cross flow, about 1.2 for a smooth cylinder
+    pub drag_coefficient: f64,
+    pub segments: usize,
+}
+
+impl TetherSpec {
+    pub fn new(length: Length, diameter: Length, linear_density: LinearDensity, axial_stiffness: Force) -> Self {
+        Self { length, diameter, linear_density, axial_stiffness, drag_coefficient: 1.2, segments: 20 }
+    }
+
+    /// Unstretched length of one segment
+    pub fn segment_length(&self) -> Length {
+        Length::new(self.length.value() / self.segments.max(1) as f64)
+    }
+
+    /// Weight of the whole tether in air
+    pub fn weight(&self) -> Force {
+        Force::new(self.linear_density.value() * self.length.value() * marine::gravity::<f64>().value())
+    }
+
+    /// Water displaced by the whole tether
+    pub fn displaced_volume(&self) -> Volume {
+        let area = TAU / 8.0 * self.diameter.value() * self.diameter.value();
+        Volume::new(area * self.length.value())
+    }
+
+    /// Weight minus buoyancy of the whole tether if it all lay at `depth`;
+    /// negative for a positively buoyant tether
+    pub fn weight_in_water(&self, water: &WaterColumn, depth: Length) -> Force {
+        Force::new(self.weight().value() - water.buoyancy_force(self.displaced_volume(), depth).value())
+    }
+}
+
+/// What the tether does to both ends at equilibrium
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TetherLoad {
+    /// Force on the vehicle at its attachment point (N, world frame)
+    pub force_on_vehicle: Vector3,
+    /// Force on the ship at its fairlead (N, world frame)
+    pub force_on_ship: Vector3,
+    pub tension_at_vehicle: Force,
+    pub tension_at_ship: Force,
+    /// Attachment point on the vehicle, in the world frame
+    pub vehicle_point: Vector3,
+    /// Whether the far end closed on the fairlead within [`CLOSURE_TOLERANCE`]
+    pub converged: bool,
+    pub iterations: usize,
+}
+
+impl TetherLoad {
+    /// Force and torque on the vehicle about `point`, e.g. its center of mass
+    pub fn wrench_on_vehicle(&self, point: Vector3) -> Wrench {
+        let arm = linalg::sub(self.vehicle_point, point);
+        Wrench::new(self.force_on_vehicle, linalg::cross(arm, self.force_on_vehicle))
+    }
+}
+
+/// A tether and its last equilibrium shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tether {
+    pub spec: TetherSpec,
+    pub water: WaterColumn,
+    /// Node positions from the fairlead to the vehicle; empty before the first solve
+    nodes: Vec<Vector3>,
+    /// Tension vector of the last segment, pulling the vehicle towards the ship
+    pull: Option<Vector3>,
+}
+
+impl Tether {
+    pub fn new(spec: TetherSpec) -> Self {
+        Self { spec, water: WaterColumn::default(), nodes: Vec::new(), pull: None }
+    }
+
+    pub fn with_water(mut self, water: WaterColumn) -> Self {
+        self.water = water;
+        self
+    }
+
+    /// Node positions of the last solution, from the fairlead to the vehicle
+    pub fn nodes(&self) -> &[Vector3] {
+        &self.nodes
+    }
+
+    /// Settle the tether between `fairlead` on the ship and `attachment` on the
+    /// vehicle (both in body coordinates) in a uniform `current` (m/s).
+    ///
+    /// A neutrally buoyant tether in still water has no equilibrium while it is
+    /// slack; the load then reports `converged: false`.
+    pub fn solve(&mut self, ship: &Motor, fairlead: Vector3, vehicle: &Motor, attachment: Vector3, current: Vector3) -> TetherLoad {
+        self.solve_between(ship.apply_point(fairlead), vehicle.apply_point(attachment), current)
+    }
+
+    /// [`Tether::solve`] with both ends already in the world frame
+    pub fn solve_between(&mut self, ship: Vector3, vehicle: Vector3, current: Vector3) -> TetherLoad {
+        let total = linalg::scale(self.segment_load(vehicle, [0.0; 3], [0.0; 3]), self.spec.segments.max(1) as f64);
+        let mut pull = self.pull.unwrap_or_else(|| {
+            // Lean towards the ship, with half the weight hanging off each end
+            let towards = linalg::sub(ship, vehicle);
+            let towards = linalg::scale(towards, linalg::norm(total) / linalg::norm(towards).max(f64::MIN_POSITIVE));
+            linalg::add(towards, linalg::scale(total, 0.5))
+        });
+        let gap = |pull: Vector3| linalg::sub(self.walk(vehicle, pull, current).0[0], ship);
+
+        let tolerance = CLOSURE_TOLERANCE * self.spec.length.value();
+        let mut residual = gap(pull);
+        let mut converged = false;
+        let mut iterations = 0;
+        while iterations < MAX_TETHER_ITERATIONS {
+            if linalg::norm(residual) <= tolerance {
+                converged = true;
+                break;
+            }
+            iterations += 1;
+
+            // Forward-difference Jacobian of the far end with respect to the pull
+            let h = 1e-7 * linalg::norm(pull).max(linalg::norm(total)).max(1.0);
+            let mut jacobian = [[0.0; 3]; 3];
+            for axis in 0..3 {
+                let mut nudged = pull;
+                nudged[axis] += h;
+                let column = linalg::scale(linalg::sub(gap(nudged), residual), 1.0 / h);
+                for (row, value) in jacobian.iter_mut().zip(column) {
+                    row[axis] = value;
+                }
+            }
+            let Some(inverse) = square::inverse(&jacobian) else {
+                break;
+            };
+            let step = linalg::scale(square::mul_vec(&inverse, &residual), -1.0);
+
+            // Halve the step until the gap shrinks
+            let mut fraction = 1.0;
+            let improved = loop {
+                let candidate = linalg::add(pull, linalg::scale(step, fraction));
+                let candidate_residual = gap(candidate);
+                if linalg::norm(candidate_residual) < linalg::norm(residual) {
+                    break Some((candidate, candidate_residual));
+                }
+                fraction *= 0.5;
+                if fraction < 1e-6 {
+                    break None;
+                }
+            };
+            let Some((next, next_residual)) = improved else {
+                break;
+            };
+            pull = next;
+            residual = next_residual;
+        }
+
+        let (nodes, ship_pull) = self.walk(vehicle, pull, current);
+        self.nodes = nodes;
+        self.pull = converged.then_some(pull);
+
+        let segments = self.nodes.len() - 1;
+        let half_load = |from: usize, to: usize| {
+            let along = linalg::sub(self.nodes[to], self.nodes[from]);
+            linalg::scale(self.segment_load(self.nodes[from], along, current), 0.5)
+        };
+        TetherLoad {
+            force_on_vehicle: linalg::add(pull, half_load(segments, segments - 1)),
+            force_on_ship: linalg::sub(half_load(0, 1), ship_pull),
+            tension_at_vehicle: Force::new(linalg::norm(pull)),
+            tension_at_ship: Force::new(linalg::norm(ship_pull)),
+            vehicle_point: vehicle,
+            converged,
+            iterations,
+        }
+    }
+
+    /// Net weight plus drag of one segment at `node` lying along `along`
+    fn segment_load(&self, node: Vector3, along: Vector3, current: Vector3) -> Vector3 {
+        let depth = Length::new(-node[2]);
+        let segments = self.spec.segments.max(1) as f64;
+        let weight = self.spec.weight_in_water(&self.water, depth).value() / segments;
+        let normal_flow = match linalg::norm(along) {
+            l if l > 0.0 => {
+                let tangent = linalg::scale(along, 1.0 / l);
+                linalg::sub(current, linalg::scale(tangent, linalg::dot(current, tangent)))
+            }
+            _ => current,
+        };
+        let projected_area = self.spec.diameter.value() * self.spec.segment_length().value();
+        let coefficient = 0.5 * self.water.density(depth).value() * self.spec.drag_coefficient * projected_area;
+        linalg::add([0.0, 0.0, -weight], linalg::scale(normal_flow, coefficient * linalg::norm(normal_flow)))
+    }
+
+    /// Walk from the vehicle to the ship, each segment lying along its tension
+    /// and stretched by it. Returns the nodes from the ship end and the tension
+    /// of the first segment, pulling towards the ship.
+    fn walk(&self, vehicle: Vector3, pull: Vector3, current: Vector3) -> (Vec<Vector3>, Vector3) {
+        let segments = self.spec.segments.max(1);
+        let rest = *self.spec.segment_length().value();
+        let stiffness = *self.spec.axial_stiffness.value();
+        let mut nodes = vec![vehicle; segments + 1];
+        let mut tension = pull;
+        for k in (0..segments).rev() {
+            let magnitude = linalg::norm(tension);
+            // An unloaded segment hangs the way its weight pulls
+            let direction = if magnitude > 0.0 {
+                linalg::scale(tension, 1.0 / magnitude)
+            } else {
+                let weight = self.segment_load(nodes[k + 1], [0.0; 3], [0.0; 3]);
+                linalg::scale(weight, 1.0 / linalg::norm(weight).max(f64::MIN_POSITIVE))
+            };
+            let along = linalg::scale(direction, rest * (1.0 + magnitude / stiffness));
+            nodes[k] = linalg::add(nodes[k + 1], along);
+            if k > 0 {
+                tension = linalg::sub(tension, self.segment_load(nodes[k], along, current));
+            }
+        }
+        (nodes, tension)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 100 m of 20 mm cable at 0.6 kg/m with EA = 200 kN
+    fn umbilical() -> TetherSpec {
+        TetherSpec::new(Length::new(100.0), Length::new(0.02), LinearDensity::new(0.6), Force::new(2e5))
+    }
+
+    #[test]
+    fn test_spec_quantities() {
+        let spec = umbilical();
+        assert!((spec.weight().value() - 0.6 * 100.0 * 9.81).abs() < 1e-9);
+        let displaced = TAU / 8.0 * 0.02 * 0.02 * 100.0;
+        assert!((spec.displaced_volume().value() - displaced).abs() < 1e-12);
+        let water = WaterColumn::default();
+        let surface = *spec.weight_in_water(&water, Length::new(0.0)).value();
+        assert!((surface - (spec.weight().value() - water.density(Length::new(0.0)).value() * 9.81 * displaced)).abs() < 1e-9);
+        // Deeper water is denser and lifts a little more
+        assert!(surface > 0.0 && *spec.weight_in_water(&water, Length::new(1000.0)).value() < surface);
+        assert!((spec.segment_length().value() - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_hanging_tether_balances_its_weight() {
+        let spec = umbilical();
+        let mut tether = Tether::new(spec);
+        // Vehicle 60 m away and 40 m deeper, with plenty of slack
+        let load = tether.solve_between([0.0; 3], [60.0, 0.0, -40.0], [0.0; 3]);
+        assert!(load.converged, "{} iterations", load.iterations);
+
+        // The two ends carry the whole weight in water between them
+        let total = linalg::add(load.force_on_ship, load.force_on_vehicle);
+        let nodes = tether.nodes();
+        let node_weight = |k: usize| *spec.weight_in_water(&tether.water, Length::new(-nodes[k][2])).value() / 20.0;
+        let weight = (1..20).map(node_weight).sum::<f64>() + 0.5 * (node_weight(0) + node_weight(20));
+        assert!(linalg::norm(linalg::add(total, [0.0, 0.0, weight])) < 1e-6);
+        // The tether pulls the vehicle back towards the ship and down
+        assert!(load.force_on_vehicle[0] < 0.0 && load.force_on_vehicle[2] < 0.0);
+        assert!(load.force_on_vehicle[1].abs() < 1e-9);
+        // Horizontal tension is constant along a catenary
+        assert!((load.force_on_ship[0] + load.force_on_vehicle[0]).abs() < 1e-3);
+        assert!(load.tension_at_ship > load.tension_at_vehicle);
+
+        let length: f64 = nodes.windows(2).map(|p| linalg::norm(linalg::sub(p[1], p[0]))).sum();
+        assert!((length - 100.0).abs() < 0.1);
+        assert!(nodes.iter().all(|n| n[1].abs() < 1e-9));
+
+        // Warm starts settle quickly after a small move
+        let again = tether.solve_between([0.0; 3], [60.5, 0.0, -40.0], [0.0; 3]);
+        assert!(again.converged && again.iterations < load.iterations.max(2));
+    }
+
+    #[test]
+    fn test_current_and_poses() {
+        let spec = umbilical();
+        let ship = Motor::from_translation([0.0, 0.0, 0.0]);
+        let vehicle = Motor::from_translation([30.0, 0.0, -50.0]);
+        let still = Tether::new(spec).solve(&ship, [0.0; 3], &vehicle, [0.0, 0.0, 0.5], [0.0; 3]);
+        let drift = Tether::new(spec).solve(&ship, [0.0; 3], &vehicle, [0.0, 0.0, 0.5], [0.0, 1.0, 0.0]);
+        assert!(still.converged && drift.converged);
+        assert_eq!(still.vehicle_point, [30.0, 0.0, -49.5]);
+
+        // A cross current drags the vehicle downstream
+        assert!(drift.force_on_vehicle[1] > 1.0);
+        let total = linalg::add(drift.force_on_ship, drift.force_on_vehicle);
+        assert!(total[1] > 0.0);
+
+        // Pulling on a point above the center of mass pitches the vehicle
+        let wrench = still.wrench_on_vehicle([30.0, 0.0, -50.0]);
+        assert!(linalg::norm(wrench.torque) > 0.0);
+        assert!(linalg::dot(wrench.torque, [0.0, 0.0, 0.5]).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Tools and payloads carried at the tip of a chain
+//!
+//! A [`Tool`] is mounted on the flange, the frame after the last segment of a
+//! [`KinematicChain`]. Its tool centre point (TCP) is a fixed motor from the
+//! flange, and once the tool is attached [`KinematicChain::forward`] and
+//! [`KinematicChain::jacobian`] refer to the TCP instead of the flange. The
+//! tool's own mass and any payloads it holds are kept as named parts, so a
+//! gripper can pick up and release objects without re-entering the rest, and
+//! the self-collision model gains a capsule from the flange to the TCP.
+//!
+//! Swapping grippers is [`KinematicChain::set_tool`], which hands back the
+//! tool that was mounted before.
+//!
+//! [`KinematicChain`]: crate::kinematics::KinematicChain
+//! [`KinematicChain::forward`]: crate::kinematics::KinematicChain::forward
+//! [`KinematicChain::jacobian`]: crate::kinematics::KinematicChain::jacobian
+//! [`KinematicChain::set_tool`]: crate::kinematics::KinematicChain::set_tool
+
+use serde::{Deserialize, Serialize};
+
+use crate::dynamics::{CompositeBody, Inertia};
+use crate::motor::Motor;
+use crate::si_units::{Length, Mass};
+
+/// Name of the tool's own part in [`Tool::body`]
+pub const TOOL_PART: &str = "tool";
+
+/// End-effector tool with its TCP, mass properties and collision radius
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tool {
+    pub name: String,
+    /// Tool centre point in the flange frame
+    pub tcp: Motor,
+    /// Radius of the capsule from the flange to the TCP
+    pub radius: Length,
+    /// Mass properties of the tool in the flange frame
+    inertia: Option<Inertia>,
+    /// Held objects in the flange frame, by name
+    payloads: Vec<(String, Inertia)>,
+}
+
+impl Tool {
+    pub fn new(name: &str, tcp: Motor, radius: Length) -> Self {
+        Self { name: name.to_string(), tcp, radius: Length::new(radius.value().abs()), inertia: None, payloads: Vec::new() }
+    }
+
+    /// The tool's own mass properties, given in the flange frame
+    pub fn with_inertia(mut self, inertia: Inertia) -> Self {
+        self.inertia = Some(inertia);
+        self
+    }
+
+    pub fn inertia(&self) -> Option<&Inertia> {
+        self.inertia.as_ref()
+    }
+
+    /// Pick up a payload given in the TCP frame, returning the one it replaces under the same name
+    pub fn attach_payload(&mut self, name: &str, payload: Inertia) -> Option<Inertia> {
+        let replaced = self.release_payload(name);
+        self.payloads.push((name.to_string(), payload.transform(&self.tcp)));
+        replaced
+    }
+
+    /// Drop a payload, returning it in the TCP frame
+    pub fn release_payload(&mut self, name: &str) -> Option<Inertia> {
+        let index = self.payloads.iter().position(|(payload, _)| payload == name)?;
+        let (_, payload) = self.payloads.remove(index);
+        Some(payload.transform(&self.tcp.inverse()))
+    }
+
+    /// Names of the held payloads
+    pub fn payloads(&self) -> impl Iterator<Item = &str> + '_ {
+        self.payloads.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// The tool and its payloads as parts of one body in the flange frame
+    pub fn body(&self) -> CompositeBody {
+        let mut body = CompositeBody::new();
+        if let Some(inertia) = self.inertia {
+            body.insert(TOOL_PART, inertia);
+        }
+        for (name, payload) in &self.payloads {
+            body.insert(name, *payload);
+        }
+        body
+    }
+
+    /// Total mass of the tool and its payloads
+    pub fn mass(&self) -> Mass {
+        self.body().mass()
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinematics::test_fixtures::{full_turn, planar, Z};
+    use crate::linalg;
+    use crate::motor::Rotor;
+    use crate::self_collision::SelfCollisionModel;
+    use crate::si_units::Acceleration;
+
+    fn gripper() -> Tool {
+        Tool::new("gripper", Motor::from_translation([0.1, 0.0, 0.0]), Length::new(0.03))
+            .with_inertia(Inertia::point_mass(Mass::new(1.0), [0.05, 0.0, 0.0]))
+    }
+
+    #[test]
+    fn test_tool_moves_forward_kinematics_and_jacobian() {
+        let mut chain = planar(&[0.5, 0.4]);
+        let q = [0.3, 0.6];
+        let flange = chain.forward(&q).unwrap();
+        assert_eq!(chain.set_tool(Some(gripper())), None);
+
+        let tcp = chain.forward(&q).unwrap();
+        assert_eq!(chain.flange(&q).unwrap(), flange);
+        assert!(linalg::norm(linalg::sub(tcp.translation, (flange * gripper().tcp).translation)) < 1e-12);
+        assert!((linalg::norm(chain.forward(&[0.0, 0.0]).unwrap().translation) - 1.0).abs() < 1e-12);
+
+        // The linear part of the first column is the TCP velocity about the base axis
+        let column = chain.jacobian(&q).unwrap().columns[0];
+        let expected = linalg::cross(Z, tcp.translation);
+        assert!(linalg::norm(linalg::sub([column[3], column[4], column[5]], expected)) < 1e-12);
+
+        // Swapping tools hands the old one back
+        let probe = Tool::new("probe", Motor::new([0.2, 0.0, 0.0], Rotor::identity()), Length::new(0.01));
+        assert_eq!(chain.set_tool(Some(probe)).map(|tool| tool.name), Some("gripper".to_string()));
+        assert!((linalg::norm(chain.forward(&[0.0, 0.0]).unwrap().translation) - 1.1).abs() < 1e-12);
+        assert!(chain.set_tool(None).is_some());
+        assert_eq!(chain.forward(&q).unwrap(), flange);
+    }
+
+    #[test]
+    fn test_analytic_solver_reaches_tool_target() {
+        use crate::inverse_kinematics::{IkSolverRegistry, Planar2R};
+
+        let solver = Planar2R::new(0.5, 0.4);
+        let chain = solver.chain(full_turn()).with_tool(gripper());
+        let target = chain.forward(&[0.4, 0.9]).unwrap();
+        let solutions = IkSolverRegistry::new().with_solver("planar_2r", solver).solve(&chain, &target, &[0.3, 1.0]).unwrap();
+        assert_eq!(solutions[0].iterations, 0);
+        assert!((solutions[0].q[0] - 0.4).abs() < 1e-9 && (solutions[0].q[1] - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_payload_updates_mass_and_holding_torques() {
+        let mut tool = gripper();
+        assert!((tool.mass().value() - 1.0).abs() < 1e-12);
+        let part = Inertia::point_mass(Mass::new(2.0), [0.0, 0.0, 0.0]);
+        assert_eq!(tool.attach_payload("part", part), None);
+        assert!((tool.mass().value() - 3.0).abs() < 1e-12);
+        assert_eq!(tool.payloads().collect::<Vec<_>>(), vec!["part"]);
+        let center = tool.body().inertia().unwrap().center_of_mass();
+        assert!(linalg::norm(linalg::sub(center, [(0.05 + 2.0 * 0.1) / 3.0, 0.0, 0.0])) < 1e-12);
+
+        // Gravity along -y pulls on the stretched arm; the shoulder holds the full moment
+        let chain = planar(&[0.5, 0.4]).with_tool(tool.clone());
+        let gravity = [Acceleration::new(0.0), Acceleration::new(-9.81), Acceleration::new(0.0)];
+        let torques = chain.tool_holding_torques(&[0.0, 0.0], gravity).unwrap();
+        let moment = 9.81 * (1.0 * 0.95 + 2.0 * 1.0);
+        assert!((torques[0] - moment).abs() < 1e-9, "{:?}", torques);
+        assert!((torques[1] - 9.81 * (1.0 * 0.45 + 2.0 * 0.5)).abs() < 1e-9);
+
+        let released = tool.release_payload("part").unwrap();
+        assert!(linalg::norm(released.center_of_mass()) < 1e-12);
+        assert!((tool.mass().value() - 1.0).abs() < 1e-12);
+        assert_eq!(planar(&[0.5, 0.4]).tool_holding_torques(&[0.0, 0.0], gravity).unwrap(), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_tool_joins_self_collision_model() {
+        let chain = planar(&[0.5, 0.5, 0.2]);
+        let model = SelfCollisionModel::new(&chain, Length::new(0.05));
+        let q = [0.0, 2.6, -3.1];
+        assert_eq!(model.links(&chain, &q).unwrap().len(), 4);
+        assert!(model.validate(&chain, &q).is_ok());
+
+        // A long tool reaches back over the first link at the same joint angles
+        let chain = chain.with_tool(Tool::new("lance", Motor::from_translation([0.6, 0.0, 0.0]), Length::new(0.05)));
+        let links = model.links(&chain, &q).unwrap();
+        assert_eq!(links.len(), 5);
+        assert_eq!(links[4].end, chain.forward(&q).unwrap().translation);
+        let contacts = model.contacts(&chain, &q).unwrap();
+        assert!(contacts.iter().any(|contact| contact.links == (1, 4)));
+        assert!(contacts.iter().all(|contact| contact.links.1 == 4));
+    }
+}
@@ -0,0 +1,528 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Time-optimal path parameterization by reachability analysis (TOPP-RA)
+//!
+//! A [`GeometricPath`] gives the joint coordinates `q(s)` along a path
+//! parameter `s ∈ [0, L]`, with the derivatives `q'(s)` and `q''(s)`. Timing
+//! the path means choosing `s(t)`; then `q̇ = q' ṡ` and `q̈ = q' s̈ + q'' ṡ²`, so
+//! in the variables `x = ṡ²` and `u = s̈` every velocity limit is a bound on `x`
+//! and every acceleration limit a linear constraint on `(u, x)`.
+//!
+//! [`time_optimal`] discretizes `s` on a grid, where `x` advances as
+//! `x' = x + 2 Δs u`. A backward pass computes, at every gridpoint, the
+//! interval of `x` from which the end can still be reached within the limits;
+//! a forward pass then takes the largest admissible `u` at each gridpoint
+//! while staying inside the next interval. Each step is a two-variable linear
+//! program, solved exactly by clipping a polygon. Limits hold at the
+//! gridpoints; between them the acceleration is constant in `s̈`, so a finer
+//! grid follows the limits more closely.
+//!
+//! The result is a [`Trajectory`] sampled by time, whose points carry the
+//! coordinates, velocities and accelerations a tracking controller expects;
+//! [`Trajectory::resample`] produces them at the period of a control loop.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::joints::Joint;
+use crate::kinematics::KinematicChain;
+use crate::si_units::{Acceleration, AngularVelocity, Measure, Time, Velocity};
+use crate::signal::{AngularAcceleration, TimeDerivative};
+use crate::spline::BSpline;
+
+/// Largest `ṡ²` considered, for gridpoints where no limit bounds the path speed
+const MAX_RATE_SQUARED: f64 = 1e12;
+
+/// Paths and limits that cannot be timed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToppError {
+    TooFewWaypoints(usize),
+    /// Two consecutive waypoints coincide, so the path has no direction there
+    RepeatedWaypoint(usize),
+    /// Waypoints or limits do not match the coordinates of the path
+    DofMismatch { expected: usize, given: usize },
+    /// A limit is not positive
+    InvalidLimit(usize),
+    /// Fewer than two gridpoints
+    InvalidGrid,
+    /// No timing from the requested start rate reaches the end within the limits
+    Infeasible,
+}
+
+impl fmt::Display for ToppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToppError::TooFewWaypoints(given) => write!(f, "a path needs two waypoints, got {}", given),
+            ToppError::RepeatedWaypoint(index) => write!(f, "waypoint {} repeats the one before it", index),
+            ToppError::DofMismatch { expected, given } => write!(f, "expected {} coordinates, got {}", expected, given),
+            ToppError::InvalidLimit(index) => write!(f, "limit of coordinate {} is not positive", index),
+            ToppError::InvalidGrid => write!(f, "time parameterization needs at least two gridpoints"),
+            ToppError::Infeasible => write!(f, "the path cannot be followed within the limits"),
+        }
+    }
+}
+
+impl std::error::Error for ToppError {}
+
+/// Velocity and acceleration limit of one coordinate, symmetric about zero
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CoordinateLimit {
+    Angular { velocity: AngularVelocity, acceleration: AngularAcceleration },
+    Linear { velocity: Velocity, acceleration: Acceleration },
+}
+
+impl CoordinateLimit {
+    pub fn angular(velocity: AngularVelocity, acceleration: AngularAcceleration) -> Self {
+        CoordinateLimit::Angular { velocity, acceleration }
+    }
+
+    pub fn linear(velocity: Velocity, acceleration: Acceleration) -> Self {
+        CoordinateLimit::Linear { velocity, acceleration }
+    }
+
+    /// Velocity and acceleration in SI units
+    fn raw(&self) -> (f64, f64) {
+        match self {
+            CoordinateLimit::Angular { velocity, acceleration } => (*velocity.value(), acceleration.raw()),
+            CoordinateLimit::Linear { velocity, acceleration } => (*velocity.value(), *acceleration.value()),
+        }
+    }
+
+    /// Limits of every coordinate of `chain`: the joints' own velocity limits
+    /// and one acceleration limit per kind of coordinate
+    pub fn for_chain(chain: &KinematicChain, angular: AngularAcceleration, linear: Acceleration) -> Vec<CoordinateLimit> {
+        chain
+            .joints()
+            .flat_map(|joint| match joint {
+                Joint::Revolute { limits, .. } => vec![Self::angular(limits.max_velocity, angular)],
+                Joint::Prismatic { limits, .. } => vec![Self::linear(limits.max_velocity, linear)],
+                Joint::Spherical { limits } => vec![Self::angular(limits.max_velocity, angular); 3],
+                Joint::Fixed => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+/// Joint coordinates along a path parameter starting at zero
+pub trait GeometricPath {
+    /// Number of coordinates
+    fn dof(&self) -> usize;
+    /// End of the path parameter
+    fn length(&self) -> f64;
+    fn point(&self, s: f64) -> Vec<f64>;
+    /// `dq/ds`
+    fn tangent(&self, s: f64) -> Vec<f64>;
+    /// `d²q/ds²`
+    fn curvature(&self, s: f64) -> Vec<f64>;
+}
+
+/// Natural cubic spline through joint-space waypoints, parametrized by the
+/// cumulative distance between them in coordinate space
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JointPath {
+    /// Path parameter at each waypoint
+    breaks: Vec<f64>,
+    /// Per segment, per coordinate: `a + b d + c d² + e d³` with `d = s - breaks[k]`
+    segments: Vec<Vec<[f64; 4]>>,
+}
+
+impl JointPath {
+    pub fn through(waypoints: &[Vec<f64>]) -> Result<Self, ToppError> {
+        if waypoints.len() < 2 {
+            return Err(ToppError::TooFewWaypoints(waypoints.len()));
+        }
+        let dof = waypoints[0].len();
+        if let Some(other) = waypoints.iter().find(|waypoint| waypoint.len() != dof) {
+            return Err(ToppError::DofMismatch { expected: dof, given: other.len() });
+        }
+        let mut breaks = vec![0.0];
+        for (k, pair) in waypoints.windows(2).enumerate() {
+            let step = pair[0].iter().zip(&pair[1]).map(|(a, b)| (b - a) * (b - a)).sum::<f64>().sqrt();
+            if step == 0.0 {
+                return Err(ToppError::RepeatedWaypoint(k + 1));
+            }
+            breaks.push(breaks[k] + step);
+        }
+
+        // Second derivatives at the waypoints, zero at both ends, by the Thomas algorithm
+        let m = waypoints.len();
+        let h: Vec<f64> = breaks.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        let mut segments = vec![Vec::with_capacity(dof); m - 1];
+        for coordinate in 0..dof {
+            let y: Vec<f64> = waypoints.iter().map(|waypoint| waypoint[coordinate]).collect();
+            let mut second = vec![0.0; m];
+            if m > 2 {
+                let n = m - 2;
+                let mut diagonal: Vec<f64> = (1..m - 1).map(|k| 2.0 * (h[k - 1] + h[k])).collect();
+                let mut rhs: Vec<f64> = (1..m - 1).map(|k| 6.0 * ((y[k + 1] - y[k]) / h[k] - (y[k] - y[k - 1]) / h[k - 1])).collect();
+                for i in 1..n {
+                    let factor = h[i] / diagonal[i - 1];
+                    diagonal[i] -= factor * h[i];
+                    rhs[i] -= factor * rhs[i - 1];
+                }
+                for i in (0..n).rev() {
+                    let next = if i + 1 < n { second[i + 2] } else { 0.0 };
+                    second[i + 1] = (rhs[i] - h[i + 1] * next) / diagonal[i];
+                }
+            }
+            for (k, segment) in segments.iter_mut().enumerate() {
+                segment.push([
+                    y[k],
+                    (y[k + 1] - y[k]) / h[k] - h[k] * (2.0 * second[k] + second[k + 1]) / 6.0,
+                    0.5 * second[k],
+                    (second[k + 1] - second[k]) / (6.0 * h[k]),
+                ]);
+            }
+        }
+        Ok(Self { breaks, segments })
+    }
+
+    /// Segment holding `s`, clamped to the path, and the offset into it
+    fn locate(&self, s: f64) -> (&[[f64; 4]], f64) {
+        let s = s.clamp(0.0, self.length());
+        let k = self.breaks[1..self.breaks.len() - 1].partition_point(|b| *b <= s);
+        (&self.segments[k], s - self.breaks[k])
+    }
+}
+
+impl GeometricPath for JointPath {
+    fn dof(&self) -> usize {
+        self.segments[0].len()
+    }
+
+    fn length(&self) -> f64 {
+        self.breaks[self.breaks.len() - 1]
+    }
+
+    fn point(&self, s: f64) -> Vec<f64> {
+        let (segment, d) = self.locate(s);
+        segment.iter().map(|[a, b, c, e]| a + d * (b + d * (c + d * e))).collect()
+    }
+
+    fn tangent(&self, s: f64) -> Vec<f64> {
+        let (segment, d) = self.locate(s);
+        segment.iter().map(|[_, b, c, e]| b + d * (2.0 * c + 3.0 * d * e)).collect()
+    }
+
+    fn curvature(&self, s: f64) -> Vec<f64> {
+        let (segment, d) = self.locate(s);
+        segment.iter().map(|[_, _, c, e]| 2.0 * c + 6.0 * d * e).collect()
+    }
+}
+
+/// A spline in time read as a path: `s` is the time since the start of its domain
+impl<Q: TimeDerivative, const N: usize> GeometricPath for BSpline<Q, N>
+where
+    Q::Rate: TimeDerivative,
+{
+    fn dof(&self) -> usize {
+        N
+    }
+
+    fn length(&self) -> f64 {
+        let (start, end) = self.domain();
+        end.value() - start.value()
+    }
+
+    fn point(&self, s: f64) -> Vec<f64> {
+        self.value(Time::new(self.domain().0.value() + s)).iter().map(|q| q.raw()).collect()
+    }
+
+    fn tangent(&self, s: f64) -> Vec<f64> {
+        self.rate(Time::new(self.domain().0.value() + s)).iter().map(|q| q.raw()).collect()
+    }
+
+    fn curvature(&self, s: f64) -> Vec<f64> {
+        self.derivative().rate(Time::new(self.domain().0.value() + s)).iter().map(|q| q.raw()).collect()
+    }
+}
+
+/// Settings of [`time_optimal`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToppOptions {
+    /// Gridpoints along the path, ends included
+    pub gridpoints: usize,
+    /// Path rate `ṡ` at the start and at the end
+    pub start_rate: f64,
+    pub end_rate: f64,
+}
+
+impl Default for ToppOptions {
+    fn default() -> Self {
+        Self { gridpoints: 200, start_rate: 0.0, end_rate: 0.0 }
+    }
+}
+
+/// Path parameter and its rates at one gridpoint
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProfilePoint {
+    pub time: Time,
+    pub s: f64,
+    /// `ṡ`
+    pub rate: f64,
+    /// `s̈` until the next gridpoint
+    pub acceleration: f64,
+}
+
+/// Coordinates, velocities and accelerations at one instant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrajectoryPoint {
+    pub time: Time,
+    pub q: Vec<f64>,
+    pub dq: Vec<f64>,
+    pub ddq: Vec<f64>,
+}
+
+/// A path with its time-optimal timing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory<P> {
+    path: P,
+    profile: Vec<ProfilePoint>,
+}
+
+impl<P: GeometricPath> Trajectory<P> {
+    pub fn path(&self) -> &P {
+        &self.path
+    }
+
+    pub fn profile(&self) -> &[ProfilePoint] {
+        &self.profile
+    }
+
+    pub fn duration(&self) -> Time {
+        self.profile[self.profile.len() - 1].time
+    }
+
+    /// State at `t`, clamped to the duration
+    pub fn sample(&self, t: Time) -> TrajectoryPoint {
+        let t = t.value().clamp(0.0, *self.duration().value());
+        let k = self.profile[1..self.profile.len() - 1].partition_point(|point| *point.time.value() <= t);
+        let (here, next) = (&self.profile[k], &self.profile[k + 1]);
+        let tau = t - here.time.value();
+        let s = (here.s + here.rate * tau + 0.5 * here.acceleration * tau * tau).clamp(here.s, next.s);
+        let rate = (here.rate + here.acceleration * tau).max(0.0);
+
+        let tangent = self.path.tangent(s);
+        let curvature = self.path.curvature(s);
+        TrajectoryPoint {
+            time: Time::new(t),
+            q: self.path.point(s),
+            dq: tangent.iter().map(|d| d * rate).collect(),
+            ddq: tangent.iter().zip(&curvature).map(|(d, c)| d * here.acceleration + c * rate * rate).collect(),
+        }
+    }
+
+    /// States every `period` from the start, ending exactly at the duration
+    pub fn resample(&self, period: Time) -> Vec<TrajectoryPoint> {
+        let (period, duration) = (*period.value(), *self.duration().value());
+        let steps = (duration / period).ceil().max(1.0) as usize;
+        (0..=steps).map(|k| self.sample(Time::new((k as f64 * period).min(duration)))).collect()
+    }
+}
+
+/// Constraints `a u + b x ≤ c` on `(u, x)` at one gridpoint, and the bound on `x`
+struct Constraints {
+    rows: Vec<(f64, f64, f64)>,
+    max_x: f64,
+}
+
+impl Constraints {
+    fn at(path: &impl GeometricPath, limits: &[(f64, f64)], s: f64) -> Self {
+        let tangent = path.tangent(s);
+        let curvature = path.curvature(s);
+        let mut rows = Vec::with_capacity(2 * limits.len());
+        let mut max_x = MAX_RATE_SQUARED;
+        for ((d, c), (velocity, acceleration)) in tangent.iter().zip(&curvature).zip(limits) {
+            if *d != 0.0 {
+                max_x = max_x.min((velocity / d).powi(2));
+            }
+            rows.push((*d, *c, *acceleration));
+            rows.push((-d, -c, *acceleration));
+        }
+        Self { rows, max_x }
+    }
+}
+
+/// Clip a convex polygon to the half-plane `a x + b y ≤ c`
+fn clip(polygon: &[[f64; 2]], a: f64, b: f64, c: f64) -> Vec<[f64; 2]> {
+    let inside = |p: &[f64; 2]| a * p[0] + b * p[1] <= c;
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+    for (i, p) in polygon.iter().enumerate() {
+        let q = &polygon[(i + 1) % polygon.len()];
+        if inside(p) {
+            result.push(*p);
+        }
+        if inside(p) != inside(q) {
+            let (fp, fq) = (a * p[0] + b * p[1] - c, a * q[0] + b * q[1] - c);
+            let w = fp / (fp - fq);
+            result.push([p[0] + w * (q[0] - p[0]), p[1] + w * (q[1] - p[1])]);
+        }
+    }
+    result
+}
+
+/// Interval of `x` at a gridpoint from which some admissible `u` reaches
+/// `next` (an interval of `x` at the following gridpoint) over `step`
+fn controllable(constraints: &Constraints, next: (f64, f64), step: f64) -> Option<(f64, f64)> {
+    // In (x, y) with y = x + 2 Δs u the next value, a u + b x ≤ c becomes
+    // (b - a / 2Δs) x + (a / 2Δs) y ≤ c
+    let mut polygon = vec![[0.0, next.0], [constraints.max_x, next.0], [constraints.max_x, next.1], [0.0, next.1]];
+    for (a, b, c) in &constraints.rows {
+        let k = a / (2.0 * step);
+        polygon = clip(&polygon, b - k, k, *c);
+        if polygon.is_empty() {
+            return None;
+        }
+    }
+    let low = polygon.iter().map(|p| p[0]).fold(f64::INFINITY, f64::min);
+    let high = polygon.iter().map(|p| p[0]).fold(f64::NEG_INFINITY, f64::max);
+    Some((low.max(0.0), high))
+}
+
+/// Time-optimal timing of `path` within `limits`, one per coordinate
+pub fn time_optimal<P: GeometricPath>(path: P, limits: &[CoordinateLimit], options: &ToppOptions) -> Result<Trajectory<P>, ToppError> {
+    if limits.len() != path.dof() {
+        return Err(ToppError::DofMismatch { expected: path.dof(), given: limits.len() });
+    }
+    let limits: Vec<(f64, f64)> = limits.iter().map(CoordinateLimit::raw).collect();
+    if let Some(index) = limits.iter().position(|(v, a)| !(*v > 0.0 && *a > 0.0)) {
+        return Err(ToppError::InvalidLimit(index));
+    }
+    if options.gridpoints < 2 {
+        return Err(ToppError::InvalidGrid);
+    }
+
+    let n = options.gridpoints - 1;
+    let step = path.length() / n as f64;
+    let grid: Vec<f64> = (0..=n).map(|i| i as f64 * step).collect();
+    let constraints: Vec<Constraints> = grid.iter().map(|s| Constraints::at(&path, &limits, *s)).collect();
+
+    // Backward pass: controllable intervals of x = ṡ²
+    let end = options.end_rate * options.end_rate;
+    if end > constraints[n].max_x {
+        return Err(ToppError::Infeasible);
+    }
+    let mut sets = vec![(0.0, 0.0); n + 1];
+    sets[n] = (end, end);
+    for i in (0..n).rev() {
+        sets[i] = controllable(&constraints[i], sets[i + 1], step).ok_or(ToppError::Infeasible)?;
+    }
+
+    // Forward pass: the largest admissible u that stays controllable
+    let mut x = options.start_rate * options.start_rate;
+    let tolerance = 1e-9 * sets[0].1.max(1.0);
+    if x < sets[0].0 - tolerance || x > sets[0].1 + tolerance {
+        return Err(ToppError::Infeasible);
+    }
+    let mut profile = Vec::with_capacity(n + 1);
+    let mut time = 0.0;
+    for i in 0..n {
+        let (next_low, next_high) = sets[i + 1];
+        let (mut lower, mut upper) = ((next_low - x) / (2.0 * step), (next_high - x) / (2.0 * step));
+        let floor = lower;
+        for (a, b, c) in &constraints[i].rows {
+            let bound = (c - b * x) / a;
+            if *a > 0.0 {
+                upper = upper.min(bound);
+            } else if *a < 0.0 {
+                lower = lower.max(bound);
+            }
+        }
+        // Rounding can close the interval; staying controllable comes first
+        let u = upper.max(lower.min(upper)).max(floor);
+        let next = (x + 2.0 * step * u).max(0.0);
+        let rates = x.sqrt() + next.sqrt();
+        if rates == 0.0 {
+            return Err(ToppError::Infeasible);
+        }
+        profile.push(ProfilePoint { time: Time::new(time), s: grid[i], rate: x.sqrt(), acceleration: u });
+        time += 2.0 * step / rates;
+        x = next;
+    }
+    profile.push(ProfilePoint { time: Time::new(time), s: grid[n], rate: x.sqrt(), acceleration: 0.0 });
+    Ok(Trajectory { path, profile })
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::joints::{JointLimits, PrismaticLimits};
+    use crate::motor::Motor;
+    use crate::si_units::{Angle, Length, TAU};
+
+    fn limit(velocity: f64, acceleration: f64) -> CoordinateLimit {
+        CoordinateLimit::angular(AngularVelocity::new(velocity), AngularAcceleration::new(acceleration))
+    }
+
+    #[test]
+    fn test_straight_move_is_bang_coast_bang() {
+        let path = JointPath::through(&[vec![0.0], vec![1.0]]).unwrap();
+        let trajectory = time_optimal(path, &[limit(1.0, 2.0)], &ToppOptions::default()).unwrap();
+        // 0.5 s to reach 1 rad/s over 0.25 rad, 0.5 s coasting, 0.5 s braking
+        assert!((trajectory.duration().value() - 1.5).abs() < 1e-2, "{:?}", trajectory.duration());
+
+        let middle = trajectory.sample(Time::new(0.75));
+        assert!((middle.q[0] - 0.5).abs() < 1e-2 && (middle.dq[0] - 1.0).abs() < 1e-6);
+        let end = trajectory.sample(trajectory.duration());
+        assert!((end.q[0] - 1.0).abs() < 1e-12 && end.dq[0].abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_curved_path_respects_limits() {
+        let waypoints = vec![vec![0.0, 0.0, 0.0], vec![0.6, -0.4, 0.1], vec![1.2, 0.3, 0.05], vec![1.5, 0.8, -0.2]];
+        let path = JointPath::through(&waypoints).unwrap();
+        assert_eq!(path.point(0.0), waypoints[0]);
+        let knee = path.point(path.breaks[2]);
+        assert!(knee.iter().zip(&waypoints[2]).all(|(a, b)| (a - b).abs() < 1e-12));
+
+        let limits = [limit(1.0, 3.0), limit(0.8, 2.0), CoordinateLimit::linear(Velocity::new(0.2), Acceleration::new(1.0))];
+        let options = ToppOptions { gridpoints: 400, ..ToppOptions::default() };
+        let trajectory = time_optimal(path, &limits, &options).unwrap();
+        let raw: Vec<(f64, f64)> = limits.iter().map(CoordinateLimit::raw).collect();
+
+        let samples = trajectory.resample(Time::new(0.01));
+        for point in &samples {
+            for ((dq, ddq), (v, a)) in point.dq.iter().zip(&point.ddq).zip(&raw) {
+                assert!(dq.abs() <= v * 1.01, "{:?}", point);
+                assert!(ddq.abs() <= a * 1.05, "{:?}", point);
+            }
+        }
+        // Some limit is active almost everywhere on a time-optimal trajectory
+        let saturated = samples
+            .iter()
+            .filter(|point| point.dq.iter().zip(&point.ddq).zip(&raw).any(|((dq, ddq), (v, a))| dq.abs() > 0.98 * v || ddq.abs() > 0.9 * a))
+            .count();
+        assert!(saturated as f64 > 0.9 * samples.len() as f64, "{} of {}", saturated, samples.len());
+        let last = samples.last().unwrap();
+        assert!(last.q.iter().zip(&waypoints[3]).all(|(a, b)| (a - b).abs() < 1e-12));
+        assert!(last.dq.iter().all(|dq| dq.abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert_eq!(JointPath::through(&[vec![0.0]]), Err(ToppError::TooFewWaypoints(1)));
+        assert_eq!(JointPath::through(&[vec![0.0], vec![0.0]]), Err(ToppError::RepeatedWaypoint(1)));
+        assert_eq!(JointPath::through(&[vec![0.0], vec![1.0, 2.0]]), Err(ToppError::DofMismatch { expected: 1, given: 2 }));
+
+        let path = JointPath::through(&[vec![0.0, 0.0], vec![1.0, 1.0]]).unwrap();
+        let result = time_optimal(path.clone(), &[limit(1.0, 1.0)], &ToppOptions::default());
+        assert_eq!(result.unwrap_err(), ToppError::DofMismatch { expected: 2, given: 1 });
+        let result = time_optimal(path.clone(), &[limit(1.0, 1.0), limit(0.0, 1.0)], &ToppOptions::default());
+        assert_eq!(result.unwrap_err(), ToppError::InvalidLimit(1));
+
+        // Starting faster than the velocity limit allows
+        let fast = ToppOptions { start_rate: 10.0, ..ToppOptions::default() };
+        assert_eq!(time_optimal(path, &[limit(1.0, 1.0), limit(1.0, 1.0)], &fast).unwrap_err(), ToppError::Infeasible);
+
+        // Chain limits expand spherical joints to three coordinates
+        let chain = KinematicChain::new()
+            .with_joint(Motor::identity(), Joint::revolute([0.0, 0.0, 1.0], JointLimits::new(Angle::new(-TAU), Angle::new(TAU), AngularVelocity::new(2.0))))
+            .with_joint(Motor::identity(), Joint::prismatic([1.0, 0.0, 0.0], PrismaticLimits::new(Length::new(0.0), Length::new(1.0), Velocity::new(0.3))));
+        let limits = CoordinateLimit::for_chain(&chain, AngularAcceleration::new(5.0), Acceleration::new(1.0));
+        assert_eq!(limits, vec![limit(2.0, 5.0), CoordinateLimit::linear(Velocity::new(0.3), Acceleration::new(1.0))]);
+    }
+}
@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Homogeneous 4×4 transforms
+//!
+//! [`Transform3`] is the matrix form of a rigid (or general affine) transform for
+//! code that exchanges poses with matrix-based libraries. Converting a motor is
+//! exact; converting back requires the upper-left block to be a proper rotation
+//! and the bottom row to be `[0, 0, 0, 1]`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use crate::linalg::{self, square, Matrix3, Vector3};
+use crate::motor::{Motor, Rotor};
+
+/// Largest deviation of `RᵀR` from the identity accepted as a rotation
+pub const RIGIDITY_TOLERANCE: f64 = 1e-6;
+
+/// Row-major homogeneous transform acting on column vectors `[x, y, z, 1]ᵀ`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transform3 {
+    pub matrix: [[f64; 4]; 4],
+}
+
+impl Default for Transform3 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform3 {
+    pub fn new(matrix: [[f64; 4]; 4]) -> Self {
+        Self { matrix }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(square::identity::<4>())
+    }
+
+    pub fn from_rotation_translation(rotation: &Matrix3, translation: Vector3) -> Self {
+        let mut matrix = square::identity::<4>();
+        for i in 0..3 {
+            matrix[i][..3].copy_from_slice(&rotation[i]);
+            matrix[i][3] = translation[i];
+        }
+        Self::new(matrix)
+    }
+
+    /// Upper-left 3×3 block
+    pub fn rotation(&self) -> Matrix3 {
+        std::array::from_fn(|i| [self.matrix[i][0], self.matrix[i][1], self.matrix[i][2]])
+    }
+
+    pub fn translation(&self) -> Vector3 {
+        [self.matrix[0][3], self.matrix[1][3], self.matrix[2][3]]
+    }
+
+    pub fn from_motor(motor: &Motor) -> Self {
+        Self::from_rotation_translation(&motor.rotor.to_rotation_matrix(), motor.translation)
+    }
+
+    /// The motor with the same action; fails unless the transform is rigid
+    pub fn to_motor(&self) -> Result<Motor, TransformError> {
+        self.check_rigid()?;
+        Ok(Motor::new(self.translation(), Rotor::from_rotation_matrix(&self.rotation())))
+    }
+
+    /// Whether the transform is a proper rigid motion
+    pub fn is_rigid(&self) -> bool {
+        self.check_rigid().is_ok()
+    }
+
+    fn check_rigid(&self) -> Result<(), TransformError> {
+        if self.matrix[3] != [0.0, 0.0, 0.0, 1.0] {
+            return Err(TransformError::NotAffine);
+        }
+        let rotation = self.rotation();
+        let gram = linalg::mat3_mul(&linalg::mat3_transpose(&rotation), &rotation);
+        let identity = square::identity::<3>();
+        let deviation = (0..3)
+            .flat_map(|i| (0..3).map(move |j| (i, j)))
+            .map(|(i, j)| (gram[i][j] - identity[i][j]).abs())
+            .fold(0.0, f64::max);
+        if deviation > RIGIDITY_TOLERANCE {
+            return Err(TransformError::NotOrthonormal { deviation });
+        }
+        if linalg::dot(rotation[0], linalg::cross(rotation[1], rotation[2])) < 0.0 {
+            return Err(TransformError::Reflection);
+        }
+        Ok(())
+    }
+
+    /// Inverse transform; rigid transforms use `[Rᵀ, -Rᵀt]`, others a general
+    /// inversion that returns `None` when singular
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_rigid() {
+            let rotation = linalg::mat3_transpose(&self.rotation());
+            let translation = linalg::scale(linalg::mat3_vec(&rotation, self.translation()), -1.0);
+            return Some(Self::from_rotation_translation(&rotation, translation));
+        }
+        square::inverse(&self.matrix).map(Self::new)
+    }
+
+    pub fn apply_point(&self, p: Vector3) -> Vector3 {
+        let [x, y, z, w] = square::mul_vec(&self.matrix, &[p[0], p[1], p[2], 1.0]);
+        [x / w, y / w, z / w]
+    }
+
+    /// Apply the linear part only, ignoring the translation
+    pub fn apply_direction(&self, v: Vector3) -> Vector3 {
+        linalg::mat3_vec(&self.rotation(), v)
+    }
+}
+
+impl std::ops::Mul for Transform3 {
+    type Output = Transform3;
+
+    /// Composition: `(a * b)` applies `b` first, then `a`
+    fn mul(self, rhs: Transform3) -> Transform3 {
+        Transform3::new(square::mul(&self.matrix, &rhs.matrix))
+    }
+}
+
+impl From<Motor> for Transform3 {
+    fn from(motor: Motor) -> Self {
+        Self::from_motor(&motor)
+    }
+}
+
+impl TryFrom<Transform3> for Motor {
+    type Error = TransformError;
+
+    fn try_from(transform: Transform3) -> Result<Self, Self::Error> {
+        transform.to_motor()
+    }
+}
+
+/// Reasons a matrix cannot be converted to a motor
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformError {
+    /// The bottom row is not `[0, 0, 0, 1]`
+    NotAffine,
+    /// The linear part scales or shears; `deviation` is the largest entry of `|RᵀR - I|`
+    NotOrthonormal { deviation: f64 },
+    /// The linear part is orthonormal but mirrors space
+    Reflection,
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::NotAffine => write!(f, "bottom row of the transform is not [0, 0, 0, 1]"),
+            TransformError::NotOrthonormal { deviation } => {
+                write!(f, "linear part is not a rotation (RᵀR deviates from I by {:e})", deviation)
+            }
+            TransformError::Reflection => write!(f, "linear part is a reflection, not a rotation"),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::si_units::TAU;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_motor_round_trip() {
+        let a = Motor::new([1.0, 2.0, 3.0], Rotor::from_axis_angle([0.0, 1.0, 0.0], 0.7));
+        let b = Motor::new([-0.5, 0.0, 2.0], Rotor::from_axis_angle([1.0, 1.0, 0.0], 1.1));
+        let (ta, tb) = (Transform3::from(a), Transform3::from(b));
+        let p = [0.3, -0.4, 0.9];
+
+        assert_close(ta.apply_point(p), a.apply_point(p));
+        assert_close(ta.apply_direction(p), a.apply_direction(p));
+        assert_close((ta * tb).apply_point(p), (a * b).apply_point(p));
+        assert_close(ta.inverse().unwrap().apply_point(p), a.inverse().apply_point(p));
+
+        let back = Motor::try_from(ta * tb).unwrap();
+        assert_close(back.translation, (a * b).translation);
+        assert_close(back.rotor.rotation_vector(), (a * b).rotor.rotation_vector());
+    }
+
+    #[test]
+    fn test_non_rigid_transforms() {
+        let quarter = Rotor::from_axis_angle([0.0, 0.0, 1.0], TAU / 4.0).to_rotation_matrix();
+        let mut scaled = Transform3::from_rotation_translation(&quarter, [1.0, 0.0, 0.0]);
+        for row in scaled.matrix.iter_mut().take(3) {
+            row[0] *= 2.0;
+        }
+        assert!(matches!(scaled.to_motor(), Err(TransformError::NotOrthonormal { .. })));
+        // General inversion still works for affine maps
+        let p = [0.5, 1.0, -2.0];
+        assert_close(scaled.inverse().unwrap().apply_point(scaled.apply_point(p)), p);
+
+        let mirror = Transform3::from_rotation_translation(&[[-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]], [0.0; 3]);
+        assert_eq!(mirror.to_motor(), Err(TransformError::Reflection));
+
+        let mut projective = Transform3::identity();
+        projective.matrix[3] = [0.0, 0.0, 1.0, 0.0];
+        assert_eq!(projective.to_motor(), Err(TransformError::NotAffine));
+        assert!(projective.inverse().is_none());
+    }
+}
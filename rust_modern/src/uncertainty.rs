@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! First-order covariance propagation through rigid-body motions
+//!
+//! Pose uncertainty is a 6×6 covariance on the motor tangent space, ordered like
+//! [`MotorGenerator`] (rotation, translation), with the perturbation applied on the
+//! right: `M = M̄ exp(ξ)`. Point uncertainty is a 3×3 covariance in the frame the
+//! point is expressed in.
+
+use serde::{Deserialize, Serialize};
+use crate::linalg::{self, square, Matrix3, Matrix6, Vector3};
+use crate::motor::{Motor, MotorGenerator};
+
+/// Point with Gaussian position uncertainty
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UncertainPoint {
+    pub mean: Vector3,
+    pub covariance: Matrix3,
+}
+
+impl UncertainPoint {
+    pub fn new(mean: Vector3, covariance: Matrix3) -> Self {
+        Self { mean, covariance }
+    }
+
+    /// Point known exactly
+    pub fn certain(mean: Vector3) -> Self {
+        Self::new(mean, [[0.0; 3]; 3])
+    }
+
+    /// Point with independent, equal variance on each axis
+    pub fn isotropic(mean: Vector3, sigma: f64) -> Self {
+        let variance = sigma * sigma;
+        Self::new(mean, [[variance, 0.0, 0.0], [0.0, variance, 0.0], [0.0, 0.0, variance]])
+    }
+
+    /// Squared Mahalanobis distance of `point` from this distribution
+    pub fn mahalanobis_squared(&self, point: Vector3) -> Option<f64> {
+        let information = square::inverse(&self.covariance)?;
+        Some(square::quadratic_form(&information, &linalg::sub(point, self.mean)))
+    }
+
+    pub fn mahalanobis(&self, point: Vector3) -> Option<f64> {
+        self.mahalanobis_squared(point).map(f64::sqrt)
+    }
+}
+
+/// Motor with Gaussian uncertainty on its tangent space
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UncertainPose {
+    pub mean: Motor,
+    pub covariance: Matrix6,
+}
+
+impl UncertainPose {
+    pub fn new(mean: Motor, covariance: Matrix6) -> Self {
+        Self { mean, covariance }
+    }
+
+    /// Pose known exactly
+    pub fn certain(mean: Motor) -> Self {
+        Self::new(mean, [[0.0; 6]; 6])
+    }
+
+    /// Pose with independent rotation (rad) and translation (m) standard deviations
+    pub fn isotropic(mean: Motor, sigma_rotation: f64, sigma_translation: f64) -> Self {
+        let mut covariance = [[0.0; 6]; 6];
+        for i in 0..3 {
+            covariance[i][i] = sigma_rotation * sigma_rotation;
+            covariance[i + 3][i + 3] = sigma_translation * sigma_translation;
+        }
+        Self::new(mean, covariance)
+    }
+
+    /// Composition `self * other` of independent poses
+    pub fn compose(&self, other: &UncertainPose) -> UncertainPose {
+        let transported = square::sandwich(&other.mean.inverse().adjoint(), &self.covariance);
+        UncertainPose::new(self.mean * other.mean, square::add(&transported, &other.covariance))
+    }
+
+    /// Composition with an exactly known motor on the right
+    pub fn compose_motor(&self, motor: &Motor) -> UncertainPose {
+        self.compose(&UncertainPose::certain(*motor))
+    }
+
+    pub fn inverse(&self) -> UncertainPose {
+        UncertainPose::new(self.mean.inverse(), square::sandwich(&self.mean.adjoint(), &self.covariance))
+    }
+
+    /// Jacobian of `M p` with respect to the tangent perturbation, as three rows of six
+    fn point_jacobian(&self, point: Vector3) -> [[f64; 6]; 3] {
+        let r = self.mean.rotor.to_rotation_matrix();
+        let rotational = linalg::mat3_mul(&r, &linalg::skew(point));
+        let mut jacobian = [[0.0; 6]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                jacobian[i][j] = -rotational[i][j];
+                jacobian[i][j + 3] = r[i][j];
+            }
+        }
+        jacobian
+    }
+
+    /// Transform an uncertain point, combining pose and point covariances
+    pub fn apply_point(&self, point: &UncertainPoint) -> UncertainPoint {
+        let jacobian = self.point_jacobian(point.mean);
+        let r = self.mean.rotor.to_rotation_matrix();
+
+        let mut covariance = square::sandwich(&r, &point.covariance);
+        for (i, row) in covariance.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value += (0..6)
+                    .flat_map(|k| (0..6).map(move |l| (k, l)))
+                    .map(|(k, l)| jacobian[i][k] * self.covariance[k][l] * jacobian[j][l])
+                    .sum::<f64>();
+            }
+        }
+        UncertainPoint::new(self.mean.apply_point(point.mean), covariance)
+    }
+
+    /// Tangent-space residual `log(M̄⁻¹ M)` of `motor` from the mean
+    pub fn residual(&self, motor: &Motor) -> MotorGenerator {
+        (self.mean.inverse() * *motor).log()
+    }
+
+    /// Squared Mahalanobis distance of `motor` from this distribution
+    pub fn mahalanobis_squared(&self, motor: &Motor) -> Option<f64> {
+        let information = square::inverse(&self.covariance)?;
+        Some(square::quadratic_form(&information, &self.residual(motor).to_array()))
+    }
+
+    pub fn mahalanobis(&self, motor: &Motor) -> Option<f64> {
+        self.mahalanobis_squared(motor).map(f64::sqrt)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motor::Rotor;
+    use crate::si_units::TAU;
+
+    #[test]
+    fn test_point_mahalanobis() {
+        let point = UncertainPoint::isotropic([1.0, 0.0, 0.0], 0.5);
+
+        assert!((point.mahalanobis([2.0, 0.0, 0.0]).unwrap() - 2.0).abs() < 1e-12);
+        assert!(UncertainPoint::certain([0.0; 3]).mahalanobis([1.0, 0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn test_apply_point_propagation() {
+        let rotation = Rotor::from_axis_angle([0.0, 0.0, 1.0], TAU / 4.0);
+        let pose = UncertainPose::new(Motor::new([1.0, 0.0, 0.0], rotation), [[0.0; 6]; 6]);
+        let mut point = UncertainPoint::certain([1.0, 0.0, 0.0]);
+        point.covariance[0][0] = 4.0;
+
+        // Variance along the point's x axis becomes variance along the target y axis
+        let mapped = pose.apply_point(&point);
+        assert!((mapped.mean[1] - 1.0).abs() < 1e-12);
+        assert!((mapped.covariance[1][1] - 4.0).abs() < 1e-12);
+        assert!(mapped.covariance[0][0].abs() < 1e-12);
+
+        // Yaw uncertainty spreads a point at unit radius tangentially
+        let mut yaw = UncertainPose::certain(Motor::identity());
+        yaw.covariance[2][2] = 0.01;
+        let spread = yaw.apply_point(&UncertainPoint::certain([1.0, 0.0, 0.0]));
+        assert!((spread.covariance[1][1] - 0.01).abs() < 1e-12);
+        assert!(spread.covariance[0][0].abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pose_composition_and_inverse() {
+        let a = UncertainPose::isotropic(Motor::from_translation([1.0, 0.0, 0.0]), 0.0, 0.1);
+        let b = UncertainPose::isotropic(Motor::from_translation([0.0, 2.0, 0.0]), 0.0, 0.2);
+
+        let c = a.compose(&b);
+        assert!((c.covariance[3][3] - 0.05).abs() < 1e-12);
+        assert_eq!(c.mean.translation, [1.0, 2.0, 0.0]);
+
+        let inverse = c.inverse();
+        assert!((inverse.covariance[4][4] - 0.05).abs() < 1e-12);
+
+        assert!(c.mahalanobis(&c.mean).is_none());
+        let d = UncertainPose::isotropic(c.mean, 0.1, 0.1);
+        let shifted = Motor::from_translation([1.0, 2.0, 0.5]);
+        assert!((d.mahalanobis(&shifted).unwrap() - 5.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,642 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Underwater vehicle-manipulator systems
+//!
+//! A [`Uvms`] couples a free-floating vehicle to the [`KinematicChain`] mounted
+//! on it. The generalized velocity is the vehicle twist in its body frame
+//! (angular velocity, then the velocity of the body origin) followed by the
+//! joint velocities, and the equations of motion `M(q) ζ̇ + h = τ` are assembled
+//! body by body as in Kane's method: the vehicle, every link and the tool each
+//! add `Jᵢᵀ Mᵢ Jᵢ` to the mass matrix and their gyroscopic, weight and buoyancy
+//! terms to `h`. Because the vehicle is not held, a joint torque turns the
+//! vehicle as well as the arm; [`UvmsAcceleration::reaction`] is the wrench the
+//! arm exerts on the vehicle.
+//!
+//! The hull adds diagonal added mass, with its Kirchhoff velocity terms, and
+//! linear plus quadratic damping in the vehicle body frame, as in Fossen's
+//! model. Every [`SubmergedBody`] is buoyed at its center of buoyancy; the arm
+//! links see no drag and the tool displaces no water. The world frame has z up.
+//!
+//! [`Uvms::step`] advances a [`UvmsState`] by semi-implicit Euler: velocities
+//! first, then the vehicle pose along its body twist and the joints in the
+//! Jacobian's coordinate basis.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dynamics::Inertia;
+use crate::grasp::Wrench;
+use crate::joints::Joint;
+use crate::kinematics::{KinematicChain, KinematicsError};
+use crate::linalg::{self, dense, Matrix3, Vector3};
+use crate::motor::{Motor, MotorGenerator};
+use crate::si_units::{marine, Acceleration, Density, Energy, Time, Volume};
+
+/// Why the coupled dynamics could not be evaluated
+#[derive(Debug, Clone, PartialEq)]
+pub enum UvmsError {
+    Kinematics(KinematicsError),
+    /// The mass matrix is not positive definite: some joint moves no mass
+    SingularMassMatrix,
+}
+
+impl fmt::Display for UvmsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UvmsError::Kinematics(error) => write!(f, "{}", error),
+            UvmsError::SingularMassMatrix => write!(f, "mass matrix is singular; every joint must move some mass"),
+        }
+    }
+}
+
+impl std::error::Error for UvmsError {}
+
+impl From<KinematicsError> for UvmsError {
+    fn from(error: KinematicsError) -> Self {
+        UvmsError::Kinematics(error)
+    }
+}
+
+/// Mass properties and displaced water of one rigid body
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SubmergedBody {
+    pub inertia: Inertia,
+    pub volume: Volume,
+    /// Centroid of the displaced water, in the frame of the inertia
+    pub center_of_buoyancy: Vector3,
+}
+
+impl SubmergedBody {
+    /// Body buoyed at its center of mass
+    pub fn new(inertia: Inertia, volume: Volume) -> Self {
+        Self { inertia, volume, center_of_buoyancy: inertia.center_of_mass() }
+    }
+
+    pub fn with_center_of_buoyancy(mut self, center: Vector3) -> Self {
+        self.center_of_buoyancy = center;
+        self
+    }
+}
+
+/// Added mass and damping of the hull, diagonal in the vehicle body frame
+///
+/// Entries follow the twist order: roll, pitch, yaw, then surge, sway, heave.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HullHydrodynamics {
+    /// kg·m² for the rotations, kg for the translations
+    pub added_mass: [f64; 6],
+    /// N·m·s and N·s/m
+    pub linear_damping: [f64; 6],
+    /// N·m·s² and N·s²/m²
+    pub quadratic_damping: [f64; 6],
+}
+
+impl HullHydrodynamics {
+    pub fn new(added_mass: [f64; 6]) -> Self {
+        Self { added_mass, ..Self::default() }
+    }
+
+    pub fn with_linear_damping(mut self, damping: [f64; 6]) -> Self {
+        self.linear_damping = damping;
+        self
+    }
+
+    pub fn with_quadratic_damping(mut self, damping: [f64; 6]) -> Self {
+        self.quadratic_damping = damping;
+        self
+    }
+
+    /// Kirchhoff velocity terms of the added mass plus damping, for the body twist `nu`
+    fn velocity_forces(&self, nu: &[f64]) -> [f64; 6] {
+        let (w, v) = ([nu[0], nu[1], nu[2]], [nu[3], nu[4], nu[5]]);
+        let a = &self.added_mass;
+        let angular_momentum = [a[0] * w[0], a[1] * w[1], a[2] * w[2]];
+        let linear_momentum = [a[3] * v[0], a[4] * v[1], a[5] * v[2]];
+        let moment = linalg::add(linalg::cross(w, angular_momentum), linalg::cross(v, linear_momentum));
+        let force = linalg::cross(w, linear_momentum);
+        let coupling = [moment[0], moment[1], moment[2], force[0], force[1], force[2]];
+        std::array::from_fn(|i| {
+            coupling[i] + (self.linear_damping[i] + self.quadratic_damping[i] * nu[i].abs()) * nu[i]
+        })
+    }
+}
+
+/// Vehicle pose and the velocities of vehicle and arm
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UvmsState {
+    /// Vehicle body frame in the world frame
+    pub pose: Motor,
+    /// Vehicle angular velocity and origin velocity, in its body frame
+    pub twist: MotorGenerator,
+    pub q: Vec<f64>,
+    /// Joint velocities in the Jacobian's coordinate basis
+    pub dq: Vec<f64>,
+}
+
+impl UvmsState {
+    pub fn at_rest(pose: Motor, q: Vec<f64>) -> Self {
+        let dq = vec![0.0; q.len()];
+        Self { pose, twist: MotorGenerator::default(), q, dq }
+    }
+}
+
+/// Thruster wrench on the vehicle and joint efforts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UvmsInput {
+    /// About the vehicle origin, in its body frame
+    pub wrench: Wrench,
+    /// N·m for rotations, N for translations
+    pub efforts: Vec<f64>,
+}
+
+impl UvmsInput {
+    /// Joint efforts with the thrusters idle
+    pub fn joints(efforts: Vec<f64>) -> Self {
+        Self { wrench: Wrench::new([0.0; 3], [0.0; 3]), efforts }
+    }
+
+    pub fn with_wrench(mut self, wrench: Wrench) -> Self {
+        self.wrench = wrench;
+        self
+    }
+}
+
+/// Accelerations of the coupled system and the arm's load on the vehicle
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvmsAcceleration {
+    /// Rate of change of the vehicle body twist
+    pub vehicle: MotorGenerator,
+    pub ddq: Vec<f64>,
+    /// Wrench the arm exerts on the vehicle, about its origin in its body frame
+    pub reaction: Wrench,
+}
+
+/// Motion of a point fixed to a body, in the world frame
+#[derive(Debug, Clone, Copy)]
+struct Motion {
+    point: Vector3,
+    angular: Vector3,
+    angular_acceleration: Vector3,
+    velocity: Vector3,
+    acceleration: Vector3,
+}
+
+impl Motion {
+    /// Motion of another point `p` fixed to the same body
+    fn at(&self, p: Vector3) -> Self {
+        let r = linalg::sub(p, self.point);
+        let w = self.angular;
+        let tangential = linalg::cross(self.angular_acceleration, r);
+        let centripetal = linalg::cross(w, linalg::cross(w, r));
+        Self {
+            point: p,
+            velocity: linalg::add(self.velocity, linalg::cross(w, r)),
+            acceleration: linalg::add(self.acceleration, linalg::add(tangential, centripetal)),
+            ..*self
+        }
+    }
+
+    /// Motion of the child of a rotational joint with parent-fixed `axes` through `self.point`
+    fn rotated(mut self, axes: &[Vector3], dq: &[f64], ddq: &[f64]) -> Self {
+        let sum = |rates: &[f64]| axes.iter().zip(rates).fold([0.0; 3], |sum, (axis, rate)| linalg::add(sum, linalg::scale(*axis, *rate)));
+        let (relative, driven) = (sum(dq), sum(ddq));
+        self.angular_acceleration =
+            linalg::add(self.angular_acceleration, linalg::add(driven, linalg::cross(self.angular, relative)));
+        self.angular = linalg::add(self.angular, relative);
+        self
+    }
+}
+
+/// A body in the world frame and the carrier it moves with: 0 for the vehicle, `k + 1` for segment `k`
+struct WorldBody {
+    carrier: usize,
+    mass: f64,
+    center: Vector3,
+    tensor: Matrix3,
+    volume: f64,
+    center_of_buoyancy: Vector3,
+}
+
+impl WorldBody {
+    fn new(carrier: usize, body: &SubmergedBody, frame: &Motor) -> Self {
+        let inertia = body.inertia.transform(frame);
+        Self {
+            carrier,
+            mass: *inertia.mass().value(),
+            center: inertia.center_of_mass(),
+            tensor: inertia.central_tensor(),
+            volume: *body.volume.value(),
+            center_of_buoyancy: frame.apply_point(body.center_of_buoyancy),
+        }
+    }
+}
+
+/// The system at one configuration: vehicle pose, joint frames and bodies in the world frame
+struct Configuration {
+    pose: Motor,
+    /// World joint origin and frame after the joint, per segment
+    segments: Vec<(Motor, Motor)>,
+    bodies: Vec<WorldBody>,
+}
+
+/// `M ζ̇ + h = τ`, with the arm's share of the vehicle rows kept apart
+struct Equations {
+    mass: Vec<f64>,
+    bias: Vec<f64>,
+    /// Vehicle rows (6×n) of the arm bodies' mass matrix
+    arm_mass: Vec<f64>,
+    arm_bias: [f64; 6],
+}
+
+/// Vehicle and manipulator as one floating multibody system
+#[derive(Debug, Clone, PartialEq)]
+pub struct Uvms {
+    vehicle: SubmergedBody,
+    hull: HullHydrodynamics,
+    chain: KinematicChain,
+    /// Chain base in the vehicle body frame
+    mount: Motor,
+    /// Body carried by each segment, in the frame after the segment
+    links: Vec<Option<SubmergedBody>>,
+    gravity: [Acceleration; 3],
+    density: Density,
+}
+
+impl Uvms {
+    /// Vehicle with `chain` mounted at `mount`, with massless links, in sea water under standard gravity
+    pub fn new(vehicle: SubmergedBody, hull: HullHydrodynamics, chain: KinematicChain, mount: Motor) -> Self {
+        let links = vec![None; chain.segments().len()];
+        let g = *marine::gravity::<f64>().value();
+        let gravity = [Acceleration::new(0.0), Acceleration::new(0.0), Acceleration::new(-g)];
+        Self { vehicle, hull, chain, mount, links, gravity, density: marine::water_density() }
+    }
+
+    /// One body per segment of the chain, `None` for segments without mass
+    pub fn with_links(mut self, links: Vec<Option<SubmergedBody>>) -> Result<Self, UvmsError> {
+        if links.len() != self.chain.segments().len() {
+            return Err(KinematicsError::LinkCount { expected: links.len(), actual: self.chain.segments().len() }.into());
+        }
+        self.links = links;
+        Ok(self)
+    }
+
+    /// Gravity in the world frame
+    pub fn with_gravity(mut self, gravity: [Acceleration; 3]) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_density(mut self, density: Density) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub fn chain(&self) -> &KinematicChain {
+        &self.chain
+    }
+
+    pub fn chain_mut(&mut self) -> &mut KinematicChain {
+        &mut self.chain
+    }
+
+    pub fn mount(&self) -> Motor {
+        self.mount
+    }
+
+    /// Number of generalized velocities: six for the vehicle and one per joint coordinate
+    pub fn dof(&self) -> usize {
+        6 + self.chain.dof()
+    }
+
+    /// End-effector pose in the world frame
+    pub fn end_effector(&self, state: &UvmsState) -> Result<Motor, UvmsError> {
+        Ok(state.pose * self.mount * self.chain.forward(&state.q)?)
+    }
+
+    fn configuration(&self, state: &UvmsState) -> Result<Configuration, UvmsError> {
+        if state.dq.len() != state.q.len() {
+            return Err(KinematicsError::CoordinateCount { expected: state.q.len(), actual: state.dq.len() }.into());
+        }
+        let base = state.pose * self.mount;
+        let segments: Vec<(Motor, Motor)> = self
+            .chain
+            .origin_and_joint_frames(&state.q)?
+            .into_iter()
+            .map(|(origin, after)| (base * origin, base * after))
+            .collect();
+
+        let mut bodies = vec![WorldBody::new(0, &self.vehicle, &state.pose)];
+        for (k, (link, (_, after))) in self.links.iter().zip(&segments).enumerate() {
+            if let Some(link) = link {
+                bodies.push(WorldBody::new(k + 1, link, after));
+            }
+        }
+        if let Some(tool) = self.chain.tool().and_then(|tool| tool.body().inertia()) {
+            let flange = segments.last().map_or(base, |(_, after)| *after);
+            let tool = SubmergedBody::new(tool, Volume::new(0.0));
+            bodies.push(WorldBody::new(segments.len(), &tool, &flange));
+        }
+        Ok(Configuration { pose: state.pose, segments, bodies })
+    }
+
+    /// Motion of every carrier for generalized velocity `zeta` and its rate `rate`
+    fn carriers(&self, configuration: &Configuration, zeta: &[f64], rate: &[f64]) -> Vec<Motion> {
+        let pose = &configuration.pose;
+        let (w, v) = ([zeta[0], zeta[1], zeta[2]], [zeta[3], zeta[4], zeta[5]]);
+        let (dw, dv) = ([rate[0], rate[1], rate[2]], [rate[3], rate[4], rate[5]]);
+        let mut carriers = vec![Motion {
+            point: pose.translation,
+            angular: pose.apply_direction(w),
+            angular_acceleration: pose.apply_direction(dw),
+            velocity: pose.apply_direction(v),
+            // The body axes turn with the vehicle
+            acceleration: pose.apply_direction(linalg::add(dv, linalg::cross(w, v))),
+        }];
+
+        let mut offset = 6;
+        for (segment, (origin, after)) in self.chain.segments().iter().zip(&configuration.segments) {
+            let parent = carriers[carriers.len() - 1];
+            let dof = segment.joint.dof();
+            let (dq, ddq) = (&zeta[offset..offset + dof], &rate[offset..offset + dof]);
+            offset += dof;
+            let child = match segment.joint {
+                Joint::Revolute { axis, .. } => {
+                    parent.at(origin.translation).rotated(&[origin.apply_direction(axis)], dq, ddq)
+                }
+                Joint::Spherical { .. } => {
+                    let axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].map(|axis| origin.apply_direction(axis));
+                    parent.at(origin.translation).rotated(&axes, dq, ddq)
+                }
+                Joint::Prismatic { axis, .. } => {
+                    let d = origin.apply_direction(axis);
+                    let mut child = parent.at(after.translation);
+                    child.velocity = linalg::add(child.velocity, linalg::scale(d, dq[0]));
+                    let coriolis = linalg::scale(linalg::cross(parent.angular, d), 2.0 * dq[0]);
+                    child.acceleration = linalg::add(child.acceleration, linalg::add(linalg::scale(d, ddq[0]), coriolis));
+                    child
+                }
+                Joint::Fixed => parent.at(origin.translation),
+            };
+            carriers.push(child);
+        }
+        carriers
+    }
+
+    fn equations(&self, state: &UvmsState) -> Result<Equations, UvmsError> {
+        let configuration = self.configuration(state)?;
+        let n = self.dof();
+        let zeta: Vec<f64> = state.twist.to_array().into_iter().chain(state.dq.iter().copied()).collect();
+        let zero = vec![0.0; n];
+
+        // Column k of each body's Jacobian is its acceleration under a unit rate k at rest
+        let columns: Vec<Vec<[Vector3; 2]>> = (0..n)
+            .map(|k| {
+                let mut unit = zero.clone();
+                unit[k] = 1.0;
+                let carriers = self.carriers(&configuration, &zero, &unit);
+                configuration
+                    .bodies
+                    .iter()
+                    .map(|body| {
+                        let motion = carriers[body.carrier].at(body.center);
+                        [motion.angular_acceleration, motion.acceleration]
+                    })
+                    .collect()
+            })
+            .collect();
+        let carriers = self.carriers(&configuration, &zeta, &zero);
+
+        let gravity = self.gravity.map(|g| *g.value());
+        let density = *self.density.value();
+        let mut mass = vec![0.0; n * n];
+        let mut bias = vec![0.0; n];
+        let mut arm_mass = vec![0.0; 6 * n];
+        let mut arm_bias = [0.0; 6];
+        for (b, body) in configuration.bodies.iter().enumerate() {
+            let motion = carriers[body.carrier].at(body.center);
+            let w = motion.angular;
+            let buoyancy = linalg::scale(gravity, -density * body.volume);
+            let external_force = linalg::add(linalg::scale(gravity, body.mass), buoyancy);
+            let external_moment = linalg::cross(linalg::sub(body.center_of_buoyancy, body.center), buoyancy);
+            let spin = linalg::mat3_vec(&body.tensor, motion.angular_acceleration);
+            let gyroscopic = linalg::cross(w, linalg::mat3_vec(&body.tensor, w));
+            let moment = linalg::sub(linalg::add(spin, gyroscopic), external_moment);
+            let force = linalg::sub(linalg::scale(motion.acceleration, body.mass), external_force);
+
+            for i in 0..n {
+                let [angular, linear] = columns[i][b];
+                let generalized = linalg::dot(angular, moment) + linalg::dot(linear, force);
+                bias[i] += generalized;
+                for j in 0..n {
+                    let [other_angular, other_linear] = columns[j][b];
+                    let entry = linalg::dot(angular, linalg::mat3_vec(&body.tensor, other_angular))
+                        + body.mass * linalg::dot(linear, other_linear);
+                    mass[i * n + j] += entry;
+                    if b > 0 && i < 6 {
+                        arm_mass[i * n + j] += entry;
+                    }
+                }
+                if b > 0 && i < 6 {
+                    arm_bias[i] += generalized;
+                }
+            }
+        }
+
+        let hull = self.hull.velocity_forces(&zeta[..6]);
+        for i in 0..6 {
+            mass[i * n + i] += self.hull.added_mass[i];
+            bias[i] += hull[i];
+        }
+        Ok(Equations { mass, bias, arm_mass, arm_bias })
+    }
+
+    /// Mass matrix (row-major, `dof`×`dof`) including the added mass of the hull
+    pub fn mass_matrix(&self, state: &UvmsState) -> Result<Vec<f64>, UvmsError> {
+        Ok(self.equations(state)?.mass)
+    }
+
+    /// Kinetic energy of vehicle, arm and the water moved with the hull
+    pub fn kinetic_energy(&self, state: &UvmsState) -> Result<Energy, UvmsError> {
+        let mass = self.mass_matrix(state)?;
+        let n = self.dof();
+        let zeta: Vec<f64> = state.twist.to_array().into_iter().chain(state.dq.iter().copied()).collect();
+        let quadratic: f64 = (0..n).map(|i| zeta[i] * (0..n).map(|j| mass[i * n + j] * zeta[j]).sum::<f64>()).sum();
+        Ok(Energy::new(0.5 * quadratic))
+    }
+
+    /// Accelerations under `input`, and the wrench the arm exerts on the vehicle
+    pub fn forward_dynamics(&self, state: &UvmsState, input: &UvmsInput) -> Result<UvmsAcceleration, UvmsError> {
+        if input.efforts.len() != self.chain.dof() {
+            return Err(KinematicsError::CoordinateCount { expected: self.chain.dof(), actual: input.efforts.len() }.into());
+        }
+        let equations = self.equations(state)?;
+        let n = self.dof();
+        let (torque, force) = (input.wrench.torque, input.wrench.force);
+        let tau = torque.into_iter().chain(force).chain(input.efforts.iter().copied());
+        let rhs: Vec<f64> = tau.zip(&equations.bias).map(|(tau, h)| tau - h).collect();
+        let rate = dense::cholesky_solve(&equations.mass, n, &rhs).ok_or(UvmsError::SingularMassMatrix)?;
+
+        // The vehicle supplies what the arm's bodies need; the arm pushes back with the opposite
+        let load: Vec<f64> = (0..6)
+            .map(|i| equations.arm_bias[i] + (0..n).map(|j| equations.arm_mass[i * n + j] * rate[j]).sum::<f64>())
+            .collect();
+        Ok(UvmsAcceleration {
+            vehicle: MotorGenerator::from_array([rate[0], rate[1], rate[2], rate[3], rate[4], rate[5]]),
+            ddq: rate[6..].to_vec(),
+            reaction: Wrench::new([-load[3], -load[4], -load[5]], [-load[0], -load[1], -load[2]]),
+        })
+    }
+
+    /// Advance `state` by `dt` under `input`, returning the accelerations at the start of the step
+    pub fn step(&self, state: &mut UvmsState, input: &UvmsInput, dt: Time) -> Result<UvmsAcceleration, UvmsError> {
+        let acceleration = self.forward_dynamics(state, input)?;
+        let dt = *dt.value();
+        let rate = acceleration.vehicle.to_array();
+        let mut nu = state.twist.to_array();
+        for (nu, rate) in nu.iter_mut().zip(rate) {
+            *nu += rate * dt;
+        }
+        state.twist = MotorGenerator::from_array(nu);
+        for (dq, ddq) in state.dq.iter_mut().zip(&acceleration.ddq) {
+            *dq += ddq * dt;
+        }
+
+        state.pose = state.pose * Motor::exp(&state.twist.scaled(dt));
+        state.pose.renormalize();
+        let displacement: Vec<f64> = state.dq.iter().map(|dq| dq * dt).collect();
+        self.chain.integrate(&mut state.q, &displacement)?;
+        Ok(acceleration)
+    }
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::joints::{PrismaticLimits, SphericalLimits};
+    use crate::kinematics::test_fixtures::{revolute, Z};
+    use crate::motor::Rotor;
+    use crate::si_units::{units, Angle, AngularVelocity, Length, Mass, Velocity};
+
+    /// Neutrally buoyant rod of `length` along x from the segment frame
+    fn rod(mass: f64, length: f64) -> Option<SubmergedBody> {
+        let size = [Length::new(length), Length::new(0.08), Length::new(0.08)];
+        let inertia = Inertia::solid_box(Mass::new(mass), size).transform(&Motor::from_translation([length / 2.0, 0.0, 0.0]));
+        Some(SubmergedBody::new(inertia, Volume::new(mass / 1025.0)))
+    }
+
+    fn vehicle() -> SubmergedBody {
+        let size = [Length::new(1.2), Length::new(0.8), Length::new(0.6)];
+        SubmergedBody::new(Inertia::solid_box(Mass::new(100.0), size), Volume::new(100.0 / 1025.0))
+    }
+
+    /// Shoulder yaw at the vehicle origin and an elbow about y
+    fn arm() -> KinematicChain {
+        KinematicChain::new()
+            .with_joint(Motor::identity(), revolute(Z))
+            .with_joint(Motor::from_translation([0.5, 0.0, 0.0]), revolute([0.0, 1.0, 0.0]))
+            .with_joint(Motor::from_translation([0.4, 0.0, 0.0]), Joint::Fixed)
+    }
+
+    fn uvms(hull: HullHydrodynamics) -> Uvms {
+        Uvms::new(vehicle(), hull, arm(), Motor::identity()).with_links(vec![rod(2.0, 0.5), rod(1.5, 0.4), None]).unwrap()
+    }
+
+    #[test]
+    fn test_neutral_system_rests_and_buoyancy_rights_the_hull() {
+        let system = uvms(HullHydrodynamics::default());
+        let state = UvmsState::at_rest(Motor::identity(), vec![0.3, 0.5]);
+        let acceleration = system.forward_dynamics(&state, &UvmsInput::joints(vec![0.0, 0.0])).unwrap();
+        assert!(acceleration.vehicle.to_array().iter().chain(&acceleration.ddq).all(|a| a.abs() < 1e-9));
+        assert!(acceleration.reaction.to_array().iter().all(|w| w.abs() < 1e-9));
+
+        // Buoyancy above the center of mass turns a heeled hull back upright
+        let hull = SubmergedBody { center_of_buoyancy: [0.0, 0.0, 0.1], ..vehicle() };
+        let system = Uvms::new(hull, HullHydrodynamics::default(), arm(), Motor::identity())
+            .with_links(vec![rod(2.0, 0.5), rod(1.5, 0.4), None])
+            .unwrap();
+        let heeled = UvmsState::at_rest(Motor::new([0.0; 3], Rotor::from_rotation_vector([0.2, 0.0, 0.0])), vec![0.0, 0.0]);
+        let acceleration = system.forward_dynamics(&heeled, &UvmsInput::joints(vec![0.0, 0.0])).unwrap();
+        assert!(acceleration.vehicle.rotation[0] < 0.0, "{:?}", acceleration.vehicle);
+    }
+
+    #[test]
+    fn test_joint_torque_turns_the_vehicle_the_other_way() {
+        let weightless = [Acceleration::new(0.0); 3];
+        let system = uvms(HullHydrodynamics::new([0.0, 0.0, 3.0, 0.0, 0.0, 0.0])).with_gravity(weightless);
+        let state = UvmsState::at_rest(Motor::identity(), vec![0.3, 0.5]);
+        let acceleration = system.forward_dynamics(&state, &UvmsInput::joints(vec![5.0, 0.0])).unwrap();
+
+        assert!(acceleration.ddq[0] > 0.0);
+        assert!((acceleration.reaction.torque[2] + 5.0).abs() < 1e-9, "{:?}", acceleration.reaction);
+        let yaw_inertia = vehicle().inertia.central_tensor()[2][2] + 3.0;
+        assert!((acceleration.vehicle.rotation[2] + 5.0 / yaw_inertia).abs() < 1e-9);
+
+        // Thrust alone accelerates vehicle and arm together
+        let thrust = UvmsInput::joints(vec![0.0, 0.0]).with_wrench(Wrench::new([10.0, 0.0, 0.0], [0.0; 3]));
+        let acceleration = system.forward_dynamics(&state, &thrust).unwrap();
+        assert!(acceleration.vehicle.translation[0] > 0.0 && acceleration.vehicle.translation[0] < 10.0 / 100.0);
+        assert!(acceleration.reaction.force[0] < 0.0);
+    }
+
+    #[test]
+    fn test_energy_is_conserved_without_damping_and_dissipated_with_it() {
+        let prismatic = PrismaticLimits::new(Length::new(0.0), Length::new(0.5), Velocity::new(1.0));
+        let spherical = SphericalLimits::new(Angle::new(1.0), AngularVelocity::new(2.0));
+        let chain = KinematicChain::new()
+            .with_joint(Motor::from_translation([0.0, 0.0, 0.3]), revolute(Z))
+            .with_joint(Motor::from_translation([0.4, 0.0, 0.0]), Joint::Spherical { limits: spherical })
+            .with_joint(Motor::from_translation([0.3, 0.0, 0.0]), Joint::prismatic([1.0, 0.0, 0.0], prismatic));
+        let hull = HullHydrodynamics::new([4.0, 6.0, 8.0, 20.0, 40.0, 60.0]);
+        let system = Uvms::new(vehicle(), hull, chain, Motor::from_translation([0.3, 0.0, 0.2]))
+            .with_links(vec![rod(2.0, 0.4), rod(1.5, 0.3), rod(1.0, 0.3)])
+            .unwrap()
+            .with_gravity([Acceleration::new(0.0); 3]);
+
+        let mut state = UvmsState::at_rest(Motor::identity(), vec![0.2, 0.1, -0.2, 0.3, 0.1]);
+        state.twist = MotorGenerator::new([0.05, -0.1, 0.2], [0.3, 0.0, -0.1]);
+        state.dq = vec![1.0, 0.5, -0.4, 0.8, 0.3];
+        let mass = system.mass_matrix(&state).unwrap();
+        let n = system.dof();
+        assert!((0..n).all(|i| (0..n).all(|j| (mass[i * n + j] - mass[j * n + i]).abs() < 1e-9)));
+
+        let idle = UvmsInput::joints(vec![0.0; 5]);
+        let initial = *system.kinetic_energy(&state).unwrap().value();
+        let start = system.end_effector(&state).unwrap();
+        let mut free = state.clone();
+        for _ in 0..2000 {
+            system.step(&mut free, &idle, units::seconds(5e-4)).unwrap();
+        }
+        let energy = *system.kinetic_energy(&free).unwrap().value();
+        assert!((energy - initial).abs() < 1e-2 * initial, "{} -> {}", initial, energy);
+        assert!(linalg::norm(linalg::sub(system.end_effector(&free).unwrap().translation, start.translation)) > 0.1);
+
+        let damped = Uvms { hull: hull.with_linear_damping([5.0; 6]).with_quadratic_damping([20.0; 6]), ..system.clone() };
+
+        for _ in 0..2000 {
+            damped.step(&mut state, &idle, units::seconds(5e-4)).unwrap();
+        }
+        assert!(*damped.kinetic_energy(&state).unwrap().value() < 0.9 * energy);
+    }
+
+    #[test]
+    fn test_model_errors() {
+        let system = Uvms::new(vehicle(), HullHydrodynamics::default(), arm(), Motor::identity());
+        assert_eq!(
+            system.clone().with_links(vec![None]),
+            Err(UvmsError::Kinematics(KinematicsError::LinkCount { expected: 1, actual: 3 }))
+        );
+
+        // Without link masses the joints move nothing
+        let state = UvmsState::at_rest(Motor::identity(), vec![0.0, 0.0]);
+        let idle = UvmsInput::joints(vec![0.0, 0.0]);
+        assert_eq!(system.forward_dynamics(&state, &idle), Err(UvmsError::SingularMassMatrix));
+        assert_eq!(
+            uvms(HullHydrodynamics::default()).forward_dynamics(&state, &UvmsInput::joints(vec![0.0])),
+            Err(UvmsError::Kinematics(KinematicsError::CoordinateCount { expected: 2, actual: 1 }))
+        );
+    }
+}
@@ -0,0 +1,289 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Water column profiles: temperature, salinity, pressure, density and sound speed
+//!
+//! A [`WaterColumn`] is described by temperature and salinity sampled at
+//! increasing depths, interpolated linearly between samples and held constant
+//! beyond them. Density follows the UNESCO 1981 equation of state (EOS-80) at
+//! the local pressure, which is integrated down from the surface once when the
+//! column is built. Sound speed follows Mackenzie's nine-term equation, valid
+//! for 2–30 °C, 25–40 PSU and depths to 8000 m.
+//!
+//! Depth is measured downwards from the surface; the marine modules use z up,
+//! so a point at height `z` lies at depth `-z`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use crate::si_units::{marine, Density, Force, Length, Pressure, Temperature, Velocity, Volume};
+
+/// Depth spacing of the precomputed pressure table (m)
+pub const PRESSURE_TABLE_STEP: f64 = 5.0;
+
+/// Deepest point of the pressure table; deeper pressures are extrapolated (m)
+pub const PRESSURE_TABLE_DEPTH: f64 = 11_000.0;
+
+/// Kelvin offset of the Celsius scale
+const ZERO_CELSIUS: f64 = 273.15;
+
+/// Errors raised while building a water column
+#[derive(Debug, Clone, PartialEq)]
+pub enum WaterColumnError {
+    /// A profile needs at least one sample
+    EmptyProfile,
+    /// Profile depths must be finite, non-negative and strictly increasing
+    UnsortedDepths,
+}
+
+impl fmt::Display for WaterColumnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaterColumnError::EmptyProfile => write!(f, "a profile needs at least one sample"),
+            WaterColumnError::UnsortedDepths => {
+                write!(f, "profile depths must be finite, non-negative and strictly increasing")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WaterColumnError {}
+
+/// Values at increasing depths
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DepthProfile {
+    depths: Vec<f64>,
+    values: Vec<f64>,
+}
+
+impl DepthProfile {
+    fn new(samples: impl IntoIterator<Item = (f64, f64)>) -> Result<Self, WaterColumnError> {
+        let (depths, values): (Vec<f64>, Vec<f64>) = samples.into_iter().unzip();
+        if depths.is_empty() {
+            return Err(WaterColumnError::EmptyProfile);
+        }
+        let valid = depths.iter().all(|d| d.is_finite() && *d >= 0.0) && depths.windows(2).all(|w| w[0] < w[1]);
+        if !valid {
+            return Err(WaterColumnError::UnsortedDepths);
+        }
+        Ok(Self { depths, values })
+    }
+
+    fn at(&self, depth: f64) -> f64 {
+        let upper = self.depths.partition_point(|d| *d < depth);
+        if upper == 0 {
+            return self.values[0];
+        }
+        if upper == self.depths.len() {
+            return self.values[upper - 1];
+        }
+        let (d0, d1) = (self.depths[upper - 1], self.depths[upper]);
+        let t = (depth - d0) / (d1 - d0);
+        self.values[upper - 1] + t * (self.values[upper] - self.values[upper - 1])
+    }
+}
+
+/// Temperature and salinity profiles with the pressure they produce
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaterColumn {
+    /// Degrees Celsius
+    temperature: DepthProfile,
+    /// Practical salinity (PSU)
+    salinity: DepthProfile,
+    /// Pressure above atmospheric every [`PRESSURE_TABLE_STEP`] (Pa)
+    sea_pressure: Vec<f64>,
+}
+
+impl WaterColumn {
+    /// Column from `(depth, temperature)` and `(depth, salinity in PSU)` samples
+    pub fn new(temperature: &[(Length, Temperature)], salinity: &[(Length, f64)]) -> Result<Self, WaterColumnError> {
+        let temperature = DepthProfile::new(temperature.iter().map(|(d, t)| (*d.value(), t.value() - ZERO_CELSIUS)))?;
+        let salinity = DepthProfile::new(salinity.iter().map(|(d, s)| (*d.value(), *s)))?;
+        Ok(Self::from_profiles(temperature, salinity))
+    }
+
+    /// Column with the same temperature and salinity at every depth
+    pub fn uniform(temperature: Temperature, salinity: f64) -> Self {
+        let temperature = DepthProfile { depths: vec![0.0], values: vec![temperature.value() - ZERO_CELSIUS] };
+        let salinity = DepthProfile { depths: vec![0.0], values: vec![salinity] };
+        Self::from_profiles(temperature, salinity)
+    }
+
+    fn from_profiles(temperature: DepthProfile, salinity: DepthProfile) -> Self {
+        let mut column = Self { temperature, salinity, sea_pressure: Vec::new() };
+        // Trapezoidal integration of ρ g with a predictor for the lower density
+        let g = *marine::gravity::<f64>().value();
+        let steps = (PRESSURE_TABLE_DEPTH / PRESSURE_TABLE_STEP) as usize;
+        let mut pressure = 0.0;
+        column.sea_pressure.push(pressure);
+        for i in 0..steps {
+            let (top, bottom) = (i as f64 * PRESSURE_TABLE_STEP, (i + 1) as f64 * PRESSURE_TABLE_STEP);
+            let upper = column.density_at(top, pressure);
+            let predicted = pressure + upper * g * PRESSURE_TABLE_STEP;
+            let lower = column.density_at(bottom, predicted);
+            pressure += 0.5 * (upper + lower) * g * PRESSURE_TABLE_STEP;
+            column.sea_pressure.push(pressure);
+        }
+        column
+    }
+
+    pub fn temperature(&self, depth: Length) -> Temperature {
+        Temperature::new(self.temperature.at(*depth.value()) + ZERO_CELSIUS)
+    }
+
+    /// Practical salinity (PSU)
+    pub fn salinity(&self, depth: Length) -> f64 {
+        self.salinity.at(*depth.value())
+    }
+
+    /// Absolute pressure, atmospheric included
+    pub fn pressure(&self, depth: Length) -> Pressure {
+        Pressure::new(marine::atmospheric_pressure::<f64>().value() + self.sea_pressure_at(*depth.value()))
+    }
+
+    pub fn density(&self, depth: Length) -> Density {
+        let depth = depth.value().max(0.0);
+        Density::new(self.density_at(depth, self.sea_pressure_at(depth)))
+    }
+
+    /// Buoyancy of `volume` submerged at `depth`
+    pub fn buoyancy_force(&self, volume: Volume, depth: Length) -> Force {
+        Force::new(self.density(depth).value() * marine::gravity::<f64>().value() * volume.value())
+    }
+
+    /// Mackenzie (1981) sound speed
+    pub fn sound_speed(&self, depth: Length) -> Velocity {
+        let d = depth.value().max(0.0);
+        let t = self.temperature.at(d);
+        let s = self.salinity.at(d) - 35.0;
+        Velocity::new(
+            1448.96 + 4.591 * t - 5.304e-2 * t * t + 2.374e-4 * t * t * t + 1.340 * s + 1.630e-2 * d + 1.675e-7 * d * d
+                - 1.025e-2 * t * s
+                - 7.139e-13 * t * d * d * d,
+        )
+    }
+
+    /// Average sound speed of a straight ray between two depths, the harmonic
+    /// mean of the profile since travel time adds up slowness
+    pub fn mean_sound_speed(&self, from: Length, to: Length) -> Velocity {
+        let (a, b) = (*from.value(), *to.value());
+        if (b - a).abs() < 1e-9 {
+            return self.sound_speed(from);
+        }
+        const SAMPLES: usize = 32;
+        let slowness: f64 = (0..SAMPLES)
+            .map(|i| {
+                let depth = a + (b - a) * (i as f64 + 0.5) / SAMPLES as f64;
+                1.0 / self.sound_speed(Length::new(depth)).value()
+            })
+            .sum::<f64>()
+            / SAMPLES as f64;
+        Velocity::new(1.0 / slowness)
+    }
+
+    /// Sea pressure (Pa) at a depth, from the table
+    fn sea_pressure_at(&self, depth: f64) -> f64 {
+        let depth = depth.max(0.0);
+        let last = self.sea_pressure.len() - 1;
+        let index = ((depth / PRESSURE_TABLE_STEP) as usize).min(last.saturating_sub(1));
+        let t = depth / PRESSURE_TABLE_STEP - index as f64;
+        let next = (index + 1).min(last);
+        self.sea_pressure[index] + t * (self.sea_pressure[next] - self.sea_pressure[index])
+    }
+
+    /// EOS-80 density at `depth` under `sea_pressure` (Pa)
+    fn density_at(&self, depth: f64, sea_pressure: f64) -> f64 {
+        eos80(self.salinity.at(depth), self.temperature.at(depth), sea_pressure * 1e-5)
+    }
+}
+
+impl Default for WaterColumn {
+    /// Open ocean at 10 °C and 35 PSU
+    fn default() -> Self {
+        Self::uniform(Temperature::new(ZERO_CELSIUS + 10.0), 35.0)
+    }
+}
+
+/// UNESCO 1981 density (kg/m³) of seawater with salinity `s` (PSU) at `t` °C
+/// and sea pressure `p` (bar)
+fn eos80(s: f64, t: f64, p: f64) -> f64 {
+    let s15 = s * s.max(0.0).sqrt();
+    let pure = 999.842594 + t * (6.793952e-2 + t * (-9.095290e-3 + t * (1.001685e-4 + t * (-1.120083e-6 + t * 6.536332e-9))));
+    let surface = pure
+        + s * (0.824493 + t * (-4.0899e-3 + t * (7.6438e-5 + t * (-8.2467e-7 + t * 5.3875e-9))))
+        + s15 * (-5.72466e-3 + t * (1.0227e-4 - t * 1.6546e-6))
+        + 4.8314e-4 * s * s;
+    if p == 0.0 {
+        return surface;
+    }
+
+    // Secant bulk modulus K(s, t, p)
+    let pure_modulus = 19652.21 + t * (148.4206 + t * (-2.327105 + t * (1.360477e-2 - t * 5.155288e-5)));
+    let modulus = pure_modulus
+        + s * (54.6746 + t * (-0.603459 + t * (1.09987e-2 - t * 6.1670e-5)))
+        + s15 * (7.944e-2 + t * (1.6483e-2 - t * 5.3009e-4));
+    let a = 3.239908 + t * (1.43713e-3 + t * (1.16092e-4 - t * 5.77905e-7))
+        + s * (2.2838e-3 + t * (-1.0981e-5 - t * 1.6078e-6))
+        + 1.91075e-4 * s15;
+    let b = 8.50935e-5 + t * (-6.12293e-6 + t * 5.2787e-8) + s * (-9.9348e-7 + t * (2.0816e-8 + t * 9.1697e-10));
+    surface / (1.0 - p / (modulus + p * (a + p * b)))
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn celsius(c: f64) -> Temperature {
+        Temperature::new(ZERO_CELSIUS + c)
+    }
+
+    #[test]
+    fn test_eos80_check_values() {
+        // Check values from UNESCO technical paper 44
+        assert!((eos80(0.0, 5.0, 0.0) - 999.96675).abs() < 1e-5);
+        assert!((eos80(35.0, 5.0, 0.0) - 1027.67547).abs() < 1e-5);
+        assert!((eos80(35.0, 25.0, 1000.0) - 1062.53817).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_profiles() {
+        // Warm mixed layer over a thermocline
+        let temperature = [(Length::new(0.0), celsius(20.0)), (Length::new(50.0), celsius(20.0)), (Length::new(250.0), celsius(5.0))];
+        let column = WaterColumn::new(&temperature, &[(Length::new(0.0), 35.0)]).unwrap();
+        assert!((column.temperature(Length::new(150.0)).value() - celsius(12.5).value()).abs() < 1e-9);
+        assert!((column.temperature(Length::new(4000.0)).value() - celsius(5.0).value()).abs() < 1e-9);
+        assert_eq!(column.salinity(Length::new(10.0)), 35.0);
+
+        // Colder, deeper water is denser and carries sound more slowly until pressure wins
+        assert!(column.density(Length::new(300.0)) > column.density(Length::new(10.0)));
+        assert!(column.sound_speed(Length::new(300.0)) < column.sound_speed(Length::new(10.0)));
+        assert!(column.sound_speed(Length::new(4000.0)) > column.sound_speed(Length::new(300.0)));
+        assert!((column.sound_speed(Length::new(0.0)).value() - 1521.46).abs() < 0.01);
+
+        let mean = *column.mean_sound_speed(Length::new(0.0), Length::new(250.0)).value();
+        assert!(mean < *column.sound_speed(Length::new(0.0)).value() && mean > *column.sound_speed(Length::new(250.0)).value());
+
+        assert_eq!(WaterColumn::new(&[], &[(Length::new(0.0), 35.0)]), Err(WaterColumnError::EmptyProfile));
+        let unsorted = [(Length::new(10.0), celsius(10.0)), (Length::new(5.0), celsius(12.0))];
+        assert_eq!(WaterColumn::new(&unsorted, &[(Length::new(0.0), 35.0)]), Err(WaterColumnError::UnsortedDepths));
+    }
+
+    #[test]
+    fn test_pressure_and_buoyancy() {
+        let column = WaterColumn::default();
+        let surface = *column.density(Length::new(0.0)).value();
+        assert!((surface - eos80(35.0, 10.0, 0.0)).abs() < 1e-9);
+        assert!((column.pressure(Length::new(0.0)).value() - 101325.0).abs() < 1e-9);
+
+        // About a hundred atmospheres per kilometre, a little more as the water compresses
+        let sea = column.pressure(Length::new(1000.0)).value() - 101325.0;
+        assert!(sea > surface * 9.81 * 1000.0 && sea < 1.01 * surface * 9.81 * 1000.0);
+
+        let litre = Volume::new(1e-3);
+        let shallow = *column.buoyancy_force(litre, Length::new(0.0)).value();
+        assert!((shallow - surface * 9.81e-3).abs() < 1e-12);
+        assert!(column.buoyancy_force(litre, Length::new(5000.0)).value() > &shallow);
+    }
+}
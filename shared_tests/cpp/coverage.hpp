@@ -0,0 +1,199 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#pragma once
+
+#include <algorithm>
+#include <cstdint>
+#include <filesystem>
+#include <fstream>
+#include <iomanip>
+#include <map>
+#include <optional>
+#include <sstream>
+#include <string>
+#include <vector>
+#include <nlohmann/json.hpp>
+
+namespace gafro_test {
+
+/**
+ * @brief Operation coverage of JSON test suites
+ *
+ * The interpreter calls coverage::record with the name of every GA operation it
+ * evaluates ("Scalar::add", "Multivector::norm") and registered operations record
+ * their own names. Counting is off unless coverage::enable was called. Reports
+ * join the counters with json/api_surface.json and mark every operation that no
+ * test exercised. Mirrors shared_tests/rust/src/coverage.rs; both runners record
+ * the same names.
+ */
+namespace coverage {
+
+/// Module under which operations registered by the host program are reported
+inline const std::string REGISTERED_MODULE = "registered";
+
+/// Module under which recorded operations missing from the API surface are reported
+inline const std::string UNLISTED_MODULE = "unlisted";
+
+inline std::optional<std::map<std::string, uint64_t>>& counters() {
+    thread_local std::optional<std::map<std::string, uint64_t>> counts;
+    return counts;
+}
+
+/// Start counting operations on this thread, discarding earlier counts
+inline void enable() { counters().emplace(); }
+
+/// Stop counting and return the counts
+inline std::map<std::string, uint64_t> disable() {
+    auto counts = counters().value_or(std::map<std::string, uint64_t>{});
+    counters().reset();
+    return counts;
+}
+
+inline bool isEnabled() { return counters().has_value(); }
+
+/// Count one evaluation of operation; no-op while counting is disabled
+inline void record(const std::string& operation) {
+    if (counters()) {
+        (*counters())[operation]++;
+    }
+}
+
+/// Counts so far, without resetting them
+inline std::map<std::string, uint64_t> counts() { return counters().value_or(std::map<std::string, uint64_t>{}); }
+
+} // namespace coverage
+
+/**
+ * @brief Operations that the test suites are expected to cover, grouped by module
+ */
+struct ApiSurface {
+    std::map<std::string, std::vector<std::string>> modules;
+
+    static ApiSurface fromJson(const nlohmann::json& value) {
+        ApiSurface surface;
+        if (value.contains("modules") && value["modules"].is_object()) {
+            for (auto it = value["modules"].begin(); it != value["modules"].end(); ++it) {
+                auto& operations = surface.modules[it.key()];
+                for (const auto& operation : it.value()) {
+                    if (operation.is_string()) {
+                        operations.push_back(operation.get<std::string>());
+                    }
+                }
+            }
+        }
+        return surface;
+    }
+
+    /// The nearest api_surface.json in the directory of test_file or its ancestors
+    static ApiSurface findFor(const std::filesystem::path& test_file) {
+        auto directory = std::filesystem::absolute(test_file).parent_path();
+        while (true) {
+            auto candidate = directory / "api_surface.json";
+            if (std::filesystem::is_regular_file(candidate)) {
+                std::ifstream file(candidate);
+                auto value = nlohmann::json::parse(file, nullptr, false);
+                return value.is_discarded() ? ApiSurface{} : fromJson(value);
+            }
+            if (directory == directory.parent_path()) {
+                return ApiSurface{};
+            }
+            directory = directory.parent_path();
+        }
+    }
+};
+
+/**
+ * @brief Which operations of the API surface a test run exercised
+ */
+class CoverageReport {
+public:
+    struct Row {
+        std::string module;
+        std::string operation;
+        uint64_t hits = 0;
+    };
+
+    /// Join recorded counts with the API surface and the registered operations
+    CoverageReport(const ApiSurface& surface, const std::map<std::string, uint64_t>& counts,
+                   const std::vector<std::string>& registered) {
+        auto hits = [&counts](const std::string& operation) {
+            auto it = counts.find(operation);
+            return it == counts.end() ? uint64_t{0} : it->second;
+        };
+        for (const auto& [module, operations] : surface.modules) {
+            for (const auto& operation : operations) {
+                rows_.push_back({module, operation, hits(operation)});
+            }
+        }
+        for (const auto& operation : registered) {
+            rows_.push_back({coverage::REGISTERED_MODULE, operation, hits(operation)});
+        }
+        for (const auto& [operation, count] : counts) {
+            bool listed = std::any_of(rows_.begin(), rows_.end(),
+                                      [&operation = operation](const Row& row) { return row.operation == operation; });
+            if (!listed) {
+                rows_.push_back({coverage::UNLISTED_MODULE, operation, count});
+            }
+        }
+    }
+
+    const std::vector<Row>& rows() const { return rows_; }
+
+    size_t covered() const {
+        return std::count_if(rows_.begin(), rows_.end(), [](const Row& row) { return row.hits > 0; });
+    }
+
+    std::vector<std::string> untested() const {
+        std::vector<std::string> operations;
+        for (const auto& row : rows_) {
+            if (row.hits == 0) {
+                operations.push_back(row.operation);
+            }
+        }
+        return operations;
+    }
+
+    nlohmann::json toJson() const {
+        nlohmann::json modules = nlohmann::json::object();
+        for (const auto& row : rows_) {
+            auto& module = modules[row.module];
+            if (module.is_null()) {
+                module = {{"covered", 0}, {"total", 0}, {"operations", nlohmann::json::object()}};
+            }
+            module["covered"] = module["covered"].get<size_t>() + (row.hits > 0 ? 1 : 0);
+            module["total"] = module["total"].get<size_t>() + 1;
+            module["operations"][row.operation] = row.hits;
+        }
+        return {{"covered", covered()}, {"total", rows_.size()}, {"modules", modules}, {"untested", untested()}};
+    }
+
+    /// Coverage matrix as text; untested operations are marked with "!!"
+    std::string toText() const {
+        std::ostringstream text;
+        text << "\n=== Coverage ===\n";
+        for (size_t begin = 0; begin < rows_.size();) {
+            size_t end = begin;
+            size_t module_covered = 0;
+            while (end < rows_.size() && rows_[end].module == rows_[begin].module) {
+                module_covered += rows_[end].hits > 0 ? 1 : 0;
+                end++;
+            }
+            text << rows_[begin].module << " (" << module_covered << "/" << (end - begin) << ")\n";
+            for (size_t i = begin; i < end; ++i) {
+                text << "  " << (rows_[i].hits == 0 ? "!!" : "  ") << " " << std::left << std::setw(36)
+                     << rows_[i].operation << " " << rows_[i].hits << "\n";
+            }
+            begin = end;
+        }
+        text << "\nCovered: " << covered() << "/" << rows_.size() << " operations\n";
+        text << "================";
+        return text.str();
+    }
+
+private:
+    std::vector<Row> rows_;
+};
+
+} // namespace gafro_test
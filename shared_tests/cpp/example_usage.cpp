@@ -128,6 +128,33 @@ int main() {
     TestExecutionContext context;
     auto result = context.executeTestCase(custom_test);
     std::cout << "  Result: " << (result.passed ? "PASSED" : "FAILED") << "\n";
+    std::cout << "\n";
+    
+    // Example 8: Register a named operation and call it from a test case
+    std::cout << "8. Calling a registered operation:\n";
+    context.operations().registerFunction(
+        "buoyancy_force",
+        OperationSchema()
+            .input("displaced_volume", ValueType::number())
+            .input("fluid_density", ValueType::number())
+            .output("force", ValueType::number()),
+        [](const json& inputs) {
+            double gravity = 9.80665;
+            return json{{"force", inputs["fluid_density"].get<double>() * inputs["displaced_volume"].get<double>() * gravity}};
+        });
+    
+    TestCase operation_test;
+    operation_test.test_name = "buoyancy_of_one_liter";
+    operation_test.description = "Buoyancy of one liter of displaced sea water";
+    operation_test.category = "custom";
+    operation_test.operation = "buoyancy_force";
+    operation_test.inputs = {{"displaced_volume", 0.001}, {"fluid_density", 1025.0}};
+    operation_test.expected_outputs = {{"force", 10.05181625}};
+    operation_test.tolerance = 1e-10;
+    
+    auto operation_result = context.executeTestCase(operation_test);
+    std::cout << "  Registered operations: " << context.operations().names().size() << "\n";
+    std::cout << "  Result: " << (operation_result.passed ? "PASSED" : "FAILED") << "\n";
     
     std::cout << "\n=== Example Complete ===\n";
     
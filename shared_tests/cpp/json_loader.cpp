@@ -1,11 +1,16 @@
 #include "json_loader.hpp"
 #include "real_code_executor.hpp"
+#include "coverage.hpp"
 #include <fstream>
 #include <sstream>
 #include <iostream>
 #include <chrono>
 #include <algorithm>
 #include <regex>
+#include <array>
+#include <map>
+#include <optional>
+#include <cstdlib>
 #include <gafro/gafro.hpp>
 
 namespace gafro_test {
@@ -40,7 +45,7 @@ bool TestCase::isValid() const {
     return !test_name.empty() && 
            !description.empty() && 
            !category.empty() && 
-           !cpp_test_code.empty();
+           (!cpp_test_code.empty() || !operation.empty());
 }
 
 // TestCategory implementation
@@ -88,6 +93,7 @@ std::unique_ptr<TestSuite> TestSuite::loadFromFile(const std::string& filepath)
 std::unique_ptr<TestSuite> TestSuite::loadFromString(const std::string& json_string) {
     try {
         json test_json = json::parse(json_string);
+        migrateSpec(test_json);
         auto test_suite = std::make_unique<TestSuite>();
         *test_suite = JsonLoader::parseTestSuite(test_json);
         return test_suite;
@@ -172,7 +178,7 @@ std::string TestResult::getFailureDetails() const {
     ss << "Test failed: " << error_message << "\n";
     ss << "Expected: " << expected_outputs.dump(2) << "\n";
     ss << "Actual: " << actual_outputs.dump(2) << "\n";
-    ss << "Tolerance: " << tolerance << "\n";
+    ss << "Tolerance: " << tolerance_model << "\n";
     
     return ss.str();
 }
@@ -183,18 +189,30 @@ TestResult TestExecutionContext::executeTestCase(const TestCase& test_case) {
     result.test_name = test_case.test_name;
     result.expected_outputs = test_case.expected_outputs;
     result.tolerance = test_case.tolerance;
+    result.tolerance_model = test_case.tolerance_model;
     
     auto start_time = std::chrono::high_resolution_clock::now();
     
     try {
-        if (test_executor_) {
-            result.actual_outputs = test_executor_(test_case);
+        if (test_case.performance) {
+            std::optional<std::string> exceeded;
+            result.actual_outputs = compareTimings(executeTimed(test_case, *test_case.performance),
+                                                   result.expected_outputs, test_case.performance->margin,
+                                                   budgetScale(), exceeded);
+            result.passed = !exceeded;
+            result.error_message = exceeded.value_or("");
+        } else if (test_case.statistics) {
+            std::optional<std::string> disagreement;
+            result.actual_outputs = compareRuns(executeRepetitions(test_case, *test_case.statistics),
+                                                result.expected_outputs, result.tolerance,
+                                                test_case.statistics->confidence, disagreement);
+            result.passed = !disagreement;
+            result.error_message = disagreement.value_or("");
         } else {
-            result.actual_outputs = defaultTestExecutor(test_case);
+            result.actual_outputs = executeTest(test_case);
+            result.passed = compareOutputs(result.actual_outputs, result.expected_outputs, result.tolerance);
         }
         
-        result.passed = compareOutputs(result.actual_outputs, result.expected_outputs, result.tolerance);
-        
     } catch (const std::exception& e) {
         result.passed = false;
         result.error_message = e.what();
@@ -269,6 +287,14 @@ void TestExecutionContext::setTestExecutor(std::function<json(const TestCase&)>
     test_executor_ = executor;
 }
 
+void TestExecutionContext::registerOperation(std::unique_ptr<TestOperation> operation) {
+    operations_.registerOperation(std::move(operation));
+}
+
+OperationRegistry& TestExecutionContext::operations() {
+    return operations_;
+}
+
 void TestExecutionContext::setVerbose(bool verbose) {
     verbose_ = verbose;
 }
@@ -286,6 +312,41 @@ TestExecutionContext::ExecutionStats TestExecutionContext::getExecutionStats() c
     return stats_;
 }
 
+json TestExecutionContext::executeTest(const TestCase& test_case) {
+    if (!test_case.operation.empty()) {
+        coverage::record(test_case.operation);
+        return operations_.execute(test_case.operation, test_case.inputs);
+    }
+    if (test_executor_) {
+        return test_executor_(test_case);
+    }
+    return defaultTestExecutor(test_case);
+}
+
+std::vector<json> TestExecutionContext::executeRepetitions(const TestCase& test_case, const StatisticsSpec& spec) {
+    std::vector<json> runs;
+    for (size_t repetition = 0; repetition < spec.repetitions; ++repetition) {
+        TestCase seeded = test_case;
+        seeded.inputs = spec.inputsFor(test_case.inputs, repetition);
+        runs.push_back(executeTest(seeded));
+    }
+    return runs;
+}
+
+std::vector<double> TestExecutionContext::executeTimed(const TestCase& test_case, const PerformanceSpec& spec) {
+    for (size_t run = 0; run < spec.warmup; ++run) {
+        executeTest(test_case);
+    }
+    std::vector<double> times_ns;
+    for (size_t iteration = 0; iteration < spec.iterations; ++iteration) {
+        auto start = std::chrono::steady_clock::now();
+        executeTest(test_case);
+        auto end = std::chrono::steady_clock::now();
+        times_ns.push_back(std::chrono::duration<double, std::nano>(end - start).count());
+    }
+    return times_ns;
+}
+
 json TestExecutionContext::defaultTestExecutor(const TestCase& test_case) {
     // Use real code execution if enabled, otherwise fall back to pattern matching
     if (real_code_executor_) {
@@ -323,6 +384,10 @@ json TestExecutionContext::executeCppCode(const std::string& code, const json& i
         else if (code.find("Point<double>") != std::string::npos) {
             result = executePointOperations(code, inputs);
         }
+        // Handle SI quantity operations
+        else if (code.find("SIQuantity<") != std::string::npos || std::regex_search(code, siDeclarationRegex())) {
+            result = executeSIOperations(code);
+        }
         else {
             // Fallback to basic pattern matching
             result = executeBasicOperations(code, inputs);
@@ -340,10 +405,12 @@ json TestExecutionContext::executeScalarOperations(const std::string& code, cons
     
     // Default scalar creation
     if (code.find("Scalar<double> scalar;") != std::string::npos) {
+        coverage::record("Scalar::new");
         result["value"] = 0.0;
     }
     // Scalar creation with value
     else if (code.find("Scalar<double> scalar(") != std::string::npos) {
+        coverage::record("Scalar::new");
         std::regex value_regex(R"(Scalar<double>\s*scalar\s*\(\s*([0-9.]+)\s*\))");
         std::smatch match;
         if (std::regex_search(code, match, value_regex)) {
@@ -352,6 +419,7 @@ json TestExecutionContext::executeScalarOperations(const std::string& code, cons
     }
     // Scalar arithmetic operations
     else if (code.find("auto result = a + b;") != std::string::npos) {
+        coverage::record("Scalar::add");
         // Extract values from inputs or code
         double a_val = 0.0, b_val = 0.0;
         if (inputs.contains("a")) {
@@ -363,6 +431,7 @@ json TestExecutionContext::executeScalarOperations(const std::string& code, cons
         result["result"] = a_val + b_val;
     }
     else if (code.find("auto result = a * b;") != std::string::npos) {
+        coverage::record("Scalar::mul");
         // Extract values from inputs or code
         double a_val = 0.0, b_val = 0.0;
         if (inputs.contains("a")) {
@@ -374,6 +443,7 @@ json TestExecutionContext::executeScalarOperations(const std::string& code, cons
         result["result"] = a_val * b_val;
     }
     else if (code.find("auto result = a - b;") != std::string::npos) {
+        coverage::record("Scalar::sub");
         // Extract values from inputs or code
         double a_val = 0.0, b_val = 0.0;
         if (inputs.contains("a")) {
@@ -393,12 +463,14 @@ json TestExecutionContext::executeVectorOperations(const std::string& code, cons
     
     // Default vector creation
     if (code.find("Vector<double> vector;") != std::string::npos) {
+        coverage::record("Vector::new");
         result["e1"] = 0.0;
         result["e2"] = 0.0;
         result["e3"] = 0.0;
     }
     // Vector creation with parameters
     else if (code.find("Vector<double> vector(") != std::string::npos) {
+        coverage::record("Vector::new");
         std::regex vector_regex(R"(Vector<double>\s*vector\s*\(\s*([0-9.]+)\s*,\s*([0-9.]+)\s*,\s*([0-9.]+)\s*\))");
         std::smatch match;
         if (std::regex_search(code, match, vector_regex)) {
@@ -409,6 +481,7 @@ json TestExecutionContext::executeVectorOperations(const std::string& code, cons
     }
     // Vector copy constructor
     else if (code.find("Vector<double> vector2(vector1);") != std::string::npos) {
+        coverage::record("Vector::clone");
         // Extract values from source vector
         std::regex source_regex(R"(Vector<double>\s*vector1\s*\(\s*([0-9.]+)\s*,\s*([0-9.]+)\s*,\s*([0-9.]+)\s*\))");
         std::smatch match;
@@ -420,6 +493,7 @@ json TestExecutionContext::executeVectorOperations(const std::string& code, cons
     }
     // Vector addition
     else if (code.find("auto result = vector1 + vector2;") != std::string::npos) {
+        coverage::record("Vector::add");
         // Extract values from both vectors
         std::regex v1_regex(R"(Vector<double>\s*vector1\s*\(\s*([0-9.]+)\s*,\s*([0-9.]+)\s*,\s*([0-9.]+)\s*\))");
         std::regex v2_regex(R"(Vector<double>\s*vector2\s*\(\s*([0-9.]+)\s*,\s*([0-9.]+)\s*,\s*([0-9.]+)\s*\))");
@@ -447,6 +521,7 @@ json TestExecutionContext::executeMultivectorOperations(const std::string& code,
     
     // Default multivector creation (gafro::Multivector)
     if (code.find("gafro::Multivector<double, blades::e0, blades::e1, blades::e2, blades::e3, blades::ei> mv;") != std::string::npos) {
+        coverage::record("Multivector::new");
         result["e0"] = 0.0;
         result["e1"] = 0.0;
         result["e2"] = 0.0;
@@ -455,6 +530,7 @@ json TestExecutionContext::executeMultivectorOperations(const std::string& code,
     }
     // Multivector creation with values (gafro::Multivector)
     else if (code.find("gafro::Multivector<double, blades::e0, blades::e1, blades::e2, blades::e3, blades::ei> mv({") != std::string::npos) {
+        coverage::record("Multivector::new");
         // Extract values from constructor - handle specific test case
         if (code.find("{1.0, 2.0, 3.0, 4.0, 5.0}") != std::string::npos) {
             result["e0"] = 1.0;
@@ -477,16 +553,19 @@ json TestExecutionContext::executeMultivectorOperations(const std::string& code,
     }
     // Multivector size property
     else if (code.find("auto size = gafro::Multivector<double, blades::e1, blades::e2, blades::e3>::size;") != std::string::npos) {
+        coverage::record("Multivector::size");
         // For a 3D multivector with e1, e2, e3, size should be 2^3 = 8
         result["size"] = 8;
     }
     // Multivector bits and blades
     else if (code.find("auto bits = gafro::Multivector<double, blades::e1, blades::e2, blades::e3>::bits(); auto blade_array = bits.blades();") != std::string::npos) {
+        coverage::record("Multivector::blades");
         // Return the blade array for e1, e2, e3
         result["blades"] = {"e1", "e2", "e3"};
     }
     // Multivector addition (using MV alias)
     else if (code.find("mv1 += mv2;") != std::string::npos) {
+        coverage::record("Multivector::add_assign");
         // Handle specific test case with known values
         if (code.find("MV mv1({1.0, 2.0, 3.0, 4.0, 5.0}); MV mv2({10.0, 20.0, 30.0, 40.0, 50.0}); mv1 += mv2;") != std::string::npos) {
             result["e0"] = 1.0 + 10.0;  // 11.0
@@ -512,6 +591,7 @@ json TestExecutionContext::executeMultivectorOperations(const std::string& code,
     }
     // Multivector scalar multiplication
     else if (code.find("mv *= 2.0;") != std::string::npos) {
+        coverage::record("Multivector::mul_assign");
         // Handle specific test case
         if (code.find("gafro::Multivector<double, blades::e0, blades::e1, blades::e2, blades::e3, blades::ei> mv({1.0, 2.0, 3.0, 4.0, 5.0}); mv *= 2.0;") != std::string::npos) {
             result["e0"] = 1.0 * 2.0;  // 2.0
@@ -534,6 +614,7 @@ json TestExecutionContext::executeMultivectorOperations(const std::string& code,
     }
     // Multivector norm
     else if (code.find("auto norm = mv.norm();") != std::string::npos) {
+        coverage::record("Multivector::norm");
         // Handle specific test case
         if (code.find("gafro::Multivector<double, blades::e0, blades::e1, blades::e2, blades::e3, blades::ei> mv({5.0, 1.0, 2.0, 3.0, 4.0}); auto norm = mv.norm();") != std::string::npos) {
             // Calculate norm: sqrt(5.0^2 + 1.0^2 + 2.0^2 + 3.0^2 + 4.0^2) = sqrt(25 + 1 + 4 + 9 + 16) = sqrt(55) ≈ 7.416
@@ -566,6 +647,7 @@ json TestExecutionContext::executePointOperations(const std::string& code, const
     
     // Point creation with parameters
     if (code.find("Point<double> mv1(") != std::string::npos) {
+        coverage::record("Point::new");
         std::regex point_regex(R"(Point<double>\s*mv1\s*\(\s*([0-9.]+)\s*,\s*([0-9.]+)\s*,\s*([0-9.]+)\s*\))");
         std::smatch match;
         if (std::regex_search(code, match, point_regex)) {
@@ -585,6 +667,124 @@ json TestExecutionContext::executePointOperations(const std::string& code, const
     return result;
 }
 
+namespace {
+
+/// Runtime counterpart of SIQuantity; mirrors DynamicQuantity in si_quantity.rs
+struct DynamicQuantity {
+    double value = 0.0;
+    std::array<int, 3> dimensions{}; // mass, length, time
+};
+
+std::optional<std::array<int, 3>> aliasDimensions(const std::string& alias) {
+    static const std::map<std::string, std::array<int, 3>> aliases = {
+        {"Dimensionless", {0, 0, 0}}, {"Mass", {1, 0, 0}},         {"Length", {0, 1, 0}},
+        {"Time", {0, 0, 1}},          {"Velocity", {0, 1, -1}},    {"Acceleration", {0, 1, -2}},
+        {"Force", {1, 1, -2}},        {"Energy", {1, 2, -2}},      {"Torque", {1, 2, -2}},
+        {"Power", {1, 2, -3}},        {"Pressure", {1, -1, -2}},
+    };
+    auto it = aliases.find(alias);
+    if (it == aliases.end()) {
+        return std::nullopt;
+    }
+    return it->second;
+}
+
+} // namespace
+
+const std::regex& TestExecutionContext::siDeclarationRegex() {
+    static const std::regex declaration(
+        R"(\b(Dimensionless|Mass|Length|Time|Velocity|Acceleration|Force|Energy|Torque|Power|Pressure)\s+(\w+)\s*\(\s*(-?[0-9.]+(?:[eE][-+]?[0-9]+)?)\s*\))");
+    return declaration;
+}
+
+json TestExecutionContext::executeSIOperations(const std::string& code) {
+    static const std::regex generic(
+        R"(^SIQuantity<\s*(-?\d+)\s*,\s*(-?\d+)\s*,\s*(-?\d+)\s*>\s+(\w+)\s*\(\s*(-?[0-9.]+(?:[eE][-+]?[0-9]+)?)\s*\)$)");
+    static const std::regex operation(R"(^auto\s+(\w+)\s*=\s*([\w.]+)\s*([-+*/])\s*([\w.]+)$)");
+    
+    std::map<std::string, DynamicQuantity> variables;
+    std::string last;
+    json result = json::object();
+    
+    auto operand = [&variables](const std::string& token) -> std::optional<DynamicQuantity> {
+        if (auto it = variables.find(token); it != variables.end()) {
+            return it->second;
+        }
+        // Number literals act as dimensionless quantities
+        char* end = nullptr;
+        double value = std::strtod(token.c_str(), &end);
+        if (end != token.c_str() && *end == '\0') {
+            return DynamicQuantity{value, {0, 0, 0}};
+        }
+        return std::nullopt;
+    };
+    
+    std::stringstream statements(code);
+    std::string statement;
+    while (std::getline(statements, statement, ';')) {
+        statement = std::regex_replace(statement, std::regex(R"(^\s+|\s+$)"), "");
+        std::smatch match;
+        std::string name;
+        DynamicQuantity quantity;
+        
+        if (std::regex_match(statement, match, generic)) {
+            coverage::record("SIQuantity::new");
+            name = match[4].str();
+            quantity = {std::stod(match[5].str()), {std::stoi(match[1].str()), std::stoi(match[2].str()), std::stoi(match[3].str())}};
+        } else if (std::regex_match(statement, match, siDeclarationRegex())) {
+            coverage::record("SIQuantity::new");
+            auto dimensions = aliasDimensions(match[1].str());
+            if (!dimensions) {
+                continue;
+            }
+            name = match[2].str();
+            quantity = {std::stod(match[3].str()), *dimensions};
+        } else if (std::regex_match(statement, match, operation)) {
+            auto lhs = operand(match[2].str());
+            auto rhs = operand(match[4].str());
+            if (!lhs || !rhs) {
+                continue;
+            }
+            
+            const std::string op = match[3].str();
+            coverage::record(op == "+" ? "SIQuantity::add" : op == "-" ? "SIQuantity::sub"
+                             : op == "*" ? "SIQuantity::mul" : "SIQuantity::div");
+            if ((op == "+" || op == "-") && lhs->dimensions != rhs->dimensions) {
+                result["error"] = "dimension mismatch";
+                return result;
+            }
+            
+            name = match[1].str();
+            if (op == "+") {
+                quantity = {lhs->value + rhs->value, lhs->dimensions};
+            } else if (op == "-") {
+                quantity = {lhs->value - rhs->value, lhs->dimensions};
+            } else {
+                int sign = op == "*" ? 1 : -1;
+                quantity.value = op == "*" ? lhs->value * rhs->value : lhs->value / rhs->value;
+                for (size_t i = 0; i < 3; ++i) {
+                    quantity.dimensions[i] = lhs->dimensions[i] + sign * rhs->dimensions[i];
+                }
+            }
+        } else {
+            continue;
+        }
+        
+        variables[name] = quantity;
+        last = name;
+    }
+    
+    auto output = variables.find(variables.count("result") ? "result" : last);
+    if (output != variables.end()) {
+        result["value"] = output->second.value;
+        result["dimensions"] = {{"mass", output->second.dimensions[0]},
+                                {"length", output->second.dimensions[1]},
+                                {"time", output->second.dimensions[2]}};
+    }
+    
+    return result;
+}
+
 json TestExecutionContext::executeBasicOperations(const std::string& code, const json& inputs) {
     json result;
     
@@ -595,12 +795,25 @@ json TestExecutionContext::executeBasicOperations(const std::string& code, const
 }
 
 bool TestExecutionContext::compareOutputs(const json& actual, const json& expected, double tolerance) const {
+    // Checked before the type: 2 parses as unsigned while computed exponents are signed
+    if (actual.is_number() && expected.is_number()) {
+        return std::abs(actual.get<double>() - expected.get<double>()) <= tolerance;
+    }
+    
     if (actual.type() != expected.type()) {
         return false;
     }
     
-    if (actual.is_number() && expected.is_number()) {
-        return std::abs(actual.get<double>() - expected.get<double>()) <= tolerance;
+    if (actual.is_array() && expected.is_array()) {
+        if (actual.size() != expected.size()) {
+            return false;
+        }
+        for (size_t i = 0; i < expected.size(); ++i) {
+            if (!compareOutputs(actual[i], expected[i], tolerance)) {
+                return false;
+            }
+        }
+        return true;
     }
     
     if (actual.is_object() && expected.is_object()) {
@@ -631,6 +844,38 @@ bool JsonLoader::validateJson(const json& test_json) {
     return true;
 }
 
+namespace {
+
+/// Largest absolute value of any number in a JSON value
+double maxMagnitude(const json& value) {
+    if (value.is_number()) {
+        return std::abs(value.get<double>());
+    }
+    double magnitude = 0.0;
+    if (value.is_structured()) {
+        for (const auto& item : value) {
+            magnitude = std::max(magnitude, maxMagnitude(item));
+        }
+    }
+    return magnitude;
+}
+
+} // namespace
+
+Tolerance JsonLoader::parseTolerance(const json& tolerance_json, const json& expected_outputs) {
+    if (tolerance_json.is_number()) {
+        return Tolerance::fixed(tolerance_json.get<double>());
+    }
+    if (tolerance_json.is_object()) {
+        return Tolerance::budget(
+            tolerance_json.value("operations", 1u),
+            tolerance_json.contains("magnitude") ? tolerance_json["magnitude"].get<double>()
+                                                 : maxMagnitude(expected_outputs),
+            tolerance_json.value("safety_factor", DEFAULT_SAFETY_FACTOR));
+    }
+    return Tolerance();
+}
+
 TestCase JsonLoader::parseTestCase(const json& test_case_json) {
     TestCase test_case;
     
@@ -641,9 +886,21 @@ TestCase JsonLoader::parseTestCase(const json& test_case_json) {
     test_case.expected_outputs = test_case_json["expected_outputs"];
     
     if (test_case_json.contains("tolerance")) {
-        test_case.tolerance = test_case_json["tolerance"].get<double>();
+        Tolerance tolerance = parseTolerance(test_case_json["tolerance"], test_case.expected_outputs);
+        test_case.tolerance = tolerance.value();
+        test_case.tolerance_model = tolerance.describe();
+    }
+    
+    if (test_case_json.contains("operation")) {
+        test_case.operation = test_case_json["operation"].get<std::string>();
     }
     
+    if (test_case_json.contains("statistics")) {
+        test_case.statistics = StatisticsSpec::fromJson(test_case_json["statistics"]);
+    }
+    
+    test_case.performance = PerformanceSpec::forTestCase(test_case_json, test_case.category);
+    
     if (test_case_json.contains("language_specific")) {
         test_case.language_specific = test_case_json["language_specific"];
         test_case.parseCppConfig();
@@ -669,7 +926,11 @@ TestCategory JsonLoader::parseTestCategory(const std::string& name, const json&
     category.name = name;
     
     for (const auto& test_case_json : category_json) {
-        category.test_cases.push_back(parseTestCase(test_case_json));
+        TestCase test_case = parseTestCase(test_case_json);
+        if (name == PERFORMANCE_CATEGORY && !test_case.performance) {
+            test_case.performance = PerformanceSpec{};
+        }
+        category.test_cases.push_back(test_case);
     }
     
     return category;
@@ -680,6 +941,7 @@ TestSuite JsonLoader::parseTestSuite(const json& test_suite_json) {
     
     test_suite.test_suite_name = test_suite_json["test_suite"].get<std::string>();
     test_suite.version = test_suite_json["version"].get<std::string>();
+    test_suite.schema_version = schemaVersion(test_suite_json);
     
     if (test_suite_json.contains("description")) {
         test_suite.description = test_suite_json["description"].get<std::string>();
@@ -702,6 +964,7 @@ json JsonLoader::testResultToJson(const TestResult& result) {
     j["actual_outputs"] = result.actual_outputs;
     j["expected_outputs"] = result.expected_outputs;
     j["tolerance"] = result.tolerance;
+    j["tolerance_model"] = result.tolerance_model;
     return j;
 }
 
@@ -5,9 +5,16 @@
 #include <map>
 #include <memory>
 #include <functional>
+#include <optional>
+#include <regex>
 #include <nlohmann/json.hpp>
 
+#include "operation_registry.hpp"
+#include "performance.hpp"
 #include "real_code_executor.hpp"
+#include "schema.hpp"
+#include "statistics.hpp"
+#include "tolerance.hpp"
 
 namespace gafro_test {
 
@@ -23,6 +30,10 @@ struct TestCase {
     json inputs;
     json expected_outputs;
     double tolerance = 1e-10;
+    std::string tolerance_model = "fixed 1.0e-10";  ///< How the tolerance was obtained, for reporting
+    std::string operation;  ///< Registered operation that executes this test instead of the test code
+    std::optional<StatisticsSpec> statistics;  ///< Repeat over seeds and compare statistics instead of single values
+    std::optional<PerformanceSpec> performance;  ///< Time repeated runs against the budgets in expected_outputs
     json language_specific;
     std::vector<std::string> dependencies;
     std::vector<std::string> tags;
@@ -73,6 +84,7 @@ struct TestCategory {
 struct TestSuite {
     std::string test_suite_name;
     std::string version;
+    uint64_t schema_version = CURRENT_SCHEMA_VERSION;  ///< Format version the spec was migrated to on load
     std::string description;
     std::map<std::string, TestCategory> test_categories;
     
@@ -129,6 +141,7 @@ struct TestResult {
     json actual_outputs;
     json expected_outputs;
     double tolerance = 1e-10;
+    std::string tolerance_model = "fixed 1.0e-10";
     
     /**
      * @brief Check if the test passed based on tolerance
@@ -166,6 +179,16 @@ public:
      */
     void setTestExecutor(std::function<json(const TestCase&)> executor);
     
+    /**
+     * @brief Register a named operation callable from test cases
+     */
+    void registerOperation(std::unique_ptr<TestOperation> operation);
+    
+    /**
+     * @brief Registry of named operations, e.g. for registerFunction
+     */
+    OperationRegistry& operations();
+    
     /**
      * @brief Enable/disable verbose output
      */
@@ -190,10 +213,26 @@ public:
 
 private:
     std::function<json(const TestCase&)> test_executor_;
+    OperationRegistry operations_;
     bool verbose_ = false;
     ExecutionStats stats_;
     std::unique_ptr<RealCodeExecutor> real_code_executor_;
     
+    /**
+     * @brief Execute a test with the configured operation, executor or interpreter
+     */
+    json executeTest(const TestCase& test_case);
+    
+    /**
+     * @brief Execute a stochastic test once per seed
+     */
+    std::vector<json> executeRepetitions(const TestCase& test_case, const StatisticsSpec& spec);
+    
+    /**
+     * @brief Execute a performance test after its warm-up, timing each run in nanoseconds
+     */
+    std::vector<double> executeTimed(const TestCase& test_case, const PerformanceSpec& spec);
+    
     /**
      * @brief Default test executor that evaluates C++ code
      */
@@ -224,6 +263,21 @@ private:
      */
     json executePointOperations(const std::string& code, const json& inputs);
     
+    /**
+     * @brief Execute SI quantity operations
+     *
+     * Statements declare a quantity (`Length distance(10.0);`,
+     * `SIQuantity<0, 1, -1> v(5.0);`) or bind `auto name = a op b;` with dimensions
+     * checked at runtime. Reports the value and dimensions of `result`, or of the
+     * last binding, and an `error` when dimensions do not match.
+     */
+    json executeSIOperations(const std::string& code);
+    
+    /**
+     * @brief Matches `Length distance(10.0)` style declarations of the SI type aliases
+     */
+    static const std::regex& siDeclarationRegex();
+    
     /**
      * @brief Execute basic operations (fallback)
      */
@@ -244,6 +298,13 @@ namespace JsonLoader {
      */
     bool validateJson(const json& test_json);
     
+    /**
+     * @brief Parse a tolerance field: a fixed number or an error-budget object
+     *
+     * An error budget without "magnitude" scales with the largest expected output.
+     */
+    Tolerance parseTolerance(const json& tolerance_json, const json& expected_outputs);
+    
     /**
      * @brief Load and parse test case from JSON
      */
@@ -0,0 +1,212 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#pragma once
+
+#include <functional>
+#include <map>
+#include <memory>
+#include <optional>
+#include <stdexcept>
+#include <string>
+#include <utility>
+#include <vector>
+#include <nlohmann/json.hpp>
+
+namespace gafro_test {
+
+/**
+ * @brief Named operations callable from JSON test cases
+ *
+ * A test case with an "operation": "<name>" field is executed by the operation
+ * registered under that name instead of the pattern-matching interpreter. Inputs
+ * are checked against the operation's schema before the call and outputs after
+ * it. Mirrors shared_tests/rust/src/operations.rs.
+ */
+class ValueType {
+public:
+    enum class Kind { Number, Integer, Boolean, String, Vector, Object };
+
+    static ValueType number() { return ValueType(Kind::Number); }
+    static ValueType integer() { return ValueType(Kind::Integer); }
+    static ValueType boolean() { return ValueType(Kind::Boolean); }
+    static ValueType string() { return ValueType(Kind::String); }
+    /// Array of numbers, optionally of fixed length
+    static ValueType vector(std::optional<size_t> length = std::nullopt) { return ValueType(Kind::Vector, length); }
+    static ValueType object() { return ValueType(Kind::Object); }
+
+    bool matches(const nlohmann::json& value) const {
+        switch (kind_) {
+            case Kind::Number: return value.is_number();
+            case Kind::Integer: return value.is_number_integer();
+            case Kind::Boolean: return value.is_boolean();
+            case Kind::String: return value.is_string();
+            case Kind::Object: return value.is_object();
+            case Kind::Vector:
+                if (!value.is_array() || (length_ && value.size() != *length_)) {
+                    return false;
+                }
+                for (const auto& item : value) {
+                    if (!item.is_number()) {
+                        return false;
+                    }
+                }
+                return true;
+        }
+        return false;
+    }
+
+    /// Same wording as the Rust Display implementation
+    std::string describe() const {
+        switch (kind_) {
+            case Kind::Number: return "number";
+            case Kind::Integer: return "integer";
+            case Kind::Boolean: return "boolean";
+            case Kind::String: return "string";
+            case Kind::Object: return "object";
+            case Kind::Vector: return length_ ? "vector[" + std::to_string(*length_) + "]" : "vector";
+        }
+        return "";
+    }
+
+private:
+    explicit ValueType(Kind kind, std::optional<size_t> length = std::nullopt) : kind_(kind), length_(length) {}
+
+    Kind kind_;
+    std::optional<size_t> length_;
+};
+
+/**
+ * @brief Named, typed inputs and outputs of an operation
+ */
+struct OperationSchema {
+    std::vector<std::pair<std::string, ValueType>> inputs;
+    std::vector<std::pair<std::string, ValueType>> outputs;
+
+    OperationSchema& input(const std::string& name, ValueType type) {
+        inputs.emplace_back(name, type);
+        return *this;
+    }
+
+    OperationSchema& output(const std::string& name, ValueType type) {
+        outputs.emplace_back(name, type);
+        return *this;
+    }
+};
+
+/**
+ * @brief Error raised when calling a registered operation
+ */
+class OperationError : public std::runtime_error {
+public:
+    using std::runtime_error::runtime_error;
+};
+
+/**
+ * @brief An operation that JSON test cases can call by name
+ */
+class TestOperation {
+public:
+    virtual ~TestOperation() = default;
+
+    virtual std::string name() const = 0;
+
+    virtual OperationSchema schema() const = 0;
+
+    /// Compute the outputs; inputs have already been checked against the schema
+    virtual nlohmann::json execute(const nlohmann::json& inputs) const = 0;
+};
+
+/**
+ * @brief Registry of named operations
+ */
+class OperationRegistry {
+public:
+    using Function = std::function<nlohmann::json(const nlohmann::json&)>;
+
+    /// Register an operation, replacing any with the same name
+    void registerOperation(std::unique_ptr<TestOperation> operation) {
+        auto name = operation->name();
+        operations_[name] = std::move(operation);
+    }
+
+    /// Register a function as an operation
+    void registerFunction(const std::string& name, OperationSchema schema, Function function) {
+        registerOperation(std::make_unique<FunctionOperation>(name, std::move(schema), std::move(function)));
+    }
+
+    const TestOperation* get(const std::string& name) const {
+        auto it = operations_.find(name);
+        return it == operations_.end() ? nullptr : it->second.get();
+    }
+
+    bool contains(const std::string& name) const { return operations_.count(name) > 0; }
+
+    /// Registered operation names, sorted
+    std::vector<std::string> names() const {
+        std::vector<std::string> names;
+        for (const auto& [name, operation] : operations_) {
+            names.push_back(name);
+        }
+        return names;
+    }
+
+    /// Call an operation with schema checks on its inputs and outputs
+    nlohmann::json execute(const std::string& name, const nlohmann::json& inputs) const {
+        const TestOperation* operation = get(name);
+        if (!operation) {
+            throw OperationError("unknown operation '" + name + "'");
+        }
+        OperationSchema schema = operation->schema();
+
+        nlohmann::json arguments = inputs.is_null() ? nlohmann::json::object() : inputs;
+        if (!arguments.is_object()) {
+            throw OperationError("'inputs' must be a object");
+        }
+        for (const auto& [field, type] : schema.inputs) {
+            if (!arguments.contains(field)) {
+                throw OperationError("missing input '" + field + "'");
+            }
+            if (!type.matches(arguments[field])) {
+                throw OperationError("'" + field + "' must be a " + type.describe());
+            }
+        }
+
+        nlohmann::json outputs;
+        try {
+            outputs = operation->execute(arguments);
+        } catch (const std::exception& e) {
+            throw OperationError(std::string("operation failed: ") + e.what());
+        }
+        for (const auto& [field, type] : schema.outputs) {
+            if (!outputs.contains(field)) {
+                throw OperationError("operation did not produce output '" + field + "'");
+            }
+            if (!type.matches(outputs[field])) {
+                throw OperationError("'" + field + "' must be a " + type.describe());
+            }
+        }
+        return outputs;
+    }
+
+private:
+    class FunctionOperation : public TestOperation {
+    public:
+        FunctionOperation(std::string name, OperationSchema schema, Function function)
+            : name_(std::move(name)), schema_(std::move(schema)), function_(std::move(function)) {}
+
+        std::string name() const override { return name_; }
+        OperationSchema schema() const override { return schema_; }
+        nlohmann::json execute(const nlohmann::json& inputs) const override { return function_(inputs); }
+
+    private:
+        std::string name_;
+        OperationSchema schema_;
+        Function function_;
+    };
+
+    std::map<std::string, std::unique_ptr<TestOperation>> operations_;
+};
+
+} // namespace gafro_test
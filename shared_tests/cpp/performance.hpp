@@ -0,0 +1,139 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#pragma once
+
+#include <algorithm>
+#include <cstdlib>
+#include <optional>
+#include <sstream>
+#include <string>
+#include <vector>
+#include <nlohmann/json.hpp>
+
+#include "statistics.hpp"
+
+namespace gafro_test {
+
+/**
+ * @brief Time budgets for performance test cases
+ *
+ * Test cases in the "performance" category, or with a "performance" section,
+ * run `warmup` times untimed and then `iterations` times timed. Each entry of
+ * expected_outputs is a budget in nanoseconds (mean_ns, median_ns, min_ns,
+ * max_ns or quantiles) exceeded when the measurement is above
+ * budget * (1 + margin) * GAFRO_PERF_BUDGET_SCALE. Mirrors
+ * shared_tests/rust/src/performance.rs.
+ */
+constexpr const char* PERFORMANCE_CATEGORY = "performance";
+constexpr double DEFAULT_MARGIN = 0.5;
+constexpr const char* BUDGET_SCALE_VARIABLE = "GAFRO_PERF_BUDGET_SCALE";
+
+struct PerformanceSpec {
+    size_t iterations = 100;
+    /// Untimed runs before the timed ones
+    size_t warmup = 5;
+    double margin = DEFAULT_MARGIN;
+
+    /// Parse a "performance" section
+    static std::optional<PerformanceSpec> fromJson(const nlohmann::json& section) {
+        if (!section.is_object()) {
+            return std::nullopt;
+        }
+        PerformanceSpec spec;
+        spec.iterations = std::max<size_t>(section.value("iterations", size_t{100}), 1);
+        spec.warmup = section.value("warmup", size_t{5});
+        spec.margin = section.value("margin", DEFAULT_MARGIN);
+        return spec;
+    }
+
+    /// Spec of a test case: its "performance" section, or the defaults in the performance category
+    static std::optional<PerformanceSpec> forTestCase(const nlohmann::json& test_case, const std::string& category) {
+        if (test_case.contains("performance")) {
+            if (auto spec = fromJson(test_case["performance"])) {
+                return spec;
+            }
+        }
+        if (category == PERFORMANCE_CATEGORY) {
+            return PerformanceSpec{};
+        }
+        return std::nullopt;
+    }
+};
+
+/// Budget multiplier from GAFRO_PERF_BUDGET_SCALE, 1 when unset or invalid
+inline double budgetScale() {
+    const char* value = std::getenv(BUDGET_SCALE_VARIABLE);
+    if (!value) {
+        return 1.0;
+    }
+    char* end = nullptr;
+    double scale = std::strtod(value, &end);
+    return end != value && scale > 0.0 ? scale : 1.0;
+}
+
+/**
+ * @brief Compare per-iteration times in nanoseconds against the budgets in expected
+ *
+ * Returns the measured statistics and stores the first exceeded budget in error.
+ */
+inline nlohmann::json compareTimings(std::vector<double> times_ns, const nlohmann::json& expected, double margin,
+                                     double scale, std::optional<std::string>& error) {
+    error.reset();
+    if (!expected.is_object()) {
+        error = "expected outputs of a performance test must be an object of budgets";
+        return nullptr;
+    }
+    if (times_ns.empty()) {
+        error = "no timed iterations";
+        return nullptr;
+    }
+    Summary summary(std::move(times_ns));
+
+    nlohmann::json actual = {{"iterations", summary.count()}, {"mean_ns", summary.mean}, {"min_ns", summary.min()},
+                             {"max_ns", summary.max()}, {"median_ns", summary.quantile(0.5)}};
+    if (expected.contains("quantiles") && expected["quantiles"].is_object()) {
+        nlohmann::json quantiles = nlohmann::json::object();
+        for (auto it = expected["quantiles"].begin(); it != expected["quantiles"].end(); ++it) {
+            quantiles[it.key()] = summary.quantile(std::stod(it.key()));
+        }
+        actual["quantiles"] = quantiles;
+    }
+
+    auto check = [&](const std::string& name, double measured, const nlohmann::json& budget) -> std::optional<std::string> {
+        if (!budget.is_number()) {
+            return "budget " + name + " is not a number";
+        }
+        double limit = budget.get<double>() * (1.0 + margin) * scale;
+        if (measured <= limit) {
+            return std::nullopt;
+        }
+        std::ostringstream message;
+        message.precision(0);
+        message << std::fixed << name << " " << measured << " ns exceeds budget " << budget.get<double>()
+                << " ns (limit " << limit << " ns)";
+        return message.str();
+    };
+
+    for (auto it = expected.begin(); it != expected.end() && !error; ++it) {
+        if (it.key() == "mean_ns") {
+            error = check("mean", summary.mean, it.value());
+        } else if (it.key() == "median_ns") {
+            error = check("median", summary.quantile(0.5), it.value());
+        } else if (it.key() == "min_ns") {
+            error = check("min", summary.min(), it.value());
+        } else if (it.key() == "max_ns") {
+            error = check("max", summary.max(), it.value());
+        } else if (it.key() == "quantiles") {
+            for (auto q = it.value().begin(); it.value().is_object() && q != it.value().end() && !error; ++q) {
+                error = check("quantile " + q.key(), summary.quantile(std::stod(q.key())), q.value());
+            }
+        } else {
+            error = "unknown budget '" + it.key() + "'";
+        }
+    }
+    return actual;
+}
+
+} // namespace gafro_test
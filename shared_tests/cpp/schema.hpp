@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#pragma once
+
+#include <cstdint>
+#include <stdexcept>
+#include <string>
+#include <nlohmann/json.hpp>
+
+namespace gafro_test {
+
+/**
+ * @brief Versioning of the JSON test specification format
+ *
+ * Spec files carry an integer "schema_version"; files without one are version 1.
+ * On load, migrateSpec() upgrades a spec step by step to CURRENT_SCHEMA_VERSION so
+ * the loader only parses the current model; specs newer than the runner are
+ * rejected. Mirrors shared_tests/rust/src/schema.rs, whose runner also rewrites
+ * files with `gafro_test_runner migrate`.
+ */
+constexpr uint64_t CURRENT_SCHEMA_VERSION = 2;
+
+/// Schema version of a spec; 1 when the field is absent
+inline uint64_t schemaVersion(const nlohmann::json& spec) {
+    if (!spec.is_object()) {
+        throw std::runtime_error("test specification must be a JSON object");
+    }
+    if (!spec.contains("schema_version")) {
+        return 1;
+    }
+    const auto& version = spec["schema_version"];
+    if (!version.is_number_unsigned() || version.get<uint64_t>() < 1) {
+        throw std::runtime_error("invalid schema_version " + version.dump());
+    }
+    return version.get<uint64_t>();
+}
+
+/// Version 2: test cases with category, inputs and expected_outputs always present
+inline void migrateV1ToV2(nlohmann::json& spec) {
+    if (!spec.contains("test_categories") || !spec["test_categories"].is_object()) {
+        return;
+    }
+    for (auto it = spec["test_categories"].begin(); it != spec["test_categories"].end(); ++it) {
+        if (!it.value().is_array()) {
+            continue;
+        }
+        for (auto& test_case : it.value()) {
+            if (!test_case.is_object()) {
+                continue;
+            }
+            if (!test_case.contains("category")) {
+                test_case["category"] = it.key();
+            }
+            if (!test_case.contains("inputs")) {
+                test_case["inputs"] = nlohmann::json::object();
+            }
+            if (!test_case.contains("expected_outputs")) {
+                test_case["expected_outputs"] = nlohmann::json::object();
+            }
+        }
+    }
+}
+
+/// Upgrade a spec to the current version; returns the number of migrations applied
+inline size_t migrateSpec(nlohmann::json& spec) {
+    uint64_t version = schemaVersion(spec);
+    if (version > CURRENT_SCHEMA_VERSION) {
+        throw std::runtime_error("schema_version " + std::to_string(version) +
+                                 " is newer than the supported version " +
+                                 std::to_string(CURRENT_SCHEMA_VERSION) + "; update the test runner");
+    }
+    size_t applied = 0;
+    for (; version < CURRENT_SCHEMA_VERSION; ++version, ++applied) {
+        if (version == 1) {
+            migrateV1ToV2(spec);
+        }
+        spec["schema_version"] = version + 1;
+    }
+    return applied;
+}
+
+} // namespace gafro_test
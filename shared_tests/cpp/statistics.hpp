@@ -0,0 +1,203 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#pragma once
+
+#include <algorithm>
+#include <cmath>
+#include <cstdint>
+#include <map>
+#include <optional>
+#include <sstream>
+#include <string>
+#include <vector>
+#include <nlohmann/json.hpp>
+
+namespace gafro_test {
+
+/**
+ * @brief Statistical comparison of stochastic test outputs
+ *
+ * A test case with a "statistics" section is executed once per seed and each
+ * numeric output is summarized over the repetitions. The mean and standard
+ * deviation agree when they are within the test tolerance plus `confidence`
+ * standard errors of the expectation; min, max and quantiles must be within the
+ * tolerance. Mirrors shared_tests/rust/src/statistics.rs.
+ */
+constexpr double DEFAULT_CONFIDENCE = 3.0;
+
+struct StatisticsSpec {
+    size_t repetitions = 30;
+    /// Seed of the first repetition; repetition i uses seed + i
+    uint64_t seed = 0;
+    /// Input that receives the seed
+    std::string seed_input = "seed";
+    double confidence = DEFAULT_CONFIDENCE;
+
+    /// Parse a "statistics" section
+    static std::optional<StatisticsSpec> fromJson(const nlohmann::json& section) {
+        if (!section.is_object()) {
+            return std::nullopt;
+        }
+        StatisticsSpec spec;
+        spec.repetitions = std::max<size_t>(section.value("repetitions", size_t{30}), 1);
+        spec.seed = section.value("seed", uint64_t{0});
+        spec.seed_input = section.value("seed_input", std::string("seed"));
+        spec.confidence = section.value("confidence", DEFAULT_CONFIDENCE);
+        return spec;
+    }
+
+    /// Inputs of repetition i: the test inputs with the seed set
+    nlohmann::json inputsFor(const nlohmann::json& inputs, size_t repetition) const {
+        nlohmann::json seeded = inputs.is_object() ? inputs : nlohmann::json::object();
+        seeded[seed_input] = seed + repetition;
+        return seeded;
+    }
+};
+
+/**
+ * @brief Sample statistics of one output over all repetitions
+ */
+class Summary {
+public:
+    explicit Summary(std::vector<double> samples) : sorted_(std::move(samples)) {
+        std::sort(sorted_.begin(), sorted_.end());
+        double n = static_cast<double>(sorted_.size());
+        double sum = 0.0;
+        for (double x : sorted_) {
+            sum += x;
+        }
+        mean = sum / n;
+        double squares = 0.0;
+        for (double x : sorted_) {
+            squares += (x - mean) * (x - mean);
+        }
+        deviation = sorted_.size() > 1 ? std::sqrt(squares / (n - 1.0)) : 0.0;
+    }
+
+    double mean = 0.0;
+    /// Sample standard deviation (divides by n - 1)
+    double deviation = 0.0;
+
+    size_t count() const { return sorted_.size(); }
+    double min() const { return sorted_.front(); }
+    double max() const { return sorted_.back(); }
+
+    /// Quantile p in [0, 1], interpolating linearly between order statistics
+    double quantile(double p) const {
+        double position = std::clamp(p, 0.0, 1.0) * static_cast<double>(sorted_.size() - 1);
+        size_t below = static_cast<size_t>(std::floor(position));
+        size_t above = static_cast<size_t>(std::ceil(position));
+        return sorted_[below] + (position - below) * (sorted_[above] - sorted_[below]);
+    }
+
+    /// Standard error of the mean
+    double meanError() const { return deviation / std::sqrt(static_cast<double>(count())); }
+
+    /// Approximate standard error of the standard deviation for normal samples
+    double stdError() const { return deviation / std::sqrt(2.0 * static_cast<double>(std::max<size_t>(count(), 2) - 1)); }
+
+    /// The statistics requested by expected, in the same shape
+    nlohmann::json toJson(const nlohmann::json& expected) const {
+        nlohmann::json fields = {{"count", count()}, {"mean", mean}, {"std", deviation}, {"min", min()}, {"max", max()}};
+        if (expected.contains("quantiles") && expected["quantiles"].is_object()) {
+            nlohmann::json quantiles = nlohmann::json::object();
+            for (auto it = expected["quantiles"].begin(); it != expected["quantiles"].end(); ++it) {
+                quantiles[it.key()] = quantile(std::stod(it.key()));
+            }
+            fields["quantiles"] = quantiles;
+        }
+        return fields;
+    }
+
+    /// Check the expected statistics of one output; returns the first disagreement
+    std::optional<std::string> disagreement(const nlohmann::json& expected, double tolerance, double confidence) const {
+        auto check = [](const std::string& name, double actual, const nlohmann::json& value,
+                        double allowed) -> std::optional<std::string> {
+            if (!value.is_number()) {
+                return "expected " + name + " is not a number";
+            }
+            if (std::abs(actual - value.get<double>()) <= allowed) {
+                return std::nullopt;
+            }
+            std::ostringstream message;
+            message << name << " " << actual << " differs from expected " << value.get<double>()
+                    << " by more than " << allowed;
+            return message.str();
+        };
+
+        for (auto it = expected.begin(); it != expected.end(); ++it) {
+            std::optional<std::string> error;
+            if (it.key() == "mean") {
+                error = check("mean", mean, it.value(), tolerance + confidence * meanError());
+            } else if (it.key() == "std") {
+                error = check("std", deviation, it.value(), tolerance + confidence * stdError());
+            } else if (it.key() == "min") {
+                error = check("min", min(), it.value(), tolerance);
+            } else if (it.key() == "max") {
+                error = check("max", max(), it.value(), tolerance);
+            } else if (it.key() == "quantiles") {
+                for (auto q = it.value().begin(); q != it.value().end() && !error; ++q) {
+                    error = check("quantile " + q.key(), quantile(std::stod(q.key())), q.value(), tolerance);
+                }
+            } else {
+                error = "unknown statistic '" + it.key() + "'";
+            }
+            if (error) {
+                return error;
+            }
+        }
+        return std::nullopt;
+    }
+
+private:
+    std::vector<double> sorted_;
+};
+
+/**
+ * @brief Compare repeated runs against expected statistics
+ *
+ * Returns the computed statistics, shaped like expected, and stores the first
+ * disagreement in error.
+ */
+inline nlohmann::json compareRuns(const std::vector<nlohmann::json>& runs, const nlohmann::json& expected,
+                                  double tolerance, double confidence, std::optional<std::string>& error) {
+    std::map<std::string, std::vector<double>> samples;
+    for (const auto& run : runs) {
+        for (auto it = run.begin(); run.is_object() && it != run.end(); ++it) {
+            if (it.value().is_number()) {
+                samples[it.key()].push_back(it.value().get<double>());
+            }
+        }
+    }
+
+    nlohmann::json actual = nlohmann::json::object();
+    error.reset();
+    for (auto it = expected.begin(); expected.is_object() && it != expected.end(); ++it) {
+        const std::string& output = it.key();
+        if (!it.value().is_object()) {
+            if (!error) {
+                error = "expected statistics of '" + output + "' must be an object";
+            }
+            continue;
+        }
+        auto values = samples.find(output);
+        if (values == samples.end()) {
+            if (!error) {
+                error = "output '" + output + "' was not produced";
+            }
+            continue;
+        }
+        Summary summary(values->second);
+        actual[output] = summary.toJson(it.value());
+        if (!error) {
+            if (auto disagreement = summary.disagreement(it.value(), tolerance, confidence)) {
+                error = output + ": " + *disagreement;
+            }
+        }
+    }
+    return actual;
+}
+
+} // namespace gafro_test
@@ -1,4 +1,5 @@
 #include "json_loader.hpp"
+#include "coverage.hpp"
 #include <iostream>
 #include <string>
 #include <vector>
@@ -14,6 +15,7 @@ void printUsage(const char* program_name) {
     std::cout << "  -c, --category <name>  Run only tests in specified category\n";
     std::cout << "  -s, --stats       Show detailed statistics\n";
     std::cout << "  -r, --real-code   Enable real GAFRO code execution (Phase 2)\n";
+    std::cout << "      --coverage    Report which operations of the API surface the tests exercised\n";
     std::cout << "  -h, --help        Show this help message\n";
     std::cout << "\nExamples:\n";
     std::cout << "  " << program_name << " scalar_tests.json\n";
@@ -26,6 +28,7 @@ void printTestSuiteInfo(const TestSuite& test_suite) {
     std::cout << "\n=== Test Suite Information ===\n";
     std::cout << "Name: " << test_suite.test_suite_name << "\n";
     std::cout << "Version: " << test_suite.version << "\n";
+    std::cout << "Schema Version: " << test_suite.schema_version << "\n";
     std::cout << "Description: " << test_suite.description << "\n";
     
     auto stats = test_suite.getStatistics();
@@ -60,6 +63,7 @@ void printTestResults(const std::vector<TestResult>& results, bool show_stats) {
             std::cout << " (" << result.execution_time_ms << "ms)";
         }
         std::cout << "\n";
+        std::cout << "  Tolerance: " << result.tolerance_model << "\n";
         
         if (result.passed) {
             passed++;
@@ -94,6 +98,7 @@ int main(int argc, char* argv[]) {
     bool verbose = false;
     bool show_stats = false;
     bool real_code_execution = false;
+    bool report_coverage = false;
     std::string filter_tag;
     std::string filter_category;
     std::string test_file;
@@ -110,6 +115,8 @@ int main(int argc, char* argv[]) {
             show_stats = true;
         } else if (arg == "-r" || arg == "--real-code") {
             real_code_execution = true;
+        } else if (arg == "--coverage") {
+            report_coverage = true;
         } else if (arg == "-t" || arg == "--tag") {
             if (i + 1 < argc) {
                 filter_tag = argv[++i];
@@ -174,6 +181,10 @@ int main(int argc, char* argv[]) {
         }
     }
     
+    if (report_coverage) {
+        coverage::enable();
+    }
+    
     // Execute tests based on filters
     std::vector<TestResult> results;
     
@@ -209,6 +220,11 @@ int main(int argc, char* argv[]) {
     // Print results
     printTestResults(results, show_stats);
     
+    if (report_coverage) {
+        CoverageReport report(ApiSurface::findFor(test_file), coverage::disable(), context.operations().names());
+        std::cout << report.toText() << "\n";
+    }
+    
     // Return exit code based on results
     bool all_passed = std::all_of(results.begin(), results.end(), 
                                  [](const TestResult& r) { return r.passed; });
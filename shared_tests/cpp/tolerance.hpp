@@ -0,0 +1,78 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#pragma once
+
+#include <algorithm>
+#include <cmath>
+#include <cstdio>
+#include <limits>
+#include <string>
+
+namespace gafro_test {
+
+/**
+ * @brief Error-budget tolerances for floating-point comparisons
+ *
+ * A computation of n correctly rounded operations on values of magnitude up to
+ * m accumulates at most about n · ε · m absolute error. Magnitudes below 1
+ * count as 1. Mirrors shared_tests/rust/src/tolerance.rs; both sides read the
+ * same JSON form: a number, or {"operations": n, "magnitude": m, "safety_factor": s}.
+ *
+ * Depends on the standard library only so that standalone validators can include it.
+ */
+constexpr double DEFAULT_SAFETY_FACTOR = 4.0;
+constexpr double DEFAULT_TOLERANCE = 1e-10;
+
+struct ErrorBudget {
+    /// Number of rounding operations, including conversion of decimal inputs
+    unsigned operations = 1;
+    /// Largest magnitude of any intermediate value
+    double magnitude = 1.0;
+    double safety_factor = DEFAULT_SAFETY_FACTOR;
+
+    /// Absolute tolerance s · n · ε · max(|m|, 1)
+    double tolerance() const {
+        return safety_factor * std::max(operations, 1u) * std::numeric_limits<double>::epsilon() *
+               std::max(std::abs(magnitude), 1.0);
+    }
+};
+
+class Tolerance {
+public:
+    static Tolerance fixed(double tolerance) { return Tolerance(tolerance, false, {}); }
+
+    static Tolerance budget(unsigned operations, double magnitude,
+                            double safety_factor = DEFAULT_SAFETY_FACTOR) {
+        return Tolerance(0.0, true, ErrorBudget{operations, magnitude, safety_factor});
+    }
+
+    Tolerance() : Tolerance(DEFAULT_TOLERANCE, false, {}) {}
+
+    double value() const { return is_budget_ ? budget_.tolerance() : fixed_; }
+
+    bool check(double actual, double expected) const { return std::abs(actual - expected) <= value(); }
+
+    /// Same wording as the Rust Display implementation
+    std::string describe() const {
+        char buffer[128];
+        if (is_budget_) {
+            std::snprintf(buffer, sizeof(buffer), "%.1e (%u ops, magnitude %g, safety %g)", value(),
+                          budget_.operations, budget_.magnitude, budget_.safety_factor);
+        } else {
+            std::snprintf(buffer, sizeof(buffer), "fixed %.1e", fixed_);
+        }
+        return buffer;
+    }
+
+private:
+    Tolerance(double fixed, bool is_budget, ErrorBudget budget)
+        : fixed_(fixed), is_budget_(is_budget), budget_(budget) {}
+
+    double fixed_;
+    bool is_budget_;
+    ErrorBudget budget_;
+};
+
+} // namespace gafro_test
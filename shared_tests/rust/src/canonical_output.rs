@@ -11,6 +11,9 @@
 
 // use std::fmt; // Not currently used
 
+use crate::config::{ConfigError, ConfigLoader};
+use crate::safety::SafetyViolation;
+
 // Trait for types that can be printed as positions
 pub trait PositionLike {
     fn x(&self) -> f64;
@@ -33,19 +36,51 @@ pub struct Config {
 
 impl Default for Config {
     fn default() -> Self {
-        Self {
-            position_precision: Self::get_env_precision("GAFRO_POSITION_PRECISION", 1),
-            angle_precision: Self::get_env_precision("GAFRO_ANGLE_PRECISION", 2),
-            distance_precision: Self::get_env_precision("GAFRO_DISTANCE_PRECISION", 1),
-            time_precision: Self::get_env_precision("GAFRO_TIME_PRECISION", 1),
-            speed_precision: Self::get_env_precision("GAFRO_SPEED_PRECISION", 2),
-            scientific_threshold: Self::get_env_float("GAFRO_SCIENTIFIC_THRESHOLD", 100.0),
-            use_tau_convention: Self::get_env_bool("GAFRO_USE_TAU", true),
-        }
+        Self::builtin()
     }
 }
 
 impl Config {
+    /// Built-in defaults, ignoring configuration files and the environment
+    pub fn builtin() -> Self {
+        Self {
+            position_precision: 1,
+            angle_precision: 2,
+            distance_precision: 1,
+            time_precision: 1,
+            speed_precision: 2,
+            scientific_threshold: 100.0,
+            use_tau_convention: true,
+        }
+    }
+
+    /// Resolve settings through a loader, ignoring an unreadable config file
+    ///
+    /// Unlike [`Config::default`], this reads `gafro.toml`/`gafro.json` and the
+    /// `GAFRO_*` environment variables.
+    pub fn load(loader: &ConfigLoader) -> Self {
+        match loader.load() {
+            Ok(effective) => effective.output,
+            Err(e) => {
+                eprintln!("Warning: {}; using defaults and environment", e);
+                Self::from_env(Self::builtin())
+            }
+        }
+    }
+
+    /// Overlay `GAFRO_*` environment variables on the given settings
+    pub fn from_env(defaults: Self) -> Self {
+        Self {
+            position_precision: Self::get_env_precision("GAFRO_POSITION_PRECISION", defaults.position_precision),
+            angle_precision: Self::get_env_precision("GAFRO_ANGLE_PRECISION", defaults.angle_precision),
+            distance_precision: Self::get_env_precision("GAFRO_DISTANCE_PRECISION", defaults.distance_precision),
+            time_precision: Self::get_env_precision("GAFRO_TIME_PRECISION", defaults.time_precision),
+            speed_precision: Self::get_env_precision("GAFRO_SPEED_PRECISION", defaults.speed_precision),
+            scientific_threshold: Self::get_env_float("GAFRO_SCIENTIFIC_THRESHOLD", defaults.scientific_threshold),
+            use_tau_convention: Self::get_env_bool("GAFRO_USE_TAU", defaults.use_tau_convention),
+        }
+    }
+
     /// Get precision from environment variable with fallback
     fn get_env_precision(env_var: &str, default: usize) -> usize {
         std::env::var(env_var)
@@ -81,17 +116,25 @@ pub struct CanonicalOutput {
 }
 
 impl CanonicalOutput {
-    /// Create a new canonical output formatter with default config
+    /// Create a formatter from built-in defaults, `gafro.toml`/`gafro.json` and
+    /// `GAFRO_*` environment variables, later sources taking precedence
+    ///
+    /// The C++ `CanonicalOutput` reads only the environment variables, so the
+    /// two agree whenever no config file is present.
     pub fn new() -> Self {
-        Self {
-            config: Config::default(),
-        }
+        Self::with_config(Config::load(&ConfigLoader::new()))
     }
     
     /// Create a new canonical output formatter with custom config
     pub fn with_config(config: Config) -> Self {
         Self { config }
     }
+
+    /// Create a new canonical output formatter from an explicit config file
+    pub fn from_config_file(path: &str) -> Result<Self, ConfigError> {
+        let effective = ConfigLoader::new().with_file(path).load()?;
+        Ok(Self::with_config(effective.output))
+    }
     
     /// Get mutable reference to config for runtime changes
     pub fn config_mut(&mut self) -> &mut Config {
@@ -146,6 +189,16 @@ impl CanonicalOutput {
         format!("{:.precision$} {}", value, unit, precision = self.config.speed_precision)
     }
     
+    /// Format a safety violation, with the precision of its unit's quantity
+    pub fn violation(&self, violation: &SafetyViolation) -> String {
+        let precision = match violation.unit.as_str() {
+            "m" => self.config.distance_precision,
+            "m/s" | "rad/s" => self.config.speed_precision,
+            _ => self.config.angle_precision,
+        };
+        violation.describe(|x| format!("{:.precision$} {}", x, violation.unit, precision = precision))
+    }
+    
     /// Format in scientific notation
     pub fn scientific(&self, value: f64, precision: usize) -> String {
         format!("{:.precision$e}", value, precision = precision)
@@ -210,6 +263,10 @@ impl CanonicalOutput {
         println!("✓ {}: {}", label, self.time(value, "s"));
     }
     
+    pub fn print_violation(&self, violation: &SafetyViolation) {
+        println!("🚫 {}", self.violation(violation));
+    }
+    
     pub fn print_success(&self, message: &str) {
         println!("✅ {}", message);
     }
@@ -256,7 +313,7 @@ impl Default for CanonicalOutput {
 /// Global canonical output instance for convenience
 pub static mut GLOBAL_OUTPUT: Option<CanonicalOutput> = None;
 
-/// Initialize global output with default config
+/// Initialize global output with config resolved as in [`CanonicalOutput::new`]
 pub fn init_global_output() {
     unsafe {
         GLOBAL_OUTPUT = Some(CanonicalOutput::new());
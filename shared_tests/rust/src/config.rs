@@ -0,0 +1,443 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Configuration file support for GAFRO Extended (Rust)
+ *
+ * Output precision and test-runner settings can be supplied through a
+ * `gafro.toml` (or `gafro.json`) file. Settings are resolved with an explicit
+ * precedence order, lowest to highest:
+ *
+ * 1. Built-in defaults
+ * 2. Configuration file
+ * 3. `GAFRO_*` environment variables
+ * 4. Command-line overrides
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::canonical_output::Config as OutputConfig;
+
+/// Environment variable pointing at an explicit configuration file
+pub const CONFIG_ENV_VAR: &str = "GAFRO_CONFIG";
+
+/// File names searched in the working directory when no file is given
+pub const DEFAULT_CONFIG_FILES: &[&str] = &["gafro.toml", "gafro.json"];
+
+/// Where an effective setting came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigSource {
+    Default,
+    File,
+    Environment,
+    CommandLine,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File => write!(f, "config file"),
+            ConfigSource::Environment => write!(f, "environment"),
+            ConfigSource::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// Errors raised while loading or overriding configuration
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, String),
+    UnknownKey(String),
+    InvalidValue { key: String, value: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "cannot read config file {}: {}", path.display(), e),
+            ConfigError::Parse(path, e) => write!(f, "invalid config file {}: {}", path.display(), e),
+            ConfigError::UnknownKey(key) => write!(f, "unknown config key '{}'", key),
+            ConfigError::InvalidValue { key, value } => {
+                write!(f, "invalid value '{}' for config key '{}'", value, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Partial output settings; unset fields fall through to lower layers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputOverrides {
+    pub position_precision: Option<usize>,
+    pub angle_precision: Option<usize>,
+    pub distance_precision: Option<usize>,
+    pub time_precision: Option<usize>,
+    pub speed_precision: Option<usize>,
+    pub scientific_threshold: Option<f64>,
+    pub use_tau_convention: Option<bool>,
+}
+
+/// Partial test-runner settings; unset fields fall through to lower layers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunnerOverrides {
+    pub verbose: Option<bool>,
+    pub stats: Option<bool>,
+    pub format: Option<String>,
+}
+
+/// One configuration layer, as stored in `gafro.toml`
+///
+/// ```toml
+/// [output]
+/// position_precision = 3
+///
+/// [runner]
+/// stats = true
+/// format = "json"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigLayer {
+    pub output: OutputOverrides,
+    pub runner: RunnerOverrides,
+}
+
+impl ConfigLayer {
+    /// Parse a layer from TOML text
+    pub fn from_toml_str(contents: &str) -> Result<Self, String> {
+        toml::from_str(contents).map_err(|e| e.to_string())
+    }
+
+    /// Parse a layer from JSON text
+    pub fn from_json_str(contents: &str) -> Result<Self, String> {
+        serde_json::from_str(contents).map_err(|e| e.to_string())
+    }
+
+    /// Load a layer from a file, choosing the format by extension
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+
+        let parsed = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            _ => Self::from_toml_str(&contents),
+        };
+
+        parsed.map_err(|e| ConfigError::Parse(path.to_path_buf(), e))
+    }
+
+    /// Build the environment layer from the `GAFRO_*` variables
+    pub fn from_env() -> Self {
+        let precision = |name: &str| std::env::var(name).ok().and_then(|v| v.parse().ok());
+
+        Self {
+            output: OutputOverrides {
+                position_precision: precision("GAFRO_POSITION_PRECISION"),
+                angle_precision: precision("GAFRO_ANGLE_PRECISION"),
+                distance_precision: precision("GAFRO_DISTANCE_PRECISION"),
+                time_precision: precision("GAFRO_TIME_PRECISION"),
+                speed_precision: precision("GAFRO_SPEED_PRECISION"),
+                scientific_threshold: std::env::var("GAFRO_SCIENTIFIC_THRESHOLD")
+                    .ok()
+                    .and_then(|v| v.parse().ok()),
+                use_tau_convention: std::env::var("GAFRO_USE_TAU").ok().and_then(|v| parse_bool(&v)),
+            },
+            runner: RunnerOverrides::default(),
+        }
+    }
+
+    /// Set a single dotted key (e.g. `output.angle_precision`) from a string
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let invalid = || ConfigError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let precision = || value.parse::<usize>().map_err(|_| invalid());
+
+        match key {
+            "output.position_precision" => self.output.position_precision = Some(precision()?),
+            "output.angle_precision" => self.output.angle_precision = Some(precision()?),
+            "output.distance_precision" => self.output.distance_precision = Some(precision()?),
+            "output.time_precision" => self.output.time_precision = Some(precision()?),
+            "output.speed_precision" => self.output.speed_precision = Some(precision()?),
+            "output.scientific_threshold" => {
+                self.output.scientific_threshold = Some(value.parse().map_err(|_| invalid())?)
+            }
+            "output.use_tau_convention" => {
+                self.output.use_tau_convention = Some(parse_bool(value).ok_or_else(invalid)?)
+            }
+            "runner.verbose" => self.runner.verbose = Some(parse_bool(value).ok_or_else(invalid)?),
+            "runner.stats" => self.runner.stats = Some(parse_bool(value).ok_or_else(invalid)?),
+            "runner.format" => match value {
+                "text" | "json" | "html" => self.runner.format = Some(value.to_string()),
+                _ => return Err(invalid()),
+            },
+            _ => return Err(ConfigError::UnknownKey(key.to_string())),
+        }
+
+        Ok(())
+    }
+
+    /// Apply a `KEY=VALUE` assignment as given on the command line
+    pub fn set_assignment(&mut self, assignment: &str) -> Result<(), ConfigError> {
+        match assignment.split_once('=') {
+            Some((key, value)) => self.set(key.trim(), value.trim()),
+            None => Err(ConfigError::InvalidValue {
+                key: assignment.to_string(),
+                value: String::new(),
+            }),
+        }
+    }
+}
+
+/// Effective test-runner settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunnerSettings {
+    pub verbose: bool,
+    pub stats: bool,
+    pub format: String,
+}
+
+impl Default for RunnerSettings {
+    fn default() -> Self {
+        Self {
+            verbose: false,
+            stats: false,
+            format: "text".to_string(),
+        }
+    }
+}
+
+/// Fully resolved configuration together with the origin of every value
+#[derive(Debug, Clone)]
+pub struct EffectiveConfig {
+    pub output: OutputConfig,
+    pub runner: RunnerSettings,
+    pub file: Option<PathBuf>,
+    sources: BTreeMap<&'static str, ConfigSource>,
+}
+
+impl EffectiveConfig {
+    /// Source of a dotted key, `None` for unknown keys
+    pub fn source_of(&self, key: &str) -> Option<ConfigSource> {
+        self.sources.get(key).copied()
+    }
+
+    /// Render the effective settings as annotated TOML
+    pub fn dump(&self) -> String {
+        let mut lines = vec!["# Effective GAFRO configuration".to_string()];
+        match &self.file {
+            Some(path) => lines.push(format!("# config file: {}", path.display())),
+            None => lines.push("# config file: none".to_string()),
+        }
+
+        let output = &self.output;
+        let runner = &self.runner;
+        let sections: [(&str, Vec<(&'static str, String)>); 2] = [
+            ("output", vec![
+                ("output.position_precision", output.position_precision.to_string()),
+                ("output.angle_precision", output.angle_precision.to_string()),
+                ("output.distance_precision", output.distance_precision.to_string()),
+                ("output.time_precision", output.time_precision.to_string()),
+                ("output.speed_precision", output.speed_precision.to_string()),
+                ("output.scientific_threshold", format!("{:?}", output.scientific_threshold)),
+                ("output.use_tau_convention", output.use_tau_convention.to_string()),
+            ]),
+            ("runner", vec![
+                ("runner.verbose", runner.verbose.to_string()),
+                ("runner.stats", runner.stats.to_string()),
+                ("runner.format", format!("{:?}", runner.format)),
+            ]),
+        ];
+
+        for (section, entries) in sections.iter() {
+            lines.push(String::new());
+            lines.push(format!("[{}]", section));
+            for (key, value) in entries {
+                let name = key.trim_start_matches(section).trim_start_matches('.');
+                let source = self.source_of(key).unwrap_or(ConfigSource::Default);
+                lines.push(format!("{} = {}  # {}", name, value, source));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Resolves configuration layers in precedence order
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    file: Option<PathBuf>,
+    command_line: ConfigLayer,
+    use_environment: bool,
+}
+
+impl ConfigLoader {
+    /// Create a loader that searches the default file locations and reads the environment
+    pub fn new() -> Self {
+        Self {
+            file: None,
+            command_line: ConfigLayer::default(),
+            use_environment: true,
+        }
+    }
+
+    /// Use an explicit configuration file instead of searching for one
+    pub fn with_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// Set the command-line layer (highest precedence)
+    pub fn with_command_line(mut self, layer: ConfigLayer) -> Self {
+        self.command_line = layer;
+        self
+    }
+
+    /// Enable or disable reading `GAFRO_*` environment variables
+    pub fn with_environment(mut self, enabled: bool) -> Self {
+        self.use_environment = enabled;
+        self
+    }
+
+    /// Locate the configuration file: explicit path, `GAFRO_CONFIG`, then the working directory
+    pub fn find_file(&self) -> Option<PathBuf> {
+        if let Some(path) = &self.file {
+            return Some(path.clone());
+        }
+
+        if let Ok(path) = std::env::var(CONFIG_ENV_VAR) {
+            if !path.is_empty() {
+                return Some(PathBuf::from(path));
+            }
+        }
+
+        DEFAULT_CONFIG_FILES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file())
+    }
+
+    /// Resolve all layers into an effective configuration
+    pub fn load(&self) -> Result<EffectiveConfig, ConfigError> {
+        let file = self.find_file();
+        let file_layer = match &file {
+            Some(path) => ConfigLayer::from_file(path)?,
+            None => ConfigLayer::default(),
+        };
+
+        let mut effective = EffectiveConfig {
+            output: OutputConfig::builtin(),
+            runner: RunnerSettings::default(),
+            file,
+            sources: BTreeMap::new(),
+        };
+
+        apply_layer(&mut effective, &file_layer, ConfigSource::File);
+        if self.use_environment {
+            apply_layer(&mut effective, &ConfigLayer::from_env(), ConfigSource::Environment);
+        }
+        apply_layer(&mut effective, &self.command_line, ConfigSource::CommandLine);
+
+        Ok(effective)
+    }
+}
+
+fn apply_layer(effective: &mut EffectiveConfig, layer: &ConfigLayer, source: ConfigSource) {
+    fn set<T: Clone>(
+        sources: &mut BTreeMap<&'static str, ConfigSource>,
+        key: &'static str,
+        target: &mut T,
+        value: &Option<T>,
+        source: ConfigSource,
+    ) {
+        if let Some(value) = value {
+            *target = value.clone();
+            sources.insert(key, source);
+        }
+    }
+
+    let output = &mut effective.output;
+    let runner = &mut effective.runner;
+    let sources = &mut effective.sources;
+
+    set(sources, "output.position_precision", &mut output.position_precision, &layer.output.position_precision, source);
+    set(sources, "output.angle_precision", &mut output.angle_precision, &layer.output.angle_precision, source);
+    set(sources, "output.distance_precision", &mut output.distance_precision, &layer.output.distance_precision, source);
+    set(sources, "output.time_precision", &mut output.time_precision, &layer.output.time_precision, source);
+    set(sources, "output.speed_precision", &mut output.speed_precision, &layer.output.speed_precision, source);
+    set(sources, "output.scientific_threshold", &mut output.scientific_threshold, &layer.output.scientific_threshold, source);
+    set(sources, "output.use_tau_convention", &mut output.use_tau_convention, &layer.output.use_tau_convention, source);
+    set(sources, "runner.verbose", &mut runner.verbose, &layer.runner.verbose, source);
+    set(sources, "runner.stats", &mut runner.stats, &layer.runner.stats, source);
+    set(sources, "runner.format", &mut runner.format, &layer.runner.format, source);
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toml_and_json_layers() {
+        let toml_layer = ConfigLayer::from_toml_str("[output]\nangle_precision = 4\n[runner]\nformat = \"json\"\n").unwrap();
+        let json_layer = ConfigLayer::from_json_str(r#"{"output": {"angle_precision": 4}, "runner": {"format": "json"}}"#).unwrap();
+
+        assert_eq!(toml_layer, json_layer);
+        assert_eq!(toml_layer.output.angle_precision, Some(4));
+        assert!(ConfigLayer::from_toml_str("[output]\nunknown = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_precedence_order() {
+        let dir = std::env::temp_dir().join(format!("gafro_config_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("gafro.toml");
+        std::fs::write(&path, "[output]\nposition_precision = 3\nangle_precision = 5\n").unwrap();
+
+        let mut command_line = ConfigLayer::default();
+        command_line.set_assignment("output.angle_precision=7").unwrap();
+
+        let effective = ConfigLoader::new()
+            .with_file(&path)
+            .with_environment(false)
+            .with_command_line(command_line)
+            .load()
+            .unwrap();
+
+        assert_eq!(effective.output.position_precision, 3);
+        assert_eq!(effective.output.angle_precision, 7);
+        assert_eq!(effective.output.time_precision, OutputConfig::builtin().time_precision);
+        assert_eq!(effective.source_of("output.position_precision"), Some(ConfigSource::File));
+        assert_eq!(effective.source_of("output.angle_precision"), Some(ConfigSource::CommandLine));
+        assert_eq!(effective.source_of("output.time_precision"), None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        let mut layer = ConfigLayer::default();
+        assert!(matches!(layer.set("output.colour", "red"), Err(ConfigError::UnknownKey(_))));
+        assert!(matches!(layer.set("runner.format", "xml"), Err(ConfigError::InvalidValue { .. })));
+        assert!(layer.set_assignment("runner.stats").is_err());
+        assert!(layer.set_assignment("runner.stats = yes").is_ok());
+        assert_eq!(layer.runner.stats, Some(true));
+    }
+}
@@ -0,0 +1,240 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Operation coverage of JSON test suites (Rust)
+ *
+ * The interpreter calls [`record`] with the name of every GA operation it
+ * evaluates (`"Scalar::add"`, `"Multivector::norm"`) and registered operations
+ * record their own names. Counting is off unless [`enable`] was called, so the
+ * instrumentation costs a thread-local lookup otherwise.
+ *
+ * A [`CoverageReport`] joins the counters with the API surface listed in
+ * `json/api_surface.json` and marks every operation that no test exercised. The
+ * C++ runner records the same names so both reports can be compared.
+ */
+
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// API surface bundled with the runner, used when no `api_surface.json` is found
+const BUNDLED_API_SURFACE: &str = include_str!("../../json/api_surface.json");
+
+/// Module under which operations registered by the host program are reported
+pub const REGISTERED_MODULE: &str = "registered";
+
+/// Module under which recorded operations missing from the API surface are reported
+pub const UNLISTED_MODULE: &str = "unlisted";
+
+thread_local! {
+    static COUNTERS: RefCell<Option<BTreeMap<String, u64>>> = const { RefCell::new(None) };
+}
+
+/// Start counting operations on this thread, discarding earlier counts
+pub fn enable() {
+    COUNTERS.with(|counters| *counters.borrow_mut() = Some(BTreeMap::new()));
+}
+
+/// Stop counting and return the counts
+pub fn disable() -> BTreeMap<String, u64> {
+    COUNTERS.with(|counters| counters.borrow_mut().take()).unwrap_or_default()
+}
+
+pub fn is_enabled() -> bool {
+    COUNTERS.with(|counters| counters.borrow().is_some())
+}
+
+/// Count one evaluation of `operation`; no-op while counting is disabled
+pub fn record(operation: &str) {
+    COUNTERS.with(|counters| {
+        if let Some(counts) = counters.borrow_mut().as_mut() {
+            *counts.entry(operation.to_string()).or_insert(0) += 1;
+        }
+    });
+}
+
+/// Counts so far, without resetting them
+pub fn counts() -> BTreeMap<String, u64> {
+    COUNTERS.with(|counters| counters.borrow().clone()).unwrap_or_default()
+}
+
+/// Operations that the test suites are expected to cover, grouped by module
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiSurface {
+    pub modules: BTreeMap<String, Vec<String>>,
+}
+
+impl ApiSurface {
+    pub fn from_json(value: &Value) -> Self {
+        let modules = value["modules"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(module, operations)| {
+                let operations = operations.as_array().into_iter().flatten().filter_map(Value::as_str);
+                (module.clone(), operations.map(str::to_string).collect())
+            })
+            .collect();
+        Self { modules }
+    }
+
+    /// The surface bundled with the runner
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_API_SURFACE).map(|value| Self::from_json(&value)).unwrap_or_default()
+    }
+
+    /// The nearest `api_surface.json` in the directory of `test_file` or its ancestors
+    pub fn find_for(test_file: &Path) -> Self {
+        test_file
+            .ancestors()
+            .skip(1)
+            .map(|directory| directory.join("api_surface.json"))
+            .find(|candidate| candidate.is_file())
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .map(|value| Self::from_json(&value))
+            .unwrap_or_else(Self::bundled)
+    }
+}
+
+/// One operation of the coverage matrix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageRow {
+    pub module: String,
+    pub operation: String,
+    pub hits: u64,
+}
+
+/// Which operations of the API surface a test run exercised
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub rows: Vec<CoverageRow>,
+}
+
+impl CoverageReport {
+    /// Join recorded counts with the API surface and the registered operations
+    pub fn new(surface: &ApiSurface, counts: &BTreeMap<String, u64>, registered: &[&str]) -> Self {
+        let hits = |operation: &str| counts.get(operation).copied().unwrap_or(0);
+        let mut rows: Vec<CoverageRow> = surface
+            .modules
+            .iter()
+            .flat_map(|(module, operations)| {
+                operations.iter().map(move |operation| (module.as_str(), operation.as_str()))
+            })
+            .chain(registered.iter().map(|operation| (REGISTERED_MODULE, *operation)))
+            .map(|(module, operation)| CoverageRow {
+                module: module.to_string(),
+                operation: operation.to_string(),
+                hits: hits(operation),
+            })
+            .collect();
+
+        let unlisted: Vec<CoverageRow> = counts
+            .iter()
+            .filter(|(operation, _)| !rows.iter().any(|row| &row.operation == *operation))
+            .map(|(operation, hits)| CoverageRow {
+                module: UNLISTED_MODULE.to_string(),
+                operation: operation.clone(),
+                hits: *hits,
+            })
+            .collect();
+        rows.extend(unlisted);
+        Self { rows }
+    }
+
+    pub fn covered(&self) -> usize {
+        self.rows.iter().filter(|row| row.hits > 0).count()
+    }
+
+    pub fn untested(&self) -> impl Iterator<Item = &CoverageRow> {
+        self.rows.iter().filter(|row| row.hits == 0)
+    }
+
+    /// Covered and total operations per module, in report order
+    pub fn by_module(&self) -> Vec<(&str, usize, usize)> {
+        let mut modules: Vec<(&str, usize, usize)> = Vec::new();
+        for row in &self.rows {
+            match modules.iter_mut().find(|(module, _, _)| *module == row.module) {
+                Some((_, covered, total)) => {
+                    *covered += usize::from(row.hits > 0);
+                    *total += 1;
+                }
+                None => modules.push((&row.module, usize::from(row.hits > 0), 1)),
+            }
+        }
+        modules
+    }
+
+    pub fn to_json(&self) -> Value {
+        let modules: serde_json::Map<String, Value> = self
+            .by_module()
+            .into_iter()
+            .map(|(module, covered, total)| {
+                let operations: serde_json::Map<String, Value> = self
+                    .rows
+                    .iter()
+                    .filter(|row| row.module == module)
+                    .map(|row| (row.operation.clone(), Value::from(row.hits)))
+                    .collect();
+                (module.to_string(), json!({ "covered": covered, "total": total, "operations": operations }))
+            })
+            .collect();
+        json!({
+            "covered": self.covered(),
+            "total": self.rows.len(),
+            "modules": modules,
+            "untested": self.untested().map(|row| row.operation.clone()).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Coverage matrix as text; untested operations are marked with `!!`
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("\n=== Coverage ===\n");
+        for (module, covered, total) in self.by_module() {
+            text += &format!("{} ({}/{})\n", module, covered, total);
+            for row in self.rows.iter().filter(|row| row.module == module) {
+                let marker = if row.hits == 0 { "!!" } else { "  " };
+                text += &format!("  {} {:<36} {}\n", marker, row.operation, row.hits);
+            }
+        }
+        text += &format!("\nCovered: {}/{} operations\n", self.covered(), self.rows.len());
+        text += "================";
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage_report() {
+        assert!(!is_enabled());
+        record("Scalar::add");
+        enable();
+        record("Scalar::add");
+        record("Scalar::add");
+        record("buoyancy_force");
+        record("Quaternion::slerp");
+        let counts = disable();
+        record("Scalar::new");
+        assert_eq!(counts.len(), 3);
+
+        let surface = ApiSurface::from_json(&json!({ "modules": { "scalar": ["Scalar::new", "Scalar::add"] } }));
+        let report = CoverageReport::new(&surface, &counts, &["buoyancy_force", "forward_kinematics"]);
+        assert_eq!(report.covered(), 3);
+        assert_eq!(report.rows.len(), 5);
+        assert_eq!(
+            report.untested().map(|row| row.operation.as_str()).collect::<Vec<_>>(),
+            vec!["Scalar::new", "forward_kinematics"]
+        );
+        assert_eq!(report.by_module(), vec![("scalar", 1, 2), (REGISTERED_MODULE, 1, 2), (UNLISTED_MODULE, 1, 1)]);
+        assert_eq!(report.to_json()["modules"]["scalar"]["operations"]["Scalar::add"], 2);
+
+        assert!(ApiSurface::bundled().modules.contains_key("si_units"));
+    }
+}
@@ -0,0 +1,199 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Structured diff of expected and actual test outputs (Rust)
+ *
+ * A failing test used to print both outputs whole, leaving the reader to find
+ * the one blade that differs. [`diff_outputs`] walks the expected outputs the
+ * way the comparison does (numbers within the absolute tolerance, arrays by
+ * length and element, objects by the expected keys) and returns only the
+ * mismatches, each with its JSON pointer and, for numbers, the absolute and
+ * relative error. Text output colors them when writing to a terminal and
+ * `NO_COLOR` is unset; JSON and HTML output carry them as records.
+ */
+
+use std::fmt;
+use std::io::IsTerminal;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What differs at a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    /// Numbers further apart than the tolerance
+    Value,
+    /// Expected output absent from the actual outputs
+    Missing,
+    /// Arrays of different lengths
+    Length,
+    /// Different JSON types, or unequal non-numeric values
+    Different,
+}
+
+/// One mismatching path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mismatch {
+    /// JSON pointer into the outputs; empty for the outputs as a whole
+    pub path: String,
+    pub kind: MismatchKind,
+    pub expected: Value,
+    pub actual: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abs_error: Option<f64>,
+    /// Absolute error over the magnitude of the expectation, when that is nonzero
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel_error: Option<f64>,
+}
+
+impl Mismatch {
+    fn new(path: &str, kind: MismatchKind, expected: &Value, actual: &Value) -> Self {
+        Self { path: path.to_string(), kind, expected: expected.clone(), actual: actual.clone(), abs_error: None, rel_error: None }
+    }
+
+    /// One line describing the mismatch, with ANSI colors when `color` is set
+    pub fn describe(&self, color: bool) -> String {
+        let paint = |text: String, code: &str| if color { format!("\x1b[{}m{}\x1b[0m", code, text) } else { text };
+        let path = if self.path.is_empty() { "(outputs)" } else { &self.path };
+        let (expected, actual) = match self.kind {
+            MismatchKind::Length => (
+                format!("{} elements", self.expected.as_array().map_or(0, Vec::len)),
+                format!("{} elements", self.actual.as_array().map_or(0, Vec::len)),
+            ),
+            MismatchKind::Missing => (self.expected.to_string(), "missing".to_string()),
+            _ => (self.expected.to_string(), self.actual.to_string()),
+        };
+        let mut line = format!("{}: expected {}, actual {}", paint(path.to_string(), "1"), paint(expected, "32"), paint(actual, "31"));
+        if let Some(abs) = self.abs_error {
+            line.push_str(&format!(" (abs {:.3e}", abs));
+            if let Some(rel) = self.rel_error {
+                line.push_str(&format!(", rel {:.3e}", rel));
+            }
+            line.push(')');
+        }
+        line
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe(false))
+    }
+}
+
+/// Whether text output to stdout should be colored
+pub fn use_color() -> bool {
+    std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+}
+
+/// Mismatches between actual and expected outputs under an absolute tolerance
+pub fn diff_outputs(actual: &Value, expected: &Value, tolerance: f64) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    diff_at("", actual, expected, tolerance, &mut mismatches);
+    mismatches
+}
+
+fn diff_at(path: &str, actual: &Value, expected: &Value, tolerance: f64, mismatches: &mut Vec<Mismatch>) {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(e)) => {
+            let (Some(a_f64), Some(e_f64)) = (a.as_f64(), e.as_f64()) else {
+                mismatches.push(Mismatch::new(path, MismatchKind::Different, expected, actual));
+                return;
+            };
+            let abs_error = (a_f64 - e_f64).abs();
+            if abs_error > tolerance {
+                let mut mismatch = Mismatch::new(path, MismatchKind::Value, expected, actual);
+                mismatch.abs_error = Some(abs_error);
+                mismatch.rel_error = (e_f64 != 0.0).then(|| abs_error / e_f64.abs());
+                mismatches.push(mismatch);
+            }
+        }
+        (Value::Array(a), Value::Array(e)) => {
+            if a.len() != e.len() {
+                mismatches.push(Mismatch::new(path, MismatchKind::Length, expected, actual));
+            }
+            for (i, (a, e)) in a.iter().zip(e).enumerate() {
+                diff_at(&format!("{}/{}", path, i), a, e, tolerance, mismatches);
+            }
+        }
+        (Value::Object(a), Value::Object(e)) => {
+            for (key, expected_value) in e {
+                let child = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                match a.get(key) {
+                    Some(actual_value) => diff_at(&child, actual_value, expected_value, tolerance, mismatches),
+                    None => mismatches.push(Mismatch::new(&child, MismatchKind::Missing, expected_value, &Value::Null)),
+                }
+            }
+        }
+        _ if actual != expected => mismatches.push(Mismatch::new(path, MismatchKind::Different, expected, actual)),
+        _ => {}
+    }
+}
+
+/// Escape text for HTML element content and attributes
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Table of mismatches for the HTML report
+pub fn mismatches_to_html(mismatches: &[Mismatch]) -> String {
+    let number = |x: Option<f64>| x.map_or_else(String::new, |x| format!("{:.3e}", x));
+    let mut html = String::from(
+        "<table class=\"diff\">\n<tr><th>Path</th><th>Kind</th><th>Expected</th><th>Actual</th><th>Abs error</th><th>Rel error</th></tr>\n",
+    );
+    for m in mismatches {
+        let kind = serde_json::to_value(m.kind).ok().and_then(|k| k.as_str().map(str::to_string)).unwrap_or_default();
+        html.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{}</td><td class=\"expected\">{}</td><td class=\"actual\">{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&m.path),
+            kind,
+            escape_html(&m.expected.to_string()),
+            escape_html(&m.actual.to_string()),
+            number(m.abs_error),
+            number(m.rel_error)
+        ));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_only_mismatching_paths_are_reported() {
+        let expected = json!({ "e1": 2.0, "e2": 0.0, "blades": [1.0, 2.0, 3.0], "name": "rotor", "nested": { "x": 1.0 } });
+        let actual = json!({ "e1": 2.5, "e2": 1e-12, "blades": [1.0, 2.1], "name": "motor" });
+        let mismatches = diff_outputs(&actual, &expected, 1e-10);
+        let paths: Vec<_> = mismatches.iter().map(|m| (m.path.as_str(), m.kind)).collect();
+        assert_eq!(
+            paths,
+            [
+                ("/e1", MismatchKind::Value),
+                ("/blades", MismatchKind::Length),
+                ("/blades/1", MismatchKind::Value),
+                ("/name", MismatchKind::Different),
+                ("/nested", MismatchKind::Missing),
+            ]
+        );
+        assert_eq!((mismatches[0].abs_error, mismatches[0].rel_error), (Some(0.5), Some(0.25)));
+        assert_eq!(mismatches[0].to_string(), "/e1: expected 2.0, actual 2.5 (abs 5.000e-1, rel 2.500e-1)");
+        assert!(diff_outputs(&expected, &expected, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_structured_and_html_forms() {
+        let mismatches = diff_outputs(&json!({ "a<b": 0.0 }), &json!({ "a<b": 1.0 }), 1e-6);
+        let record = serde_json::to_value(&mismatches[0]).unwrap();
+        assert_eq!(record["kind"], "value");
+        assert_eq!(record["rel_error"], 1.0);
+        assert!(mismatches[0].describe(true).contains("\x1b[31m0.0\x1b[0m"));
+        let html = mismatches_to_html(&mismatches);
+        assert!(html.contains("<code>/a&lt;b</code>") && html.contains("<td>value</td>"));
+    }
+}
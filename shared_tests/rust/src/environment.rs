@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Environment section of test reports (Rust)
+ *
+ * Cross-language and cross-machine divergences are easier to attribute when
+ * every report says how its numbers were produced: runner version, target
+ * triple, compiler, build profile and the floating-point environment probed at
+ * run time. The fields match `gafro_modern::build_info()`, which reports the
+ * same for the library itself.
+ */
+
+use std::hint::black_box;
+
+use serde::{Deserialize, Serialize};
+
+/// Floating-point behavior that can change results between machines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FloatEnvironment {
+    pub fma: bool,
+    pub flush_denormals: bool,
+    /// `nearest`, `toward_zero`, `upward` or `downward`
+    pub rounding: String,
+}
+
+impl FloatEnvironment {
+    pub fn current() -> Self {
+        let flush_denormals = black_box(black_box(f64::MIN_POSITIVE) * black_box(0.5)) == 0.0;
+        let nudge = black_box(0.75 * f64::EPSILON);
+        let up = black_box(1.0) + nudge > 1.0 + 0.5 * f64::EPSILON;
+        let down = black_box(-1.0) - nudge < -1.0 - 0.5 * f64::EPSILON;
+        let rounding = match (up, down) {
+            (true, true) => "nearest",
+            (false, false) => "toward_zero",
+            (true, false) => "upward",
+            (false, true) => "downward",
+        };
+        Self { fma: cfg!(target_feature = "fma"), flush_denormals, rounding: rounding.to_string() }
+    }
+}
+
+/// How this runner was built and the floating-point environment it runs in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Environment {
+    pub runner_version: String,
+    pub target: String,
+    pub rustc: String,
+    pub profile: String,
+    pub float: FloatEnvironment,
+}
+
+impl Environment {
+    pub fn capture() -> Self {
+        Self {
+            runner_version: env!("CARGO_PKG_VERSION").to_string(),
+            target: env!("GAFRO_TARGET").to_string(),
+            rustc: env!("GAFRO_RUSTC_VERSION").to_string(),
+            profile: env!("GAFRO_PROFILE").to_string(),
+            float: FloatEnvironment::current(),
+        }
+    }
+
+    /// One line for text reports
+    pub fn summary(&self) -> String {
+        format!(
+            "gafro_test_runner {} ({}, {}), {}, fma {}, flush denormals {}, rounding {}",
+            self.runner_version,
+            self.target,
+            self.profile,
+            self.rustc,
+            self.float.fma,
+            self.float.flush_denormals,
+            self.float.rounding
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_describes_this_build() {
+        let environment = Environment::capture();
+        assert!(environment.target.contains(std::env::consts::ARCH));
+        assert!(environment.rustc.starts_with("rustc "));
+        assert_eq!(environment.float.rounding, "nearest");
+        let json = serde_json::to_value(&environment).unwrap();
+        assert_eq!(json["float"]["flush_denormals"], false);
+        assert!(environment.summary().starts_with("gafro_test_runner 0.1.0"));
+    }
+}
@@ -1,10 +1,20 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, Map};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::time::Instant;
 use regex::Regex;
 
+use crate::coverage;
+use crate::diff::{self, Mismatch};
+use crate::operations::{OperationRegistry, TestOperation};
+use crate::performance::{self, PerformanceSpec};
+use crate::safety::{self, SafetyViolation};
+use crate::schema;
+use crate::si_quantity::{format_dimensions, DynamicQuantity};
+use crate::statistics::{self, StatisticsSpec};
+use crate::tolerance::{ErrorBudget, Tolerance, DEFAULT_SAFETY_FACTOR};
+
 /// Represents a single test case from JSON specification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestCase {
@@ -14,9 +24,19 @@ pub struct TestCase {
     pub inputs: Value,
     pub expected_outputs: Value,
     pub tolerance: f64,
+    /// How the tolerance was obtained, for reporting
+    pub tolerance_model: String,
+    /// Registered operation that executes this test instead of the test code
+    pub operation: Option<String>,
+    /// Repeat over seeds and compare statistics instead of single values
+    pub statistics: Option<StatisticsSpec>,
+    /// Time repeated runs against the budgets in `expected_outputs`
+    pub performance: Option<PerformanceSpec>,
     pub language_specific: Option<Value>,
     pub dependencies: Vec<String>,
     pub tags: Vec<String>,
+    /// Capabilities the implementation under test must provide, e.g. `gpu`; skipped otherwise
+    pub requires: Vec<String>,
     
     // Rust specific configuration
     pub rust_test_code: String,
@@ -61,7 +81,7 @@ impl TestCase {
         !self.test_name.is_empty() && 
         !self.description.is_empty() && 
         !self.category.is_empty() && 
-        !self.rust_test_code.is_empty()
+        (!self.rust_test_code.is_empty() || self.operation.is_some())
     }
 }
 
@@ -105,6 +125,8 @@ impl TestCategory {
 pub struct TestSuite {
     pub test_suite_name: String,
     pub version: String,
+    /// Format version the spec was migrated to on load
+    pub schema_version: u64,
     pub description: String,
     pub test_categories: HashMap<String, TestCategory>,
 }
@@ -118,7 +140,8 @@ impl TestSuite {
     
     /// Load test suite from JSON string
     pub fn load_from_string(json_string: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let test_json: Value = serde_json::from_str(json_string)?;
+        let mut test_json: Value = serde_json::from_str(json_string)?;
+        schema::migrate(&mut test_json)?;
         Ok(JsonLoader::parse_test_suite(&test_json))
     }
     
@@ -205,6 +228,16 @@ pub struct TestResult {
     pub actual_outputs: Value,
     pub expected_outputs: Value,
     pub tolerance: f64,
+    pub tolerance_model: String,
+    /// Paths where the actual outputs miss the expected ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mismatches: Vec<Mismatch>,
+    /// Safety violations the actual outputs report under `violations`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<SafetyViolation>,
+    /// Not run because a required capability is missing; `error_message` names it
+    #[serde(default)]
+    pub skipped: bool,
 }
 
 impl TestResult {
@@ -219,19 +252,31 @@ impl TestResult {
             return "Test passed".to_string();
         }
         
-        format!(
-            "Test failed: {}\nExpected: {}\nActual: {}\nTolerance: {}",
-            self.error_message,
-            serde_json::to_string_pretty(&self.expected_outputs).unwrap_or_default(),
-            serde_json::to_string_pretty(&self.actual_outputs).unwrap_or_default(),
-            self.tolerance
-        )
+        let mut details = if !self.mismatches.is_empty() {
+            let lines: Vec<String> = self.mismatches.iter().map(|m| format!("  {}", m)).collect();
+            format!("Test failed: {}\nMismatches:\n{}\nTolerance: {}", self.error_message, lines.join("\n"), self.tolerance_model)
+        } else {
+            format!(
+                "Test failed: {}\nExpected: {}\nActual: {}\nTolerance: {}",
+                self.error_message,
+                serde_json::to_string_pretty(&self.expected_outputs).unwrap_or_default(),
+                serde_json::to_string_pretty(&self.actual_outputs).unwrap_or_default(),
+                self.tolerance_model
+            )
+        };
+        if !self.violations.is_empty() {
+            let lines: Vec<String> = self.violations.iter().map(|v| format!("  {}", v)).collect();
+            details.push_str(&format!("\nViolations:\n{}", lines.join("\n")));
+        }
+        details
     }
 }
 
 /// Test execution context
 pub struct TestExecutionContext {
     test_executor: Option<Box<dyn Fn(&TestCase) -> Value + Send + Sync>>,
+    operations: OperationRegistry,
+    capabilities: BTreeSet<String>,
     verbose: bool,
     stats: ExecutionStats,
 }
@@ -249,6 +294,8 @@ impl TestExecutionContext {
     pub fn new() -> Self {
         Self {
             test_executor: None,
+            operations: OperationRegistry::new(),
+            capabilities: BTreeSet::new(),
             verbose: false,
             stats: ExecutionStats {
                 total_tests: 0,
@@ -266,22 +313,72 @@ impl TestExecutionContext {
             test_name: test_case.test_name.clone(),
             expected_outputs: test_case.expected_outputs.clone(),
             tolerance: test_case.tolerance,
+            tolerance_model: test_case.tolerance_model.clone(),
             passed: false,
             error_message: String::new(),
             execution_time_ms: 0.0,
             actual_outputs: Value::Null,
+            mismatches: Vec::new(),
+            violations: Vec::new(),
+            skipped: false,
         };
         
+        let missing: Vec<&str> =
+            test_case.requires.iter().filter(|c| !self.capabilities.contains(*c)).map(String::as_str).collect();
+        if !missing.is_empty() {
+            result.passed = true;
+            result.skipped = true;
+            result.error_message = format!("requires {}", missing.join(", "));
+            if self.verbose {
+                println!("Test: {} - SKIPPED ({})", result.test_name, result.error_message);
+            }
+            return result;
+        }
+        
         let start_time = Instant::now();
         
-        match self.execute_test(test_case) {
-            Ok(actual_outputs) => {
-                result.actual_outputs = actual_outputs;
-                result.passed = self.compare_outputs(&result.actual_outputs, &result.expected_outputs, result.tolerance);
+        if let Some(spec) = &test_case.performance {
+            match self.execute_timed(test_case, spec) {
+                Ok(times_ns) => {
+                    let (actual_outputs, outcome) =
+                        performance::compare_timings(times_ns, &result.expected_outputs, spec.margin, performance::budget_scale());
+                    result.actual_outputs = actual_outputs;
+                    result.passed = outcome.is_ok();
+                    result.error_message = outcome.err().unwrap_or_default();
+                }
+                Err(e) => {
+                    result.passed = false;
+                    result.error_message = e.to_string();
+                }
             }
-            Err(e) => {
-                result.passed = false;
-                result.error_message = e.to_string();
+        } else if let Some(spec) = &test_case.statistics {
+            match self.execute_repetitions(test_case, spec) {
+                Ok(runs) => {
+                    let (actual_outputs, outcome) =
+                        statistics::compare_runs(&runs, &result.expected_outputs, result.tolerance, spec.confidence);
+                    result.actual_outputs = actual_outputs;
+                    result.passed = outcome.is_ok();
+                    result.error_message = outcome.err().unwrap_or_default();
+                }
+                Err(e) => {
+                    result.passed = false;
+                    result.error_message = e.to_string();
+                }
+            }
+        } else {
+            match self.execute_test(test_case) {
+                Ok(actual_outputs) => {
+                    result.violations = safety::violations_in(&actual_outputs);
+                    result.actual_outputs = actual_outputs;
+                    result.passed = self.compare_outputs(&result.actual_outputs, &result.expected_outputs, result.tolerance);
+                    if !result.passed {
+                        result.mismatches = diff::diff_outputs(&result.actual_outputs, &result.expected_outputs, result.tolerance);
+                    }
+                }
+                Err(e) => {
+                    result.passed = false;
+                    result.error_message = e.to_string();
+                }
             }
         }
         
@@ -359,6 +456,26 @@ impl TestExecutionContext {
         self.test_executor = Some(Box::new(executor));
     }
     
+    /// Register a named operation callable from test cases
+    pub fn register_operation(&mut self, operation: Box<dyn TestOperation>) {
+        self.operations.register(operation);
+    }
+    
+    /// Registry of named operations
+    pub fn operations(&self) -> &OperationRegistry {
+        &self.operations
+    }
+    
+    /// Registry of named operations, e.g. for `register_fn`
+    pub fn operations_mut(&mut self) -> &mut OperationRegistry {
+        &mut self.operations
+    }
+    
+    /// Declare capabilities of the implementation under test, e.g. from `gafro_modern::capabilities()`
+    pub fn provide_capabilities<S: Into<String>>(&mut self, names: impl IntoIterator<Item = S>) {
+        self.capabilities.extend(names.into_iter().map(Into::into));
+    }
+    
     /// Enable/disable verbose output
     pub fn set_verbose(&mut self, verbose: bool) {
         self.verbose = verbose;
@@ -371,13 +488,41 @@ impl TestExecutionContext {
     
     /// Execute test using the configured executor or default
     fn execute_test(&self, test_case: &TestCase) -> Result<Value, Box<dyn std::error::Error>> {
-        if let Some(ref executor) = self.test_executor {
+        if let Some(ref operation) = test_case.operation {
+            coverage::record(operation);
+            Ok(self.operations.execute(operation, &test_case.inputs)?)
+        } else if let Some(ref executor) = self.test_executor {
             Ok(executor(test_case))
         } else {
             Ok(self.default_test_executor(test_case))
         }
     }
     
+    /// Execute a stochastic test once per seed
+    fn execute_repetitions(&self, test_case: &TestCase, spec: &StatisticsSpec) -> Result<Vec<Value>, Box<dyn std::error::Error>> {
+        (0..spec.repetitions)
+            .map(|repetition| {
+                let mut seeded = test_case.clone();
+                seeded.inputs = spec.inputs_for(&test_case.inputs, repetition);
+                self.execute_test(&seeded)
+            })
+            .collect()
+    }
+    
+    /// Execute a performance test after its warm-up, timing each run in nanoseconds
+    fn execute_timed(&self, test_case: &TestCase, spec: &PerformanceSpec) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+        for _ in 0..spec.warmup {
+            self.execute_test(test_case)?;
+        }
+        (0..spec.iterations)
+            .map(|_| {
+                let start = Instant::now();
+                std::hint::black_box(self.execute_test(test_case)?);
+                Ok(start.elapsed().as_nanos() as f64)
+            })
+            .collect()
+    }
+    
     /// Default test executor that evaluates Rust code patterns
     fn default_test_executor(&self, test_case: &TestCase) -> Value {
         self.execute_rust_code(&test_case.rust_test_code, &test_case.inputs)
@@ -409,6 +554,10 @@ impl TestExecutionContext {
         else if code.contains("Point::new") {
             return self.execute_point_operations(code, inputs);
         }
+        // Handle SI quantity operations
+        else if code.contains("SIQuantity::") || Self::si_constructor_regex().is_match(code) {
+            return self.execute_si_operations(code);
+        }
         else {
             // Fallback to basic pattern matching
             return self.execute_basic_operations(code, inputs);
@@ -425,38 +574,47 @@ impl TestExecutionContext {
             // Extract values from the code directly
             let a_val = self.extract_scalar_value_from_code(code, "a");
             let b_val = self.extract_scalar_value_from_code(code, "b");
+            coverage::record("Scalar::new");
             
             if code.contains("let result = a + b;") {
+                coverage::record("Scalar::add");
                 result.insert("result".to_string(), Value::Number(serde_json::Number::from_f64(a_val + b_val).unwrap()));
             } else if code.contains("let result = a * b;") {
+                coverage::record("Scalar::mul");
                 result.insert("result".to_string(), Value::Number(serde_json::Number::from_f64(a_val * b_val).unwrap()));
             } else if code.contains("let result = a - b;") {
+                coverage::record("Scalar::sub");
                 result.insert("result".to_string(), Value::Number(serde_json::Number::from_f64(a_val - b_val).unwrap()));
             }
         }
         // Scalar arithmetic operations
         else if code.contains("let result = a + b;") {
+            coverage::record("Scalar::add");
             // Extract values from inputs or code
             let a_val = self.extract_value_from_inputs_or_code(inputs, code, "a", 0.0);
             let b_val = self.extract_value_from_inputs_or_code(inputs, code, "b", 0.0);
             result.insert("result".to_string(), Value::Number(serde_json::Number::from_f64(a_val + b_val).unwrap()));
         }
         else if code.contains("let result = a * b;") {
+            coverage::record("Scalar::mul");
             let a_val = self.extract_value_from_inputs_or_code(inputs, code, "a", 0.0);
             let b_val = self.extract_value_from_inputs_or_code(inputs, code, "b", 0.0);
             result.insert("result".to_string(), Value::Number(serde_json::Number::from_f64(a_val * b_val).unwrap()));
         }
         else if code.contains("let result = a - b;") {
+            coverage::record("Scalar::sub");
             let a_val = self.extract_value_from_inputs_or_code(inputs, code, "a", 0.0);
             let b_val = self.extract_value_from_inputs_or_code(inputs, code, "b", 0.0);
             result.insert("result".to_string(), Value::Number(serde_json::Number::from_f64(a_val - b_val).unwrap()));
         }
         // Default scalar creation
         else if code.contains("Scalar::<f64>::new();") {
+            coverage::record("Scalar::new");
             result.insert("value".to_string(), Value::Number(serde_json::Number::from_f64(0.0).unwrap()));
         }
         // Scalar creation with value
         else if code.contains("Scalar::<f64>::new(") {
+            coverage::record("Scalar::new");
             let re = Regex::new(r"Scalar::<f64>::new\(([0-9.]+)\)").unwrap();
             if let Some(captures) = re.captures(code) {
                 if let Some(value_str) = captures.get(1) {
@@ -476,6 +634,7 @@ impl TestExecutionContext {
         
         // Vector addition (check this first before vector creation)
         if code.contains("let result = vector1 + vector2;") {
+            coverage::record("Vector::add");
             // Extract values from both vectors
             let v1_values = self.extract_vector_values_from_code(code, "vector1");
             let v2_values = self.extract_vector_values_from_code(code, "vector2");
@@ -488,12 +647,14 @@ impl TestExecutionContext {
         }
         // Default vector creation
         else if code.contains("Vector::<f64>::new();") {
+            coverage::record("Vector::new");
             result.insert("e1".to_string(), Value::Number(serde_json::Number::from_f64(0.0).unwrap()));
             result.insert("e2".to_string(), Value::Number(serde_json::Number::from_f64(0.0).unwrap()));
             result.insert("e3".to_string(), Value::Number(serde_json::Number::from_f64(0.0).unwrap()));
         }
         // Vector creation with parameters
         else if code.contains("Vector::<f64>::new(") {
+            coverage::record(if code.contains(".clone()") { "Vector::clone" } else { "Vector::new" });
             let re = Regex::new(r"Vector::<f64>::new\(([0-9.]+),\s*([0-9.]+),\s*([0-9.]+)\)").unwrap();
             if let Some(captures) = re.captures(code) {
                 if let (Some(x), Some(y), Some(z)) = (captures.get(1), captures.get(2), captures.get(3)) {
@@ -515,6 +676,7 @@ impl TestExecutionContext {
         
         // Multivector addition (check this first)
         if code.contains("mv1 += mv2;") {
+            coverage::record("Multivector::add_assign");
             // Extract values from both multivectors and perform addition
             let mv1_values = self.extract_multivector_values_from_code(code, "mv1");
             let mv2_values = self.extract_multivector_values_from_code(code, "mv2");
@@ -529,6 +691,7 @@ impl TestExecutionContext {
         }
         // Multivector scalar multiplication
         else if code.contains("mv *= 2.0;") {
+            coverage::record("Multivector::mul_assign");
             // Extract multivector values and multiply by scalar
             let mv_values = self.extract_multivector_values_from_code(code, "mv");
             if mv_values.len() == 5 {
@@ -541,10 +704,12 @@ impl TestExecutionContext {
         }
         // Multivector size
         else if code.contains("Multivector::<f64>::size();") {
+            coverage::record("Multivector::size");
             result.insert("size".to_string(), Value::Number(serde_json::Number::from(3)));
         }
         // Multivector blades
         else if code.contains("Multivector::<f64>::blades();") {
+            coverage::record("Multivector::blades");
             let mut blades = Map::new();
             blades.insert("blade_0".to_string(), Value::Number(serde_json::Number::from(1)));
             blades.insert("blade_1".to_string(), Value::Number(serde_json::Number::from(2)));
@@ -553,6 +718,7 @@ impl TestExecutionContext {
         }
         // Multivector norm
         else if code.contains("mv.norm();") {
+            coverage::record("Multivector::norm");
             // Calculate norm from multivector values
             let mv_values = self.extract_multivector_values_from_code(code, "mv");
             if mv_values.len() == 5 {
@@ -563,6 +729,7 @@ impl TestExecutionContext {
         }
         // Multivector creation with values
         else if code.contains("Multivector::<f64>::new(vec![") {
+            coverage::record("Multivector::new");
             let re = Regex::new(r"Multivector::<f64>::new\(vec!\[([0-9.,\s]+)\]\)").unwrap();
             if let Some(captures) = re.captures(code) {
                 if let Some(values_str) = captures.get(1) {
@@ -583,6 +750,7 @@ impl TestExecutionContext {
         }
         // Default multivector creation
         else if code.contains("Multivector::<f64>::new();") {
+            coverage::record("Multivector::new");
             result.insert("e0".to_string(), Value::Number(serde_json::Number::from_f64(0.0).unwrap()));
             result.insert("e1".to_string(), Value::Number(serde_json::Number::from_f64(0.0).unwrap()));
             result.insert("e2".to_string(), Value::Number(serde_json::Number::from_f64(0.0).unwrap()));
@@ -599,6 +767,7 @@ impl TestExecutionContext {
         
         // Point creation with parameters
         if code.contains("Point::new(") {
+            coverage::record("Point::new");
             let re = Regex::new(r"Point::new\(([0-9.]+),\s*([0-9.]+),\s*([0-9.]+)\)").unwrap();
             if let Some(captures) = re.captures(code) {
                 if let (Some(x), Some(y), Some(z)) = (captures.get(1), captures.get(2), captures.get(3)) {
@@ -617,6 +786,88 @@ impl TestExecutionContext {
         Value::Object(result)
     }
     
+    /// Matches `Length::m(10.0)` style constructors of the SI type aliases
+    fn si_constructor_regex() -> Regex {
+        Regex::new(r"\b(Dimensionless|Mass|Length|Time|Velocity|Acceleration|Force|Energy|Torque|Power|Pressure)::\w+\(\s*(-?[0-9.]+(?:[eE][-+]?[0-9]+)?)\s*\)").unwrap()
+    }
+    
+    /// Execute SI quantity operations
+    ///
+    /// Statements are `let` bindings of a constructor (`Length::m(10.0)`,
+    /// `SIQuantity::<0, 1, -1>::new(5.0)`) or of a binary operation on earlier
+    /// bindings and number literals. Reports the value and dimensions of `result`,
+    /// or of the last binding, and an `error` when dimensions do not match.
+    fn execute_si_operations(&self, code: &str) -> Value {
+        let binding = Regex::new(r"^let\s+(?:mut\s+)?(\w+)\s*=\s*(.+)$").unwrap();
+        let generic = Regex::new(r"^SIQuantity::<\s*(-?\d+)\s*,\s*(-?\d+)\s*,\s*(-?\d+)\s*>::new\(\s*(-?[0-9.]+(?:[eE][-+]?[0-9]+)?)\s*\)$").unwrap();
+        let alias = Self::si_constructor_regex();
+        let operation = Regex::new(r"^([\w.]+)\s*([-+*/])\s*([\w.]+)$").unwrap();
+        
+        let mut variables: HashMap<String, DynamicQuantity> = HashMap::new();
+        let mut last = None;
+        let mut result = Map::new();
+        
+        for statement in code.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let Some(captures) = binding.captures(statement) else { continue };
+            let name = captures[1].to_string();
+            let expression = captures[2].trim();
+            
+            let quantity = if let Some(c) = generic.captures(expression) {
+                coverage::record("SIQuantity::new");
+                let dimension = |i: usize| c[i].parse::<i32>().unwrap_or(0);
+                DynamicQuantity::new(c[4].parse().unwrap_or(0.0), dimension(1), dimension(2), dimension(3))
+            } else if let Some(c) = alias.captures(expression).filter(|c| c[0].len() == expression.len()) {
+                coverage::record("SIQuantity::new");
+                match DynamicQuantity::from_alias(&c[1], c[2].parse().unwrap_or(0.0)) {
+                    Some(quantity) => quantity,
+                    None => continue,
+                }
+            } else if let Some(c) = operation.captures(expression) {
+                // Number literals act as dimensionless quantities
+                let operand = |token: &str| {
+                    variables.get(token).copied()
+                        .or_else(|| token.parse::<f64>().ok().map(|v| DynamicQuantity::new(v, 0, 0, 0)))
+                };
+                let (Some(lhs), Some(rhs)) = (operand(&c[1]), operand(&c[3])) else { continue };
+                let (operation_name, evaluated) = match &c[2] {
+                    "+" => ("SIQuantity::add", lhs.checked_add(rhs)),
+                    "-" => ("SIQuantity::sub", lhs.checked_sub(rhs)),
+                    "*" => ("SIQuantity::mul", Ok(lhs * rhs)),
+                    _ => ("SIQuantity::div", Ok(lhs / rhs)),
+                };
+                coverage::record(operation_name);
+                match evaluated {
+                    Ok(quantity) => quantity,
+                    Err(mismatch) => {
+                        result.insert("error".to_string(), Value::String("dimension mismatch".to_string()));
+                        result.insert("explanation".to_string(), Value::String(mismatch.to_string()));
+                        return Value::Object(result);
+                    }
+                }
+            } else {
+                continue;
+            };
+            
+            variables.insert(name.clone(), quantity);
+            last = Some(name);
+        }
+        
+        let output = variables.get("result").or_else(|| last.as_ref().and_then(|name| variables.get(name)));
+        if let Some(quantity) = output {
+            let [mass, length, time] = quantity.dimensions;
+            let mut dimensions = Map::new();
+            dimensions.insert("mass".to_string(), Value::from(mass));
+            dimensions.insert("length".to_string(), Value::from(length));
+            dimensions.insert("time".to_string(), Value::from(time));
+            
+            result.insert("value".to_string(), Value::Number(serde_json::Number::from_f64(quantity.value).unwrap()));
+            result.insert("dimensions".to_string(), Value::Object(dimensions));
+            result.insert("dimension".to_string(), Value::String(format_dimensions(quantity.dimensions)));
+        }
+        
+        Value::Object(result)
+    }
+    
     /// Execute basic operations (fallback)
     fn execute_basic_operations(&self, code: &str, inputs: &Value) -> Value {
         // Fallback for any other operations
@@ -697,7 +948,7 @@ impl TestExecutionContext {
     }
     
     /// Compare actual and expected outputs with tolerance
-    fn compare_outputs(&self, actual: &Value, expected: &Value, tolerance: f64) -> bool {
+    pub fn compare_outputs(&self, actual: &Value, expected: &Value, tolerance: f64) -> bool {
         match (actual, expected) {
             (Value::Number(a), Value::Number(e)) => {
                 if let (Some(a_f64), Some(e_f64)) = (a.as_f64(), e.as_f64()) {
@@ -706,6 +957,9 @@ impl TestExecutionContext {
                     false
                 }
             }
+            (Value::Array(a), Value::Array(e)) => {
+                a.len() == e.len() && a.iter().zip(e).all(|(a, e)| self.compare_outputs(a, e, tolerance))
+            }
             (Value::Object(a), Value::Object(e)) => {
                 for (key, expected_value) in e {
                     if let Some(actual_value) = a.get(key) {
@@ -735,18 +989,55 @@ pub mod JsonLoader {
         test_json.get("test_categories").is_some()
     }
     
+    /// Largest absolute value of any number in a JSON value
+    fn max_magnitude(value: &Value) -> f64 {
+        match value {
+            Value::Number(n) => n.as_f64().map_or(0.0, f64::abs),
+            Value::Array(items) => items.iter().map(max_magnitude).fold(0.0, f64::max),
+            Value::Object(fields) => fields.values().map(max_magnitude).fold(0.0, f64::max),
+            _ => 0.0,
+        }
+    }
+
+    /// Parse a `tolerance` field: a fixed number or an error-budget object
+    ///
+    /// An error budget without `magnitude` scales with the largest expected output.
+    pub fn parse_tolerance(tolerance_json: &Value, expected_outputs: &Value) -> Tolerance {
+        match tolerance_json {
+            Value::Number(n) => n.as_f64().map(Tolerance::Fixed).unwrap_or_default(),
+            Value::Object(budget) => Tolerance::Budget(ErrorBudget {
+                operations: budget.get("operations").and_then(Value::as_u64).unwrap_or(1) as u32,
+                magnitude: budget
+                    .get("magnitude")
+                    .and_then(Value::as_f64)
+                    .unwrap_or_else(|| max_magnitude(expected_outputs)),
+                safety_factor: budget.get("safety_factor").and_then(Value::as_f64).unwrap_or(DEFAULT_SAFETY_FACTOR),
+            }),
+            _ => Tolerance::default(),
+        }
+    }
+
     /// Load and parse test case from JSON
     pub fn parse_test_case(test_case_json: &Value) -> TestCase {
+        let tolerance = parse_tolerance(&test_case_json["tolerance"], &test_case_json["expected_outputs"]);
         let mut test_case = TestCase {
             test_name: test_case_json["test_name"].as_str().unwrap_or("").to_string(),
             description: test_case_json["description"].as_str().unwrap_or("").to_string(),
             category: test_case_json["category"].as_str().unwrap_or("").to_string(),
             inputs: test_case_json["inputs"].clone(),
             expected_outputs: test_case_json["expected_outputs"].clone(),
-            tolerance: test_case_json["tolerance"].as_f64().unwrap_or(1e-10),
+            tolerance: tolerance.value(),
+            tolerance_model: tolerance.to_string(),
+            operation: test_case_json.get("operation").and_then(Value::as_str).map(str::to_string),
+            statistics: test_case_json.get("statistics").and_then(StatisticsSpec::from_json),
+            performance: PerformanceSpec::for_test_case(test_case_json, test_case_json["category"].as_str().unwrap_or("")),
             language_specific: test_case_json.get("language_specific").cloned(),
             dependencies: Vec::new(),
             tags: Vec::new(),
+            requires: test_case_json["requires"]
+                .as_array()
+                .map(|names| names.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                .unwrap_or_default(),
             rust_test_code: String::new(),
             rust_includes: Vec::new(),
             rust_setup_code: String::new(),
@@ -786,7 +1077,11 @@ pub mod JsonLoader {
         
         if let Some(test_cases_array) = category_json.as_array() {
             for test_case_json in test_cases_array {
-                category.test_cases.push(parse_test_case(test_case_json));
+                let mut test_case = parse_test_case(test_case_json);
+                if name == performance::PERFORMANCE_CATEGORY && test_case.performance.is_none() {
+                    test_case.performance = Some(PerformanceSpec::default());
+                }
+                category.test_cases.push(test_case);
             }
         }
         
@@ -798,6 +1093,7 @@ pub mod JsonLoader {
         let mut test_suite = TestSuite {
             test_suite_name: test_suite_json["test_suite"].as_str().unwrap_or("").to_string(),
             version: test_suite_json["version"].as_str().unwrap_or("").to_string(),
+            schema_version: schema::schema_version(test_suite_json).unwrap_or(1),
             description: test_suite_json["description"].as_str().unwrap_or("").to_string(),
             test_categories: HashMap::new(),
         };
@@ -10,11 +10,23 @@
  */
 
 pub mod json_loader;
+pub mod schema;
+pub mod merge;
 pub mod test_runner;
 pub mod utilities;
 pub mod si_quantity;
 pub mod angle;
 pub mod canonical_output;
+pub mod config;
+pub mod tolerance;
+pub mod operations;
+pub mod statistics;
+pub mod diff;
+pub mod safety;
+pub mod performance;
+pub mod self_check;
+pub mod coverage;
+pub mod environment;
 
 // Re-export utilities for easy access
 pub use utilities::*;
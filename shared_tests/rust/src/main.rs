@@ -1,8 +1,5 @@
-mod json_loader;
-mod test_runner;
-
 use clap::Parser;
-use test_runner::{Args, run_tests};
+use gafro_test_runner::test_runner::{Args, run_tests};
 
 fn main() {
     let args = Args::parse();
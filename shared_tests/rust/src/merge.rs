@@ -0,0 +1,209 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Merging of test suites authored in separate files (Rust)
+ *
+ * The C++ and Rust sides write specs independently, so the same test can land
+ * in two files. [`merge_suites`] migrates each suite to the current schema and
+ * combines their categories into one suite, keeping the first occurrence of
+ * every category and test in file order:
+ *
+ * - a test_name seen again with an identical definition is dropped as a
+ *   duplicate; with a different definition it is a conflict
+ * - a test whose `category` differs from the category it is listed under is a
+ *   mismatch
+ * - tags or categories that differ only in case or `-`/`_` are reported as
+ *   inconsistent spellings
+ *
+ * Conflicts and mismatches are errors and stop `gafro_test_runner merge` from
+ * writing the consolidated suite; duplicates and spellings are warnings.
+ */
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::schema::{self, SchemaError, CURRENT_SCHEMA_VERSION};
+
+/// Something found while merging
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeIssue {
+    /// Same name and definition in several places; only the first is kept
+    Duplicate { test: String, sources: Vec<String> },
+    /// Same name with different definitions
+    Conflict { test: String, sources: Vec<String> },
+    CategoryMismatch { test: String, source: String, declared: String, listed_under: String },
+    /// Names of one kind (`tag` or `category`) that differ only in spelling
+    InconsistentSpelling { kind: &'static str, variants: Vec<String> },
+}
+
+impl MergeIssue {
+    /// Whether the issue prevents writing the merged suite
+    pub fn is_error(&self) -> bool {
+        matches!(self, MergeIssue::Conflict { .. } | MergeIssue::CategoryMismatch { .. })
+    }
+}
+
+impl fmt::Display for MergeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeIssue::Duplicate { test, sources } => {
+                write!(f, "duplicate test '{}' in {}; keeping the first", test, sources.join(", "))
+            }
+            MergeIssue::Conflict { test, sources } => {
+                write!(f, "test '{}' has different definitions in {}", test, sources.join(", "))
+            }
+            MergeIssue::CategoryMismatch { test, source, declared, listed_under } => write!(
+                f,
+                "test '{}' in {} declares category '{}' but is listed under '{}'",
+                test, source, declared, listed_under
+            ),
+            MergeIssue::InconsistentSpelling { kind, variants } => {
+                write!(f, "inconsistent {} spellings: {}", kind, variants.join(", "))
+            }
+        }
+    }
+}
+
+/// Consolidated suite and what was found while building it
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    pub suite: Value,
+    pub issues: Vec<MergeIssue>,
+}
+
+impl MergeOutcome {
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(MergeIssue::is_error)
+    }
+
+    pub fn test_count(&self) -> usize {
+        self.suite["test_categories"].as_object().map_or(0, |c| c.values().filter_map(Value::as_array).map(Vec::len).sum())
+    }
+}
+
+/// Key under which spellings count as the same name
+fn spelling_key(name: &str) -> String {
+    name.to_lowercase().replace('-', "_")
+}
+
+/// Report groups of names in `names` that share a spelling key
+fn spelling_issues(kind: &'static str, names: impl IntoIterator<Item = String>) -> Vec<MergeIssue> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for name in names {
+        let variants = groups.entry(spelling_key(&name)).or_default();
+        if !variants.contains(&name) {
+            variants.push(name);
+        }
+    }
+    groups
+        .into_values()
+        .filter(|variants| variants.len() > 1)
+        .map(|variants| MergeIssue::InconsistentSpelling { kind, variants })
+        .collect()
+}
+
+/// Merge `(source, spec)` pairs, in order, into a suite named `name`
+pub fn merge_suites(name: &str, suites: Vec<(String, Value)>) -> Result<MergeOutcome, SchemaError> {
+    let mut categories: Map<String, Value> = Map::new();
+    // Kept definition of each test, the files listing it, and whether they disagree
+    let mut seen: BTreeMap<String, (Value, Vec<String>, bool)> = BTreeMap::new();
+    let mut issues = Vec::new();
+    let mut tags = Vec::new();
+    let mut sources = Vec::new();
+
+    for (source, mut spec) in suites {
+        schema::migrate(&mut spec)?;
+        sources.push(source.clone());
+        let Some(Value::Object(spec_categories)) = spec.get_mut("test_categories").map(Value::take) else { continue };
+        for (category, cases) in spec_categories {
+            let merged = categories.entry(category.clone()).or_insert_with(|| Value::Array(Vec::new()));
+            for case in cases.as_array().into_iter().flatten() {
+                let test = case["test_name"].as_str().unwrap_or_default().to_string();
+                let declared = case["category"].as_str().unwrap_or(&category);
+                if declared != category {
+                    issues.push(MergeIssue::CategoryMismatch {
+                        test: test.clone(),
+                        source: source.clone(),
+                        declared: declared.to_string(),
+                        listed_under: category.clone(),
+                    });
+                }
+                tags.extend(case["tags"].as_array().into_iter().flatten().filter_map(Value::as_str).map(str::to_string));
+
+                match seen.get_mut(&test) {
+                    Some((kept, places, conflicting)) => {
+                        places.push(source.clone());
+                        *conflicting |= kept != case;
+                    }
+                    None => {
+                        seen.insert(test, (case.clone(), vec![source.clone()], false));
+                        merged.as_array_mut().expect("categories are arrays").push(case.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    for (test, (_, sources, conflicting)) in seen.into_iter().filter(|(_, (_, places, _))| places.len() > 1) {
+        issues.push(if conflicting { MergeIssue::Conflict { test, sources } } else { MergeIssue::Duplicate { test, sources } });
+    }
+    issues.extend(spelling_issues("category", categories.keys().cloned()));
+    issues.extend(spelling_issues("tag", tags));
+
+    let mut suite = Map::new();
+    suite.insert("test_suite".to_string(), Value::from(name));
+    suite.insert("version".to_string(), Value::from("1.0"));
+    suite.insert("schema_version".to_string(), Value::from(CURRENT_SCHEMA_VERSION));
+    suite.insert("description".to_string(), Value::from(format!("Merged from {}", sources.join(", "))));
+    suite.insert("test_categories".to_string(), Value::Object(categories));
+    Ok(MergeOutcome { suite: Value::Object(suite), issues })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn case(name: &str, category: &str, expected: f64, tags: &[&str]) -> Value {
+        json!({ "test_name": name, "description": name, "category": category,
+                "inputs": {}, "expected_outputs": { "value": expected }, "tags": tags })
+    }
+
+    #[test]
+    fn test_duplicates_are_dropped_and_conflicts_reported() {
+        let cpp = json!({ "test_suite": "cpp", "version": "1.0", "test_categories": {
+            "scalars": [case("zero", "scalars", 0.0, &["basic"]), case("one", "scalars", 1.0, &["basic"])] } });
+        let rust = json!({ "test_suite": "rust", "version": "1.0", "schema_version": 2, "test_categories": {
+            "scalars": [case("zero", "scalars", 0.0, &["basic"]), case("one", "scalars", 2.0, &["Basic"])],
+            "vectors": [case("unit", "vectors", 1.0, &[])] } });
+
+        let outcome = merge_suites("all", vec![("cpp.json".into(), cpp), ("rust.json".into(), rust)]).unwrap();
+        assert_eq!(outcome.test_count(), 3);
+        assert_eq!(outcome.suite["test_categories"]["scalars"][1]["expected_outputs"]["value"], 1.0);
+        assert!(outcome.has_errors());
+        let sources = vec!["cpp.json".to_string(), "rust.json".to_string()];
+        assert_eq!(
+            outcome.issues,
+            vec![
+                MergeIssue::Conflict { test: "one".into(), sources: sources.clone() },
+                MergeIssue::Duplicate { test: "zero".into(), sources },
+                MergeIssue::InconsistentSpelling { kind: "tag", variants: vec!["basic".into(), "Basic".into()] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_category_mismatch_is_an_error() {
+        let spec = json!({ "test_suite": "s", "version": "1.0", "test_categories": {
+            "vector-ops": [case("add", "vector_ops", 1.0, &[])], "vector_ops": [] } });
+        let outcome = merge_suites("s", vec![("s.json".into(), spec)]).unwrap();
+        assert!(outcome.has_errors());
+        assert!(matches!(&outcome.issues[0], MergeIssue::CategoryMismatch { declared, .. } if declared == "vector_ops"));
+        assert!(matches!(&outcome.issues[1], MergeIssue::InconsistentSpelling { kind: "category", .. }));
+        assert_eq!(outcome.suite["schema_version"], CURRENT_SCHEMA_VERSION);
+    }
+}
@@ -0,0 +1,253 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Named operations callable from JSON test cases (Rust)
+ *
+ * A test case with an `"operation": "<name>"` field is executed by the operation
+ * registered under that name instead of the pattern-matching interpreter. Each
+ * operation declares typed input and output schemas; inputs are checked before
+ * the call and outputs after it, so a malformed test case or a misbehaving
+ * operation fails with a precise message. The C++ side mirrors this in
+ * `operation_registry.hpp`.
+ */
+
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// JSON type of an operation input or output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Number,
+    Integer,
+    Boolean,
+    String,
+    /// Array of numbers, optionally of fixed length
+    Vector(Option<usize>),
+    Object,
+}
+
+impl ValueType {
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            ValueType::Number => value.is_number(),
+            ValueType::Integer => value.is_i64() || value.is_u64(),
+            ValueType::Boolean => value.is_boolean(),
+            ValueType::String => value.is_string(),
+            ValueType::Vector(length) => value.as_array().is_some_and(|items| {
+                items.iter().all(Value::is_number) && length.is_none_or(|n| items.len() == n)
+            }),
+            ValueType::Object => value.is_object(),
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueType::Number => write!(f, "number"),
+            ValueType::Integer => write!(f, "integer"),
+            ValueType::Boolean => write!(f, "boolean"),
+            ValueType::String => write!(f, "string"),
+            ValueType::Vector(Some(length)) => write!(f, "vector[{}]", length),
+            ValueType::Vector(None) => write!(f, "vector"),
+            ValueType::Object => write!(f, "object"),
+        }
+    }
+}
+
+/// Named, typed inputs and outputs of an operation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationSchema {
+    pub inputs: Vec<(String, ValueType)>,
+    pub outputs: Vec<(String, ValueType)>,
+}
+
+impl OperationSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn input(mut self, name: &str, value_type: ValueType) -> Self {
+        self.inputs.push((name.to_string(), value_type));
+        self
+    }
+
+    pub fn output(mut self, name: &str, value_type: ValueType) -> Self {
+        self.outputs.push((name.to_string(), value_type));
+        self
+    }
+}
+
+/// Errors raised when calling a registered operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperationError {
+    UnknownOperation(String),
+    MissingInput(String),
+    MissingOutput(String),
+    TypeMismatch { field: String, expected: ValueType },
+    Failed(String),
+}
+
+impl fmt::Display for OperationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationError::UnknownOperation(name) => write!(f, "unknown operation '{}'", name),
+            OperationError::MissingInput(field) => write!(f, "missing input '{}'", field),
+            OperationError::MissingOutput(field) => write!(f, "operation did not produce output '{}'", field),
+            OperationError::TypeMismatch { field, expected } => write!(f, "'{}' must be a {}", field, expected),
+            OperationError::Failed(message) => write!(f, "operation failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for OperationError {}
+
+/// An operation that JSON test cases can call by name
+pub trait TestOperation: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn schema(&self) -> OperationSchema;
+
+    /// Compute the outputs; inputs have already been checked against the schema
+    fn execute(&self, inputs: &Map<String, Value>) -> Result<Map<String, Value>, String>;
+}
+
+type OperationFn = dyn Fn(&Map<String, Value>) -> Result<Map<String, Value>, String> + Send + Sync;
+
+/// Operation defined by a closure, see [`OperationRegistry::register_fn`]
+struct FnOperation {
+    name: String,
+    schema: OperationSchema,
+    function: Box<OperationFn>,
+}
+
+impl TestOperation for FnOperation {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn schema(&self) -> OperationSchema {
+        self.schema.clone()
+    }
+
+    fn execute(&self, inputs: &Map<String, Value>) -> Result<Map<String, Value>, String> {
+        (self.function)(inputs)
+    }
+}
+
+/// Registry of named operations
+#[derive(Default)]
+pub struct OperationRegistry {
+    operations: BTreeMap<String, Box<dyn TestOperation>>,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an operation, returning the one it replaces
+    pub fn register(&mut self, operation: Box<dyn TestOperation>) -> Option<Box<dyn TestOperation>> {
+        self.operations.insert(operation.name().to_string(), operation)
+    }
+
+    /// Register a closure as an operation
+    pub fn register_fn<F>(&mut self, name: &str, schema: OperationSchema, function: F) -> Option<Box<dyn TestOperation>>
+    where
+        F: Fn(&Map<String, Value>) -> Result<Map<String, Value>, String> + Send + Sync + 'static,
+    {
+        self.register(Box::new(FnOperation { name: name.to_string(), schema, function: Box::new(function) }))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn TestOperation> {
+        self.operations.get(name).map(|operation| operation.as_ref())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.operations.contains_key(name)
+    }
+
+    /// Registered operation names, sorted
+    pub fn names(&self) -> Vec<&str> {
+        self.operations.keys().map(String::as_str).collect()
+    }
+
+    /// Call an operation with schema checks on its inputs and outputs
+    pub fn execute(&self, name: &str, inputs: &Value) -> Result<Value, OperationError> {
+        let operation = self.get(name).ok_or_else(|| OperationError::UnknownOperation(name.to_string()))?;
+        let schema = operation.schema();
+
+        let empty = Map::new();
+        let inputs = match inputs {
+            Value::Object(fields) => fields,
+            Value::Null => &empty,
+            _ => return Err(OperationError::TypeMismatch { field: "inputs".to_string(), expected: ValueType::Object }),
+        };
+        for (field, value_type) in &schema.inputs {
+            let value = inputs.get(field).ok_or_else(|| OperationError::MissingInput(field.clone()))?;
+            if !value_type.matches(value) {
+                return Err(OperationError::TypeMismatch { field: field.clone(), expected: *value_type });
+            }
+        }
+
+        let outputs = operation.execute(inputs).map_err(OperationError::Failed)?;
+        for (field, value_type) in &schema.outputs {
+            let value = outputs.get(field).ok_or_else(|| OperationError::MissingOutput(field.clone()))?;
+            if !value_type.matches(value) {
+                return Err(OperationError::TypeMismatch { field: field.clone(), expected: *value_type });
+            }
+        }
+        Ok(Value::Object(outputs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn planar_arm() -> OperationRegistry {
+        let mut registry = OperationRegistry::new();
+        let schema = OperationSchema::new()
+            .input("joint_angles", ValueType::Vector(Some(2)))
+            .input("link_lengths", ValueType::Vector(Some(2)))
+            .output("position", ValueType::Vector(Some(2)));
+        registry.register_fn("forward_kinematics", schema, |inputs| {
+            let values = |key: &str| -> Vec<f64> {
+                inputs[key].as_array().unwrap().iter().filter_map(Value::as_f64).collect()
+            };
+            let (q, l) = (values("joint_angles"), values("link_lengths"));
+            let x = l[0] * q[0].cos() + l[1] * (q[0] + q[1]).cos();
+            let y = l[0] * q[0].sin() + l[1] * (q[0] + q[1]).sin();
+            Ok(json!({ "position": [x, y] }).as_object().unwrap().clone())
+        });
+        registry
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = planar_arm();
+        assert_eq!(registry.names(), vec!["forward_kinematics"]);
+
+        let outputs = registry
+            .execute("forward_kinematics", &json!({ "joint_angles": [0.0, 0.0], "link_lengths": [1.0, 0.5] }))
+            .unwrap();
+        assert_eq!(outputs, json!({ "position": [1.5, 0.0] }));
+
+        assert_eq!(
+            registry.execute("forward_kinematics", &json!({ "joint_angles": [0.0], "link_lengths": [1.0, 0.5] })),
+            Err(OperationError::TypeMismatch { field: "joint_angles".to_string(), expected: ValueType::Vector(Some(2)) })
+        );
+        assert_eq!(
+            registry.execute("forward_kinematics", &json!({ "joint_angles": [0.0, 0.0] })),
+            Err(OperationError::MissingInput("link_lengths".to_string()))
+        );
+        assert_eq!(
+            registry.execute("buoyancy_force", &json!({})),
+            Err(OperationError::UnknownOperation("buoyancy_force".to_string()))
+        );
+    }
+}
@@ -0,0 +1,178 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Time budgets for performance test cases (Rust)
+ *
+ * Test cases in the `performance` category, or with a `performance` section,
+ * are timed instead of compared by value. The runner executes the test
+ * `warmup` times untimed, then `iterations` times timing each run, and each
+ * entry of `expected_outputs` is a budget in nanoseconds for one statistic of
+ * the per-iteration times:
+ *
+ * ```json
+ * "performance": { "iterations": 1000, "warmup": 10, "margin": 0.5 },
+ * "expected_outputs": { "mean_ns": 2000, "quantiles": { "0.95": 5000 } }
+ * ```
+ *
+ * A budget is exceeded when the measured value is above
+ * `budget * (1 + margin) * scale`, where `scale` comes from the
+ * `GAFRO_PERF_BUDGET_SCALE` environment variable (default 1) so slow CI
+ * machines can loosen every budget at once. The measured statistics are
+ * reported as the test's actual outputs. The C++ side mirrors this in
+ * `performance.hpp`; both time the same registered operations, so their
+ * reports compare the two implementations.
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::statistics::Summary;
+
+/// Test category whose cases are timed against budgets
+pub const PERFORMANCE_CATEGORY: &str = "performance";
+/// Default fraction by which a measurement may exceed its budget
+pub const DEFAULT_MARGIN: f64 = 0.5;
+/// Environment variable scaling every budget
+pub const BUDGET_SCALE_VARIABLE: &str = "GAFRO_PERF_BUDGET_SCALE";
+
+/// How a performance test case is timed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceSpec {
+    pub iterations: usize,
+    /// Untimed runs before the timed ones
+    pub warmup: usize,
+    pub margin: f64,
+}
+
+impl Default for PerformanceSpec {
+    fn default() -> Self {
+        Self { iterations: 100, warmup: 5, margin: DEFAULT_MARGIN }
+    }
+}
+
+impl PerformanceSpec {
+    /// Parse a `performance` section; missing fields take their defaults
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let section = value.as_object()?;
+        let defaults = Self::default();
+        Some(Self {
+            iterations: section.get("iterations").and_then(Value::as_u64).map_or(defaults.iterations, |n| n.max(1) as usize),
+            warmup: section.get("warmup").and_then(Value::as_u64).map_or(defaults.warmup, |n| n as usize),
+            margin: section.get("margin").and_then(Value::as_f64).unwrap_or(defaults.margin),
+        })
+    }
+
+    /// Spec of a test case: its `performance` section, or the defaults in the performance category
+    pub fn for_test_case(test_case: &Value, category: &str) -> Option<Self> {
+        test_case
+            .get("performance")
+            .and_then(Self::from_json)
+            .or_else(|| (category == PERFORMANCE_CATEGORY).then(Self::default))
+    }
+}
+
+/// Budget multiplier from `GAFRO_PERF_BUDGET_SCALE`, 1 when unset or invalid
+pub fn budget_scale() -> f64 {
+    std::env::var(BUDGET_SCALE_VARIABLE)
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|s| *s > 0.0)
+        .unwrap_or(1.0)
+}
+
+/// Measured statistics of the per-iteration times, in the shape of `expected`
+pub fn timings_to_json(summary: &Summary, expected: &Map<String, Value>) -> Value {
+    let mut fields = Map::new();
+    fields.insert("iterations".to_string(), Value::from(summary.count()));
+    for (key, value) in [("mean_ns", summary.mean), ("min_ns", summary.min()), ("max_ns", summary.max())] {
+        fields.insert(key.to_string(), Value::from(value));
+    }
+    fields.insert("median_ns".to_string(), Value::from(summary.quantile(0.5)));
+    if let Some(Value::Object(quantiles)) = expected.get("quantiles") {
+        let measured = quantiles
+            .keys()
+            .filter_map(|p| p.parse::<f64>().ok().map(|q| (p.clone(), Value::from(summary.quantile(q)))))
+            .collect();
+        fields.insert("quantiles".to_string(), Value::Object(measured));
+    }
+    Value::Object(fields)
+}
+
+/// Check every budget, describing the first one exceeded
+pub fn within_budgets(summary: &Summary, expected: &Map<String, Value>, margin: f64, scale: f64) -> Result<(), String> {
+    let check = |name: &str, measured: f64, budget: &Value| -> Result<(), String> {
+        let budget = budget.as_f64().ok_or_else(|| format!("budget {} is not a number", name))?;
+        let limit = budget * (1.0 + margin) * scale;
+        if measured <= limit {
+            Ok(())
+        } else {
+            Err(format!("{} {:.0} ns exceeds budget {:.0} ns (limit {:.0} ns)", name, measured, budget, limit))
+        }
+    };
+
+    for (key, budget) in expected {
+        match key.as_str() {
+            "mean_ns" => check("mean", summary.mean, budget)?,
+            "median_ns" => check("median", summary.quantile(0.5), budget)?,
+            "min_ns" => check("min", summary.min(), budget)?,
+            "max_ns" => check("max", summary.max(), budget)?,
+            "quantiles" => {
+                for (p, budget) in budget.as_object().into_iter().flatten() {
+                    let q = p.parse::<f64>().map_err(|_| format!("invalid quantile '{}'", p))?;
+                    check(&format!("quantile {}", p), summary.quantile(q), budget)?;
+                }
+            }
+            other => return Err(format!("unknown budget '{}'", other)),
+        }
+    }
+    Ok(())
+}
+
+/// Compare per-iteration times in nanoseconds against the budgets in `expected`
+///
+/// Returns the measured statistics and the first exceeded budget if any.
+pub fn compare_timings(times_ns: Vec<f64>, expected: &Value, margin: f64, scale: f64) -> (Value, Result<(), String>) {
+    let Some(budgets) = expected.as_object() else {
+        return (Value::Null, Err("expected outputs of a performance test must be an object of budgets".to_string()));
+    };
+    if times_ns.is_empty() {
+        return (Value::Null, Err("no timed iterations".to_string()));
+    }
+    let summary = Summary::new(times_ns);
+    (timings_to_json(&summary, budgets), within_budgets(&summary, budgets, margin, scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_spec_from_section_or_category() {
+        let case = json!({ "performance": { "iterations": 20, "margin": 0.1 } });
+        let spec = PerformanceSpec::for_test_case(&case, "algebra").unwrap();
+        assert_eq!((spec.iterations, spec.warmup, spec.margin), (20, 5, 0.1));
+        assert_eq!(PerformanceSpec::for_test_case(&json!({}), PERFORMANCE_CATEGORY), Some(PerformanceSpec::default()));
+        assert_eq!(PerformanceSpec::for_test_case(&json!({}), "algebra"), None);
+    }
+
+    #[test]
+    fn test_budgets_with_margin_and_scale() {
+        let times: Vec<f64> = (1..=100).map(|i| i as f64 * 10.0).collect();
+        let budgets = json!({ "mean_ns": 400.0, "quantiles": { "0.9": 800.0 } });
+
+        // Mean 505 and 90th percentile 901 fit within 1.5 times their budgets
+        let (measured, outcome) = compare_timings(times.clone(), &budgets, 0.5, 1.0);
+        assert_eq!(outcome, Ok(()));
+        assert_eq!(measured["mean_ns"], 505.0);
+        assert_eq!(measured["iterations"], 100);
+        assert!(measured["quantiles"]["0.9"].as_f64().unwrap() > 900.0);
+
+        let (_, outcome) = compare_timings(times.clone(), &budgets, 0.1, 1.0);
+        assert!(outcome.unwrap_err().starts_with("mean 505 ns exceeds budget 400 ns"));
+        assert_eq!(compare_timings(times.clone(), &budgets, 0.1, 2.0).1, Ok(()));
+        assert!(compare_timings(times, &json!({ "p99": 1.0 }), 0.5, 1.0).1.is_err());
+    }
+}
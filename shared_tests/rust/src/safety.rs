@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Safety violations reported by test outputs (Rust)
+ *
+ * Mirrors the JSON layout of `gafro_modern::safety::SafetyViolation`: which
+ * joint broke which limit, the attempted value and the limit in one unit, and
+ * the margin by which the limit was missed. A test whose actual outputs carry
+ * a `violations` array has them parsed into its result, so text, JSON and
+ * HTML reports explain a failed joint-limit or reachability check instead of
+ * showing raw numbers.
+ */
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::diff::escape_html;
+
+/// Which limit was broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitKind {
+    Lower,
+    Upper,
+    Rotation,
+    Velocity,
+    Workspace,
+    Tolerance,
+    Clearance,
+}
+
+impl LimitKind {
+    /// What the attempted value measures
+    pub fn quantity(self) -> &'static str {
+        match self {
+            LimitKind::Lower | LimitKind::Upper => "position",
+            LimitKind::Rotation => "rotation",
+            LimitKind::Velocity => "speed",
+            LimitKind::Workspace => "distance",
+            LimitKind::Tolerance => "position error",
+            LimitKind::Clearance => "clearance",
+        }
+    }
+
+    /// Name of the limit
+    pub fn label(self) -> &'static str {
+        match self {
+            LimitKind::Lower => "lower limit",
+            LimitKind::Upper => "upper limit",
+            LimitKind::Rotation => "rotation limit",
+            LimitKind::Velocity => "velocity limit",
+            LimitKind::Workspace => "reach",
+            LimitKind::Tolerance => "tolerance",
+            LimitKind::Clearance => "required clearance",
+        }
+    }
+
+    /// Whether the limit is a minimum, so a violation lies below it
+    pub fn is_minimum(self) -> bool {
+        matches!(self, LimitKind::Lower | LimitKind::Clearance)
+    }
+}
+
+/// A limit broken by an attempted value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafetyViolation {
+    /// Index of the joint among the chain's segments; absent for a target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub joint: Option<usize>,
+    /// Index of the joint's first coordinate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinate: Option<usize>,
+    pub kind: LimitKind,
+    pub attempted: f64,
+    pub limit: f64,
+    /// Unit symbol of `attempted`, `limit` and `margin`
+    pub unit: String,
+    /// How far `attempted` lies on the wrong side of `limit`; positive
+    pub margin: f64,
+}
+
+impl SafetyViolation {
+    /// Who broke the limit: `joint 2`, or `target` for a reachability check
+    pub fn subject(&self) -> String {
+        self.joint.map_or_else(|| "target".to_string(), |joint| format!("joint {}", joint))
+    }
+
+    /// One sentence with the values formatted by `value`
+    pub fn describe(&self, value: impl Fn(f64) -> String) -> String {
+        format!(
+            "{}: {} {} {} {} {} by {}",
+            self.subject(),
+            self.kind.quantity(),
+            value(self.attempted),
+            if self.kind.is_minimum() { "below" } else { "above" },
+            self.kind.label(),
+            value(self.limit),
+            value(self.margin)
+        )
+    }
+}
+
+impl fmt::Display for SafetyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(3);
+        write!(f, "{}", self.describe(|x| format!("{:.p$} {}", x, self.unit, p = precision)))
+    }
+}
+
+/// Violations listed under `violations` in a test's outputs
+///
+/// Outputs without the field, or with entries that do not parse, give none.
+pub fn violations_in(outputs: &Value) -> Vec<SafetyViolation> {
+    outputs.get("violations").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or_default()
+}
+
+/// Table of violations for the HTML report
+pub fn violations_to_html(violations: &[SafetyViolation]) -> String {
+    let mut html = String::from(
+        "<table class=\"violations\">\n<tr><th>Joint</th><th>Limit</th><th>Attempted</th><th>Limit value</th><th>Margin</th></tr>\n",
+    );
+    for v in violations {
+        let value = |x: f64| escape_html(&format!("{:.3} {}", x, v.unit));
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"actual\">{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&v.subject()),
+            v.kind.label(),
+            value(v.attempted),
+            value(v.limit),
+            value(v.margin)
+        ));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_violations_parse_from_outputs() {
+        let outputs = json!({
+            "violations": [
+                { "joint": 2, "coordinate": 1, "kind": "lower", "attempted": -0.05, "limit": 0.0, "unit": "m", "margin": 0.05 },
+                { "kind": "clearance", "attempted": -0.1, "limit": 0.05, "unit": "m", "margin": 0.15 }
+            ]
+        });
+        let violations = violations_in(&outputs);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].to_string(), "joint 2: position -0.050 m below lower limit 0.000 m by 0.050 m");
+        assert_eq!(format!("{:.2}", violations[1]), "target: clearance -0.10 m below required clearance 0.05 m by 0.15 m");
+        assert_eq!(serde_json::to_value(&violations).unwrap(), outputs["violations"]);
+
+        assert!(violations_in(&json!({ "x": 1.0 })).is_empty());
+        assert!(violations_in(&json!({ "violations": [{ "kind": "sideways" }] })).is_empty());
+        assert!(violations_to_html(&violations).contains("<td>target</td><td>required clearance</td>"));
+    }
+}
@@ -0,0 +1,161 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Versioning of the JSON test specification format (Rust)
+ *
+ * Every spec file carries an integer `schema_version`; files written before it
+ * existed are version 1. On load, [`migrate`] upgrades a spec step by step to
+ * [`CURRENT_SCHEMA_VERSION`], so the runners only ever parse the current model
+ * and old files keep working as the format evolves. `gafro_test_runner migrate`
+ * writes the upgraded files back. Specs newer than the runner are rejected
+ * rather than misread.
+ *
+ * Changing the format means bumping [`CURRENT_SCHEMA_VERSION`] and appending a
+ * step to [`MIGRATIONS`] that rewrites the previous version into the new one.
+ * The C++ side mirrors this in `schema.hpp`.
+ */
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+/// Version of the spec format this runner parses
+pub const CURRENT_SCHEMA_VERSION: u64 = 2;
+
+/// One upgrade from `from` to `from + 1`
+#[derive(Debug)]
+pub struct Migration {
+    pub from: u64,
+    pub description: &'static str,
+    pub apply: fn(&mut Map<String, Value>),
+}
+
+/// Upgrades in order, one per version step
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    description: "add schema_version; give every test case its category and empty inputs and expected outputs when missing",
+    apply: v1_to_v2,
+}];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    NotAnObject,
+    InvalidVersion(Value),
+    /// Written by a newer runner
+    Unsupported(u64),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::NotAnObject => write!(f, "test specification must be a JSON object"),
+            SchemaError::InvalidVersion(v) => write!(f, "invalid schema_version {}", v),
+            SchemaError::Unsupported(v) => write!(
+                f,
+                "schema_version {} is newer than the supported version {}; update the test runner",
+                v, CURRENT_SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Schema version of a spec; 1 when the field is absent
+pub fn schema_version(spec: &Value) -> Result<u64, SchemaError> {
+    let object = spec.as_object().ok_or(SchemaError::NotAnObject)?;
+    match object.get("schema_version") {
+        None => Ok(1),
+        Some(v) => v.as_u64().filter(|&v| v >= 1).ok_or_else(|| SchemaError::InvalidVersion(v.clone())),
+    }
+}
+
+/// Upgrade a spec to the current version, returning the migrations applied
+pub fn migrate(spec: &mut Value) -> Result<Vec<&'static Migration>, SchemaError> {
+    let mut version = schema_version(spec)?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(SchemaError::Unsupported(version));
+    }
+    let object = spec.as_object_mut().ok_or(SchemaError::NotAnObject)?;
+    let mut applied = Vec::new();
+    while let Some(migration) = MIGRATIONS.iter().find(|m| m.from == version) {
+        (migration.apply)(object);
+        version += 1;
+        object.insert("schema_version".to_string(), Value::from(version));
+        applied.push(migration);
+    }
+    Ok(applied)
+}
+
+/// Version 2: `schema_version` right after the suite version, and test cases with
+/// `category`, `inputs` and `expected_outputs` always present
+fn v1_to_v2(spec: &mut Map<String, Value>) {
+    let entries = std::mem::take(spec);
+    let mut inserted = false;
+    for (key, value) in entries {
+        let after_header = key == "version";
+        spec.insert(key, value);
+        if after_header {
+            spec.insert("schema_version".to_string(), Value::from(2));
+            inserted = true;
+        }
+    }
+    if !inserted {
+        spec.insert("schema_version".to_string(), Value::from(2));
+    }
+
+    let Some(Value::Object(categories)) = spec.get_mut("test_categories") else { return };
+    for (name, cases) in categories.iter_mut() {
+        for case in cases.as_array_mut().into_iter().flatten().filter_map(Value::as_object_mut) {
+            case.entry("category").or_insert_with(|| Value::from(name.as_str()));
+            case.entry("inputs").or_insert_with(|| Value::Object(Map::new()));
+            case.entry("expected_outputs").or_insert_with(|| Value::Object(Map::new()));
+        }
+    }
+}
+
+/// Spec as written by `migrate`: four-space indentation like the checked-in files
+pub fn to_pretty_json(spec: &Value) -> String {
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    serde::Serialize::serialize(spec, &mut serializer).expect("JSON values serialize");
+    let mut text = String::from_utf8(buffer).expect("serde_json writes UTF-8");
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_v1_spec_migrates_to_current() {
+        let mut spec = json!({
+            "test_suite": "legacy",
+            "version": "1.0",
+            "test_categories": { "scalars": [{ "test_name": "zero", "description": "zero scalar" }] }
+        });
+        let applied = migrate(&mut spec).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(schema_version(&spec), Ok(CURRENT_SCHEMA_VERSION));
+        let keys: Vec<_> = spec.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(keys, ["test_suite", "version", "schema_version", "test_categories"]);
+        let case = &spec["test_categories"]["scalars"][0];
+        assert_eq!(case["category"], "scalars");
+        assert_eq!(case["inputs"], json!({}));
+
+        // Already current: nothing to do
+        assert!(migrate(&mut spec).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_and_newer_versions_are_rejected() {
+        assert_eq!(migrate(&mut json!({ "schema_version": 99 })).unwrap_err(), SchemaError::Unsupported(99));
+        assert!(matches!(migrate(&mut json!({ "schema_version": "2" })), Err(SchemaError::InvalidVersion(_))));
+        assert_eq!(migrate(&mut json!([])).unwrap_err(), SchemaError::NotAnObject);
+    }
+}
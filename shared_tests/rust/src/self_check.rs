@@ -0,0 +1,193 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Mutation-style self-check of the output comparison (Rust)
+ *
+ * A cross-language result is only as trustworthy as `compare_outputs`. With
+ * `--self-check` the runner does not execute tests; for every numeric expected
+ * output it builds actual outputs equal to the expectation except that one
+ * number is moved by `tolerance - epsilon` and `tolerance + epsilon` in each
+ * direction, and checks that the comparison passes inside the tolerance and
+ * fails outside it. A comparison that mixes absolute and relative tolerances,
+ * drops a sign or ignores nested outputs flips at the wrong place.
+ *
+ * `epsilon` is a hundredth of the tolerance, but never below the floating-point
+ * resolution at the expected value. Where the tolerance itself is below that
+ * resolution the boundary cannot be probed, and the output is reported as
+ * unresolvable: the test asks for more precision than the value can carry.
+ */
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::json_loader::{TestCase, TestExecutionContext};
+
+/// Smallest probe distance from the boundary, in units of the spacing of f64 at the value
+const RESOLUTION_ULPS: f64 = 8.0;
+
+/// A probe the comparison judged wrongly
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundaryFailure {
+    pub test: String,
+    /// JSON pointer of the perturbed output
+    pub path: String,
+    pub expected: f64,
+    pub offset: f64,
+    pub tolerance: f64,
+    pub should_pass: bool,
+}
+
+impl fmt::Display for BoundaryFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: expected {} moved by {:+e} with tolerance {:e} should {} but {}",
+            self.test,
+            self.path,
+            self.expected,
+            self.offset,
+            self.tolerance,
+            if self.should_pass { "pass" } else { "fail" },
+            if self.should_pass { "failed" } else { "passed" }
+        )
+    }
+}
+
+/// Outcome of probing every numeric output of a set of tests
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelfCheckReport {
+    pub probes: usize,
+    pub failures: Vec<BoundaryFailure>,
+    /// `(test, path)` of outputs whose tolerance is below the resolution of their value
+    pub unresolvable: Vec<(String, String)>,
+    /// Tests not compared value by value (statistics or performance)
+    pub skipped: Vec<String>,
+}
+
+impl SelfCheckReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for SelfCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\n=== Comparison Self-Check ===")?;
+        for failure in &self.failures {
+            writeln!(f, "[FAIL] {}", failure)?;
+        }
+        for (test, path) in &self.unresolvable {
+            writeln!(f, "[WARN] {} {}: tolerance below the floating-point resolution of the value", test, path)?;
+        }
+        writeln!(f, "Probes: {}", self.probes)?;
+        writeln!(f, "Wrong verdicts: {}", self.failures.len())?;
+        writeln!(f, "Unresolvable outputs: {}", self.unresolvable.len())?;
+        writeln!(f, "Skipped tests: {}", self.skipped.len())?;
+        write!(f, "=============================")
+    }
+}
+
+/// JSON pointers and values of the numeric leaves of `value`
+pub fn numeric_leaves(value: &Value) -> Vec<(String, f64)> {
+    fn walk(value: &Value, path: String, leaves: &mut Vec<(String, f64)>) {
+        match value {
+            Value::Number(n) => leaves.extend(n.as_f64().map(|x| (path, x))),
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    walk(item, format!("{}/{}", path, i), leaves);
+                }
+            }
+            Value::Object(fields) => {
+                for (key, field) in fields {
+                    walk(field, format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1")), leaves);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut leaves = Vec::new();
+    walk(value, String::new(), &mut leaves);
+    leaves
+}
+
+/// Probe the comparison at the tolerance boundary of every numeric expected output
+pub fn self_check(context: &TestExecutionContext, test_cases: &[TestCase]) -> SelfCheckReport {
+    let mut report = SelfCheckReport::default();
+    for test_case in test_cases {
+        if test_case.statistics.is_some() || test_case.performance.is_some() {
+            report.skipped.push(test_case.test_name.clone());
+            continue;
+        }
+        let expected = &test_case.expected_outputs;
+        let tolerance = test_case.tolerance;
+        for (path, value) in numeric_leaves(expected) {
+            let resolution = RESOLUTION_ULPS * f64::EPSILON * value.abs().max(tolerance).max(f64::MIN_POSITIVE);
+            let epsilon = (tolerance * 0.01).max(resolution);
+            if tolerance - epsilon <= resolution {
+                report.unresolvable.push((test_case.test_name.clone(), path));
+                continue;
+            }
+            for (offset, should_pass) in [
+                (tolerance - epsilon, true),
+                (-(tolerance - epsilon), true),
+                (tolerance + epsilon, false),
+                (-(tolerance + epsilon), false),
+            ] {
+                let mut actual = expected.clone();
+                if let Some(leaf) = actual.pointer_mut(&path) {
+                    *leaf = Value::from(value + offset);
+                }
+                report.probes += 1;
+                if context.compare_outputs(&actual, expected, tolerance) != should_pass {
+                    report.failures.push(BoundaryFailure {
+                        test: test_case.test_name.clone(),
+                        path: path.clone(),
+                        expected: value,
+                        offset,
+                        tolerance,
+                        should_pass,
+                    });
+                }
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_loader::TestSuite;
+
+    fn cases(expected: &str, tolerance: f64) -> Vec<TestCase> {
+        let spec = format!(
+            r#"{{ "test_suite": "s", "version": "1.0", "test_categories": {{ "c": [
+                {{ "test_name": "t", "description": "d", "category": "c", "inputs": {{}},
+                   "expected_outputs": {}, "tolerance": {:e} }} ] }} }}"#,
+            expected, tolerance
+        );
+        TestSuite::load_from_string(&spec).unwrap().get_all_test_cases()
+    }
+
+    #[test]
+    fn test_comparison_flips_at_the_boundary() {
+        let context = TestExecutionContext::new();
+        let test_cases = cases(r#"{ "scalar": 2.5, "vector": [1.0, -1000.0], "nested": { "a/b": 0 } }"#, 1e-6);
+        let report = self_check(&context, &test_cases);
+        assert_eq!(report.probes, 16);
+        assert!(report.passed(), "{}", report);
+        assert!(report.unresolvable.is_empty());
+        let paths: Vec<_> = numeric_leaves(&test_cases[0].expected_outputs).into_iter().map(|(p, _)| p).collect();
+        assert_eq!(paths, ["/scalar", "/vector/0", "/vector/1", "/nested/a~1b"]);
+    }
+
+    #[test]
+    fn test_tolerance_below_resolution_is_unresolvable() {
+        let report = self_check(&TestExecutionContext::new(), &cases(r#"{ "big": 1.0e9 }"#, 1e-10));
+        assert_eq!(report.probes, 0);
+        assert_eq!(report.unresolvable, vec![("t".to_string(), "/big".to_string())]);
+    }
+}
@@ -141,3 +141,220 @@ impl Force {
         Self::new(value)
     }
 }
+
+/// Runtime counterpart of [`SIQuantity`] for interpreting JSON test specifications
+///
+/// Dimensions are checked when the operation is evaluated rather than at compile
+/// time, so test code can build quantities from strings and report mismatches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DynamicQuantity {
+    pub value: f64,
+    /// Mass, length and time exponents
+    pub dimensions: [i32; 3],
+}
+
+/// Mass, length and time exponents printed as `kg·m²·s⁻³ (power)`
+pub fn format_dimensions(dimensions: [i32; 3]) -> String {
+    let superscript = |exponent: i32| -> String {
+        exponent
+            .to_string()
+            .chars()
+            .map(|c| match c {
+                '-' => '⁻',
+                digit => ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'][digit.to_digit(10).unwrap_or(0) as usize],
+            })
+            .collect()
+    };
+    let factors: Vec<String> = dimensions
+        .iter()
+        .zip(["kg", "m", "s"])
+        .filter(|(exponent, _)| **exponent != 0)
+        .map(|(exponent, symbol)| if *exponent == 1 { symbol.to_string() } else { format!("{}{}", symbol, superscript(*exponent)) })
+        .collect();
+    let symbol = if factors.is_empty() { "1".to_string() } else { factors.join("·") };
+    let name = match dimensions {
+        [0, 0, 0] => "dimensionless",
+        [1, 0, 0] => "mass",
+        [0, 1, 0] => "length",
+        [0, 0, 1] => "time",
+        [0, 0, -1] => "frequency",
+        [0, 1, -1] => "velocity",
+        [0, 1, -2] => "acceleration",
+        [1, 1, -2] => "force",
+        [1, 2, -2] => "energy or torque",
+        [1, 2, -3] => "power",
+        [1, -1, -2] => "pressure",
+        _ => return symbol,
+    };
+    format!("{} ({})", symbol, name)
+}
+
+const BASE_NAMES: [&str; 3] = ["mass", "length", "time"];
+
+/// Adding or subtracting quantities of different dimensions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DimensionMismatch {
+    /// `"add"` or `"subtract"`
+    pub operation: &'static str,
+    pub left: [i32; 3],
+    pub right: [i32; 3],
+}
+
+impl std::fmt::Display for DimensionMismatch {
+    /// `cannot add m (length) and s (time): length exponent is 1 on the left but 0 on the right; ...`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot {} {} and {}: ", self.operation, format_dimensions(self.left), format_dimensions(self.right))?;
+        let differences: Vec<String> = BASE_NAMES
+            .iter()
+            .zip(self.left.iter().zip(&self.right))
+            .filter(|(_, (l, r))| l != r)
+            .map(|(name, (l, r))| format!("{} exponent is {} on the left but {} on the right", name, l, r))
+            .collect();
+        f.write_str(&differences.join("; "))
+    }
+}
+
+impl std::error::Error for DimensionMismatch {}
+
+impl DynamicQuantity {
+    pub const fn new(value: f64, mass: i32, length: i32, time: i32) -> Self {
+        Self { value, dimensions: [mass, length, time] }
+    }
+
+    /// Quantity from the constructor of a type alias, e.g. `("Length", 2.0)`
+    pub fn from_alias(alias: &str, value: f64) -> Option<Self> {
+        let [mass, length, time] = match alias {
+            "Dimensionless" => [0, 0, 0],
+            "Mass" => [1, 0, 0],
+            "Length" => [0, 1, 0],
+            "Time" => [0, 0, 1],
+            "Velocity" => [0, 1, -1],
+            "Acceleration" => [0, 1, -2],
+            "Force" => [1, 1, -2],
+            "Energy" | "Torque" => [1, 2, -2],
+            "Power" => [1, 2, -3],
+            "Pressure" => [1, -1, -2],
+            _ => return None,
+        };
+        Some(Self::new(value, mass, length, time))
+    }
+
+    pub fn same_dimensions(&self, other: &Self) -> bool {
+        self.dimensions == other.dimensions
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, DimensionMismatch> {
+        self.check_dimensions(&other, "add")?;
+        Ok(Self { value: self.value + other.value, ..self })
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, DimensionMismatch> {
+        self.check_dimensions(&other, "subtract")?;
+        Ok(Self { value: self.value - other.value, ..self })
+    }
+
+    fn check_dimensions(&self, other: &Self, operation: &'static str) -> Result<(), DimensionMismatch> {
+        if self.same_dimensions(other) {
+            Ok(())
+        } else {
+            Err(DimensionMismatch { operation, left: self.dimensions, right: other.dimensions })
+        }
+    }
+
+    /// How the exponents of `self * other` arise, e.g.
+    /// `kg (mass) × m·s⁻² (acceleration) = kg·m·s⁻² (force): mass 1 + 0 = 1; length 0 + 1 = 1; time 0 + -2 = -2`
+    pub fn explain_mul(&self, other: &Self) -> String {
+        self.explain(other, "×", "+", (*self * *other).dimensions)
+    }
+
+    /// How the exponents of `self / other` arise
+    pub fn explain_div(&self, other: &Self) -> String {
+        self.explain(other, "/", "-", (*self / *other).dimensions)
+    }
+
+    fn explain(&self, other: &Self, operator: &str, combine: &str, result: [i32; 3]) -> String {
+        let steps: Vec<String> = (0..3)
+            .filter(|&i| self.dimensions[i] != 0 || other.dimensions[i] != 0)
+            .map(|i| format!("{} {} {} {} = {}", BASE_NAMES[i], self.dimensions[i], combine, other.dimensions[i], result[i]))
+            .collect();
+        format!(
+            "{} {} {} = {}: {}",
+            format_dimensions(self.dimensions),
+            operator,
+            format_dimensions(other.dimensions),
+            format_dimensions(result),
+            steps.join("; ")
+        )
+    }
+}
+
+impl Mul for DynamicQuantity {
+    type Output = DynamicQuantity;
+
+    fn mul(self, other: DynamicQuantity) -> Self::Output {
+        let [m, l, t] = self.dimensions;
+        let [m2, l2, t2] = other.dimensions;
+        DynamicQuantity::new(self.value * other.value, m + m2, l + l2, t + t2)
+    }
+}
+
+impl Div for DynamicQuantity {
+    type Output = DynamicQuantity;
+
+    fn div(self, other: DynamicQuantity) -> Self::Output {
+        let [m, l, t] = self.dimensions;
+        let [m2, l2, t2] = other.dimensions;
+        DynamicQuantity::new(self.value / other.value, m - m2, l - l2, t - t2)
+    }
+}
+
+impl<const M: i32, const L: i32, const T: i32> From<SIQuantity<M, L, T>> for DynamicQuantity {
+    fn from(quantity: SIQuantity<M, L, T>) -> Self {
+        Self::new(quantity.value(), M, L, T)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_quantity() {
+        let distance = DynamicQuantity::from(Length::m(10.0));
+        let time = DynamicQuantity::from_alias("Time", 2.0).unwrap();
+        let velocity = distance / time;
+        assert_eq!(velocity, DynamicQuantity::from(Velocity::mps(5.0)));
+
+        let force = DynamicQuantity::from(Mass::kg(2.0)) * velocity / time;
+        assert_eq!(force.dimensions, [1, 1, -2]);
+        assert!(distance.checked_add(distance).is_ok());
+        assert_eq!(
+            distance.checked_sub(time),
+            Err(DimensionMismatch { operation: "subtract", left: [0, 1, 0], right: [0, 0, 1] })
+        );
+    }
+
+    #[test]
+    fn test_dimension_explanations() {
+        assert_eq!(format_dimensions([1, 2, -3]), "kg·m²·s⁻³ (power)");
+        assert_eq!(format_dimensions([2, 0, 1]), "kg²·s");
+
+        let force = DynamicQuantity::from(Force::n(1.0));
+        let energy = DynamicQuantity::from_alias("Energy", 1.0).unwrap();
+        assert_eq!(
+            force.checked_add(energy).unwrap_err().to_string(),
+            "cannot add kg·m·s⁻² (force) and kg·m²·s⁻² (energy or torque): length exponent is 1 on the left but 2 on the right"
+        );
+
+        let mass = DynamicQuantity::from(Mass::kg(2.0));
+        let acceleration = DynamicQuantity::from_alias("Acceleration", 3.0).unwrap();
+        assert_eq!(
+            mass.explain_mul(&acceleration),
+            "kg (mass) × m·s⁻² (acceleration) = kg·m·s⁻² (force): mass 1 + 0 = 1; length 0 + 1 = 1; time 0 + -2 = -2"
+        );
+        assert_eq!(
+            DynamicQuantity::from(Length::m(1.0)).explain_div(&DynamicQuantity::from(Time::s(1.0))),
+            "m (length) / s (time) = m·s⁻¹ (velocity): length 1 - 0 = 1; time 0 - 1 = -1"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Statistical comparison of stochastic test outputs (Rust)
+ *
+ * Algorithms such as RANSAC or RRT do not produce the same value in C++ and Rust
+ * even from the same seed, so comparing single runs is meaningless. A test case
+ * with a `statistics` section is instead executed once per seed, and each numeric
+ * output is summarized over the repetitions:
+ *
+ * ```json
+ * "statistics": { "repetitions": 50, "seed": 1, "seed_input": "seed", "confidence": 3.0 },
+ * "expected_outputs": { "path_length": { "mean": 4.2, "std": 0.3, "quantiles": { "0.95": 4.8 } } }
+ * ```
+ *
+ * The mean and standard deviation agree when they are within the test tolerance
+ * plus `confidence` standard errors of the expectation; `min`, `max` and
+ * quantiles must be within the tolerance. Quantiles interpolate linearly between
+ * order statistics. The C++ side mirrors this in `statistics.hpp`.
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Default number of standard errors by which a mean or deviation may differ
+pub const DEFAULT_CONFIDENCE: f64 = 3.0;
+
+/// How a stochastic test case is repeated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatisticsSpec {
+    pub repetitions: usize,
+    /// Seed of the first repetition; repetition `i` uses `seed + i`
+    pub seed: u64,
+    /// Input that receives the seed
+    pub seed_input: String,
+    pub confidence: f64,
+}
+
+impl StatisticsSpec {
+    /// Parse a `statistics` section
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let section = value.as_object()?;
+        Some(Self {
+            repetitions: section.get("repetitions").and_then(Value::as_u64).unwrap_or(30).max(1) as usize,
+            seed: section.get("seed").and_then(Value::as_u64).unwrap_or(0),
+            seed_input: section.get("seed_input").and_then(Value::as_str).unwrap_or("seed").to_string(),
+            confidence: section.get("confidence").and_then(Value::as_f64).unwrap_or(DEFAULT_CONFIDENCE),
+        })
+    }
+
+    /// Inputs of repetition `i`: the test inputs with the seed set
+    pub fn inputs_for(&self, inputs: &Value, repetition: usize) -> Value {
+        let mut fields = inputs.as_object().cloned().unwrap_or_default();
+        fields.insert(self.seed_input.clone(), Value::from(self.seed + repetition as u64));
+        Value::Object(fields)
+    }
+}
+
+/// Sample statistics of one output over all repetitions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    sorted: Vec<f64>,
+    pub mean: f64,
+    /// Sample standard deviation (divides by `n - 1`)
+    pub deviation: f64,
+}
+
+impl Summary {
+    pub fn new(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(f64::total_cmp);
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = if samples.len() > 1 {
+            samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        Self { sorted: samples, mean, deviation: variance.sqrt() }
+    }
+
+    pub fn count(&self) -> usize {
+        self.sorted.len()
+    }
+
+    pub fn min(&self) -> f64 {
+        self.sorted[0]
+    }
+
+    pub fn max(&self) -> f64 {
+        self.sorted[self.sorted.len() - 1]
+    }
+
+    /// Quantile `p` in [0, 1], interpolating linearly between order statistics
+    pub fn quantile(&self, p: f64) -> f64 {
+        let position = p.clamp(0.0, 1.0) * (self.sorted.len() - 1) as f64;
+        let below = position.floor() as usize;
+        let above = position.ceil() as usize;
+        self.sorted[below] + (position - below as f64) * (self.sorted[above] - self.sorted[below])
+    }
+
+    /// Standard error of the mean
+    pub fn mean_error(&self) -> f64 {
+        self.deviation / (self.count() as f64).sqrt()
+    }
+
+    /// Approximate standard error of the standard deviation for normal samples
+    pub fn std_error(&self) -> f64 {
+        self.deviation / (2.0 * (self.count().max(2) - 1) as f64).sqrt()
+    }
+
+    /// The statistics requested by `expected`, in the same shape
+    pub fn to_json(&self, expected: &Map<String, Value>) -> Value {
+        let mut fields = Map::new();
+        fields.insert("count".to_string(), Value::from(self.count()));
+        for (key, value) in [("mean", self.mean), ("std", self.deviation), ("min", self.min()), ("max", self.max())] {
+            fields.insert(key.to_string(), Value::from(value));
+        }
+        if let Some(Value::Object(quantiles)) = expected.get("quantiles") {
+            let computed = quantiles
+                .keys()
+                .filter_map(|p| p.parse::<f64>().ok().map(|q| (p.clone(), Value::from(self.quantile(q)))))
+                .collect();
+            fields.insert("quantiles".to_string(), Value::Object(computed));
+        }
+        Value::Object(fields)
+    }
+
+    /// Check the expected statistics of one output, describing the first disagreement
+    pub fn agrees_with(&self, expected: &Map<String, Value>, tolerance: f64, confidence: f64) -> Result<(), String> {
+        let check = |name: &str, actual: f64, expected: &Value, allowed: f64| -> Result<(), String> {
+            let expected = expected.as_f64().ok_or_else(|| format!("expected {} is not a number", name))?;
+            if (actual - expected).abs() <= allowed {
+                Ok(())
+            } else {
+                Err(format!("{} {} differs from expected {} by more than {:.3e}", name, actual, expected, allowed))
+            }
+        };
+
+        for (key, value) in expected {
+            match key.as_str() {
+                "mean" => check("mean", self.mean, value, tolerance + confidence * self.mean_error())?,
+                "std" => check("std", self.deviation, value, tolerance + confidence * self.std_error())?,
+                "min" => check("min", self.min(), value, tolerance)?,
+                "max" => check("max", self.max(), value, tolerance)?,
+                "quantiles" => {
+                    for (p, value) in value.as_object().into_iter().flatten() {
+                        let q = p.parse::<f64>().map_err(|_| format!("invalid quantile '{}'", p))?;
+                        check(&format!("quantile {}", p), self.quantile(q), value, tolerance)?;
+                    }
+                }
+                other => return Err(format!("unknown statistic '{}'", other)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Samples of every numeric output across repetitions, keyed by output name
+pub fn collect_samples(runs: &[Value]) -> BTreeMap<String, Vec<f64>> {
+    let mut samples: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for run in runs {
+        for (key, value) in run.as_object().into_iter().flatten() {
+            if let Some(x) = value.as_f64() {
+                samples.entry(key.clone()).or_default().push(x);
+            }
+        }
+    }
+    samples
+}
+
+/// Compare repeated runs against expected statistics
+///
+/// Returns the computed statistics, shaped like `expected`, and the first
+/// disagreement if any.
+pub fn compare_runs(runs: &[Value], expected: &Value, tolerance: f64, confidence: f64) -> (Value, Result<(), String>) {
+    let samples = collect_samples(runs);
+    let mut actual = Map::new();
+    let mut outcome = Ok(());
+
+    for (output, statistics) in expected.as_object().into_iter().flatten() {
+        let Some(statistics) = statistics.as_object() else {
+            outcome = outcome.and(Err(format!("expected statistics of '{}' must be an object", output)));
+            continue;
+        };
+        let Some(values) = samples.get(output) else {
+            outcome = outcome.and(Err(format!("output '{}' was not produced", output)));
+            continue;
+        };
+        let summary = Summary::new(values.clone());
+        actual.insert(output.clone(), summary.to_json(statistics));
+        outcome = outcome.and(summary.agrees_with(statistics, tolerance, confidence).map_err(|e| format!("{}: {}", output, e)));
+    }
+    (Value::Object(actual), outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new(vec![4.0, 1.0, 3.0, 2.0, 5.0]);
+        assert_eq!(summary.mean, 3.0);
+        assert!((summary.deviation - 2.5f64.sqrt()).abs() < 1e-15);
+        assert_eq!(summary.quantile(0.5), 3.0);
+        assert_eq!(summary.quantile(0.125), 1.5);
+        assert_eq!((summary.min(), summary.max()), (1.0, 5.0));
+    }
+
+    #[test]
+    fn test_compare_seeded_runs() {
+        // Uniform samples on [0, 1) from a seeded linear congruential generator
+        let spec = StatisticsSpec::from_json(&json!({ "repetitions": 400, "seed": 7 })).unwrap();
+        let runs: Vec<Value> = (0..spec.repetitions)
+            .map(|i| {
+                let seed = spec.inputs_for(&json!({}), i)["seed"].as_u64().unwrap();
+                let state = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                json!({ "sample": (state >> 11) as f64 / (1u64 << 53) as f64 })
+            })
+            .collect();
+
+        let uniform = json!({ "sample": { "mean": 0.5, "std": 12f64.sqrt().recip(), "quantiles": { "0.5": 0.5 } } });
+        let (actual, outcome) = compare_runs(&runs, &uniform, 0.05, spec.confidence);
+        assert_eq!(outcome, Ok(()), "{}", actual);
+        assert_eq!(actual["sample"]["count"], 400);
+
+        let shifted = json!({ "sample": { "mean": 0.6 } });
+        assert!(compare_runs(&runs, &shifted, 0.0, spec.confidence).1.is_err());
+        assert!(compare_runs(&runs, &json!({ "missing": { "mean": 0.0 } }), 0.0, 3.0).1.is_err());
+    }
+}
@@ -1,14 +1,26 @@
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::Path;
+use crate::canonical_output::{CanonicalOutput, Config as OutputConfig};
+use crate::config::{ConfigLayer, ConfigLoader, EffectiveConfig};
+use crate::coverage::{self, ApiSurface, CoverageReport};
+use crate::diff;
+use crate::environment::Environment;
 use crate::json_loader::*;
+use crate::merge;
+use crate::safety;
+use crate::self_check;
+use crate::schema;
 
 #[derive(Parser)]
 #[command(name = "gafro_test_runner")]
 #[command(about = "A test runner for GAFRO JSON test specifications")]
 #[command(version)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Test file to run
-    pub test_file: String,
+    pub test_file: Option<String>,
     
     /// Enable verbose output
     #[arg(short, long)]
@@ -27,14 +39,67 @@ pub struct Args {
     pub stats: bool,
     
     /// Output format
-    #[arg(short, long, value_enum, default_value = "text")]
-    pub format: OutputFormat,
+    #[arg(short, long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Report which operations of the API surface the tests exercised
+    #[arg(long)]
+    pub coverage: bool,
+
+    /// Instead of running the tests, check that the output comparison flips exactly at each tolerance
+    #[arg(long)]
+    pub self_check: bool,
+
+    /// Configuration file (defaults to GAFRO_CONFIG, then ./gafro.toml or ./gafro.json)
+    #[arg(long, global = true)]
+    pub config: Option<String>,
+
+    /// Override a configuration key, e.g. --set output.angle_precision=3
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    pub overrides: Vec<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Upgrade test specifications to the current schema version in place
+    Migrate {
+        /// Spec files to upgrade
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Only report files that need upgrading, and fail if any do
+        #[arg(long)]
+        check: bool,
+    },
+    /// Combine test suites into one, reporting duplicate tests and inconsistent categories or tags
+    Merge {
+        /// Suites to combine; the first definition of a test wins
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// File to write the consolidated suite to
+        #[arg(short, long)]
+        output: String,
+        /// Name of the consolidated suite (defaults to the output file stem)
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print the effective settings and where each one came from
+    Dump,
 }
 
 #[derive(Clone, ValueEnum)]
 pub enum OutputFormat {
     Text,
     Json,
+    Html,
 }
 
 impl std::fmt::Display for OutputFormat {
@@ -42,6 +107,7 @@ impl std::fmt::Display for OutputFormat {
         match self {
             OutputFormat::Text => write!(f, "text"),
             OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Html => write!(f, "html"),
         }
     }
 }
@@ -53,19 +119,27 @@ pub fn print_usage() {
     println!("  -t, --tag <tag>   Run only tests with specified tag");
     println!("  -c, --category <name>  Run only tests in specified category");
     println!("  -s, --stats       Show detailed statistics");
-    println!("  -f, --format <format>  Output format (text, json)");
+    println!("  -f, --format <format>  Output format (text, json, html)");
+    println!("      --coverage        Report which operations of the API surface the tests exercised");
+    println!("      --self-check      Check that the output comparison flips exactly at each tolerance");
+    println!("      --config <file>   Configuration file (gafro.toml or gafro.json)");
+    println!("      --set <key=value> Override a configuration key");
     println!("  -h, --help        Show this help message");
     println!();
     println!("Examples:");
     println!("  gafro_test_runner scalar_tests.json");
     println!("  gafro_test_runner -v -t basic vector_tests.json");
     println!("  gafro_test_runner -c vector_creation vector_tests.json");
+    println!("  gafro_test_runner --set output.angle_precision=3 config dump");
+    println!("  gafro_test_runner migrate ../json/algebra/*.json");
+    println!("  gafro_test_runner merge cpp_specs.json rust_specs.json -o merged.json");
 }
 
 pub fn print_test_suite_info(test_suite: &TestSuite) {
     println!("\n=== Test Suite Information ===");
     println!("Name: {}", test_suite.test_suite_name);
     println!("Version: {}", test_suite.version);
+    println!("Schema Version: {}", test_suite.schema_version);
     println!("Description: {}", test_suite.description);
     
     let stats = test_suite.get_statistics();
@@ -86,23 +160,36 @@ pub fn print_test_suite_info(test_suite: &TestSuite) {
     println!("==============================");
 }
 
-pub fn print_test_results(results: &[TestResult], show_stats: bool, format: &OutputFormat) {
+pub fn print_test_results(results: &[TestResult], show_stats: bool, format: &OutputFormat, output: &OutputConfig) {
     match format {
-        OutputFormat::Text => print_test_results_text(results, show_stats),
+        OutputFormat::Text => print_test_results_text(results, show_stats, output),
         OutputFormat::Json => print_test_results_json(results, show_stats),
+        OutputFormat::Html => print_test_results_html(results, show_stats),
     }
 }
 
-fn print_test_results_text(results: &[TestResult], show_stats: bool) {
+/// Status label of a result
+fn status(result: &TestResult) -> &'static str {
+    match (result.skipped, result.passed) {
+        (true, _) => "SKIP",
+        (false, true) => "PASS",
+        (false, false) => "FAIL",
+    }
+}
+
+fn print_test_results_text(results: &[TestResult], show_stats: bool, config: &OutputConfig) {
     println!("\n=== Test Results ===");
     
     let mut passed = 0;
     let mut failed = 0;
+    let mut skipped = 0;
     let mut total_time = 0.0;
+    let color = diff::use_color();
+    let output = CanonicalOutput::with_config(config.clone());
     
     for result in results {
         print!("[{}] {}", 
-            if result.passed { "PASS" } else { "FAIL" }, 
+            status(result), 
             result.test_name
         );
         
@@ -110,12 +197,24 @@ fn print_test_results_text(results: &[TestResult], show_stats: bool) {
             print!(" ({:.2}ms)", result.execution_time_ms);
         }
         println!();
+        println!("  Tolerance: {}", result.tolerance_model);
         
-        if result.passed {
+        if result.skipped {
+            skipped += 1;
+            println!("  Skipped: {}", result.error_message);
+        } else if result.passed {
             passed += 1;
         } else {
             failed += 1;
-            println!("  Error: {}", result.error_message);
+            if !result.error_message.is_empty() || result.mismatches.is_empty() {
+                println!("  Error: {}", result.error_message);
+            }
+            for mismatch in &result.mismatches {
+                println!("  {}", mismatch.describe(color));
+            }
+        }
+        for violation in &result.violations {
+            println!("  Violation: {}", output.violation(violation));
         }
         
         total_time += result.execution_time_ms;
@@ -124,8 +223,12 @@ fn print_test_results_text(results: &[TestResult], show_stats: bool) {
     println!("\nSummary:");
     println!("  Passed: {}", passed);
     println!("  Failed: {}", failed);
+    if skipped > 0 {
+        println!("  Skipped: {}", skipped);
+    }
     println!("  Total: {}", passed + failed);
     println!("  Total Time: {:.2}ms", total_time);
+    println!("  Environment: {}", Environment::capture().summary());
     
     if passed + failed > 0 {
         println!("  Average Time: {:.2}ms", total_time / (passed + failed) as f64);
@@ -145,6 +248,9 @@ fn print_test_results_json(results: &[TestResult], _show_stats: bool) {
     for result in results {
         test_results.push(JsonLoader::test_result_to_json(result));
         
+        if result.skipped {
+            continue;
+        }
         if result.passed {
             passed += 1;
         } else {
@@ -154,9 +260,11 @@ fn print_test_results_json(results: &[TestResult], _show_stats: bool) {
     }
     
     output.insert("test_results".to_string(), serde_json::Value::Array(test_results));
+    output.insert("environment".to_string(), serde_json::to_value(Environment::capture()).unwrap_or_default());
     output.insert("summary".to_string(), serde_json::json!({
         "passed": passed,
         "failed": failed,
+        "skipped": results.iter().filter(|r| r.skipped).count(),
         "total": passed + failed,
         "total_time_ms": total_time,
         "average_time_ms": if passed + failed > 0 { total_time / (passed + failed) as f64 } else { 0.0 }
@@ -165,16 +273,151 @@ fn print_test_results_json(results: &[TestResult], _show_stats: bool) {
     println!("{}", serde_json::to_string_pretty(&serde_json::Value::Object(output)).unwrap_or_default());
 }
 
+fn print_test_results_html(results: &[TestResult], show_stats: bool) {
+    let passed = results.iter().filter(|r| r.passed && !r.skipped).count();
+    let skipped = results.iter().filter(|r| r.skipped).count();
+    println!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>GAFRO test results</title>");
+    println!("<style>.pass {{ color: green; }} .fail {{ color: red; }} .expected {{ color: green; }} .actual {{ color: red; }} table {{ border-collapse: collapse; }} td, th {{ border: 1px solid #ccc; padding: 2px 6px; }}</style>");
+    println!("</head>\n<body>\n<h1>Test Results</h1>");
+    println!("<p>Passed: {} Failed: {} Skipped: {} Total: {}</p>", passed, results.len() - passed - skipped, skipped, results.len());
+    println!("<p>Environment: {}</p>", diff::escape_html(&Environment::capture().summary()));
+    
+    for result in results {
+        let class = if result.passed { "pass" } else { "fail" };
+        let status = status(result);
+        print!("<h2 class=\"{}\">[{}] {}", class, status, diff::escape_html(&result.test_name));
+        if show_stats {
+            print!(" ({:.2}ms)", result.execution_time_ms);
+        }
+        println!("</h2>");
+        println!("<p>Tolerance: {}</p>", diff::escape_html(&result.tolerance_model));
+        if !result.error_message.is_empty() {
+            println!("<p>Error: {}</p>", diff::escape_html(&result.error_message));
+        }
+        if !result.mismatches.is_empty() {
+            print!("{}", diff::mismatches_to_html(&result.mismatches));
+        }
+        if !result.violations.is_empty() {
+            print!("{}", safety::violations_to_html(&result.violations));
+        }
+    }
+    println!("</body>\n</html>");
+}
+
+pub fn print_coverage_report(report: &CoverageReport, format: &OutputFormat) {
+    match format {
+        OutputFormat::Text => println!("{}", report.to_text()),
+        OutputFormat::Html => println!("<pre>{}</pre>", diff::escape_html(&report.to_text())),
+        OutputFormat::Json => {
+            let output = serde_json::json!({ "coverage": report.to_json() });
+            println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+        }
+    }
+}
+
+/// Resolve the effective configuration from the config file, environment and CLI flags
+pub fn load_config(args: &Args) -> Result<EffectiveConfig, Box<dyn std::error::Error>> {
+    let mut command_line = ConfigLayer::default();
+    if args.verbose {
+        command_line.runner.verbose = Some(true);
+    }
+    if args.stats {
+        command_line.runner.stats = Some(true);
+    }
+    if let Some(format) = &args.format {
+        command_line.runner.format = Some(format.to_string());
+    }
+    for assignment in &args.overrides {
+        command_line.set_assignment(assignment)?;
+    }
+
+    let mut loader = ConfigLoader::new().with_command_line(command_line);
+    if let Some(path) = &args.config {
+        loader = loader.with_file(path);
+    }
+
+    Ok(loader.load()?)
+}
+
+/// Upgrade spec files to the current schema version; with `check`, only list the outdated ones
+pub fn migrate_files(files: &[String], check: bool) -> Result<i32, Box<dyn std::error::Error>> {
+    let mut outdated = 0;
+    for file in files {
+        let mut spec: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(file)?)?;
+        let from = schema::schema_version(&spec)?;
+        let applied = schema::migrate(&mut spec).map_err(|e| format!("{}: {}", file, e))?;
+        if applied.is_empty() {
+            println!("{}: up to date (schema_version {})", file, from);
+            continue;
+        }
+        outdated += 1;
+        if !check {
+            std::fs::write(file, schema::to_pretty_json(&spec))?;
+        }
+        println!(
+            "{}: {} schema_version {} to {}",
+            file,
+            if check { "needs upgrade from" } else { "upgraded from" },
+            from,
+            schema::CURRENT_SCHEMA_VERSION
+        );
+        for migration in applied {
+            println!("  v{}: {}", migration.from, migration.description);
+        }
+    }
+    Ok(if check && outdated > 0 { 1 } else { 0 })
+}
+
+/// Merge suites into `output`; errors such as conflicting definitions leave it unwritten
+pub fn merge_files(files: &[String], output: &str, name: Option<&str>) -> Result<i32, Box<dyn std::error::Error>> {
+    let mut suites = Vec::new();
+    for file in files {
+        suites.push((file.clone(), serde_json::from_str(&std::fs::read_to_string(file)?)?));
+    }
+    let stem = Path::new(output).file_stem().and_then(|s| s.to_str()).unwrap_or("merged");
+    let outcome = merge::merge_suites(name.unwrap_or(stem), suites)?;
+
+    for issue in &outcome.issues {
+        println!("{}: {}", if issue.is_error() { "error" } else { "warning" }, issue);
+    }
+    if outcome.has_errors() {
+        eprintln!("Error: not writing {}; resolve the errors above", output);
+        return Ok(1);
+    }
+    std::fs::write(output, schema::to_pretty_json(&outcome.suite))?;
+    println!("Merged {} tests from {} files into {}", outcome.test_count(), files.len(), output);
+    Ok(0)
+}
+
 pub fn run_tests(args: Args) -> Result<i32, Box<dyn std::error::Error>> {
+    let config = load_config(&args)?;
+
+    if let Some(Command::Config { action: ConfigAction::Dump }) = &args.command {
+        println!("{}", config.dump());
+        return Ok(0);
+    }
+    if let Some(Command::Migrate { files, check }) = &args.command {
+        return migrate_files(files, *check);
+    }
+    if let Some(Command::Merge { files, output, name }) = &args.command {
+        return merge_files(files, output, name.as_deref());
+    }
+
+    let Some(test_file) = &args.test_file else {
+        print_usage();
+        return Ok(1);
+    };
+    let format = OutputFormat::from_str(&config.runner.format, true)?;
+
     // Check if file exists
-    if !Path::new(&args.test_file).exists() {
-        eprintln!("Error: Test file {} does not exist", args.test_file);
+    if !Path::new(test_file).exists() {
+        eprintln!("Error: Test file {} does not exist", test_file);
         return Ok(1);
     }
     
     // Load test suite
-    println!("Loading test suite from: {}", args.test_file);
-    let test_suite = TestSuite::load_from_file(&args.test_file)?;
+    println!("Loading test suite from: {}", test_file);
+    let test_suite = TestSuite::load_from_file(test_file)?;
     
     if !test_suite.is_valid() {
         eprintln!("Error: Invalid test suite");
@@ -186,7 +429,15 @@ pub fn run_tests(args: Args) -> Result<i32, Box<dyn std::error::Error>> {
     
     // Set up test execution context
     let mut context = TestExecutionContext::new();
-    context.set_verbose(args.verbose);
+    context.set_verbose(config.runner.verbose);
+    if args.self_check {
+        let report = self_check::self_check(&context, &test_suite.get_all_test_cases());
+        println!("{}", report);
+        return Ok(if report.passed() { 0 } else { 1 });
+    }
+    if args.coverage {
+        coverage::enable();
+    }
     
     // Execute tests based on filters
     let results = if let Some(category_name) = &args.category {
@@ -222,7 +473,13 @@ pub fn run_tests(args: Args) -> Result<i32, Box<dyn std::error::Error>> {
     };
     
     // Print results
-    print_test_results(&results, args.stats, &args.format);
+    print_test_results(&results, config.runner.stats, &format, &config.output);
+    
+    if args.coverage {
+        let surface = ApiSurface::find_for(Path::new(test_file));
+        let report = CoverageReport::new(&surface, &coverage::disable(), &context.operations().names());
+        print_coverage_report(&report, &format);
+    }
     
     // Return exit code based on results
     let all_passed = results.iter().all(|r| r.passed);
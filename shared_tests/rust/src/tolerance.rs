@@ -0,0 +1,121 @@
+// SPDX-FileCopyrightText: GAFRO Extended Implementation
+//
+// SPDX-License-Identifier: MPL-2.0
+
+/*!
+ * Error-budget tolerances for floating-point comparisons (Rust)
+ *
+ * A computation of `n` correctly rounded operations on values of magnitude up
+ * to `m` accumulates at most about `n · ε · m` absolute error, with ε the
+ * machine epsilon of f64. Tolerances derived from this budget replace guessed
+ * constants such as `1e-5`: they are tight for simple arithmetic and grow with
+ * the length and scale of the computation. Magnitudes below 1 count as 1, since
+ * results near zero usually come from cancellation of order-one terms.
+ *
+ * JSON test specifications accept either a number (a fixed tolerance) or an
+ * object `{"operations": n, "magnitude": m, "safety_factor": s}`; `magnitude`
+ * defaults to the largest expected output and `safety_factor` to
+ * [`DEFAULT_SAFETY_FACTOR`]. The C++ side mirrors this in `tolerance.hpp`.
+ *
+ * This module depends on `std` only so that standalone validators can include it.
+ */
+
+use std::fmt;
+
+/// Headroom over the first-order error bound
+pub const DEFAULT_SAFETY_FACTOR: f64 = 4.0;
+
+/// Tolerance used when a test specifies none
+pub const DEFAULT_TOLERANCE: f64 = 1e-10;
+
+/// Rounding-error budget of a floating-point computation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorBudget {
+    /// Number of rounding operations, including conversion of decimal inputs
+    pub operations: u32,
+    /// Largest magnitude of any intermediate value
+    pub magnitude: f64,
+    pub safety_factor: f64,
+}
+
+impl ErrorBudget {
+    pub fn new(operations: u32, magnitude: f64) -> Self {
+        Self { operations, magnitude, safety_factor: DEFAULT_SAFETY_FACTOR }
+    }
+
+    pub fn with_safety_factor(mut self, safety_factor: f64) -> Self {
+        self.safety_factor = safety_factor;
+        self
+    }
+
+    /// Absolute tolerance `s · n · ε · max(|m|, 1)`
+    pub fn tolerance(&self) -> f64 {
+        self.safety_factor * self.operations.max(1) as f64 * f64::EPSILON * self.magnitude.abs().max(1.0)
+    }
+}
+
+/// How a test decides that two floating-point values agree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    Fixed(f64),
+    Budget(ErrorBudget),
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance::Fixed(DEFAULT_TOLERANCE)
+    }
+}
+
+impl Tolerance {
+    pub fn budget(operations: u32, magnitude: f64) -> Self {
+        Tolerance::Budget(ErrorBudget::new(operations, magnitude))
+    }
+
+    /// Absolute tolerance
+    pub fn value(&self) -> f64 {
+        match self {
+            Tolerance::Fixed(tolerance) => *tolerance,
+            Tolerance::Budget(budget) => budget.tolerance(),
+        }
+    }
+
+    pub fn check(&self, actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() <= self.value()
+    }
+}
+
+impl fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tolerance::Fixed(tolerance) => write!(f, "fixed {:.1e}", tolerance),
+            Tolerance::Budget(budget) => write!(
+                f,
+                "{:.1e} ({} ops, magnitude {}, safety {})",
+                budget.tolerance(),
+                budget.operations,
+                budget.magnitude,
+                budget.safety_factor
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_scaling() {
+        let single = ErrorBudget::new(1, 0.5).tolerance();
+        assert_eq!(single, DEFAULT_SAFETY_FACTOR * f64::EPSILON);
+        assert_eq!(ErrorBudget::new(10, 0.5).tolerance(), 10.0 * single);
+        assert_eq!(ErrorBudget::new(1, -100.0).tolerance(), 100.0 * single);
+
+        // 0.1 + 0.2 misses 0.3 by one ulp, well inside a three-operation budget
+        let budget = Tolerance::budget(3, 0.3);
+        assert!(budget.check(0.1 + 0.2, 0.3));
+        assert!(!budget.check(0.3 + 1e-12, 0.3));
+        assert_eq!(Tolerance::default().value(), DEFAULT_TOLERANCE);
+    }
+}
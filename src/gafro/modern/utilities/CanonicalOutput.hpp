@@ -64,7 +64,8 @@ namespace gafro::modern::utilities
     
     class CanonicalOutput {
     public:
-        // Configuration for output precision and formatting
+        // Configuration for output precision and formatting, read from GAFRO_* environment
+        // variables only; gafro.toml/gafro.json files are resolved by the Rust side alone
         struct Config {
             int position_precision = get_env_int("GAFRO_POSITION_PRECISION", 1);      // Decimal places for positions
             int angle_precision = get_env_int("GAFRO_ANGLE_PRECISION", 2);         // Decimal places for angles